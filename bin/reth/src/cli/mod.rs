@@ -9,9 +9,9 @@ use clap::{value_parser, Parser, Subcommand};
 use reth_chainspec::ChainSpec;
 use reth_cli::chainspec::ChainSpecParser;
 use reth_cli_commands::{
-    config_cmd, db, dump_genesis, import, init_cmd, init_state,
+    config_cmd, db, dump_genesis, export, import, init_cmd, init_state,
     node::{self, NoArgs},
-    p2p, prune, recover, stage,
+    p2p, prune, recover, stage, static_file,
 };
 use reth_cli_runner::CliRunner;
 use reth_db::DatabaseEnv;
@@ -156,9 +156,21 @@ impl<C: ChainSpecParser<ChainSpec = ChainSpec>, Ext: clap::Args + fmt::Debug> Cl
             Commands::InitState(command) => {
                 runner.run_blocking_until_ctrl_c(command.execute::<EthereumNode>())
             }
-            Commands::Import(command) => runner.run_blocking_until_ctrl_c(
-                command.execute::<EthereumNode, _, _>(EthExecutorProvider::ethereum),
-            ),
+            Commands::Import(command) => {
+                let parallel_workers = command.parallel_workers();
+                runner.run_blocking_until_ctrl_c(command.execute::<EthereumNode, _, _>(
+                    move |chain_spec| {
+                        let executor = EthExecutorProvider::ethereum(chain_spec);
+                        match parallel_workers {
+                            Some(workers) => executor.with_parallel_execution(workers),
+                            None => executor,
+                        }
+                    },
+                ))
+            }
+            Commands::Export(command) => {
+                runner.run_blocking_until_ctrl_c(command.execute::<EthereumNode>())
+            }
             Commands::DumpGenesis(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::Db(command) => {
                 runner.run_blocking_until_ctrl_c(command.execute::<EthereumNode>())
@@ -166,6 +178,9 @@ impl<C: ChainSpecParser<ChainSpec = ChainSpec>, Ext: clap::Args + fmt::Debug> Cl
             Commands::Stage(command) => runner.run_command_until_exit(|ctx| {
                 command.execute::<EthereumNode, _, _>(ctx, EthExecutorProvider::ethereum)
             }),
+            Commands::StaticFile(command) => {
+                runner.run_blocking_until_ctrl_c(command.execute::<EthereumNode>())
+            }
             Commands::P2P(command) => runner.run_until_ctrl_c(command.execute()),
             #[cfg(feature = "dev")]
             Commands::TestVectors(command) => runner.run_until_ctrl_c(command.execute()),
@@ -205,6 +220,9 @@ pub enum Commands<C: ChainSpecParser, Ext: clap::Args + fmt::Debug> {
     /// This syncs RLP encoded blocks from a file.
     #[command(name = "import")]
     Import(import::ImportCommand<C>),
+    /// Exports canonical blocks to an RLP file or a static file bundle.
+    #[command(name = "export")]
+    Export(export::ExportCommand<C>),
     /// Dumps genesis block JSON configuration to stdout.
     DumpGenesis(dump_genesis::DumpGenesisCommand<C>),
     /// Database debugging utilities
@@ -213,6 +231,9 @@ pub enum Commands<C: ChainSpecParser, Ext: clap::Args + fmt::Debug> {
     /// Manipulate individual stages.
     #[command(name = "stage")]
     Stage(stage::Command<C>),
+    /// Static file utilities
+    #[command(name = "static-file")]
+    StaticFile(static_file::Command<C>),
     /// P2P Debugging utilities
     #[command(name = "p2p")]
     P2P(p2p::Command<C>),
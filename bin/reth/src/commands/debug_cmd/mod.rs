@@ -12,6 +12,8 @@ mod execution;
 mod in_memory_merkle;
 mod merkle;
 mod replay_engine;
+mod replay_engine_tree;
+mod validate_stateless;
 
 /// `reth debug` command
 #[derive(Debug, Parser)]
@@ -33,6 +35,10 @@ pub enum Subcommands<C: ChainSpecParser> {
     BuildBlock(build_block::Command<C>),
     /// Debug engine API by replaying stored messages.
     ReplayEngine(replay_engine::Command<C>),
+    /// Debug the engine tree by replaying stored messages and comparing the recorded responses.
+    ReplayEngineTree(replay_engine_tree::Command<C>),
+    /// Debug stateless validation by validating blocks against their execution witnesses.
+    ValidateStateless(validate_stateless::Command<C>),
 }
 
 impl<C: ChainSpecParser<ChainSpec = ChainSpec>> Command<C> {
@@ -49,6 +55,8 @@ impl<C: ChainSpecParser<ChainSpec = ChainSpec>> Command<C> {
             Subcommands::InMemoryMerkle(command) => command.execute::<N>(ctx).await,
             Subcommands::BuildBlock(command) => command.execute::<N>(ctx).await,
             Subcommands::ReplayEngine(command) => command.execute::<N>(ctx).await,
+            Subcommands::ReplayEngineTree(command) => command.execute::<N>(ctx).await,
+            Subcommands::ValidateStateless(command) => command.execute().await,
         }
     }
 }
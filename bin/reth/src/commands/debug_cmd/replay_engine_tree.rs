@@ -0,0 +1,146 @@
+use clap::Parser;
+use reth_beacon_consensus::EthBeaconConsensus;
+use reth_chainspec::ChainSpec;
+use reth_cli::chainspec::ChainSpecParser;
+use reth_cli_commands::common::{AccessRights, Environment, EnvironmentArgs};
+use reth_cli_runner::CliContext;
+use reth_consensus::Consensus;
+use reth_engine_tree::{
+    engine::{EngineApiEvent, EngineApiKind, FromEngine},
+    persistence::PersistenceHandle,
+    tree::{EngineApiTreeHandler, NoopInvalidBlockHook, TreeConfig},
+};
+use reth_engine_util::replay::EngineReplay;
+use reth_node_api::NodeTypesWithEngine;
+use reth_node_ethereum::{EthEngineTypes, EthExecutorProvider};
+use reth_payload_builder::PayloadBuilderHandle;
+use reth_payload_validator::ExecutionPayloadValidator;
+use reth_provider::{providers::BlockchainProvider2, ChainSpecProvider};
+use reth_prune::PrunerBuilder;
+use std::{path::PathBuf, sync::Arc};
+use tokio::sync::mpsc::unbounded_channel;
+use tracing::*;
+
+/// Name of the file that marks a datadir as consumed by a replay.
+const CONSUMED_MARKER: &str = "replay-engine-tree.consumed";
+
+/// `reth debug replay-engine-tree` command
+/// This script replays stored engine API messages against the engine tree, one message at a time,
+/// and compares the payload validity and forkchoice outcomes with the recorded responses.
+///
+/// The datadir is expected to be a snapshot of the node at the time the first message was
+/// recorded. Blocks are never downloaded, and built payloads are not part of the replay.
+///
+/// The replayed blocks are persisted to the datadir, so the snapshot is consumed by the replay and
+/// no longer matches the first message afterwards. The datadir is marked as consumed and refused by
+/// later replays, which have to run against a fresh copy of the snapshot.
+#[derive(Debug, Parser)]
+pub struct Command<C: ChainSpecParser> {
+    #[command(flatten)]
+    env: EnvironmentArgs<C>,
+
+    /// The path to read engine API messages and recorded responses from.
+    #[arg(long = "engine-api-store", value_name = "PATH")]
+    engine_api_store: PathBuf,
+}
+
+impl<C: ChainSpecParser<ChainSpec = ChainSpec>> Command<C> {
+    /// Execute `debug replay-engine-tree` command
+    pub async fn execute<
+        N: NodeTypesWithEngine<Engine = EthEngineTypes, ChainSpec = C::ChainSpec>,
+    >(
+        self,
+        ctx: CliContext,
+    ) -> eyre::Result<()> {
+        let data_dir = self.env.datadir.clone().resolve_datadir(self.env.chain.chain);
+        let consumed_marker = data_dir.data_dir().join(CONSUMED_MARKER);
+        if consumed_marker.exists() {
+            eyre::bail!(
+                "the datadir {} was consumed by a previous replay, replay against a fresh copy of the snapshot",
+                data_dir.data_dir().display()
+            )
+        }
+
+        let Environment { provider_factory, config, .. } = self.env.init::<N>(AccessRights::RW)?;
+        reth_fs_util::write(&consumed_marker, b"")?;
+
+        let consensus: Arc<dyn Consensus> =
+            Arc::new(EthBeaconConsensus::new(provider_factory.chain_spec()));
+        let executor = EthExecutorProvider::ethereum(provider_factory.chain_spec());
+        let blockchain_db = BlockchainProvider2::new(provider_factory.clone())?;
+        let canonical_in_memory_state = blockchain_db.canonical_in_memory_state();
+
+        let pruner = PrunerBuilder::new(config.prune.clone().unwrap_or_default())
+            .build_with_provider_factory(provider_factory.clone());
+        let (sync_metrics_tx, _sync_metrics_rx) = unbounded_channel();
+        let persistence =
+            PersistenceHandle::spawn_service(provider_factory.clone(), pruner, sync_metrics_tx);
+
+        // Payload jobs are never served, forkchoice updates with attributes only report the
+        // forkchoice status
+        let (payload_builder_tx, _payload_builder_rx) = unbounded_channel();
+        let payload_builder = PayloadBuilderHandle::<EthEngineTypes>::new(payload_builder_tx);
+
+        let (to_tree, mut from_tree) = EngineApiTreeHandler::spawn_new(
+            blockchain_db,
+            executor,
+            consensus,
+            ExecutionPayloadValidator::new(provider_factory.chain_spec()),
+            persistence,
+            payload_builder,
+            canonical_in_memory_state,
+            TreeConfig::default(),
+            Box::new(NoopInvalidBlockHook::default()),
+            EngineApiKind::Ethereum,
+        );
+        info!(target: "reth::cli", "Engine tree initialized");
+
+        // Forward the replayed messages to the tree and drain its events
+        let (to_engine, mut from_replay) = unbounded_channel();
+        ctx.task_executor.spawn(async move {
+            loop {
+                tokio::select! {
+                    msg = from_replay.recv() => {
+                        let Some(msg) = msg else { break };
+                        if to_tree.send(FromEngine::Request(msg.into())).is_err() {
+                            break
+                        }
+                    }
+                    event = from_tree.recv() => match event {
+                        Some(EngineApiEvent::BeaconConsensus(event)) => {
+                            debug!(target: "reth::cli", ?event, "Engine tree event");
+                        }
+                        Some(EngineApiEvent::BackfillAction(action)) => {
+                            warn!(target: "reth::cli", ?action, "Engine tree requested backfill, the snapshot is missing blocks");
+                        }
+                        Some(EngineApiEvent::Download(request)) => {
+                            warn!(target: "reth::cli", ?request, "Engine tree requested download, the snapshot is missing blocks");
+                        }
+                        None => break,
+                    },
+                }
+            }
+        });
+
+        let report = EngineReplay::new(self.engine_api_store, to_engine).run().await?;
+        info!(target: "reth::cli", replayed = report.replayed, compared = report.compared, "Finished replaying engine API messages");
+
+        if let Some(divergence) = report.divergence {
+            error!(
+                target: "reth::cli",
+                index = divergence.index,
+                path = %divergence.path.display(),
+                recorded = ?divergence.recorded,
+                replayed = ?divergence.replayed,
+                "First divergence from the recorded responses"
+            );
+            eyre::bail!(
+                "replay diverged at message {}: {}",
+                divergence.index,
+                divergence.path.display()
+            )
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+use clap::Parser;
+use reth_beacon_consensus::EthBeaconConsensus;
+use reth_chainspec::ChainSpec;
+use reth_cli::chainspec::ChainSpecParser;
+use reth_node_ethereum::EthExecutorProvider;
+use reth_stateless::StatelessInput;
+use std::{path::PathBuf, sync::Arc};
+use tracing::*;
+
+/// `reth debug validate-stateless` command
+/// This script validates blocks without access to the database, by re-executing them against their
+/// execution witnesses and comparing the resulting state roots with the ones of the blocks.
+///
+/// The file is expected to contain a JSON list of blocks, each one together with its execution
+/// witness, as returned by `debug_executionWitness`, and the headers of its ancestors.
+#[derive(Debug, Parser)]
+pub struct Command<C: ChainSpecParser> {
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        long_help = C::help_message(),
+        default_value = C::SUPPORTED_CHAINS[0],
+        value_parser = C::parser()
+    )]
+    chain: Arc<C::ChainSpec>,
+
+    /// The path to read the blocks and their execution witnesses from.
+    #[arg(long = "witnesses", value_name = "PATH")]
+    witnesses: PathBuf,
+}
+
+impl<C: ChainSpecParser<ChainSpec = ChainSpec>> Command<C> {
+    /// Execute `debug validate-stateless` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        let inputs: Vec<StatelessInput> = reth_fs_util::read_json_file(&self.witnesses)?;
+        info!(target: "reth::cli", blocks = inputs.len(), "Loaded execution witnesses");
+
+        let consensus = EthBeaconConsensus::new(self.chain.clone());
+        let executor = EthExecutorProvider::ethereum(self.chain);
+
+        let mut invalid = 0;
+        for input in &inputs {
+            let number = input.block.number;
+            match reth_stateless::validate_block(&executor, &consensus, input) {
+                Ok(output) => {
+                    info!(target: "reth::cli", number, gas_used = output.gas_used, state_root = ?output.state_root, "Block is valid")
+                }
+                Err(err) => {
+                    invalid += 1;
+                    error!(target: "reth::cli", number, %err, "Block is invalid");
+                }
+            }
+        }
+
+        if invalid > 0 {
+            eyre::bail!("{invalid} out of {} blocks failed stateless validation", inputs.len())
+        }
+
+        info!(target: "reth::cli", blocks = inputs.len(), "All blocks passed stateless validation");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::B256;
+    use reth_chainspec::EthereumHardforks;
+    use reth_ethereum_cli::chainspec::EthereumChainSpecParser;
+    use reth_primitives::{
+        constants::{EMPTY_RECEIPTS, EMPTY_ROOT_HASH, EMPTY_TRANSACTIONS, EMPTY_WITHDRAWALS},
+        Block, BlockBody, Header, Withdrawals, EMPTY_OMMER_ROOT_HASH,
+    };
+
+    /// Post-merge chain without any accounts, so that empty blocks can be validated against an
+    /// empty witness.
+    const GENESIS: &str = r#"{
+  "nonce": "0x0",
+  "timestamp": "0x0",
+  "extraData": "0x",
+  "gasLimit": "0x1c9c380",
+  "difficulty": "0x0",
+  "mixHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+  "coinbase": "0x0000000000000000000000000000000000000000",
+  "alloc": {},
+  "config": {
+    "chainId": 1337,
+    "homesteadBlock": 0,
+    "eip150Block": 0,
+    "eip155Block": 0,
+    "eip158Block": 0,
+    "byzantiumBlock": 0,
+    "constantinopleBlock": 0,
+    "petersburgBlock": 0,
+    "istanbulBlock": 0,
+    "berlinBlock": 0,
+    "londonBlock": 0,
+    "terminalTotalDifficulty": 0,
+    "terminalTotalDifficultyPassed": true,
+    "shanghaiTime": 0
+  }
+}"#;
+
+    #[tokio::test]
+    async fn validate_stateless_blocks() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let witnesses = temp_dir.path().join("witnesses.json");
+        let command = || {
+            Command::<EthereumChainSpecParser>::parse_from([
+                "reth",
+                "--chain",
+                GENESIS,
+                "--witnesses",
+                witnesses.to_str().unwrap(),
+            ])
+        };
+
+        let chain_spec = command().chain;
+        assert!(chain_spec.is_shanghai_active_at_timestamp(0));
+        let genesis = chain_spec.sealed_genesis_header();
+        let timestamp = genesis.timestamp + 12;
+        let block = Block {
+            header: Header {
+                parent_hash: genesis.hash(),
+                ommers_hash: EMPTY_OMMER_ROOT_HASH,
+                state_root: EMPTY_ROOT_HASH,
+                transactions_root: EMPTY_TRANSACTIONS,
+                receipts_root: EMPTY_RECEIPTS,
+                withdrawals_root: Some(EMPTY_WITHDRAWALS),
+                number: 1,
+                gas_limit: genesis.gas_limit,
+                timestamp,
+                base_fee_per_gas: genesis
+                    .next_block_base_fee(chain_spec.base_fee_params_at_timestamp(timestamp)),
+                ..Default::default()
+            },
+            body: BlockBody {
+                transactions: Vec::new(),
+                ommers: Vec::new(),
+                withdrawals: Some(Withdrawals::default()),
+                requests: None,
+            },
+        };
+        let input = StatelessInput {
+            block,
+            witness: Default::default(),
+            ancestor_headers: vec![genesis.header().clone()],
+        };
+
+        reth_fs_util::write_json_file(&witnesses, &[&input]).unwrap();
+        command().execute().await.unwrap();
+
+        let mut tampered = input.clone();
+        tampered.block.header.state_root = B256::with_last_byte(1);
+        reth_fs_util::write_json_file(&witnesses, &[input, tampered]).unwrap();
+        let err = command().execute().await.unwrap_err();
+        assert_eq!(err.to_string(), "1 out of 2 blocks failed stateless validation");
+    }
+}
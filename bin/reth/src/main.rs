@@ -3,20 +3,40 @@
 #[global_allocator]
 static ALLOC: reth_cli_util::allocator::Allocator = reth_cli_util::allocator::new_allocator();
 
-use clap::{Args, Parser};
+use clap::{Args, Parser, ValueEnum};
 use reth::cli::Cli;
 use reth_ethereum_cli::chainspec::EthereumChainSpecParser;
 use reth_node_builder::{
     engine_tree_config::{
-        TreeConfig, DEFAULT_MEMORY_BLOCK_BUFFER_TARGET, DEFAULT_PERSISTENCE_THRESHOLD,
+        PersistencePolicy, TreeConfig, DEFAULT_MAX_MEMORY_BLOCKS,
+        DEFAULT_MAX_PERSISTED_REORG_DEPTH, DEFAULT_MEMORY_BLOCK_BUFFER_TARGET,
+        DEFAULT_PERSISTENCE_TARGET_WRITE_DURATION, DEFAULT_PERSISTENCE_THRESHOLD,
     },
     EngineNodeLauncher,
 };
-use reth_node_ethereum::{node::EthereumAddOns, EthereumNode};
+use reth_node_ethereum::{
+    node::{EthereumAddOns, EthereumExecutorBuilder},
+    EthereumNode,
+};
 use reth_provider::providers::BlockchainProvider2;
 use reth_tracing::tracing::warn;
+use std::{num::NonZeroUsize, time::Duration};
 use tracing::info;
 
+/// Determines which canonical blocks the engine persists, and when.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum PersistencePolicyKind {
+    /// Persist once the number of canonical blocks in memory exceeds the persistence threshold
+    #[default]
+    BlockCount,
+    /// Persist canonical blocks up to the safe block only
+    Safe,
+    /// Persist canonical blocks up to the finalized block only
+    Finalized,
+    /// Persist by block count, adapting the persistence threshold to the write latency
+    Adaptive,
+}
+
 /// Parameters for configuring the engine
 #[derive(Debug, Clone, Args, PartialEq, Eq)]
 #[command(next_help_heading = "Engine")]
@@ -39,6 +59,48 @@ pub struct EngineArgs {
     /// Configure the target number of blocks to keep in memory.
     #[arg(long = "engine.memory-block-buffer-target", requires = "experimental", default_value_t = DEFAULT_MEMORY_BLOCK_BUFFER_TARGET)]
     pub memory_block_buffer_target: u64,
+
+    /// Configure which canonical blocks are persisted, and when.
+    #[arg(long = "engine.persistence-policy", value_enum, default_value_t = PersistencePolicyKind::BlockCount)]
+    pub persistence_policy: PersistencePolicyKind,
+
+    /// Configure the maximum number of canonical blocks to keep in memory with the `safe`,
+    /// `finalized` and `adaptive` persistence policies.
+    #[arg(long = "engine.max-memory-blocks", default_value_t = DEFAULT_MAX_MEMORY_BLOCKS)]
+    pub max_memory_blocks: u64,
+
+    /// Configure the write duration the `adaptive` persistence policy aims for, in milliseconds.
+    #[arg(long = "engine.persistence-target-write-ms", default_value_t = DEFAULT_PERSISTENCE_TARGET_WRITE_DURATION.as_millis() as u64)]
+    pub persistence_target_write_ms: u64,
+
+    /// Configure how many blocks below the last persisted block reorgs are served for without
+    /// unwinding the database through backfill sync.
+    ///
+    /// The reorged blocks are still removed from the database and the new chain is persisted
+    /// again.
+    #[arg(long = "engine.max-persisted-reorg-depth", default_value_t = DEFAULT_MAX_PERSISTED_REORG_DEPTH)]
+    pub max_persisted_reorg_depth: u64,
+
+    /// Enable the state root task, which computes the state root with a sparse trie concurrently
+    /// with the execution of the block.
+    #[arg(long = "engine.state-root-task")]
+    pub state_root_task: bool,
+
+    /// Configure the size of the cache of accounts, storage slots and bytecodes that is shared
+    /// across the execution of blocks, in megabytes, e.g. 512. The cache uses up to this much
+    /// memory in addition to the in-memory blocks. Zero disables the cache.
+    #[arg(long = "engine.cross-block-cache-size", default_value_t = 0)]
+    pub cross_block_cache_size: u64,
+
+    /// Enable prewarming, which speculatively executes the transactions of a block against the
+    /// parent state while the block is executed, to warm the state they touch.
+    #[arg(long = "engine.prewarming")]
+    pub prewarming: bool,
+
+    /// Execute the transactions of each block in parallel on the given number of workers, both
+    /// for new payloads and for historical sync.
+    #[arg(long = "engine.parallel-execution-workers", value_name = "WORKERS")]
+    pub parallel_execution_workers: Option<NonZeroUsize>,
 }
 
 impl Default for EngineArgs {
@@ -48,6 +110,31 @@ impl Default for EngineArgs {
             legacy: false,
             persistence_threshold: DEFAULT_PERSISTENCE_THRESHOLD,
             memory_block_buffer_target: DEFAULT_MEMORY_BLOCK_BUFFER_TARGET,
+            persistence_policy: PersistencePolicyKind::BlockCount,
+            max_memory_blocks: DEFAULT_MAX_MEMORY_BLOCKS,
+            persistence_target_write_ms: DEFAULT_PERSISTENCE_TARGET_WRITE_DURATION.as_millis()
+                as u64,
+            max_persisted_reorg_depth: DEFAULT_MAX_PERSISTED_REORG_DEPTH,
+            state_root_task: false,
+            cross_block_cache_size: 0,
+            prewarming: false,
+            parallel_execution_workers: None,
+        }
+    }
+}
+
+impl EngineArgs {
+    /// Returns the configured [`PersistencePolicy`].
+    pub const fn persistence_policy(&self) -> PersistencePolicy {
+        let max_memory_blocks = self.max_memory_blocks;
+        match self.persistence_policy {
+            PersistencePolicyKind::BlockCount => PersistencePolicy::BlockCount,
+            PersistencePolicyKind::Safe => PersistencePolicy::Safe { max_memory_blocks },
+            PersistencePolicyKind::Finalized => PersistencePolicy::Finalized { max_memory_blocks },
+            PersistencePolicyKind::Adaptive => PersistencePolicy::Adaptive {
+                target_write_duration: Duration::from_millis(self.persistence_target_write_ms),
+                max_memory_blocks,
+            },
         }
     }
 }
@@ -71,10 +158,21 @@ fn main() {
                 false => {
                     let engine_tree_config = TreeConfig::default()
                         .with_persistence_threshold(engine_args.persistence_threshold)
-                        .with_memory_block_buffer_target(engine_args.memory_block_buffer_target);
+                        .with_memory_block_buffer_target(engine_args.memory_block_buffer_target)
+                        .with_persistence_policy(engine_args.persistence_policy())
+                        .with_max_persisted_reorg_depth(engine_args.max_persisted_reorg_depth)
+                        .with_state_root_task(engine_args.state_root_task)
+                        .with_cross_block_cache_size(
+                            engine_args.cross_block_cache_size * 1024 * 1024,
+                        )
+                        .with_prewarming(engine_args.prewarming);
+                    let mut executor = EthereumExecutorBuilder::default();
+                    if let Some(workers) = engine_args.parallel_execution_workers {
+                        executor = executor.with_parallel_execution(workers);
+                    }
                     let handle = builder
                         .with_types_and_provider::<EthereumNode, BlockchainProvider2<_>>()
-                        .with_components(EthereumNode::components())
+                        .with_components(EthereumNode::components().executor(executor))
                         .with_add_ons(EthereumAddOns::default())
                         .launch_with_fn(|builder| {
                             let launcher = EngineNodeLauncher::new(
@@ -118,4 +216,37 @@ mod tests {
         let args = CommandParser::<EngineArgs>::parse_from(["reth"]).args;
         assert_eq!(args, default_args);
     }
+
+    #[test]
+    fn test_parse_engine_persistence_policy() {
+        let args = CommandParser::<EngineArgs>::parse_from([
+            "reth",
+            "--engine.persistence-policy",
+            "finalized",
+            "--engine.max-memory-blocks",
+            "64",
+        ])
+        .args;
+        assert_eq!(
+            args.persistence_policy(),
+            PersistencePolicy::Finalized { max_memory_blocks: 64 }
+        );
+    }
+
+    #[test]
+    fn test_parse_engine_parallel_execution_workers() {
+        let args = CommandParser::<EngineArgs>::parse_from([
+            "reth",
+            "--engine.parallel-execution-workers",
+            "4",
+        ])
+        .args;
+        assert_eq!(args.parallel_execution_workers, NonZeroUsize::new(4));
+        assert!(CommandParser::<EngineArgs>::try_parse_from([
+            "reth",
+            "--engine.parallel-execution-workers",
+            "0"
+        ])
+        .is_err());
+    }
 }
@@ -186,6 +186,13 @@ impl StateProofProvider for MemoryOverlayStateProvider {
         input.prepend_cached(nodes, state);
         self.historical.witness(input, target)
     }
+
+    fn database_trie_input(&self) -> ProviderResult<Option<TrieInput>> {
+        let Some(mut input) = self.historical.database_trie_input()? else { return Ok(None) };
+        let MemoryOverlayTrieState { nodes, state } = self.trie_state().clone();
+        input.append_cached(nodes, state);
+        Ok(Some(input))
+    }
 }
 
 impl StateProvider for MemoryOverlayStateProvider {
@@ -47,6 +47,15 @@ pub trait EthChainSpec: Send + Sync + Unpin + Debug {
     /// The bootnodes for the chain, if any.
     fn bootnodes(&self) -> Option<Vec<NodeRecord>>;
 
+    /// The block at which the Paris (Merge) hardfork was activated, if it's known.
+    ///
+    /// Defaults to the Paris activation block of the genesis configuration, which is the fork
+    /// block of [`EthereumHardfork::Paris`](crate::EthereumHardfork::Paris) for chain specs built
+    /// from a genesis file.
+    fn paris_block(&self) -> Option<u64> {
+        self.genesis().config.merge_netsplit_block
+    }
+
     /// Returns `true` if this chain contains Optimism configuration.
     fn is_optimism(&self) -> bool {
         self.chain().is_optimism()
@@ -98,6 +107,10 @@ impl EthChainSpec for ChainSpec {
         self.bootnodes()
     }
 
+    fn paris_block(&self) -> Option<u64> {
+        self.paris_block_and_final_difficulty.map(|(block, _)| block)
+    }
+
     fn is_optimism(&self) -> bool {
         Self::is_optimism(self)
     }
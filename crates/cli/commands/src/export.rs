@@ -0,0 +1,620 @@
+//! Command that exports a range of canonical blocks from the database.
+use crate::common::{AccessRights, Environment, EnvironmentArgs};
+use alloy_primitives::{BlockNumber, Keccak256, B256};
+use alloy_rlp::Encodable;
+use clap::{Parser, ValueEnum};
+use itertools::Itertools;
+use reth_chainspec::{EthChainSpec, EthereumHardforks};
+use reth_cli::chainspec::ChainSpecParser;
+use reth_db::{static_file::iter_static_files, tables};
+use reth_db_api::{
+    models::{StoredBlockBodyIndices, StoredBlockOmmers, StoredBlockWithdrawals},
+    transaction::DbTxMut,
+};
+use reth_node_builder::NodeTypesWithEngine;
+use reth_primitives::{
+    proofs::{
+        calculate_ommers_root, calculate_requests_root, calculate_transaction_root,
+        calculate_withdrawals_root,
+    },
+    Header, Requests, StaticFileSegment, Withdrawals,
+};
+use reth_provider::{
+    providers::{StaticFileProvider, StaticFileWriter},
+    BlockHashReader, BlockNumReader, BlockReader, DBProvider, HeaderProvider,
+    StageCheckpointWriter, StaticFileProviderFactory, TransactionsProvider,
+};
+use reth_stages::{StageCheckpoint, StageId};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    io::{BufWriter, Read, Write},
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
+use tracing::info;
+
+/// Number of blocks loaded from the database at once when writing RLP.
+const RLP_EXPORT_BATCH_SIZE: u64 = 1_000;
+
+/// Name of the manifest file written next to the jars of a static file bundle.
+pub const STATIC_FILE_BUNDLE_MANIFEST: &str = "manifest.json";
+
+/// Exports canonical blocks to a file or a static file bundle.
+#[derive(Debug, Parser)]
+pub struct ExportCommand<C: ChainSpecParser> {
+    #[command(flatten)]
+    env: EnvironmentArgs<C>,
+
+    /// The first block to export.
+    #[arg(long, value_name = "BLOCK", default_value_t = 0)]
+    from: BlockNumber,
+
+    /// The last block to export, inclusive. Defaults to the highest block in the database.
+    #[arg(long, value_name = "BLOCK")]
+    to: Option<BlockNumber>,
+
+    /// The output format.
+    #[arg(long, value_enum, default_value_t = ExportFormat::Rlp)]
+    format: ExportFormat,
+
+    /// The output path.
+    ///
+    /// For `rlp`, this is the file the blocks are written to. For `static-files`, this is the
+    /// directory the bundle is written to, which must not already contain a bundle.
+    #[arg(value_name = "EXPORT_PATH", verbatim_doc_comment)]
+    path: PathBuf,
+}
+
+/// Output formats supported by [`ExportCommand`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// RLP encoded blocks, one after another, readable by `reth import`.
+    Rlp,
+    /// A copy of the static file jars covering the range, alongside a manifest.
+    StaticFiles,
+}
+
+impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> ExportCommand<C> {
+    /// Execute `export` command
+    pub async fn execute<N: NodeTypesWithEngine<ChainSpec = C::ChainSpec>>(
+        self,
+    ) -> eyre::Result<()> {
+        let Environment { provider_factory, .. } = self.env.init::<N>(AccessRights::RO)?;
+
+        let last_block = provider_factory.last_block_number()?;
+        let to = self.to.unwrap_or(last_block);
+        if self.from > to {
+            eyre::bail!("invalid block range {}..={}", self.from, to)
+        }
+        if to > last_block {
+            eyre::bail!("block {to} is above the highest block in the database {last_block}")
+        }
+
+        match self.format {
+            ExportFormat::Rlp => {
+                let mut writer = BufWriter::new(reth_fs_util::create_file(&self.path)?);
+                let mut buf = Vec::new();
+                let mut total_blocks = 0;
+                let mut total_txns = 0;
+
+                let mut start = self.from;
+                while start <= to {
+                    let end = start.saturating_add(RLP_EXPORT_BATCH_SIZE - 1).min(to);
+                    let blocks = provider_factory.block_range(start..=end)?;
+                    if blocks.len() as u64 != end - start + 1 {
+                        eyre::bail!("missing block bodies in range {start}..={end}")
+                    }
+
+                    for block in blocks {
+                        total_txns += block.body.transactions.len();
+                        buf.clear();
+                        block.encode(&mut buf);
+                        writer.write_all(&buf)?;
+                    }
+                    total_blocks += end - start + 1;
+
+                    info!(target: "reth::cli", block = end, to, "Exported blocks");
+                    start = end + 1;
+                }
+                writer.flush()?;
+
+                info!(target: "reth::cli", total_blocks, total_txns, path = ?self.path, "Chain file exported");
+            }
+            ExportFormat::StaticFiles => {
+                let bodies = bundle_bodies(&provider_factory.provider()?, self.from..=to)?;
+                let bundle = StaticFileBundle::write(
+                    &provider_factory.static_file_provider(),
+                    self.env.chain.chain().id(),
+                    self.from,
+                    bodies,
+                    &self.path,
+                )?;
+                info!(target: "reth::cli", path = ?self.path, "Verifying static file bundle");
+                bundle.verify(None)?;
+
+                info!(target: "reth::cli",
+                    from = bundle.manifest.from,
+                    to = bundle.manifest.to,
+                    files = bundle.manifest.files.len(),
+                    "Static file bundle exported"
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Collects the parts of the bodies of blocks in `range` that are kept in the database rather
+/// than in static files.
+pub fn bundle_bodies<Provider: BlockReader>(
+    provider: &Provider,
+    range: RangeInclusive<BlockNumber>,
+) -> eyre::Result<Vec<StaticFileBundleBody>> {
+    range
+        .map(|number| {
+            let indices = provider
+                .block_body_indices(number)?
+                .ok_or_else(|| eyre::eyre!("missing body indices for block {number}"))?;
+            let timestamp = provider
+                .header_by_number(number)?
+                .ok_or_else(|| eyre::eyre!("missing header for block {number}"))?
+                .timestamp;
+            Ok(StaticFileBundleBody {
+                indices,
+                ommers: provider.ommers(number.into())?.unwrap_or_default(),
+                withdrawals: provider.withdrawals_by_block(number.into(), timestamp)?,
+                requests: provider.requests_by_block(number.into(), timestamp)?,
+            })
+        })
+        .collect()
+}
+
+/// Describes the content of a static file bundle written by [`ExportCommand`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StaticFileBundleManifest {
+    /// Chain ID the blocks belong to.
+    pub chain_id: u64,
+    /// First exported block.
+    pub from: BlockNumber,
+    /// Last exported block, inclusive.
+    pub to: BlockNumber,
+    /// Parent hash of the first exported block.
+    pub parent_hash: B256,
+    /// Hash of the last exported block.
+    pub tip_hash: B256,
+    /// Static file jars that are part of the bundle, by file name.
+    pub files: Vec<StaticFileBundleEntry>,
+    /// Bodies of every exported block, starting at [`Self::from`].
+    pub bodies: Vec<StaticFileBundleBody>,
+}
+
+/// The parts of a block body in a [`StaticFileBundleManifest`] that are not in static files.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StaticFileBundleBody {
+    /// Transaction indices of the block. Static files have no block to transaction mapping, so
+    /// this is required to check transaction roots.
+    pub indices: StoredBlockBodyIndices,
+    /// Ommers of the block.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ommers: Vec<Header>,
+    /// Withdrawals of the block, if Shanghai is active.
+    pub withdrawals: Option<Withdrawals>,
+    /// Requests of the block, if Prague is active.
+    pub requests: Option<Requests>,
+}
+
+/// A single static file jar in a [`StaticFileBundleManifest`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StaticFileBundleEntry {
+    /// Segment of the jar.
+    pub segment: StaticFileSegment,
+    /// Base file name of the jar, without extension.
+    pub file_name: String,
+    /// Keccak256 hash of every file of the jar, by file name.
+    pub checksums: BTreeMap<String, B256>,
+}
+
+/// A directory holding static file jars and a [`StaticFileBundleManifest`].
+#[derive(Debug)]
+pub struct StaticFileBundle {
+    /// Directory of the bundle.
+    pub path: PathBuf,
+    /// Manifest of the bundle.
+    pub manifest: StaticFileBundleManifest,
+}
+
+impl StaticFileBundle {
+    /// Opens an existing bundle by reading its manifest.
+    pub fn open(path: impl AsRef<Path>) -> eyre::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let manifest = reth_fs_util::read_json_file(&path.join(STATIC_FILE_BUNDLE_MANIFEST))?;
+        Ok(Self { path, manifest })
+    }
+
+    /// Copies every jar of `static_file_provider` that holds data for blocks `from..` (as
+    /// described by `bodies`) into `path`, and writes the manifest.
+    ///
+    /// Jars are copied whole, so the bundle may contain data outside of the exported range.
+    pub fn write(
+        static_file_provider: &StaticFileProvider,
+        chain_id: u64,
+        from: BlockNumber,
+        bodies: Vec<StaticFileBundleBody>,
+        path: impl AsRef<Path>,
+    ) -> eyre::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if path.join(STATIC_FILE_BUNDLE_MANIFEST).exists() {
+            eyre::bail!("{} already contains a static file bundle", path.display())
+        }
+        let to = from + bodies.len().saturating_sub(1) as u64;
+
+        let (parent_hash, tip_hash) = (
+            static_file_provider
+                .header_by_number(from)?
+                .ok_or_else(|| eyre::eyre!("header {from} is not in static files"))?
+                .parent_hash,
+            static_file_provider
+                .block_hash(to)?
+                .ok_or_else(|| eyre::eyre!("header {to} is not in static files"))?,
+        );
+
+        reth_fs_util::create_dir_all(&path)?;
+
+        let static_files = iter_static_files(static_file_provider.directory())?;
+        let mut files = Vec::new();
+        for (segment, ranges) in static_files.into_iter().sorted_by_key(|(segment, _)| *segment) {
+            for (block_range, _) in ranges {
+                if block_range.end() < from || block_range.start() > to {
+                    continue
+                }
+
+                let file_name =
+                    segment.filename(&static_file_provider.find_fixed_range(block_range.start()));
+                let jar_path = static_file_provider.directory().join(&file_name);
+                let mut checksums = BTreeMap::new();
+                for extension in ["", "conf", "off", "idx"] {
+                    let source = if extension.is_empty() {
+                        jar_path.clone()
+                    } else {
+                        jar_path.with_extension(extension)
+                    };
+                    // Offsets and index files are optional, depending on the jar.
+                    if !source.exists() {
+                        continue
+                    }
+                    let name = source.file_name().expect("is a file");
+                    let destination = path.join(name);
+                    std::fs::copy(&source, &destination)?;
+                    checksums
+                        .insert(name.to_string_lossy().into_owned(), file_checksum(&destination)?);
+                }
+
+                info!(target: "reth::cli", %segment, %block_range, "Copied static file");
+                files.push(StaticFileBundleEntry { segment, file_name, checksums });
+            }
+        }
+
+        let manifest =
+            StaticFileBundleManifest { chain_id, from, to, parent_hash, tip_hash, files, bodies };
+        reth_fs_util::write_json_file(&path.join(STATIC_FILE_BUNDLE_MANIFEST), &manifest)?;
+
+        Ok(Self { path, manifest })
+    }
+
+    /// Checks that every file of the bundle matches the checksum recorded in the manifest.
+    ///
+    /// This should be run before opening the jars, as their configuration is deserialized
+    /// without further checks.
+    pub fn verify_checksums(&self) -> eyre::Result<()> {
+        for entry in &self.manifest.files {
+            if entry.checksums.is_empty() {
+                eyre::bail!("static file {} has no checksums", entry.file_name)
+            }
+            for (name, expected) in &entry.checksums {
+                let checksum = file_checksum(&self.path.join(name))?;
+                if checksum != *expected {
+                    eyre::bail!(
+                        "static file {name} checksum mismatch: expected {expected}, computed {checksum}"
+                    )
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Verifies the content of the bundle against its manifest.
+    ///
+    /// Every header is re-hashed and checked to be chained to its parent, starting at
+    /// `expected_parent` if provided, and the transaction, ommers, withdrawals and requests roots
+    /// of every block are recomputed from the bundle.
+    pub fn verify(&self, expected_parent: Option<B256>) -> eyre::Result<()> {
+        let manifest = &self.manifest;
+        if manifest.bodies.len() as u64 != manifest.to - manifest.from + 1 {
+            eyre::bail!("manifest bodies do not match range {}..={}", manifest.from, manifest.to)
+        }
+        if let Some(expected_parent) = expected_parent {
+            if manifest.parent_hash != expected_parent {
+                eyre::bail!(
+                    "bundle parent hash {} does not match expected parent {expected_parent}",
+                    manifest.parent_hash
+                )
+            }
+        }
+
+        let provider = StaticFileProvider::read_only(&self.path, false)?;
+
+        let mut parent_hash = manifest.parent_hash;
+        for (number, body) in (manifest.from..=manifest.to).zip(&manifest.bodies) {
+            let header = provider
+                .sealed_header(number)?
+                .ok_or_else(|| eyre::eyre!("header {number} is missing from the bundle"))?;
+
+            let hash = header.header().hash_slow();
+            if hash != header.hash() {
+                eyre::bail!(
+                    "header {number} hash mismatch: stored {}, computed {hash}",
+                    header.hash()
+                )
+            }
+            if header.parent_hash != parent_hash {
+                eyre::bail!("header {number} is not a child of {parent_hash}")
+            }
+
+            let transactions = provider.transactions_by_tx_range(body.indices.tx_num_range())?;
+            if transactions.len() as u64 != body.indices.tx_count() {
+                eyre::bail!("block {number} is missing transactions in the bundle")
+            }
+            let transactions_root = calculate_transaction_root(
+                &transactions.into_iter().map(|tx| tx.with_hash()).collect::<Vec<_>>(),
+            );
+            if transactions_root != header.transactions_root {
+                eyre::bail!(
+                    "block {number} transaction root mismatch: expected {}, computed {transactions_root}",
+                    header.transactions_root
+                )
+            }
+            if calculate_ommers_root(&body.ommers) != header.ommers_hash {
+                eyre::bail!("block {number} ommers hash mismatch")
+            }
+            if body.withdrawals.as_ref().map(|withdrawals| calculate_withdrawals_root(withdrawals)) !=
+                header.withdrawals_root
+            {
+                eyre::bail!("block {number} withdrawals root mismatch")
+            }
+            if body.requests.as_ref().map(|requests| calculate_requests_root(&requests.0)) !=
+                header.requests_root
+            {
+                eyre::bail!("block {number} requests root mismatch")
+            }
+
+            parent_hash = hash;
+        }
+
+        if parent_hash != manifest.tip_hash {
+            eyre::bail!(
+                "bundle tip {parent_hash} does not match manifest tip {}",
+                manifest.tip_hash
+            )
+        }
+
+        Ok(())
+    }
+
+    /// Appends the blocks of the bundle to the static files and database of `provider`, and moves
+    /// the headers and bodies stages to the tip of the bundle.
+    ///
+    /// The bundle must directly follow the highest block of `provider`, and should have been
+    /// checked with [`Self::verify_checksums`] and [`Self::verify`] beforehand. Nothing is
+    /// persisted until the provider and its static file provider are committed.
+    pub fn attach<Provider>(&self, provider: &Provider) -> eyre::Result<()>
+    where
+        Provider: DBProvider<Tx: DbTxMut>
+            + StaticFileProviderFactory
+            + HeaderProvider
+            + StageCheckpointWriter,
+    {
+        let manifest = &self.manifest;
+        let bundle = StaticFileProvider::read_only(&self.path, false)?;
+        let static_file_provider = provider.static_file_provider();
+        let tx = provider.tx_ref();
+
+        let parent = manifest
+            .from
+            .checked_sub(1)
+            .ok_or_else(|| eyre::eyre!("bundles starting at genesis cannot be attached"))?;
+        let mut td = provider
+            .header_td_by_number(parent)?
+            .ok_or_else(|| eyre::eyre!("missing total difficulty of block {parent}"))?;
+
+        let mut headers_writer = static_file_provider.latest_writer(StaticFileSegment::Headers)?;
+        for number in manifest.from..=manifest.to {
+            let header = bundle
+                .sealed_header(number)?
+                .ok_or_else(|| eyre::eyre!("header {number} is missing from the bundle"))?;
+            td += header.difficulty;
+            headers_writer.append_header(header.header(), td, &header.hash())?;
+            tx.put::<tables::HeaderNumbers>(header.hash(), number)?;
+        }
+        drop(headers_writer);
+
+        let mut next_tx_num = static_file_provider
+            .get_highest_static_file_tx(StaticFileSegment::Transactions)
+            .map(|tx_num| tx_num + 1)
+            .unwrap_or_default();
+        let mut transactions_writer =
+            static_file_provider.latest_writer(StaticFileSegment::Transactions)?;
+        for (number, body) in (manifest.from..=manifest.to).zip(&manifest.bodies) {
+            // Transaction numbers are global, so they only line up if the bundle directly follows
+            // the transactions of the node.
+            if body.indices.first_tx_num != next_tx_num {
+                eyre::bail!(
+                    "block {number} starts at transaction {}, expected {next_tx_num}",
+                    body.indices.first_tx_num
+                )
+            }
+
+            transactions_writer.increment_block(number)?;
+            let transactions = bundle.transactions_by_tx_range(body.indices.tx_num_range())?;
+            for transaction in &transactions {
+                transactions_writer.append_transaction(next_tx_num, transaction)?;
+                next_tx_num += 1;
+            }
+
+            if !body.indices.is_empty() {
+                tx.put::<tables::TransactionBlocks>(body.indices.last_tx_num(), number)?;
+            }
+            if !body.ommers.is_empty() {
+                tx.put::<tables::BlockOmmers>(
+                    number,
+                    StoredBlockOmmers { ommers: body.ommers.clone() },
+                )?;
+            }
+            if let Some(withdrawals) = body.withdrawals.clone().filter(|w| !w.is_empty()) {
+                tx.put::<tables::BlockWithdrawals>(number, StoredBlockWithdrawals { withdrawals })?;
+            }
+            if let Some(requests) = body.requests.clone().filter(|r| !r.0.is_empty()) {
+                tx.put::<tables::BlockRequests>(number, requests)?;
+            }
+            tx.put::<tables::BlockBodyIndices>(number, body.indices)?;
+        }
+        drop(transactions_writer);
+
+        for stage in [StageId::Headers, StageId::Bodies] {
+            provider.save_stage_checkpoint(stage, StageCheckpoint::new(manifest.to))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the keccak256 hash of the content of the file at `path`.
+fn file_checksum(path: &Path) -> eyre::Result<B256> {
+    let mut file =
+        std::fs::File::open(path).map_err(|err| reth_fs_util::FsPathError::open(err, path))?;
+    let mut hasher = Keccak256::new();
+    let mut buf = vec![0; 64 * 1024];
+    loop {
+        let read = file.read(&mut buf)?;
+        if read == 0 {
+            break
+        }
+        hasher.update(&buf[..read]);
+    }
+    Ok(hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_ethereum_cli::chainspec::EthereumChainSpecParser;
+    use reth_provider::writer::UnifiedStorageWriter;
+    use reth_stages::test_utils::{StorageKind, TestStageDB};
+    use reth_testing_utils::generators::{self, random_block_range, BlockRangeParams};
+
+    fn insert_blocks(db: &TestStageDB, blocks: &[reth_primitives::SealedBlock]) {
+        db.insert_blocks(blocks.iter(), StorageKind::Static).unwrap();
+        // Ommers are not written by the test database.
+        db.commit(|tx| {
+            for block in blocks.iter().filter(|block| !block.body.ommers.is_empty()) {
+                tx.put::<tables::BlockOmmers>(
+                    block.number,
+                    StoredBlockOmmers { ommers: block.body.ommers.clone() },
+                )?;
+            }
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn parse_export_command() {
+        let args: ExportCommand<EthereumChainSpecParser> = ExportCommand::parse_from([
+            "reth",
+            "--from",
+            "10",
+            "--to",
+            "20",
+            "--format",
+            "static-files",
+            "bundle",
+        ]);
+        assert_eq!(args.from, 10);
+        assert_eq!(args.to, Some(20));
+        assert_eq!(args.format, ExportFormat::StaticFiles);
+        assert_eq!(args.path, PathBuf::from("bundle"));
+    }
+
+    #[test]
+    fn export_and_attach_bundle() {
+        let mut rng = generators::rng();
+        let blocks = random_block_range(
+            &mut rng,
+            0..=20,
+            BlockRangeParams { tx_count: 0..3, ..Default::default() },
+        );
+
+        let source = TestStageDB::default();
+        insert_blocks(&source, &blocks);
+        let destination = TestStageDB::default();
+        insert_blocks(&destination, &blocks[..=10]);
+
+        let bundle_dir = tempfile::tempdir().unwrap();
+        let bodies = bundle_bodies(&source.factory.provider().unwrap(), 11..=20).unwrap();
+        let bundle = StaticFileBundle::write(
+            &source.factory.static_file_provider(),
+            1,
+            11,
+            bodies,
+            bundle_dir.path(),
+        )
+        .unwrap();
+
+        let bundle = StaticFileBundle::open(&bundle.path).unwrap();
+        assert_eq!(bundle.manifest.tip_hash, blocks[20].hash());
+        bundle.verify_checksums().unwrap();
+        bundle.verify(Some(blocks[10].hash())).unwrap();
+        assert!(bundle.verify(Some(blocks[9].hash())).is_err());
+
+        let provider_rw = destination.factory.provider_rw().unwrap();
+        bundle.attach(&provider_rw.0).unwrap();
+        UnifiedStorageWriter::commit(provider_rw, destination.factory.static_file_provider())
+            .unwrap();
+
+        let provider = destination.factory.provider().unwrap();
+        for block in &blocks[11..] {
+            assert_eq!(provider.block(block.number.into()).unwrap(), Some(block.clone().unseal()));
+        }
+    }
+
+    #[test]
+    fn bundle_checksum_mismatch() {
+        let mut rng = generators::rng();
+        let blocks = random_block_range(
+            &mut rng,
+            0..=5,
+            BlockRangeParams { tx_count: 1..3, ..Default::default() },
+        );
+        let source = TestStageDB::default();
+        insert_blocks(&source, &blocks);
+
+        let bundle_dir = tempfile::tempdir().unwrap();
+        let bodies = bundle_bodies(&source.factory.provider().unwrap(), 1..=5).unwrap();
+        let bundle = StaticFileBundle::write(
+            &source.factory.static_file_provider(),
+            1,
+            1,
+            bodies,
+            bundle_dir.path(),
+        )
+        .unwrap();
+        bundle.verify_checksums().unwrap();
+
+        let entry = &bundle.manifest.files[0];
+        let mut data = reth_fs_util::read(bundle.path.join(&entry.file_name)).unwrap();
+        *data.last_mut().unwrap() ^= 1;
+        reth_fs_util::write(bundle.path.join(&entry.file_name), data).unwrap();
+        assert!(bundle.verify_checksums().is_err());
+    }
+}
@@ -30,7 +30,7 @@ use reth_provider::{
 use reth_prune::PruneModes;
 use reth_stages::{prelude::*, Pipeline, StageId, StageSet};
 use reth_static_file::StaticFileProducer;
-use std::{path::PathBuf, sync::Arc};
+use std::{num::NonZeroUsize, path::PathBuf, sync::Arc};
 use tokio::sync::watch;
 use tracing::{debug, error, info};
 
@@ -48,6 +48,12 @@ pub struct ImportCommand<C: ChainSpecParser> {
     #[arg(long, value_name = "CHUNK_LEN", verbatim_doc_comment)]
     chunk_len: Option<u64>,
 
+    /// Executes the transactions of each block in parallel on the given number of workers.
+    ///
+    /// Ignored if the executor doesn't support parallel execution.
+    #[arg(long, value_name = "WORKERS", verbatim_doc_comment)]
+    parallel_workers: Option<NonZeroUsize>,
+
     /// The path to a block file for import.
     ///
     /// The online stages (headers and bodies) are replaced by a file import, after which the
@@ -57,6 +63,12 @@ pub struct ImportCommand<C: ChainSpecParser> {
 }
 
 impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> ImportCommand<C> {
+    /// Returns the number of workers to execute the transactions of each block on in parallel, if
+    /// enabled.
+    pub const fn parallel_workers(&self) -> Option<NonZeroUsize> {
+        self.parallel_workers
+    }
+
     /// Execute `import` command
     pub async fn execute<N, E, F>(self, executor: F) -> eyre::Result<()>
     where
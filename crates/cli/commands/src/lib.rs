@@ -12,6 +12,7 @@ pub mod common;
 pub mod config_cmd;
 pub mod db;
 pub mod dump_genesis;
+pub mod export;
 pub mod import;
 pub mod init_cmd;
 pub mod init_state;
@@ -20,6 +21,7 @@ pub mod p2p;
 pub mod prune;
 pub mod recover;
 pub mod stage;
+pub mod static_file;
 #[cfg(feature = "dev")]
 pub mod test_vectors;
 
@@ -84,7 +84,7 @@ impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> Command<C>
             }
 
             // This will build an offline-only pipeline if the `offline` flag is enabled
-            let mut pipeline = self.build_pipeline(config, provider_factory)?;
+            let mut pipeline = unwind_pipeline(config, provider_factory, self.offline)?;
 
             // Move all applicable data from database to static files.
             pipeline.move_to_static_files()?;
@@ -115,61 +115,68 @@ impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> Command<C>
 
         Ok(())
     }
+}
 
-    fn build_pipeline<N: NodeTypesWithDB<ChainSpec = C::ChainSpec>>(
-        self,
-        config: Config,
-        provider_factory: ProviderFactory<N>,
-    ) -> Result<Pipeline<N>, eyre::Error> {
-        let consensus: Arc<dyn Consensus> =
-            Arc::new(EthBeaconConsensus::new(provider_factory.chain_spec()));
-        let stage_conf = &config.stages;
-        let prune_modes = config.prune.clone().map(|prune| prune.segments).unwrap_or_default();
-
-        let (tip_tx, tip_rx) = watch::channel(B256::ZERO);
-
-        // Unwinding does not require a valid executor
-        let executor = NoopBlockExecutorProvider::default();
-
-        let builder = if self.offline {
-            Pipeline::<N>::builder().add_stages(
-                OfflineStages::new(executor, config.stages, PruneModes::default())
-                    .builder()
-                    .disable(reth_stages::StageId::SenderRecovery),
-            )
-        } else {
-            Pipeline::<N>::builder().with_tip_sender(tip_tx).add_stages(
-                DefaultStages::new(
-                    provider_factory.clone(),
-                    tip_rx,
-                    Arc::clone(&consensus),
-                    NoopHeaderDownloader::default(),
-                    NoopBodiesDownloader::default(),
-                    executor.clone(),
-                    stage_conf.clone(),
-                    prune_modes.clone(),
-                )
-                .set(ExecutionStage::new(
-                    executor,
-                    ExecutionStageThresholds {
-                        max_blocks: None,
-                        max_changes: None,
-                        max_cumulative_gas: None,
-                        max_duration: None,
-                    },
-                    stage_conf.execution_external_clean_threshold(),
-                    prune_modes,
-                    ExExManagerHandle::empty(),
-                )),
+/// Builds a pipeline that can only be used for unwinding, as it has no downloaders and no valid
+/// executor.
+///
+/// If `offline` is set, only the data associated with offline stages is unwound.
+pub(crate) fn unwind_pipeline<N>(
+    config: Config,
+    provider_factory: ProviderFactory<N>,
+    offline: bool,
+) -> Result<Pipeline<N>, eyre::Error>
+where
+    N: NodeTypesWithDB<ChainSpec: EthChainSpec + EthereumHardforks>,
+{
+    let consensus: Arc<dyn Consensus> =
+        Arc::new(EthBeaconConsensus::new(provider_factory.chain_spec()));
+    let stage_conf = &config.stages;
+    let prune_modes = config.prune.clone().map(|prune| prune.segments).unwrap_or_default();
+
+    let (tip_tx, tip_rx) = watch::channel(B256::ZERO);
+
+    // Unwinding does not require a valid executor
+    let executor = NoopBlockExecutorProvider::default();
+
+    let builder = if offline {
+        Pipeline::<N>::builder().add_stages(
+            OfflineStages::new(executor, config.stages, PruneModes::default())
+                .builder()
+                .disable(reth_stages::StageId::SenderRecovery),
+        )
+    } else {
+        Pipeline::<N>::builder().with_tip_sender(tip_tx).add_stages(
+            DefaultStages::new(
+                provider_factory.clone(),
+                tip_rx,
+                Arc::clone(&consensus),
+                NoopHeaderDownloader::default(),
+                NoopBodiesDownloader::default(),
+                executor.clone(),
+                stage_conf.clone(),
+                prune_modes.clone(),
             )
-        };
-
-        let pipeline = builder.build(
-            provider_factory.clone(),
-            StaticFileProducer::new(provider_factory, PruneModes::default()),
-        );
-        Ok(pipeline)
-    }
+            .set(ExecutionStage::new(
+                executor,
+                ExecutionStageThresholds {
+                    max_blocks: None,
+                    max_changes: None,
+                    max_cumulative_gas: None,
+                    max_duration: None,
+                },
+                stage_conf.execution_external_clean_threshold(),
+                prune_modes,
+                ExExManagerHandle::empty(),
+            )),
+        )
+    };
+
+    let pipeline = builder.build(
+        provider_factory.clone(),
+        StaticFileProducer::new(provider_factory, PruneModes::default()),
+    );
+    Ok(pipeline)
 }
 
 /// `reth stage unwind` subcommand
@@ -0,0 +1,75 @@
+use crate::{
+    common::{AccessRights, Environment, EnvironmentArgs},
+    export::StaticFileBundle,
+};
+use clap::Parser;
+use reth_chainspec::{EthChainSpec, EthereumHardforks};
+use reth_cli::chainspec::ChainSpecParser;
+use reth_node_builder::NodeTypesWithEngine;
+use reth_provider::{
+    writer::UnifiedStorageWriter, BlockHashReader, BlockNumReader, StaticFileProviderFactory,
+};
+use std::path::PathBuf;
+use tracing::info;
+
+/// `reth static-file attach` command
+///
+/// Appends the blocks of a static file bundle written by `reth export --format static-files` to
+/// the node. The bundle must directly follow the highest block of the node, and every file of the
+/// bundle is checked against the checksums of its manifest before it is opened.
+#[derive(Debug, Parser)]
+pub struct Command<C: ChainSpecParser> {
+    #[command(flatten)]
+    env: EnvironmentArgs<C>,
+
+    /// The directory of the bundle.
+    #[arg(value_name = "BUNDLE_PATH")]
+    path: PathBuf,
+}
+
+impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> Command<C> {
+    /// Execute `static-file attach` command
+    pub async fn execute<N: NodeTypesWithEngine<ChainSpec = C::ChainSpec>>(
+        self,
+    ) -> eyre::Result<()> {
+        let Environment { provider_factory, .. } = self.env.init::<N>(AccessRights::RW)?;
+
+        let bundle = StaticFileBundle::open(&self.path)?;
+        let chain_id = self.env.chain.chain().id();
+        if bundle.manifest.chain_id != chain_id {
+            eyre::bail!(
+                "bundle is for chain {}, but the node is running chain {chain_id}",
+                bundle.manifest.chain_id
+            )
+        }
+
+        info!(target: "reth::cli", path = ?self.path, "Verifying static file bundle checksums");
+        bundle.verify_checksums()?;
+
+        let provider_rw = provider_factory.provider_rw()?;
+        let last_block = provider_rw.last_block_number()?;
+        if bundle.manifest.from != last_block + 1 {
+            eyre::bail!(
+                "bundle starts at block {}, but the highest block of the node is {last_block}",
+                bundle.manifest.from
+            )
+        }
+        let parent_hash = provider_rw
+            .block_hash(last_block)?
+            .ok_or_else(|| eyre::eyre!("missing hash of block {last_block}"))?;
+
+        info!(target: "reth::cli", path = ?self.path, "Verifying static file bundle");
+        bundle.verify(Some(parent_hash))?;
+
+        bundle.attach(&provider_rw.0)?;
+        UnifiedStorageWriter::commit(provider_rw, provider_factory.static_file_provider())?;
+
+        info!(target: "reth::cli",
+            from = bundle.manifest.from,
+            to = bundle.manifest.to,
+            "Static file bundle attached"
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,41 @@
+//! `reth static-file` command.
+
+use clap::{Parser, Subcommand};
+use reth_chainspec::{EthChainSpec, EthereumHardforks};
+use reth_cli::chainspec::ChainSpecParser;
+use reth_node_builder::NodeTypesWithEngine;
+
+mod attach;
+mod recompress;
+mod verify;
+
+/// `reth static-file` command
+#[derive(Debug, Parser)]
+pub struct Command<C: ChainSpecParser> {
+    #[command(subcommand)]
+    command: Subcommands<C>,
+}
+
+/// `reth static-file` subcommands
+#[derive(Subcommand, Debug)]
+pub enum Subcommands<C: ChainSpecParser> {
+    /// Verify the integrity of static files.
+    Verify(verify::Command<C>),
+    /// Rewrite the static files of a segment with a different compression.
+    Recompress(recompress::Command<C>),
+    /// Append the blocks of a static file bundle to the node.
+    Attach(attach::Command<C>),
+}
+
+impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> Command<C> {
+    /// Execute `static-file` command
+    pub async fn execute<N: NodeTypesWithEngine<ChainSpec = C::ChainSpec>>(
+        self,
+    ) -> eyre::Result<()> {
+        match self.command {
+            Subcommands::Verify(command) => command.execute::<N>().await,
+            Subcommands::Recompress(command) => command.execute::<N>().await,
+            Subcommands::Attach(command) => command.execute::<N>().await,
+        }
+    }
+}
@@ -0,0 +1,426 @@
+use crate::common::{AccessRights, Environment, EnvironmentArgs};
+use clap::Parser;
+use comfy_table::{Cell, Row, Table as ComfyTable};
+use eyre::ensure;
+use human_bytes::human_bytes;
+use reth_chainspec::{EthChainSpec, EthereumHardforks};
+use reth_cli::chainspec::ChainSpecParser;
+use reth_db::static_file::iter_static_files;
+use reth_nippy_jar::{
+    compression::Compressors, NippyJar, NippyJarCursor, NippyJarWriter, CONFIG_FILE_EXTENSION,
+    OFFSETS_FILE_EXTENSION,
+};
+use reth_node_builder::NodeTypesWithEngine;
+use reth_provider::StaticFileProviderFactory;
+use reth_static_file_types::{Compression, SegmentConfig, SegmentHeader, StaticFileSegment};
+use std::{
+    path::Path,
+    time::{Duration, Instant},
+};
+use tracing::info;
+
+/// Name of the directory inside the static files directory, where recompressed static files are
+/// staged before being swapped in.
+const STAGING_DIRECTORY: &str = "recompress";
+
+/// Extension of the marker file written once a staged static file is complete.
+const READY_FILE_EXTENSION: &str = "ready";
+
+/// Maximum number of rows sampled to train the column dictionaries.
+const DICTIONARY_SAMPLE_ROWS: usize = 100_000;
+
+/// `reth static-file recompress` command
+///
+/// Rewrites the static files of a segment with a different compression. Each static file is
+/// written to a staging directory first, and only swapped in once it's complete.
+///
+/// Recompression is offline only: it doesn't run in the background of a running node, and the
+/// swap isn't atomic. A static file consists of a data, an offsets and a configuration file that
+/// can't be replaced all at once, and a running node keeps them memory mapped. Instead, the node
+/// must be stopped while the command runs, which is enforced by opening the database with write
+/// access, and the swap is made crash safe with a marker file: if the command is interrupted, it
+/// must be run again before starting the node, to finish the pending swaps.
+///
+/// The static file with the highest block range is skipped, since the node appends to it, and
+/// static files compressed with dictionaries can't be appended to.
+#[derive(Debug, Parser)]
+pub struct Command<C: ChainSpecParser> {
+    #[command(flatten)]
+    env: EnvironmentArgs<C>,
+
+    /// Segment to recompress.
+    #[arg(long)]
+    segment: StaticFileSegment,
+
+    /// Compression to use. Defaults to the segment compression of the configuration file.
+    #[arg(long, value_enum)]
+    compression: Option<Compression>,
+
+    /// Maximum size of each column dictionary in bytes, when compressing with `zstd-dict`.
+    #[arg(long, value_name = "BYTES")]
+    max_dict_size: Option<usize>,
+
+    /// Number of rows read from each static file to measure its read latency.
+    #[arg(long, default_value_t = 10_000)]
+    latency_samples: usize,
+}
+
+impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> Command<C> {
+    /// Execute `static-file recompress` command
+    pub async fn execute<N: NodeTypesWithEngine<ChainSpec = C::ChainSpec>>(
+        self,
+    ) -> eyre::Result<()> {
+        // Opening with write access takes the storage lock, which makes sure that no node is
+        // reading the static files while they are swapped.
+        let Environment { config, provider_factory, .. } = self.env.init::<N>(AccessRights::RW)?;
+        let static_file_provider = provider_factory.static_file_provider();
+
+        let mut segment_config = config.static_files.segment(self.segment);
+        if let Some(compression) = self.compression {
+            segment_config.compression = compression;
+        }
+        if let Some(max_dict_size) = self.max_dict_size {
+            segment_config.max_dict_size = max_dict_size;
+        }
+
+        let static_files_path = static_file_provider.directory();
+        let staging_path = static_files_path.join(STAGING_DIRECTORY);
+        finish_pending_swaps(&staging_path, static_files_path)?;
+        reth_fs_util::create_dir_all(&staging_path)?;
+
+        let mut ranges = iter_static_files(static_files_path)?
+            .remove(&self.segment)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(block_range, _)| static_file_provider.find_fixed_range(block_range.start()))
+            .collect::<Vec<_>>();
+        ranges.sort_by_key(|range| range.start());
+        // The node appends to the highest static file.
+        ranges.pop();
+
+        let mut table = ComfyTable::new();
+        table.load_preset(comfy_table::presets::ASCII_MARKDOWN);
+        table.set_header([
+            "Static File",
+            "Compression",
+            "Size",
+            "Read Latency (per row)",
+            "New Compression",
+            "New Size",
+            "New Read Latency (per row)",
+        ]);
+
+        let (mut total_size, mut total_new_size) = (0, 0);
+        for range in ranges {
+            let file_name = self.segment.filename(&range);
+            let jar = NippyJar::<SegmentHeader>::load(&static_files_path.join(&file_name))?;
+
+            let compression = jar_compression(&jar);
+            if compression == segment_config.compression {
+                info!(target: "reth::cli", file = %file_name, ?compression, "Skipping static file with the requested compression");
+                continue
+            }
+
+            info!(target: "reth::cli", file = %file_name, from = ?compression, to = ?segment_config.compression, "Recompressing static file");
+            let size = jar_size(&jar)?;
+            let latency = read_latency(&jar, self.latency_samples)?;
+
+            let new_jar = recompress(&jar, &staging_path.join(&file_name), segment_config)?;
+            let new_size = jar_size(&new_jar)?;
+            let new_latency = read_latency(&new_jar, self.latency_samples)?;
+
+            // From here on, the staged static file will be swapped in, even if interrupted.
+            reth_fs_util::write(staging_path.join(ready_file_name(&file_name)), b"")?;
+            swap(&staging_path, static_files_path, &file_name)?;
+
+            total_size += size;
+            total_new_size += new_size;
+
+            let mut row = Row::new();
+            row.add_cell(Cell::new(&file_name))
+                .add_cell(Cell::new(compression.as_ref()))
+                .add_cell(Cell::new(human_bytes(size as f64)))
+                .add_cell(Cell::new(format!("{latency:?}")))
+                .add_cell(Cell::new(segment_config.compression.as_ref()))
+                .add_cell(Cell::new(human_bytes(new_size as f64)))
+                .add_cell(Cell::new(format!("{new_latency:?}")));
+            table.add_row(row);
+        }
+
+        let mut row = Row::new();
+        row.add_cell(Cell::new("Total"))
+            .add_cell(Cell::new(""))
+            .add_cell(Cell::new(human_bytes(total_size as f64)))
+            .add_cell(Cell::new(""))
+            .add_cell(Cell::new(""))
+            .add_cell(Cell::new(human_bytes(total_new_size as f64)))
+            .add_cell(Cell::new(""));
+        table.add_row(row);
+
+        println!("{table}");
+
+        reth_fs_util::remove_dir_all(&staging_path)?;
+
+        Ok(())
+    }
+}
+
+/// Writes all rows of `jar` into a new static file at `path`, using the compression of `config`.
+fn recompress(
+    jar: &NippyJar<SegmentHeader>,
+    path: &Path,
+    config: SegmentConfig,
+) -> eyre::Result<NippyJar<SegmentHeader>> {
+    let new_jar = NippyJar::new(jar.columns(), path, jar.user_header().clone());
+    let new_jar = match config.compression {
+        Compression::Lz4 => new_jar.with_lz4(),
+        Compression::Zstd => new_jar.with_zstd(false, 0),
+        Compression::ZstdWithDictionary => {
+            let mut new_jar = new_jar.with_zstd(true, config.max_dict_size);
+            new_jar.prepare_compression(dictionary_samples(jar, config.max_dict_size)?)?;
+            new_jar
+        }
+        Compression::Uncompressed => new_jar,
+    };
+
+    let mut writer = NippyJarWriter::new(new_jar)?;
+    let mut cursor = NippyJarCursor::new(jar)?;
+    while let Some(row) = cursor.next_row()? {
+        for value in row {
+            writer.append_column(Some(Ok(value)))?;
+        }
+    }
+    writer.commit()?;
+
+    // Reload from disk, so the dictionaries can be used for decompression.
+    let new_jar = NippyJar::load(path)?;
+    ensure!(
+        new_jar.rows() == jar.rows(),
+        "recompressed static file has {} rows, expected {}",
+        new_jar.rows(),
+        jar.rows()
+    );
+
+    Ok(new_jar)
+}
+
+/// Samples rows evenly across `jar`, up to a total of `100 * max_dict_size` bytes per column, to
+/// train the column dictionaries.
+fn dictionary_samples(
+    jar: &NippyJar<SegmentHeader>,
+    max_dict_size: usize,
+) -> eyre::Result<Vec<Vec<Vec<u8>>>> {
+    let max_sample_size = max_dict_size * 100;
+    let step = (jar.rows() / DICTIONARY_SAMPLE_ROWS).max(1);
+
+    let mut samples = vec![vec![]; jar.columns()];
+    let mut sizes = vec![0; jar.columns()];
+    let mut cursor = NippyJarCursor::new(jar)?;
+    for row_number in (0..jar.rows()).step_by(step) {
+        let Some(row) = cursor.row_by_number(row_number)? else { break };
+        for (column, value) in row.into_iter().enumerate() {
+            if sizes[column] + value.len() <= max_sample_size {
+                sizes[column] += value.len();
+                samples[column].push(value.to_vec());
+            }
+        }
+    }
+
+    Ok(samples)
+}
+
+/// Returns the average time it takes to read a row, over `samples` rows spread evenly across
+/// `jar`.
+fn read_latency(jar: &NippyJar<SegmentHeader>, samples: usize) -> eyre::Result<Duration> {
+    let step = (jar.rows() / samples.max(1)).max(1);
+    let mut cursor = NippyJarCursor::new(jar)?;
+
+    let mut rows = 0u32;
+    let start = Instant::now();
+    for row_number in (0..jar.rows()).step_by(step).take(samples) {
+        cursor.row_by_number(row_number)?;
+        rows += 1;
+    }
+
+    Ok(start.elapsed().checked_div(rows).unwrap_or_default())
+}
+
+/// Returns the total size of the data, offsets and configuration files of `jar`.
+fn jar_size(jar: &NippyJar<SegmentHeader>) -> eyre::Result<u64> {
+    let mut size = 0;
+    for path in [jar.data_path().to_path_buf(), jar.offsets_path(), jar.config_path()] {
+        size += reth_fs_util::metadata(path)?.len();
+    }
+    Ok(size)
+}
+
+/// Returns the [`Compression`] of `jar`.
+const fn jar_compression(jar: &NippyJar<SegmentHeader>) -> Compression {
+    match jar.compressor() {
+        None => Compression::Uncompressed,
+        Some(Compressors::Lz4(_)) => Compression::Lz4,
+        Some(Compressors::Zstd(zstd)) if zstd.use_dict => Compression::ZstdWithDictionary,
+        Some(Compressors::Zstd(_)) => Compression::Zstd,
+    }
+}
+
+fn ready_file_name(file_name: &str) -> String {
+    format!("{file_name}.{READY_FILE_EXTENSION}")
+}
+
+/// Moves the files of a staged static file into the static files directory.
+///
+/// Each file is moved with a rename, and the configuration file goes last. Files already moved by
+/// an interrupted run are skipped. The static file is inconsistent until all files are moved, so
+/// it must not be read in the meantime.
+fn swap(staging_path: &Path, static_files_path: &Path, file_name: &str) -> eyre::Result<()> {
+    for file_name in [
+        file_name.to_string(),
+        format!("{file_name}.{OFFSETS_FILE_EXTENSION}"),
+        format!("{file_name}.{CONFIG_FILE_EXTENSION}"),
+    ] {
+        let staged = staging_path.join(&file_name);
+        if staged.exists() {
+            reth_fs_util::rename(staged, static_files_path.join(file_name))?;
+        }
+    }
+
+    reth_fs_util::remove_file(staging_path.join(ready_file_name(file_name)))?;
+
+    Ok(())
+}
+
+/// Swaps in every complete static file left in the staging directory by an interrupted run, and
+/// removes the incomplete ones.
+fn finish_pending_swaps(staging_path: &Path, static_files_path: &Path) -> eyre::Result<()> {
+    if !staging_path.exists() {
+        return Ok(())
+    }
+
+    for entry in reth_fs_util::read_dir(staging_path)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|extension| extension == READY_FILE_EXTENSION) {
+            if let Some(file_name) = path.file_stem().and_then(|name| name.to_str()) {
+                info!(target: "reth::cli", file = %file_name, "Finishing interrupted static file swap");
+                swap(staging_path, static_files_path, file_name)?;
+            }
+        }
+    }
+
+    reth_fs_util::remove_dir_all(staging_path)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::keccak256;
+    use reth_static_file_types::SegmentRangeInclusive;
+
+    const FILE_NAME: &str = "static_file_headers_0_499999";
+
+    fn staged_files(file_name: &str) -> [String; 3] {
+        [
+            file_name.to_string(),
+            format!("{file_name}.{OFFSETS_FILE_EXTENSION}"),
+            format!("{file_name}.{CONFIG_FILE_EXTENSION}"),
+        ]
+    }
+
+    #[test]
+    fn swap_static_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let (static_files_path, staging_path) = (dir.path(), dir.path().join(STAGING_DIRECTORY));
+        reth_fs_util::create_dir_all(&staging_path).unwrap();
+
+        for file in staged_files(FILE_NAME) {
+            reth_fs_util::write(static_files_path.join(&file), b"old").unwrap();
+            reth_fs_util::write(staging_path.join(&file), b"new").unwrap();
+        }
+        reth_fs_util::write(staging_path.join(ready_file_name(FILE_NAME)), b"").unwrap();
+
+        swap(&staging_path, static_files_path, FILE_NAME).unwrap();
+
+        for file in staged_files(FILE_NAME) {
+            assert_eq!(reth_fs_util::read(static_files_path.join(&file)).unwrap(), b"new");
+            assert!(!staging_path.join(&file).exists());
+        }
+        assert!(!staging_path.join(ready_file_name(FILE_NAME)).exists());
+    }
+
+    #[test]
+    fn finish_interrupted_swaps() {
+        let dir = tempfile::tempdir().unwrap();
+        let (static_files_path, staging_path) = (dir.path(), dir.path().join(STAGING_DIRECTORY));
+        reth_fs_util::create_dir_all(&staging_path).unwrap();
+
+        // Interrupted after the data file was moved.
+        let [data, offsets, config] = staged_files(FILE_NAME);
+        reth_fs_util::write(static_files_path.join(&data), b"new").unwrap();
+        for file in [&offsets, &config] {
+            reth_fs_util::write(static_files_path.join(file), b"old").unwrap();
+            reth_fs_util::write(staging_path.join(file), b"new").unwrap();
+        }
+        reth_fs_util::write(staging_path.join(ready_file_name(FILE_NAME)), b"").unwrap();
+
+        // Interrupted while recompressing, so never swapped in.
+        let incomplete = "static_file_headers_500000_999999";
+        reth_fs_util::write(static_files_path.join(incomplete), b"old").unwrap();
+        reth_fs_util::write(staging_path.join(incomplete), b"partial").unwrap();
+
+        finish_pending_swaps(&staging_path, static_files_path).unwrap();
+
+        for file in staged_files(FILE_NAME) {
+            assert_eq!(reth_fs_util::read(static_files_path.join(&file)).unwrap(), b"new");
+        }
+        assert_eq!(reth_fs_util::read(static_files_path.join(incomplete)).unwrap(), b"old");
+        assert!(!staging_path.exists());
+    }
+
+    #[test]
+    fn recompress_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let columns = StaticFileSegment::Headers.columns();
+        let rows = (0..100u64)
+            .map(|row| {
+                (0..columns as u64)
+                    .map(|column| keccak256((row * columns as u64 + column).to_be_bytes()).to_vec())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let header = SegmentHeader::new(
+            SegmentRangeInclusive::new(0, 499_999),
+            None,
+            None,
+            StaticFileSegment::Headers,
+        );
+        let jar = NippyJar::new(columns, &dir.path().join(FILE_NAME), header)
+            .with_lz4()
+            .freeze(
+                (0..columns)
+                    .map(|column| rows.iter().map(move |row| Ok(row[column].clone())))
+                    .collect(),
+                rows.len() as u64,
+            )
+            .unwrap();
+
+        for compression in [Compression::ZstdWithDictionary, Compression::Zstd, Compression::Lz4] {
+            let path = dir.path().join(format!("{FILE_NAME}_{compression:?}"));
+            let config = SegmentConfig { compression, max_dict_size: 5_000 };
+            let new_jar = recompress(&jar, &path, config).unwrap();
+            assert_eq!(jar_compression(&new_jar), compression);
+            assert_eq!(new_jar.user_header(), jar.user_header());
+
+            let mut cursor = NippyJarCursor::new(&new_jar).unwrap();
+            for row in &rows {
+                assert_eq!(
+                    cursor.next_row().unwrap().unwrap(),
+                    row.iter().map(Vec::as_slice).collect::<Vec<_>>()
+                );
+            }
+            assert!(cursor.next_row().unwrap().is_none());
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use crate::{
+    common::{AccessRights, Environment, EnvironmentArgs},
+    stage::unwind::unwind_pipeline,
+};
+use alloy_primitives::BlockNumber;
+use clap::Parser;
+use reth_chainspec::{EthChainSpec, EthereumHardforks};
+use reth_cli::chainspec::ChainSpecParser;
+use reth_config::Config;
+use reth_node_builder::{NodeTypesWithDB, NodeTypesWithEngine};
+use reth_provider::ProviderFactory;
+use reth_static_file::{StaticFileVerifier, StaticFileVerifierReport};
+use reth_static_file_types::StaticFileSegment;
+use tracing::{error, info};
+
+/// `reth static-file verify` command
+///
+/// Decompresses and decodes every row of every static file, and checks header hashes and
+/// transaction and receipt roots against the headers. With `--unwind`, the node is unwound below
+/// the lowest corrupted block, so that the pipeline fetches the data again from peers on the next
+/// start.
+#[derive(Debug, Parser)]
+pub struct Command<C: ChainSpecParser> {
+    #[command(flatten)]
+    env: EnvironmentArgs<C>,
+
+    /// Segments to verify. Defaults to all segments.
+    #[arg(long, value_delimiter = ',')]
+    segments: Vec<StaticFileSegment>,
+
+    /// Only verify static files containing blocks from this block onwards.
+    #[arg(long, value_name = "BLOCK")]
+    from: Option<BlockNumber>,
+
+    /// Only verify static files containing blocks up to this block, inclusive.
+    #[arg(long, value_name = "BLOCK")]
+    to: Option<BlockNumber>,
+
+    /// Unwind all stages below the lowest corrupted block, so that the corrupted ranges are
+    /// fetched again from peers on the next start of the node.
+    #[arg(long)]
+    unwind: bool,
+}
+
+impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> Command<C> {
+    /// Execute `static-file verify` command
+    pub async fn execute<N: NodeTypesWithEngine<ChainSpec = C::ChainSpec>>(
+        self,
+    ) -> eyre::Result<()> {
+        let access = if self.unwind { AccessRights::RW } else { AccessRights::RO };
+        let Environment { provider_factory, config, .. } = self.env.init::<N>(access)?;
+
+        let mut verifier = StaticFileVerifier::new(provider_factory.clone());
+        if !self.segments.is_empty() {
+            verifier = verifier.with_segments(self.segments);
+        }
+        if self.from.is_some() || self.to.is_some() {
+            verifier = verifier
+                .with_block_range(self.from.unwrap_or_default()..=self.to.unwrap_or(u64::MAX));
+        }
+
+        let report = verifier.run()?;
+        if report.is_ok() {
+            info!(target: "reth::cli", jars = report.verified.len(), "Static files verified");
+            return Ok(())
+        }
+
+        for (segment, range) in report.corrupt_ranges() {
+            error!(target: "reth::cli", %segment, ?range, "Corrupted static file range");
+        }
+
+        if self.unwind {
+            return unwind_below_corruption(config, provider_factory, &report)
+        }
+
+        eyre::bail!(
+            "found {} corrupted blocks, run again with `--unwind` to re-fetch them from peers",
+            report.corrupted.len()
+        )
+    }
+}
+
+/// Unwinds all stages below the lowest corrupted block of the report. Unwinding makes the pipeline
+/// fetch the data again from peers.
+fn unwind_below_corruption<N>(
+    config: Config,
+    provider_factory: ProviderFactory<N>,
+    report: &StaticFileVerifierReport,
+) -> eyre::Result<()>
+where
+    N: NodeTypesWithDB<ChainSpec: EthChainSpec + EthereumHardforks>,
+{
+    let lowest = report.corrupted.iter().map(|corrupt| corrupt.block).min().unwrap_or_default();
+    if lowest == 0 {
+        eyre::bail!("the genesis block is corrupted, the node has to be re-initialized")
+    }
+
+    info!(target: "reth::cli", to = lowest - 1, "Unwinding below the corrupted blocks");
+    let mut pipeline = unwind_pipeline(config, provider_factory, false)?;
+    pipeline.move_to_static_files()?;
+    pipeline.unwind(lowest - 1, None)?;
+
+    info!(target: "reth::cli",
+        corrupted = report.corrupted.len(),
+        "Unwound below the corrupted blocks, they will be fetched again on the next start"
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::B256;
+    use reth_provider::{
+        DatabaseProviderFactory, StageCheckpointReader, StageCheckpointWriter,
+        StaticFileProviderFactory,
+    };
+    use reth_stages::{
+        test_utils::{StorageKind, TestStageDB},
+        StageCheckpoint, StageId,
+    };
+    use reth_static_file::{CorruptBlock, StaticFileCorruption};
+    use reth_testing_utils::generators::{self, random_block_range, BlockRangeParams};
+
+    #[test]
+    fn unwind_below_corrupted_blocks() {
+        let db = TestStageDB::default();
+        let blocks = random_block_range(
+            &mut generators::rng(),
+            0..=3,
+            BlockRangeParams { parent: Some(B256::ZERO), tx_count: 0..3, ..Default::default() },
+        );
+        db.insert_blocks(blocks.iter(), StorageKind::Static).expect("insert blocks");
+        let provider_rw = db.factory.database_provider_rw().unwrap();
+        for stage in [StageId::Headers, StageId::Bodies] {
+            provider_rw.save_stage_checkpoint(stage, StageCheckpoint::new(3)).unwrap();
+        }
+        provider_rw.commit().unwrap();
+
+        let report = StaticFileVerifierReport {
+            verified: Vec::new(),
+            corrupted: vec![CorruptBlock {
+                segment: StaticFileSegment::Headers,
+                block: 2,
+                corruption: StaticFileCorruption::Missing,
+            }],
+        };
+        unwind_below_corruption(Config::default(), db.factory.clone(), &report).unwrap();
+
+        // The corrupted blocks are removed, so the pipeline downloads them again.
+        let static_file_provider = db.factory.static_file_provider();
+        assert_eq!(
+            static_file_provider.get_highest_static_file_block(StaticFileSegment::Headers),
+            Some(1)
+        );
+        let provider = db.factory.database_provider_ro().unwrap();
+        for stage in [StageId::Headers, StageId::Bodies] {
+            assert_eq!(
+                provider.get_stage_checkpoint(stage).unwrap().map(|c| c.block_number),
+                Some(1)
+            );
+        }
+    }
+
+    #[test]
+    fn refuse_to_unwind_genesis() {
+        let db = TestStageDB::default();
+        let report = StaticFileVerifierReport {
+            verified: Vec::new(),
+            corrupted: vec![CorruptBlock {
+                segment: StaticFileSegment::Headers,
+                block: 0,
+                corruption: StaticFileCorruption::Missing,
+            }],
+        };
+        assert!(unwind_below_corruption(Config::default(), db.factory.clone(), &report).is_err());
+    }
+}
@@ -1,11 +1,14 @@
 //! Configuration files.
 
+use alloy_primitives::Address;
 use eyre::eyre;
 use reth_network_types::{PeersConfig, SessionsConfig};
 use reth_prune_types::PruneModes;
 use reth_stages_types::ExecutionStageThresholds;
+use reth_static_file_types::{SegmentConfig, StaticFileSegment};
 use serde::{Deserialize, Deserializer, Serialize};
 use std::{
+    collections::BTreeMap,
     ffi::OsStr,
     fs,
     path::{Path, PathBuf},
@@ -28,6 +31,10 @@ pub struct Config {
     pub peers: PeersConfig,
     /// Configuration for peer sessions.
     pub sessions: SessionsConfig,
+    /// Configuration for static files.
+    pub static_files: StaticFilesConfig,
+    /// Configuration for the transaction pool.
+    pub txpool: TransactionPoolConfig,
 }
 
 impl Config {
@@ -413,6 +420,10 @@ impl PruneConfig {
             self.segments.account_history.or(other.segments.account_history);
         self.segments.storage_history =
             self.segments.storage_history.or(other.segments.storage_history);
+        self.segments.headers_history =
+            self.segments.headers_history.or(other.segments.headers_history);
+        self.segments.bodies_history =
+            self.segments.bodies_history.or(other.segments.bodies_history);
 
         if self.segments.receipts_log_filter.0.is_empty() &&
             !other.segments.receipts_log_filter.0.is_empty()
@@ -422,6 +433,89 @@ impl PruneConfig {
     }
 }
 
+/// Static files configuration.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default)]
+pub struct StaticFilesConfig {
+    /// Headers segment configuration.
+    pub headers: SegmentConfig,
+    /// Transactions segment configuration.
+    pub transactions: SegmentConfig,
+    /// Receipts segment configuration.
+    pub receipts: SegmentConfig,
+}
+
+impl Default for StaticFilesConfig {
+    fn default() -> Self {
+        Self {
+            headers: StaticFileSegment::Headers.config(),
+            transactions: StaticFileSegment::Transactions.config(),
+            receipts: StaticFileSegment::Receipts.config(),
+        }
+    }
+}
+
+impl StaticFilesConfig {
+    /// Returns the configuration of the given segment.
+    pub const fn segment(&self, segment: StaticFileSegment) -> SegmentConfig {
+        match segment {
+            StaticFileSegment::Headers => self.headers,
+            StaticFileSegment::Transactions => self.transactions,
+            StaticFileSegment::Receipts => self.receipts,
+        }
+    }
+
+    /// Returns the configuration of every segment.
+    pub fn segments(&self) -> impl Iterator<Item = (StaticFileSegment, SegmentConfig)> + '_ {
+        [StaticFileSegment::Headers, StaticFileSegment::Transactions, StaticFileSegment::Receipts]
+            .into_iter()
+            .map(|segment| (segment, self.segment(segment)))
+    }
+}
+
+/// Transaction pool configuration.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default)]
+pub struct TransactionPoolConfig {
+    /// Admission policies of the transaction pool.
+    pub policies: TransactionPolicyConfig,
+}
+
+/// Admission policies of the transaction pool.
+///
+/// Transactions rejected by a policy never enter the pool.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default)]
+pub struct TransactionPolicyConfig {
+    /// Senders whose transactions are rejected.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub deny_senders: Vec<Address>,
+    /// Recipients whose transactions are rejected.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub deny_recipients: Vec<Address>,
+    /// Maximum gas limit of transactions calling a contract, per contract address.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub contract_gas_caps: BTreeMap<Address, u64>,
+    /// Minimum priority fee of local transactions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_tip_local: Option<u64>,
+    /// Minimum priority fee of transactions received from peers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_tip_external: Option<u64>,
+    /// Minimum priority fee of private transactions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_tip_private: Option<u64>,
+}
+
+impl TransactionPolicyConfig {
+    /// Returns `true` if a minimum priority fee is configured for any origin.
+    pub const fn has_min_tip(&self) -> bool {
+        self.min_tip_local.is_some() ||
+            self.min_tip_external.is_some() ||
+            self.min_tip_private.is_some()
+    }
+}
+
 /// Helper type to support older versions of Duration deserialization.
 fn deserialize_duration<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
 where
@@ -447,6 +541,7 @@ mod tests {
     use alloy_primitives::Address;
     use reth_network_peers::TrustedPeer;
     use reth_prune_types::{PruneMode, PruneModes, ReceiptsLogPruneConfig};
+    use reth_static_file_types::{Compression, StaticFileSegment, DEFAULT_MAX_DICT_SIZE};
     use std::{collections::BTreeMap, path::Path, str::FromStr, time::Duration};
 
     fn with_tempdir(filename: &str, proc: fn(&std::path::Path)) {
@@ -934,6 +1029,8 @@ receipts = 'full'
                 receipts: Some(PruneMode::Distance(1000)),
                 account_history: None,
                 storage_history: Some(PruneMode::Before(5000)),
+                headers_history: None,
+                bodies_history: Some(PruneMode::Before(15_537_394)),
                 receipts_log_filter: ReceiptsLogPruneConfig(BTreeMap::from([(
                     Address::random(),
                     PruneMode::Full,
@@ -949,6 +1046,8 @@ receipts = 'full'
                 receipts: Some(PruneMode::Full),
                 account_history: Some(PruneMode::Distance(2000)),
                 storage_history: Some(PruneMode::Distance(3000)),
+                headers_history: Some(PruneMode::Distance(20_000)),
+                bodies_history: Some(PruneMode::Distance(20_000)),
                 receipts_log_filter: ReceiptsLogPruneConfig(BTreeMap::from([
                     (Address::random(), PruneMode::Distance(1000)),
                     (Address::random(), PruneMode::Before(2000)),
@@ -967,9 +1066,64 @@ receipts = 'full'
         assert_eq!(config1.segments.receipts, Some(PruneMode::Distance(1000)));
         assert_eq!(config1.segments.account_history, Some(PruneMode::Distance(2000)));
         assert_eq!(config1.segments.storage_history, Some(PruneMode::Before(5000)));
+        assert_eq!(config1.segments.headers_history, Some(PruneMode::Distance(20_000)));
+        assert_eq!(config1.segments.bodies_history, Some(PruneMode::Before(15_537_394)));
         assert_eq!(config1.segments.receipts_log_filter, original_filter);
     }
 
+    #[test]
+    fn test_static_files_config() {
+        let s = r#"
+[static_files.headers]
+compression = "zstd"
+
+[static_files.receipts]
+compression = "zstd-dict"
+max_dict_size = 65536
+"#;
+        let conf: Config = toml::from_str(s).unwrap();
+
+        let headers = conf.static_files.segment(StaticFileSegment::Headers);
+        assert_eq!(headers.compression, Compression::Zstd);
+        assert_eq!(headers.max_dict_size, DEFAULT_MAX_DICT_SIZE);
+
+        let receipts = conf.static_files.segment(StaticFileSegment::Receipts);
+        assert_eq!(receipts.compression, Compression::ZstdWithDictionary);
+        assert_eq!(receipts.max_dict_size, 65536);
+
+        // Unset segments keep their defaults.
+        assert_eq!(
+            conf.static_files.segment(StaticFileSegment::Transactions),
+            StaticFileSegment::Transactions.config()
+        );
+    }
+
+    #[test]
+    fn test_txpool_policies_config() {
+        let s = r#"
+[txpool.policies]
+deny_senders = ["0x0000000000000000000000000000000000000001"]
+min_tip_external = 1000000000
+
+[txpool.policies.contract_gas_caps]
+"0x0000000000000000000000000000000000000002" = 100000
+"#;
+        let conf: Config = toml::from_str(s).unwrap();
+        let policies = &conf.txpool.policies;
+
+        assert_eq!(policies.deny_senders, vec![Address::with_last_byte(1)]);
+        assert!(policies.deny_recipients.is_empty());
+        assert_eq!(
+            policies.contract_gas_caps,
+            BTreeMap::from([(Address::with_last_byte(2), 100000)])
+        );
+        assert_eq!(policies.min_tip_external, Some(1_000_000_000));
+        assert!(policies.has_min_tip());
+
+        let s = toml::to_string(&conf).unwrap();
+        assert_eq!(toml::from_str::<Config>(&s).unwrap(), conf);
+    }
+
     #[test]
     fn test_conf_trust_nodes_only() {
         let trusted_nodes_only = r"#
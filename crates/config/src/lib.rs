@@ -9,4 +9,7 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
 pub mod config;
-pub use config::{BodiesConfig, Config, PruneConfig};
+pub use config::{
+    BodiesConfig, Config, PruneConfig, StaticFilesConfig, TransactionPolicyConfig,
+    TransactionPoolConfig,
+};
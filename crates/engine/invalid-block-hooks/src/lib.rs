@@ -1,5 +1,7 @@
 //! Invalid block hook implementations.
 
+mod stateless;
 mod witness;
 
+pub use stateless::InvalidBlockStatelessHook;
 pub use witness::InvalidBlockWitnessHook;
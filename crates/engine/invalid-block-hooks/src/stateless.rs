@@ -0,0 +1,170 @@
+use std::{collections::HashMap, fs::File, io::Write, path::PathBuf, sync::Arc};
+
+use alloy_primitives::{B256, U256};
+use alloy_rpc_types_debug::ExecutionWitness;
+use reth_consensus::Consensus;
+use reth_engine_primitives::InvalidBlockHook;
+use reth_evm::execute::{BlockExecutorProvider, Executor};
+use reth_primitives::{Receipt, SealedBlockWithSenders, SealedHeader};
+use reth_provider::{BlockExecutionOutput, HeaderProvider, StateProviderFactory};
+use reth_revm::database::StateProviderDatabase;
+use reth_stateless::{StatelessInput, StatelessValidationError};
+use reth_tracing::tracing::{info, warn};
+use reth_trie::{updates::TrieUpdates, HashedPostState};
+
+use crate::witness::extend_with_cached_state;
+
+/// The maximum number of ancestor headers that can be accessed with the `BLOCKHASH` opcode.
+const MAX_ANCESTOR_HEADERS: usize = 256;
+
+/// Generates a witness for the given block and cross-checks the engine's verdict by validating the
+/// block statelessly against it.
+///
+/// The stateless input is saved to a file, so that it can be replayed with
+/// `reth debug validate-stateless`.
+#[derive(Debug)]
+pub struct InvalidBlockStatelessHook<P, E> {
+    /// The provider to read the historical state and the ancestor headers.
+    provider: P,
+    /// The executor provider to execute the block with.
+    executor_provider: E,
+    /// The consensus implementation to validate the block with.
+    consensus: Arc<dyn Consensus>,
+    /// The directory to write the stateless input to.
+    output_directory: PathBuf,
+}
+
+impl<P, E> InvalidBlockStatelessHook<P, E> {
+    /// Creates a new stateless validation hook.
+    pub fn new(
+        provider: P,
+        executor_provider: E,
+        consensus: Arc<dyn Consensus>,
+        output_directory: PathBuf,
+    ) -> Self {
+        Self { provider, executor_provider, consensus, output_directory }
+    }
+}
+
+impl<P, E> InvalidBlockStatelessHook<P, E>
+where
+    P: StateProviderFactory + HeaderProvider + Send + Sync + 'static,
+    E: BlockExecutorProvider,
+{
+    fn on_invalid_block(
+        &self,
+        parent_header: &SealedHeader,
+        block: &SealedBlockWithSenders,
+        trie_updates: Option<(&TrieUpdates, B256)>,
+    ) -> eyre::Result<()> {
+        let witness = self.execution_witness(parent_header, block)?;
+
+        // Collect the ancestors that can be accessed during execution, parent last.
+        let mut ancestor_headers = vec![parent_header.header().clone()];
+        while ancestor_headers.len() < MAX_ANCESTOR_HEADERS {
+            let oldest = ancestor_headers.last().expect("not empty");
+            if oldest.number == 0 {
+                break
+            }
+            let Some(header) = self.provider.header(&oldest.parent_hash)? else { break };
+            ancestor_headers.push(header);
+        }
+        ancestor_headers.reverse();
+
+        let input =
+            StatelessInput { block: block.block.clone().unseal(), witness, ancestor_headers };
+        let input_path = self.output_directory.join(format!(
+            "{}_{}.stateless_input.json",
+            block.number,
+            block.hash()
+        ));
+        // Saved as a list, which is the format of the file that the debug command replays.
+        File::create(&input_path)?.write_all(serde_json::to_string(&[&input])?.as_bytes())?;
+
+        match reth_stateless::validate_block(
+            &self.executor_provider,
+            self.consensus.as_ref(),
+            &input,
+        ) {
+            Ok(output) => warn!(
+                target: "engine::invalid_block_hooks::stateless",
+                state_root = ?output.state_root,
+                input_path = %input_path.display(),
+                "Block rejected by the engine is valid according to stateless validation"
+            ),
+            Err(StatelessValidationError::StateRootMismatch(mismatch)) => {
+                let engine_root = trie_updates.map(|(_, root)| root);
+                if engine_root.is_some_and(|root| root != mismatch.got) {
+                    warn!(
+                        target: "engine::invalid_block_hooks::stateless",
+                        stateless_root = ?mismatch.got,
+                        ?engine_root,
+                        header_state_root = ?mismatch.expected,
+                        input_path = %input_path.display(),
+                        "Stateless state root does not match the state root computed by the engine"
+                    );
+                } else {
+                    info!(
+                        target: "engine::invalid_block_hooks::stateless",
+                        %mismatch,
+                        "Stateless validation rejected the block with a state root mismatch"
+                    );
+                }
+            }
+            Err(err) => info!(
+                target: "engine::invalid_block_hooks::stateless",
+                %err,
+                input_path = %input_path.display(),
+                "Stateless validation rejected the block"
+            ),
+        }
+
+        Ok(())
+    }
+
+    /// Re-executes the block on top of its parent and generates the execution witness for the
+    /// accessed state.
+    fn execution_witness(
+        &self,
+        parent_header: &SealedHeader,
+        block: &SealedBlockWithSenders,
+    ) -> eyre::Result<ExecutionWitness> {
+        let state_provider = self.provider.state_by_block_hash(parent_header.hash())?;
+
+        let mut hashed_state = HashedPostState::default();
+        let mut codes = HashMap::default();
+        self.executor_provider
+            .executor(StateProviderDatabase::new(&state_provider))
+            .execute_with_state_closure((&block.clone().unseal(), U256::MAX).into(), |statedb| {
+                codes = statedb
+                    .cache
+                    .contracts
+                    .iter()
+                    .map(|(hash, code)| (*hash, code.original_bytes()))
+                    .collect();
+
+                extend_with_cached_state(&mut hashed_state, &statedb.cache);
+            })?;
+
+        let state = state_provider.witness(Default::default(), hashed_state)?;
+        Ok(ExecutionWitness { state: HashMap::from_iter(state), codes, keys: None })
+    }
+}
+
+impl<P, E> InvalidBlockHook for InvalidBlockStatelessHook<P, E>
+where
+    P: StateProviderFactory + HeaderProvider + Send + Sync + 'static,
+    E: BlockExecutorProvider,
+{
+    fn on_invalid_block(
+        &self,
+        parent_header: &SealedHeader,
+        block: &SealedBlockWithSenders,
+        _output: &BlockExecutionOutput<Receipt>,
+        trie_updates: Option<(&TrieUpdates, B256)>,
+    ) {
+        if let Err(err) = self.on_invalid_block(parent_header, block, trie_updates) {
+            warn!(target: "engine::invalid_block_hooks::stateless", %err, "Failed to invoke hook");
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use std::{collections::HashMap, fmt::Debug, fs::File, io::Write, path::PathBuf};
 
-use alloy_primitives::{keccak256, B256, U256};
+use alloy_primitives::{keccak256, Bytes, B256, U256};
 use alloy_rpc_types_debug::ExecutionWitness;
 use eyre::OptionExt;
 use pretty_assertions::Comparison;
@@ -8,10 +8,13 @@ use reth_chainspec::{EthChainSpec, EthereumHardforks};
 use reth_engine_primitives::InvalidBlockHook;
 use reth_evm::{system_calls::SystemCaller, ConfigureEvm};
 use reth_primitives::{Header, Receipt, SealedBlockWithSenders, SealedHeader};
-use reth_provider::{BlockExecutionOutput, ChainSpecProvider, StateProviderFactory};
+use reth_provider::{
+    providers::ConsistentDbView, BlockExecutionOutput, BlockReader, ChainSpecProvider,
+    DatabaseProviderFactory, StateProviderFactory,
+};
 use reth_revm::{
     database::StateProviderDatabase,
-    db::states::bundle_state::BundleRetention,
+    db::{states::bundle_state::BundleRetention, CacheState},
     primitives::{BlockEnv, CfgEnvWithHandlerCfg, EnvWithHandlerCfg},
     state_change::post_block_balance_increments,
     DatabaseCommit, StateBuilder,
@@ -19,8 +22,45 @@ use reth_revm::{
 use reth_rpc_api::DebugApiClient;
 use reth_tracing::tracing::warn;
 use reth_trie::{updates::TrieUpdates, HashedPostState, HashedStorage};
+use reth_trie_parallel::proof::state_witness;
 use serde::Serialize;
 
+/// Adds all accounts and storage slots in the cache to the hashed state, and returns the
+/// preimages of their hashed addresses and slots.
+///
+/// Note: We grab *all* accounts in the cache here, as the `BundleState` prunes referenced
+/// accounts + storage slots.
+pub(crate) fn extend_with_cached_state(
+    hashed_state: &mut HashedPostState,
+    cache: &CacheState,
+) -> HashMap<B256, Bytes> {
+    let mut state_preimages = HashMap::default();
+    for (address, account) in &cache.accounts {
+        let hashed_address = keccak256(address);
+        hashed_state
+            .accounts
+            .insert(hashed_address, account.account.as_ref().map(|a| a.info.clone().into()));
+
+        let storage = hashed_state
+            .storages
+            .entry(hashed_address)
+            .or_insert_with(|| HashedStorage::new(account.status.was_destroyed()));
+
+        if let Some(account) = &account.account {
+            state_preimages.insert(hashed_address, alloy_rlp::encode(address).into());
+
+            for (slot, value) in &account.storage {
+                let slot = B256::from(*slot);
+                let hashed_slot = keccak256(slot);
+                storage.storage.insert(hashed_slot, *value);
+
+                state_preimages.insert(hashed_slot, alloy_rlp::encode(slot).into());
+            }
+        }
+    }
+    state_preimages
+}
+
 /// Generates a witness for the given block and saves it to a file.
 #[derive(Debug)]
 pub struct InvalidBlockWitnessHook<P, EvmConfig> {
@@ -51,6 +91,8 @@ impl<P, EvmConfig> InvalidBlockWitnessHook<P, EvmConfig>
 where
     P: StateProviderFactory
         + ChainSpecProvider<ChainSpec: EthChainSpec + EthereumHardforks>
+        + DatabaseProviderFactory<Provider: BlockReader>
+        + Clone
         + Send
         + Sync
         + 'static,
@@ -65,6 +107,10 @@ where
     ) -> eyre::Result<()> {
         // TODO(alexey): unify with `DebugApi::debug_execution_witness`
 
+        // The witness is generated from a view of the database that is consistent with the state,
+        // so the view is created before the state is opened.
+        let consistent_view = ConsistentDbView::new_with_latest_tip(self.provider.clone()).ok();
+
         // Setup database.
         let mut db = StateBuilder::new()
             .with_database(StateProviderDatabase::new(
@@ -120,42 +166,14 @@ where
         // Take the bundle state
         let mut bundle_state = db.take_bundle();
 
-        // Initialize a map of preimages.
-        let mut state_preimages = HashMap::default();
-
         // Grab all account proofs for the data accessed during block execution.
-        //
-        // Note: We grab *all* accounts in the cache here, as the `BundleState` prunes
-        // referenced accounts + storage slots.
         let mut hashed_state = HashedPostState::from_bundle_state(&bundle_state.state);
-        for (address, account) in db.cache.accounts {
-            let hashed_address = keccak256(address);
-            hashed_state
-                .accounts
-                .insert(hashed_address, account.account.as_ref().map(|a| a.info.clone().into()));
-
-            let storage = hashed_state
-                .storages
-                .entry(hashed_address)
-                .or_insert_with(|| HashedStorage::new(account.status.was_destroyed()));
-
-            if let Some(account) = account.account {
-                state_preimages.insert(hashed_address, alloy_rlp::encode(address).into());
-
-                for (slot, value) in account.storage {
-                    let slot = B256::from(slot);
-                    let hashed_slot = keccak256(slot);
-                    storage.storage.insert(hashed_slot, value);
-
-                    state_preimages.insert(hashed_slot, alloy_rlp::encode(slot).into());
-                }
-            }
-        }
+        let state_preimages = extend_with_cached_state(&mut hashed_state, &db.cache);
 
         // Generate an execution witness for the aggregated state of accessed accounts.
         // Destruct the cache database to retrieve the state provider.
         let state_provider = db.database.into_inner();
-        let state = state_provider.witness(Default::default(), hashed_state.clone())?;
+        let state = state_witness(consistent_view, &state_provider, hashed_state.clone())?;
 
         // Write the witness to the output directory.
         let response = ExecutionWitness {
@@ -297,6 +315,8 @@ impl<P, EvmConfig> InvalidBlockHook for InvalidBlockWitnessHook<P, EvmConfig>
 where
     P: StateProviderFactory
         + ChainSpecProvider<ChainSpec: EthChainSpec + EthereumHardforks>
+        + DatabaseProviderFactory<Provider: BlockReader>
+        + Clone
         + Send
         + Sync
         + 'static,
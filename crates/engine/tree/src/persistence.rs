@@ -3,8 +3,10 @@ use alloy_eips::BlockNumHash;
 use reth_chain_state::ExecutedBlock;
 use reth_errors::ProviderError;
 use reth_provider::{
-    providers::ProviderNodeTypes, writer::UnifiedStorageWriter, BlockHashReader,
-    ChainStateBlockWriter, DatabaseProviderFactory, ProviderFactory, StaticFileProviderFactory,
+    providers::{write_persisted_tip, ProviderNodeTypes},
+    writer::UnifiedStorageWriter,
+    BlockHashReader, ChainStateBlockWriter, DatabaseProviderFactory, ProviderFactory,
+    StaticFileProviderFactory,
 };
 use reth_prune::{PrunerError, PrunerOutput, PrunerWithFactory};
 use reth_stages_api::{MetricEvent, MetricEventsSender};
@@ -14,7 +16,7 @@ use std::{
 };
 use thiserror::Error;
 use tokio::sync::oneshot;
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
 
 /// Writes parts of reth's in memory tree state to the database and static files.
 ///
@@ -69,6 +71,9 @@ impl<N: ProviderNodeTypes> PersistenceService<N> {
             match action {
                 PersistenceAction::RemoveBlocksAbove(new_tip_num, sender) => {
                     let result = self.on_remove_blocks_above(new_tip_num)?;
+                    if let Some(num_hash) = result {
+                        self.record_persisted_tip(num_hash);
+                    }
                     // send new sync metrics based on removed blocks
                     let _ =
                         self.sync_metrics_tx.send(MetricEvent::SyncHeight { height: new_tip_num });
@@ -78,6 +83,7 @@ impl<N: ProviderNodeTypes> PersistenceService<N> {
                 PersistenceAction::SaveBlocks(blocks, sender) => {
                     let result = self.on_save_blocks(blocks)?;
                     if let Some(ref num_hash) = result {
+                        self.record_persisted_tip(*num_hash);
                         // send new sync metrics based on saved blocks
                         let _ = self
                             .sync_metrics_tx
@@ -107,6 +113,14 @@ impl<N: ProviderNodeTypes> PersistenceService<N> {
         Ok(())
     }
 
+    /// Records the persisted tip for secondary readers of the storage.
+    fn record_persisted_tip(&self, tip: BlockNumHash) {
+        let static_file_provider = self.provider.static_file_provider();
+        if let Err(err) = write_persisted_tip(static_file_provider.directory(), tip) {
+            warn!(target: "engine::persistence", %err, ?tip, "Failed to record persisted tip");
+        }
+    }
+
     fn on_remove_blocks_above(
         &self,
         new_tip_num: u64,
@@ -0,0 +1,598 @@
+//! Execution cache shared across blocks, see [`ExecutionCache`].
+
+use alloy_primitives::{
+    map::{HashMap, HashSet},
+    Address, BlockNumber, Bytes, StorageKey, StorageValue, B256,
+};
+use parking_lot::{Mutex, MutexGuard};
+use reth_errors::ProviderResult;
+use reth_metrics::{
+    metrics::{Counter, Gauge},
+    Metrics,
+};
+use reth_primitives::{Account, Bytecode};
+use reth_provider::{
+    AccountReader, BlockHashReader, StateProofProvider, StateProvider, StateRootProvider,
+    StorageRootProvider,
+};
+use reth_revm::db::BundleState;
+use reth_trie::{
+    updates::TrieUpdates, AccountProof, HashedPostState, HashedStorage, MultiProof, StorageProof,
+    TrieInput,
+};
+use schnellru::{ByLength, LruMap, Unlimited};
+use std::{fmt, sync::Arc};
+use tracing::trace;
+
+/// Approximate size of a cached account entry, in bytes.
+const ACCOUNT_ENTRY_SIZE: u64 = 128;
+/// Approximate size of a cached storage slot entry, in bytes.
+const STORAGE_ENTRY_SIZE: u64 = 128;
+/// Approximate average size of a cached bytecode entry, in bytes.
+const BYTECODE_ENTRY_SIZE: u64 = 16 * 1024;
+
+/// Number of shards of each cache. Each shard has its own lock, so that concurrent readers, e.g.
+/// the prewarming workers, contend on the same lock only if they access the same shard.
+const SHARDS: usize = 16;
+
+/// Returns the index of the shard of the given address or bytecode hash.
+///
+/// The last byte is used, because vanity addresses share their leading bytes.
+fn shard_index(key: &[u8]) -> usize {
+    key[key.len() - 1] as usize % SHARDS
+}
+
+/// Cache of accounts, storage slots and bytecodes read or written during execution.
+pub(crate) struct ExecutionCaches {
+    /// Accounts, sharded by address.
+    accounts: Box<[Mutex<LruMap<Address, Option<Account>>>]>,
+    /// Storage slots, sharded by the address of their account.
+    storage: Box<[Mutex<StorageShard>]>,
+    /// Bytecodes, sharded by code hash.
+    code: Box<[Mutex<LruMap<B256, Option<Bytecode>>>]>,
+    metrics: ExecutionCacheMetrics,
+}
+
+impl ExecutionCaches {
+    /// Creates the caches, splitting the size in bytes between accounts, storage and bytecodes.
+    fn new(size: u64) -> Self {
+        let entries = |share: u64, entry_size: u64| {
+            (size / 100 * share / entry_size / SHARDS as u64).clamp(1, u32::MAX as u64) as u32
+        };
+        let accounts = entries(10, ACCOUNT_ENTRY_SIZE);
+        let storage = entries(70, STORAGE_ENTRY_SIZE);
+        let code = entries(20, BYTECODE_ENTRY_SIZE);
+        Self {
+            accounts: (0..SHARDS)
+                .map(|_| Mutex::new(LruMap::new(ByLength::new(accounts))))
+                .collect(),
+            storage: (0..SHARDS).map(|_| Mutex::new(StorageShard::new(storage))).collect(),
+            code: (0..SHARDS).map(|_| Mutex::new(LruMap::new(ByLength::new(code)))).collect(),
+            metrics: ExecutionCacheMetrics::default(),
+        }
+    }
+
+    /// Returns the shard of the account cache of the given address.
+    fn accounts(&self, address: &Address) -> MutexGuard<'_, LruMap<Address, Option<Account>>> {
+        self.accounts[shard_index(address.as_slice())].lock()
+    }
+
+    /// Returns the shard of the storage cache of the given address.
+    fn storage(&self, address: &Address) -> MutexGuard<'_, StorageShard> {
+        self.storage[shard_index(address.as_slice())].lock()
+    }
+
+    /// Returns the shard of the bytecode cache of the given code hash.
+    fn code(&self, code_hash: &B256) -> MutexGuard<'_, LruMap<B256, Option<Bytecode>>> {
+        self.code[shard_index(code_hash.as_slice())].lock()
+    }
+
+    /// Clears all caches.
+    fn clear(&self) {
+        self.accounts.iter().for_each(|shard| shard.lock().clear());
+        self.storage.iter().for_each(|shard| shard.lock().clear());
+        self.code.iter().for_each(|shard| shard.lock().clear());
+        self.update_size_metrics();
+    }
+
+    /// Applies the state changes of an executed block to the caches.
+    fn insert_state(&self, state: &BundleState) {
+        for (hash, bytecode) in &state.contracts {
+            self.code(hash).insert(*hash, Some(Bytecode(bytecode.clone())));
+        }
+
+        for (address, account) in &state.state {
+            self.accounts(address).insert(*address, account.info.clone().map(Into::into));
+
+            let mut storage = self.storage(address);
+            if account.status.was_destroyed() {
+                // the storage of the account was wiped, so none of the cached slots are valid
+                storage.remove_account(address);
+            }
+            for (slot, value) in &account.storage {
+                storage.insert(*address, (*slot).into(), Some(value.present_value));
+            }
+        }
+
+        self.update_size_metrics();
+    }
+
+    fn update_size_metrics(&self) {
+        let accounts = self.accounts.iter().map(|shard| shard.lock().len()).sum::<usize>();
+        let slots = self.storage.iter().map(|shard| shard.lock().len()).sum::<usize>();
+        let code = self.code.iter().map(|shard| shard.lock().len()).sum::<usize>();
+        self.metrics.account_cache_size.set(accounts as f64);
+        self.metrics.storage_cache_size.set(slots as f64);
+        self.metrics.code_cache_size.set(code as f64);
+    }
+}
+
+impl fmt::Debug for ExecutionCaches {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExecutionCaches").field("shards", &SHARDS).finish_non_exhaustive()
+    }
+}
+
+/// Shard of the storage cache.
+///
+/// The slots are grouped by account, so that the storage of a destroyed account is dropped without
+/// scanning the other slots. Once the shard holds more than the maximum number of slots, the
+/// storage of the least recently used accounts is evicted.
+struct StorageShard {
+    /// Cached slots by account, in the order the accounts were last used.
+    accounts: LruMap<Address, HashMap<StorageKey, Option<StorageValue>>, Unlimited>,
+    /// Number of cached slots.
+    slots: usize,
+    /// Maximum number of cached slots.
+    max_slots: usize,
+}
+
+impl StorageShard {
+    fn new(max_slots: u32) -> Self {
+        Self { accounts: LruMap::new(Unlimited), slots: 0, max_slots: max_slots as usize }
+    }
+
+    /// Returns the number of cached slots.
+    const fn len(&self) -> usize {
+        self.slots
+    }
+
+    /// Returns the cached value of the slot, if any.
+    fn get(&mut self, address: &Address, slot: &StorageKey) -> Option<Option<StorageValue>> {
+        self.accounts.get(address)?.get(slot).copied()
+    }
+
+    /// Caches the value of the slot, and evicts the storage of the least recently used accounts if
+    /// the shard is full.
+    fn insert(&mut self, address: Address, slot: StorageKey, value: Option<StorageValue>) {
+        let Some(storage) = self.accounts.get_or_insert(address, HashMap::default) else { return };
+        if storage.insert(slot, value).is_none() {
+            self.slots += 1;
+        }
+        while self.slots > self.max_slots {
+            let Some((_, storage)) = self.accounts.pop_oldest() else { break };
+            self.slots -= storage.len();
+        }
+    }
+
+    /// Removes all cached slots of the account.
+    fn remove_account(&mut self, address: &Address) {
+        if let Some(storage) = self.accounts.remove(address) {
+            self.slots -= storage.len();
+        }
+    }
+
+    fn clear(&mut self) {
+        self.accounts.clear();
+        self.slots = 0;
+    }
+}
+
+/// Cross-block execution cache.
+///
+/// The cache contains the state of a single block. Blocks executed on top of that block read
+/// through the cache, and once a block is valid, its state changes are applied to the cache so it
+/// moves on to the state of that block. Executing a block on top of any other block, e.g. after a
+/// reorg, clears the cache first, so it never serves values of another chain.
+#[derive(Debug)]
+pub(crate) struct ExecutionCache {
+    /// Hash of the block whose state the caches contain.
+    block_hash: Option<B256>,
+    /// The caches.
+    caches: Arc<ExecutionCaches>,
+}
+
+impl ExecutionCache {
+    /// Creates an empty cache with the given size in bytes.
+    pub(crate) fn new(size: u64) -> Self {
+        Self { block_hash: None, caches: Arc::new(ExecutionCaches::new(size)) }
+    }
+
+    /// Returns the caches for executing a block on top of the given parent.
+    ///
+    /// The caches are cleared if they contain the state of another block.
+    pub(crate) fn caches_for_parent(&mut self, parent_hash: B256) -> Arc<ExecutionCaches> {
+        if self.block_hash != Some(parent_hash) {
+            if self.block_hash.is_some() {
+                trace!(target: "engine::caching", cached = ?self.block_hash, %parent_hash, "Clearing execution cache");
+                self.caches.metrics.invalidations.increment(1);
+            }
+            self.caches.clear();
+            self.block_hash = Some(parent_hash);
+        }
+        self.caches.clone()
+    }
+
+    /// Applies the state changes of a valid block to the caches, if the block was executed on top
+    /// of the cached state.
+    pub(crate) fn on_valid_block(
+        &mut self,
+        parent_hash: B256,
+        block_hash: B256,
+        state: &BundleState,
+    ) {
+        if self.block_hash != Some(parent_hash) {
+            return
+        }
+        self.caches.insert_state(state);
+        self.block_hash = Some(block_hash);
+    }
+}
+
+/// A state provider that reads accounts, storage slots and bytecodes through the
+/// [`ExecutionCaches`], and populates them on cache misses.
+pub(crate) struct CachedStateProvider<S> {
+    /// The state provider the cache misses are read from.
+    state_provider: S,
+    /// The caches.
+    caches: Arc<ExecutionCaches>,
+}
+
+impl<S> CachedStateProvider<S> {
+    /// Creates a new provider reading through the caches.
+    pub(crate) const fn new(state_provider: S, caches: Arc<ExecutionCaches>) -> Self {
+        Self { state_provider, caches }
+    }
+}
+
+impl<S: AccountReader> AccountReader for CachedStateProvider<S> {
+    fn basic_account(&self, address: Address) -> ProviderResult<Option<Account>> {
+        if let Some(account) = self.caches.accounts(&address).get(&address) {
+            self.caches.metrics.account_cache_hits.increment(1);
+            return Ok(*account)
+        }
+        self.caches.metrics.account_cache_misses.increment(1);
+
+        let account = self.state_provider.basic_account(address)?;
+        self.caches.accounts(&address).insert(address, account);
+        Ok(account)
+    }
+}
+
+impl<S: StateProvider> StateProvider for CachedStateProvider<S> {
+    fn storage(
+        &self,
+        account: Address,
+        storage_key: StorageKey,
+    ) -> ProviderResult<Option<StorageValue>> {
+        if let Some(value) = self.caches.storage(&account).get(&account, &storage_key) {
+            self.caches.metrics.storage_cache_hits.increment(1);
+            return Ok(value)
+        }
+        self.caches.metrics.storage_cache_misses.increment(1);
+
+        let value = self.state_provider.storage(account, storage_key)?;
+        self.caches.storage(&account).insert(account, storage_key, value);
+        Ok(value)
+    }
+
+    fn bytecode_by_hash(&self, code_hash: B256) -> ProviderResult<Option<Bytecode>> {
+        if let Some(code) = self.caches.code(&code_hash).get(&code_hash) {
+            self.caches.metrics.code_cache_hits.increment(1);
+            return Ok(code.clone())
+        }
+        self.caches.metrics.code_cache_misses.increment(1);
+
+        let code = self.state_provider.bytecode_by_hash(code_hash)?;
+        self.caches.code(&code_hash).insert(code_hash, code.clone());
+        Ok(code)
+    }
+}
+
+impl<S: BlockHashReader> BlockHashReader for CachedStateProvider<S> {
+    fn block_hash(&self, number: BlockNumber) -> ProviderResult<Option<B256>> {
+        self.state_provider.block_hash(number)
+    }
+
+    fn canonical_hashes_range(
+        &self,
+        start: BlockNumber,
+        end: BlockNumber,
+    ) -> ProviderResult<Vec<B256>> {
+        self.state_provider.canonical_hashes_range(start, end)
+    }
+}
+
+impl<S: StateRootProvider> StateRootProvider for CachedStateProvider<S> {
+    fn state_root(&self, state: HashedPostState) -> ProviderResult<B256> {
+        self.state_provider.state_root(state)
+    }
+
+    fn state_root_from_nodes(&self, input: TrieInput) -> ProviderResult<B256> {
+        self.state_provider.state_root_from_nodes(input)
+    }
+
+    fn state_root_with_updates(
+        &self,
+        state: HashedPostState,
+    ) -> ProviderResult<(B256, TrieUpdates)> {
+        self.state_provider.state_root_with_updates(state)
+    }
+
+    fn state_root_from_nodes_with_updates(
+        &self,
+        input: TrieInput,
+    ) -> ProviderResult<(B256, TrieUpdates)> {
+        self.state_provider.state_root_from_nodes_with_updates(input)
+    }
+}
+
+impl<S: StorageRootProvider> StorageRootProvider for CachedStateProvider<S> {
+    fn storage_root(&self, address: Address, storage: HashedStorage) -> ProviderResult<B256> {
+        self.state_provider.storage_root(address, storage)
+    }
+
+    fn storage_proof(
+        &self,
+        address: Address,
+        slot: B256,
+        storage: HashedStorage,
+    ) -> ProviderResult<StorageProof> {
+        self.state_provider.storage_proof(address, slot, storage)
+    }
+}
+
+impl<S: StateProofProvider> StateProofProvider for CachedStateProvider<S> {
+    fn proof(
+        &self,
+        input: TrieInput,
+        address: Address,
+        slots: &[B256],
+    ) -> ProviderResult<AccountProof> {
+        self.state_provider.proof(input, address, slots)
+    }
+
+    fn multiproof(
+        &self,
+        input: TrieInput,
+        targets: HashMap<B256, HashSet<B256>>,
+    ) -> ProviderResult<MultiProof> {
+        self.state_provider.multiproof(input, targets)
+    }
+
+    fn witness(
+        &self,
+        input: TrieInput,
+        target: HashedPostState,
+    ) -> ProviderResult<HashMap<B256, Bytes>> {
+        self.state_provider.witness(input, target)
+    }
+
+    fn database_trie_input(&self) -> ProviderResult<Option<TrieInput>> {
+        self.state_provider.database_trie_input()
+    }
+}
+
+/// Metrics for the cross-block execution cache.
+#[derive(Metrics)]
+#[metrics(scope = "sync.caching")]
+pub(crate) struct ExecutionCacheMetrics {
+    /// Number of account cache hits
+    pub(crate) account_cache_hits: Counter,
+    /// Number of account cache misses
+    pub(crate) account_cache_misses: Counter,
+    /// Number of accounts in the cache
+    pub(crate) account_cache_size: Gauge,
+    /// Number of storage cache hits
+    pub(crate) storage_cache_hits: Counter,
+    /// Number of storage cache misses
+    pub(crate) storage_cache_misses: Counter,
+    /// Number of storage slots in the cache
+    pub(crate) storage_cache_size: Gauge,
+    /// Number of bytecode cache hits
+    pub(crate) code_cache_hits: Counter,
+    /// Number of bytecode cache misses
+    pub(crate) code_cache_misses: Counter,
+    /// Number of bytecodes in the cache
+    pub(crate) code_cache_size: Gauge,
+    /// Number of times the cache was cleared because a block wasn't executed on top of the cached
+    /// state
+    pub(crate) invalidations: Counter,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::U256;
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_revm::{
+        db::{AccountStatus, BundleAccount},
+        primitives::AccountInfo,
+    };
+
+    #[test]
+    fn reads_through_and_follows_blocks() {
+        let address = Address::random();
+        let slot = B256::with_last_byte(1);
+        let provider = MockEthProvider::default();
+        provider.add_account(
+            address,
+            ExtendedAccount::new(0, U256::from(1)).extend_storage([(slot, U256::from(2))]),
+        );
+
+        let parent_hash = B256::random();
+        let mut cache = ExecutionCache::new(1024 * 1024);
+        let state_provider =
+            CachedStateProvider::new(provider.clone(), cache.caches_for_parent(parent_hash));
+        assert_eq!(state_provider.basic_account(address).unwrap().unwrap().balance, U256::from(1));
+        assert_eq!(state_provider.storage(address, slot).unwrap(), Some(U256::from(2)));
+
+        // cached values are served without reading the underlying provider
+        provider.add_account(address, ExtendedAccount::new(0, U256::from(3)));
+        assert_eq!(state_provider.basic_account(address).unwrap().unwrap().balance, U256::from(1));
+        assert_eq!(state_provider.storage(address, slot).unwrap(), Some(U256::from(2)));
+
+        // the state changes of a valid block are applied to the cache
+        let block_hash = B256::random();
+        let state = BundleState::builder(1..=1)
+            .state_present_account_info(
+                address,
+                AccountInfo { balance: U256::from(4), ..Default::default() },
+            )
+            .state_storage(
+                address,
+                HashMap::from_iter([(slot.into(), (U256::from(2), U256::from(5)))]),
+            )
+            .build();
+        cache.on_valid_block(parent_hash, block_hash, &state);
+        let state_provider =
+            CachedStateProvider::new(provider.clone(), cache.caches_for_parent(block_hash));
+        assert_eq!(state_provider.basic_account(address).unwrap().unwrap().balance, U256::from(4));
+        assert_eq!(state_provider.storage(address, slot).unwrap(), Some(U256::from(5)));
+
+        // executing on top of another block clears the cache
+        let state_provider =
+            CachedStateProvider::new(provider, cache.caches_for_parent(B256::random()));
+        assert_eq!(state_provider.basic_account(address).unwrap().unwrap().balance, U256::from(3));
+        assert_eq!(state_provider.storage(address, slot).unwrap(), None);
+    }
+
+    #[test]
+    fn destroyed_account_storage_is_invalidated() {
+        let destroyed = Address::random();
+        let other = Address::random();
+        let slot = B256::with_last_byte(1);
+        let provider = MockEthProvider::default();
+        for address in [destroyed, other] {
+            provider.add_account(
+                address,
+                ExtendedAccount::new(0, U256::ZERO).extend_storage([(slot, U256::from(1))]),
+            );
+        }
+
+        let parent_hash = B256::random();
+        let mut cache = ExecutionCache::new(1024 * 1024);
+        let state_provider =
+            CachedStateProvider::new(provider.clone(), cache.caches_for_parent(parent_hash));
+        for address in [destroyed, other] {
+            assert_eq!(state_provider.storage(address, slot).unwrap(), Some(U256::from(1)));
+        }
+
+        // the account is destroyed in the next block, which wipes its storage
+        let mut state = BundleState::default();
+        state.state.insert(
+            destroyed,
+            BundleAccount::new(
+                Some(AccountInfo::default()),
+                None,
+                Default::default(),
+                AccountStatus::Destroyed,
+            ),
+        );
+        let block_hash = B256::random();
+        cache.on_valid_block(parent_hash, block_hash, &state);
+        for address in [destroyed, other] {
+            provider.add_account(address, ExtendedAccount::new(0, U256::ZERO));
+        }
+
+        let state_provider =
+            CachedStateProvider::new(provider, cache.caches_for_parent(block_hash));
+        assert_eq!(state_provider.basic_account(destroyed).unwrap(), None);
+        assert_eq!(state_provider.storage(destroyed, slot).unwrap(), None);
+        // the storage of other accounts is still served from the cache
+        assert_eq!(state_provider.storage(other, slot).unwrap(), Some(U256::from(1)));
+    }
+
+    #[test]
+    fn reorg_clears_cache() {
+        let address = Address::random();
+        let provider = MockEthProvider::default();
+        provider.add_account(address, ExtendedAccount::new(0, U256::from(1)));
+        let balance_change = |balance: u64| {
+            BundleState::builder(1..=1)
+                .state_present_account_info(
+                    address,
+                    AccountInfo { balance: U256::from(balance), ..Default::default() },
+                )
+                .build()
+        };
+
+        // block a is executed on top of the parent and becomes valid
+        let parent_hash = B256::random();
+        let mut cache = ExecutionCache::new(1024 * 1024);
+        let state_provider =
+            CachedStateProvider::new(provider.clone(), cache.caches_for_parent(parent_hash));
+        assert_eq!(state_provider.basic_account(address).unwrap().unwrap().balance, U256::from(1));
+        let block_a = B256::random();
+        cache.on_valid_block(parent_hash, block_a, &balance_change(2));
+
+        // block b reorgs block a, so the state of block a is not served for it
+        let state_provider =
+            CachedStateProvider::new(provider.clone(), cache.caches_for_parent(parent_hash));
+        assert_eq!(state_provider.basic_account(address).unwrap().unwrap().balance, U256::from(1));
+        let block_b = B256::random();
+        cache.on_valid_block(parent_hash, block_b, &balance_change(3));
+
+        // a block that wasn't executed on top of the cached state is not applied
+        cache.on_valid_block(block_a, B256::random(), &balance_change(4));
+
+        let state_provider = CachedStateProvider::new(provider, cache.caches_for_parent(block_b));
+        assert_eq!(state_provider.basic_account(address).unwrap().unwrap().balance, U256::from(3));
+    }
+
+    #[test]
+    fn evicts_least_recently_used_entries() {
+        let slot = |slot: u8| StorageKey::with_last_byte(slot);
+        let value = Some(U256::from(1));
+        let (first, second) = (Address::random(), Address::random());
+
+        let mut shard = StorageShard::new(3);
+        shard.insert(first, slot(1), value);
+        shard.insert(first, slot(2), value);
+        shard.insert(second, slot(1), value);
+        assert_eq!(shard.len(), 3);
+
+        // overwriting a slot doesn't grow the shard
+        shard.insert(second, slot(1), None);
+        assert_eq!(shard.len(), 3);
+
+        // reading the first account makes the second one the least recently used
+        assert_eq!(shard.get(&first, &slot(1)), Some(value));
+        shard.insert(first, slot(3), value);
+        assert_eq!(shard.len(), 3);
+        assert_eq!(shard.get(&second, &slot(1)), None);
+        assert_eq!(shard.get(&first, &slot(3)), Some(value));
+
+        shard.remove_account(&first);
+        assert_eq!(shard.len(), 0);
+
+        // a cache of size zero holds a single account per shard
+        let provider = MockEthProvider::default();
+        let (first, second) = (Address::with_last_byte(1), Address::repeat_byte(1));
+        for address in [first, second] {
+            provider.add_account(address, ExtendedAccount::new(0, U256::from(1)));
+        }
+        let mut cache = ExecutionCache::new(0);
+        let state_provider =
+            CachedStateProvider::new(provider.clone(), cache.caches_for_parent(B256::random()));
+        for address in [first, second] {
+            assert_eq!(
+                state_provider.basic_account(address).unwrap().unwrap().balance,
+                U256::from(1)
+            );
+        }
+        provider.add_account(first, ExtendedAccount::new(0, U256::from(2)));
+        provider.add_account(second, ExtendedAccount::new(0, U256::from(2)));
+        assert_eq!(state_provider.basic_account(first).unwrap().unwrap().balance, U256::from(2));
+    }
+}
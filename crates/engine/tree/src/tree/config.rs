@@ -1,5 +1,7 @@
 //! Engine tree configuration.
 
+use std::time::Duration;
+
 /// Triggers persistence when the number of canonical blocks in memory exceeds this threshold.
 pub const DEFAULT_PERSISTENCE_THRESHOLD: u64 = 2;
 
@@ -11,6 +13,67 @@ const DEFAULT_MAX_INVALID_HEADER_CACHE_LENGTH: u32 = 256;
 
 const DEFAULT_MAX_EXECUTE_BLOCK_BATCH_SIZE: usize = 4;
 
+/// Default maximum number of workers that prewarm the transactions of a block.
+pub const DEFAULT_PREWARM_MAX_CONCURRENCY: usize = 8;
+
+/// Default number of persisted canonical blocks below the last persisted block that reorgs are
+/// served for without backfill sync.
+pub const DEFAULT_MAX_PERSISTED_REORG_DEPTH: u64 = 64;
+
+/// Default maximum number of canonical blocks to keep in memory with the finality-aware
+/// persistence policies.
+pub const DEFAULT_MAX_MEMORY_BLOCKS: u64 = 256;
+
+/// Default write duration the adaptive persistence policy aims for.
+pub const DEFAULT_PERSISTENCE_TARGET_WRITE_DURATION: Duration = Duration::from_millis(500);
+
+/// Determines which canonical blocks are written to the database, and when.
+///
+/// Blocks that are not persisted are kept in memory, and reorgs to any of them are served without
+/// unwinding the database.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PersistencePolicy {
+    /// Persist once the number of canonical blocks in memory exceeds the persistence threshold,
+    /// keeping the memory block buffer target in memory.
+    #[default]
+    BlockCount,
+    /// Persist canonical blocks up to the safe block only. If the number of canonical blocks in
+    /// memory exceeds `max_memory_blocks`, the oldest ones are persisted regardless.
+    Safe {
+        /// Maximum number of canonical blocks to keep in memory.
+        max_memory_blocks: u64,
+    },
+    /// Persist canonical blocks up to the finalized block only. If the number of canonical blocks
+    /// in memory exceeds `max_memory_blocks`, the oldest ones are persisted regardless.
+    Finalized {
+        /// Maximum number of canonical blocks to keep in memory.
+        max_memory_blocks: u64,
+    },
+    /// Persist by block count, adapting the persistence threshold to the write latency.
+    ///
+    /// Writes slower than `target_write_duration` double the threshold, up to
+    /// `max_memory_blocks`, so that fewer and larger batches are written. Writes faster than half
+    /// of it halve the threshold, down to the configured persistence threshold.
+    Adaptive {
+        /// The write duration to aim for.
+        target_write_duration: Duration,
+        /// Maximum number of canonical blocks to keep in memory.
+        max_memory_blocks: u64,
+    },
+}
+
+impl PersistencePolicy {
+    /// Returns the maximum number of canonical blocks to keep in memory, if the policy has one.
+    pub const fn max_memory_blocks(&self) -> Option<u64> {
+        match self {
+            Self::BlockCount => None,
+            Self::Safe { max_memory_blocks } |
+            Self::Finalized { max_memory_blocks } |
+            Self::Adaptive { max_memory_blocks, .. } => Some(*max_memory_blocks),
+        }
+    }
+}
+
 /// The configuration of the engine tree.
 #[derive(Debug)]
 pub struct TreeConfig {
@@ -22,6 +85,14 @@ pub struct TreeConfig {
     ///
     /// Note: this should be less than or equal to `persistence_threshold`.
     memory_block_buffer_target: u64,
+    /// Determines which canonical blocks are persisted, and when.
+    persistence_policy: PersistencePolicy,
+    /// Number of persisted canonical blocks below the last persisted block that reorgs are served
+    /// for by reconstructing them from the database, instead of unwinding through backfill sync.
+    ///
+    /// Note: such reorgs still remove the reorged blocks from the database and persist the new
+    /// chain again.
+    max_persisted_reorg_depth: u64,
     /// Number of pending blocks that cannot be executed due to missing parent and
     /// are kept in cache.
     block_buffer_limit: u32,
@@ -32,6 +103,21 @@ pub struct TreeConfig {
     /// This is used as a cutoff to prevent long-running sequential block execution when we receive
     /// a batch of downloaded blocks.
     max_execute_block_batch_size: usize,
+    /// Whether to compute the state root with the state root task, concurrently with the
+    /// execution of the block.
+    ///
+    /// The root and trie updates of the task are used if the root matches the block, otherwise
+    /// the state root is computed again with the regular state root computation.
+    use_state_root_task: bool,
+    /// Size of the cache of accounts, storage slots and bytecodes that is shared across the
+    /// execution of blocks, in bytes. The cache uses up to this much memory in addition to the
+    /// in-memory blocks. Zero, the default, disables the cache.
+    cross_block_cache_size: u64,
+    /// Whether to speculatively execute the transactions of a block against the parent state
+    /// while the block is executed, to warm the state they touch.
+    use_prewarming: bool,
+    /// Maximum number of workers that prewarm the transactions of a block.
+    prewarm_max_concurrency: usize,
 }
 
 impl Default for TreeConfig {
@@ -39,9 +125,15 @@ impl Default for TreeConfig {
         Self {
             persistence_threshold: DEFAULT_PERSISTENCE_THRESHOLD,
             memory_block_buffer_target: DEFAULT_MEMORY_BLOCK_BUFFER_TARGET,
+            persistence_policy: PersistencePolicy::BlockCount,
+            max_persisted_reorg_depth: DEFAULT_MAX_PERSISTED_REORG_DEPTH,
             block_buffer_limit: DEFAULT_BLOCK_BUFFER_LIMIT,
             max_invalid_header_cache_length: DEFAULT_MAX_INVALID_HEADER_CACHE_LENGTH,
             max_execute_block_batch_size: DEFAULT_MAX_EXECUTE_BLOCK_BATCH_SIZE,
+            use_state_root_task: false,
+            cross_block_cache_size: 0,
+            use_prewarming: false,
+            prewarm_max_concurrency: DEFAULT_PREWARM_MAX_CONCURRENCY,
         }
     }
 }
@@ -51,16 +143,28 @@ impl TreeConfig {
     pub const fn new(
         persistence_threshold: u64,
         memory_block_buffer_target: u64,
+        persistence_policy: PersistencePolicy,
+        max_persisted_reorg_depth: u64,
         block_buffer_limit: u32,
         max_invalid_header_cache_length: u32,
         max_execute_block_batch_size: usize,
+        use_state_root_task: bool,
+        cross_block_cache_size: u64,
+        use_prewarming: bool,
+        prewarm_max_concurrency: usize,
     ) -> Self {
         Self {
             persistence_threshold,
             memory_block_buffer_target,
+            persistence_policy,
+            max_persisted_reorg_depth,
             block_buffer_limit,
             max_invalid_header_cache_length,
             max_execute_block_batch_size,
+            use_state_root_task,
+            cross_block_cache_size,
+            use_prewarming,
+            prewarm_max_concurrency,
         }
     }
 
@@ -74,6 +178,17 @@ impl TreeConfig {
         self.memory_block_buffer_target
     }
 
+    /// Return the persistence policy.
+    pub const fn persistence_policy(&self) -> PersistencePolicy {
+        self.persistence_policy
+    }
+
+    /// Return the maximum depth below the last persisted block of reorgs served without backfill
+    /// sync.
+    pub const fn max_persisted_reorg_depth(&self) -> u64 {
+        self.max_persisted_reorg_depth
+    }
+
     /// Return the block buffer limit.
     pub const fn block_buffer_limit(&self) -> u32 {
         self.block_buffer_limit
@@ -89,6 +204,26 @@ impl TreeConfig {
         self.max_execute_block_batch_size
     }
 
+    /// Returns whether to use the state root task.
+    pub const fn use_state_root_task(&self) -> bool {
+        self.use_state_root_task
+    }
+
+    /// Returns the size of the cross-block execution cache, in bytes.
+    pub const fn cross_block_cache_size(&self) -> u64 {
+        self.cross_block_cache_size
+    }
+
+    /// Returns whether to prewarm the transactions of a block.
+    pub const fn use_prewarming(&self) -> bool {
+        self.use_prewarming
+    }
+
+    /// Returns the maximum number of workers that prewarm the transactions of a block.
+    pub const fn prewarm_max_concurrency(&self) -> usize {
+        self.prewarm_max_concurrency
+    }
+
     /// Setter for persistence threshold.
     pub const fn with_persistence_threshold(mut self, persistence_threshold: u64) -> Self {
         self.persistence_threshold = persistence_threshold;
@@ -104,6 +239,19 @@ impl TreeConfig {
         self
     }
 
+    /// Setter for persistence policy.
+    pub const fn with_persistence_policy(mut self, persistence_policy: PersistencePolicy) -> Self {
+        self.persistence_policy = persistence_policy;
+        self
+    }
+
+    /// Setter for maximum depth below the last persisted block of reorgs served without backfill
+    /// sync.
+    pub const fn with_max_persisted_reorg_depth(mut self, max_persisted_reorg_depth: u64) -> Self {
+        self.max_persisted_reorg_depth = max_persisted_reorg_depth;
+        self
+    }
+
     /// Setter for block buffer limit.
     pub const fn with_block_buffer_limit(mut self, block_buffer_limit: u32) -> Self {
         self.block_buffer_limit = block_buffer_limit;
@@ -127,4 +275,28 @@ impl TreeConfig {
         self.max_execute_block_batch_size = max_execute_block_batch_size;
         self
     }
+
+    /// Setter for whether to use the state root task.
+    pub const fn with_state_root_task(mut self, use_state_root_task: bool) -> Self {
+        self.use_state_root_task = use_state_root_task;
+        self
+    }
+
+    /// Setter for the size of the cross-block execution cache, in bytes.
+    pub const fn with_cross_block_cache_size(mut self, cross_block_cache_size: u64) -> Self {
+        self.cross_block_cache_size = cross_block_cache_size;
+        self
+    }
+
+    /// Setter for whether to prewarm the transactions of a block.
+    pub const fn with_prewarming(mut self, use_prewarming: bool) -> Self {
+        self.use_prewarming = use_prewarming;
+        self
+    }
+
+    /// Setter for the maximum number of workers that prewarm the transactions of a block.
+    pub const fn with_prewarm_max_concurrency(mut self, prewarm_max_concurrency: usize) -> Self {
+        self.prewarm_max_concurrency = prewarm_max_concurrency;
+        self
+    }
 }
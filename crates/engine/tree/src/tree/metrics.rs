@@ -1,3 +1,4 @@
+use crate::tree::prewarm::PrewarmOutcome;
 use reth_blockchain_tree::metrics::TreeMetrics;
 use reth_evm::metrics::ExecutorMetrics;
 use reth_metrics::{
@@ -15,6 +16,8 @@ pub(crate) struct EngineApiMetrics {
     pub(crate) executor: ExecutorMetrics,
     /// Metrics for block validation
     pub(crate) block_validation: BlockValidationMetrics,
+    /// Metrics for transaction prewarming
+    pub(crate) prewarm: PrewarmMetrics,
     /// A copy of legacy blockchain tree metrics, to be replaced when we replace the old tree
     pub(crate) tree: TreeMetrics,
 }
@@ -56,6 +59,8 @@ pub(crate) struct BlockValidationMetrics {
     pub(crate) state_root_histogram: Histogram,
     /// Latest state root duration
     pub(crate) state_root_duration: Gauge,
+    /// Histogram of the time the state root task took to compute the root after execution
+    pub(crate) state_root_task_histogram: Histogram,
 }
 
 impl BlockValidationMetrics {
@@ -66,4 +71,46 @@ impl BlockValidationMetrics {
         self.state_root_duration.set(elapsed_as_secs);
         self.state_root_histogram.record(elapsed_as_secs);
     }
+
+    /// Records the time the state root task took to compute the root after execution
+    pub(crate) fn record_state_root_task(&self, elapsed_as_secs: f64) {
+        self.state_root_task_histogram.record(elapsed_as_secs);
+    }
+}
+
+/// Metrics for transaction prewarming.
+#[derive(Metrics)]
+#[metrics(scope = "sync.prewarm")]
+pub(crate) struct PrewarmMetrics {
+    /// Histogram of the number of transactions prewarmed per block
+    pub(crate) transactions_executed: Histogram,
+    /// Histogram of the share of the transactions of a block that were prewarmed
+    pub(crate) transactions_executed_ratio: Histogram,
+    /// Histogram of the time it took for prewarming to stop after the block was executed
+    pub(crate) cancel_duration: Histogram,
+    /// Histogram of the execution duration of blocks with prewarming
+    pub(crate) execution_with_prewarm_histogram: Histogram,
+    /// Histogram of the execution duration of blocks without prewarming
+    pub(crate) execution_without_prewarm_histogram: Histogram,
+}
+
+impl PrewarmMetrics {
+    /// Records the outcome of prewarming a block
+    pub(crate) fn record_outcome(&self, outcome: &PrewarmOutcome) {
+        self.transactions_executed.record(outcome.executed as f64);
+        if outcome.total > 0 {
+            self.transactions_executed_ratio
+                .record(outcome.executed.min(outcome.total) as f64 / outcome.total as f64);
+        }
+        self.cancel_duration.record(outcome.wait_duration.as_secs_f64());
+    }
+
+    /// Records the execution duration of a block, depending on whether it was prewarmed
+    pub(crate) fn record_execution(&self, prewarmed: bool, elapsed_as_secs: f64) {
+        if prewarmed {
+            self.execution_with_prewarm_histogram.record(elapsed_as_secs);
+        } else {
+            self.execution_without_prewarm_histogram.record(elapsed_as_secs);
+        }
+    }
 }
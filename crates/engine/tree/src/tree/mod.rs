@@ -28,7 +28,7 @@ use reth_chainspec::EthereumHardforks;
 use reth_consensus::{Consensus, PostExecutionInput};
 use reth_engine_primitives::EngineTypes;
 use reth_errors::{ConsensusError, ProviderResult};
-use reth_evm::execute::BlockExecutorProvider;
+use reth_evm::{execute::BlockExecutorProvider, ConfigureEvm};
 use reth_payload_builder::PayloadBuilderHandle;
 use reth_payload_primitives::{PayloadAttributes, PayloadBuilder, PayloadBuilderAttributes};
 use reth_payload_validator::ExecutionPayloadValidator;
@@ -62,13 +62,22 @@ use tokio::sync::{
 };
 use tracing::*;
 
+mod cached_state;
 pub mod config;
 mod invalid_block_hook;
 mod metrics;
 mod persistence_state;
+mod prewarm;
+mod root;
 use crate::{
     engine::{EngineApiKind, EngineApiRequest},
-    tree::metrics::EngineApiMetrics,
+    tree::{
+        cached_state::{CachedStateProvider, ExecutionCache, ExecutionCaches},
+        config::PersistencePolicy,
+        metrics::EngineApiMetrics,
+        prewarm::{spawn_prewarm, PrewarmHandle, PrewarmPool},
+        root::{StateRootConfig, StateRootHandle, StateRootTask, StateRootTaskThread},
+    },
 };
 pub use config::TreeConfig;
 pub use invalid_block_hook::{InvalidBlockHooks, NoopInvalidBlockHook};
@@ -99,6 +108,9 @@ pub struct TreeState {
     ///
     /// Contains the block number for easy removal.
     persisted_trie_updates: HashMap<B256, (BlockNumber, Arc<TrieUpdates>)>,
+    /// Hashes of persisted blocks that were reinserted after a reorg without their trie updates,
+    /// which are only computed once the blocks are needed again.
+    missing_trie_updates: HashSet<B256>,
     /// Currently tracked canonical head of the chain.
     current_canonical_head: BlockNumHash,
 }
@@ -112,6 +124,7 @@ impl TreeState {
             current_canonical_head,
             parent_to_child: HashMap::default(),
             persisted_trie_updates: HashMap::default(),
+            missing_trie_updates: HashSet::default(),
         }
     }
 
@@ -173,6 +186,19 @@ impl TreeState {
         }
     }
 
+    /// Sets the trie updates of a block that was reinserted without them.
+    fn set_trie_updates(&mut self, hash: B256, trie: Arc<TrieUpdates>) {
+        self.missing_trie_updates.remove(&hash);
+
+        let Some(executed) = self.blocks_by_hash.get_mut(&hash) else { return };
+        executed.trie = trie.clone();
+        if let Some(blocks) = self.blocks_by_number.get_mut(&executed.block.number) {
+            for block in blocks.iter_mut().filter(|block| block.block.hash() == hash) {
+                block.trie = trie.clone();
+            }
+        }
+    }
+
     /// Remove single executed block by its hash.
     ///
     /// ## Returns
@@ -180,6 +206,7 @@ impl TreeState {
     /// The removed block and the block hashes of its children.
     fn remove_by_hash(&mut self, hash: B256) -> Option<(ExecutedBlock, HashSet<B256>)> {
         let executed = self.blocks_by_hash.remove(&hash)?;
+        self.missing_trie_updates.remove(&hash);
 
         // Remove this block from collection of children of its parent block.
         let parent_entry = self.parent_to_child.entry(executed.block.parent_hash);
@@ -463,9 +490,11 @@ pub enum TreeAction {
 ///
 /// This type is responsible for processing engine API requests, maintaining the canonical state and
 /// emitting events.
-pub struct EngineApiTreeHandler<P, E, T: EngineTypes, Spec> {
+pub struct EngineApiTreeHandler<P, E, T: EngineTypes, Spec, C> {
     provider: P,
     executor_provider: E,
+    /// EVM configuration, used to prewarm the transactions of a block.
+    evm_config: C,
     consensus: Arc<dyn Consensus>,
     payload_validator: ExecutionPayloadValidator<Spec>,
     /// Keeps track of internals such as executed and buffered blocks.
@@ -503,15 +532,28 @@ pub struct EngineApiTreeHandler<P, E, T: EngineTypes, Spec> {
     invalid_block_hook: Box<dyn InvalidBlockHook>,
     /// The engine API variant of this handler
     engine_kind: EngineApiKind,
+    /// Cache of the state read and written during execution, shared across blocks.
+    ///
+    /// `None` if the cache is disabled.
+    execution_cache: Option<ExecutionCache>,
+    /// Thread the state root task of every block runs on.
+    ///
+    /// `None` if the state root task is disabled.
+    state_root_task_thread: Option<StateRootTaskThread>,
+    /// Thread pool the prewarming workers run on.
+    ///
+    /// `None` if prewarming is disabled.
+    prewarm_pool: Option<PrewarmPool>,
 }
 
-impl<P: Debug, E: Debug, T: EngineTypes + Debug, Spec: Debug> std::fmt::Debug
-    for EngineApiTreeHandler<P, E, T, Spec>
+impl<P: Debug, E: Debug, T: EngineTypes + Debug, Spec: Debug, C: Debug> std::fmt::Debug
+    for EngineApiTreeHandler<P, E, T, Spec, C>
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("EngineApiTreeHandler")
             .field("provider", &self.provider)
             .field("executor_provider", &self.executor_provider)
+            .field("evm_config", &self.evm_config)
             .field("consensus", &self.consensus)
             .field("payload_validator", &self.payload_validator)
             .field("state", &self.state)
@@ -525,23 +567,28 @@ impl<P: Debug, E: Debug, T: EngineTypes + Debug, Spec: Debug> std::fmt::Debug
             .field("metrics", &self.metrics)
             .field("invalid_block_hook", &format!("{:p}", self.invalid_block_hook))
             .field("engine_kind", &self.engine_kind)
+            .field("execution_cache", &self.execution_cache)
+            .field("state_root_task_thread", &self.state_root_task_thread)
+            .field("prewarm_pool", &self.prewarm_pool)
             .finish()
     }
 }
 
-impl<P, E, T, Spec> EngineApiTreeHandler<P, E, T, Spec>
+impl<P, E, T, Spec, C> EngineApiTreeHandler<P, E, T, Spec, C>
 where
     P: DatabaseProviderFactory + BlockReader + StateProviderFactory + StateReader + Clone + 'static,
     <P as DatabaseProviderFactory>::Provider: BlockReader,
     E: BlockExecutorProvider,
     T: EngineTypes,
     Spec: Send + Sync + EthereumHardforks + 'static,
+    C: ConfigureEvm<Header = Header>,
 {
     /// Creates a new [`EngineApiTreeHandler`].
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         provider: P,
         executor_provider: E,
+        evm_config: C,
         consensus: Arc<dyn Consensus>,
         payload_validator: ExecutionPayloadValidator<Spec>,
         outgoing: UnboundedSender<EngineApiEvent>,
@@ -554,10 +601,16 @@ where
         engine_kind: EngineApiKind,
     ) -> Self {
         let (incoming_tx, incoming) = std::sync::mpsc::channel();
+        let execution_cache = (config.cross_block_cache_size() > 0)
+            .then(|| ExecutionCache::new(config.cross_block_cache_size()));
+        let state_root_task_thread = config.use_state_root_task().then(StateRootTaskThread::spawn);
+        let prewarm_pool =
+            config.use_prewarming().then(|| PrewarmPool::new(config.prewarm_max_concurrency()));
 
         Self {
             provider,
             executor_provider,
+            evm_config,
             consensus,
             payload_validator,
             incoming,
@@ -573,6 +626,9 @@ where
             incoming_tx,
             invalid_block_hook: Box::new(NoopInvalidBlockHook),
             engine_kind,
+            execution_cache,
+            state_root_task_thread,
+            prewarm_pool,
         }
     }
 
@@ -590,6 +646,7 @@ where
     pub fn spawn_new(
         provider: P,
         executor_provider: E,
+        evm_config: C,
         consensus: Arc<dyn Consensus>,
         payload_validator: ExecutionPayloadValidator<Spec>,
         persistence: PersistenceHandle,
@@ -606,6 +663,8 @@ where
             last_persisted_block: BlockNumHash::new(best_block_number, header.hash()),
             rx: None,
             remove_above_state: VecDeque::new(),
+            adaptive_threshold: 0,
+            metrics: Default::default(),
         };
 
         let (tx, outgoing) = tokio::sync::mpsc::unbounded_channel();
@@ -618,6 +677,7 @@ where
         let mut task = Self::new(
             provider,
             executor_provider,
+            evm_config,
             consensus,
             payload_validator,
             tx,
@@ -1029,6 +1089,7 @@ where
         }
 
         // 2. ensure we can apply a new chain update for the head block
+        self.compute_missing_trie_updates(state.head_block_hash)?;
         if let Some(chain_update) = self.on_new_head(state.head_block_hash)? {
             let tip = chain_update.tip().header.clone();
             self.on_canonical_chain_update(chain_update);
@@ -1159,7 +1220,13 @@ where
             // Check if persistence has complete
             match rx.try_recv() {
                 Ok(last_persisted_hash_num) => {
-                    self.metrics.engine.persistence_duration.record(start_time.elapsed());
+                    let elapsed = start_time.elapsed();
+                    self.metrics.engine.persistence_duration.record(elapsed);
+                    self.persistence_state.on_write_duration(
+                        self.config.persistence_policy(),
+                        self.config.persistence_threshold(),
+                        elapsed,
+                    );
                     let Some(BlockNumHash {
                         hash: last_persisted_block_hash,
                         number: last_persisted_block_number,
@@ -1180,6 +1247,12 @@ where
                 Err(TryRecvError::Empty) => self.persistence_state.rx = Some((rx, start_time)),
             }
         }
+
+        self.persistence_state.update_metrics(
+            self.state.tree_state.canonical_block_number(),
+            self.state.tree_state.block_count(),
+            self.config.max_persisted_reorg_depth(),
+        );
         Ok(())
     }
 
@@ -1376,6 +1449,7 @@ where
     ///
     /// This will update the tracked canonical in memory state and do the necessary housekeeping.
     fn make_canonical(&mut self, target: B256) -> ProviderResult<()> {
+        self.compute_missing_trie_updates(target)?;
         if let Some(chain_update) = self.on_new_head(target)? {
             self.on_canonical_chain_update(chain_update);
         }
@@ -1439,23 +1513,71 @@ where
         );
     }
 
-    /// Returns true if the canonical chain length minus the last persisted
-    /// block is greater than or equal to the persistence threshold and
-    /// backfill is not running.
-    const fn should_persist(&self) -> bool {
+    /// Returns true if the persistence policy allows persisting canonical blocks and backfill is
+    /// not running.
+    ///
+    /// With the block count policies, this is the case if the canonical chain length minus the
+    /// last persisted block is greater than the persistence threshold. With the finality-aware
+    /// policies, this is the case if there are canonical blocks to persist up to the persistence
+    /// target.
+    fn should_persist(&self) -> bool {
         if !self.backfill_sync_state.is_idle() {
             // can't persist if backfill is running
             return false
         }
 
         let min_block = self.persistence_state.last_persisted_block.number;
-        self.state.tree_state.canonical_block_number().saturating_sub(min_block) >
-            self.config.persistence_threshold()
+        match self.config.persistence_policy() {
+            PersistencePolicy::BlockCount | PersistencePolicy::Adaptive { .. } => {
+                self.state.tree_state.canonical_block_number().saturating_sub(min_block) >
+                    self.persistence_threshold()
+            }
+            PersistencePolicy::Safe { .. } | PersistencePolicy::Finalized { .. } => {
+                self.persistence_target_number() > min_block
+            }
+        }
+    }
+
+    /// Returns the persistence threshold, adapted to the write latency with the
+    /// [`PersistencePolicy::Adaptive`] policy.
+    fn persistence_threshold(&self) -> u64 {
+        match self.config.persistence_policy() {
+            PersistencePolicy::Adaptive { .. } => {
+                self.persistence_state.adaptive_threshold.max(self.config.persistence_threshold())
+            }
+            _ => self.config.persistence_threshold(),
+        }
+    }
+
+    /// Returns the number of the highest canonical block the persistence policy allows to
+    /// persist.
+    ///
+    /// With the finality-aware policies, this is the safe or finalized block, unless more than
+    /// the maximum number of canonical blocks would be kept in memory.
+    fn persistence_target_number(&self) -> u64 {
+        let canonical_head_number = self.state.tree_state.canonical_block_number();
+        let policy = self.config.persistence_policy();
+        let finality_target = match policy {
+            PersistencePolicy::BlockCount | PersistencePolicy::Adaptive { .. } => {
+                return canonical_head_number
+                    .saturating_sub(self.config.memory_block_buffer_target())
+            }
+            PersistencePolicy::Safe { .. } => self.canonical_in_memory_state.get_safe_num_hash(),
+            PersistencePolicy::Finalized { .. } => {
+                self.canonical_in_memory_state.get_finalized_num_hash()
+            }
+        };
+
+        let finality_target = finality_target.map(|block| block.number).unwrap_or_default();
+        let memory_cap_target = policy
+            .max_memory_blocks()
+            .map(|max| canonical_head_number.saturating_sub(max))
+            .unwrap_or_default();
+        finality_target.min(canonical_head_number).max(memory_cap_target)
     }
 
     /// Returns a batch of consecutive canonical blocks to persist in the range
-    /// `(last_persisted_number .. canonical_head - threshold]` . The expected
-    /// order is oldest -> newest.
+    /// `(last_persisted_number .. persistence_target]` . The expected order is oldest -> newest.
     fn get_canonical_blocks_to_persist(&self) -> Vec<ExecutedBlock> {
         let mut blocks_to_persist = Vec::new();
         let mut current_hash = self.state.tree_state.canonical_block_hash();
@@ -1463,8 +1585,7 @@ where
 
         let canonical_head_number = self.state.tree_state.canonical_block_number();
 
-        let target_number =
-            canonical_head_number.saturating_sub(self.config.memory_block_buffer_target());
+        let target_number = self.persistence_target_number();
 
         debug!(target: "engine::tree", ?last_persisted_number, ?canonical_head_number, ?target_number, ?current_hash, "Returning canonical blocks to persist");
         while let Some(block) = self.state.tree_state.blocks_by_hash.get(&current_hash) {
@@ -1504,6 +1625,12 @@ where
 
     /// Return an [`ExecutedBlock`] from database or in-memory state by hash.
     ///
+    /// Persisted canonical blocks are reconstructed from the database, using the trie updates kept
+    /// in memory if they were persisted by the tree. Blocks that are at most
+    /// [`TreeConfig::max_persisted_reorg_depth`] blocks below the last persisted block are also
+    /// reconstructed without trie updates, which are computed by
+    /// [`Self::compute_missing_trie_updates`] only if the blocks are needed again after the reorg.
+    ///
     /// NOTE: This cannot fetch [`ExecutedBlock`]s for _finalized_ blocks, instead it can only
     /// fetch [`ExecutedBlock`]s for _canonical_ blocks, or blocks from sidechains that the node
     /// has in memory.
@@ -1518,8 +1645,15 @@ where
             return Ok(block)
         }
 
-        let Some((_, updates)) = self.state.tree_state.persisted_trie_updates.get(&hash) else {
-            return Ok(None)
+        let updates = match self.state.tree_state.persisted_trie_updates.get(&hash) {
+            Some((_, updates)) => Some(updates.clone()),
+            None => {
+                let Some(number) = self.provider.block_number(hash)? else { return Ok(None) };
+                if !self.is_within_persisted_reorg_depth(number) {
+                    return Ok(None)
+                }
+                None
+            }
         };
 
         let SealedBlockWithSenders { block, senders } = self
@@ -1535,12 +1669,61 @@ where
         Ok(Some(ExecutedBlock {
             block: Arc::new(block),
             senders: Arc::new(senders),
-            trie: updates.clone(),
+            trie: updates.unwrap_or_default(),
             execution_output: Arc::new(execution_output),
             hashed_state: Arc::new(hashed_state),
         }))
     }
 
+    /// Returns true if the persisted canonical block with the given number can be reorged without
+    /// unwinding the database through backfill sync, i.e. it is not finalized and at most
+    /// [`TreeConfig::max_persisted_reorg_depth`] blocks below the last persisted block.
+    fn is_within_persisted_reorg_depth(&self, number: BlockNumber) -> bool {
+        let last_persisted_number = self.persistence_state.last_persisted_block.number;
+        if number > last_persisted_number ||
+            last_persisted_number - number > self.config.max_persisted_reorg_depth()
+        {
+            return false
+        }
+
+        self.canonical_in_memory_state
+            .get_finalized_num_hash()
+            .map_or(true, |finalized| number > finalized.number)
+    }
+
+    /// Computes the trie updates of the blocks that lead to the given block and were reinserted
+    /// without them after a reorg below the persisted tip.
+    ///
+    /// This must be called before the blocks become canonical again or a block is inserted on top
+    /// of them, because the trie updates of in-memory blocks are used as cached trie nodes.
+    fn compute_missing_trie_updates(&mut self, hash: B256) -> ProviderResult<()> {
+        if self.state.tree_state.missing_trie_updates.is_empty() {
+            return Ok(())
+        }
+        let Some((_, blocks)) = self.state.tree_state.blocks_by_hash(hash) else { return Ok(()) };
+
+        // oldest first, so that the trie updates of the parent are set when computing the ones of
+        // its child
+        for block in blocks.iter().rev() {
+            let hash = block.block.hash();
+            if !self.state.tree_state.missing_trie_updates.contains(&hash) {
+                continue
+            }
+
+            // the state root is computed on top of the current database tip, so the trie updates
+            // contain the nodes of the block's trie that differ from the trie of the database tip
+            debug!(target: "engine::tree", number = block.block.number, ?hash, "Computing trie updates of reinserted block");
+            let parent_hash = block.block.parent_hash;
+            let (_, updates) = self
+                .state_provider(parent_hash)?
+                .ok_or(ProviderError::StateForHashNotFound(parent_hash))?
+                .state_root_with_updates(block.hashed_state.as_ref().clone())?;
+            self.state.tree_state.set_trie_updates(hash, Arc::new(updates));
+        }
+
+        Ok(())
+    }
+
     /// Return sealed block from database or in-memory state by hash.
     fn sealed_header_by_hash(&self, hash: B256) -> ProviderResult<Option<SealedHeader>> {
         // check memory first
@@ -1911,6 +2094,11 @@ where
     /// If either of these are true, then this returns the height of the first block. Otherwise,
     /// this returns [`None`]. This should be used to check whether or not we should be sending a
     /// remove command to the persistence task.
+    ///
+    /// NOTE: Reorgs below the last persisted block, including the ones within
+    /// [`TreeConfig::max_persisted_reorg_depth`], still remove all blocks above the new tip from
+    /// the database and persist the new chain again, instead of writing only the difference
+    /// between the two chains. The configured depth only avoids backfill sync.
     fn find_disk_reorg(&self, chain_update: &NewCanonicalChain) -> Option<u64> {
         let NewCanonicalChain::Reorg { new, old: _ } = chain_update else { return None };
 
@@ -1990,10 +2178,19 @@ where
     }
 
     /// This reinserts any blocks in the new chain that do not already exist in the tree
+    ///
+    /// Persisted blocks that were reconstructed without their trie updates are tracked, see
+    /// [`Self::compute_missing_trie_updates`].
     fn reinsert_reorged_blocks(&mut self, new_chain: Vec<ExecutedBlock>) {
         for block in new_chain {
-            if self.state.tree_state.executed_block_by_hash(block.block.hash()).is_none() {
-                trace!(target: "engine::tree", num=?block.block.number, hash=?block.block.hash(), "Reinserting block into tree state");
+            let hash = block.block.hash();
+            if self.state.tree_state.executed_block_by_hash(hash).is_none() {
+                trace!(target: "engine::tree", num=?block.block.number, ?hash, "Reinserting block into tree state");
+                if block.block.number <= self.persistence_state.last_persisted_block.number &&
+                    !self.state.tree_state.persisted_trie_updates.contains_key(&hash)
+                {
+                    self.state.tree_state.missing_trie_updates.insert(hash);
+                }
                 self.state.tree_state.insert_executed(block);
             }
         }
@@ -2139,6 +2336,7 @@ where
         self.validate_block(&block)?;
 
         trace!(target: "engine::tree", block=?block.num_hash(), parent=?block.parent_hash, "Fetching block state provider");
+        self.compute_missing_trie_updates(block.parent_hash)?;
         let Some(state_provider) = self.state_provider(block.parent_hash)? else {
             // we don't have the state required to execute this block, buffering it and find the
             // missing parent block
@@ -2168,6 +2366,28 @@ where
             return Err(e.into())
         }
 
+        // The state root task also runs while blocks are persisted: its proofs are fetched through
+        // a consistent view of the database, so the task fails and the state root is computed
+        // again below if the persisted tip changes in the meantime.
+        let state_root_handle = self
+            .spawn_state_root_task(block.parent_hash)
+            .inspect_err(
+                |error| debug!(target: "engine::tree", %error, "Failed to spawn state root task"),
+            )
+            .ok()
+            .flatten();
+
+        // Read the state through the cross-block cache, which is reused if the block is executed on
+        // top of the cached state.
+        let caches =
+            self.execution_cache.as_mut().map(|cache| cache.caches_for_parent(block.parent_hash));
+        let state_provider: StateProviderBox = match &caches {
+            Some(caches) => Box::new(CachedStateProvider::new(state_provider, caches.clone())),
+            None => state_provider,
+        };
+
+        let prewarm_handle = self.spawn_prewarm(&block, caches);
+
         trace!(target: "engine::tree", block=?block.num_hash(), "Executing block");
         let executor = self.executor_provider.executor(StateProviderDatabase::new(&state_provider));
 
@@ -2177,7 +2397,25 @@ where
         let block = block.unseal();
 
         let exec_time = Instant::now();
-        let output = self.metrics.executor.execute_metered(executor, (&block, U256::MAX).into())?;
+        let output = if let Some(handle) = &state_root_handle {
+            self.metrics.executor.execute_metered_with_state_hook(
+                executor,
+                (&block, U256::MAX).into(),
+                handle.state_hook(),
+            )?
+        } else {
+            self.metrics.executor.execute_metered(executor, (&block, U256::MAX).into())?
+        };
+        self.metrics
+            .prewarm
+            .record_execution(prewarm_handle.is_some(), exec_time.elapsed().as_secs_f64());
+
+        // Prewarming has to stop before the state changes of the block are applied to the cache.
+        if let Some(handle) = prewarm_handle {
+            let outcome = handle.cancel_and_wait();
+            trace!(target: "engine::tree", executed = outcome.executed, total = outcome.total, wait_duration = ?outcome.wait_duration, "Stopped prewarming");
+            self.metrics.prewarm.record_outcome(&outcome);
+        }
 
         trace!(target: "engine::tree", elapsed=?exec_time.elapsed(), ?block_number, "Executed block");
         if let Err(err) = self.consensus.validate_block_post_execution(
@@ -2198,7 +2436,29 @@ where
 
         trace!(target: "engine::tree", block=?BlockNumHash::new(block_number, block_hash), "Calculating block state root");
         let root_time = Instant::now();
-        let mut state_root_result = None;
+
+        // The root and trie updates of the state root task are used if the root matches the
+        // block, otherwise the state root is computed again below.
+        let mut state_root_result = match state_root_handle
+            .map(|handle| handle.wait_for_result(hashed_state.clone()))
+        {
+            Some(Ok(outcome)) if outcome.state_root == block.state_root => {
+                self.metrics
+                    .block_validation
+                    .record_state_root_task(outcome.time_from_last_update.as_secs_f64());
+                debug!(target: "engine::tree", ?block_number, time_from_last_update = ?outcome.time_from_last_update, "State root task computed state root");
+                Some((outcome.state_root, outcome.trie_updates))
+            }
+            Some(Ok(outcome)) => {
+                warn!(target: "engine::tree", ?block_number, task_state_root = ?outcome.state_root, expected = ?block.state_root, "State root task computed a different state root, falling back");
+                None
+            }
+            Some(Err(error)) => {
+                debug!(target: "engine::tree", ?block_number, %error, "State root task failed, falling back");
+                None
+            }
+            None => None,
+        };
 
         // We attempt to compute state root in parallel if we are currently not persisting anything
         // to database. This is safe, because the database state cannot change until we
@@ -2206,7 +2466,7 @@ where
         // we are computing in parallel, because we initialize a different database transaction
         // per thread and it might end up with a different view of the database.
         let persistence_in_progress = self.persistence_state.in_progress();
-        if !persistence_in_progress {
+        if state_root_result.is_none() && !persistence_in_progress {
             state_root_result = match self
                 .compute_state_root_parallel(block.parent_hash, &hashed_state)
             {
@@ -2244,6 +2504,10 @@ where
         self.metrics.block_validation.record_state_root(&trie_output, root_elapsed.as_secs_f64());
         debug!(target: "engine::tree", ?root_elapsed, ?block_number, "Calculated state root");
 
+        if let Some(cache) = &mut self.execution_cache {
+            cache.on_valid_block(block.parent_hash, block_hash, &output.state);
+        }
+
         let executed = ExecutedBlock {
             block: sealed_block.clone(),
             senders: Arc::new(block.senders),
@@ -2288,6 +2552,69 @@ where
         hashed_state: &HashedPostState,
     ) -> Result<(B256, TrieUpdates), ParallelStateRootError> {
         let consistent_view = ConsistentDbView::new_with_latest_tip(self.provider.clone())?;
+        let mut input = self.parent_trie_input(parent_hash, &consistent_view)?;
+
+        // Extend with block we are validating root for.
+        input.append_ref(hashed_state);
+
+        ParallelStateRoot::new(consistent_view, input).incremental_root_with_updates()
+    }
+
+    /// Spawns the prewarming of the transactions of the block, reading the parent state through the
+    /// execution caches if given.
+    ///
+    /// Returns `None` if prewarming is disabled.
+    fn spawn_prewarm(
+        &self,
+        block: &SealedBlockWithSenders,
+        caches: Option<Arc<ExecutionCaches>>,
+    ) -> Option<PrewarmHandle> {
+        let pool = self.prewarm_pool.as_ref()?;
+        let workers = self.config.prewarm_max_concurrency().min(block.body.transactions.len());
+
+        // the state providers of the parent are opened on the workers, only the in-memory blocks
+        // they are made of are collected here
+        let provider = self.provider.clone();
+        let (historical, blocks) = self
+            .state
+            .tree_state
+            .blocks_by_hash(block.parent_hash)
+            .unwrap_or((block.parent_hash, Vec::new()));
+        let state_provider = move || -> ProviderResult<StateProviderBox> {
+            let mut state_provider = provider.state_by_block_hash(historical)?;
+            if !blocks.is_empty() {
+                state_provider =
+                    Box::new(MemoryOverlayStateProvider::new(state_provider, blocks.clone()));
+            }
+            if let Some(caches) = &caches {
+                state_provider = Box::new(CachedStateProvider::new(state_provider, caches.clone()));
+            }
+            Ok(state_provider)
+        };
+
+        Some(spawn_prewarm(
+            pool,
+            self.evm_config.clone(),
+            Arc::new(block.clone().unseal()),
+            workers,
+            state_provider,
+        ))
+    }
+
+    /// Spawns the state root task for a block on top of the given parent, if the task is enabled.
+    fn spawn_state_root_task(&self, parent_hash: B256) -> ProviderResult<Option<StateRootHandle>> {
+        let Some(thread) = &self.state_root_task_thread else { return Ok(None) };
+        let consistent_view = ConsistentDbView::new_with_latest_tip(self.provider.clone())?;
+        let input = self.parent_trie_input(parent_hash, &consistent_view)?;
+        Ok(Some(StateRootTask::spawn(StateRootConfig::new(consistent_view, input), thread)))
+    }
+
+    /// Returns the trie input of the state of the given parent block on top of the database.
+    fn parent_trie_input(
+        &self,
+        parent_hash: B256,
+        consistent_view: &ConsistentDbView<P>,
+    ) -> ProviderResult<TrieInput> {
         let mut input = TrieInput::default();
 
         if let Some((historical, blocks)) = self.state.tree_state.blocks_by_hash(parent_hash) {
@@ -2305,10 +2632,7 @@ where
             input.append(revert_state);
         }
 
-        // Extend with block we are validating root for.
-        input.append_ref(hashed_state);
-
-        ParallelStateRoot::new(consistent_view, input).incremental_root_with_updates()
+        Ok(input)
     }
 
     /// Handles an error that occurred while inserting a block.
@@ -2577,12 +2901,14 @@ mod tests {
     use reth_chainspec::{ChainSpec, HOLESKY, MAINNET};
     use reth_ethereum_engine_primitives::EthEngineTypes;
     use reth_evm::test_utils::MockExecutorProvider;
+    use reth_evm_ethereum::EthEvmConfig;
     use reth_provider::test_utils::MockEthProvider;
     use reth_rpc_types_compat::engine::{block_to_payload_v1, payload::block_to_payload_v3};
     use reth_trie::updates::TrieUpdates;
     use std::{
         str::FromStr,
         sync::mpsc::{channel, Sender},
+        time::Duration,
     };
     use tokio::sync::mpsc::unbounded_channel;
 
@@ -2642,8 +2968,13 @@ mod tests {
     }
 
     struct TestHarness {
-        tree:
-            EngineApiTreeHandler<MockEthProvider, MockExecutorProvider, EthEngineTypes, ChainSpec>,
+        tree: EngineApiTreeHandler<
+            MockEthProvider,
+            MockExecutorProvider,
+            EthEngineTypes,
+            ChainSpec,
+            EthEvmConfig,
+        >,
         to_tree_tx: Sender<FromEngine<EngineApiRequest<EthEngineTypes>>>,
         from_tree_rx: UnboundedReceiver<EngineApiEvent>,
         blocks: Vec<ExecutedBlock>,
@@ -2693,6 +3024,7 @@ mod tests {
             let tree = EngineApiTreeHandler::new(
                 provider.clone(),
                 executor_provider.clone(),
+                EthEvmConfig::new(chain_spec.clone()),
                 consensus,
                 payload_validator,
                 from_tree_tx,
@@ -2744,6 +3076,7 @@ mod tests {
                 current_canonical_head: blocks.last().unwrap().block().num_hash(),
                 parent_to_child,
                 persisted_trie_updates: HashMap::default(),
+                missing_trie_updates: HashSet::default(),
             };
 
             let last_executed_block = blocks.last().unwrap().clone();
@@ -3557,6 +3890,152 @@ mod tests {
             .any(|b| b.block.number == 4 && b.block.hash() == blocks[4].block.hash()));
     }
 
+    #[tokio::test]
+    async fn test_get_canonical_blocks_to_persist_finalized_policy() {
+        let chain_spec = MAINNET.clone();
+        let mut test_harness = TestHarness::new(chain_spec);
+        let mut test_block_builder = TestBlockBuilder::default();
+
+        let blocks: Vec<_> = test_block_builder.get_executed_blocks(0..10).collect();
+        test_harness = test_harness.with_blocks(blocks.clone());
+        test_harness.tree.persistence_state.last_persisted_block.number = 3;
+        test_harness.tree.config = TreeConfig::default()
+            .with_persistence_policy(PersistencePolicy::Finalized { max_memory_blocks: 8 });
+
+        // nothing is finalized, and the memory cap is not exceeded
+        assert!(!test_harness.tree.should_persist());
+        assert!(test_harness.tree.get_canonical_blocks_to_persist().is_empty());
+
+        // only blocks up to the finalized block are persisted
+        test_harness.tree.canonical_in_memory_state.set_finalized(blocks[6].block.header.clone());
+        assert!(test_harness.tree.should_persist());
+        let blocks_to_persist = test_harness.tree.get_canonical_blocks_to_persist();
+        assert_eq!(
+            blocks_to_persist.iter().map(|b| b.block.number).collect::<Vec<_>>(),
+            vec![4, 5, 6]
+        );
+
+        // the memory cap takes precedence over the finalized block
+        test_harness.tree.config = TreeConfig::default()
+            .with_persistence_policy(PersistencePolicy::Finalized { max_memory_blocks: 2 });
+        let blocks_to_persist = test_harness.tree.get_canonical_blocks_to_persist();
+        assert_eq!(blocks_to_persist.last().unwrap().block.number, 7);
+    }
+
+    #[tokio::test]
+    async fn test_reorg_below_persisted_tip() {
+        let chain_spec = MAINNET.clone();
+        let mut test_harness = TestHarness::new(chain_spec);
+        let mut test_block_builder = TestBlockBuilder::default();
+
+        // blocks 0..=9 are canonical and persisted, none of them are kept in memory
+        let blocks: Vec<_> = test_block_builder.get_executed_blocks(0..10).collect();
+        let blocks: Vec<_> = blocks
+            .iter()
+            .map(|b| SealedBlockWithSenders {
+                block: (*b.block).clone(),
+                senders: b.senders.to_vec(),
+            })
+            .collect();
+        test_harness.persist_blocks(blocks.clone());
+        for block in &blocks {
+            let outcome = test_block_builder.get_execution_outcome(block.clone());
+            test_harness.provider.add_execution_outcome(block.number, outcome);
+        }
+        let tip = blocks.last().unwrap().num_hash();
+        test_harness.tree.state.tree_state.set_canonical_head(tip);
+        test_harness.tree.persistence_state.last_persisted_block = tip;
+        test_harness.tree.config = TreeConfig::default().with_max_persisted_reorg_depth(4);
+
+        let insert_fork = |test_harness: &mut TestHarness, fork: &[SealedBlockWithSenders]| {
+            for block in fork {
+                test_harness.tree.state.tree_state.insert_executed(ExecutedBlock {
+                    block: Arc::new(block.block.clone()),
+                    senders: Arc::new(block.senders.clone()),
+                    execution_output: Arc::new(ExecutionOutcome::default()),
+                    hashed_state: Arc::new(HashedPostState::default()),
+                    trie: Arc::new(TrieUpdates::default()),
+                });
+            }
+        };
+
+        // a fork from block 3 would reorg block 4, which is 5 blocks below the persisted tip
+        let deep_fork = test_block_builder.create_fork(&blocks[3].block, 6);
+        insert_fork(&mut test_harness, &deep_fork);
+        assert!(test_harness.tree.on_new_head(deep_fork.last().unwrap().hash()).unwrap().is_none());
+
+        // a fork from block 4 reorgs block 5, which is exactly the configured depth below it
+        let fork = test_block_builder.create_fork(&blocks[4].block, 5);
+        insert_fork(&mut test_harness, &fork);
+        let chain_update =
+            test_harness.tree.on_new_head(fork.last().unwrap().hash()).unwrap().unwrap();
+        let NewCanonicalChain::Reorg { new, old } = &chain_update else {
+            panic!("expected reorg, got {chain_update:?}")
+        };
+        assert_eq!(
+            new.iter().map(|b| b.block.hash()).collect::<Vec<_>>(),
+            fork.iter().map(|b| b.hash()).collect::<Vec<_>>()
+        );
+        // the reorged blocks are reconstructed from the database
+        assert_eq!(
+            old.iter().map(|b| b.block.hash()).collect::<Vec<_>>(),
+            blocks[5..].iter().map(|b| b.hash()).collect::<Vec<_>>()
+        );
+        for (executed, block) in old.iter().zip(&blocks[5..]) {
+            assert_eq!(executed.senders.as_ref(), &block.senders);
+            assert_eq!(
+                executed.execution_output.as_ref(),
+                test_harness.provider.execution_outcomes.lock().get(&block.number).unwrap()
+            );
+        }
+
+        // only the persisted blocks above the fork point are removed from the database
+        test_harness.tree.on_canonical_chain_update(chain_update);
+        assert_eq!(test_harness.tree.persistence_state.remove_above_state, VecDeque::from([4]));
+        assert_eq!(
+            test_harness.tree.state.tree_state.canonical_head(),
+            fork.last().unwrap().num_hash()
+        );
+
+        // the reorged blocks are kept in memory in case the chain reorgs back, their trie updates
+        // are not computed yet
+        let reorged = blocks[5..].iter().map(|b| b.hash()).collect::<HashSet<_>>();
+        for hash in &reorged {
+            assert!(test_harness.tree.state.tree_state.block_by_hash(*hash).is_some());
+        }
+        assert_eq!(test_harness.tree.state.tree_state.missing_trie_updates, reorged);
+
+        // the trie updates are computed once the reorged blocks become canonical again
+        test_harness.tree.make_canonical(tip.hash).unwrap();
+        assert_eq!(test_harness.tree.state.tree_state.canonical_head(), tip);
+        assert!(test_harness.tree.state.tree_state.missing_trie_updates.is_empty());
+    }
+
+    #[test]
+    fn test_adaptive_persistence_threshold() {
+        let policy = PersistencePolicy::Adaptive {
+            target_write_duration: Duration::from_millis(100),
+            max_memory_blocks: 16,
+        };
+        let mut state = PersistenceState::default();
+
+        // slow writes grow the threshold up to the memory cap
+        for expected in [4, 8, 16, 16] {
+            state.on_write_duration(policy, 2, Duration::from_millis(200));
+            assert_eq!(state.adaptive_threshold, expected);
+        }
+
+        // writes close to the target keep it
+        state.on_write_duration(policy, 2, Duration::from_millis(80));
+        assert_eq!(state.adaptive_threshold, 16);
+
+        // fast writes shrink it down to the configured threshold
+        for expected in [8, 4, 2, 2] {
+            state.on_write_duration(policy, 2, Duration::from_millis(10));
+            assert_eq!(state.adaptive_threshold, expected);
+        }
+    }
+
     #[tokio::test]
     async fn test_engine_tree_fcu_missing_head() {
         let chain_spec = MAINNET.clone();
@@ -1,6 +1,11 @@
+use crate::tree::config::PersistencePolicy;
 use alloy_eips::BlockNumHash;
 use alloy_primitives::B256;
-use std::{collections::VecDeque, time::Instant};
+use reth_metrics::{metrics::Gauge, Metrics};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
 use tokio::sync::oneshot;
 use tracing::{debug, trace};
 
@@ -17,6 +22,11 @@ pub struct PersistenceState {
     /// The block above which blocks should be removed from disk, because there has been an on disk
     /// reorg.
     pub(crate) remove_above_state: VecDeque<u64>,
+    /// The persistence threshold of the [`PersistencePolicy::Adaptive`] policy. Zero until the
+    /// first write finished.
+    pub(crate) adaptive_threshold: u64,
+    /// Metrics for the persisted and in-memory blocks.
+    pub(crate) metrics: PersistenceMetrics,
 }
 
 impl PersistenceState {
@@ -38,6 +48,50 @@ impl PersistenceState {
         self.remove_above_state.push_back(new_tip_num);
     }
 
+    /// Adapts the persistence threshold of the [`PersistencePolicy::Adaptive`] policy to the
+    /// duration of the last write. Does nothing for other policies.
+    pub(crate) fn on_write_duration(
+        &mut self,
+        policy: PersistencePolicy,
+        persistence_threshold: u64,
+        elapsed: Duration,
+    ) {
+        let PersistencePolicy::Adaptive { target_write_duration, max_memory_blocks } = policy
+        else {
+            return
+        };
+
+        let current = self.adaptive_threshold.max(persistence_threshold);
+        self.adaptive_threshold = if elapsed > target_write_duration {
+            current.saturating_mul(2).clamp(persistence_threshold, max_memory_blocks.max(1))
+        } else if elapsed < target_write_duration / 2 {
+            (current / 2).max(persistence_threshold)
+        } else {
+            current
+        };
+        debug!(target: "engine::tree", ?elapsed, ?target_write_duration, threshold = self.adaptive_threshold, "Adapted persistence threshold");
+        self.metrics.adaptive_threshold.set(self.adaptive_threshold as f64);
+    }
+
+    /// Updates the metrics for the in-memory blocks.
+    ///
+    /// Reorgs to any canonical block above the last persisted block, or at most
+    /// `max_persisted_reorg_depth` blocks below it, are served without unwinding the database
+    /// through backfill sync.
+    pub(crate) fn update_metrics(
+        &self,
+        canonical_head_number: u64,
+        in_memory_blocks: usize,
+        max_persisted_reorg_depth: u64,
+    ) {
+        let last_persisted_number = self.last_persisted_block.number;
+        let unpersisted = canonical_head_number.saturating_sub(last_persisted_number);
+        let persisted = max_persisted_reorg_depth.min(last_persisted_number);
+        self.metrics.in_memory_blocks.set(in_memory_blocks as f64);
+        self.metrics.reorg_depth_without_unwind.set((unpersisted + persisted) as f64);
+        self.metrics.last_persisted_block_number.set(self.last_persisted_block.number as f64);
+    }
+
     /// Sets state for a finished persistence task.
     pub(crate) fn finish(
         &mut self,
@@ -50,3 +104,20 @@ impl PersistenceState {
             BlockNumHash::new(last_persisted_block_number, last_persisted_block_hash);
     }
 }
+
+/// Metrics for the persisted and in-memory blocks of the engine tree.
+#[derive(Metrics)]
+#[metrics(scope = "consensus.engine.persistence")]
+pub(crate) struct PersistenceMetrics {
+    /// Number of executed blocks in memory, including sidechain blocks.
+    pub(crate) in_memory_blocks: Gauge,
+    /// Number of canonical blocks that are not persisted yet, plus the persisted blocks within the
+    /// configured persisted reorg depth, i.e. how many blocks deep a reorg of the canonical chain
+    /// can be served without unwinding the database through backfill sync. Finalized blocks are
+    /// not accounted for, so this is an upper bound.
+    pub(crate) reorg_depth_without_unwind: Gauge,
+    /// Number of the last persisted block.
+    pub(crate) last_persisted_block_number: Gauge,
+    /// The persistence threshold of the adaptive persistence policy.
+    pub(crate) adaptive_threshold: Gauge,
+}
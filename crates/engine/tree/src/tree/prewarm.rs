@@ -0,0 +1,321 @@
+//! Transaction prewarming, speculatively executing the transactions of a block to warm the state
+//! they touch while the block is executed.
+
+use alloy_primitives::{Address, B256, U256};
+use reth_errors::{DatabaseError, ProviderError, ProviderResult};
+use reth_evm::ConfigureEvm;
+use reth_primitives::{BlockWithSenders, Header};
+use reth_provider::StateProviderBox;
+use reth_revm::{
+    database::StateProviderDatabase,
+    primitives::{AccountInfo, BlockEnv, Bytecode, CfgEnvWithHandlerCfg, EnvWithHandlerCfg},
+    Database,
+};
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc, Arc,
+    },
+    time::{Duration, Instant},
+};
+use tracing::{debug, trace};
+
+/// Thread pool the prewarming workers run on.
+///
+/// Prewarming has its own pool, bounded by the maximum number of workers, so that the workers don't
+/// compete with the state root computation on the global rayon pool.
+#[derive(Debug)]
+pub(crate) struct PrewarmPool(rayon::ThreadPool);
+
+impl PrewarmPool {
+    /// Builds a pool with the given number of threads.
+    pub(crate) fn new(threads: usize) -> Self {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads.max(1))
+            .thread_name(|i| format!("reth-prewarm-{i}"))
+            .build()
+            .expect("failed to build prewarm thread pool");
+        Self(pool)
+    }
+}
+
+/// Outcome of the prewarming of a block.
+#[derive(Debug)]
+pub(crate) struct PrewarmOutcome {
+    /// Number of transactions that were executed before prewarming was cancelled.
+    pub(crate) executed: usize,
+    /// Number of transactions in the block.
+    pub(crate) total: usize,
+    /// Time it took for the in-flight executions to stop after cancellation.
+    pub(crate) wait_duration: Duration,
+}
+
+/// Handle to the prewarming of a block.
+///
+/// Dropping the handle cancels prewarming and waits for the in-flight executions to stop, so
+/// nothing is written to the execution cache after the handle is gone.
+#[derive(Debug)]
+pub(crate) struct PrewarmHandle {
+    /// Whether prewarming was cancelled.
+    cancelled: Arc<AtomicBool>,
+    /// Number of transactions that were executed.
+    executed: Arc<AtomicUsize>,
+    /// Number of transactions in the block.
+    total: usize,
+    /// Receiver that is closed once all workers finished.
+    done_rx: mpsc::Receiver<()>,
+}
+
+impl PrewarmHandle {
+    /// Cancels prewarming and waits for the in-flight executions to stop.
+    pub(crate) fn cancel_and_wait(self) -> PrewarmOutcome {
+        let start = Instant::now();
+        self.cancel();
+        PrewarmOutcome {
+            executed: self.executed.load(Ordering::Relaxed),
+            total: self.total,
+            wait_duration: start.elapsed(),
+        }
+    }
+
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+        // returns an error once all workers dropped their senders
+        let _ = self.done_rx.recv();
+    }
+}
+
+impl Drop for PrewarmHandle {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+/// Spawns workers on the pool that execute the transactions of the block, each transaction on its
+/// own against the parent state, with the results discarded.
+///
+/// Every worker opens its own state provider of the parent state, sets up one EVM with the
+/// environment of the block that reads through it, and takes the next transaction that wasn't
+/// executed yet until all transactions are executed or prewarming is cancelled. The state providers
+/// are opened on the workers, so that the caller doesn't wait for them.
+pub(crate) fn spawn_prewarm<Evm, F>(
+    pool: &PrewarmPool,
+    evm_config: Evm,
+    block: Arc<BlockWithSenders>,
+    workers: usize,
+    state_provider: F,
+) -> PrewarmHandle
+where
+    Evm: ConfigureEvm<Header = Header>,
+    F: Fn() -> ProviderResult<StateProviderBox> + Send + Sync + 'static,
+{
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let executed = Arc::new(AtomicUsize::new(0));
+    let next = Arc::new(AtomicUsize::new(0));
+    let state_provider = Arc::new(state_provider);
+    let (done_tx, done_rx) = mpsc::channel();
+
+    for _ in 0..workers {
+        let evm_config = evm_config.clone();
+        let block = block.clone();
+        let cancelled = cancelled.clone();
+        let executed = executed.clone();
+        let next = next.clone();
+        let state_provider = state_provider.clone();
+        let done_tx = done_tx.clone();
+        pool.0.spawn(move || {
+            if cancelled.load(Ordering::Relaxed) {
+                return
+            }
+            let state_provider = match state_provider() {
+                Ok(state_provider) => state_provider,
+                Err(error) => {
+                    debug!(target: "engine::prewarm", %error, "Failed to open state provider");
+                    return
+                }
+            };
+
+            let mut cfg = CfgEnvWithHandlerCfg::new(Default::default(), Default::default());
+            let mut block_env = BlockEnv::default();
+            evm_config.fill_cfg_and_block_env(&mut cfg, &mut block_env, &block.header, U256::MAX);
+            let env = EnvWithHandlerCfg::new_with_cfg_env(cfg, block_env, Default::default());
+            let db = PrewarmDatabase {
+                db: StateProviderDatabase::new(&state_provider),
+                cancelled: &cancelled,
+            };
+            let mut evm = evm_config.evm_with_env(db, env);
+
+            while !cancelled.load(Ordering::Relaxed) {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let (Some(transaction), Some(sender)) =
+                    (block.body.transactions.get(index), block.senders.get(index))
+                else {
+                    break
+                };
+
+                // only the state reads matter, the state changes aren't committed so every
+                // transaction is executed against the parent state
+                evm_config.fill_tx_env(evm.tx_mut(), transaction, *sender);
+                let result = evm.transact();
+                trace!(target: "engine::prewarm", index, ok = result.is_ok(), "Prewarmed transaction");
+                executed.fetch_add(1, Ordering::Relaxed);
+            }
+            drop(evm);
+            drop(done_tx);
+        });
+    }
+
+    PrewarmHandle { cancelled, executed, total: block.body.transactions.len(), done_rx }
+}
+
+/// Database that fails all reads once prewarming was cancelled, so that in-flight executions stop
+/// early.
+struct PrewarmDatabase<'a, DB> {
+    db: DB,
+    cancelled: &'a AtomicBool,
+}
+
+impl<DB> PrewarmDatabase<'_, DB> {
+    fn ensure_not_cancelled(&self) -> Result<(), ProviderError> {
+        if self.cancelled.load(Ordering::Relaxed) {
+            return Err(DatabaseError::Other("prewarming cancelled".to_string()).into())
+        }
+        Ok(())
+    }
+}
+
+impl<DB: Database<Error = ProviderError>> Database for PrewarmDatabase<'_, DB> {
+    type Error = ProviderError;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.ensure_not_cancelled()?;
+        self.db.basic(address)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.ensure_not_cancelled()?;
+        self.db.code_by_hash(code_hash)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.ensure_not_cancelled()?;
+        self.db.storage(address, index)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        self.ensure_not_cancelled()?;
+        self.db.block_hash(number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::cached_state::{CachedStateProvider, ExecutionCache};
+    use alloy_consensus::TxLegacy;
+    use reth_chainspec::{MAINNET, MIN_TRANSACTION_GAS};
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_primitives::{Block, BlockBody, Signature, Transaction, TransactionSigned};
+    use reth_provider::{
+        test_utils::{ExtendedAccount, MockEthProvider},
+        AccountReader,
+    };
+
+    /// Returns a block with a transfer from each of the given senders to a new recipient, and the
+    /// recipients.
+    fn transfers_block(senders: &[Address]) -> (Arc<BlockWithSenders>, Vec<Address>) {
+        let recipients = senders.iter().map(|_| Address::random()).collect::<Vec<_>>();
+        let transactions = recipients
+            .iter()
+            .map(|recipient| {
+                TransactionSigned::from_transaction_and_signature(
+                    Transaction::Legacy(TxLegacy {
+                        gas_limit: MIN_TRANSACTION_GAS,
+                        gas_price: 1,
+                        to: (*recipient).into(),
+                        value: U256::from(1),
+                        ..Default::default()
+                    }),
+                    Signature::test_signature(),
+                )
+            })
+            .collect();
+        let block = Block {
+            header: Header { gas_limit: 30_000_000, ..Default::default() },
+            body: BlockBody { transactions, ..Default::default() },
+        };
+        (Arc::new(BlockWithSenders::new(block, senders.to_vec()).unwrap()), recipients)
+    }
+
+    #[test]
+    fn prewarms_touched_state() {
+        let senders = (0..16).map(|_| Address::random()).collect::<Vec<_>>();
+        let provider = MockEthProvider::default();
+        provider.extend_accounts(
+            senders.iter().map(|sender| (*sender, ExtendedAccount::new(0, U256::MAX >> 1))),
+        );
+        let (block, recipients) = transfers_block(&senders);
+
+        let mut cache = ExecutionCache::new(1024 * 1024);
+        let caches = cache.caches_for_parent(B256::random());
+        let pool = PrewarmPool::new(4);
+        let handle = spawn_prewarm(&pool, EthEvmConfig::new(MAINNET.clone()), block, 4, {
+            let provider = provider.clone();
+            let caches = caches.clone();
+            move || Ok(Box::new(CachedStateProvider::new(provider.clone(), caches.clone())) as _)
+        });
+        while handle.executed.load(Ordering::Relaxed) < handle.total {
+            std::thread::yield_now();
+        }
+        let outcome = handle.cancel_and_wait();
+        assert_eq!(outcome.executed, senders.len());
+        assert_eq!(outcome.total, senders.len());
+
+        // the accounts the transactions touched are served from the cache, even once they changed
+        for recipient in &recipients {
+            provider.add_account(*recipient, ExtendedAccount::new(0, U256::from(1)));
+        }
+        let state_provider = CachedStateProvider::new(provider, caches);
+        for recipient in recipients {
+            assert_eq!(state_provider.basic_account(recipient).unwrap(), None);
+        }
+    }
+
+    #[test]
+    fn cancellation_stops_workers() {
+        let senders = (0..16).map(|_| Address::random()).collect::<Vec<_>>();
+        let (block, _) = transfers_block(&senders);
+        let opened = Arc::new(AtomicUsize::new(0));
+
+        // the only thread of the pool is busy until prewarming is cancelled, so no worker starts
+        // before
+        let pool = PrewarmPool::new(1);
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        pool.0.spawn(move || {
+            let _ = release_rx.recv();
+        });
+        let handle = spawn_prewarm(&pool, EthEvmConfig::new(MAINNET.clone()), block, 4, {
+            let opened = opened.clone();
+            move || {
+                opened.fetch_add(1, Ordering::Relaxed);
+                Ok(Box::new(MockEthProvider::default()) as _)
+            }
+        });
+        handle.cancelled.store(true, Ordering::Relaxed);
+        drop(release_tx);
+
+        let outcome = handle.cancel_and_wait();
+        assert_eq!(outcome.executed, 0);
+        assert_eq!(opened.load(Ordering::Relaxed), 0);
+
+        // in-flight executions fail their next read
+        let cancelled = AtomicBool::new(true);
+        let mut db = PrewarmDatabase {
+            db: StateProviderDatabase::new(MockEthProvider::default()),
+            cancelled: &cancelled,
+        };
+        assert!(db.basic(Address::random()).is_err());
+        cancelled.store(false, Ordering::Relaxed);
+        assert_eq!(db.basic(Address::random()).unwrap(), None);
+    }
+}
@@ -0,0 +1,555 @@
+//! State root task, computing the state root with a sparse trie while the block is executed.
+
+use alloy_primitives::{
+    keccak256,
+    map::{HashMap, HashSet},
+    Address, B256, U256,
+};
+use reth_evm::system_calls::OnStateHook;
+use reth_provider::{
+    providers::ConsistentDbView, BlockReader, DBProvider, DatabaseProviderFactory, ProviderError,
+};
+use reth_revm::primitives::{EvmState, ResultAndState};
+use reth_trie::{
+    hashed_cursor::HashedPostStateCursorFactory,
+    prefix_set::TriePrefixSetsMut,
+    proof::Proof,
+    sparse::{SparseStateTrie, SparseStateTrieError, SparseTrieError},
+    trie_cursor::InMemoryTrieCursorFactory,
+    updates::{TrieUpdates, TrieUpdatesSorted},
+    HashedPostState, HashedPostStateSorted, MultiProof, Nibbles, TrieInput,
+};
+use reth_trie_db::{DatabaseHashedCursorFactory, DatabaseTrieCursorFactory};
+use std::{
+    sync::{mpsc, Arc},
+    time::{Duration, Instant},
+};
+use tracing::{debug, trace};
+
+/// Targets of a multiproof, the hashed addresses of the accounts and their hashed storage slots.
+type ProofTargets = HashMap<B256, HashSet<B256>>;
+
+/// Accounts touched by a transaction, with the keys of their changed storage slots.
+type TouchedState = Vec<(Address, Vec<U256>)>;
+
+/// Outcome of the state root task.
+#[derive(Debug)]
+pub(crate) struct StateRootComputeOutcome {
+    /// The computed state root.
+    pub(crate) state_root: B256,
+    /// The trie updates of the block, relative to the state of the parent block.
+    pub(crate) trie_updates: TrieUpdates,
+    /// Time it took to compute the root once execution finished.
+    pub(crate) time_from_last_update: Duration,
+}
+
+/// Errors of the state root task.
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum StateRootTaskError {
+    /// Failed to fetch a multiproof.
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+    /// Failed to update the sparse trie.
+    #[error(transparent)]
+    SparseTrie(#[from] SparseStateTrieError),
+    /// The blinded node couldn't be revealed.
+    #[error("failed to reveal blinded node: {0}")]
+    UnrevealedNode(SparseStateTrieError),
+    /// The block wasn't executed to completion.
+    #[error("state updates stopped before execution finished")]
+    Cancelled,
+}
+
+/// Configuration of the state root task.
+#[derive(Debug)]
+pub(crate) struct StateRootConfig<Factory> {
+    /// Consistent view of the database the proofs are fetched from.
+    consistent_view: ConsistentDbView<Factory>,
+    /// In-memory trie nodes of the blocks between the database and the parent block.
+    nodes_sorted: Arc<TrieUpdatesSorted>,
+    /// In-memory state of the blocks between the database and the parent block.
+    state_sorted: Arc<HashedPostStateSorted>,
+    /// Prefix sets of the in-memory state.
+    prefix_sets: Arc<TriePrefixSetsMut>,
+}
+
+impl<Factory> Clone for StateRootConfig<Factory>
+where
+    Factory: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            consistent_view: self.consistent_view.clone(),
+            nodes_sorted: self.nodes_sorted.clone(),
+            state_sorted: self.state_sorted.clone(),
+            prefix_sets: self.prefix_sets.clone(),
+        }
+    }
+}
+
+impl<Factory> StateRootConfig<Factory>
+where
+    Factory: DatabaseProviderFactory<Provider: BlockReader> + Clone + Send + Sync + 'static,
+{
+    /// Creates the configuration from the trie input of the parent block.
+    pub(crate) fn new(consistent_view: ConsistentDbView<Factory>, input: TrieInput) -> Self {
+        Self {
+            consistent_view,
+            nodes_sorted: Arc::new(input.nodes.into_sorted()),
+            state_sorted: Arc::new(input.state.into_sorted()),
+            prefix_sets: Arc::new(input.prefix_sets),
+        }
+    }
+
+    /// Generates the multiproof for the targets against the state of the parent block.
+    fn multiproof(&self, targets: ProofTargets) -> Result<MultiProof, ProviderError> {
+        let provider_ro = self.consistent_view.provider_ro()?;
+        let trie_cursor_factory = InMemoryTrieCursorFactory::new(
+            DatabaseTrieCursorFactory::new(provider_ro.tx_ref()),
+            &self.nodes_sorted,
+        );
+        let hashed_cursor_factory = HashedPostStateCursorFactory::new(
+            DatabaseHashedCursorFactory::new(provider_ro.tx_ref()),
+            &self.state_sorted,
+        );
+        Ok(Proof::new(trie_cursor_factory, hashed_cursor_factory)
+            .with_prefix_sets_mut(self.prefix_sets.as_ref().clone())
+            .with_branch_node_masks(true)
+            .multiproof(targets)?)
+    }
+}
+
+/// Messages of the state root task.
+#[derive(Debug)]
+pub(crate) enum StateRootMessage {
+    /// Accounts and storage slots changed by a transaction or system call.
+    StateUpdate(TouchedState),
+    /// A multiproof that was requested by the task was generated.
+    ProofCalculated { targets: ProofTargets, result: Result<MultiProof, ProviderError> },
+    /// The block was executed, with the state changes of the whole block.
+    FinishedStateUpdates(HashedPostState),
+    /// The block won't be executed to completion.
+    Cancelled,
+}
+
+/// Handle to the spawned state root task.
+#[derive(Debug)]
+pub(crate) struct StateRootHandle {
+    /// Sender of messages to the task.
+    tx: mpsc::Sender<StateRootMessage>,
+    /// Receiver of the result of the task.
+    result_rx: mpsc::Receiver<Result<StateRootComputeOutcome, StateRootTaskError>>,
+}
+
+impl StateRootHandle {
+    /// Returns the state hook that sends the accounts and storage slots changed by each
+    /// transaction to the task.
+    ///
+    /// Only the keys are sent, they are hashed on the task.
+    pub(crate) fn state_hook(&self) -> impl OnStateHook {
+        let tx = self.tx.clone();
+        move |result_and_state: &ResultAndState| {
+            let _ = tx.send(StateRootMessage::StateUpdate(touched_state(&result_and_state.state)));
+        }
+    }
+
+    /// Sends the state changes of the whole block to the task and waits for the state root.
+    pub(crate) fn wait_for_result(
+        self,
+        state: HashedPostState,
+    ) -> Result<StateRootComputeOutcome, StateRootTaskError> {
+        let _ = self.tx.send(StateRootMessage::FinishedStateUpdates(state));
+        self.result_rx.recv().unwrap_or(Err(StateRootTaskError::Cancelled))
+    }
+}
+
+impl Drop for StateRootHandle {
+    fn drop(&mut self) {
+        // stops the task if the block wasn't executed to completion, no-op otherwise
+        let _ = self.tx.send(StateRootMessage::Cancelled);
+    }
+}
+
+/// Long-lived thread that runs the state root tasks.
+///
+/// Blocks are executed one after another, so a single thread that is reused for the task of every
+/// block is enough. The thread exits once this is dropped.
+#[derive(Debug)]
+pub(crate) struct StateRootTaskThread {
+    /// Sender of the tasks to run.
+    tasks: mpsc::Sender<Box<dyn FnOnce() + Send>>,
+}
+
+impl StateRootTaskThread {
+    /// Spawns the thread.
+    pub(crate) fn spawn() -> Self {
+        let (tasks, rx) = mpsc::channel::<Box<dyn FnOnce() + Send>>();
+        std::thread::Builder::new()
+            .name("State Root Task".to_string())
+            .spawn(move || {
+                for task in rx {
+                    task();
+                }
+            })
+            .expect("failed to spawn state root task thread");
+        Self { tasks }
+    }
+}
+
+/// Task that computes the state root of a block with a sparse trie, concurrently with the
+/// execution of the block.
+///
+/// For the state changes of every executed transaction, multiproofs of the touched accounts and
+/// storage slots are fetched in the background and revealed in the sparse trie. Once the block is
+/// executed, the state changes of the whole block are applied to the sparse trie, and the root and
+/// the trie updates are computed from the revealed nodes only.
+#[derive(Debug)]
+pub(crate) struct StateRootTask<Factory> {
+    /// Configuration of the task.
+    config: StateRootConfig<Factory>,
+    /// Receiver of messages.
+    rx: mpsc::Receiver<StateRootMessage>,
+    /// Sender of messages, used to report generated proofs.
+    tx: mpsc::Sender<StateRootMessage>,
+    /// Proof targets that were already requested.
+    fetched_proof_targets: ProofTargets,
+    /// Number of multiproofs that are still being generated.
+    pending_proofs: usize,
+    /// The sparse trie the proofs are revealed in.
+    sparse_trie: SparseStateTrie,
+}
+
+impl<Factory> StateRootTask<Factory>
+where
+    Factory: DatabaseProviderFactory<Provider: BlockReader> + Clone + Send + Sync + 'static,
+{
+    /// Spawns the task on the given thread and returns a handle to it.
+    ///
+    /// If the thread exited, the handle reports the task as cancelled.
+    pub(crate) fn spawn(
+        config: StateRootConfig<Factory>,
+        thread: &StateRootTaskThread,
+    ) -> StateRootHandle {
+        let (tx, rx) = mpsc::channel();
+        let (result_tx, result_rx) = mpsc::channel();
+        let task = Self {
+            config,
+            rx,
+            tx: tx.clone(),
+            fetched_proof_targets: HashMap::default(),
+            pending_proofs: 0,
+            sparse_trie: SparseStateTrie::default().with_updates(true),
+        };
+        let _ = thread.tasks.send(Box::new(move || {
+            let _ = result_tx.send(task.run());
+        }));
+        StateRootHandle { tx, result_rx }
+    }
+
+    /// Runs the task until the state root is computed or the block execution is cancelled.
+    fn run(mut self) -> Result<StateRootComputeOutcome, StateRootTaskError> {
+        let mut final_state = None;
+        let mut last_update = Instant::now();
+        loop {
+            if final_state.is_some() && self.pending_proofs == 0 {
+                break
+            }
+            match self.rx.recv() {
+                Ok(StateRootMessage::StateUpdate(update)) => {
+                    let targets = touched_state_targets(update);
+                    self.fetch_multiproof(targets);
+                }
+                Ok(StateRootMessage::ProofCalculated { targets, result }) => {
+                    self.pending_proofs -= 1;
+                    self.sparse_trie.reveal_multiproof(targets, result?)?;
+                }
+                Ok(StateRootMessage::FinishedStateUpdates(state)) => {
+                    last_update = Instant::now();
+                    self.fetch_multiproof(hashed_state_targets(&state));
+                    final_state = Some(state);
+                }
+                Ok(StateRootMessage::Cancelled) | Err(_) => {
+                    return Err(StateRootTaskError::Cancelled)
+                }
+            }
+        }
+
+        let state = final_state.expect("checked above");
+        let state_root = self.update_and_compute_root(&state)?;
+        let trie_updates = self.sparse_trie.take_trie_updates();
+        let time_from_last_update = last_update.elapsed();
+        debug!(target: "engine::root", ?state_root, ?time_from_last_update, "Computed state root");
+        Ok(StateRootComputeOutcome { state_root, trie_updates, time_from_last_update })
+    }
+
+    /// Requests the multiproof for the targets that weren't requested yet.
+    fn fetch_multiproof(&mut self, targets: ProofTargets) {
+        let mut new_targets = ProofTargets::default();
+        for (account, slots) in targets {
+            let fetched = self.fetched_proof_targets.get(&account);
+            let slots = slots
+                .into_iter()
+                .filter(|slot| fetched.map_or(true, |fetched| !fetched.contains(slot)))
+                .collect::<HashSet<_>>();
+            if fetched.is_none() || !slots.is_empty() {
+                self.fetched_proof_targets
+                    .entry(account)
+                    .or_default()
+                    .extend(slots.iter().copied());
+                new_targets.insert(account, slots);
+            }
+        }
+        if new_targets.is_empty() {
+            return
+        }
+
+        trace!(target: "engine::root", accounts = new_targets.len(), "Fetching multiproof");
+        self.pending_proofs += 1;
+        let config = self.config.clone();
+        let tx = self.tx.clone();
+        rayon::spawn(move || {
+            let result = config.multiproof(new_targets.clone());
+            let _ = tx.send(StateRootMessage::ProofCalculated { targets: new_targets, result });
+        });
+    }
+
+    /// Applies the state changes of the block to the sparse trie and computes the root.
+    ///
+    /// Nodes that weren't revealed by the proofs of the changed accounts and slots, but are
+    /// needed to remove leaves, are revealed on demand.
+    fn update_and_compute_root(
+        &mut self,
+        state: &HashedPostState,
+    ) -> Result<B256, StateRootTaskError> {
+        let mut revealed_paths = HashSet::<(Option<B256>, Nibbles)>::default();
+        loop {
+            let (targets, blinded) = match self.sparse_trie.update(state) {
+                Ok(()) => break,
+                Err(SparseStateTrieError::Account(SparseTrieError::BlindedNode { path, hash })) => {
+                    let targets =
+                        ProofTargets::from_iter([(padded_key(&path), HashSet::default())]);
+                    (targets, (None, path, hash))
+                }
+                Err(SparseStateTrieError::Storage {
+                    account,
+                    error: SparseTrieError::BlindedNode { path, hash },
+                }) => {
+                    let targets = ProofTargets::from_iter([(
+                        account,
+                        HashSet::from_iter([padded_key(&path)]),
+                    )]);
+                    (targets, (Some(account), path, hash))
+                }
+                Err(error) => return Err(error.into()),
+            };
+
+            let (account, path, hash) = blinded;
+            if !revealed_paths.insert((account, path.clone())) {
+                let error = SparseTrieError::BlindedNode { path, hash };
+                let error = match account {
+                    Some(account) => SparseStateTrieError::Storage { account, error },
+                    None => SparseStateTrieError::Account(error),
+                };
+                return Err(StateRootTaskError::UnrevealedNode(error))
+            }
+
+            trace!(target: "engine::root", ?account, ?path, "Revealing blinded node");
+            let proof = self.config.multiproof(targets.clone())?;
+            self.sparse_trie.reveal_multiproof(targets, proof)?;
+        }
+
+        // the account trie stays blind if the block didn't change any state
+        self.sparse_trie.root().ok_or(StateRootTaskError::SparseTrie(SparseTrieError::Blind.into()))
+    }
+}
+
+/// Returns the accounts and storage slots that were changed.
+fn touched_state(state: &EvmState) -> TouchedState {
+    state
+        .iter()
+        .filter(|(_, account)| account.is_touched())
+        .map(|(address, account)| {
+            let slots = account
+                .storage
+                .iter()
+                .filter(|(_, slot)| slot.is_changed())
+                .map(|(slot, _)| *slot)
+                .collect();
+            (*address, slots)
+        })
+        .collect()
+}
+
+/// Returns the proof targets of the accounts and storage slots that were changed.
+fn touched_state_targets(state: TouchedState) -> ProofTargets {
+    state
+        .into_iter()
+        .map(|(address, slots)| {
+            let slots = slots.into_iter().map(|slot| keccak256(B256::from(slot))).collect();
+            (keccak256(address), slots)
+        })
+        .collect()
+}
+
+/// Returns the proof targets of the hashed post state.
+fn hashed_state_targets(state: &HashedPostState) -> ProofTargets {
+    let mut targets = state
+        .accounts
+        .keys()
+        .map(|account| (*account, HashSet::default()))
+        .collect::<ProofTargets>();
+    for (account, storage) in &state.storages {
+        targets.entry(*account).or_default().extend(storage.storage.keys().copied());
+    }
+    targets
+}
+
+/// Returns the key that starts with the given path, right padded with zeros.
+fn padded_key(path: &Nibbles) -> B256 {
+    let mut padded_key = path.pack();
+    padded_key.resize(32, 0);
+    B256::from_slice(&padded_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use reth_primitives::Account;
+    use reth_provider::{test_utils::create_test_provider_factory, StateChangeWriter, TrieWriter};
+    use reth_trie::{BranchNodeCompact, HashedStorage, StateRoot};
+    use reth_trie_db::DatabaseStateRoot;
+    use std::collections::BTreeMap;
+
+    /// Branch nodes of the account trie and the storage tries.
+    #[derive(PartialEq, Eq, Default, Debug)]
+    struct TrieNodes {
+        accounts: BTreeMap<Nibbles, BranchNodeCompact>,
+        storages: BTreeMap<B256, BTreeMap<Nibbles, BranchNodeCompact>>,
+    }
+
+    impl TrieNodes {
+        fn apply(&mut self, updates: &TrieUpdates) {
+            for path in updates.removed_nodes_ref() {
+                self.accounts.remove(path);
+            }
+            self.accounts.extend(updates.account_nodes_ref().clone());
+
+            for (account, storage_updates) in updates.storage_tries_ref() {
+                let nodes = self.storages.entry(*account).or_default();
+                if storage_updates.is_deleted() {
+                    nodes.clear();
+                }
+                for path in storage_updates.removed_nodes_ref() {
+                    nodes.remove(path);
+                }
+                nodes.extend(storage_updates.storage_nodes_ref().clone());
+            }
+            self.storages.retain(|_, nodes| !nodes.is_empty());
+        }
+    }
+
+    /// Returns a random key, most keys share the first nibbles so that branch nodes collapse when
+    /// leaves are removed.
+    fn random_key(rng: &mut StdRng) -> B256 {
+        let mut key = rng.gen::<[u8; 32]>();
+        if rng.gen_ratio(3, 4) {
+            key[0] = rng.gen_range(0..4);
+        }
+        B256::from(key)
+    }
+
+    fn random_storage(rng: &mut StdRng) -> BTreeMap<B256, U256> {
+        (0..rng.gen_range(0..20))
+            .map(|_| (random_key(rng), U256::from(rng.gen_range(1..u64::MAX))))
+            .collect()
+    }
+
+    #[test]
+    fn state_root_task_equivalence() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let thread = StateRootTaskThread::spawn();
+
+        for _ in 0..16 {
+            let factory = create_test_provider_factory();
+            let accounts = (0..rng.gen_range(1..50))
+                .map(|_| (random_key(&mut rng), random_storage(&mut rng)))
+                .collect::<BTreeMap<_, _>>();
+
+            let mut initial_state = HashedPostState::default();
+            for (account, storage) in &accounts {
+                let info = Account { nonce: 1, ..Default::default() };
+                initial_state.accounts.insert(*account, Some(info));
+                initial_state
+                    .storages
+                    .insert(*account, HashedStorage::from_iter(false, storage.clone()));
+            }
+            let provider_rw = factory.provider_rw().unwrap();
+            provider_rw.write_hashed_state(&initial_state.into_sorted()).unwrap();
+            let (_, initial_updates) =
+                StateRoot::from_tx(provider_rw.tx_ref()).root_with_updates().unwrap();
+            provider_rw.write_trie_updates(&initial_updates).unwrap();
+            provider_rw.commit().unwrap();
+
+            let mut state = HashedPostState::default();
+            for (account, storage) in &accounts {
+                let info = Account { nonce: 2, ..Default::default() };
+                match rng.gen_range(0..6) {
+                    // storage changes only, zero values remove the slots
+                    1 => {
+                        let slots = (0..rng.gen_range(0..10))
+                            .map(|_| (random_key(&mut rng), U256::from(rng.gen_range(0u64..3))));
+                        state.storages.insert(*account, HashedStorage::from_iter(false, slots));
+                    }
+                    // every other slot is removed
+                    2 => {
+                        let removed = storage.keys().step_by(2).map(|slot| (*slot, U256::ZERO));
+                        state.accounts.insert(*account, Some(info));
+                        state.storages.insert(*account, HashedStorage::from_iter(false, removed));
+                    }
+                    // the storage is wiped and rewritten
+                    3 => {
+                        let slots = random_storage(&mut rng);
+                        state.accounts.insert(*account, Some(info));
+                        state.storages.insert(*account, HashedStorage::from_iter(true, slots));
+                    }
+                    // the account is destroyed
+                    4 => {
+                        state.accounts.insert(*account, None);
+                        state.storages.insert(*account, HashedStorage::new(true));
+                    }
+                    _ => {}
+                }
+            }
+            for _ in 0..rng.gen_range(1..10) {
+                let account = random_key(&mut rng);
+                if accounts.contains_key(&account) {
+                    continue
+                }
+                let storage = random_storage(&mut rng);
+                state.accounts.insert(account, Some(Account::default()));
+                state.storages.insert(account, HashedStorage::from_iter(false, storage));
+            }
+
+            let config = StateRootConfig::new(
+                ConsistentDbView::new(factory.clone(), None),
+                TrieInput::default(),
+            );
+            let outcome = StateRootTask::spawn(config, &thread).wait_for_result(state.clone());
+            let outcome = outcome.unwrap();
+
+            let provider = factory.provider().unwrap();
+            let (expected_root, expected_updates) =
+                StateRoot::overlay_root_with_updates(provider.tx_ref(), state).unwrap();
+            assert_eq!(outcome.state_root, expected_root);
+
+            let mut expected_nodes = TrieNodes::default();
+            expected_nodes.apply(&initial_updates);
+            let mut nodes = TrieNodes::default();
+            nodes.apply(&initial_updates);
+            expected_nodes.apply(&expected_updates);
+            nodes.apply(&outcome.trie_updates);
+            assert_eq!(nodes, expected_nodes);
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! Stream wrapper that delays engine API messages.
+
+use futures::{Stream, StreamExt};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use reth_beacon_consensus::BeaconEngineMessage;
+use reth_engine_primitives::EngineTypes;
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{ready, Context, Poll},
+    time::Duration,
+};
+use tokio::time::Sleep;
+
+/// Engine API stream wrapper that delays every message by a fixed delay plus a random jitter.
+///
+/// Messages are delayed one at a time, so their order is preserved.
+#[derive(Debug)]
+#[pin_project::pin_project]
+pub struct EngineDelay<S, Engine: EngineTypes> {
+    #[pin]
+    stream: S,
+    /// The fixed delay of every message.
+    delay: Duration,
+    /// The maximum random delay added to the fixed delay.
+    jitter: Duration,
+    /// The source of the jitter.
+    rng: StdRng,
+    /// The message that is currently delayed.
+    delayed: Option<(Pin<Box<Sleep>>, BeaconEngineMessage<Engine>)>,
+}
+
+impl<S, Engine: EngineTypes> EngineDelay<S, Engine> {
+    /// Creates new [`EngineDelay`] stream wrapper, with the jitter seeded with the given seed.
+    pub fn new(stream: S, delay: Duration, jitter: Duration, seed: u64) -> Self {
+        Self { stream, delay, jitter, rng: StdRng::seed_from_u64(seed), delayed: None }
+    }
+}
+
+impl<S, Engine> Stream for EngineDelay<S, Engine>
+where
+    S: Stream<Item = BeaconEngineMessage<Engine>>,
+    Engine: EngineTypes,
+{
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        loop {
+            if let Some((sleep, _)) = this.delayed.as_mut() {
+                ready!(sleep.as_mut().poll(cx));
+                let (_, msg) = this.delayed.take().expect("delayed message exists");
+                return Poll::Ready(Some(msg))
+            }
+
+            let Some(msg) = ready!(this.stream.poll_next_unpin(cx)) else {
+                return Poll::Ready(None)
+            };
+            let delay = *this.delay + this.jitter.mul_f64(this.rng.gen());
+            tracing::trace!(target: "engine::stream::delay", %msg, ?delay, "Delaying message");
+            *this.delayed = Some((Box::pin(tokio::time::sleep(delay)), msg));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{chain_messages, describe};
+
+    #[tokio::test]
+    async fn preserves_order() {
+        let (messages, _payload_rxs, _forkchoice_rxs) = chain_messages(4);
+        let expected = messages.iter().map(describe).collect::<Vec<_>>();
+        let stream = EngineDelay::new(
+            futures::stream::iter(messages),
+            Duration::from_millis(1),
+            Duration::from_millis(1),
+            0,
+        );
+        let delivered = stream.map(|msg| describe(&msg)).collect::<Vec<_>>().await;
+        assert_eq!(delivered, expected);
+    }
+}
@@ -0,0 +1,53 @@
+//! Helpers for forwarding engine API messages independently of the consensus client.
+
+use alloy_rpc_types_engine::{PayloadStatus, PayloadStatusEnum};
+use reth_beacon_consensus::{BeaconEngineMessage, OnForkChoiceUpdated};
+use reth_engine_primitives::EngineTypes;
+use tokio::sync::oneshot;
+
+/// Responds to the consensus client as if the engine was syncing, and returns the message with a
+/// response that is discarded, so that it can be forwarded to the engine at any later point.
+pub(crate) fn detach<Engine: EngineTypes>(
+    msg: BeaconEngineMessage<Engine>,
+) -> BeaconEngineMessage<Engine> {
+    match msg {
+        BeaconEngineMessage::NewPayload { payload, cancun_fields, tx } => {
+            let _ = tx.send(Ok(PayloadStatus::from_status(PayloadStatusEnum::Syncing)));
+            BeaconEngineMessage::NewPayload { payload, cancun_fields, tx: oneshot::channel().0 }
+        }
+        BeaconEngineMessage::ForkchoiceUpdated { state, payload_attrs, tx } => {
+            let _ = tx.send(Ok(OnForkChoiceUpdated::syncing()));
+            BeaconEngineMessage::ForkchoiceUpdated {
+                state,
+                payload_attrs,
+                tx: oneshot::channel().0,
+            }
+        }
+        msg @ BeaconEngineMessage::TransitionConfigurationExchanged => msg,
+    }
+}
+
+/// Returns a copy of the message with a response that is discarded.
+pub(crate) fn detached_copy<Engine: EngineTypes>(
+    msg: &BeaconEngineMessage<Engine>,
+) -> BeaconEngineMessage<Engine> {
+    match msg {
+        BeaconEngineMessage::NewPayload { payload, cancun_fields, .. } => {
+            BeaconEngineMessage::NewPayload {
+                payload: payload.clone(),
+                cancun_fields: cancun_fields.clone(),
+                tx: oneshot::channel().0,
+            }
+        }
+        BeaconEngineMessage::ForkchoiceUpdated { state, payload_attrs, .. } => {
+            BeaconEngineMessage::ForkchoiceUpdated {
+                state: *state,
+                payload_attrs: payload_attrs.clone(),
+                tx: oneshot::channel().0,
+            }
+        }
+        BeaconEngineMessage::TransitionConfigurationExchanged => {
+            BeaconEngineMessage::TransitionConfigurationExchanged
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! Stream wrapper that duplicates engine API messages.
+
+use crate::detach::detached_copy;
+use futures::{Stream, StreamExt};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use reth_beacon_consensus::BeaconEngineMessage;
+use reth_engine_primitives::EngineTypes;
+use std::{
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+/// Engine API stream wrapper that duplicates messages with the specified probability.
+///
+/// The duplicate is delivered right after the original message, and the response to it is
+/// discarded.
+#[derive(Debug)]
+#[pin_project::pin_project]
+pub struct EngineDuplicate<S, Engine: EngineTypes> {
+    #[pin]
+    stream: S,
+    /// The probability of duplicating a message, in percent.
+    percentage: u8,
+    /// The source of the duplication decisions.
+    rng: StdRng,
+    /// The duplicate to deliver next.
+    duplicate: Option<BeaconEngineMessage<Engine>>,
+}
+
+impl<S, Engine: EngineTypes> EngineDuplicate<S, Engine> {
+    /// Creates new [`EngineDuplicate`] stream wrapper, with the decisions seeded with the given
+    /// seed.
+    pub fn new(stream: S, percentage: u8, seed: u64) -> Self {
+        Self {
+            stream,
+            percentage: percentage.min(100),
+            rng: StdRng::seed_from_u64(seed),
+            duplicate: None,
+        }
+    }
+}
+
+impl<S, Engine> Stream for EngineDuplicate<S, Engine>
+where
+    S: Stream<Item = BeaconEngineMessage<Engine>>,
+    Engine: EngineTypes,
+{
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        if let Some(duplicate) = this.duplicate.take() {
+            return Poll::Ready(Some(duplicate))
+        }
+
+        let next = ready!(this.stream.poll_next_unpin(cx));
+        if let Some(msg) = &next {
+            if this.rng.gen_ratio(u32::from(*this.percentage), 100) {
+                tracing::warn!(target: "engine::stream::duplicate", %msg, "Duplicating message");
+                *this.duplicate = Some(detached_copy(msg));
+            }
+        }
+        Poll::Ready(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{chain_messages, describe};
+
+    #[tokio::test]
+    async fn fixed_seed_order() {
+        let (messages, mut payload_rxs, _forkchoice_rxs) = chain_messages(4);
+        let stream = EngineDuplicate::new(futures::stream::iter(messages), 50, 0);
+        let delivered = stream.map(|msg| describe(&msg)).collect::<Vec<_>>().await;
+        assert_eq!(
+            delivered,
+            ["np1", "fcu1", "np2", "np2", "fcu2", "np3", "np3", "fcu3", "np4", "np4", "fcu4"]
+        );
+
+        // the consensus client is only responded to by the engine
+        for rx in &mut payload_rxs {
+            assert!(rx.try_recv().is_err());
+        }
+    }
+}
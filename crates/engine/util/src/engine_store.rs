@@ -1,20 +1,35 @@
 //! Stores engine API messages to disk for later inspection and replay.
 
-use alloy_rpc_types_engine::{CancunPayloadFields, ExecutionPayload, ForkchoiceState};
-use futures::{Stream, StreamExt};
-use reth_beacon_consensus::BeaconEngineMessage;
+use alloy_rpc_types_engine::{
+    CancunPayloadFields, ExecutionPayload, ForkchoiceState, PayloadStatus,
+};
+use futures::{
+    stream::{Fuse, FuturesUnordered},
+    Stream, StreamExt,
+};
+use reth_beacon_consensus::{
+    BeaconEngineMessage, BeaconOnNewPayloadError, ForkchoiceStatus, OnForkChoiceUpdated,
+};
 use reth_engine_primitives::EngineTypes;
+use reth_errors::RethResult;
 use reth_fs_util as fs;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::{
     collections::BTreeMap,
-    path::PathBuf,
+    future::Future,
+    mem,
+    path::{Path, PathBuf},
     pin::Pin,
     task::{ready, Context, Poll},
     time::SystemTime,
 };
+use tokio::sync::oneshot;
 use tracing::*;
 
+/// File extension of stored engine API responses, replacing the `json` extension of the message
+/// they belong to.
+const RESPONSE_EXTENSION: &str = "response.json";
+
 /// A message from the engine API that has been stored to disk.
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -35,8 +50,89 @@ pub enum StoredEngineApiMessage<Attributes> {
     },
 }
 
+/// The response to an engine API message that has been stored to disk.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StoredEngineApiResponse {
+    /// The on-disk representation of an `engine_forkchoiceUpdated` response.
+    ForkchoiceUpdated {
+        /// The status of the forkchoice state, or the error message if the call failed.
+        status: Result<StoredForkchoiceStatus, String>,
+    },
+    /// The on-disk representation of an `engine_newPayload` response.
+    NewPayload {
+        /// The [`PayloadStatus`] of the payload, or the error message if the call failed.
+        status: Result<PayloadStatus, String>,
+    },
+}
+
+impl StoredEngineApiResponse {
+    /// Creates the on-disk representation of a forkchoice updated response.
+    pub fn forkchoice_updated(response: &RethResult<OnForkChoiceUpdated>) -> Self {
+        Self::ForkchoiceUpdated {
+            status: response
+                .as_ref()
+                .map(|response| response.forkchoice_status().into())
+                .map_err(ToString::to_string),
+        }
+    }
+
+    /// Creates the on-disk representation of a new payload response.
+    pub fn new_payload(response: &Result<PayloadStatus, BeaconOnNewPayloadError>) -> Self {
+        Self::NewPayload { status: response.as_ref().cloned().map_err(ToString::to_string) }
+    }
+
+    /// Returns `true` if both responses have the same outcome.
+    ///
+    /// Validation and error messages are not compared, since they are not stable across versions.
+    pub fn same_outcome(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::ForkchoiceUpdated { status }, Self::ForkchoiceUpdated { status: other }) => {
+                match (status, other) {
+                    (Ok(status), Ok(other)) => status == other,
+                    (Err(_), Err(_)) => true,
+                    _ => false,
+                }
+            }
+            (Self::NewPayload { status }, Self::NewPayload { status: other }) => {
+                match (status, other) {
+                    (Ok(status), Ok(other)) => {
+                        mem::discriminant(&status.status) == mem::discriminant(&other.status) &&
+                            status.latest_valid_hash == other.latest_valid_hash
+                    }
+                    (Err(_), Err(_)) => true,
+                    _ => false,
+                }
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The on-disk representation of a [`ForkchoiceStatus`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StoredForkchoiceStatus {
+    /// The forkchoice state is valid.
+    Valid,
+    /// The forkchoice state is invalid.
+    Invalid,
+    /// The forkchoice state is unknown.
+    Syncing,
+}
+
+impl From<ForkchoiceStatus> for StoredForkchoiceStatus {
+    fn from(status: ForkchoiceStatus) -> Self {
+        match status {
+            ForkchoiceStatus::Valid => Self::Valid,
+            ForkchoiceStatus::Invalid => Self::Invalid,
+            ForkchoiceStatus::Syncing => Self::Syncing,
+        }
+    }
+}
+
 /// This can read and write engine API messages in a specific directory.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct EngineMessageStore {
     /// The path to the directory that stores the engine API messages.
     path: PathBuf,
@@ -61,12 +157,11 @@ impl EngineMessageStore {
         Engine: EngineTypes,
     {
         fs::create_dir_all(&self.path)?; // ensure that store path had been created
-        let timestamp = received_at.duration_since(SystemTime::UNIX_EPOCH).unwrap().as_millis();
+        let Some(path) = self.message_path(msg, received_at) else { return Ok(()) };
         match msg {
             BeaconEngineMessage::ForkchoiceUpdated { state, payload_attrs, tx: _tx } => {
-                let filename = format!("{}-fcu-{}.json", timestamp, state.head_block_hash);
                 fs::write(
-                    self.path.join(filename),
+                    path,
                     serde_json::to_vec(&StoredEngineApiMessage::ForkchoiceUpdated {
                         state: *state,
                         payload_attrs: payload_attrs.clone(),
@@ -74,9 +169,8 @@ impl EngineMessageStore {
                 )?;
             }
             BeaconEngineMessage::NewPayload { payload, cancun_fields, tx: _tx } => {
-                let filename = format!("{}-new_payload-{}.json", timestamp, payload.block_hash());
                 fs::write(
-                    self.path.join(filename),
+                    path,
                     serde_json::to_vec(
                         &StoredEngineApiMessage::<Engine::PayloadAttributes>::NewPayload {
                             payload: payload.clone(),
@@ -91,13 +185,71 @@ impl EngineMessageStore {
         Ok(())
     }
 
+    /// Returns the path the given message received at the given time is stored at, or `None` if
+    /// the message isn't stored.
+    pub fn message_path<Engine>(
+        &self,
+        msg: &BeaconEngineMessage<Engine>,
+        received_at: SystemTime,
+    ) -> Option<PathBuf>
+    where
+        Engine: EngineTypes,
+    {
+        let timestamp = received_at.duration_since(SystemTime::UNIX_EPOCH).unwrap().as_millis();
+        let filename = match msg {
+            BeaconEngineMessage::ForkchoiceUpdated { state, .. } => {
+                format!("{}-fcu-{}.json", timestamp, state.head_block_hash)
+            }
+            BeaconEngineMessage::NewPayload { payload, .. } => {
+                format!("{}-new_payload-{}.json", timestamp, payload.block_hash())
+            }
+            BeaconEngineMessage::TransitionConfigurationExchanged => return None,
+        };
+        Some(self.path.join(filename))
+    }
+
+    /// Stores the response to the message stored at the given path.
+    pub fn on_response(
+        &self,
+        message_path: &Path,
+        response: &StoredEngineApiResponse,
+    ) -> eyre::Result<()> {
+        fs::write(message_path.with_extension(RESPONSE_EXTENSION), serde_json::to_vec(response)?)?;
+        Ok(())
+    }
+
+    /// Reads the message stored at the given path.
+    pub fn read_message<Attributes: DeserializeOwned>(
+        &self,
+        message_path: &Path,
+    ) -> eyre::Result<StoredEngineApiMessage<Attributes>> {
+        Ok(serde_json::from_slice(&fs::read(message_path)?)?)
+    }
+
+    /// Reads the response to the message stored at the given path, if it was recorded.
+    pub fn read_response(
+        &self,
+        message_path: &Path,
+    ) -> eyre::Result<Option<StoredEngineApiResponse>> {
+        let path = message_path.with_extension(RESPONSE_EXTENSION);
+        if !path.exists() {
+            return Ok(None)
+        }
+        Ok(Some(serde_json::from_slice(&fs::read(path)?)?))
+    }
+
     /// Finds and iterates through any stored engine API message files, ordered by timestamp.
+    ///
+    /// Stored responses are skipped, see [`Self::read_response`].
     pub fn engine_messages_iter(&self) -> eyre::Result<impl Iterator<Item = PathBuf>> {
         let mut filenames_by_ts = BTreeMap::<u64, Vec<PathBuf>>::default();
         for entry in fs::read_dir(&self.path)? {
             let entry = entry?;
             let filename = entry.file_name();
             if let Some(filename) = filename.to_str().filter(|n| n.ends_with(".json")) {
+                if filename.ends_with(RESPONSE_EXTENSION) {
+                    continue
+                }
                 if let Some(Ok(timestamp)) = filename.split('-').next().map(|n| n.parse::<u64>()) {
                     filenames_by_ts.entry(timestamp).or_default().push(entry.path());
                     tracing::debug!(target: "engine::store", timestamp, filename, "Queued engine API message");
@@ -112,22 +264,69 @@ impl EngineMessageStore {
     }
 }
 
-/// A wrapper stream that stores Engine API messages in
+type StoreResponseFut = Pin<Box<dyn Future<Output = ()> + Send + Sync>>;
+
+/// A wrapper stream that stores Engine API messages and the responses to them in
 /// the specified directory.
 #[derive(Debug)]
 #[pin_project::pin_project]
 pub struct EngineStoreStream<S> {
     /// Inner message stream.
     #[pin]
-    stream: S,
+    stream: Fuse<S>,
     /// Engine message store.
     store: EngineMessageStore,
+    /// Pending engine responses that are stored before they are forwarded.
+    responses: FuturesUnordered<StoreResponseFut>,
 }
 
-impl<S> EngineStoreStream<S> {
+impl<S: Stream> EngineStoreStream<S> {
     /// Create new engine store stream wrapper.
-    pub const fn new(stream: S, path: PathBuf) -> Self {
-        Self { stream, store: EngineMessageStore::new(path) }
+    pub fn new(stream: S, path: PathBuf) -> Self {
+        Self {
+            stream: stream.fuse(),
+            store: EngineMessageStore::new(path),
+            responses: FuturesUnordered::new(),
+        }
+    }
+}
+
+/// Intercepts the response to the message stored at the given path, storing it before it is
+/// forwarded to the original receiver.
+fn store_response<Engine: EngineTypes>(
+    store: &EngineMessageStore,
+    path: PathBuf,
+    msg: BeaconEngineMessage<Engine>,
+) -> (BeaconEngineMessage<Engine>, StoreResponseFut) {
+    let store = store.clone();
+    match msg {
+        BeaconEngineMessage::ForkchoiceUpdated { state, payload_attrs, tx } => {
+            let (store_tx, store_rx) = oneshot::channel();
+            let fut = Box::pin(async move {
+                let Ok(response) = store_rx.await else { return };
+                let stored = StoredEngineApiResponse::forkchoice_updated(&response);
+                if let Err(error) = store.on_response(&path, &stored) {
+                    error!(target: "engine::stream::store", ?stored, %error, "Error storing Engine API response");
+                }
+                let _ = tx.send(response);
+            });
+            (BeaconEngineMessage::ForkchoiceUpdated { state, payload_attrs, tx: store_tx }, fut)
+        }
+        BeaconEngineMessage::NewPayload { payload, cancun_fields, tx } => {
+            let (store_tx, store_rx) = oneshot::channel();
+            let fut = Box::pin(async move {
+                let Ok(response) = store_rx.await else { return };
+                let stored = StoredEngineApiResponse::new_payload(&response);
+                if let Err(error) = store.on_response(&path, &stored) {
+                    error!(target: "engine::stream::store", ?stored, %error, "Error storing Engine API response");
+                }
+                let _ = tx.send(response);
+            });
+            (BeaconEngineMessage::NewPayload { payload, cancun_fields, tx: store_tx }, fut)
+        }
+        msg @ BeaconEngineMessage::TransitionConfigurationExchanged => {
+            (msg, Box::pin(futures::future::ready(())))
+        }
     }
 }
 
@@ -140,12 +339,96 @@ where
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         let mut this = self.project();
-        let next = ready!(this.stream.poll_next_unpin(cx));
-        if let Some(msg) = &next {
-            if let Err(error) = this.store.on_message(msg, SystemTime::now()) {
-                error!(target: "engine::stream::store", ?msg, %error, "Error handling Engine API message");
-            }
+
+        // Store and forward the responses that are ready
+        while let Poll::Ready(Some(())) = this.responses.poll_next_unpin(cx) {}
+
+        let Some(msg) = ready!(this.stream.poll_next_unpin(cx)) else {
+            // Wait for the in-flight responses to be stored and forwarded before ending the stream
+            return if this.responses.is_empty() { Poll::Ready(None) } else { Poll::Pending }
+        };
+        let received_at = SystemTime::now();
+        if let Err(error) = this.store.on_message(&msg, received_at) {
+            error!(target: "engine::stream::store", ?msg, %error, "Error handling Engine API message");
+            return Poll::Ready(Some(msg))
+        }
+        let Some(path) = this.store.message_path(&msg, received_at) else {
+            return Poll::Ready(Some(msg))
+        };
+
+        let (msg, response) = store_response(this.store, path, msg);
+        this.responses.push(response);
+        // Poll the new response once, so that it registers for wakeups
+        let _ = this.responses.poll_next_unpin(cx);
+        Poll::Ready(Some(msg))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::B256;
+    use alloy_rpc_types_engine::PayloadStatusEnum;
+
+    fn new_payload(
+        status: PayloadStatusEnum,
+        latest_valid_hash: Option<B256>,
+    ) -> StoredEngineApiResponse {
+        StoredEngineApiResponse::NewPayload {
+            status: Ok(PayloadStatus::new(status, latest_valid_hash)),
         }
-        Poll::Ready(next)
+    }
+
+    fn invalid(validation_error: &str) -> PayloadStatusEnum {
+        PayloadStatusEnum::Invalid { validation_error: validation_error.to_string() }
+    }
+
+    #[test]
+    fn same_outcome_new_payload() {
+        let hash = B256::with_last_byte(1);
+        let valid = new_payload(PayloadStatusEnum::Valid, Some(hash));
+        assert!(valid.same_outcome(&new_payload(PayloadStatusEnum::Valid, Some(hash))));
+
+        // validation errors aren't compared
+        assert!(new_payload(invalid("a"), Some(hash))
+            .same_outcome(&new_payload(invalid("b"), Some(hash))));
+
+        // the status and the latest valid hash are compared
+        assert!(!valid.same_outcome(&new_payload(PayloadStatusEnum::Syncing, None)));
+        assert!(!valid.same_outcome(&new_payload(invalid("a"), Some(hash))));
+        assert!(!valid.same_outcome(&new_payload(PayloadStatusEnum::Valid, None)));
+        assert!(!valid.same_outcome(&new_payload(PayloadStatusEnum::Valid, Some(B256::ZERO))));
+
+        // error messages aren't compared
+        let error = |message: &str| StoredEngineApiResponse::NewPayload {
+            status: Err(message.to_string()),
+        };
+        assert!(error("a").same_outcome(&error("b")));
+        assert!(!error("a").same_outcome(&valid));
+        assert!(!valid.same_outcome(&error("a")));
+    }
+
+    #[test]
+    fn same_outcome_forkchoice_updated() {
+        let fcu = |status| StoredEngineApiResponse::ForkchoiceUpdated { status: Ok(status) };
+        let error = |message: &str| StoredEngineApiResponse::ForkchoiceUpdated {
+            status: Err(message.to_string()),
+        };
+
+        assert!(
+            fcu(StoredForkchoiceStatus::Valid).same_outcome(&fcu(StoredForkchoiceStatus::Valid))
+        );
+        assert!(
+            !fcu(StoredForkchoiceStatus::Valid).same_outcome(&fcu(StoredForkchoiceStatus::Syncing))
+        );
+        assert!(!fcu(StoredForkchoiceStatus::Invalid)
+            .same_outcome(&fcu(StoredForkchoiceStatus::Syncing)));
+        assert!(error("a").same_outcome(&error("b")));
+        assert!(!error("a").same_outcome(&fcu(StoredForkchoiceStatus::Valid)));
+
+        // responses to different messages never have the same outcome
+        let valid = new_payload(PayloadStatusEnum::Valid, None);
+        assert!(!fcu(StoredForkchoiceStatus::Valid).same_outcome(&valid));
+        assert!(!valid.same_outcome(&fcu(StoredForkchoiceStatus::Valid)));
     }
 }
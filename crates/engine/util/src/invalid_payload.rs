@@ -0,0 +1,153 @@
+//! Stream wrapper that injects invalid payloads.
+
+use alloy_primitives::B256;
+use alloy_rpc_types_engine::{CancunPayloadFields, ExecutionPayload, PayloadError};
+use futures::{Stream, StreamExt};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use reth_beacon_consensus::BeaconEngineMessage;
+use reth_engine_primitives::EngineTypes;
+use reth_rpc_types_compat::engine::payload::{block_to_payload, try_into_block};
+use std::{
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+use tokio::sync::oneshot;
+
+/// Engine API stream wrapper that injects an invalid sibling before new payload messages with the
+/// specified probability.
+///
+/// The invalid payload has a random state root and a matching block hash, so it is only rejected
+/// after execution. The response to it is discarded.
+#[derive(Debug)]
+#[pin_project::pin_project]
+pub struct EngineInvalidPayload<S, Engine: EngineTypes> {
+    #[pin]
+    stream: S,
+    /// The probability of injecting an invalid payload, in percent.
+    percentage: u8,
+    /// The source of the injection decisions and invalid state roots.
+    rng: StdRng,
+    /// The message to deliver next.
+    queued: Option<BeaconEngineMessage<Engine>>,
+}
+
+impl<S, Engine: EngineTypes> EngineInvalidPayload<S, Engine> {
+    /// Creates new [`EngineInvalidPayload`] stream wrapper, with the decisions seeded with the
+    /// given seed.
+    pub fn new(stream: S, percentage: u8, seed: u64) -> Self {
+        Self {
+            stream,
+            percentage: percentage.min(100),
+            rng: StdRng::seed_from_u64(seed),
+            queued: None,
+        }
+    }
+}
+
+impl<S, Engine> Stream for EngineInvalidPayload<S, Engine>
+where
+    S: Stream<Item = BeaconEngineMessage<Engine>>,
+    Engine: EngineTypes,
+{
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        if let Some(msg) = this.queued.take() {
+            return Poll::Ready(Some(msg))
+        }
+
+        let next = ready!(this.stream.poll_next_unpin(cx));
+        let item = match next {
+            Some(BeaconEngineMessage::NewPayload { payload, cancun_fields, tx })
+                if this.rng.gen_ratio(u32::from(*this.percentage), 100) =>
+            {
+                match invalid_payload(&payload, cancun_fields.as_ref(), this.rng) {
+                    Ok(invalid) => {
+                        tracing::warn!(target: "engine::stream::invalid_payload", block_hash = %payload.block_hash(), invalid_block_hash = %invalid.block_hash(), "Injecting invalid payload");
+                        let invalid = BeaconEngineMessage::NewPayload {
+                            payload: invalid,
+                            cancun_fields: cancun_fields.clone(),
+                            tx: oneshot::channel().0,
+                        };
+                        *this.queued =
+                            Some(BeaconEngineMessage::NewPayload { payload, cancun_fields, tx });
+                        Some(invalid)
+                    }
+                    Err(error) => {
+                        tracing::warn!(target: "engine::stream::invalid_payload", %error, "Failed to create invalid payload");
+                        Some(BeaconEngineMessage::NewPayload { payload, cancun_fields, tx })
+                    }
+                }
+            }
+            next => next,
+        };
+        Poll::Ready(item)
+    }
+}
+
+/// Returns a copy of the payload with a random state root.
+fn invalid_payload(
+    payload: &ExecutionPayload,
+    cancun_fields: Option<&CancunPayloadFields>,
+    rng: &mut StdRng,
+) -> Result<ExecutionPayload, PayloadError> {
+    let mut block = try_into_block(
+        payload.clone(),
+        cancun_fields.map(|fields| fields.parent_beacon_block_root),
+    )?;
+    block.header.state_root = B256::from(rng.gen::<[u8; 32]>());
+    Ok(block_to_payload(block.seal_slow()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{chain_messages, describe};
+
+    #[tokio::test]
+    async fn fixed_seed_order() {
+        let (messages, mut payload_rxs, _forkchoice_rxs) = chain_messages(4);
+        let valid_hashes = messages
+            .iter()
+            .filter_map(|msg| match msg {
+                BeaconEngineMessage::NewPayload { payload, .. } => Some(payload.block_hash()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let stream = EngineInvalidPayload::new(futures::stream::iter(messages), 50, 1);
+        let delivered = stream
+            .map(|msg| match &msg {
+                BeaconEngineMessage::NewPayload { payload, .. }
+                    if !valid_hashes.contains(&payload.block_hash()) =>
+                {
+                    format!("{} invalid", describe(&msg))
+                }
+                _ => describe(&msg),
+            })
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(
+            delivered,
+            [
+                "np1",
+                "fcu1",
+                "np2",
+                "fcu2",
+                "np3 invalid",
+                "np3",
+                "fcu3",
+                "np4 invalid",
+                "np4",
+                "fcu4"
+            ]
+        );
+
+        // the consensus client is only responded to by the engine for the valid payloads
+        for rx in &mut payload_rxs {
+            assert!(rx.try_recv().is_err());
+        }
+    }
+}
@@ -4,7 +4,7 @@ use futures::Stream;
 use reth_beacon_consensus::BeaconEngineMessage;
 use reth_engine_primitives::EngineTypes;
 use reth_payload_validator::ExecutionPayloadValidator;
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 use tokio_util::either::Either;
 
 pub mod engine_store;
@@ -19,6 +19,28 @@ use skip_new_payload::EngineSkipNewPayload;
 pub mod reorg;
 use reorg::EngineReorg;
 
+pub mod replay;
+
+pub mod delay;
+use delay::EngineDelay;
+
+pub mod duplicate;
+use duplicate::EngineDuplicate;
+
+pub mod reorder_fcu;
+use reorder_fcu::EngineReorderFcu;
+
+pub mod out_of_order;
+use out_of_order::EngineOutOfOrderPayloads;
+
+pub mod invalid_payload;
+use invalid_payload::EngineInvalidPayload;
+
+mod detach;
+
+#[cfg(test)]
+mod test_utils;
+
 /// The collection of stream extensions for engine API message stream.
 pub trait EngineMessageStreamExt<Engine: EngineTypes>:
     Stream<Item = BeaconEngineMessage<Engine>>
@@ -142,6 +164,143 @@ pub trait EngineMessageStreamExt<Engine: EngineTypes>:
             Either::Right(self)
         }
     }
+
+    /// Delays every message by the specified delay plus a random jitter up to the specified
+    /// maximum. The jitter is seeded with the given seed.
+    fn delay(self, delay: Duration, jitter: Duration, seed: u64) -> EngineDelay<Self, Engine>
+    where
+        Self: Sized,
+    {
+        EngineDelay::new(self, delay, jitter, seed)
+    }
+
+    /// If the delay or the jitter is [Some], returns the stream that delays every message.
+    /// Otherwise, returns `Self`.
+    fn maybe_delay(
+        self,
+        delay: Option<Duration>,
+        jitter: Option<Duration>,
+        seed: u64,
+    ) -> Either<EngineDelay<Self, Engine>, Self>
+    where
+        Self: Sized,
+    {
+        if delay.is_some() || jitter.is_some() {
+            Either::Left(self.delay(delay.unwrap_or_default(), jitter.unwrap_or_default(), seed))
+        } else {
+            Either::Right(self)
+        }
+    }
+
+    /// Duplicates messages with the specified probability in percent. The decisions are seeded
+    /// with the given seed.
+    fn duplicate(self, percentage: u8, seed: u64) -> EngineDuplicate<Self, Engine>
+    where
+        Self: Sized,
+    {
+        EngineDuplicate::new(self, percentage, seed)
+    }
+
+    /// If the percentage is [Some], returns the stream that duplicates messages. Otherwise, returns
+    /// `Self`.
+    fn maybe_duplicate(
+        self,
+        percentage: Option<u8>,
+        seed: u64,
+    ) -> Either<EngineDuplicate<Self, Engine>, Self>
+    where
+        Self: Sized,
+    {
+        if let Some(percentage) = percentage {
+            Either::Left(self.duplicate(percentage, seed))
+        } else {
+            Either::Right(self)
+        }
+    }
+
+    /// Delivers [`BeaconEngineMessage::ForkchoiceUpdated`] messages after the next
+    /// [`BeaconEngineMessage::NewPayload`] message with the specified probability in percent. The
+    /// decisions are seeded with the given seed.
+    fn reorder_fcu(self, percentage: u8, seed: u64) -> EngineReorderFcu<Self, Engine>
+    where
+        Self: Sized,
+    {
+        EngineReorderFcu::new(self, percentage, seed)
+    }
+
+    /// If the percentage is [Some], returns the stream that delivers FCUs after the next new
+    /// payload. Otherwise, returns `Self`.
+    fn maybe_reorder_fcu(
+        self,
+        percentage: Option<u8>,
+        seed: u64,
+    ) -> Either<EngineReorderFcu<Self, Engine>, Self>
+    where
+        Self: Sized,
+    {
+        if let Some(percentage) = percentage {
+            Either::Left(self.reorder_fcu(percentage, seed))
+        } else {
+            Either::Right(self)
+        }
+    }
+
+    /// Delivers [`BeaconEngineMessage::NewPayload`] messages after the next one, i.e. the child
+    /// before its parent, with the specified probability in percent. The decisions are seeded with
+    /// the given seed.
+    fn out_of_order_payloads(
+        self,
+        percentage: u8,
+        seed: u64,
+    ) -> EngineOutOfOrderPayloads<Self, Engine>
+    where
+        Self: Sized,
+    {
+        EngineOutOfOrderPayloads::new(self, percentage, seed)
+    }
+
+    /// If the percentage is [Some], returns the stream that delivers new payloads out of order.
+    /// Otherwise, returns `Self`.
+    fn maybe_out_of_order_payloads(
+        self,
+        percentage: Option<u8>,
+        seed: u64,
+    ) -> Either<EngineOutOfOrderPayloads<Self, Engine>, Self>
+    where
+        Self: Sized,
+    {
+        if let Some(percentage) = percentage {
+            Either::Left(self.out_of_order_payloads(percentage, seed))
+        } else {
+            Either::Right(self)
+        }
+    }
+
+    /// Injects an invalid sibling before [`BeaconEngineMessage::NewPayload`] messages with the
+    /// specified probability in percent. The decisions are seeded with the given seed.
+    fn invalid_payloads(self, percentage: u8, seed: u64) -> EngineInvalidPayload<Self, Engine>
+    where
+        Self: Sized,
+    {
+        EngineInvalidPayload::new(self, percentage, seed)
+    }
+
+    /// If the percentage is [Some], returns the stream that injects invalid payloads. Otherwise,
+    /// returns `Self`.
+    fn maybe_invalid_payloads(
+        self,
+        percentage: Option<u8>,
+        seed: u64,
+    ) -> Either<EngineInvalidPayload<Self, Engine>, Self>
+    where
+        Self: Sized,
+    {
+        if let Some(percentage) = percentage {
+            Either::Left(self.invalid_payloads(percentage, seed))
+        } else {
+            Either::Right(self)
+        }
+    }
 }
 
 impl<Engine, T> EngineMessageStreamExt<Engine> for T
@@ -0,0 +1,110 @@
+//! Stream wrapper that delivers new payloads out of order.
+
+use crate::detach::detach;
+use futures::{Stream, StreamExt};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use reth_beacon_consensus::BeaconEngineMessage;
+use reth_engine_primitives::EngineTypes;
+use std::{
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+/// Engine API stream wrapper that holds back new payload messages with the specified probability,
+/// and delivers them after the next new payload message, so that the child is delivered before
+/// its parent.
+///
+/// The consensus client is immediately responded to with `SYNCING` for held back messages, and the
+/// response of the engine is discarded.
+#[derive(Debug)]
+#[pin_project::pin_project]
+pub struct EngineOutOfOrderPayloads<S, Engine: EngineTypes> {
+    #[pin]
+    stream: S,
+    /// The probability of holding back a new payload, in percent.
+    percentage: u8,
+    /// The source of the reordering decisions.
+    rng: StdRng,
+    /// The new payload that is held back.
+    held: Option<BeaconEngineMessage<Engine>>,
+    /// The message to deliver next.
+    queued: Option<BeaconEngineMessage<Engine>>,
+}
+
+impl<S, Engine: EngineTypes> EngineOutOfOrderPayloads<S, Engine> {
+    /// Creates new [`EngineOutOfOrderPayloads`] stream wrapper, with the decisions seeded with the
+    /// given seed.
+    pub fn new(stream: S, percentage: u8, seed: u64) -> Self {
+        Self {
+            stream,
+            percentage: percentage.min(100),
+            rng: StdRng::seed_from_u64(seed),
+            held: None,
+            queued: None,
+        }
+    }
+}
+
+impl<S, Engine> Stream for EngineOutOfOrderPayloads<S, Engine>
+where
+    S: Stream<Item = BeaconEngineMessage<Engine>>,
+    Engine: EngineTypes,
+{
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        if let Some(msg) = this.queued.take() {
+            return Poll::Ready(Some(msg))
+        }
+
+        loop {
+            let item = match ready!(this.stream.poll_next_unpin(cx)) {
+                Some(msg @ BeaconEngineMessage::NewPayload { .. }) => match this.held.take() {
+                    Some(held) => {
+                        // Deliver the held back parent after its child
+                        *this.queued = Some(held);
+                        Some(msg)
+                    }
+                    None if this.rng.gen_ratio(u32::from(*this.percentage), 100) => {
+                        tracing::warn!(target: "engine::stream::out_of_order", %msg, "Holding back new payload");
+                        *this.held = Some(detach(msg));
+                        continue
+                    }
+                    None => Some(msg),
+                },
+                None => this.held.take(),
+                next => next,
+            };
+            return Poll::Ready(item)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{chain_messages, describe, new_payload, responded_syncing};
+
+    #[tokio::test]
+    async fn fixed_seed_order() {
+        let (messages, mut payload_rxs, _forkchoice_rxs) = chain_messages(4);
+        let stream = EngineOutOfOrderPayloads::new(futures::stream::iter(messages), 50, 2);
+        let delivered = stream.map(|msg| describe(&msg)).collect::<Vec<_>>().await;
+        assert_eq!(delivered, ["fcu1", "np2", "np1", "fcu2", "fcu3", "np4", "np3", "fcu4"]);
+
+        // held back payloads are responded to with `SYNCING`
+        let syncing = payload_rxs.iter_mut().map(responded_syncing).collect::<Vec<_>>();
+        assert_eq!(syncing, [true, false, true, false]);
+    }
+
+    #[tokio::test]
+    async fn flushes_held_payload() {
+        let (msg, mut rx) = new_payload(1);
+        let stream = EngineOutOfOrderPayloads::new(futures::stream::iter([msg]), 100, 0);
+        let delivered = stream.map(|msg| describe(&msg)).collect::<Vec<_>>().await;
+        assert_eq!(delivered, ["np1"]);
+        assert!(responded_syncing(&mut rx));
+    }
+}
@@ -0,0 +1,108 @@
+//! Stream wrapper that delivers FCUs after the next new payload.
+
+use crate::detach::detach;
+use futures::{Stream, StreamExt};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use reth_beacon_consensus::BeaconEngineMessage;
+use reth_engine_primitives::EngineTypes;
+use std::{
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+/// Engine API stream wrapper that holds back forkchoice updated messages with the specified
+/// probability, and delivers them after the next new payload message.
+///
+/// The consensus client is immediately responded to with `SYNCING` for held back messages, and the
+/// response of the engine is discarded.
+#[derive(Debug)]
+#[pin_project::pin_project]
+pub struct EngineReorderFcu<S, Engine: EngineTypes> {
+    #[pin]
+    stream: S,
+    /// The probability of holding back an FCU, in percent.
+    percentage: u8,
+    /// The source of the reordering decisions.
+    rng: StdRng,
+    /// The FCU that is held back.
+    held: Option<BeaconEngineMessage<Engine>>,
+    /// The message to deliver next.
+    queued: Option<BeaconEngineMessage<Engine>>,
+}
+
+impl<S, Engine: EngineTypes> EngineReorderFcu<S, Engine> {
+    /// Creates new [`EngineReorderFcu`] stream wrapper, with the decisions seeded with the given
+    /// seed.
+    pub fn new(stream: S, percentage: u8, seed: u64) -> Self {
+        Self {
+            stream,
+            percentage: percentage.min(100),
+            rng: StdRng::seed_from_u64(seed),
+            held: None,
+            queued: None,
+        }
+    }
+}
+
+impl<S, Engine> Stream for EngineReorderFcu<S, Engine>
+where
+    S: Stream<Item = BeaconEngineMessage<Engine>>,
+    Engine: EngineTypes,
+{
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        if let Some(msg) = this.queued.take() {
+            return Poll::Ready(Some(msg))
+        }
+
+        loop {
+            let item = match ready!(this.stream.poll_next_unpin(cx)) {
+                Some(msg @ BeaconEngineMessage::ForkchoiceUpdated { .. })
+                    if this.held.is_none() &&
+                        this.rng.gen_ratio(u32::from(*this.percentage), 100) =>
+                {
+                    tracing::warn!(target: "engine::stream::reorder_fcu", %msg, "Holding back FCU");
+                    *this.held = Some(detach(msg));
+                    continue
+                }
+                Some(msg @ BeaconEngineMessage::NewPayload { .. }) => {
+                    // Deliver the held back FCU after the new payload
+                    *this.queued = this.held.take();
+                    Some(msg)
+                }
+                Some(msg) => match this.held.take() {
+                    Some(held) => {
+                        *this.queued = Some(msg);
+                        Some(held)
+                    }
+                    None => Some(msg),
+                },
+                None => this.held.take(),
+            };
+            return Poll::Ready(item)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{chain_messages, describe, forkchoice_responded_syncing};
+
+    #[tokio::test]
+    async fn fixed_seed_order() {
+        let (messages, _payload_rxs, mut forkchoice_rxs) = chain_messages(4);
+        let stream = EngineReorderFcu::new(futures::stream::iter(messages), 50, 1);
+        let delivered = stream.map(|msg| describe(&msg)).collect::<Vec<_>>().await;
+        // the last FCU is still held back when the stream ends, and is delivered before it ends
+        assert_eq!(delivered, ["np1", "fcu1", "np2", "fcu2", "np3", "np4", "fcu3", "fcu4"]);
+
+        // held back FCUs are responded to with `SYNCING`
+        let syncing =
+            forkchoice_rxs.iter_mut().map(forkchoice_responded_syncing).collect::<Vec<_>>();
+        assert_eq!(syncing, [false, false, true, true]);
+    }
+}
@@ -0,0 +1,228 @@
+//! Deterministic replay of stored engine API messages.
+
+use crate::engine_store::{EngineMessageStore, StoredEngineApiMessage, StoredEngineApiResponse};
+use eyre::Context;
+use reth_beacon_consensus::BeaconEngineMessage;
+use reth_engine_primitives::EngineTypes;
+use std::path::PathBuf;
+use tokio::sync::{mpsc::UnboundedSender, oneshot};
+use tracing::*;
+
+/// The first replayed engine API message with a different outcome than the recorded one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EngineReplayDivergence {
+    /// Position of the message in the replayed messages.
+    pub index: usize,
+    /// Path of the stored message.
+    pub path: PathBuf,
+    /// The recorded response.
+    pub recorded: StoredEngineApiResponse,
+    /// The response of the replay.
+    pub replayed: StoredEngineApiResponse,
+}
+
+/// Outcome of an engine API replay.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EngineReplayReport {
+    /// Number of replayed messages.
+    pub replayed: usize,
+    /// Number of replayed messages that had a recorded response to compare with.
+    pub compared: usize,
+    /// The first divergence from the recorded responses, if any. The replay stops there.
+    pub divergence: Option<EngineReplayDivergence>,
+}
+
+/// Replays the messages of an [`EngineMessageStore`] to an engine and compares the responses with
+/// the recorded ones.
+///
+/// Messages are sent one at a time, and the next message is only sent once the response to the
+/// previous one was received, so that the outcome doesn't depend on timing.
+#[derive(Debug)]
+pub struct EngineReplay<Engine: EngineTypes> {
+    /// The store to read messages and recorded responses from.
+    store: EngineMessageStore,
+    /// Sender of messages to the engine.
+    to_engine: UnboundedSender<BeaconEngineMessage<Engine>>,
+}
+
+impl<Engine: EngineTypes> EngineReplay<Engine> {
+    /// Creates a new replay of the messages stored at the given path.
+    pub const fn new(
+        path: PathBuf,
+        to_engine: UnboundedSender<BeaconEngineMessage<Engine>>,
+    ) -> Self {
+        Self { store: EngineMessageStore::new(path), to_engine }
+    }
+
+    /// Replays all stored messages until the first divergence from the recorded responses.
+    pub async fn run(self) -> eyre::Result<EngineReplayReport> {
+        let mut report = EngineReplayReport::default();
+        for (index, path) in self.store.engine_messages_iter()?.enumerate() {
+            let message = self
+                .store
+                .read_message::<Engine::PayloadAttributes>(&path)
+                .wrap_err_with(|| format!("failed to read: {}", path.display()))?;
+            debug!(target: "engine::replay", index, path = %path.display(), "Replaying Engine API message");
+
+            let replayed = match message {
+                StoredEngineApiMessage::ForkchoiceUpdated { state, payload_attrs } => {
+                    let (tx, rx) = oneshot::channel();
+                    self.send(BeaconEngineMessage::ForkchoiceUpdated { state, payload_attrs, tx })?;
+                    StoredEngineApiResponse::forkchoice_updated(&rx.await?)
+                }
+                StoredEngineApiMessage::NewPayload { payload, cancun_fields } => {
+                    let (tx, rx) = oneshot::channel();
+                    self.send(BeaconEngineMessage::NewPayload { payload, cancun_fields, tx })?;
+                    StoredEngineApiResponse::new_payload(&rx.await?)
+                }
+            };
+            report.replayed += 1;
+
+            let Some(recorded) = self
+                .store
+                .read_response(&path)
+                .wrap_err_with(|| format!("failed to read response: {}", path.display()))?
+            else {
+                trace!(target: "engine::replay", index, ?replayed, "No recorded response");
+                continue
+            };
+            report.compared += 1;
+
+            if !recorded.same_outcome(&replayed) {
+                warn!(target: "engine::replay", index, path = %path.display(), ?recorded, ?replayed, "Replayed response diverged");
+                report.divergence =
+                    Some(EngineReplayDivergence { index, path, recorded, replayed });
+                break
+            }
+        }
+
+        Ok(report)
+    }
+
+    fn send(&self, msg: BeaconEngineMessage<Engine>) -> eyre::Result<()> {
+        self.to_engine.send(msg).map_err(|_| eyre::eyre!("engine channel closed"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        engine_store::EngineStoreStream,
+        test_utils::{forkchoice_updated, new_payload},
+    };
+    use alloy_primitives::B256;
+    use alloy_rpc_types_engine::{PayloadStatus, PayloadStatusEnum};
+    use futures::StreamExt;
+    use reth_beacon_consensus::{ForkchoiceStatus, OnForkChoiceUpdated};
+    use reth_ethereum_engine_primitives::EthEngineTypes;
+    use std::time::{Duration, SystemTime};
+    use tokio::{sync::mpsc::unbounded_channel, task::JoinHandle};
+
+    /// Spawns a mock engine that responds to the n-th message with the n-th status, and returns
+    /// the number of received messages once the sender is dropped.
+    fn spawn_engine(
+        statuses: Vec<PayloadStatus>,
+    ) -> (UnboundedSender<BeaconEngineMessage<EthEngineTypes>>, JoinHandle<usize>) {
+        let (to_engine, mut from_replay) = unbounded_channel();
+        let engine = tokio::spawn(async move {
+            let mut received = 0;
+            while let Some(msg) = from_replay.recv().await {
+                let status = statuses[received].clone();
+                received += 1;
+                match msg {
+                    BeaconEngineMessage::NewPayload { tx, .. } => {
+                        let _ = tx.send(Ok(status));
+                    }
+                    BeaconEngineMessage::ForkchoiceUpdated { tx, .. } => {
+                        let _ = tx.send(Ok(OnForkChoiceUpdated::valid(status)));
+                    }
+                    BeaconEngineMessage::TransitionConfigurationExchanged => {}
+                }
+            }
+            received
+        });
+        (to_engine, engine)
+    }
+
+    #[tokio::test]
+    async fn store_and_replay() {
+        let dir = tempfile::tempdir().unwrap();
+        let (to_store, messages) = futures::channel::mpsc::unbounded();
+        let mut stream = EngineStoreStream::new(messages, dir.path().to_path_buf());
+        let valid = PayloadStatus::new(PayloadStatusEnum::Valid, Some(B256::with_last_byte(1)));
+
+        let (msg, payload_rx) = new_payload(1);
+        to_store.unbounded_send(msg).unwrap();
+        let Some(BeaconEngineMessage::NewPayload { tx, .. }) = stream.next().await else {
+            panic!("expected new payload")
+        };
+        tx.send(Ok(valid.clone())).unwrap();
+
+        // stored messages are ordered by their millisecond timestamps
+        std::thread::sleep(Duration::from_millis(2));
+        let (msg, forkchoice_rx) = forkchoice_updated(1);
+        to_store.unbounded_send(msg).unwrap();
+        let Some(BeaconEngineMessage::ForkchoiceUpdated { tx, .. }) = stream.next().await else {
+            panic!("expected forkchoice updated")
+        };
+        tx.send(Ok(OnForkChoiceUpdated::valid(valid.clone()))).unwrap();
+
+        // the stream only ends once the in-flight responses are stored and forwarded
+        drop(to_store);
+        assert!(stream.next().await.is_none());
+        assert_eq!(payload_rx.await.unwrap().unwrap(), valid);
+        assert_eq!(
+            forkchoice_rx.await.unwrap().unwrap().forkchoice_status(),
+            ForkchoiceStatus::Valid
+        );
+
+        let (to_engine, engine) = spawn_engine(vec![valid.clone(), valid]);
+        let report = EngineReplay::<EthEngineTypes>::new(dir.path().to_path_buf(), to_engine)
+            .run()
+            .await
+            .unwrap();
+        assert_eq!(report, EngineReplayReport { replayed: 2, compared: 2, divergence: None });
+        assert_eq!(engine.await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn replay_stops_at_first_divergence() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = EngineMessageStore::new(dir.path().to_path_buf());
+        let valid = PayloadStatus::new(PayloadStatusEnum::Valid, Some(B256::with_last_byte(1)));
+        let recorded = StoredEngineApiResponse::NewPayload { status: Ok(valid.clone()) };
+
+        let mut paths = Vec::new();
+        for millis in 0..3 {
+            let (msg, _rx) = new_payload(millis + 1);
+            let received_at = SystemTime::UNIX_EPOCH + Duration::from_millis(millis);
+            store.on_message(&msg, received_at).unwrap();
+            let path = store.message_path(&msg, received_at).unwrap();
+            store.on_response(&path, &recorded).unwrap();
+            paths.push(path);
+        }
+
+        let syncing = PayloadStatus::from_status(PayloadStatusEnum::Syncing);
+        let (to_engine, engine) = spawn_engine(vec![valid.clone(), syncing.clone(), valid]);
+        let report = EngineReplay::<EthEngineTypes>::new(dir.path().to_path_buf(), to_engine)
+            .run()
+            .await
+            .unwrap();
+        assert_eq!(
+            report,
+            EngineReplayReport {
+                replayed: 2,
+                compared: 2,
+                divergence: Some(EngineReplayDivergence {
+                    index: 1,
+                    path: paths[1].clone(),
+                    recorded,
+                    replayed: StoredEngineApiResponse::NewPayload { status: Ok(syncing) },
+                }),
+            }
+        );
+        // the message after the divergence isn't sent
+        assert_eq!(engine.await.unwrap(), 2);
+    }
+}
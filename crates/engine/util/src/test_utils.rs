@@ -0,0 +1,81 @@
+//! Helpers for testing the engine API stream wrappers.
+
+use alloy_primitives::B256;
+use alloy_rpc_types_engine::{ForkchoiceState, PayloadStatus, PayloadStatusEnum};
+use reth_beacon_consensus::{
+    BeaconEngineMessage, BeaconOnNewPayloadError, ForkchoiceStatus, OnForkChoiceUpdated,
+};
+use reth_errors::RethResult;
+use reth_ethereum_engine_primitives::EthEngineTypes;
+use reth_primitives::{Block, Header};
+use reth_rpc_types_compat::engine::payload::block_to_payload;
+use tokio::sync::oneshot;
+
+/// Receiver of the response to a new payload message.
+pub(crate) type NewPayloadRx = oneshot::Receiver<Result<PayloadStatus, BeaconOnNewPayloadError>>;
+
+/// Receiver of the response to a forkchoice updated message.
+pub(crate) type ForkchoiceUpdatedRx = oneshot::Receiver<RethResult<OnForkChoiceUpdated>>;
+
+/// Returns the new payload message of an empty block with the given number.
+pub(crate) fn new_payload(number: u64) -> (BeaconEngineMessage<EthEngineTypes>, NewPayloadRx) {
+    let block = Block { header: Header { number, ..Default::default() }, body: Default::default() };
+    let (tx, rx) = oneshot::channel();
+    let payload = block_to_payload(block.seal_slow());
+    (BeaconEngineMessage::NewPayload { payload, cancun_fields: None, tx }, rx)
+}
+
+/// Returns the forkchoice updated message with the head hash ending with the given number.
+pub(crate) fn forkchoice_updated(
+    number: u8,
+) -> (BeaconEngineMessage<EthEngineTypes>, ForkchoiceUpdatedRx) {
+    let state =
+        ForkchoiceState { head_block_hash: B256::with_last_byte(number), ..Default::default() };
+    let (tx, rx) = oneshot::channel();
+    (BeaconEngineMessage::ForkchoiceUpdated { state, payload_attrs: None, tx }, rx)
+}
+
+/// Returns the new payload and forkchoice updated messages of the blocks `1..=count`, and the
+/// receivers of their responses.
+pub(crate) fn chain_messages(
+    count: u8,
+) -> (Vec<BeaconEngineMessage<EthEngineTypes>>, Vec<NewPayloadRx>, Vec<ForkchoiceUpdatedRx>) {
+    let mut messages = Vec::new();
+    let mut payload_rxs = Vec::new();
+    let mut forkchoice_rxs = Vec::new();
+    for number in 1..=count {
+        let (msg, rx) = new_payload(number.into());
+        messages.push(msg);
+        payload_rxs.push(rx);
+        let (msg, rx) = forkchoice_updated(number);
+        messages.push(msg);
+        forkchoice_rxs.push(rx);
+    }
+    (messages, payload_rxs, forkchoice_rxs)
+}
+
+/// Returns a short description of the message, `np<number>` for new payloads and `fcu<number>`
+/// for forkchoice updates.
+pub(crate) fn describe(msg: &BeaconEngineMessage<EthEngineTypes>) -> String {
+    match msg {
+        BeaconEngineMessage::NewPayload { payload, .. } => format!("np{}", payload.block_number()),
+        BeaconEngineMessage::ForkchoiceUpdated { state, .. } => {
+            format!("fcu{}", state.head_block_hash[31])
+        }
+        BeaconEngineMessage::TransitionConfigurationExchanged => "transition".to_string(),
+    }
+}
+
+/// Returns `true` if the consensus client was responded to with `SYNCING`.
+pub(crate) fn responded_syncing(rx: &mut NewPayloadRx) -> bool {
+    rx.try_recv().is_ok_and(|response| {
+        response.is_ok_and(|status| status.status == PayloadStatusEnum::Syncing)
+    })
+}
+
+/// Returns `true` if the consensus client was responded to with a `SYNCING` forkchoice status.
+pub(crate) fn forkchoice_responded_syncing(rx: &mut ForkchoiceUpdatedRx) -> bool {
+    rx.try_recv().is_ok_and(|response| {
+        response.is_ok_and(|response| response.forkchoice_status() == ForkchoiceStatus::Syncing)
+    })
+}
@@ -0,0 +1,117 @@
+#![allow(missing_docs)]
+
+use alloy_consensus::TxLegacy;
+use alloy_primitives::{keccak256, Address, Bytes, TxKind, U256};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::Rng;
+use reth_chainspec::{ChainSpec, ChainSpecBuilder, MAINNET};
+use reth_evm::execute::{BlockExecutorProvider, Executor};
+use reth_evm_ethereum::{execute::EthExecutorProvider, EthEvmConfig};
+use reth_primitives::{
+    constants::ETH_TO_WEI, public_key_to_address, Account, Block, BlockBody, BlockWithSenders,
+    Transaction,
+};
+use reth_revm::{database::StateProviderDatabase, test_utils::StateProviderTest};
+use reth_testing_utils::generators::{self, sign_tx_with_key_pair};
+use std::{num::NonZeroUsize, sync::Arc};
+
+/// Number of senders in the block, each sending the same number of transactions.
+const SENDERS: usize = 200;
+/// Number of transactions of each sender.
+const TRANSACTIONS_PER_SENDER: u64 = 5;
+/// Gas limit of each transaction.
+const GAS_LIMIT: u64 = 100_000;
+
+/// Returns the state and a block in which every sender alternates between transfers to new
+/// accounts and deposits into a contract that stores the deposited value in the slot of the
+/// sender, so that only the transactions of the same sender depend on each other.
+fn independent_senders_block(chain_spec: &ChainSpec) -> (StateProviderTest, BlockWithSenders) {
+    let mut rng = generators::rng();
+    let key_pairs = generators::generate_keys(&mut rng, SENDERS);
+
+    // stores the call value in the slot of the caller
+    let deposits = Address::repeat_byte(0x02);
+    let deposits_code = Bytes::from_static(&[0x34, 0x33, 0x55, 0x00]);
+
+    let mut db = StateProviderTest::default();
+    for key_pair in &key_pairs {
+        db.insert_account(
+            public_key_to_address(key_pair.public_key()),
+            Account { nonce: 0, balance: U256::from(ETH_TO_WEI), bytecode_hash: None },
+            None,
+            Default::default(),
+        );
+    }
+    db.insert_account(
+        deposits,
+        Account { nonce: 1, balance: U256::ZERO, bytecode_hash: Some(keccak256(&deposits_code)) },
+        Some(deposits_code),
+        Default::default(),
+    );
+
+    let mut header = chain_spec.genesis_header().clone();
+    header.gas_limit = SENDERS as u64 * TRANSACTIONS_PER_SENDER * GAS_LIMIT;
+    let base_fee = header.base_fee_per_gas.unwrap();
+
+    let transactions = (0..TRANSACTIONS_PER_SENDER)
+        .flat_map(|nonce| key_pairs.iter().map(move |key_pair| (nonce, *key_pair)))
+        .map(|(nonce, key_pair)| {
+            let to = if nonce % 2 == 0 { Address::from(rng.gen::<[u8; 20]>()) } else { deposits };
+            sign_tx_with_key_pair(
+                key_pair,
+                Transaction::Legacy(TxLegacy {
+                    chain_id: Some(chain_spec.chain.id()),
+                    nonce,
+                    gas_price: u128::from(base_fee),
+                    gas_limit: GAS_LIMIT,
+                    to: TxKind::Call(to),
+                    value: U256::from(rng.gen_range(1..1_000u64)),
+                    input: Bytes::new(),
+                }),
+            )
+        })
+        .collect();
+    let block = Block { header, body: BlockBody { transactions, ..Default::default() } }
+        .with_recovered_senders()
+        .unwrap();
+
+    (db, block)
+}
+
+/// Compares the sequential execution of a block with its parallel execution.
+///
+/// The state is held in memory, so the benchmark measures the speculative execution and its
+/// overhead, not reads of the database overlapping with execution.
+fn parallel_execution(c: &mut Criterion) {
+    let chain_spec = Arc::new(ChainSpecBuilder::from(&*MAINNET).shanghai_activated().build());
+    let (db, block) = independent_senders_block(&chain_spec);
+    let executor_provider =
+        EthExecutorProvider::new(chain_spec.clone(), EthEvmConfig::new(chain_spec));
+
+    let mut group = c.benchmark_group("Block execution");
+    group.sample_size(20);
+    group.bench_function("sequential", |b| {
+        b.iter(|| {
+            executor_provider
+                .executor(StateProviderDatabase::new(&db))
+                .execute((&block, U256::ZERO).into())
+                .unwrap()
+        })
+    });
+    for workers in [2, 4, 8] {
+        let executor_provider =
+            executor_provider.clone().with_parallel_execution(NonZeroUsize::new(workers).unwrap());
+        group.bench_function(BenchmarkId::new("parallel", workers), |b| {
+            b.iter(|| {
+                executor_provider
+                    .executor(StateProviderDatabase::new(&db))
+                    .execute((&block, U256::ZERO).into())
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parallel_execution);
+criterion_main!(benches);
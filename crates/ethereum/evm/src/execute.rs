@@ -2,11 +2,12 @@
 
 use crate::{
     dao_fork::{DAO_HARDFORK_BENEFICIARY, DAO_HARDKFORK_ACCOUNTS},
+    parallel::SpeculativeExecution,
     EthEvmConfig,
 };
 use alloc::{boxed::Box, sync::Arc, vec, vec::Vec};
 use alloy_primitives::{BlockNumber, U256};
-use core::fmt::Display;
+use core::{fmt::Display, num::NonZeroUsize};
 use reth_chainspec::{ChainSpec, EthereumHardforks, MAINNET};
 use reth_ethereum_consensus::validate_block_post_execution;
 use reth_evm::{
@@ -36,6 +37,8 @@ use revm_primitives::{
 pub struct EthExecutorProvider<EvmConfig = EthEvmConfig> {
     chain_spec: Arc<ChainSpec>,
     evm_config: EvmConfig,
+    /// Number of workers for the parallel execution of transactions, if enabled.
+    parallel_workers: Option<NonZeroUsize>,
 }
 
 impl EthExecutorProvider {
@@ -53,7 +56,18 @@ impl EthExecutorProvider {
 impl<EvmConfig> EthExecutorProvider<EvmConfig> {
     /// Creates a new executor provider.
     pub const fn new(chain_spec: Arc<ChainSpec>, evm_config: EvmConfig) -> Self {
-        Self { chain_spec, evm_config }
+        Self { chain_spec, evm_config, parallel_workers: None }
+    }
+
+    /// Enables optimistic parallel execution of the transactions of a block on the given number of
+    /// workers.
+    ///
+    /// Transactions are executed speculatively in parallel and committed in order, transactions
+    /// that conflict with the preceding ones are executed again. The outcome is the same as with
+    /// sequential execution.
+    pub const fn with_parallel_execution(mut self, workers: NonZeroUsize) -> Self {
+        self.parallel_workers = Some(workers);
+        self
     }
 }
 
@@ -65,11 +79,15 @@ where
     where
         DB: Database<Error: Into<ProviderError>>,
     {
-        EthBlockExecutor::new(
+        let executor = EthBlockExecutor::new(
             self.chain_spec.clone(),
             self.evm_config.clone(),
             State::builder().with_database(db).with_bundle_update().without_state_clear().build(),
-        )
+        );
+        match self.parallel_workers {
+            Some(workers) => executor.with_parallel_execution(workers),
+            None => executor,
+        }
     }
 }
 
@@ -114,6 +132,8 @@ struct EthEvmExecutor<EvmConfig> {
     chain_spec: Arc<ChainSpec>,
     /// How to create an EVM.
     evm_config: EvmConfig,
+    /// Number of workers for the parallel execution of transactions, if enabled.
+    parallel_workers: Option<NonZeroUsize>,
 }
 
 impl<EvmConfig> EthEvmExecutor<EvmConfig>
@@ -148,10 +168,16 @@ where
 
         system_caller.apply_pre_execution_changes(block, &mut evm)?;
 
+        // speculatively execute transactions in parallel, if enabled
+        let mut speculative = self
+            .parallel_workers
+            .filter(|_| block.body.transactions.len() > 1)
+            .map(|workers| SpeculativeExecution::run(&self.evm_config, block, &mut evm, workers));
+
         // execute transactions
         let mut cumulative_gas_used = 0;
         let mut receipts = Vec::with_capacity(block.body.transactions.len());
-        for (sender, transaction) in block.transactions_with_sender() {
+        for (index, (sender, transaction)) in block.transactions_with_sender().enumerate() {
             // The sum of the transaction’s gas limit, Tg, and the gas utilized in this block prior,
             // must be no greater than the block’s gasLimit.
             let block_available_gas = block.header.gas_limit - cumulative_gas_used;
@@ -163,17 +189,26 @@ where
                 .into())
             }
 
-            self.evm_config.fill_tx_env(evm.tx_mut(), transaction, *sender);
-
-            // Execute transaction.
-            let result_and_state = evm.transact().map_err(move |err| {
-                let new_err = err.map_db_err(|e| e.into());
-                // Ensure hash is calculated for error log, if not already done
-                BlockValidationError::EVM {
-                    hash: transaction.recalculate_hash(),
-                    error: Box::new(new_err),
+            // Use the speculative result if it doesn't conflict with the preceding transactions.
+            let speculative_result = speculative
+                .as_mut()
+                .and_then(|speculative| speculative.take_valid(index, evm.db_mut()));
+            let result_and_state = match speculative_result {
+                Some(result_and_state) => result_and_state,
+                None => {
+                    self.evm_config.fill_tx_env(evm.tx_mut(), transaction, *sender);
+
+                    // Execute transaction.
+                    evm.transact().map_err(move |err| {
+                        let new_err = err.map_db_err(|e| e.into());
+                        // Ensure hash is calculated for error log, if not already done
+                        BlockValidationError::EVM {
+                            hash: transaction.recalculate_hash(),
+                            error: Box::new(new_err),
+                        }
+                    })?
                 }
-            })?;
+            };
             system_caller.on_state(&result_and_state);
             let ResultAndState { result, state } = result_and_state;
             evm.db_mut().commit(state);
@@ -229,7 +264,13 @@ pub struct EthBlockExecutor<EvmConfig, DB> {
 impl<EvmConfig, DB> EthBlockExecutor<EvmConfig, DB> {
     /// Creates a new Ethereum block executor.
     pub const fn new(chain_spec: Arc<ChainSpec>, evm_config: EvmConfig, state: State<DB>) -> Self {
-        Self { executor: EthEvmExecutor { chain_spec, evm_config }, state }
+        Self { executor: EthEvmExecutor { chain_spec, evm_config, parallel_workers: None }, state }
+    }
+
+    /// Enables optimistic parallel execution of the transactions on the given number of workers.
+    pub const fn with_parallel_execution(mut self, workers: NonZeroUsize) -> Self {
+        self.executor.parallel_workers = Some(workers);
+        self
     }
 
     #[inline]
@@ -497,7 +538,8 @@ mod tests {
         eip4788::{BEACON_ROOTS_ADDRESS, BEACON_ROOTS_CODE, SYSTEM_ADDRESS},
         eip7002::{WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS, WITHDRAWAL_REQUEST_PREDEPLOY_CODE},
     };
-    use alloy_primitives::{b256, fixed_bytes, keccak256, Bytes, TxKind, B256};
+    use alloy_primitives::{b256, fixed_bytes, keccak256, Address, Bytes, TxKind, B256};
+    use rand::Rng;
     use reth_chainspec::{ChainSpecBuilder, ForkCondition};
     use reth_primitives::{
         constants::{EMPTY_ROOT_HASH, ETH_TO_WEI},
@@ -550,7 +592,7 @@ mod tests {
     }
 
     fn executor_provider(chain_spec: Arc<ChainSpec>) -> EthExecutorProvider<EthEvmConfig> {
-        EthExecutorProvider { evm_config: EthEvmConfig::new(chain_spec.clone()), chain_spec }
+        EthExecutorProvider::new(chain_spec.clone(), EthEvmConfig::new(chain_spec))
     }
 
     #[test]
@@ -1390,4 +1432,104 @@ mod tests {
             ),
         }
     }
+
+    #[test]
+    fn parallel_execution_matches_sequential() {
+        let chain_spec = Arc::new(ChainSpecBuilder::from(&*MAINNET).shanghai_activated().build());
+
+        let mut rng = generators::rng();
+        let key_pairs = generators::generate_keys(&mut rng, 4);
+        let senders = key_pairs
+            .iter()
+            .map(|key_pair| public_key_to_address(key_pair.public_key()))
+            .collect::<Vec<_>>();
+        let coinbase = Address::repeat_byte(0xcc);
+
+        // increments slot 0
+        let counter = Address::repeat_byte(0x01);
+        let counter_code =
+            Bytes::from_static(&[0x60, 0x00, 0x54, 0x60, 0x01, 0x01, 0x60, 0x00, 0x55, 0x00]);
+        // stores the call value in the slot of the caller
+        let deposits = Address::repeat_byte(0x02);
+        let deposits_code = Bytes::from_static(&[0x34, 0x33, 0x55, 0x00]);
+        // stores the block beneficiary in slot 0
+        let beneficiary = Address::repeat_byte(0x03);
+        let beneficiary_code = Bytes::from_static(&[0x41, 0x60, 0x00, 0x55, 0x00]);
+
+        let mut db = StateProviderTest::default();
+        for sender in &senders {
+            db.insert_account(
+                *sender,
+                Account { nonce: 0, balance: U256::from(ETH_TO_WEI), bytecode_hash: None },
+                None,
+                HashMap::default(),
+            );
+        }
+        for (address, code) in
+            [(counter, counter_code), (deposits, deposits_code), (beneficiary, beneficiary_code)]
+        {
+            db.insert_account(
+                address,
+                Account { nonce: 1, balance: U256::ZERO, bytecode_hash: Some(keccak256(&code)) },
+                Some(code),
+                HashMap::default(),
+            );
+        }
+
+        let mut header = chain_spec.genesis_header().clone();
+        header.gas_limit = 30_000_000;
+        header.beneficiary = coinbase;
+        let base_fee = header.base_fee_per_gas.unwrap();
+
+        for _ in 0..10 {
+            // dependent transfers, contract storage writes and beneficiary accesses
+            let mut nonces = vec![0; senders.len()];
+            let transactions = (0..20)
+                .map(|_| {
+                    let sender = rng.gen_range(0..senders.len());
+                    let (to, value) = match rng.gen_range(0..5) {
+                        0 => (
+                            senders[rng.gen_range(0..senders.len())],
+                            U256::from(rng.gen_range(1..1_000_000u64)),
+                        ),
+                        1 => (coinbase, U256::from(1)),
+                        2 => (counter, U256::ZERO),
+                        3 => (deposits, U256::from(rng.gen_range(1..1_000u64))),
+                        _ => (beneficiary, U256::ZERO),
+                    };
+                    let nonce = nonces[sender];
+                    nonces[sender] += 1;
+                    sign_tx_with_key_pair(
+                        key_pairs[sender],
+                        Transaction::Legacy(TxLegacy {
+                            chain_id: Some(chain_spec.chain.id()),
+                            nonce,
+                            gas_price: u128::from(base_fee) + rng.gen_range(0..10),
+                            gas_limit: 100_000,
+                            to: TxKind::Call(to),
+                            value,
+                            input: Bytes::new(),
+                        }),
+                    )
+                })
+                .collect();
+            let block = Block {
+                header: header.clone(),
+                body: BlockBody { transactions, ..Default::default() },
+            }
+            .with_recovered_senders()
+            .unwrap();
+
+            let sequential = executor_provider(chain_spec.clone())
+                .executor(StateProviderDatabase::new(&db))
+                .execute((&block, U256::ZERO).into())
+                .unwrap();
+            let parallel = executor_provider(chain_spec.clone())
+                .with_parallel_execution(NonZeroUsize::new(4).unwrap())
+                .executor(StateProviderDatabase::new(&db))
+                .execute((&block, U256::ZERO).into())
+                .unwrap();
+            assert_eq!(parallel, sequential);
+        }
+    }
 }
@@ -27,6 +27,7 @@ use reth_ethereum_forks::EthereumHardfork;
 use reth_primitives::constants::EIP1559_INITIAL_BASE_FEE;
 
 pub mod execute;
+mod parallel;
 
 /// Ethereum DAO hardfork state change data.
 pub mod dao_fork;
@@ -0,0 +1,423 @@
+//! Optimistic parallel execution of the transactions of a block.
+//!
+//! All transactions of the block are first executed speculatively on worker threads against a
+//! multi-version memory that holds the writes of the other speculative executions, each execution
+//! recording the values it read. The transactions are then committed in order, and a speculative
+//! result is only used if all of its reads still match the state at that point of the block,
+//! otherwise the transaction is executed again on top of the committed state.
+//!
+//! The base state, i.e. the state at the start of the block, is owned by the calling thread, which
+//! serves the reads of the workers. Every value of the base state is only read once, the workers
+//! share the read values.
+//!
+//! Since all speculative executions would conflict on the block beneficiary, they are credited
+//! their fees to a placeholder beneficiary instead, and the fees are moved to the actual
+//! beneficiary on commit. Transactions that observe the beneficiary are always executed again.
+
+use alloc::collections::BTreeMap;
+use alloy_primitives::{keccak256, map::HashMap, Address, B256, U256};
+use core::num::NonZeroUsize;
+use reth_evm::ConfigureEvm;
+use reth_primitives::{BlockWithSenders, Header};
+use reth_revm::{
+    db::State,
+    interpreter::{opcode, Interpreter},
+    Evm, EvmContext, Inspector,
+};
+use revm_primitives::{
+    db::Database, Account, AccountInfo, Bytecode, EnvWithHandlerCfg, EvmState, ResultAndState,
+};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, PoisonError, RwLock,
+    },
+    thread,
+};
+
+/// Results of the speculative execution of the transactions of a block.
+#[derive(Debug)]
+pub(crate) struct SpeculativeExecution {
+    /// Speculative results by transaction index, `None` if the execution failed or the result was
+    /// already taken.
+    results: Vec<Option<SpeculativeResult>>,
+    /// The actual block beneficiary.
+    coinbase: Address,
+    /// The placeholder beneficiary the speculative executions were credited their fees to.
+    beneficiary: Address,
+}
+
+impl SpeculativeExecution {
+    /// Speculatively executes all transactions of the block on up to `workers` threads.
+    ///
+    /// The workers read the state that isn't written by other transactions through the given
+    /// [EVM](Evm), whose state is expected to be at the start of the transaction execution.
+    pub(crate) fn run<EvmConfig, Ext, DB>(
+        evm_config: &EvmConfig,
+        block: &BlockWithSenders,
+        evm: &mut Evm<'_, Ext, &mut State<DB>>,
+        workers: NonZeroUsize,
+    ) -> Self
+    where
+        EvmConfig: ConfigureEvm<Header = Header>,
+        DB: Database,
+    {
+        let coinbase = evm.context.evm.env.block.coinbase;
+        let beneficiary = Address::from_slice(&keccak256(block.header.hash_slow())[12..]);
+
+        let mut env = evm.context.evm.env.clone();
+        env.block.coinbase = beneficiary;
+        let env = EnvWithHandlerCfg::new_with_spec_id(env, evm.spec_id());
+
+        let transactions = block.body.transactions.len();
+        let memory = MvMemory::default();
+        let base_values = BaseValues::default();
+        let next = AtomicUsize::new(0);
+        let (request_tx, request_rx) = mpsc::channel::<BaseReadRequest>();
+
+        let mut results: Vec<Option<SpeculativeResult>> = (0..transactions).map(|_| None).collect();
+        let executed = thread::scope(|scope| {
+            let handles = (0..workers.get().min(transactions))
+                .map(|_| {
+                    let request_tx = request_tx.clone();
+                    let (env, memory, base_values, next) = (&env, &memory, &base_values, &next);
+                    scope.spawn(move || {
+                        let (reply_tx, reply_rx) = mpsc::channel();
+                        let base =
+                            BaseReader { values: base_values, request_tx, reply_tx, reply_rx };
+                        let mut executed = Vec::new();
+                        loop {
+                            let index = next.fetch_add(1, Ordering::Relaxed);
+                            let (Some(transaction), Some(sender)) =
+                                (block.body.transactions.get(index), block.senders.get(index))
+                            else {
+                                break
+                            };
+
+                            let mut db = SpeculativeDb {
+                                index,
+                                beneficiary,
+                                memory,
+                                base: &base,
+                                reads: Vec::new(),
+                            };
+                            let mut evm = evm_config.evm_with_env_and_inspector(
+                                &mut db,
+                                env.clone(),
+                                CoinbaseInspector::default(),
+                            );
+                            evm_config.fill_tx_env(evm.tx_mut(), transaction, *sender);
+                            let result = evm.transact();
+                            let uses_coinbase = evm.context.external.used;
+                            drop(evm);
+
+                            if let Ok(result_and_state) = result {
+                                memory.insert(index, beneficiary, &result_and_state.state);
+                                executed.push((
+                                    index,
+                                    SpeculativeResult {
+                                        result_and_state,
+                                        reads: db.reads,
+                                        uses_coinbase,
+                                    },
+                                ));
+                            }
+                        }
+                        executed
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            // serve the reads of the base state until all workers are done
+            drop(request_tx);
+            while let Ok((read, reply_tx)) = request_rx.recv() {
+                let _ = reply_tx.send(read.execute(evm.db_mut()).ok());
+            }
+
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap_or_default())
+                .collect::<Vec<_>>()
+        });
+        for (index, result) in executed {
+            results[index] = Some(result);
+        }
+
+        Self { results, coinbase, beneficiary }
+    }
+
+    /// Takes the speculative result of the transaction at the given index if it is valid on top of
+    /// the given state, crediting the fees to the actual beneficiary.
+    ///
+    /// Returns `None` if the transaction has to be executed again.
+    pub(crate) fn take_valid<DB: Database>(
+        &mut self,
+        index: usize,
+        state: &mut State<DB>,
+    ) -> Option<ResultAndState> {
+        let speculative = self.results.get_mut(index)?.take()?;
+        if speculative.uses_coinbase {
+            return None
+        }
+
+        for read in &speculative.reads {
+            if read.address() == Some(self.coinbase) || !read.is_valid(state)? {
+                return None
+            }
+        }
+
+        let ResultAndState { result, state: mut evm_state } = speculative.result_and_state;
+        let fee = evm_state.remove(&self.beneficiary).map(|account| account.info.balance);
+        let mut coinbase = match state.basic(self.coinbase).ok()? {
+            Some(info) => Account::from(info),
+            None => Account::new_not_existing(),
+        };
+        coinbase.info.balance = coinbase.info.balance.saturating_add(fee.unwrap_or_default());
+        coinbase.mark_touch();
+        evm_state.insert(self.coinbase, coinbase);
+
+        Some(ResultAndState { result, state: evm_state })
+    }
+}
+
+/// Speculative result of a transaction.
+#[derive(Debug)]
+struct SpeculativeResult {
+    /// Outcome of the execution.
+    result_and_state: ResultAndState,
+    /// All values read during the execution.
+    reads: Vec<Read>,
+    /// Whether the execution used the `COINBASE` opcode.
+    uses_coinbase: bool,
+}
+
+/// A value read by a speculative execution.
+#[derive(Debug)]
+enum Read {
+    Account(Address, Option<AccountInfo>),
+    Storage(Address, U256, U256),
+    Code(B256, Bytecode),
+    BlockHash(u64, B256),
+}
+
+impl Read {
+    /// Returns the address of the account the read belongs to, if any.
+    const fn address(&self) -> Option<Address> {
+        match self {
+            Self::Account(address, _) | Self::Storage(address, _, _) => Some(*address),
+            Self::Code(_, _) | Self::BlockHash(_, _) => None,
+        }
+    }
+
+    /// Returns whether the given state still has the read value, or `None` if the state couldn't
+    /// be read.
+    fn is_valid<DB: Database>(&self, state: &mut State<DB>) -> Option<bool> {
+        let valid = match self {
+            Self::Account(address, info) => state.basic(*address).ok()? == *info,
+            Self::Storage(address, slot, value) => {
+                // storage can only be read from loaded accounts
+                state.basic(*address).ok()?;
+                state.storage(*address, *slot).ok()? == *value
+            }
+            Self::Code(hash, code) => state.code_by_hash(*hash).ok()? == *code,
+            Self::BlockHash(number, hash) => state.block_hash(*number).ok()? == *hash,
+        };
+        Some(valid)
+    }
+}
+
+/// Multi-version memory holding the writes of the speculative executions by transaction index.
+#[derive(Debug, Default)]
+struct MvMemory {
+    accounts: RwLock<HashMap<Address, BTreeMap<usize, Option<AccountInfo>>>>,
+    storage: RwLock<HashMap<(Address, U256), BTreeMap<usize, U256>>>,
+    code: RwLock<HashMap<B256, Bytecode>>,
+}
+
+impl MvMemory {
+    /// Records the writes of the transaction at the given index, skipping the placeholder
+    /// beneficiary.
+    fn insert(&self, index: usize, beneficiary: Address, state: &EvmState) {
+        let mut accounts = self.accounts.write().unwrap_or_else(PoisonError::into_inner);
+        let mut storage = self.storage.write().unwrap_or_else(PoisonError::into_inner);
+        let mut code = self.code.write().unwrap_or_else(PoisonError::into_inner);
+        for (address, account) in state {
+            if *address == beneficiary || !account.is_touched() {
+                continue
+            }
+
+            let info = (!account.is_selfdestructed()).then(|| account.info.clone());
+            if let Some(bytecode) = info.as_ref().and_then(|info| info.code.clone()) {
+                code.insert(account.info.code_hash, bytecode);
+            }
+            accounts.entry(*address).or_default().insert(index, info);
+            for (slot, value) in account.changed_storage_slots() {
+                storage.entry((*address, *slot)).or_default().insert(index, value.present_value);
+            }
+        }
+    }
+
+    /// Returns the latest account written before the transaction at the given index.
+    fn account(&self, address: Address, index: usize) -> Option<Option<AccountInfo>> {
+        let accounts = self.accounts.read().unwrap_or_else(PoisonError::into_inner);
+        accounts.get(&address)?.range(..index).next_back().map(|(_, info)| info.clone())
+    }
+
+    /// Returns the latest storage value written before the transaction at the given index.
+    fn storage(&self, address: Address, slot: U256, index: usize) -> Option<U256> {
+        let storage = self.storage.read().unwrap_or_else(PoisonError::into_inner);
+        storage.get(&(address, slot))?.range(..index).next_back().map(|(_, value)| *value)
+    }
+
+    /// Returns written code by hash.
+    fn code(&self, code_hash: B256) -> Option<Bytecode> {
+        self.code.read().unwrap_or_else(PoisonError::into_inner).get(&code_hash).cloned()
+    }
+}
+
+/// A read of the state at the start of the transaction execution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum BaseRead {
+    Account(Address),
+    Storage(Address, U256),
+    Code(B256),
+    BlockHash(u64),
+}
+
+/// A value of the state at the start of the transaction execution.
+#[derive(Debug, Clone)]
+enum BaseValue {
+    Account(Option<AccountInfo>),
+    Storage(U256),
+    Code(Bytecode),
+    BlockHash(B256),
+}
+
+/// Request to read the base state, with the sender for the value or `None` if the read failed.
+type BaseReadRequest = (BaseRead, mpsc::Sender<Option<BaseValue>>);
+
+/// Values of the base state that were read by any of the workers.
+type BaseValues = RwLock<HashMap<BaseRead, BaseValue>>;
+
+impl BaseRead {
+    fn execute<DB: Database>(self, state: &mut State<DB>) -> Result<BaseValue, DB::Error> {
+        Ok(match self {
+            Self::Account(address) => BaseValue::Account(state.basic(address)?),
+            Self::Storage(address, slot) => {
+                state.basic(address)?;
+                BaseValue::Storage(state.storage(address, slot)?)
+            }
+            Self::Code(code_hash) => BaseValue::Code(state.code_by_hash(code_hash)?),
+            Self::BlockHash(number) => BaseValue::BlockHash(state.block_hash(number)?),
+        })
+    }
+}
+
+/// Reads the base state through the thread that owns it, unless another worker already read the
+/// value.
+#[derive(Debug)]
+struct BaseReader<'a> {
+    values: &'a BaseValues,
+    request_tx: mpsc::Sender<BaseReadRequest>,
+    reply_tx: mpsc::Sender<Option<BaseValue>>,
+    reply_rx: mpsc::Receiver<Option<BaseValue>>,
+}
+
+impl BaseReader<'_> {
+    fn read(&self, read: BaseRead) -> Result<BaseValue, SpeculationAborted> {
+        if let Some(value) = self.values.read().unwrap_or_else(PoisonError::into_inner).get(&read) {
+            return Ok(value.clone())
+        }
+
+        self.request_tx.send((read, self.reply_tx.clone())).map_err(|_| SpeculationAborted)?;
+        let value = self.reply_rx.recv().ok().flatten().ok_or(SpeculationAborted)?;
+        self.values.write().unwrap_or_else(PoisonError::into_inner).insert(read, value.clone());
+        Ok(value)
+    }
+}
+
+/// Error of a speculative execution that couldn't read the base state. The transaction is
+/// executed again on commit, surfacing the actual error.
+#[derive(Debug)]
+struct SpeculationAborted;
+
+/// Database of a speculative execution, reading the writes of the preceding transactions from the
+/// [`MvMemory`] and everything else from the base state, recording all read values.
+#[derive(Debug)]
+struct SpeculativeDb<'a> {
+    /// Index of the executed transaction.
+    index: usize,
+    /// The placeholder beneficiary, which is never read from the state.
+    beneficiary: Address,
+    memory: &'a MvMemory,
+    base: &'a BaseReader<'a>,
+    reads: Vec<Read>,
+}
+
+impl Database for SpeculativeDb<'_> {
+    type Error = SpeculationAborted;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        if address == self.beneficiary {
+            return Ok(None)
+        }
+
+        let info = match self.memory.account(address, self.index) {
+            Some(info) => info,
+            None => match self.base.read(BaseRead::Account(address))? {
+                BaseValue::Account(info) => info,
+                _ => unreachable!("account read"),
+            },
+        };
+        self.reads.push(Read::Account(address, info.clone()));
+        Ok(info)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        let code = match self.memory.code(code_hash) {
+            Some(code) => code,
+            None => match self.base.read(BaseRead::Code(code_hash))? {
+                BaseValue::Code(code) => code,
+                _ => unreachable!("code read"),
+            },
+        };
+        self.reads.push(Read::Code(code_hash, code.clone()));
+        Ok(code)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        let value = match self.memory.storage(address, index, self.index) {
+            Some(value) => value,
+            None => match self.base.read(BaseRead::Storage(address, index))? {
+                BaseValue::Storage(value) => value,
+                _ => unreachable!("storage read"),
+            },
+        };
+        self.reads.push(Read::Storage(address, index, value));
+        Ok(value)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        let hash = match self.base.read(BaseRead::BlockHash(number))? {
+            BaseValue::BlockHash(hash) => hash,
+            _ => unreachable!("block hash read"),
+        };
+        self.reads.push(Read::BlockHash(number, hash));
+        Ok(hash)
+    }
+}
+
+/// Inspector that records whether the `COINBASE` opcode was executed, since its result differs
+/// between the speculative and the actual execution.
+#[derive(Debug, Default)]
+struct CoinbaseInspector {
+    used: bool,
+}
+
+impl<DB: Database> Inspector<DB> for CoinbaseInspector {
+    fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        if interp.current_opcode() == opcode::COINBASE {
+            self.used = true;
+        }
+    }
+}
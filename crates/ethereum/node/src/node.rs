@@ -1,6 +1,6 @@
 //! Ethereum Node types config.
 
-use std::sync::Arc;
+use std::{num::NonZeroUsize, sync::Arc};
 
 use reth_auto_seal_consensus::AutoSealConsensus;
 use reth_basic_payload_builder::{BasicPayloadJobGenerator, BasicPayloadJobGeneratorConfig};
@@ -26,8 +26,10 @@ use reth_provider::CanonStateSubscriptions;
 use reth_rpc::EthApi;
 use reth_tracing::tracing::{debug, info};
 use reth_transaction_pool::{
-    blobstore::DiskFileBlobStore, EthTransactionPool, TransactionPool,
-    TransactionValidationTaskExecutor,
+    blobstore::{
+        DiskFileBlobStore, DiskFileBlobStoreConfig, OpenDiskFileBlobStore, StaticFileBlobStore,
+    },
+    EthTransactionPool, TransactionPool, TransactionValidationTaskExecutor,
 };
 
 use crate::{EthEngineTypes, EthEvmConfig};
@@ -114,7 +116,19 @@ where
 /// A regular ethereum evm and executor builder.
 #[derive(Debug, Default, Clone, Copy)]
 #[non_exhaustive]
-pub struct EthereumExecutorBuilder;
+pub struct EthereumExecutorBuilder {
+    /// Number of workers for the parallel execution of transactions, if enabled.
+    parallel_workers: Option<NonZeroUsize>,
+}
+
+impl EthereumExecutorBuilder {
+    /// Enables optimistic parallel execution of the transactions of a block on the given number of
+    /// workers, see [`EthExecutorProvider::with_parallel_execution`].
+    pub const fn with_parallel_execution(mut self, workers: NonZeroUsize) -> Self {
+        self.parallel_workers = Some(workers);
+        self
+    }
+}
 
 impl<Types, Node> ExecutorBuilder<Node> for EthereumExecutorBuilder
 where
@@ -130,7 +144,10 @@ where
     ) -> eyre::Result<(Self::EVM, Self::Executor)> {
         let chain_spec = ctx.chain_spec();
         let evm_config = EthEvmConfig::new(ctx.chain_spec());
-        let executor = EthExecutorProvider::new(chain_spec, evm_config.clone());
+        let mut executor = EthExecutorProvider::new(chain_spec, evm_config.clone());
+        if let Some(workers) = self.parallel_workers {
+            executor = executor.with_parallel_execution(workers);
+        }
 
         Ok((evm_config, executor))
     }
@@ -156,11 +173,21 @@ where
     async fn build_pool(self, ctx: &BuilderContext<Node>) -> eyre::Result<Self::Pool> {
         let data_dir = ctx.config().datadir();
         let pool_config = ctx.pool_config();
-        let blob_store = DiskFileBlobStore::open(data_dir.blobstore(), Default::default())?;
+        let mut blob_store_config = DiskFileBlobStoreConfig::default();
+        if let Some(config) = ctx.config().txpool.blob_archive_config() {
+            let static_files =
+                StaticFileBlobStore::open(data_dir.blobstore_static_files(), config)?;
+            // blobs of blocks that are not finalized yet must survive a restart
+            blob_store_config = blob_store_config
+                .with_open(OpenDiskFileBlobStore::ReIndex)
+                .with_static_files(static_files);
+        }
+        let blob_store = DiskFileBlobStore::open(data_dir.blobstore(), blob_store_config)?;
         let validator = TransactionValidationTaskExecutor::eth_builder(ctx.chain_spec())
             .with_head_timestamp(ctx.head().timestamp)
             .kzg_settings(ctx.kzg_settings()?)
             .with_local_transactions_config(pool_config.local_transactions_config.clone())
+            .with_max_inflight_delegated_slots(pool_config.max_inflight_delegated_slots)
             .with_additional_tasks(ctx.config().txpool.additional_validation_tasks)
             .build_with_tasks(
                 ctx.provider().clone(),
@@ -171,15 +198,14 @@ where
         let transaction_pool =
             reth_transaction_pool::Pool::eth_pool(validator, blob_store, pool_config);
         info!(target: "reth::cli", "Transaction pool initialized");
-        let transactions_path = data_dir.txpool_transactions();
 
         // spawn txpool maintenance task
         {
             let pool = transaction_pool.clone();
             let chain_events = ctx.provider().canonical_state_stream();
             let client = ctx.provider().clone();
-            let transactions_backup_config =
-                reth_transaction_pool::maintain::LocalTransactionBackupConfig::with_local_txs_backup(transactions_path);
+            let transactions_backup_config = ctx.transactions_backup_config();
+            let task_executor = ctx.task_executor().clone();
 
             ctx.task_executor().spawn_critical_with_graceful_shutdown_signal(
                 "local transactions backup task",
@@ -188,6 +214,7 @@ where
                         shutdown,
                         pool.clone(),
                         transactions_backup_config,
+                        task_executor,
                     )
                 },
             );
@@ -297,7 +324,7 @@ where
         pool: Pool,
     ) -> eyre::Result<NetworkHandle> {
         let network = ctx.network_builder().await?;
-        let handle = ctx.start_network(network, pool);
+        let handle = ctx.start_network(network, pool)?;
 
         Ok(handle)
     }
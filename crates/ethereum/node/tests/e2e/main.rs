@@ -4,6 +4,7 @@ mod blobs;
 mod dev;
 mod eth;
 mod p2p;
+mod reorg;
 mod utils;
 
 const fn main() {}
@@ -0,0 +1,115 @@
+use crate::utils::eth_payload_attributes;
+use alloy_genesis::Genesis;
+use reth::{
+    args::RpcServerArgs,
+    builder::{NodeBuilder, NodeConfig, NodeHandle},
+    rpc::types::engine::PayloadStatusEnum,
+    tasks::TaskManager,
+};
+use reth_chainspec::{ChainSpecBuilder, MAINNET};
+use reth_e2e_test_utils::{
+    node::NodeTestContext, transaction::TransactionTestContext, wallet::Wallet,
+};
+use reth_node_builder::{engine_tree_config::TreeConfig, EngineNodeLauncher};
+use reth_node_ethereum::{node::EthereumAddOns, EthereumNode};
+use reth_provider::{
+    providers::BlockchainProvider2, BlockHashReader, BlockNumReader, BlockReader,
+    CanonStateNotification, CanonStateSubscriptions, DatabaseProviderFactory, ReceiptProvider,
+};
+use std::{sync::Arc, time::Duration};
+
+#[tokio::test]
+async fn can_reorg_below_persisted_tip() -> eyre::Result<()> {
+    reth_tracing::init_test_tracing();
+    let tasks = TaskManager::current();
+    let exec = tasks.executor();
+
+    // Chain spec with test allocs
+    let genesis: Genesis = serde_json::from_str(include_str!("../assets/genesis.json")).unwrap();
+    let chain_spec = Arc::new(
+        ChainSpecBuilder::default()
+            .chain(MAINNET.chain)
+            .genesis(genesis)
+            .cancun_activated()
+            .build(),
+    );
+
+    // After every four forkchoice updates, the next payload is followed by a reorg of its parent
+    // and grandparent and a forkchoice update to the reorg block.
+    let mut node_config = NodeConfig::test()
+        .with_chain(chain_spec)
+        .with_rpc(RpcServerArgs::default().with_unused_ports().with_http());
+    node_config.debug.reorg_frequency = Some(3);
+    node_config.debug.reorg_depth = Some(1);
+
+    // Persist every canonical block right away, so that the reorged blocks are read back from the
+    // database.
+    let tree_config =
+        TreeConfig::default().with_persistence_threshold(0).with_memory_block_buffer_target(0);
+
+    let NodeHandle { node, node_exit_future: _ } = NodeBuilder::new(node_config)
+        .testing_node(exec)
+        .with_types_and_provider::<EthereumNode, BlockchainProvider2<_>>()
+        .with_components(EthereumNode::components())
+        .with_add_ons(EthereumAddOns::default())
+        .launch_with_fn(|builder| {
+            let launcher = EngineNodeLauncher::new(
+                builder.task_executor().clone(),
+                builder.config().datadir(),
+                tree_config,
+            );
+            builder.launch_with(launcher)
+        })
+        .await?;
+    let mut node = NodeTestContext::new(node).await?;
+    let mut notifications = node.inner.provider.subscribe_to_canonical_state();
+
+    let mut chain = Vec::new();
+    for signer in Wallet::new(12).gen() {
+        let raw_tx = TransactionTestContext::transfer_tx_bytes(1, signer).await;
+        node.rpc.inject_tx(raw_tx).await?;
+
+        let (payload, attributes) = node.new_payload(eth_payload_attributes).await?;
+        let block_hash = payload.block().hash();
+        let block_number = payload.block().number;
+        node.engine_api
+            .submit_payload(payload, attributes, PayloadStatusEnum::Valid, vec![])
+            .await?;
+
+        // The block must not be finalized, otherwise it can't be reorged. If the reorg stream
+        // reorged its ancestors, this reorgs back to the chain of the block.
+        node.engine_api.update_optimistic_forkchoice(block_hash).await?;
+        chain.push(block_hash);
+
+        // wait until the block is persisted and no longer kept in memory
+        let in_memory = node.inner.provider.canonical_in_memory_state();
+        while node.inner.provider.database_provider_ro()?.last_block_number()? < block_number ||
+            in_memory.state_by_number(block_number).is_some()
+        {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }
+
+    // The fifth and the ninth payload were followed by a reorg of two persisted blocks, each
+    // reverted by the next forkchoice update.
+    let mut reorged = Vec::new();
+    while let Ok(notification) = notifications.try_recv() {
+        if let CanonStateNotification::Reorg { old, .. } = notification {
+            reorged.push(old.range());
+        }
+    }
+    assert_eq!(reorged, vec![3..=4, 3..=3, 7..=8, 7..=7]);
+
+    // the database contains the chain, with the blocks that were reorged and written again from
+    // their reconstructed execution outcomes
+    let provider = node.inner.provider.database_provider_ro()?;
+    assert_eq!(provider.last_block_number()?, chain.len() as u64);
+    for (number, hash) in (1..).zip(chain) {
+        assert_eq!(provider.block_hash(number)?, Some(hash));
+        let block = provider.block(number.into())?.unwrap();
+        let receipts = provider.receipts_by_block(number.into())?.unwrap();
+        assert_eq!(receipts.len(), block.body.transactions.len());
+    }
+
+    Ok(())
+}
@@ -118,3 +118,63 @@ impl core::error::Error for TrieWitnessError {
         }
     }
 }
+
+/// Sparse trie errors.
+#[derive(Display, From, Debug, PartialEq, Eq, Clone)]
+pub enum SparseTrieError {
+    /// Sparse trie is still blind. Thrown on attempt to update it.
+    #[display("sparse trie is blind")]
+    Blind,
+    /// Encountered blinded node on update.
+    #[display("attempted to update blind node at {path:?}: {hash}")]
+    BlindedNode {
+        /// Blind node path.
+        path: Nibbles,
+        /// Node hash.
+        hash: B256,
+    },
+    /// A node that is referenced by its parent is missing from the trie.
+    #[display("missing node at {_0:?}")]
+    MissingNode(Nibbles),
+    /// RLP decoding error.
+    #[from]
+    Rlp(alloy_rlp::Error),
+}
+
+impl core::error::Error for SparseTrieError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Rlp(source) => core::error::Error::source(source),
+            _ => Option::None,
+        }
+    }
+}
+
+/// Sparse state trie errors.
+#[derive(Display, From, Debug, PartialEq, Eq, Clone)]
+pub enum SparseStateTrieError {
+    /// Error in the account trie.
+    #[from]
+    Account(SparseTrieError),
+    /// Error in the storage trie of the account.
+    #[display("storage trie of {account}: {error}")]
+    Storage {
+        /// Hashed address of the account.
+        account: B256,
+        /// The storage trie error.
+        error: SparseTrieError,
+    },
+    /// RLP decoding error.
+    #[from]
+    Rlp(alloy_rlp::Error),
+}
+
+impl core::error::Error for SparseStateTrieError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Account(source) => core::error::Error::source(source),
+            Self::Storage { error, .. } => core::error::Error::source(error),
+            Self::Rlp(source) => core::error::Error::source(source),
+        }
+    }
+}
@@ -9,7 +9,7 @@ use reth_execution_types::{BlockExecutionInput, BlockExecutionOutput};
 use reth_metrics::Metrics;
 use reth_primitives::BlockWithSenders;
 
-use crate::execute::Executor;
+use crate::{execute::Executor, system_calls::OnStateHook};
 
 /// Executor metrics.
 // TODO(onbjerg): add sload/sstore
@@ -102,7 +102,39 @@ impl ExecutorMetrics {
             })
         })?;
 
-        // Update the metrics for the number of accounts, storage slots and bytecodes updated
+        self.record_updated(&output);
+        Ok(output)
+    }
+
+    /// Execute the given block using the provided [`Executor`] with the state hook and update
+    /// metrics for the execution.
+    ///
+    /// Compared to [`Self::execute_metered`], this method doesn't update the metrics for the number
+    /// of accounts, storage slots and bytecodes loaded.
+    pub fn execute_metered_with_state_hook<'a, E, DB, O, Error, F>(
+        &self,
+        executor: E,
+        input: BlockExecutionInput<'a, BlockWithSenders>,
+        state_hook: F,
+    ) -> Result<BlockExecutionOutput<O>, Error>
+    where
+        E: Executor<
+            DB,
+            Input<'a> = BlockExecutionInput<'a, BlockWithSenders>,
+            Output = BlockExecutionOutput<O>,
+            Error = Error,
+        >,
+        F: OnStateHook,
+    {
+        let output =
+            self.metered(input.block, || executor.execute_with_state_hook(input, state_hook))?;
+
+        self.record_updated(&output);
+        Ok(output)
+    }
+
+    /// Update the metrics for the number of accounts, storage slots and bytecodes updated.
+    fn record_updated<O>(&self, output: &BlockExecutionOutput<O>) {
         let accounts = output.state.state.len();
         let storage_slots =
             output.state.state.values().map(|account| account.storage.len()).sum::<usize>();
@@ -111,8 +143,6 @@ impl ExecutorMetrics {
         self.accounts_updated_histogram.record(accounts as f64);
         self.storage_slots_updated_histogram.record(storage_slots as f64);
         self.bytecodes_updated_histogram.record(bytecodes as f64);
-
-        Ok(output)
     }
 
     /// Execute the given block and update metrics for the execution.
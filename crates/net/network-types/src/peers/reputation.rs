@@ -28,6 +28,10 @@ const BAD_MESSAGE_REPUTATION_CHANGE: i32 = 16 * REPUTATION_UNIT;
 /// apply any changes to the peer's reputation, effectively ignoring it.
 const ALREADY_SEEN_TRANSACTION_REPUTATION_CHANGE: i32 = 0;
 
+/// The reputation change to apply to a peer that sent transactions exceeding its quota of
+/// transactions in the pool, once per imported batch.
+const EXCEEDED_TRANSACTION_QUOTA_REPUTATION_CHANGE: i32 = REPUTATION_UNIT;
+
 /// The reputation change to apply to a peer which violates protocol rules: minimal reputation
 const BAD_PROTOCOL_REPUTATION_CHANGE: i32 = i32::MIN;
 
@@ -75,6 +79,12 @@ pub enum ReputationChangeKind {
     /// > originally). This is usually achieved by remembering a set of transaction hashes recently
     /// > relayed by the peer.
     AlreadySeenTransaction,
+    /// Peer sent transactions although it reached its quota of transactions in the pool.
+    ///
+    /// This is reported once per imported batch of transactions that exceeds the quota, no matter
+    /// how many transactions of the batch were rejected. A batch is what the peer sent in a single
+    /// message, so the penalty scales with the number of messages rather than their size.
+    ExceededTransactionQuota,
     /// Peer failed to respond in time.
     Timeout,
     /// Peer does not adhere to network protocol rules.
@@ -114,6 +124,8 @@ pub struct ReputationChangeWeights {
     pub bad_transactions: Reputation,
     /// Weight for [`ReputationChangeKind::AlreadySeenTransaction`]
     pub already_seen_transactions: Reputation,
+    /// Weight for [`ReputationChangeKind::ExceededTransactionQuota`]
+    pub exceeded_transaction_quota: Reputation,
     /// Weight for [`ReputationChangeKind::Timeout`]
     pub timeout: Reputation,
     /// Weight for [`ReputationChangeKind::BadProtocol`]
@@ -135,6 +147,7 @@ impl ReputationChangeWeights {
             bad_block: 0,
             bad_transactions: 0,
             already_seen_transactions: 0,
+            exceeded_transaction_quota: 0,
             bad_message: 0,
             timeout: 0,
             bad_protocol: 0,
@@ -152,6 +165,9 @@ impl ReputationChangeWeights {
             ReputationChangeKind::BadBlock => self.bad_block.into(),
             ReputationChangeKind::BadTransactions => self.bad_transactions.into(),
             ReputationChangeKind::AlreadySeenTransaction => self.already_seen_transactions.into(),
+            ReputationChangeKind::ExceededTransactionQuota => {
+                self.exceeded_transaction_quota.into()
+            }
             ReputationChangeKind::Timeout => self.timeout.into(),
             ReputationChangeKind::BadProtocol => self.bad_protocol.into(),
             ReputationChangeKind::FailedToConnect => self.failed_to_connect.into(),
@@ -169,6 +185,7 @@ impl Default for ReputationChangeWeights {
             bad_block: BAD_MESSAGE_REPUTATION_CHANGE,
             bad_transactions: BAD_MESSAGE_REPUTATION_CHANGE,
             already_seen_transactions: ALREADY_SEEN_TRANSACTION_REPUTATION_CHANGE,
+            exceeded_transaction_quota: EXCEEDED_TRANSACTION_QUOTA_REPUTATION_CHANGE,
             bad_message: BAD_MESSAGE_REPUTATION_CHANGE,
             timeout: TIMEOUT_REPUTATION_CHANGE,
             bad_protocol: BAD_PROTOCOL_REPUTATION_CHANGE,
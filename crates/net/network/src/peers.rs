@@ -416,7 +416,8 @@ impl PeersManager {
                         ReputationChangeKind::Dropped |
                             ReputationChangeKind::BadAnnouncement |
                             ReputationChangeKind::Timeout |
-                            ReputationChangeKind::AlreadySeenTransaction
+                            ReputationChangeKind::AlreadySeenTransaction |
+                            ReputationChangeKind::ExceededTransactionQuota
                     ) {
                         return
                     }
@@ -6,6 +6,7 @@ pub mod config;
 pub mod constants;
 /// Component responsible for fetching transactions from [`NewPooledTransactionHashes`].
 pub mod fetcher;
+pub mod private;
 pub mod validation;
 
 pub use self::constants::{
@@ -51,7 +52,7 @@ use reth_network_types::ReputationChangeKind;
 use reth_primitives::{PooledTransactionsElement, TransactionSigned, TransactionSignedEcRecovered};
 use reth_tokio_util::EventStream;
 use reth_transaction_pool::{
-    error::{PoolError, PoolResult},
+    error::{PoolError, PoolErrorKind, PoolResult},
     GetPooledTransactionLimit, PoolTransaction, PropagateKind, PropagatedTransactions,
     TransactionPool, ValidPoolTransaction,
 };
@@ -1060,7 +1061,7 @@ where
                 trace!(target: "net::tx::propagation", new_txs_len=?new_txs.len(), "Importing new transactions");
                 let import = Box::pin(async move {
                     let added = new_txs.len();
-                    let res = pool.add_external_transactions(new_txs).await;
+                    let res = pool.add_peer_transactions(peer_id, new_txs).await;
 
                     // update metrics
                     metric_pending_pool_imports.decrement(added as f64);
@@ -1093,17 +1094,32 @@ where
     }
 
     /// Processes a batch import results.
+    ///
+    /// A peer that exceeds its quota of transactions in the pool is reported once per batch, see
+    /// [`ReputationChangeKind::ExceededTransactionQuota`].
     fn on_batch_import_result(&mut self, batch_results: Vec<PoolResult<TxHash>>) {
+        let mut quota_exceeded_by = None;
         for res in batch_results {
             match res {
                 Ok(hash) => {
                     self.on_good_import(hash);
                 }
                 Err(err) => {
+                    if let PoolErrorKind::ExceededPeerQuota(peer_id) = err.kind {
+                        quota_exceeded_by = Some(peer_id);
+                    }
                     self.on_bad_import(err);
                 }
             }
         }
+
+        // a batch is imported on behalf of a single peer, which is penalized once per batch that
+        // exceeds its quota
+        if let Some(peer_id) = quota_exceeded_by {
+            if !self.network.is_syncing() {
+                self.report_peer(peer_id, ReputationChangeKind::ExceededTransactionQuota);
+            }
+        }
     }
 
     /// Processes a [`FetchEvent`].
@@ -0,0 +1,100 @@
+//! Relaying private transactions to HTTP JSON-RPC endpoints.
+
+use alloy_primitives::hex;
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::sync::{
+    atomic::{self, AtomicUsize},
+    Arc,
+};
+
+/// Errors that can occur when relaying a transaction to an HTTP endpoint.
+#[derive(Debug, thiserror::Error)]
+pub enum HttpRelayError {
+    /// The request failed.
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    /// The response isn't a valid JSON-RPC response.
+    #[error("invalid response: {0}")]
+    InvalidResponse(#[from] serde_json::Error),
+    /// The endpoint rejected the transaction.
+    #[error("transaction rejected: {0}")]
+    Rejected(String),
+}
+
+/// A client that relays raw transactions to an HTTP endpoint via `eth_sendRawTransaction`.
+#[derive(Debug, Clone)]
+pub struct HttpRelayClient {
+    inner: Arc<HttpRelayClientInner>,
+}
+
+impl HttpRelayClient {
+    /// Creates a new [`HttpRelayClient`].
+    ///
+    /// Returns an error if the HTTP client can't be initialized.
+    pub fn new(endpoint: impl Into<String>) -> Result<Self, HttpRelayError> {
+        let client = Client::builder().use_rustls_tls().build()?;
+        Ok(Self::with_client(endpoint, client))
+    }
+
+    /// Creates a new [`HttpRelayClient`] with the given HTTP client.
+    pub fn with_client(endpoint: impl Into<String>, http_client: Client) -> Self {
+        let inner = HttpRelayClientInner {
+            endpoint: endpoint.into(),
+            http_client,
+            id: AtomicUsize::new(0),
+        };
+        Self { inner: Arc::new(inner) }
+    }
+
+    /// Returns the endpoint of the client.
+    pub fn endpoint(&self) -> &str {
+        &self.inner.endpoint
+    }
+
+    /// Returns the next id for the request.
+    fn next_request_id(&self) -> usize {
+        self.inner.id.fetch_add(1, atomic::Ordering::SeqCst)
+    }
+
+    /// Relays the EIP-2718 encoded transaction to the endpoint.
+    ///
+    /// Returns an error if the request failed or the endpoint responded with an error.
+    pub async fn send_raw_transaction(&self, tx: &[u8]) -> Result<(), HttpRelayError> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "method": "eth_sendRawTransaction",
+            "params": [format!("0x{}", hex::encode(tx))],
+            "id": self.next_request_id()
+        });
+
+        let response = self
+            .inner
+            .http_client
+            .post(self.endpoint())
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.to_string())
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        let response: Value = serde_json::from_str(&response)?;
+        if let Some(error) = response.get("error") {
+            return Err(HttpRelayError::Rejected(error.to_string()))
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct HttpRelayClientInner {
+    /// The endpoint transactions are relayed to.
+    endpoint: String,
+    /// The HTTP client.
+    http_client: Client,
+    /// Keeps track of unique request ids.
+    id: AtomicUsize,
+}
@@ -0,0 +1,644 @@
+//! Relaying of [`TransactionOrigin::Private`] transactions.
+//!
+//! Private transactions are never announced to the network. Instead, the
+//! [`PrivateTransactionRelay`] forwards them to a configured set of [`RelayTarget`]s: trusted peers
+//! over the `prtx` RLPx subprotocol, see [`PrivateTxProtocolHandler`], or HTTP JSON-RPC endpoints
+//! via `eth_sendRawTransaction`.
+//!
+//! Deliveries are retried until they are acknowledged or [`PrivateRelayConfig::max_attempts`] is
+//! reached. If a private transaction is still in the pool
+//! [`PrivateRelayConfig::fallback_after_blocks`] blocks after it was added, the configured
+//! [`PrivateTxFallback`] is applied.
+
+mod http;
+mod proto;
+
+pub use http::{HttpRelayClient, HttpRelayError};
+pub use proto::{
+    PrivateTxConnection, PrivateTxConnectionHandler, PrivateTxMessage, PrivateTxMessageId,
+    PrivateTxProtocolHandler,
+};
+
+use alloy_eips::eip2718::Encodable2718;
+use alloy_primitives::TxHash;
+use futures::{future::BoxFuture, stream::FuturesUnordered, StreamExt};
+use proto::{PrivateTxCommand, PrivateTxProtocolEvent};
+use reth_network_peers::PeerId;
+use reth_transaction_pool::{
+    error::PoolResult, NewTransactionEvent, PoolTransaction, TransactionListenerKind,
+    TransactionOrigin, TransactionPool,
+};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+use tokio::sync::mpsc;
+use tracing::{debug, trace};
+
+/// Default interval between delivery attempts.
+pub const DEFAULT_RELAY_RETRY_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Default max number of delivery attempts per target.
+pub const DEFAULT_RELAY_MAX_ATTEMPTS: usize = 5;
+
+/// Default number of blocks after which the [`PrivateTxFallback`] is applied.
+pub const DEFAULT_RELAY_FALLBACK_AFTER_BLOCKS: u64 = 25;
+
+/// A target private transactions are relayed to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RelayTarget {
+    /// A trusted peer that supports the `prtx` protocol.
+    Peer(PeerId),
+    /// An HTTP JSON-RPC endpoint that accepts `eth_sendRawTransaction`.
+    Http(String),
+}
+
+/// What happens to a private transaction that wasn't included after
+/// [`PrivateRelayConfig::fallback_after_blocks`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PrivateTxFallback {
+    /// Remove the transaction from the pool.
+    #[default]
+    Drop,
+    /// Allow the transaction to be propagated to the network, see
+    /// [`TransactionPool::allow_propagation`].
+    Propagate,
+}
+
+/// Configuration of the [`PrivateTransactionRelay`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrivateRelayConfig {
+    /// Targets private transactions are relayed to.
+    pub targets: Vec<RelayTarget>,
+    /// Interval between delivery attempts to the same target.
+    pub retry_interval: Duration,
+    /// Max number of delivery attempts per target.
+    pub max_attempts: usize,
+    /// Number of blocks after which the fallback is applied to transactions still in the pool.
+    pub fallback_after_blocks: u64,
+    /// What happens to transactions still in the pool after `fallback_after_blocks`.
+    pub fallback: PrivateTxFallback,
+}
+
+impl PrivateRelayConfig {
+    /// Adds a trusted peer target.
+    pub fn with_peer(mut self, peer_id: PeerId) -> Self {
+        self.targets.push(RelayTarget::Peer(peer_id));
+        self
+    }
+
+    /// Adds an HTTP endpoint target.
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.targets.push(RelayTarget::Http(endpoint.into()));
+        self
+    }
+
+    /// Sets the fallback that is applied after the given number of blocks.
+    pub const fn with_fallback(mut self, fallback: PrivateTxFallback, after_blocks: u64) -> Self {
+        self.fallback = fallback;
+        self.fallback_after_blocks = after_blocks;
+        self
+    }
+
+    /// Returns `true` if the given peer is a target.
+    fn is_trusted_peer(&self, peer_id: &PeerId) -> bool {
+        self.targets.iter().any(|target| matches!(target, RelayTarget::Peer(id) if id == peer_id))
+    }
+}
+
+impl Default for PrivateRelayConfig {
+    fn default() -> Self {
+        Self {
+            targets: Vec::new(),
+            retry_interval: DEFAULT_RELAY_RETRY_INTERVAL,
+            max_attempts: DEFAULT_RELAY_MAX_ATTEMPTS,
+            fallback_after_blocks: DEFAULT_RELAY_FALLBACK_AFTER_BLOCKS,
+            fallback: PrivateTxFallback::default(),
+        }
+    }
+}
+
+/// Delivery state of a transaction to a single target.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DeliveryStatus {
+    /// Waiting for the next attempt.
+    #[default]
+    Pending,
+    /// Sent, waiting for the acknowledgement.
+    InFlight,
+    /// Acknowledged by the target.
+    Delivered,
+    /// All attempts failed.
+    Failed,
+}
+
+/// Delivery of a transaction to a single target.
+#[derive(Debug, Clone, Copy, Default)]
+struct Delivery {
+    status: DeliveryStatus,
+    attempts: usize,
+    last_attempt: Option<Instant>,
+}
+
+impl Delivery {
+    /// Returns `true` if the transaction should be sent to the target.
+    fn is_due(&self, now: Instant, config: &PrivateRelayConfig) -> bool {
+        self.status == DeliveryStatus::Pending &&
+            self.attempts < config.max_attempts &&
+            self.last_attempt
+                .map_or(true, |last| now.duration_since(last) >= config.retry_interval)
+    }
+
+    /// Marks the current attempt as failed.
+    fn on_failed(&mut self, config: &PrivateRelayConfig) {
+        self.status = if self.attempts >= config.max_attempts {
+            DeliveryStatus::Failed
+        } else {
+            DeliveryStatus::Pending
+        };
+    }
+}
+
+/// A private transaction tracked by the relay.
+#[derive(Debug)]
+struct RelayedTransaction {
+    /// Block number the pool was at when the transaction was added.
+    added_at_block: u64,
+    /// Deliveries, by index of the target.
+    deliveries: Vec<Delivery>,
+}
+
+/// Result of a relayed HTTP request: the transaction, the index of the target and the outcome.
+type HttpRelayOutcome = (TxHash, usize, Result<(), HttpRelayError>);
+
+/// Result of an import into the pool: the transactions relayed to us by a peer, if any, and the
+/// outcome of every imported transaction.
+type PoolImportOutcome = (Vec<TxHash>, Vec<PoolResult<TxHash>>);
+
+/// Relays private transactions of the pool to the configured [`RelayTarget`]s.
+///
+/// Transactions relayed to us by trusted peers are added to the pool as private transactions.
+///
+/// This should be spawned, see [`PrivateTransactionRelay::run`].
+#[derive(Debug)]
+pub struct PrivateTransactionRelay<Pool> {
+    pool: Pool,
+    config: PrivateRelayConfig,
+    /// HTTP clients, by index of the target.
+    http_clients: HashMap<usize, HttpRelayClient>,
+    /// Connections of peers that support the `prtx` protocol.
+    peers: HashMap<PeerId, mpsc::UnboundedSender<PrivateTxCommand>>,
+    /// Events from `prtx` connections.
+    protocol_events: mpsc::UnboundedReceiver<PrivateTxProtocolEvent>,
+    /// Tracked private transactions.
+    transactions: HashMap<TxHash, RelayedTransaction>,
+    /// Transactions relayed to us, and the peer they were received from.
+    received_from: HashMap<TxHash, PeerId>,
+    /// Number of pending imports of each transaction relayed to us.
+    importing: HashMap<TxHash, usize>,
+    /// Requests to peers that weren't acknowledged yet: the transaction and the index of the
+    /// target.
+    inflight_requests: HashMap<u64, (TxHash, usize)>,
+    /// Id of the next request to a peer.
+    next_request_id: u64,
+    /// Pending HTTP requests.
+    http_requests: FuturesUnordered<BoxFuture<'static, HttpRelayOutcome>>,
+    /// Pending imports into the pool.
+    pool_imports: FuturesUnordered<BoxFuture<'static, PoolImportOutcome>>,
+}
+
+impl<Pool> PrivateTransactionRelay<Pool>
+where
+    Pool: TransactionPool + 'static,
+{
+    /// Creates a new relay, and the handler of the `prtx` protocol that must be added to the
+    /// network.
+    ///
+    /// Returns an error if the client of an HTTP target can't be initialized.
+    pub fn new(
+        pool: Pool,
+        config: PrivateRelayConfig,
+    ) -> Result<(Self, PrivateTxProtocolHandler), HttpRelayError> {
+        let (events_tx, events_rx) = mpsc::unbounded_channel();
+        let http_clients = config
+            .targets
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, target)| match target {
+                RelayTarget::Http(endpoint) => {
+                    Some(HttpRelayClient::new(endpoint.clone()).map(|client| (idx, client)))
+                }
+                RelayTarget::Peer(_) => None,
+            })
+            .collect::<Result<_, _>>()?;
+        let relay = Self {
+            pool,
+            config,
+            http_clients,
+            peers: Default::default(),
+            protocol_events: events_rx,
+            transactions: Default::default(),
+            received_from: Default::default(),
+            importing: Default::default(),
+            inflight_requests: Default::default(),
+            next_request_id: 0,
+            http_requests: Default::default(),
+            pool_imports: Default::default(),
+        };
+        Ok((relay, PrivateTxProtocolHandler::new(events_tx)))
+    }
+
+    /// Returns the delivery status of the transaction for every target, if it is tracked.
+    pub fn delivery_status(&self, hash: &TxHash) -> Option<Vec<(RelayTarget, DeliveryStatus)>> {
+        let tx = self.transactions.get(hash)?;
+        Some(
+            self.config
+                .targets
+                .iter()
+                .cloned()
+                .zip(tx.deliveries.iter().map(|delivery| delivery.status))
+                .collect(),
+        )
+    }
+
+    /// Runs the relay until the pool or the network shuts down.
+    pub async fn run(mut self) {
+        let mut new_transactions =
+            self.pool.new_transactions_listener_for(TransactionListenerKind::All);
+        // the interval panics on a zero period
+        let mut interval =
+            tokio::time::interval(self.config.retry_interval.max(Duration::from_millis(1)));
+
+        loop {
+            tokio::select! {
+                event = new_transactions.recv() => {
+                    let Some(event) = event else { break };
+                    self.on_new_transaction(event);
+                }
+                event = self.protocol_events.recv() => {
+                    let Some(event) = event else { break };
+                    self.on_protocol_event(event);
+                }
+                Some((hash, idx, res)) = self.http_requests.next() => {
+                    self.on_http_response(hash, idx, res);
+                }
+                Some((hashes, res)) = self.pool_imports.next() => {
+                    self.on_pool_import(hashes, res);
+                }
+                _ = interval.tick() => {
+                    self.on_tick();
+                }
+            }
+        }
+    }
+
+    /// Starts tracking new private transactions.
+    fn on_new_transaction(&mut self, event: NewTransactionEvent<Pool::Transaction>) {
+        if !event.transaction.origin.is_private() {
+            return
+        }
+        let hash = *event.transaction.hash();
+        let mut deliveries = vec![Delivery::default(); self.config.targets.len()];
+
+        // don't relay the transaction back to the peer we received it from
+        if let Some(peer_id) = self.received_from.remove(&hash) {
+            for (target, delivery) in self.config.targets.iter().zip(&mut deliveries) {
+                if *target == RelayTarget::Peer(peer_id) {
+                    delivery.status = DeliveryStatus::Delivered;
+                }
+            }
+        }
+
+        let added_at_block = self.pool.block_info().last_seen_block_number;
+        self.transactions.insert(hash, RelayedTransaction { added_at_block, deliveries });
+        self.relay(hash);
+    }
+
+    /// Sends the transaction to all targets that are due for another attempt.
+    fn relay(&mut self, hash: TxHash) {
+        let Some(tx) = self.transactions.get(&hash) else { return };
+        let now = Instant::now();
+        let due = tx
+            .deliveries
+            .iter()
+            .enumerate()
+            .filter(|(_, delivery)| delivery.is_due(now, &self.config))
+            .map(|(idx, _)| idx)
+            .collect::<Vec<_>>();
+        if due.is_empty() {
+            return
+        }
+
+        let Some(transaction) = self.pool.get_pooled_transaction_element(hash) else {
+            // the transaction left the pool
+            self.transactions.remove(&hash);
+            return
+        };
+
+        for idx in due {
+            match &self.config.targets[idx] {
+                RelayTarget::Peer(peer_id) => {
+                    let Some(conn) = self.peers.get(peer_id) else {
+                        // retried once the peer connects
+                        continue
+                    };
+                    let request_id = self.next_request_id;
+                    self.next_request_id += 1;
+                    let cmd = PrivateTxCommand::Send {
+                        request_id,
+                        transactions: vec![transaction.clone()],
+                    };
+                    if conn.send(cmd).is_err() {
+                        self.peers.remove(peer_id);
+                        continue
+                    }
+                    self.inflight_requests.insert(request_id, (hash, idx));
+                }
+                RelayTarget::Http(_) => {
+                    let client = self.http_clients[&idx].clone();
+                    let encoded = transaction.encoded_2718();
+                    self.http_requests.push(Box::pin(async move {
+                        (hash, idx, client.send_raw_transaction(&encoded).await)
+                    }));
+                }
+            }
+
+            trace!(target: "net::tx::private", %hash, target=?self.config.targets[idx], "relaying private transaction");
+            if let Some(tx) = self.transactions.get_mut(&hash) {
+                let delivery = &mut tx.deliveries[idx];
+                delivery.status = DeliveryStatus::InFlight;
+                delivery.attempts += 1;
+                delivery.last_attempt = Some(now);
+            }
+        }
+    }
+
+    /// Handles events of `prtx` connections.
+    fn on_protocol_event(&mut self, event: PrivateTxProtocolEvent) {
+        match event {
+            PrivateTxProtocolEvent::Established { peer_id, to_connection } => {
+                if !self.config.is_trusted_peer(&peer_id) {
+                    return
+                }
+                trace!(target: "net::tx::private", ?peer_id, "trusted peer connected");
+                self.peers.insert(peer_id, to_connection);
+
+                // relay all transactions that are waiting for the peer
+                let hashes = self.transactions.keys().copied().collect::<Vec<_>>();
+                for hash in hashes {
+                    self.relay(hash);
+                }
+            }
+            PrivateTxProtocolEvent::Transactions { peer_id, transactions } => {
+                if !self.config.is_trusted_peer(&peer_id) {
+                    trace!(target: "net::tx::private", ?peer_id, "ignoring transactions relayed by untrusted peer");
+                    return
+                }
+                let mut hashes = Vec::with_capacity(transactions.len());
+                let mut new_txs = Vec::with_capacity(transactions.len());
+                for tx in transactions {
+                    let Ok(tx) = tx.try_into_ecrecovered() else {
+                        debug!(target: "net::tx::private", ?peer_id, "failed ecrecovery for relayed transaction");
+                        continue
+                    };
+                    let hash = *tx.hash();
+                    self.received_from.insert(hash, peer_id);
+                    *self.importing.entry(hash).or_default() += 1;
+                    hashes.push(hash);
+                    new_txs.push(Pool::Transaction::from_pooled(tx.into()));
+                }
+                if new_txs.is_empty() {
+                    return
+                }
+                let pool = self.pool.clone();
+                self.pool_imports.push(Box::pin(async move {
+                    let res = pool.add_transactions(TransactionOrigin::Private, new_txs).await;
+                    (hashes, res)
+                }));
+            }
+            PrivateTxProtocolEvent::Ack { peer_id, request_id } => {
+                let Some((hash, idx)) = self.inflight_requests.remove(&request_id) else { return };
+                if self.config.targets[idx] != RelayTarget::Peer(peer_id) {
+                    return
+                }
+                if let Some(tx) = self.transactions.get_mut(&hash) {
+                    trace!(target: "net::tx::private", %hash, ?peer_id, "private transaction delivered");
+                    tx.deliveries[idx].status = DeliveryStatus::Delivered;
+                }
+            }
+            PrivateTxProtocolEvent::Closed { peer_id } => {
+                self.peers.remove(&peer_id);
+            }
+        }
+    }
+
+    /// Handles the outcome of an import into the pool.
+    ///
+    /// Transactions relayed to us that failed to import are forgotten, the imported ones are
+    /// tracked once the pool announces them, see [`Self::on_new_transaction`].
+    fn on_pool_import(&mut self, hashes: Vec<TxHash>, res: Vec<PoolResult<TxHash>>) {
+        trace!(target: "net::tx::private", ?res, "imported transactions");
+        for (hash, res) in hashes.into_iter().zip(res) {
+            if let Some(count) = self.importing.get_mut(&hash) {
+                *count -= 1;
+                if *count == 0 {
+                    self.importing.remove(&hash);
+                }
+            }
+            if res.is_err() && !self.importing.contains_key(&hash) {
+                self.received_from.remove(&hash);
+            }
+        }
+    }
+
+    /// Records the outcome of a relayed HTTP request.
+    fn on_http_response(&mut self, hash: TxHash, idx: usize, res: Result<(), HttpRelayError>) {
+        let Some(tx) = self.transactions.get_mut(&hash) else { return };
+        let delivery = &mut tx.deliveries[idx];
+        match res {
+            Ok(()) => {
+                trace!(target: "net::tx::private", %hash, target=?self.config.targets[idx], "private transaction delivered");
+                delivery.status = DeliveryStatus::Delivered;
+            }
+            Err(err) => {
+                debug!(target: "net::tx::private", %hash, target=?self.config.targets[idx], %err, "failed to relay private transaction");
+                delivery.on_failed(&self.config);
+            }
+        }
+    }
+
+    /// Retries deliveries that weren't acknowledged in time, stops tracking transactions that
+    /// left the pool, and applies the fallback to expired transactions.
+    fn on_tick(&mut self) {
+        let block_number = self.pool.block_info().last_seen_block_number;
+        let now = Instant::now();
+        let mut expired = Vec::new();
+
+        self.transactions.retain(|hash, tx| {
+            if !self.pool.contains(hash) {
+                return false
+            }
+            if block_number >= tx.added_at_block + self.config.fallback_after_blocks {
+                expired.push(*hash);
+                return false
+            }
+            for delivery in &mut tx.deliveries {
+                if delivery.status == DeliveryStatus::InFlight &&
+                    delivery.last_attempt.is_some_and(|last| {
+                        now.duration_since(last) >= self.config.retry_interval
+                    })
+                {
+                    // timed out
+                    delivery.on_failed(&self.config);
+                }
+            }
+            true
+        });
+        let transactions = &self.transactions;
+        self.inflight_requests.retain(|_, (hash, _)| transactions.contains_key(hash));
+        // relayed transactions that left the pool before they were announced, transactions that
+        // are still being imported are kept until the import resolves
+        let importing = &self.importing;
+        self.received_from
+            .retain(|hash, _| importing.contains_key(hash) || self.pool.contains(hash));
+
+        for hash in expired {
+            self.on_expired(hash);
+        }
+
+        let hashes = self.transactions.keys().copied().collect::<Vec<_>>();
+        for hash in hashes {
+            self.relay(hash);
+        }
+    }
+
+    /// Applies the fallback to a private transaction that wasn't included in time.
+    fn on_expired(&mut self, hash: TxHash) {
+        debug!(target: "net::tx::private", %hash, fallback=?self.config.fallback, "private transaction expired");
+        match self.config.fallback {
+            PrivateTxFallback::Drop => {
+                self.pool.remove_transactions(vec![hash]);
+            }
+            PrivateTxFallback::Propagate => {
+                self.pool.allow_propagation(hash);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_transaction_pool::{
+        error::{PoolError, PoolErrorKind},
+        test_utils::{testing_pool, MockTransaction, TestPool},
+        TransactionPoolExt,
+    };
+
+    async fn add_private(pool: &TestPool) -> TxHash {
+        pool.add_transaction(TransactionOrigin::Private, MockTransaction::eip1559()).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn relay_to_peer_until_acknowledged() {
+        let pool = testing_pool();
+        let peer_id = PeerId::random();
+        let config = PrivateRelayConfig { retry_interval: Duration::ZERO, ..Default::default() }
+            .with_peer(peer_id);
+        let (mut relay, _handler) = PrivateTransactionRelay::new(pool.clone(), config).unwrap();
+        let mut events = pool.new_transactions_listener();
+
+        let hash = add_private(&pool).await;
+        relay.on_new_transaction(events.recv().await.unwrap());
+        assert_eq!(
+            relay.delivery_status(&hash).unwrap(),
+            vec![(RelayTarget::Peer(peer_id), DeliveryStatus::Pending)]
+        );
+
+        let (to_connection, mut conn) = mpsc::unbounded_channel();
+        relay.on_protocol_event(PrivateTxProtocolEvent::Established { peer_id, to_connection });
+        let PrivateTxCommand::Send { request_id, transactions } = conn.try_recv().unwrap();
+        assert_eq!(*transactions[0].hash(), hash);
+        assert_eq!(relay.delivery_status(&hash).unwrap()[0].1, DeliveryStatus::InFlight);
+
+        // not acknowledged in time
+        relay.on_tick();
+        let PrivateTxCommand::Send { request_id: retry_id, .. } = conn.try_recv().unwrap();
+        assert_ne!(request_id, retry_id);
+
+        relay.on_protocol_event(PrivateTxProtocolEvent::Ack { peer_id, request_id: retry_id });
+        assert_eq!(relay.delivery_status(&hash).unwrap()[0].1, DeliveryStatus::Delivered);
+        relay.on_tick();
+        assert!(conn.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn ignore_public_transactions() {
+        let pool = testing_pool();
+        let config = PrivateRelayConfig::default().with_peer(PeerId::random());
+        let (mut relay, _handler) = PrivateTransactionRelay::new(pool.clone(), config).unwrap();
+        let mut events = pool.new_transactions_listener();
+
+        let hash = pool
+            .add_transaction(TransactionOrigin::Local, MockTransaction::eip1559())
+            .await
+            .unwrap();
+        relay.on_new_transaction(events.recv().await.unwrap());
+        assert!(relay.delivery_status(&hash).is_none());
+    }
+
+    #[tokio::test]
+    async fn fallback_after_blocks() {
+        for fallback in [PrivateTxFallback::Drop, PrivateTxFallback::Propagate] {
+            let pool = testing_pool();
+            let config = PrivateRelayConfig::default().with_fallback(fallback, 2);
+            let (mut relay, _handler) = PrivateTransactionRelay::new(pool.clone(), config).unwrap();
+            let mut events = pool.new_transactions_listener();
+
+            let hash = add_private(&pool).await;
+            relay.on_new_transaction(events.recv().await.unwrap());
+
+            let mut block_info = pool.block_info();
+            block_info.last_seen_block_number += 1;
+            pool.set_block_info(block_info);
+            relay.on_tick();
+            assert!(relay.delivery_status(&hash).is_some());
+
+            block_info.last_seen_block_number += 1;
+            pool.set_block_info(block_info);
+            relay.on_tick();
+            assert!(relay.delivery_status(&hash).is_none());
+
+            if fallback == PrivateTxFallback::Propagate {
+                // the transaction stays in the pool as is, but may be propagated
+                let tx = pool.get(&hash).unwrap();
+                assert!(tx.propagate);
+                assert_eq!(tx.origin, TransactionOrigin::Private);
+                assert_eq!(pool.pooled_transaction_hashes(), vec![hash]);
+            } else {
+                assert!(!pool.contains(&hash));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn keep_relaying_peer_until_imported() {
+        let pool = testing_pool();
+        let peer_id = PeerId::random();
+        let config = PrivateRelayConfig::default().with_peer(peer_id);
+        let (mut relay, _handler) = PrivateTransactionRelay::new(pool, config).unwrap();
+
+        // a relayed transaction whose import is still pending isn't in the pool yet
+        let hash = TxHash::random();
+        relay.received_from.insert(hash, peer_id);
+        relay.importing.insert(hash, 1);
+        relay.on_tick();
+        assert_eq!(relay.received_from.get(&hash), Some(&peer_id));
+
+        // forgotten once the import failed
+        let err = PoolError::new(hash, PoolErrorKind::AlreadyImported);
+        relay.on_pool_import(vec![hash], vec![Err(err)]);
+        assert!(relay.importing.is_empty());
+        assert!(relay.received_from.is_empty());
+    }
+}
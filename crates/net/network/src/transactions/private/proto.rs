@@ -0,0 +1,270 @@
+//! The `prtx` RLPx subprotocol used to relay private transactions between trusted peers.
+
+use crate::protocol::{ConnectionHandler, OnNotSupported, ProtocolHandler};
+use alloy_primitives::bytes::{Buf, BufMut, BytesMut};
+use alloy_rlp::{Decodable, Encodable};
+use futures::{Stream, StreamExt};
+use reth_eth_wire::{
+    capability::SharedCapabilities, multiplex::ProtocolConnection, protocol::Protocol, Capability,
+};
+use reth_network_api::{Direction, PeerId};
+use reth_primitives::PooledTransactionsElement;
+use std::{
+    net::SocketAddr,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tracing::trace;
+
+/// Message ids of the `prtx` protocol.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PrivateTxMessageId {
+    /// Private transactions relayed to the peer.
+    Transactions = 0x00,
+    /// Acknowledges the receipt of relayed transactions.
+    Ack = 0x01,
+}
+
+/// A message of the `prtx` protocol.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PrivateTxMessage {
+    /// Private transactions relayed to the peer.
+    Transactions {
+        /// Id of the request, echoed in the [`PrivateTxMessage::Ack`].
+        request_id: u64,
+        /// The relayed transactions.
+        transactions: Vec<PooledTransactionsElement>,
+    },
+    /// Acknowledges the receipt of the request with the given id.
+    Ack {
+        /// Id of the acknowledged request.
+        request_id: u64,
+    },
+}
+
+impl PrivateTxMessage {
+    /// Returns the capability for the `prtx` protocol.
+    pub fn capability() -> Capability {
+        Capability::new_static("prtx", 1)
+    }
+
+    /// Returns the protocol for the `prtx` protocol.
+    pub fn protocol() -> Protocol {
+        Protocol::new(Self::capability(), 2)
+    }
+
+    /// Returns the id of the message.
+    pub const fn message_id(&self) -> PrivateTxMessageId {
+        match self {
+            Self::Transactions { .. } => PrivateTxMessageId::Transactions,
+            Self::Ack { .. } => PrivateTxMessageId::Ack,
+        }
+    }
+
+    /// Encodes the message: the message id, followed by the RLP encoded fields.
+    pub fn encoded(&self) -> BytesMut {
+        let mut buf = BytesMut::new();
+        buf.put_u8(self.message_id() as u8);
+        match self {
+            Self::Transactions { request_id, transactions } => {
+                request_id.encode(&mut buf);
+                transactions.encode(&mut buf);
+            }
+            Self::Ack { request_id } => request_id.encode(&mut buf),
+        }
+        buf
+    }
+
+    /// Decodes a message from the given buffer.
+    pub fn decode_message(buf: &mut &[u8]) -> Option<Self> {
+        if buf.is_empty() {
+            return None
+        }
+        let id = buf[0];
+        buf.advance(1);
+        let request_id = u64::decode(buf).ok()?;
+        match id {
+            0x00 => {
+                let transactions = Vec::<PooledTransactionsElement>::decode(buf).ok()?;
+                Some(Self::Transactions { request_id, transactions })
+            }
+            0x01 => Some(Self::Ack { request_id }),
+            _ => None,
+        }
+    }
+}
+
+/// Commands sent to a `prtx` connection.
+#[derive(Debug)]
+pub(crate) enum PrivateTxCommand {
+    /// Relays the transactions to the peer.
+    Send { request_id: u64, transactions: Vec<PooledTransactionsElement> },
+}
+
+/// Events emitted by `prtx` connections.
+#[derive(Debug)]
+pub(crate) enum PrivateTxProtocolEvent {
+    /// A connection with a peer that supports the protocol was established.
+    Established { peer_id: PeerId, to_connection: mpsc::UnboundedSender<PrivateTxCommand> },
+    /// The peer relayed transactions to us.
+    Transactions { peer_id: PeerId, transactions: Vec<PooledTransactionsElement> },
+    /// The peer acknowledged a request.
+    Ack { peer_id: PeerId, request_id: u64 },
+    /// The connection with the peer was closed.
+    Closed { peer_id: PeerId },
+}
+
+/// The [`ProtocolHandler`] of the `prtx` protocol.
+///
+/// This must be added to the network, see
+/// [`NetworkProtocols::add_rlpx_sub_protocol`](crate::NetworkProtocols::add_rlpx_sub_protocol).
+#[derive(Debug, Clone)]
+pub struct PrivateTxProtocolHandler {
+    events: mpsc::UnboundedSender<PrivateTxProtocolEvent>,
+}
+
+impl PrivateTxProtocolHandler {
+    /// Creates a new handler that reports to the relay.
+    pub(crate) const fn new(events: mpsc::UnboundedSender<PrivateTxProtocolEvent>) -> Self {
+        Self { events }
+    }
+}
+
+impl ProtocolHandler for PrivateTxProtocolHandler {
+    type ConnectionHandler = PrivateTxConnectionHandler;
+
+    fn on_incoming(&self, _socket_addr: SocketAddr) -> Option<Self::ConnectionHandler> {
+        Some(PrivateTxConnectionHandler { events: self.events.clone() })
+    }
+
+    fn on_outgoing(
+        &self,
+        _socket_addr: SocketAddr,
+        _peer_id: PeerId,
+    ) -> Option<Self::ConnectionHandler> {
+        Some(PrivateTxConnectionHandler { events: self.events.clone() })
+    }
+}
+
+/// The [`ConnectionHandler`] of the `prtx` protocol.
+#[derive(Debug)]
+pub struct PrivateTxConnectionHandler {
+    events: mpsc::UnboundedSender<PrivateTxProtocolEvent>,
+}
+
+impl ConnectionHandler for PrivateTxConnectionHandler {
+    type Connection = PrivateTxConnection;
+
+    fn protocol(&self) -> Protocol {
+        PrivateTxMessage::protocol()
+    }
+
+    fn on_unsupported_by_peer(
+        self,
+        _supported: &SharedCapabilities,
+        _direction: Direction,
+        _peer_id: PeerId,
+    ) -> OnNotSupported {
+        OnNotSupported::KeepAlive
+    }
+
+    fn into_connection(
+        self,
+        _direction: Direction,
+        peer_id: PeerId,
+        conn: ProtocolConnection,
+    ) -> Self::Connection {
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.events.send(PrivateTxProtocolEvent::Established { peer_id, to_connection: tx }).ok();
+        PrivateTxConnection {
+            peer_id,
+            conn,
+            commands: UnboundedReceiverStream::new(rx),
+            events: self.events,
+        }
+    }
+}
+
+/// A `prtx` connection with a peer.
+///
+/// Received transactions are acknowledged as soon as they are decoded, and reported to the relay.
+#[derive(Debug)]
+pub struct PrivateTxConnection {
+    peer_id: PeerId,
+    conn: ProtocolConnection,
+    commands: UnboundedReceiverStream<PrivateTxCommand>,
+    events: mpsc::UnboundedSender<PrivateTxProtocolEvent>,
+}
+
+impl Stream for PrivateTxConnection {
+    type Item = BytesMut;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        if let Poll::Ready(Some(cmd)) = this.commands.poll_next_unpin(cx) {
+            return match cmd {
+                PrivateTxCommand::Send { request_id, transactions } => Poll::Ready(Some(
+                    PrivateTxMessage::Transactions { request_id, transactions }.encoded(),
+                )),
+            }
+        }
+
+        loop {
+            let Some(msg) = ready!(this.conn.poll_next_unpin(cx)) else { return Poll::Ready(None) };
+
+            let Some(msg) = PrivateTxMessage::decode_message(&mut &msg[..]) else {
+                trace!(target: "net::tx::private", peer_id=?this.peer_id, "invalid prtx message");
+                return Poll::Ready(None)
+            };
+
+            match msg {
+                PrivateTxMessage::Transactions { request_id, transactions } => {
+                    let peer_id = this.peer_id;
+                    this.events
+                        .send(PrivateTxProtocolEvent::Transactions { peer_id, transactions })
+                        .ok();
+                    return Poll::Ready(Some(PrivateTxMessage::Ack { request_id }.encoded()))
+                }
+                PrivateTxMessage::Ack { request_id } => {
+                    let peer_id = this.peer_id;
+                    this.events.send(PrivateTxProtocolEvent::Ack { peer_id, request_id }).ok();
+                }
+            }
+        }
+    }
+}
+
+impl Drop for PrivateTxConnection {
+    fn drop(&mut self) {
+        self.events.send(PrivateTxProtocolEvent::Closed { peer_id: self.peer_id }).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_eips::eip2718::Decodable2718;
+    use alloy_primitives::hex;
+
+    #[test]
+    fn message_roundtrip() {
+        let input = hex!("02f871018302a90f808504890aef60826b6c94ddf4c5025d1a5742cf12f74eec246d4432c295e487e09c3bbcc12b2b80c080a0f21a4eacd0bf8fea9c5105c543be5a1d8c796516875710fafafdf16d16d8ee23a001280915021bb446d1973501a67f93d2b38894a514b976e7b46dc2fe54598d76");
+        let tx = PooledTransactionsElement::decode_2718(&mut &input[..]).unwrap();
+
+        let msgs = [
+            PrivateTxMessage::Transactions { request_id: 7, transactions: vec![tx] },
+            PrivateTxMessage::Transactions { request_id: 0, transactions: vec![] },
+            PrivateTxMessage::Ack { request_id: 7 },
+        ];
+        for msg in msgs {
+            let encoded = msg.encoded();
+            assert_eq!(PrivateTxMessage::decode_message(&mut &encoded[..]), Some(msg));
+        }
+
+        assert_eq!(PrivateTxMessage::decode_message(&mut &[0x02, 0x80][..]), None);
+    }
+}
@@ -3,6 +3,7 @@
 mod big_pooled_txs_req;
 mod connect;
 mod multiplex;
+mod private;
 mod requests;
 mod session;
 mod startup;
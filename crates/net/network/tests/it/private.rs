@@ -0,0 +1,53 @@
+//! Testing the relay of private transactions between trusted peers.
+
+use alloy_primitives::U256;
+use rand::thread_rng;
+use reth_network::{
+    test_utils::Testnet,
+    transactions::private::{PrivateRelayConfig, PrivateTransactionRelay},
+};
+use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+use reth_transaction_pool::{
+    test_utils::TransactionGenerator, PoolTransaction, TransactionOrigin, TransactionPool,
+};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_private_tx_relay() {
+    reth_tracing::init_test_tracing();
+
+    let provider = MockEthProvider::default();
+    let mut net = Testnet::create_with(2, provider.clone()).await.with_eth_pool();
+
+    // each peer trusts the other one
+    let peer_ids = [net.peers()[0].peer_id(), net.peers()[1].peer_id()];
+    for (idx, peer) in net.peers_mut().iter_mut().enumerate() {
+        let config = PrivateRelayConfig::default().with_peer(peer_ids[1 - idx]);
+        let (relay, protocol) =
+            PrivateTransactionRelay::new(peer.pool().unwrap().clone(), config).unwrap();
+        peer.add_rlpx_sub_protocol(protocol);
+        tokio::spawn(relay.run());
+    }
+
+    let handle = net.spawn();
+    // connect all the peers
+    handle.connect_peers().await;
+
+    let peer0_pool = handle.peers()[0].pool().unwrap();
+    let peer1_pool = handle.peers()[1].pool().unwrap();
+    let mut peer1_tx_listener = peer1_pool.pending_transactions_listener();
+
+    let mut gen = TransactionGenerator::new(thread_rng());
+    let tx = gen.gen_eip1559_pooled();
+
+    // ensure the sender has balance
+    let sender = tx.sender();
+    provider.add_account(sender, ExtendedAccount::new(0, U256::from(100_000_000)));
+
+    // insert private tx in peer0's pool
+    let hash = peer0_pool.add_transaction(TransactionOrigin::Private, tx).await.unwrap();
+
+    // ensure tx is relayed to peer1, and stays private there
+    let received = peer1_tx_listener.recv().await.unwrap();
+    assert_eq!(received, hash);
+    assert!(peer1_pool.get(&hash).unwrap().origin.is_private());
+}
@@ -25,8 +25,8 @@ use reth_db_api::{
 };
 use reth_exex::ExExContext;
 use reth_network::{
-    transactions::TransactionsManagerConfig, NetworkBuilder, NetworkConfig, NetworkConfigBuilder,
-    NetworkHandle, NetworkManager,
+    transactions::{private::PrivateTransactionRelay, TransactionsManagerConfig},
+    NetworkBuilder, NetworkConfig, NetworkConfigBuilder, NetworkHandle, NetworkManager,
 };
 use reth_node_api::{
     FullNodeTypes, FullNodeTypesAdapter, NodeAddOns, NodeTypes, NodeTypesWithDBAdapter,
@@ -42,7 +42,11 @@ use reth_node_core::{
 use reth_primitives::revm_primitives::EnvKzgSettings;
 use reth_provider::{providers::BlockchainProvider, ChainSpecProvider, FullProvider};
 use reth_tasks::TaskExecutor;
-use reth_transaction_pool::{PoolConfig, TransactionPool};
+use reth_transaction_pool::{
+    maintain::LocalTransactionBackupConfig,
+    validate::{ContractGasCapPolicy, DenyListPolicy, MinTipPolicy, TransactionPolicies},
+    PoolConfig, TransactionPool,
+};
 use secp256k1::SecretKey;
 use tracing::{info, trace, warn};
 
@@ -568,9 +572,45 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
         self.config().dev.dev
     }
 
-    /// Returns the transaction pool config of the node.
+    /// Returns the transaction pool config of the node, including the admission policies of the
+    /// reth.toml config.
     pub fn pool_config(&self) -> PoolConfig {
-        self.config().txpool.pool_config()
+        PoolConfig { policies: self.transaction_policies(), ..self.config().txpool.pool_config() }
+    }
+
+    /// Returns the config of the transaction pool backup task, writing to the transactions file of
+    /// the data directory.
+    pub fn transactions_backup_config(&self) -> LocalTransactionBackupConfig {
+        self.config()
+            .txpool
+            .transactions_backup_config(self.config().datadir().txpool_transactions())
+    }
+
+    /// Returns the transaction admission policies configured in the reth.toml config.
+    pub fn transaction_policies(&self) -> TransactionPolicies {
+        let config = &self.reth_config().txpool.policies;
+        let mut policies = TransactionPolicies::default();
+
+        if !config.deny_senders.is_empty() || !config.deny_recipients.is_empty() {
+            policies.push(DenyListPolicy {
+                senders: config.deny_senders.iter().copied().collect(),
+                recipients: config.deny_recipients.iter().copied().collect(),
+            });
+        }
+        if !config.contract_gas_caps.is_empty() {
+            policies.push(ContractGasCapPolicy {
+                caps: config.contract_gas_caps.iter().map(|(to, cap)| (*to, *cap)).collect(),
+            });
+        }
+        if config.has_min_tip() {
+            policies.push(MinTipPolicy {
+                local: config.min_tip_local.map(Into::into),
+                external: config.min_tip_external.map(Into::into),
+                private: config.min_tip_private.map(Into::into),
+            });
+        }
+
+        policies
     }
 
     /// Loads `EnvKzgSettings::Default`.
@@ -587,7 +627,11 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
     ///
     /// Spawns the configured network and associated tasks and returns the [`NetworkHandle`]
     /// connected to that network.
-    pub fn start_network<Pool>(&self, builder: NetworkBuilder<(), ()>, pool: Pool) -> NetworkHandle
+    pub fn start_network<Pool>(
+        &self,
+        builder: NetworkBuilder<(), ()>,
+        pool: Pool,
+    ) -> eyre::Result<NetworkHandle>
     where
         Pool: TransactionPool + Unpin + 'static,
     {
@@ -599,16 +643,25 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
     /// Accepts the config for the transaction task.
     ///
     /// Spawns the configured network and associated tasks and returns the [`NetworkHandle`]
-    /// connected to that network.
+    /// connected to that network. If relay targets for private transactions are configured, the
+    /// [`PrivateTransactionRelay`] is spawned as well.
+    ///
+    /// Returns an error if the [`PrivateTransactionRelay`] can't be created.
     pub fn start_network_with<Pool>(
         &self,
-        builder: NetworkBuilder<(), ()>,
+        mut builder: NetworkBuilder<(), ()>,
         pool: Pool,
         tx_config: TransactionsManagerConfig,
-    ) -> NetworkHandle
+    ) -> eyre::Result<NetworkHandle>
     where
         Pool: TransactionPool + Unpin + 'static,
     {
+        if let Some(config) = self.config().network.private_relay_config() {
+            let (relay, protocol) = PrivateTransactionRelay::new(pool.clone(), config)?;
+            builder.network_mut().add_rlpx_sub_protocol(protocol);
+            self.executor.spawn_critical("private transaction relay", relay.run());
+        }
+
         let (handle, network, txpool, eth) = builder
             .transactions(pool, tx_config)
             .request_handler(self.provider().clone())
@@ -639,7 +692,7 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
             },
         );
 
-        handle
+        Ok(handle)
     }
 
     /// Get the network secret from the given data dir
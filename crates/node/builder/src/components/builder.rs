@@ -6,7 +6,10 @@ use reth_consensus::Consensus;
 use reth_evm::execute::BlockExecutorProvider;
 use reth_node_api::{EngineValidator, NodeTypesWithEngine};
 use reth_primitives::Header;
+use reth_provider::CanonStateSubscriptions;
 use reth_transaction_pool::TransactionPool;
+use reth_transaction_pool_simulation::{simulate_pending_transactions, SimulationConfig};
+use tracing::debug;
 
 use crate::{
     components::{
@@ -27,6 +30,8 @@ use super::EngineValidatorBuilder;
 /// The components of the node depend on each other:
 /// - The payload builder service depends on the transaction pool.
 /// - The network depends on the transaction pool.
+/// - The transaction simulation service, if enabled, depends on the transaction pool and the EVM
+///   configuration.
 ///
 /// We distinguish between different kind of components:
 /// - Components that are standalone, such as the transaction pool.
@@ -379,6 +384,20 @@ where
 
         let (evm_config, executor) = evm_builder.build_evm(context).await?;
         let pool = pool_builder.build_pool(context).await?;
+        if context.config().txpool.simulate {
+            context.task_executor().spawn_critical(
+                "txpool simulation task",
+                simulate_pending_transactions(
+                    context.provider().clone(),
+                    pool.clone(),
+                    evm_config.clone(),
+                    context.provider().canonical_state_stream(),
+                    context.task_executor().clone(),
+                    SimulationConfig::default(),
+                ),
+            );
+            debug!(target: "reth::cli", "Spawned txpool simulation task");
+        }
         let network = network_builder.build_network(context, pool.clone()).await?;
         let payload_builder = payload_builder.spawn_payload_service(context, pool.clone()).await?;
         let consensus = consensus_builder.build_consensus(context).await?;
@@ -19,7 +19,7 @@ use reth_downloaders::{bodies::noop::NoopBodiesDownloader, headers::noop::NoopHe
 use reth_engine_tree::tree::{InvalidBlockHook, InvalidBlockHooks, NoopInvalidBlockHook};
 use reth_evm::noop::NoopBlockExecutorProvider;
 use reth_fs_util as fs;
-use reth_invalid_block_hooks::InvalidBlockWitnessHook;
+use reth_invalid_block_hooks::{InvalidBlockStatelessHook, InvalidBlockWitnessHook};
 use reth_network_p2p::headers::client::HeadersClient;
 use reth_node_api::{FullNodeTypes, NodeTypes, NodeTypesWithDB};
 use reth_node_core::{
@@ -411,7 +411,8 @@ where
         let factory = ProviderFactory::new(
             self.right().clone(),
             self.chain_spec(),
-            StaticFileProvider::read_write(self.data_dir().static_files())?,
+            StaticFileProvider::read_write(self.data_dir().static_files())?
+                .with_segment_configs(self.toml_config().static_files.segments()),
         )
         .with_prune_modes(self.prune_modes())
         .with_static_files_metrics();
@@ -906,6 +907,12 @@ where
                         output_directory,
                         healthy_node_rpc_client.clone(),
                     )),
+                    InvalidBlockHookType::Stateless => Box::new(InvalidBlockStatelessHook::new(
+                        self.blockchain_db().clone(),
+                        self.components().block_executor().clone(),
+                        self.consensus(),
+                        output_directory,
+                    )),
                     InvalidBlockHookType::PreState | InvalidBlockHookType::Opcode => {
                         eyre::bail!("invalid block hook {hook:?} is not implemented yet")
                     }
@@ -1079,6 +1086,12 @@ mod tests {
                     storage_history_full: false,
                     storage_history_distance: None,
                     storage_history_before: None,
+                    headers_history_distance: None,
+                    headers_history_before: None,
+                    headers_history_pre_merge: false,
+                    bodies_history_distance: None,
+                    bodies_history_before: None,
+                    bodies_history_pre_merge: false,
                     receipts_log_filter: vec![],
                 },
                 ..NodeConfig::test()
@@ -34,7 +34,7 @@ use reth_rpc_engine_api::{capabilities::EngineCapabilities, EngineApi};
 use reth_tasks::TaskExecutor;
 use reth_tokio_util::EventSender;
 use reth_tracing::tracing::{debug, error, info};
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 use tokio::sync::{mpsc::unbounded_channel, oneshot};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
@@ -162,6 +162,24 @@ where
                 node_config.debug.reorg_frequency,
                 node_config.debug.reorg_depth,
             )
+            .maybe_delay(
+                node_config.debug.engine_delay_ms.map(Duration::from_millis),
+                node_config.debug.engine_jitter_ms.map(Duration::from_millis),
+                node_config.debug.chaos_seed_for(1),
+            )
+            .maybe_duplicate(
+                node_config.debug.duplicate_messages,
+                node_config.debug.chaos_seed_for(2),
+            )
+            .maybe_reorder_fcu(node_config.debug.reorder_fcu, node_config.debug.chaos_seed_for(3))
+            .maybe_out_of_order_payloads(
+                node_config.debug.out_of_order_payloads,
+                node_config.debug.chaos_seed_for(4),
+            )
+            .maybe_invalid_payloads(
+                node_config.debug.invalid_payloads,
+                node_config.debug.chaos_seed_for(5),
+            )
             // Store messages _after_ skipping so that `replay-engine` command
             // would replay only the messages that were observed by the engine
             // during this run.
@@ -214,6 +232,7 @@ where
         let mut eth_service = EngineService::new(
             ctx.consensus(),
             ctx.components().block_executor().clone(),
+            ctx.components().evm_config().clone(),
             ctx.chain_spec(),
             network_client.clone(),
             Box::pin(consensus_engine_stream),
@@ -9,7 +9,7 @@ pub use common::LaunchContext;
 use common::{Attached, LaunchContextWith, WithConfigs};
 pub use exex::ExExLauncher;
 
-use std::{future::Future, sync::Arc};
+use std::{future::Future, sync::Arc, time::Duration};
 
 use alloy_primitives::utils::format_ether;
 use alloy_rpc_types::engine::ClientVersionV1;
@@ -201,6 +201,24 @@ where
                 node_config.debug.reorg_frequency,
                 node_config.debug.reorg_depth,
             )
+            .maybe_delay(
+                node_config.debug.engine_delay_ms.map(Duration::from_millis),
+                node_config.debug.engine_jitter_ms.map(Duration::from_millis),
+                node_config.debug.chaos_seed_for(1),
+            )
+            .maybe_duplicate(
+                node_config.debug.duplicate_messages,
+                node_config.debug.chaos_seed_for(2),
+            )
+            .maybe_reorder_fcu(node_config.debug.reorder_fcu, node_config.debug.chaos_seed_for(3))
+            .maybe_out_of_order_payloads(
+                node_config.debug.out_of_order_payloads,
+                node_config.debug.chaos_seed_for(4),
+            )
+            .maybe_invalid_payloads(
+                node_config.debug.invalid_payloads,
+                node_config.debug.chaos_seed_for(5),
+            )
             // Store messages _after_ skipping so that `replay-engine` command
             // would replay only the messages that were observed by the engine
             // during this run.
@@ -62,8 +62,62 @@ pub struct DebugArgs {
     #[arg(long = "debug.reorg-depth", requires = "reorg_frequency", help_heading = "Debug")]
     pub reorg_depth: Option<usize>,
 
+    /// The seed for the randomness of the engine API fault injection flags.
+    #[arg(long = "debug.chaos-seed", help_heading = "Debug", default_value_t = 0)]
+    pub chaos_seed: u64,
+
+    /// If provided, every engine API message will be delayed by the specified number of
+    /// milliseconds.
+    #[arg(long = "debug.engine-delay-ms", help_heading = "Debug", value_name = "MILLIS")]
+    pub engine_delay_ms: Option<u64>,
+
+    /// If provided, every engine API message will be delayed by an additional random number of
+    /// milliseconds up to the specified maximum.
+    #[arg(long = "debug.engine-jitter-ms", help_heading = "Debug", value_name = "MILLIS")]
+    pub engine_jitter_ms: Option<u64>,
+
+    /// If provided, engine API messages will be duplicated with the specified probability in
+    /// percent.
+    #[arg(
+        long = "debug.duplicate-messages",
+        help_heading = "Debug",
+        value_name = "PERCENT",
+        value_parser = clap::value_parser!(u8).range(1..=100)
+    )]
+    pub duplicate_messages: Option<u8>,
+
+    /// If provided, forkchoice updates will be delivered after the next new payload with the
+    /// specified probability in percent.
+    #[arg(
+        long = "debug.reorder-fcu",
+        help_heading = "Debug",
+        value_name = "PERCENT",
+        value_parser = clap::value_parser!(u8).range(1..=100)
+    )]
+    pub reorder_fcu: Option<u8>,
+
+    /// If provided, new payloads will be delivered after the next new payload with the specified
+    /// probability in percent.
+    #[arg(
+        long = "debug.out-of-order-payloads",
+        help_heading = "Debug",
+        value_name = "PERCENT",
+        value_parser = clap::value_parser!(u8).range(1..=100)
+    )]
+    pub out_of_order_payloads: Option<u8>,
+
+    /// If provided, an invalid sibling will be injected before new payloads with the specified
+    /// probability in percent.
+    #[arg(
+        long = "debug.invalid-payloads",
+        help_heading = "Debug",
+        value_name = "PERCENT",
+        value_parser = clap::value_parser!(u8).range(1..=100)
+    )]
+    pub invalid_payloads: Option<u8>,
+
     /// The path to store engine API messages at.
-    /// If specified, all of the intercepted engine API messages
+    /// If specified, all of the intercepted engine API messages and the responses to them
     /// will be written to specified location.
     #[arg(long = "debug.engine-api-store", help_heading = "Debug", value_name = "PATH")]
     pub engine_api_store: Option<PathBuf>,
@@ -89,6 +143,17 @@ pub struct DebugArgs {
     pub healthy_node_rpc_url: Option<String>,
 }
 
+impl DebugArgs {
+    /// Returns the seed of the fault injection flag with the given index, derived from the
+    /// [`Self::chaos_seed`].
+    ///
+    /// Every flag gets its own seed, so that enabling one flag doesn't change the decisions of
+    /// another flag with the same probability.
+    pub const fn chaos_seed_for(&self, index: u64) -> u64 {
+        self.chaos_seed ^ index
+    }
+}
+
 impl Default for DebugArgs {
     fn default() -> Self {
         Self {
@@ -101,6 +166,13 @@ impl Default for DebugArgs {
             skip_new_payload: None,
             reorg_frequency: None,
             reorg_depth: None,
+            chaos_seed: 0,
+            engine_delay_ms: None,
+            engine_jitter_ms: None,
+            duplicate_messages: None,
+            reorder_fcu: None,
+            out_of_order_payloads: None,
+            invalid_payloads: None,
             engine_api_store: None,
             invalid_block_hook: Some(InvalidBlockSelection::default()),
             healthy_node_rpc_url: None,
@@ -280,6 +352,8 @@ pub enum InvalidBlockHookType {
     PreState,
     /// An opcode trace value enum
     Opcode,
+    /// A stateless validation value enum
+    Stateless,
 }
 
 impl FromStr for InvalidBlockHookType {
@@ -290,6 +364,7 @@ impl FromStr for InvalidBlockHookType {
             "witness" => Self::Witness,
             "prestate" => Self::PreState,
             "opcode" => Self::Opcode,
+            "stateless" => Self::Stateless,
             _ => return Err(ParseError::VariantNotFound),
         })
     }
@@ -26,13 +26,14 @@ use reth_network::{
                 DEFAULT_MAX_COUNT_PENDING_POOL_IMPORTS, DEFAULT_MAX_COUNT_TRANSACTIONS_SEEN_BY_PEER,
             },
         },
+        private::{PrivateRelayConfig, PrivateTxFallback},
         TransactionFetcherConfig, TransactionsManagerConfig,
         DEFAULT_SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESP_ON_PACK_GET_POOLED_TRANSACTIONS_REQ,
         SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESPONSE,
     },
     HelloMessageWithProtocols, NetworkConfigBuilder, SessionsConfig,
 };
-use reth_network_peers::{mainnet_nodes, TrustedPeer};
+use reth_network_peers::{mainnet_nodes, PeerId, TrustedPeer};
 use secp256k1::SecretKey;
 use tracing::error;
 
@@ -154,6 +155,22 @@ pub struct NetworkArgs {
     /// If flag is set, but no value is passed, the default interface for docker `eth0` is tried.
     #[arg(long = "net-if.experimental", conflicts_with = "addr", value_name = "IF_NAME")]
     pub net_if: Option<String>,
+
+    /// Comma separated ids of trusted peers that private transactions are relayed to over the
+    /// `prtx` subprotocol. Transactions relayed by these peers are accepted as private
+    /// transactions.
+    #[arg(long = "private-tx.peers", value_delimiter = ',', value_name = "PEER_ID")]
+    pub private_tx_peers: Vec<PeerId>,
+
+    /// Comma separated HTTP JSON-RPC endpoints that private transactions are relayed to with
+    /// `eth_sendRawTransaction`.
+    #[arg(long = "private-tx.endpoints", value_delimiter = ',', value_name = "URL")]
+    pub private_tx_endpoints: Vec<String>,
+
+    /// Propagate private transactions to the network if they weren't included in time, instead
+    /// of dropping them.
+    #[arg(long = "private-tx.propagate-fallback")]
+    pub private_tx_propagate_fallback: bool,
 }
 
 impl NetworkArgs {
@@ -297,6 +314,25 @@ impl NetworkArgs {
         self.discovery.adjust_instance_ports(instance);
     }
 
+    /// Returns the configuration of the private transaction relay, if any relay target is
+    /// configured.
+    pub fn private_relay_config(&self) -> Option<PrivateRelayConfig> {
+        if self.private_tx_peers.is_empty() && self.private_tx_endpoints.is_empty() {
+            return None
+        }
+        let mut config = PrivateRelayConfig::default();
+        for peer_id in &self.private_tx_peers {
+            config = config.with_peer(*peer_id);
+        }
+        for endpoint in &self.private_tx_endpoints {
+            config = config.with_endpoint(endpoint.clone());
+        }
+        if self.private_tx_propagate_fallback {
+            config.fallback = PrivateTxFallback::Propagate;
+        }
+        Some(config)
+    }
+
     /// Resolve all trusted peers at once
     pub async fn resolve_trusted_peers(&self) -> Result<Vec<NodeRecord>, std::io::Error> {
         futures::future::try_join_all(
@@ -332,6 +368,9 @@ impl Default for NetworkArgs {
             max_seen_tx_history: DEFAULT_MAX_COUNT_TRANSACTIONS_SEEN_BY_PEER,
             max_capacity_cache_txns_pending_fetch: DEFAULT_MAX_CAPACITY_CACHE_PENDING_FETCH,
             net_if: None,
+            private_tx_peers: vec![],
+            private_tx_endpoints: vec![],
+            private_tx_propagate_fallback: false,
         }
     }
 }
@@ -520,6 +559,7 @@ impl Default for DiscoveryArgs {
 mod tests {
     use super::*;
     use clap::Parser;
+    use reth_network::transactions::private::DEFAULT_RELAY_FALLBACK_AFTER_BLOCKS;
     /// A helper type to parse Args more easily
     #[derive(Parser)]
     struct CommandParser<T: Args> {
@@ -591,6 +631,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_private_tx_args() {
+        let args = CommandParser::<NetworkArgs>::parse_from(["reth"]).args;
+        assert!(args.private_relay_config().is_none());
+
+        let peer_id = PeerId::random();
+        let args = CommandParser::<NetworkArgs>::parse_from([
+            "reth",
+            "--private-tx.peers",
+            &peer_id.to_string(),
+            "--private-tx.endpoints",
+            "http://localhost:8545",
+            "--private-tx.propagate-fallback",
+        ])
+        .args;
+        let config = args.private_relay_config().unwrap();
+        assert_eq!(
+            config,
+            PrivateRelayConfig::default()
+                .with_peer(peer_id)
+                .with_endpoint("http://localhost:8545")
+                .with_fallback(PrivateTxFallback::Propagate, DEFAULT_RELAY_FALLBACK_AFTER_BLOCKS)
+        );
+    }
+
     #[cfg(not(feature = "optimism"))]
     #[test]
     fn network_args_default_sanity_test() {
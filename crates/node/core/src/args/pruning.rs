@@ -82,6 +82,34 @@ pub struct PruningArgs {
     #[arg(long = "prune.storagehistory.before", value_name = "BLOCK_NUMBER", conflicts_with_all = &["storage_history_full", "storage_history_distance"])]
     pub storage_history_before: Option<BlockNumber>,
 
+    // Headers History
+    /// Prune headers before the `head-N` block number. In other words, keep last N + 1 blocks.
+    /// Headers are deleted in whole static files, so more blocks may be kept.
+    #[arg(long = "prune.headers.distance", value_name = "BLOCKS", conflicts_with_all = &["headers_history_before", "headers_history_pre_merge"])]
+    pub headers_history_distance: Option<u64>,
+    /// Prune headers before the specified block number. The specified block number is not
+    /// pruned.
+    #[arg(long = "prune.headers.before", value_name = "BLOCK_NUMBER", conflicts_with_all = &["headers_history_distance", "headers_history_pre_merge"])]
+    pub headers_history_before: Option<BlockNumber>,
+    /// Prune headers before the Paris (Merge) hardfork block. Ignored if the chain doesn't know
+    /// its Paris block.
+    #[arg(long = "prune.headers.pre-merge", conflicts_with_all = &["headers_history_distance", "headers_history_before"])]
+    pub headers_history_pre_merge: bool,
+
+    // Bodies History
+    /// Prune block transactions before the `head-N` block number. In other words, keep last N + 1
+    /// blocks. Transactions are deleted in whole static files, so more blocks may be kept.
+    #[arg(long = "prune.bodies.distance", value_name = "BLOCKS", conflicts_with_all = &["bodies_history_before", "bodies_history_pre_merge"])]
+    pub bodies_history_distance: Option<u64>,
+    /// Prune block transactions before the specified block number. The specified block number is
+    /// not pruned.
+    #[arg(long = "prune.bodies.before", value_name = "BLOCK_NUMBER", conflicts_with_all = &["bodies_history_distance", "bodies_history_pre_merge"])]
+    pub bodies_history_before: Option<BlockNumber>,
+    /// Prune block transactions before the Paris (Merge) hardfork block. Ignored if the chain
+    /// doesn't know its Paris block.
+    #[arg(long = "prune.bodies.pre-merge", conflicts_with_all = &["bodies_history_distance", "bodies_history_before"])]
+    pub bodies_history_pre_merge: bool,
+
     // Receipts Log Filter
     /// Configure receipts log filter. Format:
     /// <`address`>:<`prune_mode`>[,<`address`>:<`prune_mode`>...] Where <`prune_mode`> can be
@@ -111,6 +139,8 @@ impl PruningArgs {
                         .or(Some(PruneMode::Distance(MINIMUM_PRUNING_DISTANCE))),
                     account_history: Some(PruneMode::Distance(MINIMUM_PRUNING_DISTANCE)),
                     storage_history: Some(PruneMode::Distance(MINIMUM_PRUNING_DISTANCE)),
+                    headers_history: None,
+                    bodies_history: None,
                     receipts_log_filter: ReceiptsLogPruneConfig(
                         chain_spec
                             .deposit_contract()
@@ -138,6 +168,12 @@ impl PruningArgs {
         if let Some(mode) = self.storage_history_prune_mode() {
             config.segments.storage_history = Some(mode);
         }
+        if let Some(mode) = self.headers_history_prune_mode(chain_spec) {
+            config.segments.headers_history = Some(mode);
+        }
+        if let Some(mode) = self.bodies_history_prune_mode(chain_spec) {
+            config.segments.bodies_history = Some(mode);
+        }
 
         Some(config)
     }
@@ -200,6 +236,30 @@ impl PruningArgs {
             None
         }
     }
+
+    fn headers_history_prune_mode(&self, chain_spec: &impl EthChainSpec) -> Option<PruneMode> {
+        if let Some(distance) = self.headers_history_distance {
+            Some(PruneMode::Distance(distance))
+        } else if let Some(block_number) = self.headers_history_before {
+            Some(PruneMode::Before(block_number))
+        } else if self.headers_history_pre_merge {
+            chain_spec.paris_block().map(PruneMode::Before)
+        } else {
+            None
+        }
+    }
+
+    fn bodies_history_prune_mode(&self, chain_spec: &impl EthChainSpec) -> Option<PruneMode> {
+        if let Some(distance) = self.bodies_history_distance {
+            Some(PruneMode::Distance(distance))
+        } else if let Some(block_number) = self.bodies_history_before {
+            Some(PruneMode::Before(block_number))
+        } else if self.bodies_history_pre_merge {
+            chain_spec.paris_block().map(PruneMode::Before)
+        } else {
+            None
+        }
+    }
 }
 
 pub(crate) fn parse_receipts_log_filter(
@@ -249,6 +309,7 @@ pub(crate) fn parse_receipts_log_filter(
 mod tests {
     use super::*;
     use clap::Parser;
+    use reth_chainspec::MAINNET;
 
     /// A helper type to parse Args more easily
     #[derive(Parser)]
@@ -264,6 +325,21 @@ mod tests {
         assert_eq!(args, default_args);
     }
 
+    #[test]
+    fn pruning_args_pre_merge() {
+        let args = CommandParser::<PruningArgs>::parse_from([
+            "reth",
+            "--prune.headers.distance",
+            "20000",
+            "--prune.bodies.pre-merge",
+        ])
+        .args;
+
+        let segments = args.prune_config(&*MAINNET).unwrap().segments;
+        assert_eq!(segments.headers_history, Some(PruneMode::Distance(20_000)));
+        assert_eq!(segments.bodies_history, Some(PruneMode::Before(15_537_394)));
+    }
+
     #[test]
     fn test_parse_receipts_log_filter() {
         let filter1 = "0x0000000000000000000000000000000000000001:full";
@@ -2,17 +2,26 @@
 
 use crate::cli::config::RethTransactionPoolConfig;
 use alloy_primitives::Address;
-use clap::Args;
+use clap::{Args, ValueEnum};
+use humantime::parse_duration;
 use reth_primitives::constants::{ETHEREUM_BLOCK_GAS_LIMIT, MIN_PROTOCOL_BASE_FEE};
 use reth_transaction_pool::{
-    blobstore::disk::DEFAULT_MAX_CACHED_BLOBS,
+    blobstore::{
+        disk::DEFAULT_MAX_CACHED_BLOBS, static_file::DEFAULT_BLOB_RETENTION_BLOCKS,
+        StaticFileBlobStoreConfig,
+    },
+    maintain::{LocalTransactionBackupConfig, DEFAULT_TRANSACTIONS_BACKUP_MAX_SIZE},
     pool::{NEW_TX_LISTENER_BUFFER_SIZE, PENDING_TX_LISTENER_BUFFER_SIZE},
     validate::DEFAULT_MAX_TX_INPUT_BYTES,
-    LocalTransactionConfig, PoolConfig, PriceBumpConfig, SubPoolLimit, DEFAULT_PRICE_BUMP,
-    DEFAULT_TXPOOL_ADDITIONAL_VALIDATION_TASKS, REPLACE_BLOB_PRICE_BUMP,
-    TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER, TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT,
+    AgeEviction, EffectiveTipEviction, EvictionStrategy, FairnessEviction, LocalTransactionConfig,
+    PoolConfig, PriceBumpConfig, SenderQuotaEviction, SourceQuotas, SubPoolLimit,
+    DEFAULT_PRICE_BUMP, DEFAULT_TXPOOL_ADDITIONAL_VALIDATION_TASKS, REPLACE_BLOB_PRICE_BUMP,
+    TXPOOL_JOURNAL_MAX_TRANSACTIONS_DEFAULT, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
+    TXPOOL_MAX_INFLIGHT_DELEGATED_SLOTS, TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT,
     TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
 };
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
 /// Parameters for debugging purposes
 #[derive(Debug, Clone, Args, PartialEq, Eq)]
 #[command(next_help_heading = "TxPool")]
@@ -42,6 +51,44 @@ pub struct TxPoolArgs {
     #[arg(long = "txpool.max-account-slots", alias = "txpool.max_account_slots", default_value_t = TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER)]
     pub max_account_slots: usize,
 
+    /// Max number of local transactions that may be in the pool
+    #[arg(long = "txpool.local-max-count")]
+    pub local_max_count: Option<usize>,
+    /// Max size of the local transactions that may be in the pool, in megabytes
+    #[arg(long = "txpool.local-max-size")]
+    pub local_max_size: Option<usize>,
+
+    /// Max number of external transactions that may be in the pool
+    #[arg(long = "txpool.external-max-count")]
+    pub external_max_count: Option<usize>,
+    /// Max size of the external transactions that may be in the pool, in megabytes
+    #[arg(long = "txpool.external-max-size")]
+    pub external_max_size: Option<usize>,
+
+    /// Max number of private transactions that may be in the pool
+    #[arg(long = "txpool.private-max-count")]
+    pub private_max_count: Option<usize>,
+    /// Max size of the private transactions that may be in the pool, in megabytes
+    #[arg(long = "txpool.private-max-size")]
+    pub private_max_size: Option<usize>,
+
+    /// Max number of transactions propagated by a single peer that may be in the pool
+    #[arg(long = "txpool.peer-max-count")]
+    pub peer_max_count: Option<usize>,
+    /// Max size of the transactions propagated by a single peer that may be in the pool, in
+    /// megabytes
+    #[arg(long = "txpool.peer-max-size")]
+    pub peer_max_size: Option<usize>,
+
+    /// Strategy that picks the transactions evicted from a full sub-pool, instead of the
+    /// sub-pool's own ordering
+    #[arg(long = "txpool.eviction-strategy", value_enum)]
+    pub eviction_strategy: Option<TxPoolEvictionStrategy>,
+    /// Combined size of the transactions a single sender may have in a sub-pool, in megabytes,
+    /// before its transactions are evicted first. Only used by the `sender-quota` strategy.
+    #[arg(long = "txpool.eviction-max-sender-size", default_value_t = DEFAULT_EVICTION_MAX_SENDER_SIZE_MB)]
+    pub eviction_max_sender_size: usize,
+
     /// Price bump (in %) for the transaction pool underpriced check.
     #[arg(long = "txpool.pricebump", default_value_t = DEFAULT_PRICE_BUMP)]
     pub price_bump: u128,
@@ -58,6 +105,18 @@ pub struct TxPoolArgs {
     #[arg(long = "blobpool.pricebump", default_value_t = REPLACE_BLOB_PRICE_BUMP)]
     pub blob_transaction_price_bump: u128,
 
+    /// Keep the blobs of finalized blocks in static files for the data availability window, so
+    /// they can still be served once their transactions were included.
+    ///
+    /// Ignored on chains without blob transactions, like Optimism.
+    #[arg(long = "blobpool.archive")]
+    pub blob_archive: bool,
+
+    /// Number of blocks the blobs of finalized blocks are kept for, if `--blobpool.archive` is
+    /// set.
+    #[arg(long = "blobpool.archive-retention", default_value_t = DEFAULT_BLOB_RETENTION_BLOCKS)]
+    pub blob_archive_retention: u64,
+
     /// Max size in bytes of a single transaction allowed to enter the pool
     #[arg(long = "txpool.max-tx-input-bytes", alias = "txpool.max_tx_input_bytes", default_value_t = DEFAULT_MAX_TX_INPUT_BYTES)]
     pub max_tx_input_bytes: usize,
@@ -86,6 +145,32 @@ pub struct TxPoolArgs {
     /// Maximum number of new transactions to buffer
     #[arg(long = "txpool.max-new-txns", alias = "txpool.max_new_txns", default_value_t = NEW_TX_LISTENER_BUFFER_SIZE)]
     pub new_tx_listener_buffer_size: usize,
+
+    /// Maximum number of transactions whose lifecycle is recorded by the transaction journal, 0
+    /// disables the journal
+    #[arg(long = "txpool.journal-size", default_value_t = TXPOOL_JOURNAL_MAX_TRANSACTIONS_DEFAULT)]
+    pub journal_size: usize,
+
+    /// Simulate pending transactions on top of the latest block, the results are served by the
+    /// `txpool_simulation` RPC method.
+    #[arg(long = "txpool.simulate")]
+    pub simulate: bool,
+
+    /// Persist all transactions of the pool on shutdown, including blob sidecars, instead of only
+    /// local transactions.
+    #[arg(long = "txpool.persist-all")]
+    pub persist_all: bool,
+
+    /// Interval at which the persisted transactions are written while the node is running.
+    ///
+    /// Parses strings using [`humantime::parse_duration`]
+    /// --txpool.persist-interval 5m
+    #[arg(long = "txpool.persist-interval", value_parser = parse_duration, verbatim_doc_comment)]
+    pub persist_interval: Option<Duration>,
+
+    /// Max size of the persisted transactions in megabytes.
+    #[arg(long = "txpool.persist-max-size", default_value_t = DEFAULT_TRANSACTIONS_BACKUP_MAX_SIZE / 1024 / 1024)]
+    pub persist_max_size: usize,
 }
 
 impl Default for TxPoolArgs {
@@ -98,10 +183,22 @@ impl Default for TxPoolArgs {
             queued_max_count: TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
             queued_max_size: TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT,
             max_account_slots: TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
+            local_max_count: None,
+            local_max_size: None,
+            external_max_count: None,
+            external_max_size: None,
+            private_max_count: None,
+            private_max_size: None,
+            peer_max_count: None,
+            peer_max_size: None,
+            eviction_strategy: None,
+            eviction_max_sender_size: DEFAULT_EVICTION_MAX_SENDER_SIZE_MB,
             price_bump: DEFAULT_PRICE_BUMP,
             minimal_protocol_basefee: MIN_PROTOCOL_BASE_FEE,
             gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
             blob_transaction_price_bump: REPLACE_BLOB_PRICE_BUMP,
+            blob_archive: false,
+            blob_archive_retention: DEFAULT_BLOB_RETENTION_BLOCKS,
             max_tx_input_bytes: DEFAULT_MAX_TX_INPUT_BYTES,
             max_cached_entries: DEFAULT_MAX_CACHED_BLOBS,
             no_locals: false,
@@ -110,10 +207,48 @@ impl Default for TxPoolArgs {
             additional_validation_tasks: DEFAULT_TXPOOL_ADDITIONAL_VALIDATION_TASKS,
             pending_tx_listener_buffer_size: PENDING_TX_LISTENER_BUFFER_SIZE,
             new_tx_listener_buffer_size: NEW_TX_LISTENER_BUFFER_SIZE,
+            journal_size: TXPOOL_JOURNAL_MAX_TRANSACTIONS_DEFAULT,
+            simulate: false,
+            persist_all: false,
+            persist_interval: None,
+            persist_max_size: DEFAULT_TRANSACTIONS_BACKUP_MAX_SIZE / 1024 / 1024,
         }
     }
 }
 
+impl TxPoolArgs {
+    /// Returns the quotas of transactions per origin and per peer.
+    const fn quotas(&self) -> SourceQuotas {
+        SourceQuotas {
+            local: quota(self.local_max_count, self.local_max_size),
+            external: quota(self.external_max_count, self.external_max_size),
+            private: quota(self.private_max_count, self.private_max_size),
+            peer: quota(self.peer_max_count, self.peer_max_size),
+        }
+    }
+
+    /// Returns the configured eviction strategy, if any.
+    fn eviction_strategy(&self) -> Option<Arc<dyn EvictionStrategy>> {
+        let strategy: Arc<dyn EvictionStrategy> = match self.eviction_strategy? {
+            TxPoolEvictionStrategy::EffectiveTip => Arc::new(EffectiveTipEviction),
+            TxPoolEvictionStrategy::Age => Arc::new(AgeEviction),
+            TxPoolEvictionStrategy::SenderQuota => Arc::new(SenderQuotaEviction {
+                max_sender_size: self.eviction_max_sender_size * 1024 * 1024,
+            }),
+            TxPoolEvictionStrategy::Fairness => Arc::new(FairnessEviction),
+        };
+        Some(strategy)
+    }
+
+    /// Returns the configuration of the static files that keep the blobs of finalized blocks, if
+    /// enabled.
+    pub fn blob_archive_config(&self) -> Option<StaticFileBlobStoreConfig> {
+        self.blob_archive.then(|| {
+            StaticFileBlobStoreConfig::default().with_retention_blocks(self.blob_archive_retention)
+        })
+    }
+}
+
 impl RethTransactionPoolConfig for TxPoolArgs {
     /// Returns transaction pool configuration.
     fn pool_config(&self) -> PoolConfig {
@@ -140,6 +275,7 @@ impl RethTransactionPoolConfig for TxPoolArgs {
                 max_size: self.queued_max_size * 1024 * 1024,
             },
             max_account_slots: self.max_account_slots,
+            max_inflight_delegated_slots: TXPOOL_MAX_INFLIGHT_DELEGATED_SLOTS,
             price_bumps: PriceBumpConfig {
                 default_price_bump: self.price_bump,
                 replace_blob_tx_price_bump: self.blob_transaction_price_bump,
@@ -148,8 +284,55 @@ impl RethTransactionPoolConfig for TxPoolArgs {
             gas_limit: self.gas_limit,
             pending_tx_listener_buffer_size: self.pending_tx_listener_buffer_size,
             new_tx_listener_buffer_size: self.new_tx_listener_buffer_size,
+            max_journal_transactions: self.journal_size,
+            eviction_strategy: self.eviction_strategy(),
+            quotas: self.quotas(),
         }
     }
+
+    /// Returns the transactions backup configuration.
+    fn transactions_backup_config(
+        &self,
+        transactions_path: PathBuf,
+    ) -> LocalTransactionBackupConfig {
+        LocalTransactionBackupConfig::with_local_txs_backup(transactions_path)
+            .with_all_transactions(self.persist_all)
+            .with_interval(self.persist_interval)
+            .with_max_size(self.persist_max_size * 1024 * 1024)
+    }
+}
+
+/// Returns the quota for the given max count and max size in megabytes, if any of them is set.
+const fn quota(max_count: Option<usize>, max_size: Option<usize>) -> Option<SubPoolLimit> {
+    if max_count.is_none() && max_size.is_none() {
+        return None
+    }
+    let max_txs = match max_count {
+        Some(count) => count,
+        None => usize::MAX,
+    };
+    let max_size = match max_size {
+        Some(size) => size * 1024 * 1024,
+        None => usize::MAX,
+    };
+    Some(SubPoolLimit { max_txs, max_size })
+}
+
+/// Default combined size of the transactions of a single sender in a sub-pool, in megabytes, for
+/// the `sender-quota` eviction strategy.
+pub const DEFAULT_EVICTION_MAX_SENDER_SIZE_MB: usize = 1;
+
+/// The strategy that picks the transactions evicted from a full sub-pool.
+#[derive(Debug, Copy, Clone, ValueEnum, Eq, PartialEq)]
+pub enum TxPoolEvictionStrategy {
+    /// Evict the transaction with the lowest effective tip first
+    EffectiveTip,
+    /// Evict the most recently added transaction first
+    Age,
+    /// Evict transactions of the sender that exceeds its size quota the most first
+    SenderQuota,
+    /// Evict by effective tip divided by the number of transactions of the sender first
+    Fairness,
 }
 
 #[cfg(test)]
@@ -181,4 +364,61 @@ mod tests {
         .args;
         assert_eq!(args.locals, vec![Address::ZERO]);
     }
+
+    #[test]
+    fn txpool_parse_persist() {
+        let args = CommandParser::<TxPoolArgs>::parse_from([
+            "reth",
+            "--txpool.persist-all",
+            "--txpool.persist-interval",
+            "5m",
+            "--txpool.persist-max-size",
+            "64",
+        ])
+        .args;
+        let config =
+            args.transactions_backup_config(PathBuf::from("txpool-transactions-backup.rlp"));
+        assert!(config.all_transactions);
+        assert_eq!(config.interval, Some(Duration::from_secs(300)));
+        assert_eq!(config.max_size, 64 * 1024 * 1024);
+    }
+
+    #[test]
+    fn txpool_parse_eviction_strategy() {
+        let args = CommandParser::<TxPoolArgs>::parse_from([
+            "reth",
+            "--txpool.eviction-strategy",
+            "sender-quota",
+            "--txpool.eviction-max-sender-size",
+            "2",
+        ])
+        .args;
+        assert_eq!(args.eviction_strategy, Some(TxPoolEvictionStrategy::SenderQuota));
+        let strategy = args.pool_config().eviction_strategy.unwrap();
+        assert_eq!(strategy.name(), "sender_quota");
+
+        let args = CommandParser::<TxPoolArgs>::parse_from(["reth"]).args;
+        assert!(args.pool_config().eviction_strategy.is_none());
+    }
+
+    #[test]
+    fn txpool_parse_quotas() {
+        let args = CommandParser::<TxPoolArgs>::parse_from([
+            "reth",
+            "--txpool.local-max-count",
+            "100",
+            "--txpool.external-max-size",
+            "32",
+            "--txpool.private-max-count",
+            "10",
+            "--txpool.private-max-size",
+            "1",
+        ])
+        .args;
+        let quotas = args.pool_config().quotas;
+        assert_eq!(quotas.local, Some(SubPoolLimit::new(100, usize::MAX)));
+        assert_eq!(quotas.external, Some(SubPoolLimit::new(usize::MAX, 32 * 1024 * 1024)));
+        assert_eq!(quotas.private, Some(SubPoolLimit::new(10, 1024 * 1024)));
+        assert_eq!(quotas.peer, None);
+    }
 }
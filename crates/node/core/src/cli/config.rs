@@ -2,8 +2,8 @@
 
 use alloy_primitives::Bytes;
 use reth_network::protocol::IntoRlpxSubProtocol;
-use reth_transaction_pool::PoolConfig;
-use std::{borrow::Cow, time::Duration};
+use reth_transaction_pool::{maintain::LocalTransactionBackupConfig, PoolConfig};
+use std::{borrow::Cow, path::PathBuf, time::Duration};
 
 /// A trait that provides payload builder settings.
 ///
@@ -64,4 +64,10 @@ impl RethNetworkConfig for reth_network::NetworkManager {
 pub trait RethTransactionPoolConfig {
     /// Returns transaction pool configuration.
     fn pool_config(&self) -> PoolConfig;
+
+    /// Returns the configuration of the transactions backup, written to `transactions_path`.
+    fn transactions_backup_config(
+        &self,
+        transactions_path: PathBuf,
+    ) -> LocalTransactionBackupConfig;
 }
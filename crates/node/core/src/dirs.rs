@@ -323,6 +323,14 @@ impl<D> ChainPath<D> {
         self.data_dir().join("blobstore")
     }
 
+    /// Returns the path to the directory for this chain where the blobs of finalized blocks are
+    /// kept, if enabled.
+    ///
+    /// `<DIR>/<CHAIN_ID>/blobstore-static-files`
+    pub fn blobstore_static_files(&self) -> PathBuf {
+        self.data_dir().join("blobstore-static-files")
+    }
+
     /// Returns the path to the local transactions backup file
     ///
     /// `<DIR>/<CHAIN_ID>/txpool-transactions-backup.rlp`
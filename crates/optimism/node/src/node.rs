@@ -22,10 +22,10 @@ use reth_optimism_rpc::OpEthApi;
 use reth_payload_builder::{PayloadBuilderHandle, PayloadBuilderService};
 use reth_primitives::Header;
 use reth_provider::CanonStateSubscriptions;
-use reth_tracing::tracing::{debug, info};
+use reth_tracing::tracing::{debug, info, warn};
 use reth_transaction_pool::{
-    blobstore::DiskFileBlobStore, CoinbaseTipOrdering, TransactionPool,
-    TransactionValidationTaskExecutor,
+    blobstore::DiskFileBlobStore, validate::TransactionPolicies, CoinbaseTipOrdering,
+    TransactionPool, TransactionValidationTaskExecutor,
 };
 
 use crate::{
@@ -171,6 +171,8 @@ where
 pub struct OptimismPoolBuilder {
     /// Enforced overrides that are applied to the pool config.
     pub pool_config_overrides: PoolBuilderConfigOverrides,
+    /// Admission policies checked in addition to the ones of the reth.toml config.
+    pub policies: TransactionPolicies,
 }
 
 impl<Node> PoolBuilder<Node> for OptimismPoolBuilder
@@ -180,9 +182,15 @@ where
     type Pool = OpTransactionPool<Node::Provider, DiskFileBlobStore>;
 
     async fn build_pool(self, ctx: &BuilderContext<Node>) -> eyre::Result<Self::Pool> {
-        let Self { pool_config_overrides } = self;
+        let Self { pool_config_overrides, policies } = self;
         let data_dir = ctx.config().datadir();
+        // blob transactions are rejected by the validator, so there are no blobs to archive
+        if ctx.config().txpool.blob_archive {
+            warn!(target: "reth::cli", "--blobpool.archive is ignored, blob transactions are not supported");
+        }
         let blob_store = DiskFileBlobStore::open(data_dir.blobstore(), Default::default())?;
+        let mut pool_config = pool_config_overrides.apply(ctx.pool_config());
+        pool_config.policies.extend(policies);
 
         let validator = TransactionValidationTaskExecutor::eth_builder(Arc::new(
             ctx.chain_spec().inner.clone(),
@@ -190,6 +198,7 @@ where
         .with_head_timestamp(ctx.head().timestamp)
         .kzg_settings(ctx.kzg_settings()?)
         .with_additional_tasks(ctx.config().txpool.additional_validation_tasks)
+        .with_max_inflight_delegated_slots(pool_config.max_inflight_delegated_slots)
         .build_with_tasks(ctx.provider().clone(), ctx.task_executor().clone(), blob_store.clone())
         .map(|validator| {
             OpTransactionValidator::new(validator)
@@ -202,18 +211,17 @@ where
             validator,
             CoinbaseTipOrdering::default(),
             blob_store,
-            pool_config_overrides.apply(ctx.pool_config()),
+            pool_config,
         );
         info!(target: "reth::cli", "Transaction pool initialized");
-        let transactions_path = data_dir.txpool_transactions();
 
         // spawn txpool maintenance task
         {
             let pool = transaction_pool.clone();
             let chain_events = ctx.provider().canonical_state_stream();
             let client = ctx.provider().clone();
-            let transactions_backup_config =
-                reth_transaction_pool::maintain::LocalTransactionBackupConfig::with_local_txs_backup(transactions_path);
+            let transactions_backup_config = ctx.transactions_backup_config();
+            let task_executor = ctx.task_executor().clone();
 
             ctx.task_executor().spawn_critical_with_graceful_shutdown_signal(
                 "local transactions backup task",
@@ -222,6 +230,7 @@ where
                         shutdown,
                         pool.clone(),
                         transactions_backup_config,
+                        task_executor,
                     )
                 },
             );
@@ -391,7 +400,7 @@ where
     ) -> eyre::Result<NetworkHandle> {
         let network_config = self.network_config(ctx)?;
         let network = NetworkManager::builder(network_config).await?;
-        let handle = ctx.start_network(network, pool);
+        let handle = ctx.start_network(network, pool)?;
 
         Ok(handle)
     }
@@ -130,12 +130,7 @@ where
         }
 
         // ensure that the account has enough balance to cover the L1 gas cost
-        if let TransactionValidationOutcome::Valid {
-            balance,
-            state_nonce,
-            transaction: valid_tx,
-            propagate,
-        } = outcome
+        if let TransactionValidationOutcome::Valid { balance, transaction: valid_tx, .. } = &outcome
         {
             let l1_block_info = self.block_info.l1_block_info.read().clone();
 
@@ -156,22 +151,15 @@ where
             let cost = valid_tx.transaction().cost().saturating_add(cost_addition);
 
             // Checks for max cost
-            if cost > balance {
+            if cost > *balance {
                 return TransactionValidationOutcome::Invalid(
-                    valid_tx.into_transaction(),
+                    valid_tx.transaction().clone(),
                     InvalidTransactionError::InsufficientFunds(
-                        GotExpected { got: balance, expected: cost }.into(),
+                        GotExpected { got: *balance, expected: cost }.into(),
                     )
                     .into(),
                 )
             }
-
-            return TransactionValidationOutcome::Valid {
-                balance,
-                state_nonce,
-                transaction: valid_tx,
-                propagate,
-            }
         }
 
         outcome
@@ -21,7 +21,8 @@ use reth_primitives::{
 use reth_provider::StateProviderFactory;
 use reth_revm::database::StateProviderDatabase;
 use reth_transaction_pool::{
-    noop::NoopTransactionPool, BestTransactionsAttributes, TransactionPool,
+    conditional_transactions_filter, noop::NoopTransactionPool, BestTransactions,
+    BestTransactionsAttributes, BestTransactionsFilter, TransactionPool,
 };
 use reth_trie::HashedPostState;
 use revm::{
@@ -186,15 +187,24 @@ where
     let mut executed_txs = Vec::with_capacity(attributes.transactions.len());
     let mut executed_senders = Vec::with_capacity(attributes.transactions.len());
 
-    let mut best_txs = pool.best_transactions_with_attributes(BestTransactionsAttributes::new(
+    let block_number = initialized_block_env.number.to::<u64>();
+
+    // skip conditional transactions whose conditions don't hold for this block
+    let best_txs = pool.best_transactions_with_attributes(BestTransactionsAttributes::new(
         base_fee,
         initialized_block_env.get_blob_gasprice().map(|gasprice| gasprice as u64),
     ));
+    let mut best_txs = BestTransactionsFilter::filter(
+        best_txs,
+        conditional_transactions_filter(
+            block_number,
+            attributes.payload_attributes.timestamp,
+            || client.state_by_block_hash(parent_block.hash()),
+        ),
+    );
 
     let mut total_fees = U256::ZERO;
 
-    let block_number = initialized_block_env.number.to::<u64>();
-
     let is_regolith = chain_spec.is_fork_active_at_timestamp(
         OptimismHardfork::Regolith,
         attributes.payload_attributes.timestamp,
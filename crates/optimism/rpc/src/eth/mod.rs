@@ -11,7 +11,7 @@ pub use receipt::{OpReceiptBuilder, OpReceiptFieldsBuilder};
 
 use std::{fmt, sync::Arc};
 
-use alloy_primitives::U256;
+use alloy_primitives::{Address, B256, U256};
 use derive_more::Deref;
 use op_alloy_network::Optimism;
 use reth_chainspec::EthereumHardforks;
@@ -19,10 +19,10 @@ use reth_evm::ConfigureEvm;
 use reth_network_api::NetworkInfo;
 use reth_node_api::{BuilderProvider, FullNodeComponents, FullNodeTypes, NodeTypes};
 use reth_node_builder::EthApiBuilderCtx;
-use reth_primitives::Header;
+use reth_primitives::{BlockId, Header};
 use reth_provider::{
-    BlockIdReader, BlockNumReader, BlockReaderIdExt, ChainSpecProvider, HeaderProvider,
-    StageCheckpointReader, StateProviderFactory,
+    providers::ConsistentDbView, BlockIdReader, BlockNumReader, BlockReaderIdExt,
+    ChainSpecProvider, HeaderProvider, StageCheckpointReader, StateProviderFactory,
 };
 use reth_rpc::eth::{core::EthApiInner, DevSigner};
 use reth_rpc_eth_api::{
@@ -30,7 +30,7 @@ use reth_rpc_eth_api::{
         AddDevSigners, EthApiSpec, EthFees, EthSigner, EthState, LoadBlock, LoadFee, LoadState,
         SpawnBlocking, Trace,
     },
-    EthApiTypes,
+    EthApiTypes, FromEthApiError,
 };
 use reth_rpc_eth_types::{EthStateCache, FeeHistoryCache, GasPriceOracle};
 use reth_tasks::{
@@ -38,6 +38,8 @@ use reth_tasks::{
     TaskSpawner,
 };
 use reth_transaction_pool::TransactionPool;
+use reth_trie::AccountProof;
+use reth_trie_parallel::proof::state_account_proof;
 use tokio::sync::OnceCell;
 
 use crate::{OpEthApiError, OpTxBuilder, SequencerClient};
@@ -217,6 +219,20 @@ where
     fn max_proof_window(&self) -> u64 {
         self.inner.eth_proof_window()
     }
+
+    fn account_proof_at_block_id(
+        &self,
+        block_id: BlockId,
+        address: Address,
+        slots: &[B256],
+    ) -> Result<AccountProof, Self::Error> {
+        // the view has to be created before the state, so that it is consistent with it
+        let consistent_view =
+            ConsistentDbView::new_with_latest_tip(self.inner.provider().clone()).ok();
+        let state = self.state_at_block_id(block_id)?;
+        state_account_proof(consistent_view, &state, address, slots)
+            .map_err(Self::Error::from_eth_err)
+    }
 }
 
 impl<N> EthFees for OpEthApi<N>
@@ -1,6 +1,6 @@
 use crate::segments::{
-    AccountHistory, ReceiptsByLogs, Segment, SenderRecovery, StorageHistory, TransactionLookup,
-    UserReceipts,
+    AccountHistory, BodiesHistory, HeadersHistory, ReceiptsByLogs, Segment, SenderRecovery,
+    StorageHistory, TransactionLookup, UserReceipts,
 };
 use reth_db::transaction::DbTxMut;
 use reth_provider::{
@@ -59,6 +59,8 @@ where
             receipts,
             account_history,
             storage_history,
+            headers_history,
+            bodies_history,
             receipts_log_filter,
         } = prune_modes;
 
@@ -68,7 +70,15 @@ where
             // Static file transactions
             .segment(StaticFileTransactions::new(static_file_provider.clone()))
             // Static file receipts
-            .segment(StaticFileReceipts::new(static_file_provider))
+            .segment(StaticFileReceipts::new(static_file_provider.clone()))
+            // Headers history
+            .segment_opt(
+                headers_history.map(|mode| HeadersHistory::new(static_file_provider.clone(), mode)),
+            )
+            // Bodies history
+            .segment_opt(
+                bodies_history.map(|mode| BodiesHistory::new(static_file_provider.clone(), mode)),
+            )
             // Account history
             .segment_opt(account_history.map(AccountHistory::new))
             // Storage history
@@ -0,0 +1,264 @@
+use crate::{
+    db_ext::DbTxPruneExt,
+    segments::{user::static_file_history::prune_static_files, PruneInput, Segment},
+    PrunerError,
+};
+use alloy_primitives::TxNumber;
+use rayon::prelude::*;
+use reth_db::{tables, transaction::DbTxMut};
+use reth_provider::{providers::StaticFileProvider, DBProvider, TransactionsProvider};
+use reth_prune_types::{PruneLimiter, PruneMode, PrunePurpose, PruneSegment, SegmentOutput};
+use reth_static_file_types::StaticFileSegment;
+use std::ops::RangeInclusive;
+use tracing::{instrument, trace};
+
+/// Maximum number of transactions whose hashes are computed at once.
+const TRANSACTION_HASHES_BATCH_SIZE: u64 = 100_000;
+
+/// Deletes the transactions static files below the configured target, together with the database
+/// entries of their blocks: transaction hash to number mappings, senders, transaction to block
+/// mappings, block body indices, ommers, withdrawals and requests.
+#[derive(Debug)]
+pub struct BodiesHistory {
+    static_file_provider: StaticFileProvider,
+    mode: PruneMode,
+}
+
+impl BodiesHistory {
+    pub const fn new(static_file_provider: StaticFileProvider, mode: PruneMode) -> Self {
+        Self { static_file_provider, mode }
+    }
+
+    /// Prunes the transaction hash to number mappings, senders and transaction to block mappings
+    /// of the transactions in `tx_range`.
+    fn prune_transactions<Provider: DBProvider<Tx: DbTxMut>>(
+        &self,
+        provider: &Provider,
+        tx_range: RangeInclusive<TxNumber>,
+        limiter: &mut PruneLimiter,
+    ) -> Result<(usize, bool), PrunerError> {
+        let mut hashes = self
+            .static_file_provider
+            .transactions_by_tx_range(tx_range.clone())?
+            .into_par_iter()
+            .map(|transaction| transaction.hash())
+            .collect::<Vec<_>>();
+        hashes.sort_unstable();
+
+        let tx = provider.tx_ref();
+        let mut pruned = 0;
+        let mut done = true;
+        for (table_pruned, table_done) in [
+            tx.prune_table_with_iterator::<tables::TransactionHashNumbers>(
+                hashes,
+                limiter,
+                |_| {},
+            )?,
+            tx.prune_table_with_range::<tables::TransactionSenders>(
+                tx_range.clone(),
+                limiter,
+                |_| false,
+                |_| {},
+            )?,
+            tx.prune_table_with_range::<tables::TransactionBlocks>(
+                tx_range.clone(),
+                limiter,
+                |_| false,
+                |_| {},
+            )?,
+        ] {
+            pruned += table_pruned;
+            done &= table_done;
+        }
+        trace!(target: "pruner", %pruned, %done, ?tx_range, "Pruned transaction entries");
+
+        Ok((pruned, done))
+    }
+}
+
+impl<Provider> Segment<Provider> for BodiesHistory
+where
+    Provider: DBProvider<Tx: DbTxMut>,
+{
+    fn segment(&self) -> PruneSegment {
+        PruneSegment::BodiesHistory
+    }
+
+    fn mode(&self) -> Option<PruneMode> {
+        Some(self.mode)
+    }
+
+    fn purpose(&self) -> PrunePurpose {
+        PrunePurpose::User
+    }
+
+    #[instrument(level = "trace", target = "pruner", skip(self, provider), ret)]
+    fn prune(&self, provider: &Provider, input: PruneInput) -> Result<SegmentOutput, PrunerError> {
+        prune_static_files(
+            &self.static_file_provider,
+            StaticFileSegment::Transactions,
+            input,
+            |header, last_pruned_tx, limiter| {
+                let mut pruned = 0;
+
+                // Transaction based entries are pruned in batches, which are recorded in the
+                // checkpoint, so that the hashes of the same transactions aren't computed again.
+                if let Some(tx_range) = header.tx_range() {
+                    let mut start =
+                        last_pruned_tx.map_or(tx_range.start(), |tx| tx_range.start().max(tx + 1));
+                    while start <= tx_range.end() {
+                        if limiter.is_limit_reached() {
+                            return Ok((pruned, start.checked_sub(1), false))
+                        }
+                        let end = tx_range.end().min(start + TRANSACTION_HASHES_BATCH_SIZE - 1);
+                        let (batch_pruned, done) =
+                            self.prune_transactions(provider, start..=end, limiter)?;
+                        pruned += batch_pruned;
+                        if !done {
+                            return Ok((pruned, start.checked_sub(1), false))
+                        }
+                        start = end + 1;
+                    }
+                }
+                let last_pruned_tx = header.tx_range().map(|range| range.end()).or(last_pruned_tx);
+
+                let Some(block_range) = header.block_range() else {
+                    return Ok((pruned, last_pruned_tx, true))
+                };
+                let blocks = block_range.start()..=block_range.end();
+                let tx = provider.tx_ref();
+                let mut done = true;
+                for (table_pruned, table_done) in [
+                    tx.prune_table_with_range::<tables::BlockOmmers>(
+                        blocks.clone(),
+                        limiter,
+                        |_| false,
+                        |_| {},
+                    )?,
+                    tx.prune_table_with_range::<tables::BlockWithdrawals>(
+                        blocks.clone(),
+                        limiter,
+                        |_| false,
+                        |_| {},
+                    )?,
+                    tx.prune_table_with_range::<tables::BlockRequests>(
+                        blocks.clone(),
+                        limiter,
+                        |_| false,
+                        |_| {},
+                    )?,
+                    tx.prune_table_with_range::<tables::BlockBodyIndices>(
+                        blocks,
+                        limiter,
+                        |_| false,
+                        |_| {},
+                    )?,
+                ] {
+                    pruned += table_pruned;
+                    done &= table_done;
+                }
+                trace!(target: "pruner", %pruned, %done, ?block_range, "Pruned block bodies");
+
+                Ok((pruned, last_pruned_tx, done))
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::segments::{
+        user::static_file_history::test_stage_db, BodiesHistory, PruneInput, Segment,
+    };
+    use alloy_primitives::{Address, B256};
+    use reth_db::tables;
+    use reth_provider::DatabaseProviderFactory;
+    use reth_prune_types::{PruneLimiter, PruneMode};
+    use reth_stages::test_utils::StorageKind;
+    use reth_static_file_types::StaticFileSegment;
+    use reth_testing_utils::generators::{self, random_block_range, BlockRangeParams};
+
+    #[test]
+    fn prune() {
+        let db = test_stage_db(10);
+        let mut rng = generators::rng();
+
+        let blocks = random_block_range(
+            &mut rng,
+            0..=29,
+            BlockRangeParams { parent: Some(B256::ZERO), tx_count: 2..3, ..Default::default() },
+        );
+        db.insert_blocks(blocks.iter(), StorageKind::Static).expect("insert blocks");
+
+        let transactions =
+            blocks.iter().flat_map(|block| &block.body.transactions).collect::<Vec<_>>();
+        db.insert_tx_hash_numbers(
+            transactions.iter().enumerate().map(|(i, tx)| (tx.hash, i as u64)),
+        )
+        .expect("insert tx hash numbers");
+        db.insert_transaction_senders(
+            (0..transactions.len()).map(|i| (i as u64, Address::with_last_byte(i as u8))),
+        )
+        .expect("insert transaction senders");
+        assert_eq!(db.table::<tables::TransactionHashNumbers>().unwrap().len(), 60);
+
+        let static_file_provider = db.factory.static_file_provider();
+        let segment = BodiesHistory::new(static_file_provider.clone(), PruneMode::Before(25));
+
+        // The limiter is reached while pruning the senders of the first static file, which is
+        // kept.
+        let provider = db.factory.database_provider_rw().unwrap();
+        let input = PruneInput {
+            previous_checkpoint: None,
+            to_block: 24,
+            limiter: PruneLimiter::default().set_deleted_entries_limit(30),
+        };
+        let result = segment.prune(&provider, input).unwrap();
+        provider.commit().expect("commit");
+
+        assert!(!result.progress.is_finished());
+        assert_eq!(result.pruned, 30);
+        assert_eq!(
+            static_file_provider.get_lowest_static_file_block(StaticFileSegment::Transactions),
+            Some(0)
+        );
+        assert_eq!(db.table::<tables::TransactionHashNumbers>().unwrap().len(), 40);
+        assert_eq!(db.table::<tables::TransactionSenders>().unwrap().len(), 50);
+        assert_eq!(db.table::<tables::BlockBodyIndices>().unwrap().len(), 30);
+
+        // Both static files below the target are deleted, the one containing it is kept.
+        let provider = db.factory.database_provider_rw().unwrap();
+        let input = PruneInput {
+            previous_checkpoint: result
+                .checkpoint
+                .map(|checkpoint| checkpoint.as_prune_checkpoint(PruneMode::Before(25))),
+            to_block: 24,
+            limiter: PruneLimiter::default(),
+        };
+        let result = segment.prune(&provider, input).unwrap();
+        provider.commit().expect("commit");
+
+        assert!(result.progress.is_finished());
+        let checkpoint = result.checkpoint.unwrap();
+        assert_eq!((checkpoint.block_number, checkpoint.tx_number), (Some(19), Some(39)));
+        assert_eq!(
+            static_file_provider.get_lowest_static_file_block(StaticFileSegment::Transactions),
+            Some(20)
+        );
+        let tx_hash_numbers = db.table::<tables::TransactionHashNumbers>().unwrap();
+        assert_eq!(tx_hash_numbers.len(), 20);
+        assert!(tx_hash_numbers.iter().all(|(_, tx_number)| *tx_number >= 40));
+        assert_eq!(
+            db.table::<tables::TransactionSenders>().unwrap().first().map(|(key, _)| *key),
+            Some(40)
+        );
+        assert_eq!(
+            db.table::<tables::TransactionBlocks>().unwrap().first().map(|(key, _)| *key),
+            Some(41)
+        );
+        assert_eq!(
+            db.table::<tables::BlockBodyIndices>().unwrap().first().map(|(key, _)| *key),
+            Some(20)
+        );
+    }
+}
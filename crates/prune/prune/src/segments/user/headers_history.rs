@@ -0,0 +1,134 @@
+use crate::{
+    db_ext::DbTxPruneExt,
+    segments::{user::static_file_history::prune_static_files, PruneInput, Segment},
+    PrunerError,
+};
+use reth_db::{tables, transaction::DbTxMut};
+use reth_provider::{providers::StaticFileProvider, BlockHashReader, DBProvider};
+use reth_prune_types::{PruneMode, PrunePurpose, PruneSegment, SegmentOutput};
+use reth_static_file_types::StaticFileSegment;
+use tracing::{instrument, trace};
+
+/// Deletes the headers static files below the configured target, together with the hash to number
+/// mappings of their headers.
+#[derive(Debug)]
+pub struct HeadersHistory {
+    static_file_provider: StaticFileProvider,
+    mode: PruneMode,
+}
+
+impl HeadersHistory {
+    pub const fn new(static_file_provider: StaticFileProvider, mode: PruneMode) -> Self {
+        Self { static_file_provider, mode }
+    }
+}
+
+impl<Provider> Segment<Provider> for HeadersHistory
+where
+    Provider: DBProvider<Tx: DbTxMut>,
+{
+    fn segment(&self) -> PruneSegment {
+        PruneSegment::HeadersHistory
+    }
+
+    fn mode(&self) -> Option<PruneMode> {
+        Some(self.mode)
+    }
+
+    fn purpose(&self) -> PrunePurpose {
+        PrunePurpose::User
+    }
+
+    #[instrument(level = "trace", target = "pruner", skip(self, provider), ret)]
+    fn prune(&self, provider: &Provider, input: PruneInput) -> Result<SegmentOutput, PrunerError> {
+        prune_static_files(
+            &self.static_file_provider,
+            StaticFileSegment::Headers,
+            input,
+            |header, _, limiter| {
+                let Some(block_range) = header.block_range() else { return Ok((0, None, true)) };
+                let mut hashes = self
+                    .static_file_provider
+                    .canonical_hashes_range(block_range.start(), block_range.end() + 1)?;
+                hashes.sort_unstable();
+
+                let (pruned, done) = provider
+                    .tx_ref()
+                    .prune_table_with_iterator::<tables::HeaderNumbers>(hashes, limiter, |_| {})?;
+                trace!(target: "pruner", %pruned, %done, ?block_range, "Pruned header numbers");
+                Ok((pruned, None, done))
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::segments::{
+        user::static_file_history::test_stage_db, HeadersHistory, PruneInput, Segment,
+    };
+    use alloy_primitives::B256;
+    use reth_db::tables;
+    use reth_provider::DatabaseProviderFactory;
+    use reth_prune_types::{PruneLimiter, PruneMode};
+    use reth_stages::test_utils::StorageKind;
+    use reth_static_file_types::StaticFileSegment;
+    use reth_testing_utils::generators::{self, random_block_range, BlockRangeParams};
+
+    #[test]
+    fn prune() {
+        let db = test_stage_db(10);
+        let mut rng = generators::rng();
+
+        let blocks = random_block_range(
+            &mut rng,
+            0..=29,
+            BlockRangeParams { parent: Some(B256::ZERO), tx_count: 0..1, ..Default::default() },
+        );
+        db.insert_blocks(blocks.iter(), StorageKind::Static).expect("insert blocks");
+        assert_eq!(db.table::<tables::HeaderNumbers>().unwrap().len(), 30);
+
+        let static_file_provider = db.factory.static_file_provider();
+        let segment = HeadersHistory::new(static_file_provider.clone(), PruneMode::Before(25));
+
+        // The limiter is reached in the middle of the first static file, which is kept.
+        let provider = db.factory.database_provider_rw().unwrap();
+        let input = PruneInput {
+            previous_checkpoint: None,
+            to_block: 24,
+            limiter: PruneLimiter::default().set_deleted_entries_limit(5),
+        };
+        let result = segment.prune(&provider, input).unwrap();
+        provider.commit().expect("commit");
+
+        assert!(!result.progress.is_finished());
+        assert_eq!(result.pruned, 5);
+        assert_eq!(
+            static_file_provider.get_lowest_static_file_block(StaticFileSegment::Headers),
+            Some(0)
+        );
+        assert_eq!(db.table::<tables::HeaderNumbers>().unwrap().len(), 25);
+
+        // Both static files below the target are deleted, the one containing it is kept.
+        let provider = db.factory.database_provider_rw().unwrap();
+        let input = PruneInput {
+            previous_checkpoint: result
+                .checkpoint
+                .map(|checkpoint| checkpoint.as_prune_checkpoint(PruneMode::Before(25))),
+            to_block: 24,
+            limiter: PruneLimiter::default(),
+        };
+        let result = segment.prune(&provider, input).unwrap();
+        provider.commit().expect("commit");
+
+        assert!(result.progress.is_finished());
+        assert_eq!(result.checkpoint.and_then(|checkpoint| checkpoint.block_number), Some(19));
+        assert_eq!(
+            static_file_provider.get_lowest_static_file_block(StaticFileSegment::Headers),
+            Some(20)
+        );
+        let header_numbers = db.table::<tables::HeaderNumbers>().unwrap();
+        assert_eq!(header_numbers.len(), 10);
+        assert!(header_numbers.iter().all(|(_, number)| *number >= 20));
+    }
+}
@@ -1,12 +1,17 @@
 mod account_history;
+mod bodies_history;
+mod headers_history;
 mod history;
 mod receipts;
 mod receipts_by_logs;
 mod sender_recovery;
+mod static_file_history;
 mod storage_history;
 mod transaction_lookup;
 
 pub use account_history::AccountHistory;
+pub use bodies_history::BodiesHistory;
+pub use headers_history::HeadersHistory;
 pub use receipts::Receipts;
 pub use receipts_by_logs::ReceiptsByLogs;
 pub use sender_recovery::SenderRecovery;
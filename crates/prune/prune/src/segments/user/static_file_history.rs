@@ -0,0 +1,100 @@
+use crate::{segments::PruneInput, PrunerError};
+use alloy_primitives::TxNumber;
+use reth_provider::providers::StaticFileProvider;
+use reth_prune_types::{PruneLimiter, PruneProgress, SegmentOutput, SegmentOutputCheckpoint};
+use reth_static_file_types::{SegmentHeader, StaticFileSegment};
+use tracing::trace;
+
+/// Deletes the static files of `segment` that are entirely below `to_block` of the input, lowest
+/// first.
+///
+/// Before a static file is deleted, `prune_indexes` prunes the database entries pointing into it,
+/// starting after the last transaction whose entries were pruned. It returns the number of pruned
+/// entries, the new last pruned transaction, and whether all entries were pruned. If the limiter is
+/// reached before that, the static file is kept, so that pruning resumes with it on the next run.
+///
+/// Static files are deleted as a whole, so up to one static file worth of blocks below the target
+/// is kept. The highest static file is never deleted. The number of pruned entries is the number of
+/// pruned database entries plus the number of deleted static files.
+pub(crate) fn prune_static_files(
+    static_file_provider: &StaticFileProvider,
+    segment: StaticFileSegment,
+    input: PruneInput,
+    mut prune_indexes: impl FnMut(
+        &SegmentHeader,
+        Option<TxNumber>,
+        &mut PruneLimiter,
+    ) -> Result<(usize, Option<TxNumber>, bool), PrunerError>,
+) -> Result<SegmentOutput, PrunerError> {
+    let mut limiter = input.limiter;
+    let mut pruned = 0;
+    let mut last_deleted_block = None;
+    let mut last_pruned_tx = input.previous_checkpoint.and_then(|checkpoint| checkpoint.tx_number);
+
+    let done = loop {
+        let (Some(lowest_block), Some(highest_block)) = (
+            static_file_provider.get_lowest_static_file_block(segment),
+            static_file_provider.get_highest_static_file_block(segment),
+        ) else {
+            break true
+        };
+        let range = static_file_provider.find_fixed_range(lowest_block);
+        if range.end() > input.to_block ||
+            range.end() >= static_file_provider.find_fixed_range(highest_block).start()
+        {
+            break true
+        }
+
+        let header = static_file_provider
+            .get_segment_provider_from_block(segment, lowest_block, None)?
+            .user_header()
+            .clone();
+        let (pruned_indexes, tx_number, done) =
+            prune_indexes(&header, last_pruned_tx, &mut limiter)?;
+        pruned += pruned_indexes;
+        last_pruned_tx = tx_number;
+        if !done {
+            break false
+        }
+
+        let deleted = static_file_provider.delete_jars_below(segment, range.end())?;
+        trace!(target: "pruner", ?segment, ?deleted, "Pruned static files");
+        pruned += deleted.len();
+        last_deleted_block = Some(range.end());
+    };
+
+    if done && last_deleted_block.is_none() {
+        trace!(target: "pruner", ?segment, "No static files to prune");
+        return Ok(SegmentOutput::done())
+    }
+
+    Ok(SegmentOutput {
+        progress: PruneProgress::new(done, &limiter),
+        pruned,
+        checkpoint: Some(SegmentOutputCheckpoint {
+            block_number: last_deleted_block
+                .or(input.previous_checkpoint.and_then(|checkpoint| checkpoint.block_number)),
+            tx_number: last_pruned_tx,
+        }),
+    })
+}
+
+/// Returns a test database whose static files hold `blocks_per_file` blocks each.
+#[cfg(test)]
+pub(crate) fn test_stage_db(blocks_per_file: u64) -> reth_stages::test_utils::TestStageDB {
+    use reth_chainspec::MAINNET;
+    use reth_db::test_utils::{create_test_rw_db, create_test_static_files_dir};
+    use reth_provider::ProviderFactory;
+
+    let (temp_static_files_dir, static_files_path) = create_test_static_files_dir();
+    reth_stages::test_utils::TestStageDB {
+        factory: ProviderFactory::new(
+            create_test_rw_db(),
+            MAINNET.clone(),
+            StaticFileProvider::read_write(static_files_path)
+                .unwrap()
+                .with_custom_blocks_per_file(blocks_per_file),
+        ),
+        temp_static_files_dir,
+    }
+}
@@ -39,6 +39,10 @@ pub enum PruneSegment {
     Headers,
     /// Prune segment responsible for the `Transactions` table.
     Transactions,
+    /// Prune segment responsible for the headers static files.
+    HeadersHistory,
+    /// Prune segment responsible for the transactions static files.
+    BodiesHistory,
 }
 
 impl PruneSegment {
@@ -49,9 +53,11 @@ impl PruneSegment {
                 0
             }
             Self::Receipts if purpose.is_static_file() => 0,
-            Self::ContractLogs | Self::AccountHistory | Self::StorageHistory => {
-                MINIMUM_PRUNING_DISTANCE
-            }
+            Self::ContractLogs |
+            Self::AccountHistory |
+            Self::StorageHistory |
+            Self::HeadersHistory |
+            Self::BodiesHistory => MINIMUM_PRUNING_DISTANCE,
             Self::Receipts => MINIMUM_PRUNING_DISTANCE,
         }
     }
@@ -95,3 +101,33 @@ impl Default for PruneSegment {
         Self::SenderRecovery
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compact_discriminants() {
+        // Segments are stored as keys of prune checkpoints, so new segments must only be
+        // appended, without changing the discriminants of existing ones.
+        let segments = [
+            (PruneSegment::SenderRecovery, 0),
+            (PruneSegment::TransactionLookup, 1),
+            (PruneSegment::Receipts, 2),
+            (PruneSegment::ContractLogs, 3),
+            (PruneSegment::AccountHistory, 4),
+            (PruneSegment::StorageHistory, 5),
+            (PruneSegment::Headers, 6),
+            (PruneSegment::Transactions, 7),
+            (PruneSegment::HeadersHistory, 8),
+            (PruneSegment::BodiesHistory, 9),
+        ];
+
+        for (segment, discriminant) in segments {
+            let mut buf = vec![];
+            segment.to_compact(&mut buf);
+            assert_eq!(buf, vec![discriminant], "{segment}");
+            assert_eq!(PruneSegment::from_compact(&buf, buf.len()).0, segment);
+        }
+    }
+}
@@ -37,6 +37,20 @@ pub struct PruneModes {
         deserialize_with = "deserialize_opt_prune_mode_with_min_blocks::<MINIMUM_PRUNING_DISTANCE, _>"
     )]
     pub storage_history: Option<PruneMode>,
+    /// Headers History pruning configuration. Deletes whole static files of headers below the
+    /// target block.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_opt_prune_mode_with_min_blocks::<MINIMUM_PRUNING_DISTANCE, _>"
+    )]
+    pub headers_history: Option<PruneMode>,
+    /// Bodies History pruning configuration. Deletes whole static files of transactions below
+    /// the target block.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_opt_prune_mode_with_min_blocks::<MINIMUM_PRUNING_DISTANCE, _>"
+    )]
+    pub bodies_history: Option<PruneMode>,
     /// Receipts pruning configuration by retaining only those receipts that contain logs emitted
     /// by the specified addresses, discarding others. This setting is overridden by `receipts`.
     ///
@@ -59,6 +73,8 @@ impl PruneModes {
             receipts: Some(PruneMode::Full),
             account_history: Some(PruneMode::Full),
             storage_history: Some(PruneMode::Full),
+            headers_history: None,
+            bodies_history: None,
             receipts_log_filter: Default::default(),
         }
     }
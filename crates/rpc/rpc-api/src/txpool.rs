@@ -1,7 +1,9 @@
+use alloy_eips::eip4844::BlobAndProofV1;
 use alloy_json_rpc::RpcObject;
-use alloy_primitives::Address;
+use alloy_primitives::{Address, TxHash, B256};
 use alloy_rpc_types_txpool::{TxpoolContent, TxpoolContentFrom, TxpoolInspect, TxpoolStatus};
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use reth_transaction_pool_types::{SimulationResult, TransactionHistory};
 
 /// Txpool rpc interface.
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "txpool"))]
@@ -34,4 +36,32 @@ pub trait TxPoolApi<T: RpcObject> {
     /// See [here](https://geth.ethereum.org/docs/rpc/ns-txpool#txpool_content) for more details
     #[method(name = "content")]
     async fn txpool_content(&self) -> RpcResult<TxpoolContent<T>>;
+
+    /// Returns the recorded lifecycle of the transaction, including why it was parked or dropped,
+    /// if it is tracked by the pool's journal.
+    #[method(name = "transactionHistory")]
+    async fn txpool_transaction_history(
+        &self,
+        hash: TxHash,
+    ) -> RpcResult<Option<TransactionHistory>>;
+
+    /// Returns the recorded lifecycle of all transactions of this address that are tracked by the
+    /// pool's journal, ordered by nonce.
+    #[method(name = "senderHistory")]
+    async fn txpool_sender_history(&self, from: Address) -> RpcResult<Vec<TransactionHistory>>;
+
+    /// Returns the result of executing the pending transaction on top of the current canonical
+    /// head, if it was simulated.
+    #[method(name = "simulation")]
+    async fn txpool_simulation(&self, hash: TxHash) -> RpcResult<Option<SimulationResult>>;
+
+    /// Returns the blobs and proofs for the given versioned hashes, if they are known to the pool
+    /// or kept by its blob store.
+    ///
+    /// At most 128 versioned hashes can be requested at once.
+    #[method(name = "blobs")]
+    async fn txpool_blobs(
+        &self,
+        versioned_hashes: Vec<B256>,
+    ) -> RpcResult<Vec<Option<BlobAndProofV1>>>;
 }
@@ -15,6 +15,7 @@ use reth_provider::{
 use reth_rpc_eth_types::{EthApiError, EthStateCache, PendingBlockEnv, RpcInvalidTransactionError};
 use reth_rpc_types_compat::proof::from_primitive_account_proof;
 use reth_transaction_pool::TransactionPool;
+use reth_trie::AccountProof;
 use revm_primitives::{BlockEnv, CfgEnvWithHandlerCfg, SpecId};
 
 use crate::{EthApiTypes, FromEthApiError};
@@ -114,17 +115,29 @@ pub trait EthState: LoadState + SpawnBlocking {
             }
 
             self.spawn_blocking_io(move |this| {
-                let state = this.state_at_block_id(block_id)?;
                 let storage_keys = keys.iter().map(|key| key.0).collect::<Vec<_>>();
-                let proof = state
-                    .proof(Default::default(), address, &storage_keys)
-                    .map_err(Self::Error::from_eth_err)?;
+                let proof = this.account_proof_at_block_id(block_id, address, &storage_keys)?;
                 Ok(from_primitive_account_proof(proof))
             })
             .await
         })
     }
 
+    /// Returns the proof of the account and the given storage slots at the given block
+    /// identifier.
+    ///
+    /// Called by [`EthState::get_proof`] on a blocking task, after the proof window was checked.
+    fn account_proof_at_block_id(
+        &self,
+        block_id: BlockId,
+        address: Address,
+        slots: &[B256],
+    ) -> Result<AccountProof, Self::Error> {
+        self.state_at_block_id(block_id)?
+            .proof(Default::default(), address, slots)
+            .map_err(Self::Error::from_eth_err)
+    }
+
     /// Returns the account at the given address for the provided block identifier.
     fn get_account(
         &self,
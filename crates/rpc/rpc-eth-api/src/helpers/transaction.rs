@@ -17,7 +17,10 @@ use reth_rpc_eth_types::{
     EthApiError, EthStateCache, SignError, TransactionSource,
 };
 use reth_rpc_types_compat::transaction::{from_recovered, from_recovered_with_block_context};
-use reth_transaction_pool::{PoolTransaction, TransactionOrigin, TransactionPool};
+use reth_transaction_pool::{
+    PoolTransaction, TransactionConditional, TransactionOrigin, TransactionPool,
+    MAX_TRANSACTION_CONDITIONAL_COST,
+};
 
 use crate::{FromEthApiError, FullEthApiTypes, IntoEthApiError, RpcReceipt, RpcTransaction};
 
@@ -336,6 +339,73 @@ pub trait EthTransactions: LoadTransaction {
         }
     }
 
+    /// Decodes and recovers the transaction and submits it to the pool, to be included only while
+    /// the given conditions hold.
+    ///
+    /// The conditions are checked against the latest block before the transaction is submitted.
+    ///
+    /// Returns the hash of the transaction.
+    fn send_raw_transaction_conditional(
+        &self,
+        tx: Bytes,
+        conditional: TransactionConditional,
+    ) -> impl Future<Output = Result<B256, Self::Error>> + Send
+    where
+        Self: LoadState,
+    {
+        async move {
+            let cost = conditional.cost();
+            if cost > MAX_TRANSACTION_CONDITIONAL_COST {
+                return Err(Self::Error::from_eth_err(EthApiError::TransactionConditionalFailed(
+                    format!("cost {cost} exceeds maximum {MAX_TRANSACTION_CONDITIONAL_COST}"),
+                )))
+            }
+
+            let recovered = recover_raw_transaction(tx)?;
+            let pool_transaction =
+                <Self::Pool as TransactionPool>::Transaction::from_pooled(recovered.into());
+
+            let conditional = self
+                .spawn_blocking_io(move |this| {
+                    let latest = EthTransactions::provider(&this)
+                        .latest_header()
+                        .map_err(Self::Error::from_eth_err)?
+                        .ok_or(EthApiError::HeaderNotFound(BlockId::latest()))?;
+                    if conditional.has_exceeded_block_attributes(latest.number, latest.timestamp) {
+                        return Err(Self::Error::from_eth_err(
+                            EthApiError::TransactionConditionalFailed(
+                                "block number or timestamp bounds exceeded".to_string(),
+                            ),
+                        ))
+                    }
+                    let state = this.latest_state()?;
+                    if !conditional.matches_state(&state).map_err(Self::Error::from_eth_err)? {
+                        return Err(Self::Error::from_eth_err(
+                            EthApiError::TransactionConditionalFailed(
+                                "known accounts don't match the latest state".to_string(),
+                            ),
+                        ))
+                    }
+                    Ok(conditional)
+                })
+                .await?;
+
+            // submit the transaction to the pool with a `Local` origin, conditional transactions
+            // are still neither exempt from eviction nor persisted across restarts
+            let hash = self
+                .pool()
+                .add_conditional_transaction(
+                    TransactionOrigin::Local,
+                    pool_transaction,
+                    conditional,
+                )
+                .await
+                .map_err(Self::Error::from_eth_err)?;
+
+            Ok(hash)
+        }
+    }
+
     /// Signs transaction with a matching signer, if any and submits the transaction to the pool.
     /// Returns the hash of the signed transaction.
     fn send_transaction(
@@ -95,6 +95,10 @@ impl reth_storage_api::StateProofProvider for StateProviderTraitObjWrapper<'_> {
     {
         self.0.witness(input, target)
     }
+
+    fn database_trie_input(&self) -> ProviderResult<Option<reth_trie::TrieInput>> {
+        self.0.database_trie_input()
+    }
 }
 
 impl reth_storage_api::AccountReader for StateProviderTraitObjWrapper<'_> {
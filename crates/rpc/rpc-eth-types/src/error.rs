@@ -97,6 +97,10 @@ pub enum EthApiError {
     /// Some feature is unsupported
     #[error("unsupported")]
     Unsupported(&'static str),
+    /// Thrown when the conditions of a conditional transaction
+    /// (`eth_sendRawTransactionConditional`) aren't met
+    #[error("transaction conditional failed: {0}")]
+    TransactionConditionalFailed(String),
     /// General purpose error for invalid params
     #[error("{0}")]
     InvalidParams(String),
@@ -202,6 +206,9 @@ impl From<EthApiError> for jsonrpsee_types::error::ErrorObject<'static> {
             EthApiError::Unsupported(msg) => internal_rpc_err(msg),
             EthApiError::InternalJsTracerError(msg) => internal_rpc_err(msg),
             EthApiError::InvalidParams(msg) => invalid_params_rpc_err(msg),
+            err @ EthApiError::TransactionConditionalFailed(_) => {
+                rpc_error_with_code(EthRpcErrorCode::TransactionRejected.code(), err.to_string())
+            }
             err @ EthApiError::ExecutionTimedOut(_) => rpc_error_with_code(
                 jsonrpsee_types::error::CALL_EXECUTION_FAILED_CODE,
                 err.to_string(),
@@ -20,8 +20,9 @@ use reth_evm::{
 };
 use reth_primitives::{Block, BlockId, BlockNumberOrTag, TransactionSignedEcRecovered};
 use reth_provider::{
-    BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, HeaderProvider, StateProofProvider,
-    StateProviderFactory, TransactionVariant,
+    providers::ConsistentDbView, BlockReader, BlockReaderIdExt, ChainSpecProvider,
+    DatabaseProviderFactory, EvmEnvProvider, HeaderProvider, StateProviderFactory,
+    TransactionVariant,
 };
 use reth_revm::database::StateProviderDatabase;
 use reth_rpc_api::DebugApiServer;
@@ -33,6 +34,7 @@ use reth_rpc_eth_types::{EthApiError, StateCacheDb};
 use reth_rpc_server_types::{result::internal_rpc_err, ToRpcResult};
 use reth_tasks::pool::BlockingTaskGuard;
 use reth_trie::{HashedPostState, HashedStorage};
+use reth_trie_parallel::proof::state_witness;
 use revm::{
     db::CacheDB,
     primitives::{db::DatabaseCommit, BlockEnv, CfgEnvWithHandlerCfg, Env, EnvWithHandlerCfg},
@@ -81,6 +83,8 @@ where
         + ChainSpecProvider<ChainSpec: EthereumHardforks>
         + StateProviderFactory
         + EvmEnvProvider
+        + DatabaseProviderFactory<Provider: BlockReader>
+        + Clone
         + 'static,
     Eth: EthApiTypes + TraceExt + 'static,
     BlockExecutor: BlockExecutorProvider,
@@ -602,6 +606,11 @@ where
             .await?
             .ok_or(EthApiError::HeaderNotFound(block_id.into()))?;
 
+        // The witness is generated from a view of the database that is consistent with the state,
+        // so the view is created before the state is opened.
+        let consistent_view =
+            ConsistentDbView::new_with_latest_tip(self.inner.provider.clone()).ok();
+
         self.eth_api()
             .spawn_with_state_at_block(block.parent_hash.into(), move |state_provider| {
                 let db = StateProviderDatabase::new(&state_provider);
@@ -650,8 +659,8 @@ where
                     )
                     .map_err(|err| EthApiError::Internal(err.into()))?;
 
-                let state =
-                    state_provider.witness(Default::default(), hashed_state).map_err(Into::into)?;
+                let state = state_witness(consistent_view, &state_provider, hashed_state)
+                    .map_err(Into::into)?;
                 Ok(ExecutionWitness {
                     state: HashMap::from_iter(state.into_iter()),
                     codes,
@@ -813,6 +822,8 @@ where
         + ChainSpecProvider<ChainSpec: EthereumHardforks>
         + StateProviderFactory
         + EvmEnvProvider
+        + DatabaseProviderFactory<Provider: BlockReader>
+        + Clone
         + 'static,
     Eth: EthApiSpec + EthTransactions + TraceExt + 'static,
     BlockExecutor: BlockExecutorProvider,
@@ -1,10 +1,20 @@
 //! Contains RPC handler implementations specific to state.
 
+use alloy_primitives::{Address, B256};
 use reth_chainspec::EthereumHardforks;
-use reth_provider::{ChainSpecProvider, StateProviderFactory};
+use reth_primitives::BlockId;
+use reth_provider::{
+    providers::ConsistentDbView, BlockReader, ChainSpecProvider, DatabaseProviderFactory,
+    StateProviderFactory,
+};
 use reth_transaction_pool::TransactionPool;
+use reth_trie::AccountProof;
+use reth_trie_parallel::proof::state_account_proof;
 
-use reth_rpc_eth_api::helpers::{EthState, LoadState, SpawnBlocking};
+use reth_rpc_eth_api::{
+    helpers::{EthState, LoadState, SpawnBlocking},
+    FromEthApiError,
+};
 use reth_rpc_eth_types::EthStateCache;
 
 use crate::EthApi;
@@ -12,10 +22,25 @@ use crate::EthApi;
 impl<Provider, Pool, Network, EvmConfig> EthState for EthApi<Provider, Pool, Network, EvmConfig>
 where
     Self: LoadState + SpawnBlocking,
+    Provider: DatabaseProviderFactory<Provider: BlockReader> + Clone + 'static,
 {
     fn max_proof_window(&self) -> u64 {
         self.inner.eth_proof_window()
     }
+
+    fn account_proof_at_block_id(
+        &self,
+        block_id: BlockId,
+        address: Address,
+        slots: &[B256],
+    ) -> Result<AccountProof, Self::Error> {
+        // the view has to be created before the state, so that it is consistent with it
+        let consistent_view =
+            ConsistentDbView::new_with_latest_tip(self.inner.provider().clone()).ok();
+        let state = self.state_at_block_id(block_id)?;
+        state_account_proof(consistent_view, &state, address, slots)
+            .map_err(Self::Error::from_eth_err)
+    }
 }
 
 impl<Provider, Pool, Network, EvmConfig> LoadState for EthApi<Provider, Pool, Network, EvmConfig>
@@ -1,6 +1,7 @@
 use std::{collections::BTreeMap, marker::PhantomData};
 
-use alloy_primitives::Address;
+use alloy_eips::eip4844::BlobAndProofV1;
+use alloy_primitives::{Address, TxHash, B256};
 use alloy_rpc_types_txpool::{
     TxpoolContent, TxpoolContentFrom, TxpoolInspect, TxpoolInspectSummary, TxpoolStatus,
 };
@@ -9,10 +10,17 @@ use jsonrpsee::core::RpcResult as Result;
 use reth_primitives::TransactionSignedEcRecovered;
 use reth_rpc_api::TxPoolApiServer;
 use reth_rpc_eth_api::{FullEthApiTypes, RpcTransaction};
+use reth_rpc_server_types::result::{internal_rpc_err, invalid_params_rpc_err};
 use reth_rpc_types_compat::{transaction::from_recovered, TransactionCompat};
-use reth_transaction_pool::{AllPoolTransactions, PoolTransaction, TransactionPool};
+use reth_transaction_pool::{
+    simulation::SimulationResult, AllPoolTransactions, PoolTransaction, TransactionHistory,
+    TransactionPool,
+};
 use tracing::trace;
 
+/// The maximum number of blobs that can be requested with `txpool_blobs`.
+const MAX_BLOBS_PER_REQUEST: usize = 128;
+
 /// `txpool` API implementation.
 ///
 /// This type provides the functionality for handling `txpool` related requests.
@@ -144,6 +152,51 @@ where
         trace!(target: "rpc::eth", "Serving txpool_content");
         Ok(self.content())
     }
+
+    /// Returns the recorded lifecycle of the transaction, if it is tracked by the pool's journal.
+    ///
+    /// Handler for `txpool_transactionHistory`
+    async fn txpool_transaction_history(&self, hash: TxHash) -> Result<Option<TransactionHistory>> {
+        trace!(target: "rpc::eth", ?hash, "Serving txpool_transactionHistory");
+        Ok(self.pool.transaction_history(hash))
+    }
+
+    /// Returns the recorded lifecycle of all transactions of this address that are tracked by the
+    /// pool's journal.
+    ///
+    /// Handler for `txpool_senderHistory`
+    async fn txpool_sender_history(&self, from: Address) -> Result<Vec<TransactionHistory>> {
+        trace!(target: "rpc::eth", ?from, "Serving txpool_senderHistory");
+        Ok(self.pool.transaction_histories_by_sender(from))
+    }
+
+    /// Returns the result of executing the pending transaction on top of the current canonical
+    /// head, if it was simulated.
+    ///
+    /// Handler for `txpool_simulation`
+    async fn txpool_simulation(&self, hash: TxHash) -> Result<Option<SimulationResult>> {
+        trace!(target: "rpc::eth", ?hash, "Serving txpool_simulation");
+        Ok(self.pool.simulations().get(&hash).map(|result| (*result).clone()))
+    }
+
+    /// Returns the blobs and proofs for the given versioned hashes.
+    ///
+    /// Handler for `txpool_blobs`
+    async fn txpool_blobs(
+        &self,
+        versioned_hashes: Vec<B256>,
+    ) -> Result<Vec<Option<BlobAndProofV1>>> {
+        trace!(target: "rpc::eth", len = versioned_hashes.len(), "Serving txpool_blobs");
+        if versioned_hashes.len() > MAX_BLOBS_PER_REQUEST {
+            return Err(invalid_params_rpc_err(format!(
+                "requested more than {MAX_BLOBS_PER_REQUEST} blobs: {}",
+                versioned_hashes.len()
+            )))
+        }
+        self.pool
+            .get_blobs_for_versioned_hashes(&versioned_hashes)
+            .map_err(|err| internal_rpc_err(err.to_string()))
+    }
 }
 
 impl<Pool, Eth> std::fmt::Debug for TxPoolApi<Pool, Eth> {
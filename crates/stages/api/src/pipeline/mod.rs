@@ -2,14 +2,16 @@ mod ctrl;
 mod event;
 pub use crate::pipeline::ctrl::ControlFlow;
 use crate::{PipelineTarget, StageCheckpoint, StageId};
+use alloy_eips::BlockNumHash;
 use alloy_primitives::{BlockNumber, B256};
 pub use event::*;
 use futures_util::Future;
 use reth_primitives_traits::constants::BEACON_CONSENSUS_REORG_UNWIND_DEPTH;
 use reth_provider::{
-    providers::ProviderNodeTypes, writer::UnifiedStorageWriter, ChainStateBlockReader,
-    ChainStateBlockWriter, DatabaseProviderFactory, ProviderFactory, StageCheckpointReader,
-    StageCheckpointWriter, StaticFileProviderFactory,
+    providers::{write_persisted_tip, ProviderNodeTypes},
+    writer::UnifiedStorageWriter,
+    BlockHashReader, ChainStateBlockReader, ChainStateBlockWriter, DatabaseProviderFactory,
+    ProviderFactory, StageCheckpointReader, StageCheckpointWriter, StaticFileProviderFactory,
 };
 use reth_prune::PrunerBuilder;
 use reth_static_file::StaticFileProducer;
@@ -352,6 +354,10 @@ impl<N: ProviderNodeTypes> Pipeline<N> {
                             self.provider_factory.static_file_provider(),
                         )?;
 
+                        if stage_id.is_finish() {
+                            record_persisted_tip(&self.provider_factory, checkpoint.block_number);
+                        }
+
                         stage.post_unwind_commit()?;
 
                         provider_rw = self.provider_factory.database_provider_rw()?;
@@ -463,6 +469,10 @@ impl<N: ProviderNodeTypes> Pipeline<N> {
                         self.provider_factory.static_file_provider(),
                     )?;
 
+                    if stage_id.is_finish() {
+                        record_persisted_tip(&self.provider_factory, checkpoint.block_number);
+                    }
+
                     stage.post_execute_commit()?;
 
                     if done {
@@ -489,6 +499,21 @@ impl<N: ProviderNodeTypes> Pipeline<N> {
     }
 }
 
+/// Records the block that all stages have reached as the persisted tip, for secondary readers of
+/// the storage.
+fn record_persisted_tip<N: ProviderNodeTypes>(factory: &ProviderFactory<N>, number: BlockNumber) {
+    let result = factory.block_hash(number).and_then(|hash| {
+        let Some(hash) = hash else { return Ok(()) };
+        write_persisted_tip(
+            factory.static_file_provider().directory(),
+            BlockNumHash { number, hash },
+        )
+    });
+    if let Err(err) = result {
+        warn!(target: "sync::pipeline", %err, number, "Failed to record persisted tip");
+    }
+}
+
 fn on_stage_error<N: ProviderNodeTypes>(
     factory: &ProviderFactory<N>,
     stage_id: StageId,
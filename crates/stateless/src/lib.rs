@@ -0,0 +1,22 @@
+//! Stateless validation of blocks against their execution witnesses.
+//!
+//! An execution witness, as returned by `debug_executionWitness`, contains the trie nodes and
+//! bytecodes that are accessed during the execution of a block. Together with the headers of the
+//! ancestors of the block, this is enough to re-execute the block and verify its state root without
+//! access to the database.
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+mod witness;
+pub use witness::WitnessState;
+
+mod validation;
+pub use validation::{
+    validate_block, StatelessInput, StatelessValidationError, StatelessValidationOutput,
+};
@@ -0,0 +1,276 @@
+//! Stateless validation of a block against its execution witness.
+
+use crate::WitnessState;
+use alloy_primitives::{map::HashMap, B256, U256};
+use alloy_rpc_types_debug::ExecutionWitness;
+use reth_consensus::{Consensus, ConsensusError, PostExecutionInput};
+use reth_evm::execute::{BlockExecutionError, BlockExecutorProvider, Executor};
+use reth_primitives::{Block, GotExpected, Header, SealedHeader};
+use reth_revm::database::StateProviderDatabase;
+use reth_storage_errors::provider::ProviderError;
+use reth_trie::{sparse::SparseStateTrieError, HashedPostState};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+/// A block together with everything that is required to validate it without access to the state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatelessInput {
+    /// The block to validate.
+    pub block: Block,
+    /// The execution witness of the block, as returned by `debug_executionWitness`.
+    pub witness: ExecutionWitness,
+    /// Headers of the ancestors of the block in ascending order, the last one being the parent.
+    ///
+    /// Ancestors are only required for the block hashes that are accessed during execution.
+    pub ancestor_headers: Vec<Header>,
+}
+
+/// The outcome of a successful stateless validation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatelessValidationOutput {
+    /// The state root after the execution of the block.
+    pub state_root: B256,
+    /// The gas used by the block.
+    pub gas_used: u64,
+}
+
+/// Errors that can occur during stateless validation.
+#[derive(Debug, thiserror::Error)]
+pub enum StatelessValidationError {
+    /// The parent header of the block is not part of the ancestor headers.
+    #[error("missing parent header {0}")]
+    MissingParentHeader(B256),
+    /// The ancestor headers don't form a chain.
+    #[error("ancestor header {number} is not the parent of the next header")]
+    DisconnectedAncestor {
+        /// Number of the disconnected ancestor.
+        number: u64,
+    },
+    /// The senders of the block transactions could not be recovered.
+    #[error("failed to recover transaction senders")]
+    SenderRecovery,
+    /// The block is invalid according to the consensus rules.
+    #[error(transparent)]
+    Consensus(#[from] ConsensusError),
+    /// The block failed to execute.
+    #[error(transparent)]
+    Execution(#[from] BlockExecutionError),
+    /// The witness is malformed or incomplete.
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+    /// The state changes could not be applied to the witness.
+    #[error(transparent)]
+    Trie(#[from] SparseStateTrieError),
+    /// The state root after execution doesn't match the one of the block.
+    #[error("state root mismatch: {0}")]
+    StateRootMismatch(GotExpected<B256>),
+}
+
+/// Validates the block of the input by executing it on top of the state revealed by the witness
+/// and comparing the resulting state root with the one of the block header.
+///
+/// The witness is not trusted: every trie node and bytecode is looked up by its hash, starting
+/// from the state root of the parent, and the parent is in turn authenticated by the hash chain of
+/// the ancestor headers.
+pub fn validate_block<E>(
+    executor_provider: &E,
+    consensus: &dyn Consensus,
+    input: &StatelessInput,
+) -> Result<StatelessValidationOutput, StatelessValidationError>
+where
+    E: BlockExecutorProvider,
+{
+    let block = input.block.clone().seal_slow();
+
+    // authenticate the ancestors, starting from the parent of the block
+    let mut block_hashes = HashMap::default();
+    let mut expected_hash = block.parent_hash;
+    let mut parent = None;
+    for header in input.ancestor_headers.iter().rev() {
+        let hash = header.hash_slow();
+        if hash != expected_hash {
+            if parent.is_none() {
+                return Err(StatelessValidationError::MissingParentHeader(block.parent_hash))
+            }
+            return Err(StatelessValidationError::DisconnectedAncestor { number: header.number })
+        }
+        parent.get_or_insert_with(|| SealedHeader::new(header.clone(), hash));
+        block_hashes.insert(header.number, hash);
+        expected_hash = header.parent_hash;
+    }
+    let parent = parent.ok_or(StatelessValidationError::MissingParentHeader(block.parent_hash))?;
+
+    consensus.validate_header(&block.header)?;
+    consensus.validate_header_against_parent(&block.header, &parent)?;
+    consensus.validate_block_pre_execution(&block)?;
+
+    let block =
+        block.unseal().with_recovered_senders().ok_or(StatelessValidationError::SenderRecovery)?;
+
+    // nodes and bytecodes are reference counted, so cloning the state is cheap
+    let state = WitnessState::new(parent.state_root, &input.witness, block_hashes);
+    let output = executor_provider
+        .executor(StateProviderDatabase::new(state.clone()))
+        .execute((&block, U256::MAX).into())?;
+    consensus.validate_block_post_execution(
+        &block,
+        PostExecutionInput::new(&output.receipts, &output.requests),
+    )?;
+
+    let hashed_state = HashedPostState::from_bundle_state(&output.state.state);
+    let mut trie = state.sparse_trie(&hashed_state)?;
+    trie.update(&hashed_state)?;
+    let state_root = trie.root().ok_or_else(|| {
+        ProviderError::TrieWitnessError("state root is not part of the witness".to_string())
+    })?;
+    if state_root != block.state_root {
+        return Err(StatelessValidationError::StateRootMismatch(GotExpected {
+            got: state_root,
+            expected: block.state_root,
+        }))
+    }
+
+    debug!(target: "stateless", number = block.number, ?state_root, "Validated block statelessly");
+    Ok(StatelessValidationOutput { state_root, gas_used: output.gas_used })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_genesis::{Genesis, GenesisAccount};
+    use alloy_primitives::{keccak256, Address, Bloom, Bytes};
+    use assert_matches::assert_matches;
+    use reth_chainspec::{ChainSpecBuilder, MAINNET};
+    use reth_db_common::init::init_genesis;
+    use reth_ethereum_consensus::EthBeaconConsensus;
+    use reth_evm_ethereum::execute::EthExecutorProvider;
+    use reth_primitives::{
+        proofs, public_key_to_address, BlockBody, Receipt, Transaction, TxEip1559, Withdrawals,
+    };
+    use reth_provider::{
+        test_utils::create_test_provider_factory_with_chain_spec, DatabaseProviderFactory,
+        StateProviderFactory,
+    };
+    use reth_testing_utils::generators::{self, sign_tx_with_key_pair};
+    use reth_trie::{witness::TrieWitness, StateRoot};
+    use reth_trie_db::{DatabaseStateRoot, DatabaseTrieWitness};
+    use std::{collections::BTreeMap, sync::Arc};
+
+    #[test]
+    fn validate_executed_block() {
+        let key_pair = generators::generate_keys(&mut generators::rng(), 1)[0];
+        let signer = public_key_to_address(key_pair.public_key());
+        // PUSH1 0x01 PUSH1 0x00 SSTORE STOP
+        let code = Bytes::from_static(&[0x60, 0x01, 0x60, 0x00, 0x55, 0x00]);
+        let contract = Address::with_last_byte(0x42);
+
+        let chain_spec = Arc::new(
+            ChainSpecBuilder::default()
+                .chain(MAINNET.chain)
+                .genesis(Genesis {
+                    gas_limit: 30_000_000,
+                    alloc: BTreeMap::from([
+                        (
+                            signer,
+                            GenesisAccount::default()
+                                .with_balance(U256::from(10).pow(U256::from(18))),
+                        ),
+                        (
+                            contract,
+                            GenesisAccount {
+                                code: Some(code.clone()),
+                                storage: Some(BTreeMap::from([(
+                                    B256::with_last_byte(1),
+                                    B256::with_last_byte(5),
+                                )])),
+                                ..Default::default()
+                            },
+                        ),
+                    ]),
+                    ..Default::default()
+                })
+                .shanghai_activated()
+                .build(),
+        );
+        let factory = create_test_provider_factory_with_chain_spec(chain_spec.clone());
+        init_genesis(&factory).unwrap();
+        let parent = chain_spec.sealed_genesis_header();
+
+        let transaction = sign_tx_with_key_pair(
+            key_pair,
+            Transaction::Eip1559(TxEip1559 {
+                chain_id: chain_spec.chain.id(),
+                nonce: 0,
+                gas_limit: 100_000,
+                to: contract.into(),
+                max_fee_per_gas: 2_000_000_000,
+                max_priority_fee_per_gas: 1_000_000_000,
+                ..Default::default()
+            }),
+        );
+        let timestamp = parent.timestamp + 12;
+        let mut block = Block {
+            header: Header {
+                parent_hash: parent.hash(),
+                ommers_hash: proofs::calculate_ommers_root(&[]),
+                beneficiary: Address::with_last_byte(0xfe),
+                transactions_root: proofs::calculate_transaction_root(&[transaction.clone()]),
+                withdrawals_root: Some(proofs::calculate_withdrawals_root(&[])),
+                number: parent.number + 1,
+                gas_limit: parent.gas_limit,
+                timestamp,
+                base_fee_per_gas: parent
+                    .next_block_base_fee(chain_spec.base_fee_params_at_timestamp(timestamp)),
+                ..Default::default()
+            },
+            body: BlockBody {
+                transactions: vec![transaction],
+                ommers: Vec::new(),
+                withdrawals: Some(Withdrawals::default()),
+                requests: None,
+            },
+        };
+
+        // execute the block against the database and complete its header with the outcome
+        let executor_provider = EthExecutorProvider::ethereum(chain_spec.clone());
+        let output = executor_provider
+            .executor(StateProviderDatabase::new(factory.latest().unwrap()))
+            .execute((&block.clone().with_recovered_senders().unwrap(), U256::MAX).into())
+            .unwrap();
+        let receipts = output.receipts.iter().map(Receipt::with_bloom_ref).collect::<Vec<_>>();
+        block.header.receipts_root = proofs::calculate_receipt_root_ref(&receipts);
+        block.header.logs_bloom = receipts.iter().fold(Bloom::ZERO, |bloom, r| bloom | r.bloom);
+        block.header.gas_used = output.gas_used;
+
+        let provider = factory.database_provider_ro().unwrap();
+        let hashed_state = HashedPostState::from_bundle_state(&output.state.state);
+        block.header.state_root =
+            StateRoot::overlay_root(provider.tx_ref(), hashed_state.clone()).unwrap();
+        let witness = ExecutionWitness {
+            state: TrieWitness::from_tx(provider.tx_ref())
+                .compute(hashed_state)
+                .unwrap()
+                .into_iter()
+                .collect(),
+            codes: [(keccak256(&code), code)].into_iter().collect(),
+            keys: None,
+        };
+
+        let consensus = EthBeaconConsensus::new(chain_spec);
+        let mut input =
+            StatelessInput { block, witness, ancestor_headers: vec![parent.header().clone()] };
+        let state_root = input.block.header.state_root;
+        assert_eq!(
+            validate_block(&executor_provider, &consensus, &input).unwrap(),
+            StatelessValidationOutput { state_root, gas_used: output.gas_used }
+        );
+
+        // a tampered post-state root is detected
+        input.block.header.state_root = B256::with_last_byte(1);
+        assert_matches!(
+            validate_block(&executor_provider, &consensus, &input),
+            Err(StatelessValidationError::StateRootMismatch(GotExpected { got, expected }))
+                if got == state_root && expected == B256::with_last_byte(1)
+        );
+    }
+}
@@ -0,0 +1,395 @@
+//! State backed by an execution witness.
+
+use alloy_primitives::{
+    keccak256,
+    map::{HashMap, HashSet},
+    Address, BlockNumber, Bytes, StorageKey, StorageValue, B256,
+};
+use alloy_rlp::Decodable;
+use alloy_rpc_types_debug::ExecutionWitness;
+use reth_primitives::{constants::KECCAK_EMPTY, Account, Bytecode};
+use reth_revm::database::EvmStateProvider;
+use reth_storage_errors::provider::{ProviderError, ProviderResult};
+use reth_trie::{
+    sparse::SparseStateTrie, HashedPostState, MultiProof, Nibbles, StorageMultiProof, TrieAccount,
+    TrieNode, EMPTY_ROOT_HASH,
+};
+use reth_trie_common::proof::ProofNodes;
+
+/// The state of a block's parent, as far as it is revealed by the execution witness of the block.
+///
+/// Trie nodes and bytecodes are keyed by the hash of their contents, so a tampered witness can
+/// only result in missing nodes, never in a different state.
+#[derive(Debug, Clone)]
+pub struct WitnessState {
+    /// The state root of the parent block.
+    state_root: B256,
+    /// Trie nodes of the account trie and the storage tries by their hash.
+    nodes: HashMap<B256, Bytes>,
+    /// Bytecodes by their hash.
+    codes: HashMap<B256, Bytes>,
+    /// Hashes of the ancestor blocks by their number.
+    block_hashes: HashMap<BlockNumber, B256>,
+}
+
+impl WitnessState {
+    /// Creates the state with the given state root from the witness and the hashes of the ancestor
+    /// blocks.
+    pub fn new(
+        state_root: B256,
+        witness: &ExecutionWitness,
+        block_hashes: HashMap<BlockNumber, B256>,
+    ) -> Self {
+        let nodes = witness.state.values().map(|node| (keccak256(node), node.clone())).collect();
+        let codes = witness.codes.values().map(|code| (keccak256(code), code.clone())).collect();
+        Self { state_root, nodes, codes, block_hashes }
+    }
+
+    /// Returns the state root of the state.
+    pub const fn state_root(&self) -> B256 {
+        self.state_root
+    }
+
+    /// Returns the account at the given hashed address from the account trie.
+    fn trie_account(&self, hashed_address: B256) -> ProviderResult<Option<TrieAccount>> {
+        let Some(value) = self.get_leaf(self.state_root, &Nibbles::unpack(hashed_address))? else {
+            return Ok(None)
+        };
+        Ok(Some(TrieAccount::decode(&mut &value[..])?))
+    }
+
+    /// Returns the value of the leaf at the given path of the trie with the given root.
+    ///
+    /// Returns an error if a node on the path is missing from the witness.
+    fn get_leaf(&self, root: B256, path: &Nibbles) -> ProviderResult<Option<Vec<u8>>> {
+        if root == EMPTY_ROOT_HASH {
+            return Ok(None)
+        }
+
+        let mut node = self.node(root)?;
+        let mut offset = 0;
+        loop {
+            match TrieNode::decode(&mut &node[..])? {
+                TrieNode::EmptyRoot => return Ok(None),
+                TrieNode::Branch(branch) => {
+                    let Some(nibble) = path.get(offset).copied() else { return Ok(None) };
+                    if !branch.state_mask.is_bit_set(nibble) {
+                        return Ok(None)
+                    }
+                    let index = branch.as_ref().first_child_index() +
+                        (0..nibble).filter(|idx| branch.state_mask.is_bit_set(*idx)).count();
+                    node = self.child(&branch.stack[index])?;
+                    offset += 1;
+                }
+                TrieNode::Extension(ext) => {
+                    if !path[offset..].starts_with(&ext.key[..]) {
+                        return Ok(None)
+                    }
+                    node = self.child(&ext.child)?;
+                    offset += ext.key.len();
+                }
+                TrieNode::Leaf(leaf) => {
+                    return Ok((path[offset..] == leaf.key[..]).then_some(leaf.value))
+                }
+            }
+        }
+    }
+
+    /// Returns the node referenced by its parent, either by hash or as an embedded node.
+    fn child(&self, reference: &[u8]) -> ProviderResult<Bytes> {
+        if reference.len() == B256::len_bytes() + 1 {
+            self.node(B256::from_slice(&reference[1..]))
+        } else {
+            Ok(Bytes::copy_from_slice(reference))
+        }
+    }
+
+    /// Returns the node with the given hash.
+    fn node(&self, hash: B256) -> ProviderResult<Bytes> {
+        self.nodes.get(&hash).cloned().ok_or_else(|| {
+            ProviderError::TrieWitnessError(format!("missing trie node in witness: {hash}"))
+        })
+    }
+
+    /// Collects the nodes of the trie with the given root that are revealed by the witness,
+    /// invoking the closure with the full path and the value of every revealed leaf.
+    fn collect_nodes(
+        &self,
+        root: B256,
+        mut on_leaf: impl FnMut(Nibbles, Vec<u8>),
+    ) -> ProviderResult<ProofNodes> {
+        let mut proof = Vec::new();
+        let mut stack = vec![(Nibbles::default(), self.node(root)?, true)];
+        while let Some((path, node, hashed)) = stack.pop() {
+            // embedded nodes are revealed together with their parent
+            if hashed {
+                proof.push((path.clone(), node.clone()));
+            }
+
+            let mut push_child = |path: Nibbles, reference: &[u8]| {
+                if reference.len() == B256::len_bytes() + 1 {
+                    // nodes missing from the witness stay blinded
+                    if let Some(node) = self.nodes.get(&B256::from_slice(&reference[1..])) {
+                        stack.push((path, node.clone(), true));
+                    }
+                } else {
+                    stack.push((path, Bytes::copy_from_slice(reference), false));
+                }
+            };
+
+            match TrieNode::decode(&mut &node[..])? {
+                TrieNode::EmptyRoot => {}
+                TrieNode::Branch(branch) => {
+                    let mut index = branch.as_ref().first_child_index();
+                    for nibble in 0..16 {
+                        if branch.state_mask.is_bit_set(nibble) {
+                            let mut child = path.clone();
+                            child.push(nibble);
+                            push_child(child, &branch.stack[index]);
+                            index += 1;
+                        }
+                    }
+                }
+                TrieNode::Extension(ext) => {
+                    let mut child = path.clone();
+                    child.extend_from_slice(&ext.key);
+                    push_child(child, &ext.child);
+                }
+                TrieNode::Leaf(leaf) => {
+                    let mut full_path = path;
+                    full_path.extend_from_slice(&leaf.key);
+                    on_leaf(full_path, leaf.value);
+                }
+            }
+        }
+        Ok(ProofNodes::from_iter(proof))
+    }
+
+    /// Reveals the witness in a [`SparseStateTrie`], so that the given state changes can be
+    /// applied to it.
+    ///
+    /// Storage tries are only revealed if the witness contains their root node, accounts that
+    /// don't exist yet are revealed with an empty storage trie.
+    pub fn sparse_trie(&self, state: &HashedPostState) -> ProviderResult<SparseStateTrie> {
+        let mut accounts = Vec::new();
+        let account_subtree = if self.state_root == EMPTY_ROOT_HASH {
+            StorageMultiProof::empty().subtree
+        } else {
+            self.collect_nodes(self.state_root, |path, value| accounts.push((path, value)))?
+        };
+
+        let mut targets = HashMap::<B256, HashSet<B256>>::default();
+        let mut storages = HashMap::default();
+        for (path, value) in accounts {
+            let hashed_address = B256::from_slice(&path.pack());
+            let storage_root = TrieAccount::decode(&mut &value[..])?.storage_root;
+            let storage = if storage_root == EMPTY_ROOT_HASH {
+                StorageMultiProof::empty()
+            } else if self.nodes.contains_key(&storage_root) {
+                let mut slots = HashSet::default();
+                let subtree = self.collect_nodes(storage_root, |path, _| {
+                    slots.insert(B256::from_slice(&path.pack()));
+                })?;
+                targets.entry(hashed_address).or_default().extend(slots);
+                StorageMultiProof {
+                    root: storage_root,
+                    subtree,
+                    branch_node_hash_masks: Default::default(),
+                    branch_node_tree_masks: Default::default(),
+                }
+            } else {
+                // the storage of the account isn't part of the witness
+                continue
+            };
+            targets.entry(hashed_address).or_default();
+            storages.insert(hashed_address, storage);
+        }
+
+        // changed accounts that are proven to not exist yet
+        for hashed_address in state.accounts.keys().chain(state.storages.keys()) {
+            if !targets.contains_key(hashed_address) &&
+                matches!(self.trie_account(*hashed_address), Ok(None))
+            {
+                targets.entry(*hashed_address).or_default();
+            }
+        }
+
+        let mut trie = SparseStateTrie::default();
+        let multiproof = MultiProof { account_subtree, storages, ..Default::default() };
+        trie.reveal_multiproof(targets, multiproof)
+            .map_err(|err| ProviderError::TrieWitnessError(err.to_string()))?;
+        Ok(trie)
+    }
+}
+
+impl EvmStateProvider for WitnessState {
+    fn basic_account(&self, address: Address) -> ProviderResult<Option<Account>> {
+        Ok(self.trie_account(keccak256(address))?.map(|account| Account {
+            nonce: account.nonce,
+            balance: account.balance,
+            bytecode_hash: (account.code_hash != KECCAK_EMPTY).then_some(account.code_hash),
+        }))
+    }
+
+    fn block_hash(&self, number: BlockNumber) -> ProviderResult<Option<B256>> {
+        self.block_hashes
+            .get(&number)
+            .copied()
+            .map(Some)
+            .ok_or_else(|| ProviderError::HeaderNotFound(number.into()))
+    }
+
+    fn bytecode_by_hash(&self, code_hash: B256) -> ProviderResult<Option<Bytecode>> {
+        if code_hash == KECCAK_EMPTY {
+            return Ok(Some(Bytecode::new_raw(Bytes::new())))
+        }
+        let code = self.codes.get(&code_hash).ok_or_else(|| {
+            ProviderError::TrieWitnessError(format!("missing bytecode in witness: {code_hash}"))
+        })?;
+        Ok(Some(Bytecode::new_raw(code.clone())))
+    }
+
+    fn storage(
+        &self,
+        account: Address,
+        storage_key: StorageKey,
+    ) -> ProviderResult<Option<StorageValue>> {
+        let Some(account) = self.trie_account(keccak256(account))? else { return Ok(None) };
+        let path = Nibbles::unpack(keccak256(storage_key));
+        let Some(value) = self.get_leaf(account.storage_root, &path)? else { return Ok(None) };
+        Ok(Some(StorageValue::decode(&mut &value[..])?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::U256;
+    use reth_trie::{proof::ProofRetainer, HashBuilder, HashedStorage};
+    use std::collections::BTreeMap;
+
+    /// Computes the root of the trie with the given leaves, adding all of its nodes to the
+    /// witness.
+    fn trie_root(leaves: &BTreeMap<Nibbles, Vec<u8>>, witness: &mut ExecutionWitness) -> B256 {
+        let mut hash_builder = HashBuilder::default()
+            .with_proof_retainer(ProofRetainer::from_iter(leaves.keys().cloned()));
+        for (path, value) in leaves {
+            hash_builder.add_leaf(path.clone(), value);
+        }
+        let root = hash_builder.root();
+        for (_, node) in hash_builder.take_proof_nodes().iter() {
+            witness.state.insert(keccak256(node), node.clone());
+        }
+        root
+    }
+
+    fn account_leaf(account: Account, storage_root: B256) -> Vec<u8> {
+        alloy_rlp::encode(TrieAccount::from((account, storage_root)))
+    }
+
+    #[test]
+    fn witness_state() {
+        let code = Bytes::from_static(&[0x60, 0x00, 0x60, 0x00, 0xf3]);
+        let alice = Address::with_last_byte(1);
+        let bob = Address::with_last_byte(2);
+        let contract = Address::with_last_byte(3);
+        let slot = B256::with_last_byte(1);
+
+        let mut witness = ExecutionWitness::default();
+        witness.codes.insert(keccak256(&code), code.clone());
+        let storage = BTreeMap::from([
+            (
+                Nibbles::unpack(keccak256(slot)),
+                alloy_rlp::encode_fixed_size(&U256::from(7)).to_vec(),
+            ),
+            (
+                Nibbles::unpack(keccak256(B256::with_last_byte(2))),
+                alloy_rlp::encode_fixed_size(&U256::from(8)).to_vec(),
+            ),
+        ]);
+        let storage_root = trie_root(&storage, &mut witness);
+
+        let alice_account = Account { nonce: 1, balance: U256::from(100), bytecode_hash: None };
+        let bob_account = Account { nonce: 0, balance: U256::from(5), bytecode_hash: None };
+        let contract_account =
+            Account { nonce: 1, balance: U256::ZERO, bytecode_hash: Some(keccak256(&code)) };
+        let mut accounts = BTreeMap::from([
+            (Nibbles::unpack(keccak256(alice)), account_leaf(alice_account, EMPTY_ROOT_HASH)),
+            (Nibbles::unpack(keccak256(bob)), account_leaf(bob_account, EMPTY_ROOT_HASH)),
+            (Nibbles::unpack(keccak256(contract)), account_leaf(contract_account, storage_root)),
+        ]);
+        let state_root = trie_root(&accounts, &mut witness);
+
+        let state = WitnessState::new(state_root, &witness, HashMap::from_iter([(1, B256::ZERO)]));
+        assert_eq!(state.basic_account(alice).unwrap(), Some(alice_account));
+        assert_eq!(state.basic_account(contract).unwrap(), Some(contract_account));
+        assert_eq!(state.basic_account(Address::with_last_byte(4)).unwrap(), None);
+        assert_eq!(state.storage(contract, slot).unwrap(), Some(U256::from(7)));
+        assert_eq!(state.storage(contract, B256::with_last_byte(3)).unwrap(), None);
+        assert_eq!(
+            state.bytecode_by_hash(keccak256(&code)).unwrap(),
+            Some(Bytecode::new_raw(code))
+        );
+        assert_eq!(state.block_hash(1).unwrap(), Some(B256::ZERO));
+        assert!(state.block_hash(0).is_err());
+
+        // apply state changes, including a new account and a changed storage slot
+        let carol = Address::with_last_byte(5);
+        let carol_account = Account { nonce: 0, balance: U256::from(1), bytecode_hash: None };
+        let mut post_state = HashedPostState::default();
+        post_state.accounts.insert(keccak256(alice), None);
+        post_state.accounts.insert(keccak256(carol), Some(carol_account));
+        post_state.storages.insert(
+            keccak256(contract),
+            HashedStorage::from_iter(false, [(keccak256(slot), U256::from(9))]),
+        );
+        let mut trie = state.sparse_trie(&post_state).unwrap();
+        trie.update(&post_state).unwrap();
+
+        let mut storage = storage;
+        storage.insert(
+            Nibbles::unpack(keccak256(slot)),
+            alloy_rlp::encode_fixed_size(&U256::from(9)).to_vec(),
+        );
+        let storage_root = trie_root(&storage, &mut ExecutionWitness::default());
+        accounts.remove(&Nibbles::unpack(keccak256(alice)));
+        accounts.insert(
+            Nibbles::unpack(keccak256(carol)),
+            account_leaf(carol_account, EMPTY_ROOT_HASH),
+        );
+        accounts.insert(
+            Nibbles::unpack(keccak256(contract)),
+            account_leaf(contract_account, storage_root),
+        );
+        assert_eq!(trie.root(), Some(trie_root(&accounts, &mut ExecutionWitness::default())));
+    }
+
+    #[test]
+    fn missing_witness_nodes() {
+        let mut witness = ExecutionWitness::default();
+        let accounts = (0..16u8)
+            .map(|i| {
+                let account = Account { nonce: i as u64, ..Default::default() };
+                (
+                    Nibbles::unpack(keccak256(Address::with_last_byte(i))),
+                    account_leaf(account, EMPTY_ROOT_HASH),
+                )
+            })
+            .collect::<BTreeMap<_, _>>();
+        let state_root = trie_root(&accounts, &mut witness);
+
+        // only keep the root node
+        witness.state.retain(|hash, _| *hash == state_root);
+        let state = WitnessState::new(state_root, &witness, HashMap::default());
+        assert!(matches!(
+            state.basic_account(Address::with_last_byte(1)),
+            Err(ProviderError::TrieWitnessError(_))
+        ));
+
+        // nodes that don't hash to their key are ignored
+        let mut witness = ExecutionWitness::default();
+        witness.state.insert(state_root, Bytes::from_static(&[0x80]));
+        let state = WitnessState::new(state_root, &witness, HashMap::default());
+        assert!(state.basic_account(Address::with_last_byte(1)).is_err());
+    }
+}
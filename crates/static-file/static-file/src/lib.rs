@@ -10,12 +10,16 @@
 mod event;
 pub mod segments;
 mod static_file_producer;
+mod verifier;
 
 pub use event::StaticFileProducerEvent;
 pub use static_file_producer::{
     StaticFileProducer, StaticFileProducerInner, StaticFileProducerResult,
     StaticFileProducerWithResult, StaticFileTargets,
 };
+pub use verifier::{
+    CorruptBlock, StaticFileCorruption, StaticFileVerifier, StaticFileVerifierReport,
+};
 
 // Re-export for convenience.
 pub use reth_static_file_types::*;
@@ -0,0 +1,447 @@
+//! Integrity verification of static files.
+
+use alloy_primitives::{BlockNumber, B256};
+use rayon::prelude::*;
+use reth_chainspec::{EthChainSpec, EthereumHardforks};
+use reth_db::static_file::iter_static_files;
+use reth_primitives::{
+    proofs::{calculate_receipt_root_no_memo, calculate_transaction_root},
+    Header,
+};
+use reth_provider::{
+    providers::StaticFileProvider, BlockHashReader, BlockReader, ChainSpecProvider, DBProvider,
+    DatabaseProviderFactory, HeaderProvider, ReceiptProvider, StaticFileProviderFactory,
+    TransactionsProvider,
+};
+use reth_static_file_types::{SegmentRangeInclusive, StaticFileSegment};
+use reth_storage_errors::provider::{ProviderError, ProviderResult};
+use std::{ops::RangeInclusive, time::Instant};
+use tracing::{debug, info, warn};
+
+/// Reason a block of a static file segment failed verification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StaticFileCorruption {
+    /// A row could not be read, decompressed or decoded.
+    Unreadable(String),
+    /// A row expected to be in the static file is missing.
+    Missing,
+    /// The stored header hash doesn't match the hash of the decoded header.
+    HeaderHash {
+        /// Hash stored alongside the header.
+        stored: B256,
+        /// Hash of the decoded header.
+        computed: B256,
+    },
+    /// The parent hash of the header doesn't match the hash of the previous header.
+    ParentHash {
+        /// Hash of the previous header.
+        expected: B256,
+        /// Parent hash of the header.
+        got: B256,
+    },
+    /// The transactions root computed from the static file doesn't match the header.
+    TransactionsRoot {
+        /// Root in the header.
+        expected: B256,
+        /// Root computed from the static file transactions.
+        computed: B256,
+    },
+    /// The receipts root computed from the static file doesn't match the header.
+    ReceiptsRoot {
+        /// Root in the header.
+        expected: B256,
+        /// Root computed from the static file receipts.
+        computed: B256,
+    },
+}
+
+/// A block of a static file segment that failed verification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorruptBlock {
+    /// Segment of the static file.
+    pub segment: StaticFileSegment,
+    /// Block number.
+    pub block: BlockNumber,
+    /// What is wrong with the block.
+    pub corruption: StaticFileCorruption,
+}
+
+/// Outcome of [`StaticFileVerifier::run`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StaticFileVerifierReport {
+    /// Jars that were verified, with their block ranges.
+    pub verified: Vec<(StaticFileSegment, SegmentRangeInclusive)>,
+    /// Blocks that failed verification, sorted by segment and block number.
+    pub corrupted: Vec<CorruptBlock>,
+}
+
+impl StaticFileVerifierReport {
+    /// Returns `true` if no corruption was found.
+    pub fn is_ok(&self) -> bool {
+        self.corrupted.is_empty()
+    }
+
+    /// Returns the corrupted blocks merged into contiguous ranges per segment.
+    pub fn corrupt_ranges(&self) -> Vec<(StaticFileSegment, RangeInclusive<BlockNumber>)> {
+        let mut ranges: Vec<(StaticFileSegment, RangeInclusive<BlockNumber>)> = Vec::new();
+        for corrupt in &self.corrupted {
+            match ranges.last_mut() {
+                Some((segment, range))
+                    if *segment == corrupt.segment && *range.end() + 1 >= corrupt.block =>
+                {
+                    *range = *range.start()..=corrupt.block.max(*range.end());
+                }
+                _ => ranges.push((corrupt.segment, corrupt.block..=corrupt.block)),
+            }
+        }
+        ranges
+    }
+}
+
+/// Verifies that every row of every static file jar can be decompressed and decoded, and that the
+/// data is consistent with the headers:
+///
+/// * [`StaticFileSegment::Headers`]: header hashes are recomputed and checked to be chained.
+/// * [`StaticFileSegment::Transactions`]: transaction roots are recomputed for every block.
+/// * [`StaticFileSegment::Receipts`]: receipt roots are recomputed for every block from Byzantium
+///   onwards. Earlier receipts commit to intermediate state roots, which aren't stored.
+///
+/// Static files have no block to transaction mapping, so transaction based segments use the
+/// block body indices from the database.
+#[derive(Debug)]
+pub struct StaticFileVerifier<Provider> {
+    provider: Provider,
+    segments: Vec<StaticFileSegment>,
+    block_range: Option<RangeInclusive<BlockNumber>>,
+}
+
+impl<Provider> StaticFileVerifier<Provider> {
+    /// Creates a new [`StaticFileVerifier`] over all segments and all blocks.
+    pub fn new(provider: Provider) -> Self {
+        Self {
+            provider,
+            segments: vec![
+                StaticFileSegment::Headers,
+                StaticFileSegment::Transactions,
+                StaticFileSegment::Receipts,
+            ],
+            block_range: None,
+        }
+    }
+
+    /// Restricts verification to the given segments.
+    pub fn with_segments(mut self, segments: Vec<StaticFileSegment>) -> Self {
+        self.segments = segments;
+        self
+    }
+
+    /// Restricts verification to jars overlapping the given block range.
+    pub fn with_block_range(mut self, block_range: RangeInclusive<BlockNumber>) -> Self {
+        self.block_range = Some(block_range);
+        self
+    }
+}
+
+impl<Provider> StaticFileVerifier<Provider>
+where
+    Provider: StaticFileProviderFactory
+        + DatabaseProviderFactory<Provider: BlockReader>
+        + ChainSpecProvider<ChainSpec: EthChainSpec + EthereumHardforks>,
+{
+    /// Verifies all jars of the configured segments, in parallel.
+    pub fn run(&self) -> ProviderResult<StaticFileVerifierReport> {
+        let static_file_provider = self.provider.static_file_provider();
+        let static_files = iter_static_files(static_file_provider.directory())
+            .map_err(|err| ProviderError::NippyJar(err.to_string()))?;
+
+        let mut jars = Vec::new();
+        for segment in &self.segments {
+            for (block_range, _) in static_files.get(segment).into_iter().flatten() {
+                if self.block_range.as_ref().map_or(true, |range| {
+                    block_range.start() <= *range.end() && block_range.end() >= *range.start()
+                }) {
+                    jars.push((*segment, *block_range));
+                }
+            }
+        }
+
+        info!(target: "static_file::verifier", jars = jars.len(), "Verifying static files");
+        let start = Instant::now();
+
+        let mut corrupted = jars
+            .par_iter()
+            .map(|(segment, block_range)| {
+                let corrupted = self.verify_jar(&static_file_provider, *segment, *block_range)?;
+                debug!(target: "static_file::verifier", %segment, %block_range, corrupted = corrupted.len(), "Verified static file");
+                Ok(corrupted)
+            })
+            .collect::<ProviderResult<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        corrupted.sort_by_key(|corrupt| (corrupt.segment, corrupt.block));
+
+        let report = StaticFileVerifierReport { verified: jars, corrupted };
+        info!(
+            target: "static_file::verifier",
+            corrupted = report.corrupted.len(),
+            elapsed = ?start.elapsed(),
+            "Finished verifying static files"
+        );
+
+        Ok(report)
+    }
+
+    /// Verifies every block of a single jar.
+    fn verify_jar(
+        &self,
+        static_file_provider: &StaticFileProvider,
+        segment: StaticFileSegment,
+        block_range: SegmentRangeInclusive,
+    ) -> ProviderResult<Vec<CorruptBlock>> {
+        let provider = self.provider.database_provider_ro()?.disable_long_read_transaction_safety();
+        let chain_spec = self.provider.chain_spec();
+
+        let mut corrupted = Vec::new();
+        let mut report = |block, corruption| {
+            warn!(target: "static_file::verifier", %segment, block, ?corruption, "Corrupted static file data");
+            corrupted.push(CorruptBlock { segment, block, corruption });
+        };
+
+        let range =
+            self.block_range.as_ref().map_or(block_range.start()..=block_range.end(), |r| {
+                block_range.start().max(*r.start())..=block_range.end().min(*r.end())
+            });
+
+        // Hash of the previous header, if it could be read.
+        let mut parent_hash = match range.start().checked_sub(1) {
+            Some(parent) => static_file_provider.block_hash(parent).ok().flatten(),
+            None => None,
+        };
+
+        for block in range {
+            let header = match read_header(static_file_provider, block) {
+                Ok(header) => header,
+                Err(corruption) => {
+                    if segment.is_headers() {
+                        report(block, corruption);
+                    }
+                    parent_hash = None;
+                    continue
+                }
+            };
+
+            match segment {
+                StaticFileSegment::Headers => {
+                    let (header, stored_hash) = header;
+                    let computed = header.hash_slow();
+                    if computed != stored_hash {
+                        report(
+                            block,
+                            StaticFileCorruption::HeaderHash { stored: stored_hash, computed },
+                        );
+                    } else if let Err(err) = static_file_provider.header_td_by_number(block) {
+                        report(block, StaticFileCorruption::Unreadable(err.to_string()));
+                    }
+
+                    if let Some(expected) = parent_hash {
+                        if header.parent_hash != expected {
+                            report(
+                                block,
+                                StaticFileCorruption::ParentHash {
+                                    expected,
+                                    got: header.parent_hash,
+                                },
+                            );
+                        }
+                    }
+                    parent_hash = Some(computed);
+                }
+                StaticFileSegment::Transactions => {
+                    let Some(indices) = provider.block_body_indices(block)? else { break };
+                    match static_file_provider.transactions_by_tx_range(indices.tx_num_range()) {
+                        Ok(transactions) if transactions.len() as u64 != indices.tx_count() => {
+                            report(block, StaticFileCorruption::Missing)
+                        }
+                        Ok(transactions) => {
+                            let computed = calculate_transaction_root(
+                                &transactions
+                                    .into_iter()
+                                    .map(|tx| tx.with_hash())
+                                    .collect::<Vec<_>>(),
+                            );
+                            if computed != header.0.transactions_root {
+                                report(
+                                    block,
+                                    StaticFileCorruption::TransactionsRoot {
+                                        expected: header.0.transactions_root,
+                                        computed,
+                                    },
+                                );
+                            }
+                        }
+                        Err(err) => {
+                            report(block, StaticFileCorruption::Unreadable(err.to_string()))
+                        }
+                    }
+                }
+                StaticFileSegment::Receipts => {
+                    let Some(indices) = provider.block_body_indices(block)? else { break };
+                    match static_file_provider.receipts_by_tx_range(indices.tx_num_range()) {
+                        Ok(receipts) if receipts.len() as u64 != indices.tx_count() => {
+                            report(block, StaticFileCorruption::Missing)
+                        }
+                        Ok(receipts) => {
+                            // Pre-Byzantium receipts commit to intermediate state roots, and
+                            // Optimism deposit receipts are encoded differently, so only the rows
+                            // are checked to be decodable there.
+                            if chain_spec.is_byzantium_active_at_block(block) &&
+                                !chain_spec.is_optimism()
+                            {
+                                let computed = calculate_receipt_root_no_memo(
+                                    &receipts.iter().collect::<Vec<_>>(),
+                                );
+                                if computed != header.0.receipts_root {
+                                    report(
+                                        block,
+                                        StaticFileCorruption::ReceiptsRoot {
+                                            expected: header.0.receipts_root,
+                                            computed,
+                                        },
+                                    );
+                                }
+                            }
+                        }
+                        Err(err) => {
+                            report(block, StaticFileCorruption::Unreadable(err.to_string()))
+                        }
+                    }
+                }
+            }
+        }
+
+        // Removes from cache, since if we have many files, it may hit ulimit limits
+        static_file_provider.remove_cached_provider(
+            segment,
+            static_file_provider.find_fixed_range(block_range.start()).end(),
+        );
+
+        Ok(corrupted)
+    }
+}
+
+/// Reads a header and its stored hash from static files.
+fn read_header(
+    static_file_provider: &StaticFileProvider,
+    block: BlockNumber,
+) -> Result<(Header, B256), StaticFileCorruption> {
+    match static_file_provider.sealed_header(block) {
+        Ok(Some(header)) => {
+            let hash = header.hash();
+            Ok((header.unseal(), hash))
+        }
+        Ok(None) => Err(StaticFileCorruption::Missing),
+        Err(err) => Err(StaticFileCorruption::Unreadable(err.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_stages::test_utils::{StorageKind, TestStageDB};
+    use reth_testing_utils::generators::{self, random_block_range, BlockRangeParams};
+
+    #[test]
+    fn verify_static_files() {
+        let mut rng = generators::rng();
+        let db = TestStageDB::default();
+
+        let blocks = random_block_range(
+            &mut rng,
+            0..=3,
+            BlockRangeParams { parent: Some(B256::ZERO), tx_count: 2..3, ..Default::default() },
+        );
+        db.insert_blocks(blocks.iter(), StorageKind::Static).expect("insert blocks");
+
+        let report = StaticFileVerifier::new(db.factory.clone())
+            .with_segments(vec![StaticFileSegment::Headers, StaticFileSegment::Transactions])
+            .run()
+            .expect("verify static files");
+        assert!(report.is_ok(), "{:?}", report.corrupted);
+        assert_eq!(
+            report.verified,
+            vec![
+                (StaticFileSegment::Headers, SegmentRangeInclusive::new(0, 3)),
+                (StaticFileSegment::Transactions, SegmentRangeInclusive::new(0, 3)),
+            ]
+        );
+    }
+
+    #[test]
+    fn detect_corrupted_header() {
+        let mut rng = generators::rng();
+        let db = TestStageDB::default();
+
+        let blocks = random_block_range(
+            &mut rng,
+            0..=3,
+            BlockRangeParams { parent: Some(B256::ZERO), tx_count: 0..1, ..Default::default() },
+        );
+        db.insert_blocks(blocks.iter(), StorageKind::Static).expect("insert blocks");
+
+        // The hash of the last header is the last column of the last row, so flipping the last
+        // byte of the data file corrupts the stored hash of block 3.
+        let static_file_provider = db.factory.static_file_provider();
+        let path = static_file_provider
+            .directory()
+            .join(StaticFileSegment::Headers.filename(&static_file_provider.find_fixed_range(0)));
+        let mut data = std::fs::read(&path).unwrap();
+        *data.last_mut().unwrap() ^= 0xff;
+        std::fs::write(&path, data).unwrap();
+        static_file_provider.remove_cached_provider(
+            StaticFileSegment::Headers,
+            static_file_provider.find_fixed_range(0).end(),
+        );
+
+        let report = StaticFileVerifier::new(db.factory.clone())
+            .with_segments(vec![StaticFileSegment::Headers])
+            .run()
+            .expect("verify static files");
+        assert_eq!(
+            report
+                .corrupted
+                .iter()
+                .map(|corrupt| (corrupt.segment, corrupt.block))
+                .collect::<Vec<_>>(),
+            vec![(StaticFileSegment::Headers, 3)]
+        );
+        assert_eq!(report.corrupt_ranges(), vec![(StaticFileSegment::Headers, 3..=3)]);
+    }
+
+    #[test]
+    fn corrupt_ranges() {
+        let corrupt = |segment, block| CorruptBlock {
+            segment,
+            block,
+            corruption: StaticFileCorruption::Missing,
+        };
+        let report = StaticFileVerifierReport {
+            verified: Vec::new(),
+            corrupted: vec![
+                corrupt(StaticFileSegment::Headers, 1),
+                corrupt(StaticFileSegment::Headers, 2),
+                corrupt(StaticFileSegment::Headers, 2),
+                corrupt(StaticFileSegment::Headers, 5),
+                corrupt(StaticFileSegment::Transactions, 6),
+            ],
+        };
+        assert_eq!(
+            report.corrupt_ranges(),
+            vec![
+                (StaticFileSegment::Headers, 1..=2),
+                (StaticFileSegment::Headers, 5..=5),
+                (StaticFileSegment::Transactions, 6..=6),
+            ]
+        );
+    }
+}
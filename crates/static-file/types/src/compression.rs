@@ -1,20 +1,26 @@
+use serde::{Deserialize, Serialize};
 use strum::AsRefStr;
 
 /// Static File compression types.
-#[derive(Debug, Copy, Clone, Default, AsRefStr)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash, AsRefStr, Serialize, Deserialize)]
 #[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
 pub enum Compression {
     /// LZ4 compression algorithm.
     #[strum(serialize = "lz4")]
+    #[serde(rename = "lz4")]
     Lz4,
     /// Zstandard (Zstd) compression algorithm.
     #[strum(serialize = "zstd")]
+    #[serde(rename = "zstd")]
     Zstd,
     /// Zstandard (Zstd) compression algorithm with a dictionary.
     #[strum(serialize = "zstd-dict")]
+    #[serde(rename = "zstd-dict")]
+    #[cfg_attr(feature = "clap", value(name = "zstd-dict"))]
     ZstdWithDictionary,
     /// No compression.
     #[strum(serialize = "uncompressed")]
+    #[serde(rename = "uncompressed")]
     #[default]
     Uncompressed,
 }
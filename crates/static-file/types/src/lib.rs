@@ -13,7 +13,9 @@ mod segment;
 
 use alloy_primitives::BlockNumber;
 pub use compression::Compression;
-pub use segment::{SegmentConfig, SegmentHeader, SegmentRangeInclusive, StaticFileSegment};
+pub use segment::{
+    SegmentConfig, SegmentHeader, SegmentRangeInclusive, StaticFileSegment, DEFAULT_MAX_DICT_SIZE,
+};
 
 /// Default static file block count.
 pub const DEFAULT_BLOCKS_PER_STATIC_FILE: u64 = 500_000;
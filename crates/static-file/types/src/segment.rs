@@ -47,8 +47,15 @@ impl StaticFileSegment {
     }
 
     /// Returns the default configuration of the segment.
+    ///
+    /// Transactions and receipts are left uncompressed, since their encoding already compresses
+    /// them natively.
     pub const fn config(&self) -> SegmentConfig {
-        SegmentConfig { compression: Compression::Lz4 }
+        let compression = match self {
+            Self::Headers => Compression::Lz4,
+            Self::Transactions | Self::Receipts => Compression::Uncompressed,
+        };
+        SegmentConfig { compression, max_dict_size: DEFAULT_MAX_DICT_SIZE }
     }
 
     /// Returns the number of columns for the segment
@@ -293,11 +300,23 @@ impl SegmentHeader {
     }
 }
 
+/// Default maximum size of a zstd dictionary, in bytes.
+pub const DEFAULT_MAX_DICT_SIZE: usize = 110 * 1024;
+
 /// Configuration used on the segment.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SegmentConfig {
     /// Compression used on the segment
     pub compression: Compression,
+    /// Maximum size of each column dictionary, in bytes.
+    ///
+    /// Only used with [`Compression::ZstdWithDictionary`].
+    #[serde(default = "default_max_dict_size")]
+    pub max_dict_size: usize,
+}
+
+const fn default_max_dict_size() -> usize {
+    DEFAULT_MAX_DICT_SIZE
 }
 
 /// Helper type to handle segment transaction and block INCLUSIVE ranges.
@@ -84,6 +84,13 @@ where
     let genesis = chain.genesis();
     let hash = chain.genesis_hash();
 
+    // The genesis header was pruned together with the rest of the headers history, so it must
+    // have been written already.
+    if factory.static_file_provider().is_pruned_block(StaticFileSegment::Headers, 0) {
+        debug!("Genesis header pruned, skipping.");
+        return Ok(hash)
+    }
+
     // Check if we already have the genesis header or if we have the wrong one.
     match factory.block_hash(0) {
         Ok(None) | Err(ProviderError::MissingStaticFileBlock(StaticFileSegment::Headers, 0)) => {}
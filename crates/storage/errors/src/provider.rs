@@ -136,6 +136,9 @@ pub enum ProviderError {
     /// Static File Provider was initialized as read-only.
     #[display("cannot get a writer on a read-only environment.")]
     ReadOnlyStaticFileAccess,
+    /// The provider factory was opened as a secondary reader, which can't write to the database.
+    #[display("cannot write to the database from a secondary reader.")]
+    SecondaryWriteAccess,
     /// Error encountered when the block number conversion from U256 to u64 causes an overflow.
     #[display("failed to convert block number U256 to u64: {_0}")]
     BlockNumberOverflow(U256),
@@ -67,7 +67,7 @@ impl Zstd {
     }
 
     /// If using dictionaries, creates a list of [`Compressor`].
-    pub fn compressors(&self) -> Result<Option<Vec<Compressor<'_>>>, NippyJarError> {
+    pub fn compressors(&self) -> Result<Option<Vec<Compressor<'static>>>, NippyJarError> {
         match self.state {
             ZstdState::PendingDictionary => Err(NippyJarError::CompressorNotReady),
             ZstdState::Ready => {
@@ -185,7 +185,6 @@ impl Compression for Zstd {
         matches!(self.state, ZstdState::Ready)
     }
 
-    #[cfg(test)]
     /// If using it with dictionaries, prepares a dictionary for each column.
     fn prepare_compression(
         &mut self,
@@ -273,7 +272,6 @@ impl std::fmt::Debug for ZstdDictionaries<'_> {
 }
 
 impl ZstdDictionaries<'_> {
-    #[cfg(test)]
     /// Creates [`ZstdDictionaries`].
     pub(crate) fn new(raw: Vec<RawDictionary>) -> Self {
         Self(raw.into_iter().map(ZstdDictionary::Raw).collect())
@@ -301,7 +299,7 @@ impl ZstdDictionaries<'_> {
     }
 
     /// Creates a list of compressors from a list of [`ZstdDictionary::Raw`].
-    pub(crate) fn compressors(&self) -> Result<Vec<Compressor<'_>>, NippyJarError> {
+    pub(crate) fn compressors(&self) -> Result<Vec<Compressor<'static>>, NippyJarError> {
         Ok(self
             .iter()
             .flat_map(|dict| {
@@ -316,7 +314,6 @@ impl ZstdDictionaries<'_> {
 /// A Zstd dictionary. It's created and serialized with [`ZstdDictionary::Raw`], and deserialized as
 /// [`ZstdDictionary::Loaded`].
 pub(crate) enum ZstdDictionary<'a> {
-    #[allow(dead_code)]
     Raw(RawDictionary),
     Loaded(DecoderDictionary<'a>),
 }
@@ -29,9 +29,7 @@ use std::os::windows::prelude::OpenOptionsExt;
 use tracing::*;
 
 pub mod compression;
-#[cfg(test)]
-use compression::Compression;
-use compression::Compressors;
+use compression::{Compression, Compressors};
 
 /// empty enum for backwards compatibility
 #[derive(Debug, Serialize, Deserialize)]
@@ -57,8 +55,8 @@ pub use consistency::NippyJarChecker;
 
 const NIPPY_JAR_VERSION: usize = 1;
 
-const INDEX_FILE_EXTENSION: &str = "idx";
-const OFFSETS_FILE_EXTENSION: &str = "off";
+pub const INDEX_FILE_EXTENSION: &str = "idx";
+pub const OFFSETS_FILE_EXTENSION: &str = "off";
 pub const CONFIG_FILE_EXTENSION: &str = "conf";
 
 /// A [`RefRow`] is a list of column value slices pointing to either an internal buffer or a
@@ -256,7 +254,6 @@ impl<H: NippyJarHeader> NippyJar<H> {
     }
 }
 
-#[cfg(test)]
 impl<H: NippyJarHeader> NippyJar<H> {
     /// If required, prepares any compression algorithm to an early pass of the data.
     pub fn prepare_compression(
@@ -546,6 +543,14 @@ mod tests {
         } else {
             panic!("Expected Zstd compressor")
         }
+
+        // Dictionaries loaded from disk can't be used for compression, so appending must fail
+        // instead of writing data without them.
+        let mut writer = NippyJarWriter::new(loaded_nippy).unwrap();
+        assert!(matches!(
+            writer.append_column(Some(Ok(&col1[0]))),
+            Err(NippyJarError::DictionaryNotLoaded)
+        ));
     }
 
     #[test]
@@ -173,6 +173,13 @@ impl<SP: StateProvider, EDP: ExecutionDataProvider> StateProofProvider
         input.prepend(HashedPostState::from_bundle_state(&bundle_state.state));
         self.state_provider.witness(input, target)
     }
+
+    fn database_trie_input(&self) -> ProviderResult<Option<TrieInput>> {
+        let Some(mut input) = self.state_provider.database_trie_input()? else { return Ok(None) };
+        let bundle_state = self.block_execution_data_provider.execution_outcome().state();
+        input.append(HashedPostState::from_bundle_state(&bundle_state.state));
+        Ok(Some(input))
+    }
 }
 
 impl<SP: StateProvider, EDP: ExecutionDataProvider> StateProvider for BundleStateProvider<SP, EDP> {
@@ -11,7 +11,7 @@ use alloy_eips::BlockHashOrNumber;
 use alloy_primitives::{Address, BlockHash, BlockNumber, TxHash, TxNumber, B256, U256};
 use core::fmt;
 use reth_chainspec::{ChainInfo, EthereumHardforks};
-use reth_db::{init_db, mdbx::DatabaseArguments, DatabaseEnv};
+use reth_db::{init_db, mdbx::DatabaseArguments, open_db_read_only, DatabaseEnv};
 use reth_db_api::{database::Database, models::StoredBlockBodyIndices};
 use reth_errors::{RethError, RethResult};
 use reth_evm::ConfigureEvmEnv;
@@ -53,6 +53,8 @@ pub struct ProviderFactory<N: NodeTypesWithDB> {
     static_file_provider: StaticFileProvider,
     /// Optional pruning configuration
     prune_modes: PruneModes,
+    /// Whether the storage was opened as a secondary reader, which refuses write access.
+    secondary: bool,
 }
 
 impl<N> fmt::Debug for ProviderFactory<N>
@@ -60,12 +62,13 @@ where
     N: NodeTypesWithDB<DB: fmt::Debug, ChainSpec: fmt::Debug>,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let Self { db, chain_spec, static_file_provider, prune_modes } = self;
+        let Self { db, chain_spec, static_file_provider, prune_modes, secondary } = self;
         f.debug_struct("ProviderFactory")
             .field("db", &db)
             .field("chain_spec", &chain_spec)
             .field("static_file_provider", &static_file_provider)
             .field("prune_modes", &prune_modes)
+            .field("secondary", &secondary)
             .finish()
     }
 }
@@ -77,7 +80,13 @@ impl<N: NodeTypesWithDB> ProviderFactory<N> {
         chain_spec: Arc<N::ChainSpec>,
         static_file_provider: StaticFileProvider,
     ) -> Self {
-        Self { db, chain_spec, static_file_provider, prune_modes: PruneModes::none() }
+        Self {
+            db,
+            chain_spec,
+            static_file_provider,
+            prune_modes: PruneModes::none(),
+            secondary: false,
+        }
     }
 
     /// Enables metrics on the static file provider.
@@ -118,6 +127,29 @@ impl<N: NodeTypesWithDB<DB = Arc<DatabaseEnv>>> ProviderFactory<N> {
             chain_spec,
             static_file_provider,
             prune_modes: PruneModes::none(),
+            secondary: false,
+        })
+    }
+
+    /// Opens the database and static files of a running node as a secondary reader.
+    ///
+    /// Both are opened read-only, so the node is never blocked by the reader, and any attempt to
+    /// write through the returned [`ProviderFactory`] is refused. The static file indices are
+    /// reloaded whenever the node commits new static file data. Use
+    /// [`PersistedTipWatcher`](crate::providers::PersistedTipWatcher) to follow the persisted tip
+    /// of the node.
+    pub fn new_secondary(
+        db_path: impl AsRef<Path>,
+        static_files_path: impl AsRef<Path>,
+        chain_spec: Arc<N::ChainSpec>,
+        args: DatabaseArguments,
+    ) -> RethResult<Self> {
+        Ok(Self {
+            db: Arc::new(open_db_read_only(db_path.as_ref(), args).map_err(RethError::msg)?),
+            chain_spec,
+            static_file_provider: StaticFileProvider::read_only(static_files_path, true)?,
+            prune_modes: PruneModes::none(),
+            secondary: true,
         })
     }
 }
@@ -142,9 +174,13 @@ impl<N: ProviderNodeTypes> ProviderFactory<N> {
     /// Returns a provider with a created `DbTxMut` inside, which allows fetching and updating
     /// data from the database using different types of providers. Example: [`HeaderProvider`]
     /// [`BlockHashReader`].  This may fail if the inner read/write database transaction fails to
-    /// open.
+    /// open, or if the factory was opened with [`ProviderFactory::new_secondary`].
     #[track_caller]
     pub fn provider_rw(&self) -> ProviderResult<DatabaseProviderRW<N::DB, N::ChainSpec>> {
+        if self.secondary {
+            return Err(ProviderError::SecondaryWriteAccess)
+        }
+
         Ok(DatabaseProviderRW(DatabaseProvider::new_rw(
             self.db.tx_mut()?,
             self.chain_spec.clone(),
@@ -622,6 +658,7 @@ impl<N: NodeTypesWithDB> Clone for ProviderFactory<N> {
             chain_spec: self.chain_spec.clone(),
             static_file_provider: self.static_file_provider.clone(),
             prune_modes: self.prune_modes.clone(),
+            secondary: self.secondary,
         }
     }
 }
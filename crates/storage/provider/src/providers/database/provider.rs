@@ -461,6 +461,12 @@ impl<TX: DbTx, Spec: Send + Sync> DatabaseProvider<TX, Spec> {
         // `None`.
         let Some(body) = self.block_body_indices(block_number)? else { return Ok(None) };
 
+        // The transactions of the block were pruned.
+        if self.static_file_provider.is_pruned_block(StaticFileSegment::Transactions, block_number)
+        {
+            return Ok(None)
+        }
+
         let tx_range = body.tx_num_range();
 
         let (transactions, senders) = if tx_range.is_empty() {
@@ -532,7 +538,14 @@ impl<TX: DbTx, Spec: Send + Sync> DatabaseProvider<TX, Spec> {
             // not exist in the database yet, or they do exit but are
             // not indexed. If they exist but are not indexed, we don't
             // have enough information to return the block anyways, so
-            // we skip the block.
+            // we skip the block. The same goes for blocks with pruned transactions.
+            if self
+                .static_file_provider
+                .is_pruned_block(StaticFileSegment::Transactions, header_ref.number)
+            {
+                continue
+            }
+
             if let Some((_, block_body_indices)) =
                 block_body_cursor.seek_exact(header_ref.number)?
             {
@@ -2001,6 +2014,14 @@ impl<TX: DbTx, Spec: Send + Sync + EthereumHardforks> TransactionsProvider
         let mut tx_cursor = self.tx.cursor_read::<tables::Transactions>()?;
 
         if let Some(block_number) = self.convert_hash_or_number(id)? {
+            // The transactions of the block were pruned.
+            if self
+                .static_file_provider
+                .is_pruned_block(StaticFileSegment::Transactions, block_number)
+            {
+                return Ok(None)
+            }
+
             if let Some(body) = self.block_body_indices(block_number)? {
                 let tx_range = body.tx_num_range();
                 return if tx_range.is_empty() {
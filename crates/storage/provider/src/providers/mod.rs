@@ -45,6 +45,11 @@ pub use static_file::{
     StaticFileProviderRWRefMut, StaticFileWriter,
 };
 
+mod secondary;
+pub use secondary::{
+    read_persisted_tip, write_persisted_tip, PersistedTipWatcher, PERSISTED_TIP_FILE_NAME,
+};
+
 mod state;
 pub use state::{
     historical::{HistoricalStateProvider, HistoricalStateProviderRef},
@@ -0,0 +1,171 @@
+//! Support for reading the storage of a running node from another process.
+//!
+//! The node records its persisted tip in a file inside the static files directory, every time the
+//! engine has committed blocks to the database and static files. A secondary reader follows that
+//! file with [`PersistedTipWatcher`], which reloads the static file indices before exposing a new
+//! tip, so everything up to the tip is readable once it's observed.
+
+use crate::providers::StaticFileProvider;
+use alloy_eips::BlockNumHash;
+use alloy_primitives::B256;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use reth_storage_errors::provider::{ProviderError, ProviderResult};
+use std::{io::Write, path::Path, str::FromStr};
+use tokio::sync::watch;
+use tracing::{debug, warn};
+
+/// Name of the file inside the static files directory, where the node records its persisted tip.
+pub const PERSISTED_TIP_FILE_NAME: &str = "persisted_tip";
+
+/// Atomically records `tip` as the persisted tip of the node, in the static files directory.
+pub fn write_persisted_tip(static_files_path: &Path, tip: BlockNumHash) -> ProviderResult<()> {
+    reth_fs_util::atomic_write_file(&static_files_path.join(PERSISTED_TIP_FILE_NAME), |file| {
+        write!(file, "{} {}", tip.number, tip.hash)
+    })
+    .map_err(|err| ProviderError::FsPathError(err.to_string()))
+}
+
+/// Reads the persisted tip of the node from the static files directory, if it was ever recorded.
+pub fn read_persisted_tip(static_files_path: &Path) -> ProviderResult<Option<BlockNumHash>> {
+    let path = static_files_path.join(PERSISTED_TIP_FILE_NAME);
+    if !path.exists() {
+        return Ok(None)
+    }
+
+    let contents = reth_fs_util::read_to_string(&path)
+        .map_err(|err| ProviderError::FsPathError(err.to_string()))?;
+    let invalid = || ProviderError::FsPathError(format!("invalid persisted tip file {path:?}"));
+
+    let (number, hash) = contents.trim().split_once(' ').ok_or_else(invalid)?;
+    Ok(Some(BlockNumHash {
+        number: number.parse().map_err(|_| invalid())?,
+        hash: B256::from_str(hash).map_err(|_| invalid())?,
+    }))
+}
+
+/// Follows the persisted tip of a node, from a process reading its storage.
+///
+/// Whenever the node records a new tip, the static file indices of the provider are reloaded
+/// before the tip is published, so that all data up to the tip can be read through it.
+#[derive(Debug, Clone)]
+pub struct PersistedTipWatcher {
+    tip: watch::Receiver<Option<BlockNumHash>>,
+}
+
+impl PersistedTipWatcher {
+    /// Spawns a thread watching the persisted tip of the node owning the static files of
+    /// `provider`.
+    pub fn spawn(provider: StaticFileProvider) -> ProviderResult<Self> {
+        let path = provider.directory().to_path_buf();
+        let (tip_tx, tip_rx) = watch::channel(read_persisted_tip(&path)?);
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut watcher =
+            RecommendedWatcher::new(move |res| tx.send(res).unwrap(), notify::Config::default())
+                .map_err(|err| ProviderError::FsPathError(err.to_string()))?;
+        watcher
+            .watch(&path, RecursiveMode::NonRecursive)
+            .map_err(|err| ProviderError::FsPathError(err.to_string()))?;
+
+        std::thread::spawn(move || {
+            // Keeps the watcher alive for as long as the thread runs.
+            let _watcher = watcher;
+
+            while let Ok(res) = rx.recv() {
+                let event = match res {
+                    Ok(event) => event,
+                    Err(err) => {
+                        warn!(target: "providers::secondary", "watch error: {err:?}");
+                        continue
+                    }
+                };
+
+                // The tip file is replaced by a rename, so any kind of event may signal it.
+                if !event.paths.iter().any(|path| path.ends_with(PERSISTED_TIP_FILE_NAME)) {
+                    continue
+                }
+
+                let tip = match read_persisted_tip(&path) {
+                    Ok(tip) => tip,
+                    Err(err) => {
+                        warn!(target: "providers::secondary", %err, "failed to read persisted tip");
+                        continue
+                    }
+                };
+                if tip == *tip_tx.borrow() {
+                    continue
+                }
+
+                if let Err(err) = provider.initialize_index() {
+                    warn!(target: "providers::secondary", %err, "failed to re-initialize static file index");
+                    continue
+                }
+
+                debug!(target: "providers::secondary", ?tip, "new persisted tip");
+                if tip_tx.send(tip).is_err() {
+                    // All receivers were dropped.
+                    break
+                }
+            }
+        });
+
+        Ok(Self { tip: tip_rx })
+    }
+
+    /// Returns the latest persisted tip of the node, if any.
+    pub fn tip(&self) -> Option<BlockNumHash> {
+        *self.tip.borrow()
+    }
+
+    /// Returns a new receiver of the persisted tip of the node.
+    pub fn subscribe(&self) -> watch::Receiver<Option<BlockNumHash>> {
+        self.tip.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{providers::ProviderFactory, test_utils::MockNodeTypesWithDB};
+    use assert_matches::assert_matches;
+    use reth_chainspec::MAINNET;
+    use reth_db::{init_db, mdbx::DatabaseArguments, ClientVersion, DatabaseEnv};
+    use std::sync::Arc;
+
+    #[test]
+    fn persisted_tip_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(read_persisted_tip(dir.path()).unwrap(), None);
+
+        let tip = BlockNumHash { number: 10, hash: B256::random() };
+        write_persisted_tip(dir.path(), tip).unwrap();
+        assert_eq!(read_persisted_tip(dir.path()).unwrap(), Some(tip));
+    }
+
+    #[test]
+    fn secondary_refuses_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let (db_path, static_files_path) = (dir.path().join("db"), dir.path().join("static_files"));
+        StaticFileProvider::read_write(&static_files_path).unwrap();
+
+        // Only the secondary mode refuses writes, not read-only static files in general.
+        let db = init_db(&db_path, DatabaseArguments::new(ClientVersion::default())).unwrap();
+        let factory = ProviderFactory::<MockNodeTypesWithDB<DatabaseEnv>>::new(
+            Arc::new(db),
+            MAINNET.clone(),
+            StaticFileProvider::read_only(&static_files_path, false).unwrap(),
+        );
+        assert!(factory.provider_rw().is_ok());
+        drop(factory);
+
+        let secondary = ProviderFactory::<MockNodeTypesWithDB<DatabaseEnv>>::new_secondary(
+            &db_path,
+            &static_files_path,
+            MAINNET.clone(),
+            Default::default(),
+        )
+        .unwrap();
+        assert_matches!(secondary.provider_rw(), Err(ProviderError::SecondaryWriteAccess));
+        assert!(secondary.provider().is_ok());
+    }
+}
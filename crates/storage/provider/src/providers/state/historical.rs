@@ -376,6 +376,10 @@ impl<TX: DbTx> StateProofProvider for HistoricalStateProviderRef<'_, TX> {
         input.prepend(self.revert_state()?);
         TrieWitness::overlay_witness(self.tx, input, target).map_err(Into::<ProviderError>::into)
     }
+
+    fn database_trie_input(&self) -> ProviderResult<Option<TrieInput>> {
+        Ok(Some(TrieInput::from_state(self.revert_state()?)))
+    }
 }
 
 impl<TX: DbTx> StateProvider for HistoricalStateProviderRef<'_, TX> {
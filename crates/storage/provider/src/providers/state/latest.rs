@@ -159,6 +159,10 @@ impl<TX: DbTx> StateProofProvider for LatestStateProviderRef<'_, TX> {
     ) -> ProviderResult<HashMap<B256, Bytes>> {
         TrieWitness::overlay_witness(self.tx, input, target).map_err(Into::<ProviderError>::into)
     }
+
+    fn database_trie_input(&self) -> ProviderResult<Option<TrieInput>> {
+        Ok(Some(TrieInput::default()))
+    }
 }
 
 impl<TX: DbTx> StateProvider for LatestStateProviderRef<'_, TX> {
@@ -27,7 +27,7 @@ use reth_db_api::{
 use reth_nippy_jar::{NippyJar, NippyJarChecker, CONFIG_FILE_EXTENSION};
 use reth_primitives::{
     static_file::{
-        find_fixed_range, HighestStaticFiles, SegmentHeader, SegmentRangeInclusive,
+        find_fixed_range, HighestStaticFiles, SegmentConfig, SegmentHeader, SegmentRangeInclusive,
         DEFAULT_BLOCKS_PER_STATIC_FILE,
     },
     Block, BlockWithSenders, Header, Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader,
@@ -205,6 +205,10 @@ pub struct StaticFileProviderInner {
     static_files_max_block: RwLock<HashMap<StaticFileSegment, u64>>,
     /// Available static file block ranges on disk indexed by max transactions.
     static_files_tx_index: RwLock<SegmentRanges>,
+    /// Min static file block for each segment. Static files below it were pruned.
+    static_files_min_block: RwLock<HashMap<StaticFileSegment, u64>>,
+    /// Min static file transaction for each segment. Static files below it were pruned.
+    static_files_min_tx: RwLock<HashMap<StaticFileSegment, u64>>,
     /// Directory where `static_files` are located
     path: PathBuf,
     /// Maintains a writer set of [`StaticFileSegment`].
@@ -214,6 +218,9 @@ pub struct StaticFileProviderInner {
     access: StaticFileAccess,
     /// Number of blocks per file.
     blocks_per_file: u64,
+    /// Configuration used when creating new static files of a segment. Segments without an entry
+    /// use [`StaticFileSegment::config`].
+    segment_configs: HashMap<StaticFileSegment, SegmentConfig>,
     /// Write lock for when access is [`StaticFileAccess::RW`].
     _lock_file: Option<StorageLock>,
 }
@@ -232,10 +239,13 @@ impl StaticFileProviderInner {
             writers: Default::default(),
             static_files_max_block: Default::default(),
             static_files_tx_index: Default::default(),
+            static_files_min_block: Default::default(),
+            static_files_min_tx: Default::default(),
             path: path.as_ref().to_path_buf(),
             metrics: None,
             access,
             blocks_per_file: DEFAULT_BLOCKS_PER_STATIC_FILE,
+            segment_configs: Default::default(),
             _lock_file,
         };
 
@@ -251,6 +261,11 @@ impl StaticFileProviderInner {
     pub const fn find_fixed_range(&self, block: BlockNumber) -> SegmentRangeInclusive {
         find_fixed_range(block, self.blocks_per_file)
     }
+
+    /// Returns the configuration used when creating new static files of the segment.
+    pub fn segment_config(&self, segment: StaticFileSegment) -> SegmentConfig {
+        self.segment_configs.get(&segment).copied().unwrap_or_else(|| segment.config())
+    }
 }
 
 impl StaticFileProvider {
@@ -263,6 +278,17 @@ impl StaticFileProvider {
         Self(Arc::new(provider))
     }
 
+    /// Sets the configuration used when creating new static files of each given segment.
+    pub fn with_segment_configs(
+        self,
+        configs: impl IntoIterator<Item = (StaticFileSegment, SegmentConfig)>,
+    ) -> Self {
+        let mut provider =
+            Arc::try_unwrap(self.0).expect("should be called when initializing only");
+        provider.segment_configs.extend(configs);
+        Self(Arc::new(provider))
+    }
+
     /// Enables metrics on the [`StaticFileProvider`].
     pub fn with_metrics(self) -> Self {
         let mut provider =
@@ -415,6 +441,46 @@ impl StaticFileProvider {
         Ok(())
     }
 
+    /// Given a segment and block, it deletes all static files of the segment whose block range
+    /// ends at or below it, except for the highest one. Returns the fixed block ranges of the
+    /// deleted static files, in ascending order.
+    ///
+    /// CAUTION: destructive. Deletes files on disk.
+    pub fn delete_jars_below(
+        &self,
+        segment: StaticFileSegment,
+        block: BlockNumber,
+    ) -> ProviderResult<Vec<SegmentRangeInclusive>> {
+        let (Some(lowest_block), Some(highest_block)) = (
+            self.get_lowest_static_file_block(segment),
+            self.get_highest_static_file_block(segment),
+        ) else {
+            return Ok(Vec::new())
+        };
+        let highest_range = self.find_fixed_range(highest_block);
+
+        let mut deleted = Vec::new();
+        let mut range = self.find_fixed_range(lowest_block);
+        while range.end() <= block && range.end() < highest_range.start() {
+            let jar = if let Some((_, jar)) = self.map.remove(&(range.end(), segment)) {
+                jar.jar
+            } else {
+                NippyJar::<SegmentHeader>::load(&self.path.join(segment.filename(&range)))
+                    .map_err(|e| ProviderError::NippyJar(e.to_string()))?
+            };
+            jar.delete().map_err(|e| ProviderError::NippyJar(e.to_string()))?;
+            deleted.push(range);
+
+            range = self.find_fixed_range(range.end() + 1);
+        }
+
+        if !deleted.is_empty() {
+            self.initialize_index()?;
+        }
+
+        Ok(deleted)
+    }
+
     /// Given a segment and block range it returns a cached
     /// [`StaticFileJarProvider`]. TODO(joshie): we should check the size and pop N if there's too
     /// many.
@@ -450,6 +516,10 @@ impl StaticFileProvider {
         segment: StaticFileSegment,
         block: u64,
     ) -> Option<SegmentRangeInclusive> {
+        if self.is_pruned_block(segment, block) {
+            return None
+        }
+
         self.static_files_max_block
             .read()
             .get(&segment)
@@ -464,6 +534,11 @@ impl StaticFileProvider {
         segment: StaticFileSegment,
         tx: u64,
     ) -> Option<SegmentRangeInclusive> {
+        let lowest_tx = self.get_lowest_static_file_tx(segment).unwrap_or_default();
+        if tx < lowest_tx {
+            return None
+        }
+
         let static_files = self.static_files_tx_index.read();
         let segment_static_files = static_files.get(&segment)?;
 
@@ -476,7 +551,8 @@ impl StaticFileProvider {
                 // request tx is higher than highest static file tx
                 return None
             }
-            let tx_start = static_files_rev_iter.peek().map(|(tx_end, _)| *tx_end + 1).unwrap_or(0);
+            let tx_start =
+                static_files_rev_iter.peek().map(|(tx_end, _)| *tx_end + 1).unwrap_or(lowest_tx);
             if tx_start <= tx {
                 return Some(self.find_fixed_range(block_range.end()))
             }
@@ -497,6 +573,8 @@ impl StaticFileProvider {
     ) -> ProviderResult<()> {
         let mut max_block = self.static_files_max_block.write();
         let mut tx_index = self.static_files_tx_index.write();
+        let mut min_block = self.static_files_min_block.write();
+        let mut min_tx = self.static_files_min_tx.write();
 
         match segment_max_block {
             Some(segment_max_block) => {
@@ -509,6 +587,16 @@ impl StaticFileProvider {
                 )
                 .map_err(|e| ProviderError::NippyJar(e.to_string()))?;
 
+                // Update the min block for the segment, if this is its first static file or it was
+                // truncated below the previous min block.
+                if min_block.get(&segment).map_or(true, |min| *min > fixed_range.start()) {
+                    min_block.insert(segment, fixed_range.start());
+                    match jar.user_header().tx_range() {
+                        Some(tx_range) => min_tx.insert(segment, tx_range.start()),
+                        None => min_tx.remove(&segment),
+                    };
+                }
+
                 // Updates the tx index by first removing all entries which have a higher
                 // block_start than our current static file.
                 if let Some(tx_range) = jar.user_header().tx_range() {
@@ -558,6 +646,8 @@ impl StaticFileProvider {
             None => {
                 tx_index.remove(&segment);
                 max_block.remove(&segment);
+                min_block.remove(&segment);
+                min_tx.remove(&segment);
             }
         };
 
@@ -568,13 +658,25 @@ impl StaticFileProvider {
     pub fn initialize_index(&self) -> ProviderResult<()> {
         let mut max_block = self.static_files_max_block.write();
         let mut tx_index = self.static_files_tx_index.write();
+        let mut min_block = self.static_files_min_block.write();
+        let mut min_tx = self.static_files_min_tx.write();
 
         max_block.clear();
         tx_index.clear();
+        min_block.clear();
+        min_tx.clear();
 
         for (segment, ranges) in
             iter_static_files(&self.path).map_err(|e| ProviderError::NippyJar(e.to_string()))?
         {
+            // Update first block and transaction for each segment
+            if let Some((block_range, tx_range)) = ranges.first() {
+                min_block.insert(segment, block_range.start());
+                if let Some(tx_range) = tx_range {
+                    min_tx.insert(segment, tx_range.start());
+                }
+            }
+
             // Update last block for each segment
             if let Some((block_range, _)) = ranges.last() {
                 max_block.insert(segment, block_range.end());
@@ -889,6 +991,27 @@ impl StaticFileProvider {
         Ok(None)
     }
 
+    /// Gets the lowest static file block if it exists for a static file segment. Anything below
+    /// it was pruned.
+    ///
+    /// If there is nothing on disk for the given segment, this will return [`None`].
+    pub fn get_lowest_static_file_block(&self, segment: StaticFileSegment) -> Option<BlockNumber> {
+        self.static_files_min_block.read().get(&segment).copied()
+    }
+
+    /// Gets the lowest static file transaction. Anything below it was pruned.
+    ///
+    /// If there is nothing on disk for the given segment, or its lowest static file has no
+    /// transactions, this will return [`None`].
+    pub fn get_lowest_static_file_tx(&self, segment: StaticFileSegment) -> Option<TxNumber> {
+        self.static_files_min_tx.read().get(&segment).copied()
+    }
+
+    /// Returns `true` if the data of `block` was pruned from the static files of `segment`.
+    pub fn is_pruned_block(&self, segment: StaticFileSegment, block: BlockNumber) -> bool {
+        self.get_lowest_static_file_block(segment).is_some_and(|lowest| block < lowest)
+    }
+
     /// Gets the highest static file block if it exists for a static file segment.
     ///
     /// If there is nothing on disk for the given segment, this will return [`None`].
@@ -923,8 +1046,9 @@ impl StaticFileProvider {
         func: impl Fn(StaticFileJarProvider<'_>) -> ProviderResult<Option<T>>,
     ) -> ProviderResult<Option<T>> {
         if let Some(highest_block) = self.get_highest_static_file_block(segment) {
+            let lowest_block = self.get_lowest_static_file_block(segment).unwrap_or_default();
             let mut range = self.find_fixed_range(highest_block);
-            while range.end() > 0 {
+            while range.end() > 0 && range.end() >= lowest_block {
                 if let Some(res) = func(self.get_or_create_jar_provider(segment, &range)?)? {
                     return Ok(Some(res))
                 }
@@ -1298,6 +1422,9 @@ impl HeaderProvider for StaticFileProvider {
 
 impl BlockHashReader for StaticFileProvider {
     fn block_hash(&self, num: u64) -> ProviderResult<Option<B256>> {
+        if self.is_pruned_block(StaticFileSegment::Headers, num) {
+            return Ok(None)
+        }
         self.get_segment_provider_from_block(StaticFileSegment::Headers, num, None)?.block_hash(num)
     }
 
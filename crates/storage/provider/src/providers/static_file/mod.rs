@@ -294,6 +294,45 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_delete_jars_below() {
+        let (static_dir, _) = create_test_static_files_dir();
+        let blocks_per_file = 10;
+        let segment = StaticFileSegment::Transactions;
+
+        let sf_rw = StaticFileProvider::read_write(&static_dir)
+            .expect("Failed to create static file provider")
+            .with_custom_blocks_per_file(blocks_per_file);
+        setup_tx_based_scenario(&sf_rw, segment, blocks_per_file);
+        assert_eq!(sf_rw.get_lowest_static_file_block(segment), Some(0));
+
+        // Only static files that end at or below the block are deleted.
+        assert_eq!(
+            sf_rw.delete_jars_below(segment, blocks_per_file + 5).unwrap(),
+            vec![SegmentRangeInclusive::new(0, 9)]
+        );
+        assert_eq!(sf_rw.get_lowest_static_file_block(segment), Some(blocks_per_file));
+        assert!(sf_rw.is_pruned_block(segment, blocks_per_file - 1));
+        assert_eq!(sf_rw.transaction_by_id(0).unwrap(), None);
+        assert!(sf_rw.transaction_by_id(9).unwrap().is_some());
+
+        // The highest static file is never deleted.
+        assert_eq!(
+            sf_rw.delete_jars_below(segment, blocks_per_file * 5).unwrap(),
+            vec![SegmentRangeInclusive::new(10, 19)]
+        );
+        assert_eq!(sf_rw.get_lowest_static_file_block(segment), Some(blocks_per_file * 2));
+        assert_eq!(sf_rw.get_lowest_static_file_tx(segment), Some(9));
+        assert_eq!(sf_rw.get_highest_static_file_tx(segment), Some(9));
+
+        // The index is rebuilt the same way from disk.
+        let sf_rw = StaticFileProvider::read_write(&static_dir)
+            .expect("Failed to create static file provider")
+            .with_custom_blocks_per_file(blocks_per_file);
+        assert_eq!(sf_rw.get_lowest_static_file_block(segment), Some(blocks_per_file * 2));
+        assert!(sf_rw.delete_jars_below(segment, blocks_per_file * 5).unwrap().is_empty());
+    }
+
     /// 3 block ranges are built
     ///
     /// for `blocks_per_file = 10`:
@@ -8,7 +8,7 @@ use reth_codecs::Compact;
 use reth_db_api::models::CompactU256;
 use reth_nippy_jar::{NippyJar, NippyJarError, NippyJarWriter};
 use reth_primitives::{
-    static_file::{SegmentHeader, SegmentRangeInclusive},
+    static_file::{Compression, SegmentConfig, SegmentHeader, SegmentRangeInclusive},
     Header, Receipt, StaticFileSegment, TransactionSignedNoHash,
 };
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
@@ -152,7 +152,8 @@ impl StaticFileProviderRW {
             ),
             Err(ProviderError::MissingStaticFileBlock(_, _)) => {
                 let path = static_file_provider.directory().join(segment.filename(&block_range));
-                (create_jar(segment, &path, block_range), path)
+                let config = static_file_provider.segment_config(segment);
+                (create_jar(segment, &path, block_range, config), path)
             }
             Err(err) => return Err(err),
         };
@@ -795,18 +796,19 @@ fn create_jar(
     segment: StaticFileSegment,
     path: &Path,
     expected_block_range: SegmentRangeInclusive,
+    config: SegmentConfig,
 ) -> NippyJar<SegmentHeader> {
-    let mut jar = NippyJar::new(
+    let jar = NippyJar::new(
         segment.columns(),
         path,
         SegmentHeader::new(expected_block_range, None, None, segment),
     );
 
-    // Transaction and Receipt already have the compression scheme used natively in its encoding.
-    // (zstd-dictionary)
-    if segment.is_headers() {
-        jar = jar.with_lz4();
+    match config.compression {
+        Compression::Lz4 => jar.with_lz4(),
+        // Dictionaries can only be trained once the static file is complete, which is done by
+        // recompressing it.
+        Compression::Zstd | Compression::ZstdWithDictionary => jar.with_zstd(false, 0),
+        Compression::Uncompressed => jar,
     }
-
-    jar
 }
@@ -54,6 +54,8 @@ pub struct MockEthProvider {
     pub chain_spec: Arc<ChainSpec>,
     /// Local state roots
     pub state_roots: Arc<Mutex<Vec<B256>>>,
+    /// Local execution outcome store
+    pub execution_outcomes: Arc<Mutex<HashMap<BlockNumber, ExecutionOutcome>>>,
 }
 
 impl Default for MockEthProvider {
@@ -64,6 +66,7 @@ impl Default for MockEthProvider {
             accounts: Default::default(),
             chain_spec: Arc::new(reth_chainspec::ChainSpecBuilder::mainnet().build()),
             state_roots: Default::default(),
+            execution_outcomes: Default::default(),
         }
     }
 }
@@ -148,6 +151,11 @@ impl MockEthProvider {
     pub fn add_state_root(&self, state_root: B256) {
         self.state_roots.lock().push(state_root);
     }
+
+    /// Add the execution outcome of a block to local execution outcome store
+    pub fn add_execution_outcome(&self, block_number: BlockNumber, outcome: ExecutionOutcome) {
+        self.execution_outcomes.lock().insert(block_number, outcome);
+    }
 }
 
 impl DatabaseProviderFactory for MockEthProvider {
@@ -521,10 +529,15 @@ impl BlockReader for MockEthProvider {
 
     fn sealed_block_with_senders(
         &self,
-        _id: BlockHashOrNumber,
+        id: BlockHashOrNumber,
         _transaction_kind: TransactionVariant,
     ) -> ProviderResult<Option<SealedBlockWithSenders>> {
-        Ok(None)
+        let lock = self.blocks.lock();
+        let block = match id {
+            BlockHashOrNumber::Hash(hash) => lock.get_key_value(&hash),
+            BlockHashOrNumber::Number(num) => lock.iter().find(|(_, b)| b.number == num),
+        };
+        Ok(block.and_then(|(hash, block)| block.clone().seal(*hash).seal_with_senders()))
     }
 
     fn block_range(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<Vec<Block>> {
@@ -847,7 +860,7 @@ impl BlockExecutionReader for MockEthProvider {
 }
 
 impl StateReader for MockEthProvider {
-    fn get_state(&self, _block: BlockNumber) -> ProviderResult<Option<ExecutionOutcome>> {
-        Ok(None)
+    fn get_state(&self, block: BlockNumber) -> ProviderResult<Option<ExecutionOutcome>> {
+        Ok(self.execution_outcomes.lock().get(&block).cloned())
     }
 }
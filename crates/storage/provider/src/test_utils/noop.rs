@@ -14,6 +14,7 @@ use reth_chain_state::{
     ForkChoiceSubscriptions,
 };
 use reth_chainspec::{ChainInfo, ChainSpec, MAINNET};
+use reth_db::mock::{DatabaseMock, TxMock};
 use reth_db_api::models::{AccountBeforeTx, StoredBlockBodyIndices};
 use reth_errors::ProviderError;
 use reth_evm::ConfigureEvmEnv;
@@ -24,7 +25,7 @@ use reth_primitives::{
 };
 use reth_prune_types::{PruneCheckpoint, PruneSegment};
 use reth_stages_types::{StageCheckpoint, StageId};
-use reth_storage_api::{StateProofProvider, StorageRootProvider};
+use reth_storage_api::{DatabaseProviderFactory, StateProofProvider, StorageRootProvider};
 use reth_storage_errors::provider::ProviderResult;
 use reth_trie::{
     updates::TrieUpdates, AccountProof, HashedPostState, HashedStorage, MultiProof, TrieInput,
@@ -36,10 +37,10 @@ use crate::{
     providers::StaticFileProvider,
     traits::{BlockSource, ReceiptProvider},
     AccountReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
-    ChainSpecProvider, ChangeSetReader, EvmEnvProvider, HeaderProvider, PruneCheckpointReader,
-    ReceiptProviderIdExt, RequestsProvider, StageCheckpointReader, StateProvider, StateProviderBox,
-    StateProviderFactory, StateRootProvider, StaticFileProviderFactory, TransactionVariant,
-    TransactionsProvider, WithdrawalsProvider,
+    ChainSpecProvider, ChangeSetReader, DatabaseProvider, EvmEnvProvider, HeaderProvider,
+    PruneCheckpointReader, ReceiptProviderIdExt, RequestsProvider, StageCheckpointReader,
+    StateProvider, StateProviderBox, StateProviderFactory, StateRootProvider,
+    StaticFileProviderFactory, TransactionVariant, TransactionsProvider, WithdrawalsProvider,
 };
 
 /// Supports various api interfaces for testing purposes.
@@ -47,6 +48,20 @@ use crate::{
 #[non_exhaustive]
 pub struct NoopProvider;
 
+impl DatabaseProviderFactory for NoopProvider {
+    type DB = DatabaseMock;
+    type Provider = DatabaseProvider<TxMock, ChainSpec>;
+    type ProviderRW = DatabaseProvider<TxMock, ChainSpec>;
+
+    fn database_provider_ro(&self) -> ProviderResult<Self::Provider> {
+        Err(ProviderError::UnsupportedProvider)
+    }
+
+    fn database_provider_rw(&self) -> ProviderResult<Self::ProviderRW> {
+        Err(ProviderError::UnsupportedProvider)
+    }
+}
+
 impl ChainSpecProvider for NoopProvider {
     type ChainSpec = ChainSpec;
 
@@ -1,9 +1,9 @@
 //! Helper provider traits to encapsulate all provider traits for simplicity.
 
 use crate::{
-    AccountReader, BlockReaderIdExt, ChainSpecProvider, ChangeSetReader, DatabaseProviderFactory,
-    EvmEnvProvider, HeaderProvider, StageCheckpointReader, StateProviderFactory,
-    StaticFileProviderFactory, TransactionsProvider,
+    AccountReader, BlockReader, BlockReaderIdExt, ChainSpecProvider, ChangeSetReader,
+    DatabaseProviderFactory, EvmEnvProvider, HeaderProvider, StageCheckpointReader,
+    StateProviderFactory, StaticFileProviderFactory, TransactionsProvider,
 };
 use reth_chain_state::{CanonStateSubscriptions, ForkChoiceSubscriptions};
 use reth_chainspec::EthereumHardforks;
@@ -11,7 +11,7 @@ use reth_node_types::NodeTypesWithDB;
 
 /// Helper trait to unify all provider traits for simplicity.
 pub trait FullProvider<N: NodeTypesWithDB>:
-    DatabaseProviderFactory<DB = N::DB>
+    DatabaseProviderFactory<DB = N::DB, Provider: BlockReader>
     + StaticFileProviderFactory
     + BlockReaderIdExt
     + AccountReader
@@ -29,7 +29,7 @@ pub trait FullProvider<N: NodeTypesWithDB>:
 }
 
 impl<T, N: NodeTypesWithDB> FullProvider<N> for T where
-    T: DatabaseProviderFactory<DB = N::DB>
+    T: DatabaseProviderFactory<DB = N::DB, Provider: BlockReader>
         + StaticFileProviderFactory
         + BlockReaderIdExt
         + AccountReader
@@ -50,6 +50,7 @@ impl<T, N: NodeTypesWithDB> FullProvider<N> for T where
 /// simplicity.
 pub trait FullRpcProvider:
     StateProviderFactory
+    + DatabaseProviderFactory<Provider: BlockReader>
     + EvmEnvProvider
     + ChainSpecProvider<ChainSpec: EthereumHardforks>
     + BlockReaderIdExt
@@ -64,6 +65,7 @@ pub trait FullRpcProvider:
 
 impl<T> FullRpcProvider for T where
     T: StateProviderFactory
+        + DatabaseProviderFactory<Provider: BlockReader>
         + EvmEnvProvider
         + ChainSpecProvider<ChainSpec: EthereumHardforks>
         + BlockReaderIdExt
@@ -84,4 +84,13 @@ pub trait StateProofProvider: Send + Sync {
         input: TrieInput,
         target: HashedPostState,
     ) -> ProviderResult<HashMap<B256, Bytes>>;
+
+    /// Returns the trie input of the current state on top of the latest state in the database, if
+    /// the state is backed by the database.
+    ///
+    /// Proofs of the current state can then be generated from any transaction of the database, as
+    /// long as the database doesn't change in the meantime. Returns `None` by default.
+    fn database_trie_input(&self) -> ProviderResult<Option<TrieInput>> {
+        Ok(None)
+    }
 }
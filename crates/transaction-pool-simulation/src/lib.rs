@@ -0,0 +1,366 @@
+//! Pre-execution of pending pool transactions against the latest state.
+//!
+//! The [`simulate_pending_transactions`] service executes new pending transactions on top of the
+//! canonical head with the node's [`ConfigureEvm`], and records the results in the pool's
+//! [`SimulationCache`], see [`TransactionPool::simulations`]. Results are keyed by transaction hash
+//! and the state root they were simulated on, and are invalidated on every canonical state change.
+//!
+//! This lives in its own crate so that the pool itself doesn't depend on the EVM.
+//!
+//! [`SimulationCache`]: reth_transaction_pool::simulation::SimulationCache
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+use alloy_primitives::{Address, Bytes, TxHash, B256};
+use futures_util::{
+    future::{Fuse, FusedFuture},
+    FutureExt, Stream, StreamExt,
+};
+use reth_chain_state::CanonStateNotification;
+use reth_evm::{ConfigureEvm, NextBlockEnvAttributes};
+use reth_primitives::{constants::SLOT_DURATION, Header, SealedHeader};
+use reth_revm::database::StateProviderDatabase;
+use reth_storage_api::{
+    errors::provider::ProviderResult, BlockReaderIdExt, HeaderProvider, StateProviderFactory,
+};
+use reth_tasks::TaskSpawner;
+use reth_transaction_pool::{
+    simulation::{SimulationResult, SimulationStatus},
+    PoolTransaction, TransactionListenerKind, TransactionPool,
+};
+use revm::{
+    db::CacheDB,
+    primitives::{EnvWithHandlerCfg, ExecutionResult, ResultAndState},
+    DatabaseCommit,
+};
+use std::collections::{BTreeMap, HashSet};
+use tokio::sync::oneshot;
+use tracing::{debug, trace};
+
+/// Default max number of transactions simulated at once.
+pub const DEFAULT_MAX_SIMULATION_BATCH: usize = 1024;
+
+/// Settings for the [`simulate_pending_transactions`] service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimulationConfig {
+    /// Max number of pending transactions simulated at once.
+    ///
+    /// On a new canonical head, only the first `max_batch` pending transactions are re-simulated.
+    pub max_batch: usize,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self { max_batch: DEFAULT_MAX_SIMULATION_BATCH }
+    }
+}
+
+/// Simulates new pending transactions on top of the canonical head and records the results in the
+/// pool's [`SimulationCache`](reth_transaction_pool::simulation::SimulationCache).
+///
+/// The pending transactions are simulated once on startup. On every canonical state change, the
+/// cache is invalidated and the pending transactions are re-simulated on top of the new head.
+///
+/// One batch is simulated at a time on a blocking task, new pending transactions and canonical
+/// state changes are queued in the meantime.
+pub async fn simulate_pending_transactions<Client, P, EvmConfig, St, Tasks>(
+    client: Client,
+    pool: P,
+    evm_config: EvmConfig,
+    mut events: St,
+    task_spawner: Tasks,
+    config: SimulationConfig,
+) where
+    Client: StateProviderFactory + BlockReaderIdExt + Clone + Send + 'static,
+    P: TransactionPool + 'static,
+    EvmConfig: ConfigureEvm<Header = Header>,
+    St: Stream<Item = CanonStateNotification> + Send + Unpin + 'static,
+    Tasks: TaskSpawner + 'static,
+{
+    let cache = pool.simulations();
+    let mut pending = pool.pending_transactions_listener_for(TransactionListenerKind::All);
+
+    let mut head = client.latest_header().ok().flatten();
+    if let Some(head) = &head {
+        cache.set_state_root(head.state_root);
+    }
+    let mut queued = pending_batch(&pool, &config);
+    let mut simulation = Fuse::terminated();
+
+    loop {
+        if let Some(head) = head.as_ref().filter(|_| simulation.is_terminated()) {
+            queued.retain(|hash| !cache.contains(hash));
+            if !queued.is_empty() {
+                let batch = std::mem::take(&mut queued);
+                simulation = spawn_simulation(
+                    &client,
+                    &pool,
+                    &evm_config,
+                    &task_spawner,
+                    head.clone(),
+                    batch,
+                )
+                .fuse();
+            }
+        }
+
+        tokio::select! {
+            _ = &mut simulation => {}
+            hash = pending.recv(), if queued.len() < config.max_batch => {
+                let Some(hash) = hash else { break };
+                queued.push(hash);
+                while queued.len() < config.max_batch {
+                    let Ok(hash) = pending.try_recv() else { break };
+                    queued.push(hash);
+                }
+            }
+            event = events.next() => {
+                let Some(event) = event else { break };
+                let tip = event.tip();
+                cache.set_state_root(tip.state_root);
+                head = Some(tip.header.clone());
+                queued = pending_batch(&pool, &config);
+            }
+        }
+    }
+}
+
+/// Returns the hashes of the first [`SimulationConfig::max_batch`] pending transactions.
+fn pending_batch<P: TransactionPool>(pool: &P, config: &SimulationConfig) -> Vec<TxHash> {
+    pool.pending_transactions().into_iter().take(config.max_batch).map(|tx| *tx.hash()).collect()
+}
+
+/// Spawns a blocking task that simulates the given transactions and records the results in the
+/// pool's cache.
+///
+/// The returned receiver resolves once the task is done. Results of a head that is no longer the
+/// canonical head when the task finishes are discarded by the cache.
+fn spawn_simulation<Client, P, EvmConfig, Tasks>(
+    client: &Client,
+    pool: &P,
+    evm_config: &EvmConfig,
+    task_spawner: &Tasks,
+    head: SealedHeader,
+    batch: Vec<TxHash>,
+) -> oneshot::Receiver<()>
+where
+    Client: StateProviderFactory + BlockReaderIdExt + Clone + Send + 'static,
+    P: TransactionPool + 'static,
+    EvmConfig: ConfigureEvm<Header = Header>,
+    Tasks: TaskSpawner + 'static,
+{
+    trace!(
+        target: "txpool::simulation",
+        count = batch.len(),
+        head = head.number,
+        "simulating pending transactions"
+    );
+    let (tx, rx) = oneshot::channel();
+    let (client, pool, evm_config) = (client.clone(), pool.clone(), evm_config.clone());
+    task_spawner.spawn_blocking(Box::pin(async move {
+        match simulate_transactions(&client, &pool, &evm_config, &head, batch) {
+            Ok(results) => {
+                let cache = pool.simulations();
+                for (hash, result) in results {
+                    cache.insert(hash, result);
+                }
+            }
+            Err(err) => {
+                debug!(target: "txpool::simulation", %err, "failed to simulate pending transactions");
+            }
+        }
+        let _ = tx.send(());
+    }));
+    rx
+}
+
+/// Returns the attributes of the block that follows the given head.
+///
+/// Like a pending block derived from the latest block, the next block is assumed to be built by
+/// the fee recipient of the head, with the randomness of the head. It's assumed to follow the head
+/// after the same interval as the head followed its parent, or after [`SLOT_DURATION`] if the
+/// parent is unknown.
+fn next_block_attributes<Client: HeaderProvider>(
+    client: &Client,
+    head: &SealedHeader,
+) -> ProviderResult<NextBlockEnvAttributes> {
+    let block_time = client
+        .header(&head.parent_hash)?
+        .map(|parent| head.timestamp.saturating_sub(parent.timestamp))
+        .filter(|block_time| *block_time > 0)
+        .unwrap_or(SLOT_DURATION.as_secs());
+
+    Ok(NextBlockEnvAttributes {
+        timestamp: head.timestamp + block_time,
+        suggested_fee_recipient: head.beneficiary,
+        prev_randao: head.mix_hash,
+    })
+}
+
+/// Simulates the given pool transactions on top of the state of the given head.
+///
+/// The pending transactions of each sender are executed once in nonce order, up to the highest
+/// nonce of the given transactions of the sender, since a transaction can't be executed without its
+/// ancestors. All senders are executed one after another on the same state, like in a block.
+fn simulate_transactions<Client, P, EvmConfig>(
+    client: &Client,
+    pool: &P,
+    evm_config: &EvmConfig,
+    head: &SealedHeader,
+    hashes: Vec<TxHash>,
+) -> ProviderResult<Vec<(TxHash, SimulationResult)>>
+where
+    Client: StateProviderFactory + HeaderProvider,
+    P: TransactionPool,
+    EvmConfig: ConfigureEvm<Header = Header>,
+{
+    let state = client.history_by_block_hash(head.hash())?;
+    let (cfg, block_env) =
+        evm_config.next_cfg_and_block_env(head.header(), next_block_attributes(client, head)?);
+
+    // the highest nonce to execute of each sender
+    let mut senders = BTreeMap::<Address, u64>::new();
+    let mut requested = HashSet::with_capacity(hashes.len());
+    for tx in pool.get_all(hashes) {
+        let nonce = senders.entry(tx.sender()).or_default();
+        *nonce = (*nonce).max(tx.nonce());
+        requested.insert(*tx.hash());
+    }
+
+    let mut db = CacheDB::new(StateProviderDatabase::new(&state));
+    let mut results = Vec::with_capacity(requested.len());
+    for (sender, max_nonce) in senders {
+        let mut txs = pool
+            .get_transactions_by_sender(sender)
+            .into_iter()
+            .filter(|tx| tx.nonce() <= max_nonce)
+            .collect::<Vec<_>>();
+        txs.sort_unstable_by_key(|tx| tx.nonce());
+
+        for tx in txs {
+            let env = EnvWithHandlerCfg::new_with_cfg_env(
+                cfg.clone(),
+                block_env.clone(),
+                evm_config.tx_env(&tx.to_recovered_transaction()),
+            );
+            let res = evm_config.evm_with_env(&mut db, env).transact();
+
+            let mut result = SimulationResult {
+                state_root: head.state_root,
+                status: SimulationStatus::Invalid,
+                gas_used: 0,
+                output: Bytes::new(),
+                touched_slots: BTreeMap::new(),
+                error: None,
+            };
+            match res {
+                Ok(ResultAndState { result: execution, state }) => {
+                    result.gas_used = execution.gas_used();
+                    result.touched_slots = state
+                        .iter()
+                        .filter(|(_, account)| !account.storage.is_empty())
+                        .map(|(address, account)| {
+                            (
+                                *address,
+                                account.storage.keys().map(|slot| B256::from(*slot)).collect(),
+                            )
+                        })
+                        .collect();
+                    match execution {
+                        ExecutionResult::Success { output, .. } => {
+                            result.status = SimulationStatus::Success;
+                            result.output = output.into_data();
+                        }
+                        ExecutionResult::Revert { output, .. } => {
+                            result.status = SimulationStatus::Revert;
+                            result.output = output;
+                        }
+                        ExecutionResult::Halt { reason, .. } => {
+                            result.status = SimulationStatus::Halt;
+                            result.error = Some(format!("{reason:?}"));
+                        }
+                    }
+                    db.commit(state);
+                }
+                Err(err) => result.error = Some(err.to_string()),
+            }
+
+            if requested.contains(tx.hash()) {
+                results.push((*tx.hash(), result));
+            }
+        }
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::U256;
+    use reth_chainspec::MAINNET;
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_primitives::Block;
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_tasks::TokioTaskExecutor;
+    use reth_transaction_pool::{
+        blobstore::InMemoryBlobStore, test_utils::signed_eth_transaction,
+        validate::EthTransactionValidatorBuilder, CoinbaseTipOrdering, Pool, TransactionOrigin,
+    };
+    use std::time::Duration;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn simulates_pending_transaction() {
+        let (transaction, sender) = signed_eth_transaction();
+
+        let provider = MockEthProvider::default();
+        // the nonce of the transaction, so it's pending
+        provider.add_account(sender, ExtendedAccount::new(transaction.nonce(), U256::MAX));
+        let head = Header {
+            number: 1,
+            timestamp: 12,
+            gas_limit: 30_000_000,
+            base_fee_per_gas: Some(7),
+            state_root: B256::random(),
+            ..Default::default()
+        };
+        provider.add_block(head.hash_slow(), Block { header: head.clone(), ..Default::default() });
+
+        let blob_store = InMemoryBlobStore::default();
+        let validator = EthTransactionValidatorBuilder::new(MAINNET.clone())
+            .build(provider.clone(), blob_store.clone());
+        let pool =
+            Pool::new(validator, CoinbaseTipOrdering::default(), blob_store, Default::default());
+        let hash = pool.add_transaction(TransactionOrigin::External, transaction).await.unwrap();
+
+        tokio::spawn(simulate_pending_transactions(
+            provider,
+            pool.clone(),
+            EthEvmConfig::new(MAINNET.clone()),
+            futures_util::stream::pending(),
+            TokioTaskExecutor::default(),
+            SimulationConfig::default(),
+        ));
+
+        let result = tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                if let Some(result) = pool.simulations().get(&hash) {
+                    return result
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(result.state_root, head.state_root);
+        assert!(result.is_success(), "{result:?}");
+        assert_eq!(result.gas_used, 21_000);
+    }
+}
@@ -0,0 +1,121 @@
+//! Types of the transaction journal, which records the state transitions of pool transactions.
+
+use crate::SubPool;
+use alloy_primitives::{Address, TxHash, B256};
+
+/// Why a transaction is parked in a sub-pool other than the pending sub-pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum ParkedReason {
+    /// The nonce of the transaction isn't the next nonce of the sender.
+    NonceGap,
+    /// A transaction of the sender with a lower nonce is parked.
+    ParkedAncestor,
+    /// The sender can't cover the cumulative cost of its transactions.
+    InsufficientBalance,
+    /// The gas limit of the transaction exceeds the block gas limit.
+    GasLimitExceeded,
+    /// The fee cap of the transaction is below the base fee of the pending block.
+    FeeCapBelowBaseFee,
+    /// The blob fee cap of the transaction is below the blob fee of the pending block.
+    BlobFeeCapBelowBlobFee,
+}
+
+/// Why a transaction was dropped from the pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum DropReason {
+    /// The sub-pool exceeded its limits and the transaction was among its worst transactions.
+    SizeLimit(SubPool),
+    /// The sub-pool exceeded its limits and the source of the transaction exceeded its quota.
+    QuotaExceeded,
+    /// A transaction of the sender with a lower nonce was evicted.
+    AncestorDropped,
+    /// The nonce of the transaction is below the on-chain nonce of the sender.
+    NonceTooLow,
+    /// The sender was delegated with EIP-7702 and the transaction exceeds the in-flight limit.
+    DelegationLimit,
+    /// The conditions of the conditional transaction can no longer be met.
+    ConditionFailed,
+    /// The transaction was removed explicitly.
+    Removed,
+}
+
+/// A state transition of a transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type", rename_all = "camelCase")
+)]
+pub enum JournalEvent {
+    /// The transaction was moved to the pending sub-pool.
+    Pending,
+    /// The transaction was moved to a parked sub-pool.
+    Parked {
+        /// The sub-pool the transaction was moved to.
+        subpool: SubPool,
+        /// Why the transaction isn't pending.
+        reason: Option<ParkedReason>,
+    },
+    /// The transaction was rejected by the pool.
+    Rejected {
+        /// The error the transaction was rejected with.
+        error: String,
+    },
+    /// The transaction was replaced by the transaction with the given hash.
+    Replaced {
+        /// Hash of the replacement transaction.
+        by: TxHash,
+    },
+    /// The transaction was included in the block with the given hash.
+    Mined {
+        /// Hash of the block.
+        block_hash: B256,
+    },
+    /// The transaction was dropped from the pool.
+    Dropped {
+        /// Why the transaction was dropped.
+        reason: DropReason,
+    },
+}
+
+/// A recorded [`JournalEvent`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct JournalEntry {
+    /// Unix timestamp of the event, in milliseconds.
+    pub timestamp: u64,
+    /// The recorded event.
+    pub event: JournalEvent,
+}
+
+/// The recorded history of a transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct TransactionHistory {
+    /// Hash of the transaction.
+    pub hash: TxHash,
+    /// Sender of the transaction.
+    pub sender: Address,
+    /// Nonce of the transaction.
+    pub nonce: u64,
+    /// All recorded events, oldest first.
+    pub events: Vec<JournalEntry>,
+}
@@ -0,0 +1,25 @@
+//! Commonly used transaction pool types.
+//!
+//! These are shared with consumers of the pool, like the `txpool` RPC namespace, that don't depend
+//! on the pool itself.
+//!
+//! ## Feature Flags
+//!
+//! - `serde` (default): Enable serde support
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+mod journal;
+pub use journal::{DropReason, JournalEntry, JournalEvent, ParkedReason, TransactionHistory};
+
+mod simulation;
+pub use simulation::{SimulationResult, SimulationStatus};
+
+mod subpool;
+pub use subpool::SubPool;
@@ -0,0 +1,51 @@
+//! Results of simulating pool transactions.
+
+use alloy_primitives::{Address, Bytes, B256};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// How the simulation of a transaction ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum SimulationStatus {
+    /// The transaction executed successfully.
+    Success,
+    /// The transaction reverted.
+    Revert,
+    /// The transaction halted, for example because it ran out of gas.
+    Halt,
+    /// The transaction can't be executed on top of the state.
+    Invalid,
+}
+
+/// The result of executing a transaction on top of a state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct SimulationResult {
+    /// State root of the block the transaction was executed on.
+    pub state_root: B256,
+    /// How the execution ended.
+    pub status: SimulationStatus,
+    /// Gas used by the transaction.
+    pub gas_used: u64,
+    /// Output of the transaction, the revert data if it reverted.
+    pub output: Bytes,
+    /// Storage slots read or written by the transaction, by account.
+    pub touched_slots: BTreeMap<Address, BTreeSet<B256>>,
+    /// Why the transaction halted or couldn't be executed.
+    pub error: Option<String>,
+}
+
+impl SimulationResult {
+    /// Returns `true` if the transaction executed successfully.
+    pub const fn is_success(&self) -> bool {
+        matches!(self.status, SimulationStatus::Success)
+    }
+}
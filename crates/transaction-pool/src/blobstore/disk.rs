@@ -1,8 +1,10 @@
 //! A simple diskstore for blobs
 
-use crate::blobstore::{BlobStore, BlobStoreCleanupStat, BlobStoreError, BlobStoreSize};
+use crate::blobstore::{
+    BlobStore, BlobStoreCleanupStat, BlobStoreError, BlobStoreSize, StaticFileBlobStore,
+};
 use alloy_eips::eip4844::BlobAndProofV1;
-use alloy_primitives::{TxHash, B256};
+use alloy_primitives::{BlockNumber, TxHash, B256};
 use alloy_rlp::{Decodable, Encodable};
 use parking_lot::{Mutex, RwLock};
 use reth_primitives::BlobTransactionSidecar;
@@ -30,12 +32,20 @@ impl DiskFileBlobStore {
         opts: DiskFileBlobStoreConfig,
     ) -> Result<Self, DiskFileBlobStoreError> {
         let blob_dir = blob_dir.into();
-        let DiskFileBlobStoreConfig { max_cached_entries, .. } = opts;
-        let inner = DiskFileBlobStoreInner::new(blob_dir, max_cached_entries);
+        let DiskFileBlobStoreConfig { max_cached_entries, open, static_files } = opts;
+        let inner = DiskFileBlobStoreInner::new(blob_dir, max_cached_entries, static_files);
 
         // initialize the blob store
-        inner.delete_all()?;
-        inner.create_blob_dir()?;
+        match open {
+            OpenDiskFileBlobStore::Clear => {
+                inner.delete_all()?;
+                inner.create_blob_dir()?;
+            }
+            OpenDiskFileBlobStore::ReIndex => {
+                inner.create_blob_dir()?;
+                inner.reindex()?;
+            }
+        }
 
         Ok(Self { inner: Arc::new(inner) })
     }
@@ -76,6 +86,17 @@ impl BlobStore for DiskFileBlobStore {
         Ok(())
     }
 
+    fn keep_all(&self, txs: Vec<B256>) -> Result<(), BlobStoreError> {
+        if self.inner.static_files.is_none() {
+            return Ok(())
+        }
+        let mut txs_to_delete = self.inner.txs_to_delete.write();
+        for tx in txs {
+            txs_to_delete.remove(&tx);
+        }
+        Ok(())
+    }
+
     fn cleanup(&self) -> BlobStoreCleanupStat {
         let txs_to_delete = {
             let mut txs_to_delete = self.inner.txs_to_delete.write();
@@ -151,9 +172,42 @@ impl BlobStore for DiskFileBlobStore {
                 break;
             }
         }
+
+        // look up the remaining blobs in the blobs of finalized blocks
+        if let Some(static_files) = &self.inner.static_files {
+            let missing = result
+                .iter()
+                .enumerate()
+                .filter_map(|(i, blob)| blob.is_none().then_some(i))
+                .collect::<Vec<_>>();
+            if !missing.is_empty() {
+                let hashes = missing.iter().map(|i| versioned_hashes[*i]).collect::<Vec<_>>();
+                let blobs = static_files.get_by_versioned_hashes(&hashes)?;
+                for (i, blob) in missing.into_iter().zip(blobs) {
+                    result[i] = blob;
+                }
+            }
+        }
         Ok(result)
     }
 
+    fn archive_finalized(
+        &self,
+        blocks: Vec<(BlockNumber, Vec<B256>)>,
+    ) -> Result<(), BlobStoreError> {
+        let Some(static_files) = &self.inner.static_files else { return Ok(()) };
+        let mut tip = None;
+        for (block_number, txs) in blocks {
+            let sidecars = self.get_all(txs)?;
+            static_files.append_block(block_number, sidecars.into_iter().map(|(_, blob)| blob))?;
+            tip = tip.max(Some(block_number));
+        }
+        if let Some(tip) = tip {
+            static_files.prune(tip)?;
+        }
+        Ok(())
+    }
+
     fn data_size_hint(&self) -> Option<usize> {
         Some(self.inner.size_tracker.data_size())
     }
@@ -169,17 +223,20 @@ struct DiskFileBlobStoreInner {
     size_tracker: BlobStoreSize,
     file_lock: RwLock<()>,
     txs_to_delete: RwLock<HashSet<B256>>,
+    /// Keeps the blobs of finalized blocks, if enabled.
+    static_files: Option<StaticFileBlobStore>,
 }
 
 impl DiskFileBlobStoreInner {
     /// Creates a new empty disk file blob store with the given maximum length of the blob cache.
-    fn new(blob_dir: PathBuf, max_length: u32) -> Self {
+    fn new(blob_dir: PathBuf, max_length: u32, static_files: Option<StaticFileBlobStore>) -> Self {
         Self {
             blob_dir,
             blob_cache: Mutex::new(LruMap::new(ByLength::new(max_length))),
             size_tracker: Default::default(),
             file_lock: Default::default(),
             txs_to_delete: Default::default(),
+            static_files,
         }
     }
 
@@ -202,8 +259,33 @@ impl DiskFileBlobStoreInner {
         Ok(())
     }
 
+    /// Indexes the blobs that are already on disk.
+    ///
+    /// They are all scheduled for deletion: blobs that are still needed are either re-inserted or
+    /// kept with [`BlobStore::keep_all`] before the next [`BlobStore::cleanup`].
+    fn reindex(&self) -> Result<(), DiskFileBlobStoreError> {
+        let open_err = |err| DiskFileBlobStoreError::Open(self.blob_dir.clone(), err);
+        let mut size = 0;
+        let mut txs = HashSet::new();
+        for entry in fs::read_dir(&self.blob_dir).map_err(open_err)? {
+            let entry = entry.map_err(open_err)?;
+            let Some(tx) = entry.file_name().to_str().and_then(|name| name.parse::<B256>().ok())
+            else {
+                continue
+            };
+            size += entry.metadata().map_err(open_err)?.len() as usize;
+            txs.insert(tx);
+        }
+        debug!(target:"txpool::blob", blob_dir = ?self.blob_dir, num_blobs = txs.len(), "Reindexed blob store");
+        self.size_tracker.add_size(size);
+        self.size_tracker.inc_len(txs.len());
+        self.txs_to_delete.write().extend(txs);
+        Ok(())
+    }
+
     /// Ensures blob is in the blob cache and written to the disk.
     fn insert_one(&self, tx: B256, data: BlobTransactionSidecar) -> Result<(), BlobStoreError> {
+        self.txs_to_delete.write().remove(&tx);
         let mut buf = Vec::with_capacity(data.fields_len());
         data.encode(&mut buf);
         self.blob_cache.lock().insert(tx, data);
@@ -226,8 +308,10 @@ impl DiskFileBlobStoreInner {
             .collect::<Vec<_>>();
 
         {
+            let mut txs_to_delete = self.txs_to_delete.write();
             let mut cache = self.blob_cache.lock();
             for (tx, data) in txs {
+                txs_to_delete.remove(&tx);
                 cache.insert(tx, data);
             }
         }
@@ -425,6 +509,7 @@ impl fmt::Debug for DiskFileBlobStoreInner {
             .field("blob_dir", &self.blob_dir)
             .field("cached_blobs", &self.blob_cache.try_lock().map(|lock| lock.len()))
             .field("txs_to_delete", &self.txs_to_delete.try_read())
+            .field("static_files", &self.static_files)
             .finish()
     }
 }
@@ -463,11 +548,17 @@ pub struct DiskFileBlobStoreConfig {
     pub max_cached_entries: u32,
     /// How to open the blob store.
     pub open: OpenDiskFileBlobStore,
+    /// Where the blobs of finalized blocks are kept, instead of deleting them.
+    pub static_files: Option<StaticFileBlobStore>,
 }
 
 impl Default for DiskFileBlobStoreConfig {
     fn default() -> Self {
-        Self { max_cached_entries: DEFAULT_MAX_CACHED_BLOBS, open: Default::default() }
+        Self {
+            max_cached_entries: DEFAULT_MAX_CACHED_BLOBS,
+            open: Default::default(),
+            static_files: None,
+        }
     }
 }
 
@@ -477,6 +568,19 @@ impl DiskFileBlobStoreConfig {
         self.max_cached_entries = max_cached_entries;
         self
     }
+
+    /// Sets how to open the blob store.
+    pub const fn with_open(mut self, open: OpenDiskFileBlobStore) -> Self {
+        self.open = open;
+        self
+    }
+
+    /// Keeps the blobs of finalized blocks in the given static files, see
+    /// [`BlobStore::archive_finalized`].
+    pub fn with_static_files(mut self, static_files: StaticFileBlobStore) -> Self {
+        self.static_files = Some(static_files);
+        self
+    }
 }
 
 /// How to open a disk file blob store.
@@ -485,13 +589,17 @@ pub enum OpenDiskFileBlobStore {
     /// Clear everything in the blob store.
     #[default]
     Clear,
-    /// Keep the existing blob store and index
+    /// Keep the existing blob store and index it.
+    ///
+    /// Existing blobs are deleted on the next [`BlobStore::cleanup`], unless they are re-inserted
+    /// or kept with [`BlobStore::keep_all`].
     ReIndex,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloy_eips::eip4844::{Blob, Bytes48};
     use std::sync::atomic::Ordering;
 
     fn tmp_store() -> (DiskFileBlobStore, tempfile::TempDir) {
@@ -546,4 +654,59 @@ mod tests {
         assert_eq!(store.data_size_hint(), Some(0));
         assert_eq!(store.inner.size_tracker.num_blobs.load(Ordering::Relaxed), 0);
     }
+
+    fn archive_store(dir: &tempfile::TempDir, open: OpenDiskFileBlobStore) -> DiskFileBlobStore {
+        let static_files =
+            StaticFileBlobStore::open(dir.path().join("static_files"), Default::default()).unwrap();
+        let config =
+            DiskFileBlobStoreConfig::default().with_open(open).with_static_files(static_files);
+        DiskFileBlobStore::open(dir.path().join("blobs"), config).unwrap()
+    }
+
+    #[test]
+    fn disk_reindex_keeps_pending_blobs() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = archive_store(&dir, OpenDiskFileBlobStore::Clear);
+        let blobs = rng_blobs(3);
+        store.insert_all(blobs.clone()).unwrap();
+        drop(store);
+
+        let store = archive_store(&dir, OpenDiskFileBlobStore::ReIndex);
+        assert_eq!(store.blobs_len(), 3);
+        for (tx, _) in &blobs {
+            assert!(store.contains(*tx).unwrap());
+        }
+
+        // only the kept and re-inserted blobs survive the cleanup
+        store.keep_all(vec![blobs[0].0]).unwrap();
+        store.insert(blobs[1].0, blobs[1].1.clone()).unwrap();
+        store.cleanup();
+        assert!(store.contains(blobs[0].0).unwrap());
+        assert!(store.contains(blobs[1].0).unwrap());
+        store.clear_cache();
+        assert!(!store.contains(blobs[2].0).unwrap());
+    }
+
+    #[test]
+    fn disk_archive_finalized_blob() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = archive_store(&dir, OpenDiskFileBlobStore::Clear);
+        let sidecar = BlobTransactionSidecar {
+            blobs: vec![Blob::random()],
+            commitments: vec![Bytes48::random()],
+            proofs: vec![Bytes48::random()],
+        };
+        let tx = TxHash::random();
+        store.insert(tx, sidecar.clone()).unwrap();
+
+        // the blob is still served after the block is finalized and the blob deleted
+        store.archive_finalized(vec![(1, vec![tx])]).unwrap();
+        store.delete(tx).unwrap();
+        store.cleanup();
+        store.clear_cache();
+        assert!(!store.contains(tx).unwrap());
+        let versioned_hash = sidecar.versioned_hashes().next().unwrap();
+        let blobs = store.get_by_versioned_hashes(&[versioned_hash]).unwrap();
+        assert_eq!(blobs[0].as_ref().unwrap().proof, sidecar.proofs[0]);
+    }
 }
@@ -1,11 +1,12 @@
 //! Storage for blob data of EIP4844 transactions.
 
 use alloy_eips::eip4844::BlobAndProofV1;
-use alloy_primitives::B256;
+use alloy_primitives::{BlockNumber, B256};
 pub use disk::{DiskFileBlobStore, DiskFileBlobStoreConfig, OpenDiskFileBlobStore};
 pub use mem::InMemoryBlobStore;
 pub use noop::NoopBlobStore;
 use reth_primitives::BlobTransactionSidecar;
+pub use static_file::{StaticFileBlobStore, StaticFileBlobStoreConfig, StaticFileBlobStoreError};
 use std::{
     fmt,
     sync::atomic::{AtomicUsize, Ordering},
@@ -15,6 +16,7 @@ pub use tracker::{BlobStoreCanonTracker, BlobStoreUpdates};
 pub mod disk;
 mod mem;
 mod noop;
+pub mod static_file;
 mod tracker;
 
 /// A blob store that can be used to store blob data of EIP4844 transactions.
@@ -72,6 +74,29 @@ pub trait BlobStore: fmt::Debug + Send + Sync + 'static {
         versioned_hashes: &[B256],
     ) -> Result<Vec<Option<BlobAndProofV1>>, BlobStoreError>;
 
+    /// Invoked with the blob transactions of finalized blocks, by block, before they are deleted
+    /// from the store.
+    ///
+    /// Stores that keep the blobs of finalized blocks, see [`StaticFileBlobStore`], copy them here.
+    /// This is a no-op by default.
+    fn archive_finalized(
+        &self,
+        blocks: Vec<(BlockNumber, Vec<B256>)>,
+    ) -> Result<(), BlobStoreError> {
+        let _ = blocks;
+        Ok(())
+    }
+
+    /// Cancels the pending deletion of the given blob sidecars, because their transactions were
+    /// included in a block and their blobs are kept until the block is finalized.
+    ///
+    /// This is only relevant for stores that keep the blobs of finalized blocks, and a no-op by
+    /// default.
+    fn keep_all(&self, txs: Vec<B256>) -> Result<(), BlobStoreError> {
+        let _ = txs;
+        Ok(())
+    }
+
     /// Data size of all transactions in the blob store.
     fn data_size_hint(&self) -> Option<usize>;
 
@@ -0,0 +1,524 @@
+//! An append-only store for the blobs of finalized blocks.
+
+use crate::blobstore::BlobStoreError;
+use alloy_eips::eip4844::{Blob, BlobAndProofV1, Bytes48, BYTES_PER_BLOB};
+use alloy_primitives::{BlockNumber, B256};
+use parking_lot::{Mutex, RwLock};
+use reth_primitives::BlobTransactionSidecar;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt, fs,
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tracing::{debug, trace};
+
+/// Number of epochs blob sidecars must be available for, see
+/// <https://github.com/ethereum/consensus-specs/blob/dev/specs/deneb/p2p-interface.md#configuration>
+pub const MIN_EPOCHS_FOR_BLOB_SIDECARS_REQUESTS: u64 = 4096;
+
+/// Default number of blocks blobs are kept for: the blob data availability window.
+pub const DEFAULT_BLOB_RETENTION_BLOCKS: u64 = MIN_EPOCHS_FOR_BLOB_SIDECARS_REQUESTS * 32;
+
+/// Default number of blocks per static file.
+pub const DEFAULT_BLOCKS_PER_BLOB_STATIC_FILE: u64 = 4096;
+
+/// Size of a stored blob: the block number, versioned hash, blob, commitment and proof.
+const BLOB_RECORD_SIZE: usize = 8 + 32 + BYTES_PER_BLOB + 48 + 48;
+
+/// Offset of the blob in a stored blob, after the block number and versioned hash.
+const BLOB_OFFSET: u64 = 8 + 32;
+
+/// An append-only store for the blobs of finalized blocks, indexed by block and versioned hash.
+///
+/// Blobs are appended to static files that each cover a range of
+/// [`StaticFileBlobStoreConfig::blocks_per_file`] blocks, as fixed-size records. Static files
+/// that only contain blocks outside of the retention window are deleted as a whole, see
+/// [`Self::prune`].
+///
+/// The index is kept in memory and rebuilt from the static files on [`Self::open`].
+#[derive(Clone, Debug)]
+pub struct StaticFileBlobStore {
+    inner: Arc<StaticFileBlobStoreInner>,
+}
+
+impl StaticFileBlobStore {
+    /// Opens the store in the given directory, indexing all existing static files.
+    pub fn open(
+        dir: impl Into<PathBuf>,
+        config: StaticFileBlobStoreConfig,
+    ) -> Result<Self, StaticFileBlobStoreError> {
+        config.validate()?;
+        let dir = dir.into();
+        fs::create_dir_all(&dir).map_err(|err| StaticFileBlobStoreError::Open(dir.clone(), err))?;
+
+        let mut index = BlobIndex::default();
+        let entries =
+            fs::read_dir(&dir).map_err(|err| StaticFileBlobStoreError::Open(dir.clone(), err))?;
+        for entry in entries {
+            let path =
+                entry.map_err(|err| StaticFileBlobStoreError::Open(dir.clone(), err))?.path();
+            let Some(file_start) = parse_static_file_name(&path) else { continue };
+            index.index_static_file(file_start, &path)?;
+        }
+        debug!(
+            target: "txpool::blob",
+            ?dir,
+            blobs = index.blobs.len(),
+            blocks = index.blocks.len(),
+            "opened blob static files"
+        );
+
+        let inner = StaticFileBlobStoreInner {
+            dir,
+            config,
+            index: RwLock::new(index),
+            append_lock: Mutex::new(()),
+            file_lock: RwLock::new(()),
+        };
+        Ok(Self { inner: Arc::new(inner) })
+    }
+
+    /// Returns the number of stored blobs.
+    pub fn blobs_len(&self) -> usize {
+        self.inner.index.read().blobs.len()
+    }
+
+    /// Returns the lowest and highest block with stored blobs.
+    pub fn block_range(&self) -> Option<(BlockNumber, BlockNumber)> {
+        let index = self.inner.index.read();
+        Some((*index.blocks.first_key_value()?.0, *index.blocks.last_key_value()?.0))
+    }
+
+    /// Returns the versioned hashes of the stored blobs of the given block.
+    pub fn versioned_hashes_by_block(&self, block_number: BlockNumber) -> Vec<B256> {
+        self.inner.index.read().blocks.get(&block_number).cloned().unwrap_or_default()
+    }
+
+    /// Appends the blobs of the sidecars included in the given block.
+    ///
+    /// Blobs that are already stored are skipped, so this completes blocks that were only
+    /// partially stored, for example because some sidecars were missing or an append was
+    /// interrupted.
+    pub fn append_block(
+        &self,
+        block_number: BlockNumber,
+        sidecars: impl IntoIterator<Item = BlobTransactionSidecar>,
+    ) -> Result<(), StaticFileBlobStoreError> {
+        // appends only write past the indexed records, so they don't block reads
+        let _append_lock = self.inner.append_lock.lock();
+        let _file_lock = self.inner.file_lock.read();
+        let mut buf = Vec::new();
+        let mut versioned_hashes = Vec::new();
+        {
+            let index = self.inner.index.read();
+            for sidecar in sidecars {
+                for (i, versioned_hash) in sidecar.versioned_hashes().enumerate() {
+                    if index.blobs.contains_key(&versioned_hash) ||
+                        versioned_hashes.contains(&versioned_hash)
+                    {
+                        continue
+                    }
+                    buf.extend_from_slice(&block_number.to_be_bytes());
+                    buf.extend_from_slice(versioned_hash.as_slice());
+                    buf.extend_from_slice(sidecar.blobs[i].as_slice());
+                    buf.extend_from_slice(sidecar.commitments[i].as_slice());
+                    buf.extend_from_slice(sidecar.proofs[i].as_slice());
+                    versioned_hashes.push(versioned_hash);
+                }
+            }
+        }
+        if versioned_hashes.is_empty() {
+            return Ok(())
+        }
+
+        let file_start = self.inner.config.file_start(block_number);
+        let path = self.inner.static_file(file_start);
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|err| StaticFileBlobStoreError::Write(path.clone(), err))?;
+        let offset = file
+            .metadata()
+            .map_err(|err| StaticFileBlobStoreError::Write(path.clone(), err))?
+            .len();
+        file.write_all(&buf)
+            .and_then(|_| file.sync_data())
+            .map_err(|err| StaticFileBlobStoreError::Write(path.clone(), err))?;
+
+        trace!(
+            target: "txpool::blob",
+            block_number,
+            blobs = versioned_hashes.len(),
+            "appended blobs to static file"
+        );
+        let mut index = self.inner.index.write();
+        for (i, versioned_hash) in versioned_hashes.iter().enumerate() {
+            let offset = offset + (i * BLOB_RECORD_SIZE) as u64;
+            index.blobs.insert(*versioned_hash, BlobLocation { file_start, offset });
+        }
+        index.blocks.entry(block_number).or_default().extend(versioned_hashes);
+        Ok(())
+    }
+
+    /// Returns the blobs and proofs for the given versioned hashes, in the requested order.
+    pub fn get_by_versioned_hashes(
+        &self,
+        versioned_hashes: &[B256],
+    ) -> Result<Vec<Option<BlobAndProofV1>>, StaticFileBlobStoreError> {
+        let locations = {
+            let index = self.inner.index.read();
+            versioned_hashes.iter().map(|hash| index.blobs.get(hash).copied()).collect::<Vec<_>>()
+        };
+        if locations.iter().all(Option::is_none) {
+            return Ok(vec![None; versioned_hashes.len()])
+        }
+
+        // hold the lock so that static files can't be pruned while they are read
+        let _lock = self.inner.file_lock.read();
+        locations
+            .into_iter()
+            .map(|location| location.map(|location| self.inner.read_blob(location)).transpose())
+            .collect()
+    }
+
+    /// Deletes all static files that only contain blocks outside of the retention window of the
+    /// given tip.
+    ///
+    /// Returns the number of pruned blobs.
+    pub fn prune(&self, tip: BlockNumber) -> Result<usize, StaticFileBlobStoreError> {
+        let Some(min_block) = tip.checked_sub(self.inner.config.retention_blocks) else {
+            return Ok(0)
+        };
+        // static files that end before this block are deleted
+        let prune_below = self.inner.config.file_start(min_block);
+
+        let _lock = self.inner.file_lock.write();
+        let mut index = self.inner.index.write();
+        let mut pruned_files = Vec::new();
+        let mut pruned = 0;
+        while let Some(entry) = index.blocks.first_entry() {
+            if *entry.key() >= prune_below {
+                break
+            }
+            let (block_number, versioned_hashes) = entry.remove_entry();
+            pruned += versioned_hashes.len();
+            for hash in versioned_hashes {
+                index.blobs.remove(&hash);
+            }
+            let file_start = self.inner.config.file_start(block_number);
+            if pruned_files.last() != Some(&file_start) {
+                pruned_files.push(file_start);
+            }
+        }
+        drop(index);
+
+        for file_start in pruned_files {
+            let path = self.inner.static_file(file_start);
+            fs::remove_file(&path).map_err(|err| StaticFileBlobStoreError::Delete(path, err))?;
+        }
+        if pruned > 0 {
+            debug!(target: "txpool::blob", tip, pruned, "pruned blob static files");
+        }
+        Ok(pruned)
+    }
+}
+
+struct StaticFileBlobStoreInner {
+    dir: PathBuf,
+    config: StaticFileBlobStoreConfig,
+    index: RwLock<BlobIndex>,
+    /// Serializes appends to the static files.
+    append_lock: Mutex<()>,
+    /// Held for reading while static files are read or appended to, and for writing while they
+    /// are deleted.
+    file_lock: RwLock<()>,
+}
+
+impl StaticFileBlobStoreInner {
+    /// Returns the path of the static file that starts at the given block.
+    fn static_file(&self, file_start: BlockNumber) -> PathBuf {
+        let file_end = file_start + self.config.blocks_per_file - 1;
+        self.dir.join(format!("blobs_{file_start}_{file_end}"))
+    }
+
+    /// Reads the blob and proof at the given location.
+    fn read_blob(
+        &self,
+        location: BlobLocation,
+    ) -> Result<BlobAndProofV1, StaticFileBlobStoreError> {
+        let path = self.static_file(location.file_start);
+        let read = || -> io::Result<BlobAndProofV1> {
+            let mut file = fs::File::open(&path)?;
+            file.seek(SeekFrom::Start(location.offset + BLOB_OFFSET))?;
+            let mut blob = Box::new(Blob::ZERO);
+            file.read_exact(blob.as_mut_slice())?;
+            // skip the commitment
+            file.seek(SeekFrom::Current(48))?;
+            let mut proof = Bytes48::ZERO;
+            file.read_exact(proof.as_mut_slice())?;
+            Ok(BlobAndProofV1 { blob, proof })
+        };
+        read().map_err(|err| StaticFileBlobStoreError::Read(path.clone(), err))
+    }
+}
+
+impl fmt::Debug for StaticFileBlobStoreInner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticFileBlobStoreInner")
+            .field("dir", &self.dir)
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Location of a blob in the static files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BlobLocation {
+    /// First block of the static file.
+    file_start: BlockNumber,
+    /// Offset of the record in the static file.
+    offset: u64,
+}
+
+/// In-memory index of the static files.
+#[derive(Debug, Default)]
+struct BlobIndex {
+    /// Location of the blobs, by versioned hash.
+    blobs: HashMap<B256, BlobLocation>,
+    /// Versioned hashes of the blobs, by block.
+    blocks: BTreeMap<BlockNumber, Vec<B256>>,
+}
+
+impl BlobIndex {
+    /// Indexes all records of the static file.
+    ///
+    /// A trailing partial record, for example after a crash during an append, is truncated.
+    fn index_static_file(
+        &mut self,
+        file_start: BlockNumber,
+        path: &Path,
+    ) -> Result<(), StaticFileBlobStoreError> {
+        let read_err = |err| StaticFileBlobStoreError::Read(path.to_path_buf(), err);
+        let mut file =
+            fs::OpenOptions::new().read(true).write(true).open(path).map_err(read_err)?;
+        let len = file.metadata().map_err(read_err)?.len();
+        let records = len / BLOB_RECORD_SIZE as u64;
+        if len % BLOB_RECORD_SIZE as u64 != 0 {
+            debug!(target: "txpool::blob", ?path, "truncating partial blob record");
+            file.set_len(records * BLOB_RECORD_SIZE as u64).map_err(read_err)?;
+        }
+
+        let mut header = [0u8; BLOB_OFFSET as usize];
+        for record in 0..records {
+            let offset = record * BLOB_RECORD_SIZE as u64;
+            file.seek(SeekFrom::Start(offset)).map_err(read_err)?;
+            file.read_exact(&mut header).map_err(read_err)?;
+            let block_number = u64::from_be_bytes(header[..8].try_into().expect("8 bytes"));
+            let versioned_hash = B256::from_slice(&header[8..]);
+            self.blobs.insert(versioned_hash, BlobLocation { file_start, offset });
+            self.blocks.entry(block_number).or_default().push(versioned_hash);
+        }
+        Ok(())
+    }
+}
+
+/// Returns the first block of the static file with the given path, if it is a blob static file.
+fn parse_static_file_name(path: &Path) -> Option<BlockNumber> {
+    let name = path.file_name()?.to_str()?;
+    let (start, _end) = name.strip_prefix("blobs_")?.split_once('_')?;
+    start.parse().ok()
+}
+
+/// Configuration for a [`StaticFileBlobStore`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StaticFileBlobStoreConfig {
+    /// Number of blocks blobs are kept for.
+    pub retention_blocks: u64,
+    /// Number of blocks per static file.
+    pub blocks_per_file: u64,
+}
+
+impl StaticFileBlobStoreConfig {
+    /// Sets the number of blocks blobs are kept for.
+    pub const fn with_retention_blocks(mut self, retention_blocks: u64) -> Self {
+        self.retention_blocks = retention_blocks;
+        self
+    }
+
+    /// Sets the number of blocks per static file.
+    ///
+    /// This must not be zero, see [`Self::validate`].
+    pub const fn with_blocks_per_file(mut self, blocks_per_file: u64) -> Self {
+        self.blocks_per_file = blocks_per_file;
+        self
+    }
+
+    /// Returns an error if the configuration is invalid.
+    pub const fn validate(&self) -> Result<(), StaticFileBlobStoreError> {
+        if self.blocks_per_file == 0 {
+            return Err(StaticFileBlobStoreError::ZeroBlocksPerFile)
+        }
+        Ok(())
+    }
+
+    /// Returns the first block of the static file that contains the given block.
+    const fn file_start(&self, block_number: BlockNumber) -> BlockNumber {
+        block_number - block_number % self.blocks_per_file
+    }
+}
+
+impl Default for StaticFileBlobStoreConfig {
+    fn default() -> Self {
+        Self {
+            retention_blocks: DEFAULT_BLOB_RETENTION_BLOCKS,
+            blocks_per_file: DEFAULT_BLOCKS_PER_BLOB_STATIC_FILE,
+        }
+    }
+}
+
+/// Errors that can occur when interacting with a [`StaticFileBlobStore`].
+#[derive(Debug, thiserror::Error)]
+pub enum StaticFileBlobStoreError {
+    /// Thrown during [`StaticFileBlobStore::open`] if the static files would not cover any blocks.
+    #[error("blocks per blob static file must not be zero")]
+    ZeroBlocksPerFile,
+    /// Thrown during [`StaticFileBlobStore::open`] if the directory cannot be opened.
+    #[error("failed to open blob static files at {0}: {1}")]
+    Open(PathBuf, io::Error),
+    /// Failure while reading a static file.
+    #[error("failed to read blob static file at {0}: {1}")]
+    Read(PathBuf, io::Error),
+    /// Failure while appending to a static file.
+    #[error("failed to write blob static file at {0}: {1}")]
+    Write(PathBuf, io::Error),
+    /// Failure while deleting a static file.
+    #[error("failed to delete blob static file at {0}: {1}")]
+    Delete(PathBuf, io::Error),
+}
+
+impl From<StaticFileBlobStoreError> for BlobStoreError {
+    fn from(value: StaticFileBlobStoreError) -> Self {
+        Self::Other(Box::new(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_eips::eip4844::kzg_to_versioned_hash;
+
+    fn rng_sidecar(num_blobs: usize) -> BlobTransactionSidecar {
+        BlobTransactionSidecar {
+            blobs: (0..num_blobs).map(|_| Blob::random()).collect(),
+            commitments: (0..num_blobs).map(|_| Bytes48::random()).collect(),
+            proofs: (0..num_blobs).map(|_| Bytes48::random()).collect(),
+        }
+    }
+
+    fn config() -> StaticFileBlobStoreConfig {
+        StaticFileBlobStoreConfig { retention_blocks: 20, blocks_per_file: 10 }
+    }
+
+    #[test]
+    fn append_and_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = StaticFileBlobStore::open(dir.path(), config()).unwrap();
+
+        let sidecars = vec![rng_sidecar(2), rng_sidecar(1)];
+        store.append_block(5, sidecars.clone()).unwrap();
+        store.append_block(12, vec![rng_sidecar(1)]).unwrap();
+        // already stored
+        store.append_block(5, sidecars.clone()).unwrap();
+        assert_eq!(store.blobs_len(), 4);
+        assert_eq!(store.block_range(), Some((5, 12)));
+
+        let hash = kzg_to_versioned_hash(sidecars[1].commitments[0].as_slice());
+        let expected =
+            BlobAndProofV1 { blob: Box::new(sidecars[1].blobs[0]), proof: sidecars[1].proofs[0] };
+        let blobs = store.get_by_versioned_hashes(&[B256::random(), hash]).unwrap();
+        assert_eq!(blobs, vec![None, Some(expected.clone())]);
+
+        // the index is rebuilt from the static files
+        drop(store);
+        let store = StaticFileBlobStore::open(dir.path(), config()).unwrap();
+        assert_eq!(store.blobs_len(), 4);
+        assert_eq!(store.versioned_hashes_by_block(5).len(), 3);
+        assert_eq!(store.get_by_versioned_hashes(&[hash]).unwrap(), vec![Some(expected)]);
+    }
+
+    #[test]
+    fn complete_partial_block() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = StaticFileBlobStore::open(dir.path(), config()).unwrap();
+
+        let sidecars = vec![rng_sidecar(1), rng_sidecar(2)];
+        store.append_block(3, sidecars[..1].to_vec()).unwrap();
+        assert_eq!(store.versioned_hashes_by_block(3).len(), 1);
+
+        // the missing sidecar is appended, the stored one is skipped
+        store.append_block(3, sidecars.clone()).unwrap();
+        assert_eq!(store.blobs_len(), 3);
+        let expected = sidecars.iter().flat_map(|sidecar| sidecar.versioned_hashes());
+        assert_eq!(store.versioned_hashes_by_block(3), expected.collect::<Vec<_>>());
+
+        drop(store);
+        let store = StaticFileBlobStore::open(dir.path(), config()).unwrap();
+        assert_eq!(store.versioned_hashes_by_block(3).len(), 3);
+        let path = dir.path().join("blobs_0_9");
+        assert_eq!(fs::metadata(path).unwrap().len(), 3 * BLOB_RECORD_SIZE as u64);
+    }
+
+    #[test]
+    fn reject_zero_blocks_per_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = config().with_blocks_per_file(0);
+        assert!(matches!(
+            StaticFileBlobStore::open(dir.path(), config),
+            Err(StaticFileBlobStoreError::ZeroBlocksPerFile)
+        ));
+    }
+
+    #[test]
+    fn truncate_partial_record() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = StaticFileBlobStore::open(dir.path(), config()).unwrap();
+        store.append_block(1, vec![rng_sidecar(1)]).unwrap();
+        drop(store);
+
+        let path = dir.path().join("blobs_0_9");
+        let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&[1; 100]).unwrap();
+
+        let store = StaticFileBlobStore::open(dir.path(), config()).unwrap();
+        assert_eq!(store.blobs_len(), 1);
+        assert_eq!(fs::metadata(&path).unwrap().len(), BLOB_RECORD_SIZE as u64);
+
+        // appends after the truncated record stay aligned
+        let sidecar = rng_sidecar(1);
+        store.append_block(1, vec![sidecar.clone()]).unwrap();
+        assert_eq!(store.blobs_len(), 2);
+        let hash = sidecar.versioned_hashes().next().unwrap();
+        assert!(store.get_by_versioned_hashes(&[hash]).unwrap()[0].is_some());
+    }
+
+    #[test]
+    fn prune_outside_retention_window() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = StaticFileBlobStore::open(dir.path(), config()).unwrap();
+        for block in [1, 9, 15, 25] {
+            store.append_block(block, vec![rng_sidecar(1)]).unwrap();
+        }
+
+        // window starts at block 15, in the second static file
+        assert_eq!(store.prune(35).unwrap(), 2);
+        assert_eq!(store.block_range(), Some((15, 25)));
+        assert!(!dir.path().join("blobs_0_9").exists());
+        assert!(dir.path().join("blobs_10_19").exists());
+
+        // nothing left to prune
+        assert_eq!(store.prune(35).unwrap(), 0);
+    }
+}
@@ -53,20 +53,38 @@ impl BlobStoreCanonTracker {
     ///
     /// This returns all blob transactions that were included in blocks that are now finalized.
     pub fn on_finalized_block(&mut self, finalized_block: BlockNumber) -> BlobStoreUpdates {
+        let finalized = self
+            .take_finalized_blocks(finalized_block)
+            .into_iter()
+            .flat_map(|(_, blob_txs)| blob_txs)
+            .collect::<Vec<_>>();
+
+        if finalized.is_empty() {
+            BlobStoreUpdates::None
+        } else {
+            BlobStoreUpdates::Finalized(finalized)
+        }
+    }
+
+    /// Invoked when a block is finalized.
+    ///
+    /// Like [`Self::on_finalized_block`], but returns the blob transactions by finalized block.
+    pub fn take_finalized_blocks(
+        &mut self,
+        finalized_block: BlockNumber,
+    ) -> Vec<(BlockNumber, Vec<B256>)> {
         let mut finalized = Vec::new();
         while let Some(entry) = self.blob_txs_in_blocks.first_entry() {
             if *entry.key() <= finalized_block {
-                finalized.extend(entry.remove_entry().1);
+                let (block_number, blob_txs) = entry.remove_entry();
+                if !blob_txs.is_empty() {
+                    finalized.push((block_number, blob_txs));
+                }
             } else {
                 break
             }
         }
-
-        if finalized.is_empty() {
-            BlobStoreUpdates::None
-        } else {
-            BlobStoreUpdates::Finalized(finalized)
-        }
+        finalized
     }
 }
 
@@ -101,4 +119,18 @@ mod tests {
             BlobStoreUpdates::Finalized(block2.into_iter().chain(block3).collect::<Vec<_>>())
         );
     }
+
+    #[test]
+    fn test_take_finalized_blocks() {
+        let mut tracker = BlobStoreCanonTracker::default();
+
+        let block1 = vec![B256::random()];
+        let block3 = vec![B256::random(), B256::random()];
+        tracker.add_block(1, block1.clone());
+        tracker.add_block(2, vec![]);
+        tracker.add_block(3, block3.clone());
+
+        assert_eq!(tracker.take_finalized_blocks(3), vec![(1, block1), (3, block3)]);
+        assert!(tracker.take_finalized_blocks(3).is_empty());
+    }
 }
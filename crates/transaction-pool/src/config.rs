@@ -1,5 +1,6 @@
 use crate::{
-    pool::{NEW_TX_LISTENER_BUFFER_SIZE, PENDING_TX_LISTENER_BUFFER_SIZE},
+    pool::{EvictionStrategy, NEW_TX_LISTENER_BUFFER_SIZE, PENDING_TX_LISTENER_BUFFER_SIZE},
+    validate::TransactionPolicies,
     PoolSize, TransactionOrigin,
 };
 use alloy_primitives::Address;
@@ -7,7 +8,7 @@ use reth_primitives::{
     constants::{ETHEREUM_BLOCK_GAS_LIMIT, MIN_PROTOCOL_BASE_FEE},
     EIP4844_TX_TYPE_ID,
 };
-use std::{collections::HashSet, ops::Mul};
+use std::{collections::HashSet, ops::Mul, sync::Arc};
 
 /// Guarantees max transactions for one sender, compatible with geth/erigon
 pub const TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER: usize = 16;
@@ -18,6 +19,12 @@ pub const TXPOOL_SUBPOOL_MAX_TXS_DEFAULT: usize = 10_000;
 /// The default maximum allowed size of the given subpool.
 pub const TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT: usize = 20;
 
+/// Max in-flight transactions of an account delegated with EIP-7702, compatible with geth.
+pub const TXPOOL_MAX_INFLIGHT_DELEGATED_SLOTS: usize = 1;
+
+/// The default number of transactions tracked by the transaction journal.
+pub const TXPOOL_JOURNAL_MAX_TRANSACTIONS_DEFAULT: usize = 10_000;
+
 /// The default additional validation tasks size.
 pub const DEFAULT_TXPOOL_ADDITIONAL_VALIDATION_TASKS: usize = 1;
 
@@ -42,6 +49,9 @@ pub struct PoolConfig {
     pub blob_limit: SubPoolLimit,
     /// Max number of executable transaction slots guaranteed per account
     pub max_account_slots: usize,
+    /// Max number of in-flight transactions of an account that is delegated with EIP-7702, or is
+    /// an authority of a pending EIP-7702 transaction.
+    pub max_inflight_delegated_slots: usize,
     /// Price bump (in %) for the transaction pool underpriced check.
     pub price_bumps: PriceBumpConfig,
     /// Minimum base fee required by the protocol.
@@ -55,6 +65,17 @@ pub struct PoolConfig {
     pub pending_tx_listener_buffer_size: usize,
     /// Bound on number of new transactions from `reth_network::TransactionsManager` to buffer.
     pub new_tx_listener_buffer_size: usize,
+    /// Max number of transactions whose lifecycle is recorded by the transaction journal, `0`
+    /// disables the journal.
+    pub max_journal_transactions: usize,
+    /// How to pick the transactions evicted from sub-pools that exceed their limits.
+    ///
+    /// If unset, each sub-pool evicts by its own ordering.
+    pub eviction_strategy: Option<Arc<dyn EvictionStrategy>>,
+    /// Quotas of transactions per origin and per propagating peer.
+    pub quotas: SourceQuotas,
+    /// Admission policies checked for every validated transaction before it's inserted.
+    pub policies: TransactionPolicies,
 }
 
 impl PoolConfig {
@@ -76,14 +97,55 @@ impl Default for PoolConfig {
             queued_limit: Default::default(),
             blob_limit: Default::default(),
             max_account_slots: TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
+            max_inflight_delegated_slots: TXPOOL_MAX_INFLIGHT_DELEGATED_SLOTS,
             price_bumps: Default::default(),
             minimal_protocol_basefee: MIN_PROTOCOL_BASE_FEE,
             gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
             local_transactions_config: Default::default(),
             pending_tx_listener_buffer_size: PENDING_TX_LISTENER_BUFFER_SIZE,
             new_tx_listener_buffer_size: NEW_TX_LISTENER_BUFFER_SIZE,
+            max_journal_transactions: TXPOOL_JOURNAL_MAX_TRANSACTIONS_DEFAULT,
+            eviction_strategy: None,
+            quotas: Default::default(),
+            policies: Default::default(),
+        }
+    }
+}
+
+/// Quotas of transactions in the pool per source, counted across all sub-pools.
+///
+/// Once a sub-pool exceeds its limits, transactions of sources that exceed their quota are evicted
+/// first, from any sub-pool, until all sources are within their quota. Transactions propagated by
+/// a peer that reached its quota are rejected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SourceQuotas {
+    /// Quota of [`TransactionOrigin::Local`] transactions.
+    pub local: Option<SubPoolLimit>,
+    /// Quota of [`TransactionOrigin::External`] transactions.
+    pub external: Option<SubPoolLimit>,
+    /// Quota of [`TransactionOrigin::Private`] transactions.
+    pub private: Option<SubPoolLimit>,
+    /// Quota of the transactions propagated by a single peer.
+    pub peer: Option<SubPoolLimit>,
+}
+
+impl SourceQuotas {
+    /// Returns the quota of the given origin.
+    pub const fn origin(&self, origin: TransactionOrigin) -> Option<SubPoolLimit> {
+        match origin {
+            TransactionOrigin::Local => self.local,
+            TransactionOrigin::External => self.external,
+            TransactionOrigin::Private => self.private,
         }
     }
+
+    /// Returns `true` if no quota is configured.
+    pub const fn is_empty(&self) -> bool {
+        self.local.is_none() &&
+            self.external.is_none() &&
+            self.private.is_none() &&
+            self.peer.is_none()
+    }
 }
 
 /// Size limits for a sub-pool.
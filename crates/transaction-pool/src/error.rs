@@ -1,6 +1,7 @@
 //! Transaction pool errors
 
 use alloy_primitives::{Address, TxHash, U256};
+use reth_network_peers::PeerId;
 use reth_primitives::{BlobTransactionValidationError, InvalidTransactionError};
 
 /// Transaction pool result type.
@@ -50,6 +51,10 @@ pub enum PoolErrorKind {
     /// Thrown when the number of unique transactions of a sender exceeded the slot capacity.
     #[error("rejected due to {0} being identified as a spammer")]
     SpammerExceededCapacity(Address),
+    /// Thrown when the peer that propagated the transaction reached its quota of transactions in
+    /// the pool.
+    #[error("rejected due to peer {0} exceeding its transaction quota")]
+    ExceededPeerQuota(PeerId),
     /// Thrown when a new transaction is added to the pool, but then immediately discarded to
     /// respect the size limits of the pool.
     #[error("transaction discarded outright due to pool size constraints")]
@@ -120,6 +125,10 @@ impl PoolError {
                 // (pool lags behind) and old transaction still occupy a slot in the pool
                 false
             }
+            PoolErrorKind::ExceededPeerQuota(_) => {
+                // the tx itself is fine, the peer is penalized separately for exceeding its quota
+                false
+            }
             PoolErrorKind::DiscardedOnInsert => {
                 // valid tx but dropped due to size constraints
                 false
@@ -179,6 +188,18 @@ pub enum Eip7702PoolTransactionError {
     /// Thrown if the transaction has no items in its authorization list
     #[error("no items in authorization list for EIP7702 transaction")]
     MissingEip7702AuthorizationList,
+    /// Thrown if a transaction of a delegated account has a nonce gap.
+    ///
+    /// Delegated accounts can be drained by their delegation at any time, so only gapless
+    /// transactions are accepted for them.
+    #[error("gapped-nonce transaction from delegated account")]
+    OutOfOrderTxFromDelegated,
+    /// Thrown if a delegated account already has the maximum number of in-flight transactions.
+    #[error("in-flight transaction limit reached for delegated account")]
+    InflightTxLimitReached,
+    /// Thrown if an authority of the EIP-7702 transaction already has transactions in the pool.
+    #[error("authority {0} already has transactions in the pool")]
+    AuthorityReserved(Address),
 }
 
 /// Represents errors that can happen when validating transactions for the pool
@@ -312,6 +333,12 @@ impl InvalidPoolTransactionError {
             }
             Self::Eip7702(eip7702_err) => match eip7702_err {
                 Eip7702PoolTransactionError::MissingEip7702AuthorizationList => false,
+                Eip7702PoolTransactionError::OutOfOrderTxFromDelegated |
+                Eip7702PoolTransactionError::InflightTxLimitReached |
+                Eip7702PoolTransactionError::AuthorityReserved(_) => {
+                    // these depend on the current state of the pool
+                    false
+                }
             },
         }
     }
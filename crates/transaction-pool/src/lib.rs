@@ -150,12 +150,13 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
-use crate::{identifier::TransactionId, pool::PoolInner};
+use crate::{identifier::TransactionId, pool::PoolInner, simulation::SimulationCache};
 use alloy_eips::eip4844::BlobAndProofV1;
-use alloy_primitives::{Address, TxHash, B256, U256};
+use alloy_primitives::{Address, BlockNumber, TxHash, B256, U256};
 use aquamarine as _;
 use reth_eth_wire_types::HandleMempoolData;
 use reth_execution_types::ChangedAccount;
+use reth_network_peers::PeerId;
 use reth_primitives::{BlobTransactionSidecar, PooledTransactionsElement};
 use reth_storage_api::StateProviderFactory;
 use std::{collections::HashSet, sync::Arc};
@@ -165,16 +166,20 @@ use tracing::{instrument, trace};
 pub use crate::{
     blobstore::{BlobStore, BlobStoreError},
     config::{
-        LocalTransactionConfig, PoolConfig, PriceBumpConfig, SubPoolLimit, DEFAULT_PRICE_BUMP,
-        DEFAULT_TXPOOL_ADDITIONAL_VALIDATION_TASKS, REPLACE_BLOB_PRICE_BUMP,
-        TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER, TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT,
+        LocalTransactionConfig, PoolConfig, PriceBumpConfig, SourceQuotas, SubPoolLimit,
+        DEFAULT_PRICE_BUMP, DEFAULT_TXPOOL_ADDITIONAL_VALIDATION_TASKS, REPLACE_BLOB_PRICE_BUMP,
+        TXPOOL_JOURNAL_MAX_TRANSACTIONS_DEFAULT, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
+        TXPOOL_MAX_INFLIGHT_DELEGATED_SLOTS, TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT,
         TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
     },
     error::PoolResult,
     ordering::{CoinbaseTipOrdering, Priority, TransactionOrdering},
     pool::{
-        blob_tx_priority, fee_delta, state::SubPool, AllTransactionsEvents, FullTransactionEvent,
-        TransactionEvent, TransactionEvents,
+        blob_tx_priority, conditional_transactions_filter, fee_delta, state::SubPool,
+        AccountStorage, AgeEviction, AllTransactionsEvents, DropReason, EffectiveTipEviction,
+        EvictionCandidate, EvictionStrategy, FairnessEviction, FullTransactionEvent, JournalEntry,
+        JournalEvent, ParkedReason, SenderQuotaEviction, TransactionConditional, TransactionEvent,
+        TransactionEvents, TransactionHistory, MAX_TRANSACTION_CONDITIONAL_COST,
     },
     traits::*,
     validate::{
@@ -188,6 +193,7 @@ pub mod maintain;
 pub mod metrics;
 pub mod noop;
 pub mod pool;
+pub mod simulation;
 pub mod validate;
 
 pub mod blobstore;
@@ -370,6 +376,34 @@ where
         self.pool.add_transactions(origin, validated.into_iter().map(|(_, tx)| tx))
     }
 
+    async fn add_peer_transactions(
+        &self,
+        peer_id: PeerId,
+        transactions: Vec<Self::Transaction>,
+    ) -> Vec<PoolResult<TxHash>> {
+        if transactions.is_empty() {
+            return Vec::new()
+        }
+        let origin = TransactionOrigin::External;
+        let validated = self.validate_all(origin, transactions).await;
+
+        self.pool.add_transactions_with_peer(
+            origin,
+            Some(peer_id),
+            validated.into_iter().map(|(_, tx)| tx),
+        )
+    }
+
+    async fn add_conditional_transaction(
+        &self,
+        origin: TransactionOrigin,
+        transaction: Self::Transaction,
+        conditional: TransactionConditional,
+    ) -> PoolResult<TxHash> {
+        let (_, tx) = self.validate(origin, transaction).await;
+        self.pool.add_conditional_transaction(origin, tx, conditional)
+    }
+
     fn transaction_event_listener(&self, tx_hash: TxHash) -> Option<TransactionEvents> {
         self.pool.add_transaction_event_listener(tx_hash)
     }
@@ -463,6 +497,17 @@ where
         self.pool.remove_transactions(hashes)
     }
 
+    fn allow_propagation(
+        &self,
+        hash: TxHash,
+    ) -> Option<Arc<ValidPoolTransaction<Self::Transaction>>> {
+        self.pool.allow_propagation(hash)
+    }
+
+    fn conditional_transactions(&self) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>> {
+        self.pool.conditional_transactions()
+    }
+
     fn retain_unknown<A>(&self, announcement: &mut A)
     where
         A: HandleMempoolData,
@@ -496,6 +541,18 @@ where
         self.pool.get_highest_transaction_by_sender(sender)
     }
 
+    fn transaction_history(&self, tx_hash: TxHash) -> Option<TransactionHistory> {
+        self.pool.get_pool_data().journal().history(&tx_hash)
+    }
+
+    fn transaction_histories_by_sender(&self, sender: Address) -> Vec<TransactionHistory> {
+        self.pool.get_pool_data().journal().histories_by_sender(&sender)
+    }
+
+    fn simulations(&self) -> SimulationCache {
+        self.pool.simulations().clone()
+    }
+
     fn get_transaction_by_sender_and_nonce(
         &self,
         sender: Address,
@@ -572,6 +629,13 @@ where
         self.pool.update_accounts(accounts);
     }
 
+    fn remove_failed_conditional_transactions(
+        &self,
+        hashes: Vec<TxHash>,
+    ) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>> {
+        self.pool.remove_failed_conditional_transactions(hashes)
+    }
+
     fn delete_blob(&self, tx: TxHash) {
         self.pool.delete_blob(tx)
     }
@@ -580,6 +644,17 @@ where
         self.pool.delete_blobs(txs)
     }
 
+    fn keep_blobs(&self, txs: Vec<TxHash>) {
+        self.pool.keep_blobs(txs)
+    }
+
+    fn archive_finalized_blobs(
+        &self,
+        blocks: Vec<(BlockNumber, Vec<TxHash>)>,
+    ) -> Result<(), BlobStoreError> {
+        self.pool.archive_finalized_blobs(blocks)
+    }
+
     fn cleanup_blobs(&self) {
         self.pool.cleanup_blobs()
     }
@@ -1,24 +1,25 @@
 //! Support for maintaining the state of the transaction pool
 
 use crate::{
-    blobstore::{BlobStoreCanonTracker, BlobStoreUpdates},
+    blobstore::BlobStoreCanonTracker,
     error::PoolError,
     metrics::MaintainPoolMetrics,
     traits::{CanonicalStateUpdate, TransactionPool, TransactionPoolExt},
-    BlockInfo, PoolTransaction,
+    BlockInfo, PoolTransaction, TransactionOrigin,
 };
-use alloy_primitives::{Address, BlockHash, BlockNumber, Sealable};
+use alloy_primitives::{Address, BlockHash, BlockNumber, Sealable, TxHash};
+use alloy_rlp::{Decodable, Encodable, Header};
 use futures_util::{
     future::{BoxFuture, Fuse, FusedFuture},
     FutureExt, Stream, StreamExt,
 };
 use reth_chain_state::CanonStateNotification;
 use reth_chainspec::{ChainSpecProvider, EthChainSpec};
-use reth_execution_types::ChangedAccount;
+use reth_execution_types::{ChainBlocks, ChangedAccount};
 use reth_fs_util::FsPathError;
 use reth_primitives::{
-    BlockNumberOrTag, PooledTransactionsElementEcRecovered, SealedHeader, TransactionSigned,
-    TransactionSignedEcRecovered,
+    BlockNumberOrTag, PooledTransactionsElement, PooledTransactionsElementEcRecovered,
+    SealedHeader, TransactionSigned,
 };
 use reth_storage_api::{errors::provider::ProviderError, BlockReaderIdExt, StateProviderFactory};
 use reth_tasks::TaskSpawner;
@@ -26,9 +27,11 @@ use std::{
     borrow::Borrow,
     collections::HashSet,
     hash::{Hash, Hasher},
+    io::Write,
     path::{Path, PathBuf},
+    time::Duration,
 };
-use tokio::sync::oneshot;
+use tokio::sync::{mpsc, oneshot};
 use tracing::{debug, error, info, trace, warn};
 
 /// Additional settings for maintaining the transaction pool
@@ -51,17 +54,64 @@ impl Default for MaintainPoolConfig {
     }
 }
 
+/// Default maximum size of the transactions backup file: 256 MB.
+pub const DEFAULT_TRANSACTIONS_BACKUP_MAX_SIZE: usize = 256 * 1024 * 1024;
+
 /// Settings for local transaction backup task
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct LocalTransactionBackupConfig {
     /// Path to transactions backup file
     pub transactions_path: Option<PathBuf>,
+    /// Whether to back up all transactions of the pool, instead of only local ones.
+    ///
+    /// This covers every sub-pool, and blob transactions are saved with their sidecars.
+    pub all_transactions: bool,
+    /// Interval at which the backup is written while the node is running. If not set, the backup
+    /// is only written on shutdown.
+    pub interval: Option<Duration>,
+    /// Maximum size of the backup in bytes. Local transactions are saved first, then pending
+    /// ones, and the rest is dropped once the limit is reached.
+    pub max_size: usize,
 }
 
 impl LocalTransactionBackupConfig {
     /// Receive path to transactions backup and return initialized config
     pub const fn with_local_txs_backup(transactions_path: PathBuf) -> Self {
-        Self { transactions_path: Some(transactions_path) }
+        Self {
+            transactions_path: Some(transactions_path),
+            all_transactions: false,
+            interval: None,
+            max_size: DEFAULT_TRANSACTIONS_BACKUP_MAX_SIZE,
+        }
+    }
+
+    /// Sets whether to back up all transactions of the pool, instead of only local ones.
+    pub const fn with_all_transactions(mut self, all_transactions: bool) -> Self {
+        self.all_transactions = all_transactions;
+        self
+    }
+
+    /// Sets the interval at which the backup is written while the node is running.
+    pub const fn with_interval(mut self, interval: Option<Duration>) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Sets the maximum size of the backup in bytes.
+    pub const fn with_max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+}
+
+impl Default for LocalTransactionBackupConfig {
+    fn default() -> Self {
+        Self {
+            transactions_path: None,
+            all_transactions: false,
+            interval: None,
+            max_size: DEFAULT_TRANSACTIONS_BACKUP_MAX_SIZE,
+        }
     }
 }
 
@@ -124,8 +174,28 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
     let mut blob_store_tracker = BlobStoreCanonTracker::default();
 
     // keeps track of the latest finalized block
-    let mut last_finalized_block =
-        FinalizedBlockTracker::new(client.finalized_block_number().ok().flatten());
+    let finalized_block = client.finalized_block_number().ok().flatten();
+    let mut last_finalized_block = FinalizedBlockTracker::new(finalized_block);
+
+    // blobs of blocks that were mined before a restart are still kept until the blocks are
+    // finalized
+    if let Some(finalized) = finalized_block {
+        let tip = pool.block_info().last_seen_block_number;
+        let blocks = (finalized + 1..=tip).map_while(|number| {
+            let block = client.block_by_number(number).ok().flatten()?;
+            let blob_txs = block
+                .body
+                .transactions()
+                .filter(|tx| tx.transaction.is_eip4844())
+                .map(|tx| tx.hash)
+                .collect::<Vec<_>>();
+            Some((number, blob_txs))
+        });
+        track_blob_transactions(&mut blob_store_tracker, &pool, blocks);
+    }
+
+    // finalized blocks whose blobs failed to be archived, retried on the next finalized block
+    let (archive_retry_tx, mut archive_retry_rx) = mpsc::unbounded_channel();
 
     // keeps track of any dirty accounts that we know of are out of sync with the pool
     let mut dirty_addresses = HashSet::default();
@@ -186,19 +256,33 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
             task_spawner.spawn_blocking(fut);
         }
 
+        while let Ok(blocks) = archive_retry_rx.try_recv() {
+            blob_store_tracker.add_blocks(blocks);
+        }
+
         // check if we have a new finalized block
         if let Some(finalized) =
             last_finalized_block.update(client.finalized_block_number().ok().flatten())
         {
-            if let BlobStoreUpdates::Finalized(blobs) =
-                blob_store_tracker.on_finalized_block(finalized)
-            {
+            let finalized_blocks = blob_store_tracker.take_finalized_blocks(finalized);
+            if !finalized_blocks.is_empty() {
+                let blobs = finalized_blocks
+                    .iter()
+                    .flat_map(|(_, blob_txs)| blob_txs.iter().copied())
+                    .collect::<Vec<_>>();
                 metrics.inc_deleted_tracked_blobs(blobs.len());
-                // remove all finalized blobs from the blob store
-                pool.delete_blobs(blobs);
-                // and also do periodic cleanup
                 let pool = pool.clone();
+                let archive_retry_tx = archive_retry_tx.clone();
                 task_spawner.spawn_blocking(Box::pin(async move {
+                    // keep the finalized blobs if the blob store is configured to, they stay in
+                    // the blob store until they are archived
+                    if pool.archive_finalized_blobs(finalized_blocks.clone()).is_err() {
+                        let _ = archive_retry_tx.send(finalized_blocks);
+                        return
+                    }
+                    // remove all finalized blobs from the blob store
+                    pool.delete_blobs(blobs);
+                    // and also do periodic cleanup
                     debug!(target: "txpool", finalized_block = %finalized, "cleaning up blob store");
                     pool.cleanup_blobs();
                 }));
@@ -343,6 +427,8 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
                     })
                     .collect::<Vec<_>>();
 
+                let delegated_accounts = delegated_accounts(new_blocks.transactions());
+
                 // update the pool first
                 let update = CanonicalStateUpdate {
                     new_tip: &new_tip.block,
@@ -351,9 +437,13 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
                     changed_accounts,
                     // all transactions mined in the new chain need to be removed from the pool
                     mined_transactions: new_blocks.transaction_hashes().collect(),
+                    delegated_accounts,
                 };
                 pool.on_canonical_state_change(update);
 
+                // re-check the conditions of conditional transactions on top of the new tip
+                spawn_conditional_transactions_check(&client, &pool, &task_spawner, new_tip);
+
                 // all transactions that were mined in the old chain but not in the new chain need
                 // to be re-injected
                 //
@@ -364,7 +454,7 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
                 let _ = pool.add_external_transactions(pruned_old_transactions).await;
 
                 // keep track of new mined blob transactions
-                blob_store_tracker.add_new_chain_blocks(&new_blocks);
+                track_new_chain_blob_transactions(&mut blob_store_tracker, &pool, &new_blocks);
             }
             CanonStateNotification::Commit { new } => {
                 let (blocks, state) = new.inner();
@@ -404,7 +494,7 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
                     pool.set_block_info(info);
 
                     // keep track of mined blob transactions
-                    blob_store_tracker.add_new_chain_blocks(&blocks);
+                    track_new_chain_blob_transactions(&mut blob_store_tracker, &pool, &blocks);
 
                     continue
                 }
@@ -426,6 +516,8 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
                     maintained_state = MaintainedPoolState::Drifted;
                 }
 
+                let delegated_accounts = delegated_accounts(blocks.transactions());
+
                 // Canonical update
                 let update = CanonicalStateUpdate {
                     new_tip: &tip.block,
@@ -433,16 +525,55 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
                     pending_block_blob_fee,
                     changed_accounts,
                     mined_transactions,
+                    delegated_accounts,
                 };
                 pool.on_canonical_state_change(update);
 
+                // re-check the conditions of conditional transactions on top of the new tip
+                spawn_conditional_transactions_check(&client, &pool, &task_spawner, tip);
+
                 // keep track of mined blob transactions
-                blob_store_tracker.add_new_chain_blocks(&blocks);
+                track_new_chain_blob_transactions(&mut blob_store_tracker, &pool, &blocks);
             }
         }
     }
 }
 
+/// Tracks the blob transactions of the given blocks until the blocks are finalized, and keeps
+/// their blobs in the blob store until then.
+fn track_blob_transactions<P: TransactionPoolExt>(
+    tracker: &mut BlobStoreCanonTracker,
+    pool: &P,
+    blocks: impl IntoIterator<Item = (BlockNumber, Vec<TxHash>)>,
+) {
+    let mut mined = Vec::new();
+    for (block_number, blob_txs) in blocks {
+        mined.extend_from_slice(&blob_txs);
+        tracker.add_block(block_number, blob_txs);
+    }
+    if !mined.is_empty() {
+        pool.keep_blobs(mined);
+    }
+}
+
+/// Like [`track_blob_transactions`], for the blocks of a new canonical chain.
+fn track_new_chain_blob_transactions<P: TransactionPoolExt>(
+    tracker: &mut BlobStoreCanonTracker,
+    pool: &P,
+    blocks: &ChainBlocks<'_>,
+) {
+    let blocks = blocks.iter().map(|(number, block)| {
+        let blob_txs = block
+            .body
+            .transactions()
+            .filter(|tx| tx.transaction.is_eip4844())
+            .map(|tx| tx.hash)
+            .collect();
+        (*number, blob_txs)
+    });
+    track_blob_transactions(tracker, pool, blocks);
+}
+
 struct FinalizedBlockTracker {
     last_finalized_block: Option<BlockNumber>,
 }
@@ -490,6 +621,67 @@ impl MaintainedPoolState {
     }
 }
 
+/// Returns the recovered authorities of all EIP-7702 authorizations of the given transactions.
+///
+/// Note: this doesn't check whether an authorization was actually applied.
+fn delegated_accounts<'a>(
+    transactions: impl Iterator<Item = &'a TransactionSigned>,
+) -> Vec<Address> {
+    transactions
+        .filter_map(|tx| tx.authorization_list())
+        .flatten()
+        .filter_map(|auth| auth.recover_authority().ok())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Spawns a blocking task that removes all conditional transactions from the pool whose conditions
+/// can no longer be met on top of the given tip.
+fn spawn_conditional_transactions_check<Client, P, Tasks>(
+    client: &Client,
+    pool: &P,
+    task_spawner: &Tasks,
+    tip: &SealedHeader,
+) where
+    Client: StateProviderFactory + Clone + Send + 'static,
+    P: TransactionPoolExt + 'static,
+    Tasks: TaskSpawner,
+{
+    let transactions = pool.conditional_transactions();
+    if transactions.is_empty() {
+        return
+    }
+
+    let (client, pool) = (client.clone(), pool.clone());
+    let (hash, number, timestamp) = (tip.hash(), tip.number, tip.timestamp);
+    task_spawner.spawn_blocking(Box::pin(async move {
+        let state = match client.history_by_block_hash(hash) {
+            Ok(state) => state,
+            Err(err) => {
+                debug!(target: "txpool", %err, "failed to load state for conditional transactions");
+                return
+            }
+        };
+
+        let failed = transactions
+            .into_iter()
+            .filter(|tx| {
+                let Some(conditional) = &tx.conditional else { return false };
+                // transactions are only removed if their conditions are known to fail
+                conditional.has_exceeded_block_attributes(number, timestamp) ||
+                    !conditional.matches_state(&*state).unwrap_or(true)
+            })
+            .map(|tx| *tx.hash())
+            .collect::<Vec<_>>();
+
+        if !failed.is_empty() {
+            debug!(target: "txpool", count = failed.len(), "removing failed conditional transactions");
+            pool.remove_failed_conditional_transactions(failed);
+        }
+    }));
+}
+
 /// A unique `ChangedAccount` identified by its address that can be used for deduplication
 #[derive(Eq)]
 struct ChangedAccountEntry(ChangedAccount);
@@ -555,12 +747,99 @@ where
     Ok(res)
 }
 
+/// Transactions saved by the backup task, grouped by their [`TransactionOrigin`].
+///
+/// Transactions are stored in their network encoding, so blob transactions include their sidecar.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct TransactionsBackup {
+    local: Vec<PooledTransactionsElement>,
+    private: Vec<PooledTransactionsElement>,
+    external: Vec<PooledTransactionsElement>,
+}
+
+impl TransactionsBackup {
+    fn payload_length(&self) -> usize {
+        self.local.length() + self.private.length() + self.external.length()
+    }
+
+    fn len(&self) -> usize {
+        self.local.len() + self.private.len() + self.external.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Decodes the backup, falling back to the format of previous versions, which only contained
+    /// a list of local transactions.
+    fn decode_compat(data: &[u8]) -> alloy_rlp::Result<Self> {
+        if let Ok(backup) = Self::decode(&mut &data[..]) {
+            return Ok(backup)
+        }
+
+        let local: Vec<TransactionSigned> = Decodable::decode(&mut &data[..])?;
+        Ok(Self {
+            local: local
+                .into_iter()
+                .filter_map(|tx| PooledTransactionsElement::try_from(tx).ok())
+                .collect(),
+            ..Default::default()
+        })
+    }
+}
+
+impl Encodable for TransactionsBackup {
+    fn encode(&self, out: &mut dyn alloy_rlp::BufMut) {
+        Header { list: true, payload_length: self.payload_length() }.encode(out);
+        self.local.encode(out);
+        self.private.encode(out);
+        self.external.encode(out);
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = self.payload_length();
+        payload_length + alloy_rlp::length_of_length(payload_length)
+    }
+}
+
+impl Decodable for TransactionsBackup {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let header = Header::decode(buf)?;
+        if !header.list {
+            return Err(alloy_rlp::Error::UnexpectedString)
+        }
+
+        let started_len = buf.len();
+        let backup = Self {
+            local: Decodable::decode(buf)?,
+            private: Decodable::decode(buf)?,
+            external: Decodable::decode(buf)?,
+        };
+
+        let consumed = started_len - buf.len();
+        if consumed != header.payload_length {
+            return Err(alloy_rlp::Error::ListLengthMismatch {
+                expected: header.payload_length,
+                got: consumed,
+            })
+        }
+
+        Ok(backup)
+    }
+}
+
 /// Loads transactions from a file, decodes them from the RLP format, and inserts them
 /// into the transaction pool on node boot up.
-/// The file is removed after the transactions have been successfully processed.
+///
+/// Transactions are validated again against the current state, so the ones that were mined or
+/// became invalid in the meantime are discarded.
+///
+/// If `remove_file` is set, the file is removed after the transactions have been successfully
+/// processed.
 async fn load_and_reinsert_transactions<P>(
     pool: P,
     file_path: &Path,
+    remove_file: bool,
 ) -> Result<(), TransactionsBackupError>
 where
     P: TransactionPool,
@@ -576,55 +855,120 @@ where
         return Ok(())
     }
 
-    let txs_signed: Vec<TransactionSigned> = alloy_rlp::Decodable::decode(&mut data.as_slice())?;
-
-    let pool_transactions = txs_signed
-        .into_iter()
-        .filter_map(|tx| tx.try_ecrecovered())
-        .filter_map(|tx| {
-            // Filter out errors
-            <P::Transaction as PoolTransaction>::try_from_consensus(tx.into()).ok()
-        })
-        .collect::<Vec<_>>();
-
-    let outcome = pool.add_transactions(crate::TransactionOrigin::Local, pool_transactions).await;
+    let TransactionsBackup { local, private, external } = TransactionsBackup::decode_compat(&data)?;
+
+    let (mut num_txs, mut num_valid_txs) = (0, 0);
+    for (origin, transactions) in [
+        (TransactionOrigin::Local, local),
+        (TransactionOrigin::Private, private),
+        (TransactionOrigin::External, external),
+    ] {
+        let pool_transactions = transactions
+            .into_iter()
+            .filter_map(|tx| tx.try_into_ecrecovered().ok())
+            .map(|tx| <P::Transaction as PoolTransaction>::from_pooled(tx.into()))
+            .collect::<Vec<_>>();
+
+        let outcome = pool.add_transactions(origin, pool_transactions).await;
+        num_txs += outcome.len();
+        num_valid_txs += outcome.iter().filter(|res| res.is_ok()).count();
+    }
 
-    info!(target: "txpool", txs_file =?file_path, num_txs=%outcome.len(), "Successfully reinserted local transactions from file");
-    reth_fs_util::remove_file(file_path)?;
+    info!(target: "txpool", txs_file =?file_path, %num_txs, %num_valid_txs, "Successfully reinserted transactions from file");
+    if remove_file {
+        reth_fs_util::remove_file(file_path)?;
+    }
     Ok(())
 }
 
-fn save_local_txs_backup<P>(pool: P, file_path: &Path)
+/// Collects the transactions of the pool to back up, up to `max_size` bytes.
+///
+/// Local transactions come first, then private and external ones. Within each origin, pending
+/// transactions come first, ordered by sender and nonce.
+///
+/// Conditional transactions are skipped: their conditions aren't persisted, so they would be
+/// reinserted as unconditional transactions.
+fn collect_transactions_backup<P>(
+    pool: &P,
+    all_transactions: bool,
+    max_size: usize,
+) -> TransactionsBackup
 where
     P: TransactionPool,
 {
-    let local_transactions = pool.get_local_transactions();
-    if local_transactions.is_empty() {
-        trace!(target: "txpool", "no local transactions to save");
-        return
+    let pending = if all_transactions {
+        pool.pending_transactions().iter().map(|tx| *tx.hash()).collect()
+    } else {
+        HashSet::new()
+    };
+
+    let mut backup = TransactionsBackup::default();
+    let mut size = 0;
+    for origin in
+        [TransactionOrigin::Local, TransactionOrigin::Private, TransactionOrigin::External]
+    {
+        if !all_transactions && !origin.is_local() {
+            break
+        }
+
+        let mut transactions = pool.get_transactions_by_origin(origin);
+        transactions.retain(|tx| tx.conditional.is_none());
+        transactions.sort_by_key(|tx| (!pending.contains(tx.hash()), tx.sender(), tx.nonce()));
+
+        let saved = match origin {
+            TransactionOrigin::Local => &mut backup.local,
+            TransactionOrigin::Private => &mut backup.private,
+            TransactionOrigin::External => &mut backup.external,
+        };
+        for tx in transactions {
+            // Includes the blob sidecar of blob transactions.
+            let Some(element) = pool.get_pooled_transaction_element(*tx.hash()) else { continue };
+
+            size += element.length();
+            if size > max_size {
+                debug!(target: "txpool", %max_size, "Transactions backup size limit reached");
+                return backup
+            }
+            saved.push(element);
+        }
     }
 
-    let local_transactions = local_transactions
-        .into_iter()
-        .map(|tx| {
-            let recovered: TransactionSignedEcRecovered =
-                tx.transaction.clone().into_consensus().into();
-            recovered.into_signed()
-        })
-        .collect::<Vec<_>>();
+    backup
+}
 
-    let num_txs = local_transactions.len();
-    let mut buf = Vec::new();
-    alloy_rlp::encode_list(&local_transactions, &mut buf);
-    info!(target: "txpool", txs_file =?file_path, num_txs=%num_txs, "Saving current local transactions");
-    let parent_dir = file_path.parent().map(std::fs::create_dir_all).transpose();
+fn save_local_txs_backup<P>(pool: &P, config: &LocalTransactionBackupConfig, file_path: &Path)
+where
+    P: TransactionPool,
+{
+    let backup = collect_transactions_backup(pool, config.all_transactions, config.max_size);
+    if backup.is_empty() {
+        trace!(target: "txpool", "no transactions to save");
+        // Make sure that a previous backup isn't reinserted.
+        if file_path.exists() {
+            if let Err(err) = reth_fs_util::remove_file(file_path) {
+                warn!(target: "txpool", %err, txs_file=?file_path, "Failed to remove transactions backup file");
+            }
+        }
+        return
+    }
 
-    match parent_dir.map(|_| reth_fs_util::write(file_path, buf)) {
+    let num_txs = backup.len();
+    let mut buf = Vec::with_capacity(backup.length());
+    backup.encode(&mut buf);
+    info!(target: "txpool", txs_file =?file_path, num_txs=%num_txs, "Saving current transactions");
+
+    // The backup is written to a temporary file first, so a crash never leaves a partial backup.
+    let res = file_path
+        .parent()
+        .map(reth_fs_util::create_dir_all)
+        .transpose()
+        .and_then(|_| reth_fs_util::atomic_write_file(file_path, |file| file.write_all(&buf)));
+    match res {
         Ok(_) => {
-            info!(target: "txpool", txs_file=?file_path, "Wrote local transactions to file");
+            info!(target: "txpool", txs_file=?file_path, "Wrote transactions to file");
         }
         Err(err) => {
-            warn!(target: "txpool", %err, txs_file=?file_path, "Failed to write local transactions to file");
+            warn!(target: "txpool", %err, txs_file=?file_path, "Failed to write transactions to file");
         }
     }
 }
@@ -645,26 +989,58 @@ pub enum TransactionsBackupError {
 
 /// Task which manages saving local transactions to the persistent file in case of shutdown.
 /// Reloads the transactions from the file on the boot up and inserts them into the pool.
-pub async fn backup_local_transactions_task<P>(
+///
+/// Depending on the [`LocalTransactionBackupConfig`], all transactions of the pool are saved, and
+/// the file is also written periodically while the node is running.
+pub async fn backup_local_transactions_task<P, Tasks>(
     shutdown: reth_tasks::shutdown::GracefulShutdown,
     pool: P,
     config: LocalTransactionBackupConfig,
+    task_spawner: Tasks,
 ) where
-    P: TransactionPool + Clone,
+    P: TransactionPool + Clone + 'static,
+    Tasks: TaskSpawner,
 {
-    let Some(transactions_path) = config.transactions_path else {
+    let Some(transactions_path) = config.transactions_path.clone() else {
         // nothing to do
         return
     };
 
-    if let Err(err) = load_and_reinsert_transactions(pool.clone(), &transactions_path).await {
+    // With periodic backups, the file is kept until it's overwritten, so that the transactions
+    // survive a crash right after boot up.
+    let remove_file = config.interval.is_none();
+    if let Err(err) =
+        load_and_reinsert_transactions(pool.clone(), &transactions_path, remove_file).await
+    {
         error!(target: "txpool", "{}", err)
     }
 
-    let graceful_guard = shutdown.await;
+    let graceful_guard = match config.interval {
+        Some(period) => {
+            let mut shutdown = std::pin::pin!(shutdown);
+            let mut interval =
+                tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+            loop {
+                tokio::select! {
+                    guard = &mut shutdown => break guard,
+                    _ = interval.tick() => {
+                        // Collecting and encoding the transactions is blocking.
+                        let (pool, config, path) =
+                            (pool.clone(), config.clone(), transactions_path.clone());
+                        let _ = task_spawner
+                            .spawn_blocking(Box::pin(async move {
+                                save_local_txs_backup(&pool, &config, &path);
+                            }))
+                            .await;
+                    }
+                }
+            }
+        }
+        None => shutdown.await,
+    };
 
     // write transactions to disk
-    save_local_txs_backup(pool, &transactions_path);
+    save_local_txs_backup(&pool, &config, &transactions_path);
 
     drop(graceful_guard)
 }
@@ -673,14 +1049,15 @@ pub async fn backup_local_transactions_task<P>(
 mod tests {
     use super::*;
     use crate::{
-        blobstore::InMemoryBlobStore, validate::EthTransactionValidatorBuilder,
+        blobstore::InMemoryBlobStore,
+        pool::TransactionConditional,
+        test_utils::signed_eth_transaction,
+        validate::{EthTransactionValidator, EthTransactionValidatorBuilder},
         CoinbaseTipOrdering, EthPooledTransaction, Pool, TransactionOrigin,
     };
-    use alloy_eips::eip2718::Decodable2718;
-    use alloy_primitives::{hex, U256};
+    use alloy_primitives::U256;
     use reth_chainspec::MAINNET;
     use reth_fs_util as fs;
-    use reth_primitives::PooledTransactionsElement;
     use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
     use reth_tasks::TaskManager;
 
@@ -695,35 +1072,39 @@ mod tests {
     const EXTENSION: &str = "rlp";
     const FILENAME: &str = "test_transactions_backup";
 
-    #[tokio::test(flavor = "multi_thread")]
-    async fn test_save_local_txs_backup() {
-        let temp_dir = tempfile::tempdir().unwrap();
-        let transactions_path = temp_dir.path().join(FILENAME).with_extension(EXTENSION);
-        let tx_bytes = hex!("02f87201830655c2808505ef61f08482565f94388c818ca8b9251b393131c08a736a67ccb192978801049e39c4b5b1f580c001a01764ace353514e8abdfb92446de356b260e3c1225b73fc4c8876a6258d12a129a04f02294aa61ca7676061cd99f29275491218b4754b46a0248e5e42bc5091f507");
-        let tx = PooledTransactionsElement::decode_2718(&mut &tx_bytes[..]).unwrap();
+    type TestEthPool = Pool<
+        EthTransactionValidator<MockEthProvider, EthPooledTransaction>,
+        CoinbaseTipOrdering<EthPooledTransaction>,
+        InMemoryBlobStore,
+    >;
+
+    /// Returns a new pool in which the sender of [`signed_eth_transaction`] is funded.
+    fn funded_pool() -> TestEthPool {
+        let (_, sender) = signed_eth_transaction();
         let provider = MockEthProvider::default();
-        let transaction: EthPooledTransaction = tx.try_into_ecrecovered().unwrap().into();
-        let tx_to_cmp = transaction.clone();
-        let sender = hex!("1f9090aaE28b8a3dCeaDf281B0F12828e676c326").into();
         provider.add_account(sender, ExtendedAccount::new(42, U256::MAX));
         let blob_store = InMemoryBlobStore::default();
         let validator = EthTransactionValidatorBuilder::new(MAINNET.clone())
             .build(provider, blob_store.clone());
+        Pool::new(validator, CoinbaseTipOrdering::default(), blob_store, Default::default())
+    }
 
-        let txpool = Pool::new(
-            validator.clone(),
-            CoinbaseTipOrdering::default(),
-            blob_store.clone(),
-            Default::default(),
-        );
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_save_local_txs_backup() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let transactions_path = temp_dir.path().join(FILENAME).with_extension(EXTENSION);
+        let (transaction, _) = signed_eth_transaction();
+        let tx_to_cmp = transaction.clone();
+        let txpool = funded_pool();
 
         txpool.add_transaction(TransactionOrigin::Local, transaction.clone()).await.unwrap();
 
         let handle = tokio::runtime::Handle::current();
         let manager = TaskManager::new(handle);
         let config = LocalTransactionBackupConfig::with_local_txs_backup(transactions_path.clone());
-        manager.executor().spawn_critical_with_graceful_shutdown_signal("test task", |shutdown| {
-            backup_local_transactions_task(shutdown, txpool.clone(), config)
+        let executor = manager.executor();
+        executor.clone().spawn_critical_with_graceful_shutdown_signal("test task", |shutdown| {
+            backup_local_transactions_task(shutdown, txpool.clone(), config, executor)
         });
 
         let mut txns = txpool.get_local_transactions();
@@ -736,13 +1117,93 @@ mod tests {
 
         let data = fs::read(transactions_path).unwrap();
 
-        let txs: Vec<TransactionSigned> =
-            alloy_rlp::Decodable::decode(&mut data.as_slice()).unwrap();
+        let backup = TransactionsBackup::decode(&mut data.as_slice()).unwrap();
+        assert_eq!(backup.local.len(), 1);
+        assert!(backup.external.is_empty());
+
+        temp_dir.close().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_reinsert_all_txs_backup() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let transactions_path = temp_dir.path().join(FILENAME).with_extension(EXTENSION);
+        let (transaction, _) = signed_eth_transaction();
+
+        let txpool = funded_pool();
+        txpool.add_transaction(TransactionOrigin::External, transaction.clone()).await.unwrap();
+
+        let config = LocalTransactionBackupConfig::with_local_txs_backup(transactions_path.clone())
+            .with_all_transactions(true);
+
+        // external transactions are only saved if all transactions are backed up
+        let backup = collect_transactions_backup(&txpool, false, config.max_size);
+        assert!(backup.is_empty());
+
+        // the size limit is respected
+        let backup = collect_transactions_backup(&txpool, true, 0);
+        assert!(backup.is_empty());
+
+        save_local_txs_backup(&txpool, &config, &transactions_path);
+        let data = fs::read(&transactions_path).unwrap();
+        let backup = TransactionsBackup::decode(&mut data.as_slice()).unwrap();
+        assert_eq!(backup.external.len(), 1);
+        assert!(backup.local.is_empty());
+
+        let txpool = funded_pool();
+        load_and_reinsert_transactions(txpool.clone(), &transactions_path, true).await.unwrap();
+        let txs = txpool.get_transactions_by_origin(TransactionOrigin::External);
+        assert_eq!(txs.len(), 1);
+        assert_eq!(txs[0].hash(), transaction.hash());
+        assert!(!transactions_path.exists());
+
+        temp_dir.close().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_conditional_txs_not_restored() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let transactions_path = temp_dir.path().join(FILENAME).with_extension(EXTENSION);
+        let (transaction, _) = signed_eth_transaction();
+
+        let txpool = funded_pool();
+        let conditional =
+            TransactionConditional { block_number_max: Some(10), ..Default::default() };
+        txpool
+            .add_conditional_transaction(TransactionOrigin::Local, transaction.clone(), conditional)
+            .await
+            .unwrap();
+
+        // conditional transactions aren't exempt as local transactions
+        let txs = txpool.get_transactions_by_origin(TransactionOrigin::Local);
         assert_eq!(txs.len(), 1);
+        assert!(!txs[0].is_local());
+
+        let config = LocalTransactionBackupConfig::with_local_txs_backup(transactions_path.clone())
+            .with_all_transactions(true);
+        save_local_txs_backup(&txpool, &config, &transactions_path);
+        assert!(!transactions_path.exists());
+
+        // a restart doesn't turn the conditional transaction into an unconditional one
+        let txpool = funded_pool();
+        load_and_reinsert_transactions(txpool.clone(), &transactions_path, true).await.unwrap();
+        assert!(txpool.get_transactions_by_origin(TransactionOrigin::Local).is_empty());
+        assert!(txpool.conditional_transactions().is_empty());
 
         temp_dir.close().unwrap();
     }
 
+    #[test]
+    fn decode_legacy_txs_backup() {
+        let tx = signed_eth_transaction().0.into_consensus().into_signed();
+        let mut buf = Vec::new();
+        alloy_rlp::encode_list(&[tx], &mut buf);
+
+        let backup = TransactionsBackup::decode_compat(&buf).unwrap();
+        assert_eq!(backup.local.len(), 1);
+        assert!(backup.private.is_empty() && backup.external.is_empty());
+    }
+
     #[test]
     fn test_update_with_higher_finalized_block() {
         let mut tracker = FinalizedBlockTracker::new(Some(10));
@@ -123,3 +123,17 @@ pub struct AllTransactionsMetrics {
     /// The current base fee
     pub(crate) base_fee: Gauge,
 }
+
+/// Metrics of a single transaction admission policy
+#[derive(Metrics, Clone)]
+#[metrics(scope = "transaction_pool.policy")]
+pub struct TransactionPolicyMetrics {
+    /// Number of transactions accepted by the policy
+    pub(crate) accepted: Counter,
+    /// Number of transactions rejected by the policy
+    pub(crate) rejected: Counter,
+    /// Number of transactions accepted without propagation by the policy
+    pub(crate) not_propagated: Counter,
+    /// Number of transactions deprioritized by the policy
+    pub(crate) deprioritized: Counter,
+}
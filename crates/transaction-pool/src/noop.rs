@@ -13,8 +13,9 @@ use crate::{
     validate::ValidTransaction,
     AllPoolTransactions, AllTransactionsEvents, BestTransactions, BlockInfo, EthPoolTransaction,
     EthPooledTransaction, NewTransactionEvent, PoolResult, PoolSize, PoolTransaction,
-    PooledTransactionsElement, PropagatedTransactions, TransactionEvents, TransactionOrigin,
-    TransactionPool, TransactionValidationOutcome, TransactionValidator, ValidPoolTransaction,
+    PooledTransactionsElement, PropagatedTransactions, TransactionConditional, TransactionEvents,
+    TransactionHistory, TransactionOrigin, TransactionPool, TransactionValidationOutcome,
+    TransactionValidator, ValidPoolTransaction,
 };
 use alloy_eips::eip4844::BlobAndProofV1;
 use alloy_primitives::{Address, TxHash, B256, U256};
@@ -80,6 +81,16 @@ impl TransactionPool for NoopTransactionPool {
             .collect()
     }
 
+    async fn add_conditional_transaction(
+        &self,
+        _origin: TransactionOrigin,
+        transaction: Self::Transaction,
+        _conditional: TransactionConditional,
+    ) -> PoolResult<TxHash> {
+        let hash = *transaction.hash();
+        Err(PoolError::other(hash, Box::new(NoopInsertError::new(transaction))))
+    }
+
     fn transaction_event_listener(&self, _tx_hash: TxHash) -> Option<TransactionEvents> {
         None
     }
@@ -183,6 +194,17 @@ impl TransactionPool for NoopTransactionPool {
         vec![]
     }
 
+    fn allow_propagation(
+        &self,
+        _hash: TxHash,
+    ) -> Option<Arc<ValidPoolTransaction<Self::Transaction>>> {
+        None
+    }
+
+    fn conditional_transactions(&self) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>> {
+        vec![]
+    }
+
     fn retain_unknown<A>(&self, _announcement: &mut A)
     where
         A: HandleMempoolData,
@@ -213,6 +235,14 @@ impl TransactionPool for NoopTransactionPool {
         None
     }
 
+    fn transaction_history(&self, _tx_hash: TxHash) -> Option<TransactionHistory> {
+        None
+    }
+
+    fn transaction_histories_by_sender(&self, _sender: Address) -> Vec<TransactionHistory> {
+        vec![]
+    }
+
     fn get_transaction_by_sender_and_nonce(
         &self,
         _sender: Address,
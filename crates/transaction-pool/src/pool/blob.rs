@@ -1,6 +1,11 @@
 use super::txpool::PendingFees;
 use crate::{
-    identifier::TransactionId, pool::size::SizeTracker, traits::BestTransactionsAttributes,
+    identifier::{SenderId, TransactionId},
+    pool::{
+        eviction::{EvictionPool, SenderIndex},
+        size::SizeTracker,
+    },
+    traits::BestTransactionsAttributes,
     PoolTransaction, SubPoolLimit, ValidPoolTransaction,
 };
 use std::{
@@ -31,6 +36,8 @@ pub(crate) struct BlobTransactions<T: PoolTransaction> {
     ///
     /// See also [`PoolTransaction::size`].
     size_of: SizeTracker,
+    /// Number and combined size of the transactions of each sender.
+    senders: SenderIndex,
 }
 
 // === impl BlobTransactions ===
@@ -50,6 +57,7 @@ impl<T: PoolTransaction> BlobTransactions<T> {
 
         // keep track of size
         self.size_of += tx.size();
+        self.senders.add(tx.sender_id(), tx.size());
 
         // set transaction, which will also calculate priority based on current pending fees
         let transaction = BlobTransaction::new(tx, submission_id, &self.pending_fees);
@@ -64,6 +72,24 @@ impl<T: PoolTransaction> BlobTransactions<T> {
         id
     }
 
+    /// Returns an iterator over all transactions in the pool
+    pub(crate) fn all(&self) -> impl Iterator<Item = Arc<ValidPoolTransaction<T>>> + '_ {
+        self.by_id.values().map(|tx| tx.transaction.clone())
+    }
+
+    /// Replaces the transaction with the same id by the given one, which must have the same fees.
+    ///
+    /// Returns `false` if the transaction isn't in the pool.
+    pub(crate) fn replace_in_place(&mut self, tx: Arc<ValidPoolTransaction<T>>) -> bool {
+        let Some(existing) = self.by_id.get_mut(tx.id()) else { return false };
+        let replaced = existing.clone();
+        existing.transaction = tx;
+        if self.all.remove(&replaced) {
+            self.all.insert(existing.clone());
+        }
+        true
+    }
+
     /// Removes the transaction from the pool
     pub(crate) fn remove_transaction(
         &mut self,
@@ -76,6 +102,7 @@ impl<T: PoolTransaction> BlobTransactions<T> {
 
         // keep track of size
         self.size_of -= tx.transaction.size();
+        self.senders.remove(id.sender, tx.transaction.size());
 
         Some(tx.transaction)
     }
@@ -257,11 +284,28 @@ impl<T: PoolTransaction> Default for BlobTransactions<T> {
             by_id: Default::default(),
             all: Default::default(),
             size_of: Default::default(),
+            senders: Default::default(),
             pending_fees: Default::default(),
         }
     }
 }
 
+impl<T: PoolTransaction> EvictionPool<T> for BlobTransactions<T> {
+    fn senders(&self) -> &SenderIndex {
+        &self.senders
+    }
+
+    fn sender_transactions_desc(
+        &self,
+        sender: SenderId,
+    ) -> impl Iterator<Item = &Arc<ValidPoolTransaction<T>>> + '_ {
+        self.by_id
+            .range(TransactionId::new(sender, 0)..=TransactionId::new(sender, u64::MAX))
+            .rev()
+            .map(|(_, tx)| &tx.transaction)
+    }
+}
+
 /// A transaction that is ready to be included in a block.
 #[derive(Debug)]
 struct BlobTransaction<T: PoolTransaction> {
@@ -0,0 +1,303 @@
+//! Conditions of transactions submitted with `eth_sendRawTransactionConditional`.
+//!
+//! A conditional transaction may only be included in a block while its [`TransactionConditional`]
+//! holds: the block must be within the given number and timestamp bounds, and the storage of the
+//! known accounts must match the state the block is built on.
+
+use crate::{PoolTransaction, ValidPoolTransaction};
+use alloy_primitives::{Address, B256};
+use reth_storage_api::{errors::provider::ProviderResult, StateProvider};
+use std::{collections::HashMap, sync::Arc};
+use tracing::trace;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Maximum [`TransactionConditional::cost`] of a conditional transaction.
+pub const MAX_TRANSACTION_CONDITIONAL_COST: usize = 1000;
+
+/// The expected storage of a known account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(untagged))]
+pub enum AccountStorage {
+    /// The expected storage root of the account.
+    RootHash(B256),
+    /// The expected values of individual storage slots of the account.
+    Slots(HashMap<B256, B256>),
+}
+
+/// Conditions under which a transaction may be included in a block.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub struct TransactionConditional {
+    /// The expected storage of accounts, in the state the block is built on.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub known_accounts: HashMap<Address, AccountStorage>,
+    /// Minimum number of the including block.
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            skip_serializing_if = "Option::is_none",
+            with = "alloy_serde::quantity::opt"
+        )
+    )]
+    pub block_number_min: Option<u64>,
+    /// Maximum number of the including block.
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            skip_serializing_if = "Option::is_none",
+            with = "alloy_serde::quantity::opt"
+        )
+    )]
+    pub block_number_max: Option<u64>,
+    /// Minimum timestamp of the including block.
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            skip_serializing_if = "Option::is_none",
+            with = "alloy_serde::quantity::opt"
+        )
+    )]
+    pub timestamp_min: Option<u64>,
+    /// Maximum timestamp of the including block.
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            skip_serializing_if = "Option::is_none",
+            with = "alloy_serde::quantity::opt"
+        )
+    )]
+    pub timestamp_max: Option<u64>,
+}
+
+impl TransactionConditional {
+    /// Returns the number of state lookups required to check the known accounts: one per storage
+    /// root and one per storage slot.
+    pub fn cost(&self) -> usize {
+        self.known_accounts
+            .values()
+            .map(|storage| match storage {
+                AccountStorage::RootHash(_) => 1,
+                AccountStorage::Slots(slots) => slots.len(),
+            })
+            .sum()
+    }
+
+    /// Returns `true` if a block with the given number and timestamp is within the bounds.
+    pub fn matches_block_attributes(&self, number: u64, timestamp: u64) -> bool {
+        self.block_number_min.map_or(true, |min| number >= min) &&
+            self.block_number_max.map_or(true, |max| number <= max) &&
+            self.timestamp_min.map_or(true, |min| timestamp >= min) &&
+            self.timestamp_max.map_or(true, |max| timestamp <= max)
+    }
+
+    /// Returns `true` if no block after the block with the given number and timestamp can be
+    /// within the bounds anymore.
+    pub fn has_exceeded_block_attributes(&self, number: u64, timestamp: u64) -> bool {
+        self.block_number_max.is_some_and(|max| number >= max) ||
+            self.timestamp_max.is_some_and(|max| timestamp >= max)
+    }
+
+    /// Returns `true` if the storage of all known accounts matches the given state.
+    pub fn matches_state<S: StateProvider + ?Sized>(&self, state: &S) -> ProviderResult<bool> {
+        for (address, storage) in &self.known_accounts {
+            match storage {
+                AccountStorage::RootHash(expected) => {
+                    if state.storage_root(*address, Default::default())? != *expected {
+                        return Ok(false)
+                    }
+                }
+                AccountStorage::Slots(slots) => {
+                    for (slot, expected) in slots {
+                        let value = state.storage(*address, *slot)?.unwrap_or_default();
+                        if B256::from(value) != *expected {
+                            return Ok(false)
+                        }
+                    }
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    /// Returns `true` if the conditions hold for a block with the given number and timestamp,
+    /// built on the given state.
+    ///
+    /// Failing state lookups are treated as unmet conditions.
+    pub fn matches<S: StateProvider + ?Sized>(
+        &self,
+        number: u64,
+        timestamp: u64,
+        state: &S,
+    ) -> bool {
+        self.matches_block_attributes(number, timestamp) &&
+            self.matches_state(state).unwrap_or_else(|err| {
+                trace!(target: "txpool", %err, "failed to check known accounts");
+                false
+            })
+    }
+}
+
+/// Returns a predicate for
+/// [`BestTransactionsFilter::filter`](crate::BestTransactionsFilter::filter) that skips conditional
+/// transactions whose conditions don't hold for the block with the given number and timestamp.
+///
+/// Transactions without conditions are always yielded. The state the block is built on is only
+/// opened with the given function once a conditional transaction with known accounts is checked, if
+/// it can't be opened, such transactions are skipped.
+pub fn conditional_transactions_filter<T, F, S>(
+    number: u64,
+    timestamp: u64,
+    state_provider: F,
+) -> impl FnMut(&Arc<ValidPoolTransaction<T>>) -> bool
+where
+    T: PoolTransaction,
+    F: FnOnce() -> ProviderResult<S>,
+    S: StateProvider,
+{
+    let mut state_provider = Some(state_provider);
+    let mut state = None;
+    move |tx| {
+        let Some(conditional) = tx.conditional.as_ref() else { return true };
+        if !conditional.matches_block_attributes(number, timestamp) {
+            return false
+        }
+        if conditional.known_accounts.is_empty() {
+            return true
+        }
+
+        if let Some(open) = state_provider.take() {
+            state = open()
+                .inspect_err(|err| trace!(target: "txpool", %err, "failed to open state"))
+                .ok();
+        }
+        state.as_ref().is_some_and(|state| conditional.matches(number, timestamp, state))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{MockTransaction, MockTransactionFactory};
+    use alloy_primitives::{address, U256};
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+
+    #[test]
+    fn block_attributes() {
+        let conditional = TransactionConditional {
+            block_number_min: Some(10),
+            block_number_max: Some(20),
+            timestamp_max: Some(1000),
+            ..Default::default()
+        };
+
+        assert!(!conditional.matches_block_attributes(9, 0));
+        assert!(conditional.matches_block_attributes(10, 0));
+        assert!(conditional.matches_block_attributes(20, 1000));
+        assert!(!conditional.matches_block_attributes(21, 1000));
+        assert!(!conditional.matches_block_attributes(15, 1001));
+
+        assert!(!conditional.has_exceeded_block_attributes(19, 999));
+        assert!(conditional.has_exceeded_block_attributes(20, 999));
+        assert!(conditional.has_exceeded_block_attributes(19, 1000));
+        assert!(!TransactionConditional::default().has_exceeded_block_attributes(u64::MAX, 0));
+    }
+
+    #[test]
+    fn known_accounts() {
+        let provider = MockEthProvider::default();
+        let address = Address::random();
+        let slot = B256::with_last_byte(1);
+        provider.add_account(
+            address,
+            ExtendedAccount::new(0, U256::ZERO).extend_storage([(slot, U256::from(42))]),
+        );
+
+        let mut conditional = TransactionConditional::default();
+        conditional.known_accounts.insert(
+            address,
+            AccountStorage::Slots(HashMap::from([(slot, B256::from(U256::from(42)))])),
+        );
+        assert_eq!(conditional.cost(), 1);
+        assert!(conditional.matches_state(&provider).unwrap());
+
+        // unset slots are zero
+        conditional
+            .known_accounts
+            .insert(Address::random(), AccountStorage::Slots(HashMap::from([(slot, B256::ZERO)])));
+        assert!(conditional.matches_state(&provider).unwrap());
+
+        conditional.known_accounts.insert(
+            address,
+            AccountStorage::Slots(HashMap::from([(slot, B256::from(U256::from(43)))])),
+        );
+        assert!(!conditional.matches_state(&provider).unwrap());
+    }
+
+    #[test]
+    fn filter_opens_state_lazily() {
+        let mut f = MockTransactionFactory::default();
+        let plain = f.validated_arc(MockTransaction::eip1559());
+        let mut bounded = f.validated(MockTransaction::eip1559());
+        bounded.conditional = Some(Arc::new(TransactionConditional {
+            block_number_max: Some(1),
+            ..Default::default()
+        }));
+        let bounded = Arc::new(bounded);
+
+        let mut opened = 0;
+        let mut filter = conditional_transactions_filter(1, 0, || {
+            opened += 1;
+            Ok(MockEthProvider::default())
+        });
+        assert!(filter(&plain));
+        assert!(filter(&bounded));
+        drop(filter);
+        // no known accounts to check, so the state isn't needed
+        assert_eq!(opened, 0);
+
+        let mut known = f.validated(MockTransaction::eip1559());
+        let mut conditional = TransactionConditional::default();
+        conditional.known_accounts.insert(Address::random(), AccountStorage::RootHash(B256::ZERO));
+        known.conditional = Some(Arc::new(conditional));
+        let known = Arc::new(known);
+
+        let mut filter = conditional_transactions_filter(1, 0, || {
+            opened += 1;
+            Ok(MockEthProvider::default())
+        });
+        filter(&known);
+        filter(&known);
+        drop(filter);
+        assert_eq!(opened, 1);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_conditional() {
+        let json = r#"{
+            "knownAccounts": {
+                "0x000000000000000000000000000000000000dead": "0x0000000000000000000000000000000000000000000000000000000000000001",
+                "0x000000000000000000000000000000000000beef": {
+                    "0x0000000000000000000000000000000000000000000000000000000000000002": "0x0000000000000000000000000000000000000000000000000000000000000003"
+                }
+            },
+            "blockNumberMax": "0x64",
+            "timestampMin": "0x10"
+        }"#;
+        let conditional: TransactionConditional = serde_json::from_str(json).unwrap();
+        assert_eq!(conditional.cost(), 2);
+        assert_eq!(conditional.block_number_min, None);
+        assert_eq!(conditional.block_number_max, Some(100));
+        assert_eq!(conditional.timestamp_min, Some(16));
+        assert_eq!(
+            conditional.known_accounts[&address!("000000000000000000000000000000000000dead")],
+            AccountStorage::RootHash(B256::with_last_byte(1))
+        );
+    }
+}
@@ -0,0 +1,461 @@
+//! Pluggable strategies for picking the transactions that are evicted from a sub-pool that exceeds
+//! its limits.
+//!
+//! By default, every sub-pool evicts by its own fixed ordering, see for example
+//! [`ParkedPool::truncate_pool`](super::ParkedPool::truncate_pool). If an [`EvictionStrategy`] is
+//! configured via [`PoolConfig::eviction_strategy`](crate::PoolConfig::eviction_strategy), it is
+//! used for all sub-pools instead.
+
+use crate::{
+    identifier::{SenderId, TransactionId},
+    PoolTransaction, SubPoolLimit, ValidPoolTransaction,
+};
+use std::{
+    cmp::Ordering,
+    collections::{btree_map::Entry, BTreeMap, BinaryHeap},
+    fmt,
+    sync::Arc,
+    time::Instant,
+};
+
+/// A transaction that can be evicted from a sub-pool, as seen by an [`EvictionStrategy`].
+///
+/// Only the highest nonce transaction of each sender is a candidate, so evictions never introduce
+/// a nonce gap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EvictionCandidate {
+    /// Identifier of the transaction.
+    pub id: TransactionId,
+    /// Whether the transaction originated locally.
+    pub is_local: bool,
+    /// Priority fee per gas the transaction pays at the pending base fee, `0` if the transaction
+    /// can't pay the base fee.
+    pub effective_tip: u128,
+    /// When the transaction was added to the pool.
+    pub timestamp: Instant,
+    /// Size of the transaction.
+    pub size: usize,
+    /// Number of transactions of the sender in the sub-pool.
+    pub sender_txs: usize,
+    /// Combined size of all transactions of the sender in the sub-pool.
+    pub sender_size: usize,
+}
+
+/// Decides which transaction is evicted next from a sub-pool that exceeds its limits.
+///
+/// Non-local transactions are always evicted before local transactions, the strategy only orders
+/// candidates of the same kind.
+pub trait EvictionStrategy: fmt::Debug + Send + Sync {
+    /// Name of the strategy.
+    fn name(&self) -> &str;
+
+    /// Compares two candidates, returns [`Ordering::Less`] if `a` should be evicted before `b`.
+    fn compare(&self, a: &EvictionCandidate, b: &EvictionCandidate) -> Ordering;
+}
+
+/// Evicts the transaction with the lowest effective tip first, the most recent one on ties.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EffectiveTipEviction;
+
+impl EvictionStrategy for EffectiveTipEviction {
+    fn name(&self) -> &str {
+        "effective_tip"
+    }
+
+    fn compare(&self, a: &EvictionCandidate, b: &EvictionCandidate) -> Ordering {
+        a.effective_tip.cmp(&b.effective_tip).then_with(|| b.timestamp.cmp(&a.timestamp))
+    }
+}
+
+/// Evicts the most recently added transaction first, which protects long-lived transactions from
+/// bursts of new transactions.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AgeEviction;
+
+impl EvictionStrategy for AgeEviction {
+    fn name(&self) -> &str {
+        "age"
+    }
+
+    fn compare(&self, a: &EvictionCandidate, b: &EvictionCandidate) -> Ordering {
+        b.timestamp.cmp(&a.timestamp)
+    }
+}
+
+/// Evicts transactions of the sender that exceeds its byte quota the most first, and falls back to
+/// the [`EffectiveTipEviction`] once all senders are within their quota.
+#[derive(Debug, Clone, Copy)]
+pub struct SenderQuotaEviction {
+    /// Combined size of transactions a sender may have in a sub-pool before its transactions are
+    /// evicted first.
+    pub max_sender_size: usize,
+}
+
+impl SenderQuotaEviction {
+    /// Returns by how many bytes the sender of the candidate exceeds its quota.
+    const fn excess(&self, candidate: &EvictionCandidate) -> usize {
+        candidate.sender_size.saturating_sub(self.max_sender_size)
+    }
+}
+
+impl EvictionStrategy for SenderQuotaEviction {
+    fn name(&self) -> &str {
+        "sender_quota"
+    }
+
+    fn compare(&self, a: &EvictionCandidate, b: &EvictionCandidate) -> Ordering {
+        self.excess(b).cmp(&self.excess(a)).then_with(|| EffectiveTipEviction.compare(a, b))
+    }
+}
+
+/// Evicts by the effective tip of a transaction divided by the number of transactions its sender
+/// has in the sub-pool, lowest first.
+///
+/// This spreads evictions across senders: a sender flooding the pool is penalized, even if its
+/// transactions pay a slightly higher tip than those of other senders.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FairnessEviction;
+
+impl EvictionStrategy for FairnessEviction {
+    fn name(&self) -> &str {
+        "fairness"
+    }
+
+    fn compare(&self, a: &EvictionCandidate, b: &EvictionCandidate) -> Ordering {
+        // compare `a.tip / a.txs` with `b.tip / b.txs`
+        let score_a = a.effective_tip.saturating_mul(b.sender_txs as u128);
+        let score_b = b.effective_tip.saturating_mul(a.sender_txs as u128);
+        score_a
+            .cmp(&score_b)
+            .then_with(|| b.sender_txs.cmp(&a.sender_txs))
+            .then_with(|| b.timestamp.cmp(&a.timestamp))
+    }
+}
+
+/// Number and combined size of the transactions of a sender in a sub-pool.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct SenderTotals {
+    /// Number of transactions.
+    pub(crate) txs: usize,
+    /// Combined size of the transactions.
+    pub(crate) size: usize,
+}
+
+/// The [`SenderTotals`] of all senders of a sub-pool.
+///
+/// This is maintained by the sub-pool as transactions are added and removed, so selecting
+/// evictions doesn't need to group the sub-pool's transactions by sender.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SenderIndex {
+    senders: BTreeMap<SenderId, SenderTotals>,
+}
+
+impl SenderIndex {
+    /// Accounts a transaction of the sender that was added to the sub-pool.
+    pub(crate) fn add(&mut self, sender: SenderId, size: usize) {
+        let totals = self.senders.entry(sender).or_default();
+        totals.txs += 1;
+        totals.size += size;
+    }
+
+    /// Accounts a transaction of the sender that was removed from the sub-pool.
+    pub(crate) fn remove(&mut self, sender: SenderId, size: usize) {
+        if let Entry::Occupied(mut entry) = self.senders.entry(sender) {
+            let totals = entry.get_mut();
+            totals.txs -= 1;
+            totals.size -= size;
+            if totals.txs == 0 {
+                entry.remove();
+            }
+        }
+    }
+
+    /// Removes all senders.
+    pub(crate) fn clear(&mut self) {
+        self.senders.clear();
+    }
+
+    /// Returns all senders with their totals, ordered by sender.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (SenderId, SenderTotals)> + '_ {
+        self.senders.iter().map(|(sender, totals)| (*sender, *totals))
+    }
+}
+
+/// A sub-pool that transactions can be evicted from.
+pub(crate) trait EvictionPool<T: PoolTransaction> {
+    /// Returns the number and combined size of the transactions of each sender.
+    fn senders(&self) -> &SenderIndex;
+
+    /// Returns the transactions of the sender, highest nonce first.
+    fn sender_transactions_desc(
+        &self,
+        sender: SenderId,
+    ) -> impl Iterator<Item = &Arc<ValidPoolTransaction<T>>> + '_;
+}
+
+/// Returns the transactions to evict from the given sub-pool until it satisfies the given
+/// [`SubPoolLimit`], in eviction order.
+///
+/// The next candidate of every sender is kept in a heap ordered by the strategy. A candidate only
+/// depends on the transactions of its own sender, so only the sender of an evicted transaction
+/// needs a new candidate.
+pub(crate) fn select_evictions<T: PoolTransaction>(
+    strategy: &dyn EvictionStrategy,
+    pool: &impl EvictionPool<T>,
+    base_fee: u64,
+    limit: &SubPoolLimit,
+) -> Vec<TransactionId> {
+    let (mut len, mut size) = pool
+        .senders()
+        .iter()
+        .fold((0, 0), |(len, size), (_, totals)| (len + totals.txs, size + totals.size));
+
+    let mut heap = pool
+        .senders()
+        .iter()
+        .filter_map(|(sender, totals)| {
+            let mut txs = pool.sender_transactions_desc(sender);
+            let candidate = EvictionCandidate::new(txs.next()?, base_fee, totals);
+            Some(SenderCandidate { candidate, totals, txs, strategy })
+        })
+        .collect::<BinaryHeap<_>>();
+
+    let mut evicted = Vec::new();
+    while limit.is_exceeded(len, size) {
+        let Some(SenderCandidate { candidate: worst, mut totals, mut txs, .. }) = heap.pop() else {
+            break
+        };
+
+        len -= 1;
+        size -= worst.size;
+        evicted.push(worst.id);
+
+        // the sender's next highest nonce transaction becomes its candidate
+        totals.txs -= 1;
+        totals.size -= worst.size;
+        if let Some(next) = txs.next() {
+            let candidate = EvictionCandidate::new(next, base_fee, totals);
+            heap.push(SenderCandidate { candidate, totals, txs, strategy });
+        }
+    }
+
+    evicted
+}
+
+impl EvictionCandidate {
+    /// Creates the candidate of the given transaction, whose sender has the given remaining
+    /// totals in the sub-pool.
+    fn new<T: PoolTransaction>(
+        tx: &ValidPoolTransaction<T>,
+        base_fee: u64,
+        totals: SenderTotals,
+    ) -> Self {
+        Self {
+            id: *tx.id(),
+            is_local: tx.is_local(),
+            effective_tip: tx.transaction.effective_tip_per_gas(base_fee).unwrap_or_default(),
+            timestamp: tx.timestamp,
+            size: tx.size(),
+            sender_txs: totals.txs,
+            sender_size: totals.size,
+        }
+    }
+}
+
+/// The next eviction candidate of a sender, ordered so that the candidate evicted first is the
+/// greatest.
+struct SenderCandidate<'a, I> {
+    /// The sender's highest nonce transaction that isn't evicted yet.
+    candidate: EvictionCandidate,
+    /// Totals of the sender's transactions that aren't evicted yet.
+    totals: SenderTotals,
+    /// The sender's remaining transactions, highest nonce first.
+    txs: I,
+    /// The strategy that orders the candidates.
+    strategy: &'a dyn EvictionStrategy,
+}
+
+impl<I> PartialEq for SenderCandidate<'_, I> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<I> Eq for SenderCandidate<'_, I> {}
+
+impl<I> PartialOrd for SenderCandidate<'_, I> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<I> Ord for SenderCandidate<'_, I> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (a, b) = (&self.candidate, &other.candidate);
+        // non-local transactions first, then by the strategy, then by sender
+        b.is_local
+            .cmp(&a.is_local)
+            .then_with(|| self.strategy.compare(b, a))
+            .then_with(|| b.id.cmp(&a.id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pool::{ParkedPool, QueuedOrd},
+        test_utils::{MockTransaction, MockTransactionFactory},
+        TransactionOrigin,
+    };
+    use std::time::Duration;
+
+    const BASE_FEE: u64 = 10;
+
+    /// Limit that allows `max_txs` transactions of any size.
+    const fn max_txs(max_txs: usize) -> SubPoolLimit {
+        SubPoolLimit { max_txs, max_size: usize::MAX }
+    }
+
+    /// A sub-pool with the given transactions.
+    fn pool(
+        txs: impl IntoIterator<Item = Arc<ValidPoolTransaction<MockTransaction>>>,
+    ) -> ParkedPool<QueuedOrd<MockTransaction>> {
+        let mut pool = ParkedPool::default();
+        for tx in txs {
+            pool.add_transaction(tx);
+        }
+        pool
+    }
+
+    /// A burst of `count` single-transaction senders paying the given tip.
+    fn spam(
+        f: &mut MockTransactionFactory,
+        count: usize,
+        tip: u128,
+    ) -> Vec<Arc<ValidPoolTransaction<MockTransaction>>> {
+        (0..count)
+            .map(|_| {
+                f.validated_arc(
+                    MockTransaction::eip1559()
+                        .with_max_fee(BASE_FEE as u128 + tip)
+                        .with_priority_fee(tip),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn effective_tip_keeps_high_value_transactions() {
+        let mut f = MockTransactionFactory::default();
+        let valuable = f.validated_arc(
+            MockTransaction::eip1559().with_max_fee(BASE_FEE as u128 + 100).with_priority_fee(100),
+        );
+        let mut txs = vec![valuable.clone()];
+        txs.extend(spam(&mut f, 10, 1));
+
+        let evicted = select_evictions(&EffectiveTipEviction, &pool(txs), BASE_FEE, &max_txs(1));
+        assert_eq!(evicted.len(), 10);
+        assert!(!evicted.contains(valuable.id()));
+    }
+
+    #[test]
+    fn age_evicts_newest_first() {
+        let mut f = MockTransactionFactory::default();
+        let now = Instant::now();
+        let txs = (0..5u64)
+            .map(|age| {
+                let mut tx = f.validated(MockTransaction::eip1559());
+                tx.timestamp = now - Duration::from_secs(age);
+                Arc::new(tx)
+            })
+            .collect::<Vec<_>>();
+
+        let evicted = select_evictions(&AgeEviction, &pool(txs.clone()), BASE_FEE, &max_txs(2));
+        assert_eq!(evicted, vec![*txs[0].id(), *txs[1].id(), *txs[2].id()]);
+    }
+
+    #[test]
+    fn sender_quota_evicts_exceeding_sender() {
+        let mut f = MockTransactionFactory::default();
+        let tx = MockTransaction::eip1559().with_size(100).with_priority_fee(50).with_max_fee(100);
+        let heavy = [tx.clone(), tx.next(), tx.next().next()].map(|tx| f.validated_arc(tx));
+
+        let mut txs = heavy.to_vec();
+        txs.extend((0..3).map(|_| {
+            f.validated_arc(
+                MockTransaction::eip1559().with_size(100).with_priority_fee(1).with_max_fee(100),
+            )
+        }));
+
+        let strategy = SenderQuotaEviction { max_sender_size: 100 };
+        let evicted = select_evictions(&strategy, &pool(txs), BASE_FEE, &max_txs(4));
+        // the heavy sender is trimmed down to its quota, highest nonce first
+        assert_eq!(evicted, vec![*heavy[2].id(), *heavy[1].id()]);
+    }
+
+    #[test]
+    fn fairness_spreads_evictions_across_senders() {
+        let mut f = MockTransactionFactory::default();
+        let tx = MockTransaction::eip1559().with_priority_fee(20).with_max_fee(100);
+        let mut flood = vec![tx.clone()];
+        for _ in 0..4 {
+            let next = flood.last().unwrap().next();
+            flood.push(next);
+        }
+        let flood = flood.into_iter().map(|tx| f.validated_arc(tx)).collect::<Vec<_>>();
+        let single = spam(&mut f, 2, 10);
+
+        let mut txs = flood.clone();
+        txs.extend(single.clone());
+
+        let evicted = select_evictions(&FairnessEviction, &pool(txs), BASE_FEE, &max_txs(4));
+        assert_eq!(evicted.len(), 3);
+        assert!(evicted.iter().all(|id| id.sender == flood[0].sender_id()));
+    }
+
+    #[test]
+    fn local_transactions_are_evicted_last() {
+        let mut f = MockTransactionFactory::default();
+        let local = Arc::new(f.validated_with_origin(
+            TransactionOrigin::Local,
+            MockTransaction::eip1559().with_max_fee(BASE_FEE as u128).with_priority_fee(0),
+        ));
+        let mut txs = vec![local.clone()];
+        txs.extend(spam(&mut f, 2, 100));
+
+        let evicted = select_evictions(&EffectiveTipEviction, &pool(txs), BASE_FEE, &max_txs(1));
+        assert_eq!(evicted.len(), 2);
+        assert!(!evicted.contains(local.id()));
+    }
+
+    #[test]
+    fn sender_index_follows_sub_pool() {
+        let mut f = MockTransactionFactory::default();
+        let tx = MockTransaction::eip1559().with_size(10);
+        let txs = [tx.clone(), tx.next(), tx.next().next()].map(|tx| f.validated_arc(tx));
+        let other = f.validated_arc(MockTransaction::eip1559().with_size(20));
+
+        let mut pool = pool(txs.iter().cloned().chain([other.clone()]));
+        assert_eq!(
+            pool.senders().iter().collect::<Vec<_>>(),
+            vec![
+                (txs[0].sender_id(), SenderTotals { txs: 3, size: 30 }),
+                (other.sender_id(), SenderTotals { txs: 1, size: 20 }),
+            ]
+        );
+        assert_eq!(
+            pool.sender_transactions_desc(txs[0].sender_id())
+                .map(|tx| *tx.id())
+                .collect::<Vec<_>>(),
+            vec![*txs[2].id(), *txs[1].id(), *txs[0].id()]
+        );
+
+        pool.remove_transaction(txs[2].id());
+        pool.remove_transaction(other.id());
+        assert_eq!(
+            pool.senders().iter().collect::<Vec<_>>(),
+            vec![(txs[0].sender_id(), SenderTotals { txs: 2, size: 20 })]
+        );
+    }
+}
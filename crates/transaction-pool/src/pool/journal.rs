@@ -0,0 +1,311 @@
+//! A bounded journal of the state transitions of pool transactions.
+//!
+//! In contrast to the [`TransactionEvents`](crate::TransactionEvents) stream, the journal keeps the
+//! history of a transaction after the fact, including the reason why it was parked or dropped.
+
+use crate::{pool::state::TxState, PoolTransaction, ValidPoolTransaction};
+use alloy_primitives::{Address, TxHash};
+use reth_transaction_pool_types::{JournalEntry, JournalEvent, ParkedReason, TransactionHistory};
+use std::{
+    collections::{hash_map::Entry, HashMap, VecDeque},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Maximum number of events recorded per transaction, older events are dropped first.
+pub const MAX_JOURNAL_EVENTS_PER_TRANSACTION: usize = 32;
+
+/// Maximum number of tracked transactions that were rejected without ever entering the pool.
+///
+/// Rejections are bounded separately, so that a flood of invalid transactions can't push the
+/// histories of pooled transactions out of the journal.
+pub const MAX_JOURNAL_REJECTED_TRANSACTIONS: usize = 1_000;
+
+/// Returns the first unmet requirement of the pending sub-pool, if any.
+pub(crate) const fn parked_reason(state: TxState) -> Option<ParkedReason> {
+    if state.has_nonce_gap() {
+        Some(ParkedReason::NonceGap)
+    } else if !state.contains(TxState::NO_PARKED_ANCESTORS) {
+        Some(ParkedReason::ParkedAncestor)
+    } else if !state.contains(TxState::ENOUGH_BALANCE) {
+        Some(ParkedReason::InsufficientBalance)
+    } else if !state.contains(TxState::NOT_TOO_MUCH_GAS) {
+        Some(ParkedReason::GasLimitExceeded)
+    } else if !state.contains(TxState::ENOUGH_FEE_CAP_BLOCK) {
+        Some(ParkedReason::FeeCapBelowBaseFee)
+    } else if !state.contains(TxState::ENOUGH_BLOB_FEE_CAP_BLOCK) {
+        Some(ParkedReason::BlobFeeCapBelowBlobFee)
+    } else {
+        None
+    }
+}
+
+/// A journal of the state transitions of pool transactions, keyed by hash and sender.
+///
+/// The journal tracks up to `max_transactions` transactions and forgets the least recently added
+/// transaction first. Transactions that were rejected before entering the pool are tracked
+/// separately, up to the same capacity but at most [`MAX_JOURNAL_REJECTED_TRANSACTIONS`]. A journal
+/// with a capacity of zero is disabled.
+#[derive(Debug, Default)]
+pub struct TransactionJournal {
+    /// Max number of tracked transactions.
+    max_transactions: usize,
+    /// The history of all tracked transactions.
+    histories: HashMap<TxHash, RecordedTransaction>,
+    /// Hashes of tracked transactions, by sender.
+    by_sender: HashMap<Address, Vec<TxHash>>,
+    /// Hashes of tracked transactions, in the order they were first recorded.
+    order: VecDeque<TxHash>,
+    /// Hashes of tracked transactions that were only rejected, in the order they were recorded.
+    rejected: VecDeque<TxHash>,
+}
+
+impl TransactionJournal {
+    /// Creates a new journal that tracks up to `max_transactions` transactions.
+    pub fn new(max_transactions: usize) -> Self {
+        Self { max_transactions, ..Default::default() }
+    }
+
+    /// Returns `true` if the journal records events.
+    pub const fn is_enabled(&self) -> bool {
+        self.max_transactions > 0
+    }
+
+    /// Returns the number of tracked transactions.
+    pub fn len(&self) -> usize {
+        self.histories.len()
+    }
+
+    /// Returns `true` if no transactions are tracked.
+    pub fn is_empty(&self) -> bool {
+        self.histories.is_empty()
+    }
+
+    /// Records an event of the given transaction.
+    pub fn record<T: PoolTransaction>(
+        &mut self,
+        tx: &ValidPoolTransaction<T>,
+        event: JournalEvent,
+    ) {
+        self.record_event(*tx.hash(), tx.sender(), tx.nonce(), event)
+    }
+
+    /// Records an event of the transaction with the given hash, sender and nonce.
+    pub fn record_event(&mut self, hash: TxHash, sender: Address, nonce: u64, event: JournalEvent) {
+        if !self.is_enabled() {
+            return
+        }
+
+        let is_rejection = matches!(event, JournalEvent::Rejected { .. });
+        let entry = JournalEntry { timestamp: unix_timestamp_millis(), event };
+        match self.histories.entry(hash) {
+            Entry::Occupied(mut history) => {
+                let history = history.get_mut();
+                if history.events.len() >= MAX_JOURNAL_EVENTS_PER_TRANSACTION {
+                    history.events.pop_front();
+                }
+                history.events.push_back(entry);
+
+                if history.rejected_only && !is_rejection {
+                    // the transaction made it into the pool, track it with the pooled ones
+                    history.rejected_only = false;
+                    self.rejected.retain(|tracked| *tracked != hash);
+                    self.order.push_back(hash);
+                    while self.order.len() > self.max_transactions {
+                        Self::forget_oldest(
+                            &mut self.order,
+                            &mut self.histories,
+                            &mut self.by_sender,
+                        );
+                    }
+                }
+            }
+            Entry::Vacant(history) => {
+                history.insert(RecordedTransaction {
+                    sender,
+                    nonce,
+                    rejected_only: is_rejection,
+                    events: VecDeque::from([entry]),
+                });
+                self.by_sender.entry(sender).or_default().push(hash);
+                if is_rejection {
+                    self.rejected.push_back(hash);
+                    let max_rejected = self.max_transactions.min(MAX_JOURNAL_REJECTED_TRANSACTIONS);
+                    while self.rejected.len() > max_rejected {
+                        Self::forget_oldest(
+                            &mut self.rejected,
+                            &mut self.histories,
+                            &mut self.by_sender,
+                        );
+                    }
+                } else {
+                    self.order.push_back(hash);
+                    while self.order.len() > self.max_transactions {
+                        Self::forget_oldest(
+                            &mut self.order,
+                            &mut self.histories,
+                            &mut self.by_sender,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the history of the transaction with the given hash, if it is tracked.
+    pub fn history(&self, hash: &TxHash) -> Option<TransactionHistory> {
+        self.histories.get(hash).map(|recorded| TransactionHistory {
+            hash: *hash,
+            sender: recorded.sender,
+            nonce: recorded.nonce,
+            events: recorded.events.iter().cloned().collect(),
+        })
+    }
+
+    /// Returns the histories of all tracked transactions of the given sender, ordered by nonce.
+    pub fn histories_by_sender(&self, sender: &Address) -> Vec<TransactionHistory> {
+        let mut histories = self
+            .by_sender
+            .get(sender)
+            .into_iter()
+            .flatten()
+            .filter_map(|hash| self.history(hash))
+            .collect::<Vec<_>>();
+        histories.sort_by_key(|history| history.nonce);
+        histories
+    }
+
+    /// Forgets the least recently added transaction of the given order.
+    fn forget_oldest(
+        order: &mut VecDeque<TxHash>,
+        histories: &mut HashMap<TxHash, RecordedTransaction>,
+        by_sender: &mut HashMap<Address, Vec<TxHash>>,
+    ) {
+        let Some(hash) = order.pop_front() else { return };
+        let Some(history) = histories.remove(&hash) else { return };
+        if let Entry::Occupied(mut hashes) = by_sender.entry(history.sender) {
+            hashes.get_mut().retain(|tracked| *tracked != hash);
+            if hashes.get().is_empty() {
+                hashes.remove();
+            }
+        }
+    }
+}
+
+/// The recorded events of a tracked transaction.
+#[derive(Debug)]
+struct RecordedTransaction {
+    /// Sender of the transaction.
+    sender: Address,
+    /// Nonce of the transaction.
+    nonce: u64,
+    /// Whether the transaction was only rejected so far and never entered the pool.
+    rejected_only: bool,
+    /// All recorded events, oldest first.
+    events: VecDeque<JournalEntry>,
+}
+
+/// Returns the current unix timestamp in milliseconds.
+fn unix_timestamp_millis() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::B256;
+    use reth_transaction_pool_types::{DropReason, SubPool};
+
+    #[test]
+    fn bounded_journal() {
+        let mut journal = TransactionJournal::new(2);
+        let sender = Address::random();
+        let hashes = [B256::random(), B256::random(), B256::random()];
+
+        for (nonce, hash) in hashes.iter().enumerate() {
+            journal.record_event(*hash, sender, nonce as u64, JournalEvent::Pending);
+        }
+        assert_eq!(journal.len(), 2);
+        assert!(journal.history(&hashes[0]).is_none());
+
+        journal.record_event(
+            hashes[1],
+            sender,
+            1,
+            JournalEvent::Dropped { reason: DropReason::SizeLimit(SubPool::Pending) },
+        );
+        let history = journal.history(&hashes[1]).unwrap();
+        assert_eq!(history.events.len(), 2);
+        assert_eq!(
+            history.events[1].event,
+            JournalEvent::Dropped { reason: DropReason::SizeLimit(SubPool::Pending) }
+        );
+
+        let by_sender = journal.histories_by_sender(&sender);
+        assert_eq!(
+            by_sender.iter().map(|history| history.hash).collect::<Vec<_>>(),
+            vec![hashes[1], hashes[2]]
+        );
+    }
+
+    #[test]
+    fn rejections_are_bounded_separately() {
+        let mut journal = TransactionJournal::new(2);
+        let pooled = B256::random();
+        journal.record_event(pooled, Address::random(), 0, JournalEvent::Pending);
+
+        // rejections are capped at the journal's capacity as well
+        let rejected = [B256::random(), B256::random(), B256::random()];
+        for hash in &rejected {
+            journal.record_event(
+                *hash,
+                Address::random(),
+                0,
+                JournalEvent::Rejected { error: "invalid".to_string() },
+            );
+        }
+        // rejections don't push pooled transactions out of the journal
+        assert!(journal.history(&pooled).is_some());
+        assert_eq!(journal.len(), 3);
+        assert!(journal.history(&rejected[0]).is_none());
+
+        // a rejected transaction that enters the pool counts against the pooled transactions
+        let last = *rejected.last().unwrap();
+        journal.record_event(last, Address::ZERO, 0, JournalEvent::Pending);
+        assert_eq!(journal.history(&last).unwrap().events.len(), 2);
+        let other = B256::random();
+        journal.record_event(other, Address::random(), 0, JournalEvent::Pending);
+        assert!(journal.history(&pooled).is_none());
+        assert!(journal.history(&last).is_some());
+        assert_eq!(journal.len(), 3);
+    }
+
+    #[test]
+    fn disabled_journal() {
+        let mut journal = TransactionJournal::new(0);
+        journal.record_event(B256::random(), Address::random(), 0, JournalEvent::Pending);
+        assert!(journal.is_empty());
+    }
+
+    #[test]
+    fn events_per_transaction_are_capped() {
+        let mut journal = TransactionJournal::new(1);
+        let hash = B256::random();
+        for _ in 0..MAX_JOURNAL_EVENTS_PER_TRANSACTION + 1 {
+            journal.record_event(hash, Address::ZERO, 0, JournalEvent::Pending);
+        }
+        assert_eq!(
+            journal.history(&hash).unwrap().events.len(),
+            MAX_JOURNAL_EVENTS_PER_TRANSACTION
+        );
+    }
+
+    #[test]
+    fn parked_reason_from_state() {
+        assert_eq!(parked_reason(TxState::default()), Some(ParkedReason::NonceGap));
+        assert_eq!(
+            parked_reason(TxState::BASE_FEE_POOL_BITS),
+            Some(ParkedReason::FeeCapBelowBaseFee)
+        );
+        assert_eq!(parked_reason(TxState::PENDING_POOL_BITS), None);
+    }
+}
@@ -66,7 +66,7 @@
 //!    category (2.) and become pending.
 
 use crate::{
-    error::{PoolError, PoolErrorKind, PoolResult},
+    error::{InvalidPoolTransactionError, PoolError, PoolErrorKind, PoolResult},
     identifier::{SenderId, SenderIdentifiers, TransactionId},
     pool::{
         listener::PoolEventBroadcast,
@@ -80,11 +80,12 @@ use crate::{
     validate::{TransactionValidationOutcome, ValidPoolTransaction},
     CanonicalStateUpdate, PoolConfig, TransactionOrdering, TransactionValidator,
 };
-use alloy_primitives::{Address, TxHash, B256};
+use alloy_primitives::{Address, BlockNumber, TxHash, B256};
 use best::BestTransactions;
 use parking_lot::{Mutex, RwLock, RwLockReadGuard};
 use reth_eth_wire_types::HandleMempoolData;
 use reth_execution_types::ChangedAccount;
+use reth_network_peers::PeerId;
 
 use reth_primitives::{
     BlobTransaction, BlobTransactionSidecar, PooledTransactionsElement, TransactionSigned,
@@ -100,24 +101,43 @@ use tokio::sync::mpsc;
 use tracing::{debug, trace, warn};
 mod events;
 use crate::{
-    blobstore::BlobStore,
+    blobstore::{BlobStore, BlobStoreError},
     metrics::BlobStoreMetrics,
     pool::txpool::UpdateOutcome,
+    simulation::SimulationCache,
     traits::{GetPooledTransactionLimit, NewBlobSidecar, TransactionListenerKind},
     validate::ValidTransaction,
 };
 pub use best::BestTransactionFilter;
 pub use blob::{blob_tx_priority, fee_delta};
+pub use conditional::{
+    conditional_transactions_filter, AccountStorage, TransactionConditional,
+    MAX_TRANSACTION_CONDITIONAL_COST,
+};
 pub use events::{FullTransactionEvent, TransactionEvent};
+pub use eviction::{
+    AgeEviction, EffectiveTipEviction, EvictionCandidate, EvictionStrategy, FairnessEviction,
+    SenderQuotaEviction,
+};
+pub use journal::{
+    TransactionJournal, MAX_JOURNAL_EVENTS_PER_TRANSACTION, MAX_JOURNAL_REJECTED_TRANSACTIONS,
+};
 pub use listener::{AllTransactionsEvents, TransactionEvents};
 pub use parked::{BasefeeOrd, ParkedOrd, ParkedPool, QueuedOrd};
 pub use pending::PendingPool;
+pub use reth_transaction_pool_types::{
+    DropReason, JournalEntry, JournalEvent, ParkedReason, TransactionHistory,
+};
 
 mod best;
 mod blob;
+mod conditional;
+mod eviction;
+mod journal;
 mod listener;
 mod parked;
 pub(crate) mod pending;
+mod quota;
 pub(crate) mod size;
 pub(crate) mod state;
 pub mod txpool;
@@ -155,6 +175,8 @@ where
     blob_transaction_sidecar_listener: Mutex<Vec<BlobTransactionSidecarListener>>,
     /// Metrics for the blob store
     blob_store_metrics: BlobStoreMetrics,
+    /// Simulation results of pending transactions.
+    simulations: SimulationCache,
 }
 
 // === impl PoolInner ===
@@ -178,6 +200,7 @@ where
             config,
             blob_store,
             blob_store_metrics: Default::default(),
+            simulations: Default::default(),
         }
     }
 
@@ -186,6 +209,11 @@ where
         &self.blob_store
     }
 
+    /// Returns the simulation results of pending transactions.
+    pub(crate) const fn simulations(&self) -> &SimulationCache {
+        &self.simulations
+    }
+
     /// Returns stats about the size of the pool.
     pub(crate) fn size(&self) -> PoolSize {
         self.get_pool_data().size()
@@ -302,6 +330,17 @@ where
         self.get_pool_data().all().transactions_iter().filter(|tx| tx.propagate).collect()
     }
 
+    /// Returns all transactions in the pool that were added with a [`TransactionConditional`].
+    pub(crate) fn conditional_transactions(
+        &self,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        self.get_pool_data()
+            .all()
+            .transactions_iter()
+            .filter(|tx| tx.conditional.is_some())
+            .collect()
+    }
+
     /// Returns the [`BlobTransaction`] for the given transaction if the sidecar exists.
     ///
     /// Caution: this assumes the given transaction is eip-4844
@@ -389,16 +428,28 @@ where
         trace!(target: "txpool", ?update, "updating pool on canonical state change");
 
         let block_info = update.block_info();
-        let CanonicalStateUpdate { new_tip, changed_accounts, mined_transactions, .. } = update;
+        let CanonicalStateUpdate {
+            new_tip,
+            changed_accounts,
+            mined_transactions,
+            delegated_accounts,
+            ..
+        } = update;
         self.validator.on_new_head_block(new_tip);
 
         let changed_senders = self.changed_senders(changed_accounts.into_iter());
+        // only senders known to the pool can have pooled transactions
+        let delegated_senders = {
+            let identifiers = self.identifiers.read();
+            delegated_accounts.iter().filter_map(|addr| identifiers.sender_id(addr)).collect()
+        };
 
         // update the pool
         let outcome = self.pool.write().on_canonical_state_change(
             block_info,
             mined_transactions,
             changed_senders,
+            delegated_senders,
         );
 
         // This will discard outdated transactions based on the account's nonce
@@ -427,11 +478,13 @@ where
 
     /// Add a single validated transaction into the pool.
     ///
-    /// Note: this is only used internally by [`Self::add_transactions()`], all new transaction(s)
-    /// come in through that function, either as a batch or `std::iter::once`.
+    /// Note: this is only used internally by [`Self::add_validated_transactions()`], all new
+    /// transaction(s) come in through that function, either as a batch or `std::iter::once`.
     fn add_transaction(
         &self,
         origin: TransactionOrigin,
+        peer_id: Option<PeerId>,
+        conditional: Option<Arc<TransactionConditional>>,
         tx: TransactionValidationOutcome<T::Transaction>,
     ) -> PoolResult<TxHash> {
         match tx {
@@ -441,8 +494,28 @@ where
                 transaction,
                 propagate,
             } => {
+                let policy =
+                    match self.config.policies.check(origin, peer_id, transaction.transaction()) {
+                        Ok(policy) => policy,
+                        Err(err) => {
+                            let hash = *transaction.hash();
+                            self.event_listener.write().discarded(&hash);
+                            return Err(PoolError::new(
+                                hash,
+                                InvalidPoolTransactionError::Other(Box::new(err)),
+                            ))
+                        }
+                    };
+
                 let sender_id = self.get_sender_id(transaction.sender());
                 let transaction_id = TransactionId::new(sender_id, transaction.nonce());
+                let authority_ids = transaction.transaction().authorization_list().map(|auths| {
+                    auths
+                        .iter()
+                        .filter_map(|auth| auth.recover_authority().ok())
+                        .map(|addr| self.get_sender_id(addr))
+                        .collect()
+                });
 
                 // split the valid transaction and the blob sidecar if it has any
                 let (transaction, maybe_sidecar) = match transaction {
@@ -459,9 +532,13 @@ where
                 let tx = ValidPoolTransaction {
                     transaction,
                     transaction_id,
-                    propagate,
+                    propagate: propagate && !policy.no_propagate,
+                    deprioritized: policy.deprioritize,
                     timestamp: Instant::now(),
                     origin,
+                    authority_ids,
+                    peer_id,
+                    conditional,
                 };
 
                 let added = self.pool.write().add_transaction(tx, balance, state_nonce)?;
@@ -531,8 +608,50 @@ where
         origin: TransactionOrigin,
         transactions: impl IntoIterator<Item = TransactionValidationOutcome<T::Transaction>>,
     ) -> Vec<PoolResult<TxHash>> {
-        let mut added =
-            transactions.into_iter().map(|tx| self.add_transaction(origin, tx)).collect::<Vec<_>>();
+        self.add_transactions_with_peer(origin, None, transactions)
+    }
+
+    /// Adds all transactions in the iterator to the pool, returning a list of results.
+    ///
+    /// The transactions are accounted against the quota of the given peer that propagated them.
+    pub fn add_transactions_with_peer(
+        &self,
+        origin: TransactionOrigin,
+        peer_id: Option<PeerId>,
+        transactions: impl IntoIterator<Item = TransactionValidationOutcome<T::Transaction>>,
+    ) -> Vec<PoolResult<TxHash>> {
+        self.add_validated_transactions(origin, peer_id, None, transactions)
+    }
+
+    /// Adds a transaction that may only be included in a block while the given conditions hold.
+    pub fn add_conditional_transaction(
+        &self,
+        origin: TransactionOrigin,
+        tx: TransactionValidationOutcome<T::Transaction>,
+        conditional: TransactionConditional,
+    ) -> PoolResult<TxHash> {
+        let mut results = self.add_validated_transactions(
+            origin,
+            None,
+            Some(Arc::new(conditional)),
+            std::iter::once(tx),
+        );
+        results.pop().expect("result length is the same as the input")
+    }
+
+    /// Adds all transactions in the iterator to the pool and enforces the pool size limits,
+    /// returning a list of results.
+    fn add_validated_transactions(
+        &self,
+        origin: TransactionOrigin,
+        peer_id: Option<PeerId>,
+        conditional: Option<Arc<TransactionConditional>>,
+        transactions: impl IntoIterator<Item = TransactionValidationOutcome<T::Transaction>>,
+    ) -> Vec<PoolResult<TxHash>> {
+        let mut added = transactions
+            .into_iter()
+            .map(|tx| self.add_transaction(origin, peer_id, conditional.clone(), tx))
+            .collect::<Vec<_>>();
 
         // If at least one transaction was added successfully, then we enforce the pool size limits.
         let discarded =
@@ -715,6 +834,46 @@ where
         removed
     }
 
+    /// Allows the transaction with the given hash to be propagated.
+    ///
+    /// If the transaction is pending, it's sent to the listeners of propagatable pending
+    /// transactions, like the network.
+    pub(crate) fn allow_propagation(
+        &self,
+        hash: TxHash,
+    ) -> Option<Arc<ValidPoolTransaction<T::Transaction>>> {
+        let (tx, subpool) = self.pool.write().allow_propagation(&hash)?;
+        if subpool.is_pending() {
+            self.pending_transaction_listener.lock().retain_mut(|listener| {
+                if !listener.kind.is_propagate_only() {
+                    // the listener already received the transaction
+                    return !listener.sender.is_closed()
+                }
+                listener.send_all([hash])
+            });
+        }
+        Some(tx)
+    }
+
+    /// Removes the conditional transactions with the given hashes because their conditions can
+    /// no longer be met.
+    pub(crate) fn remove_failed_conditional_transactions(
+        &self,
+        hashes: Vec<TxHash>,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        if hashes.is_empty() {
+            return Vec::new()
+        }
+        let removed =
+            self.pool.write().remove_transactions_with_reason(hashes, DropReason::ConditionFailed);
+
+        let mut listener = self.event_listener.write();
+
+        removed.iter().for_each(|tx| listener.discarded(tx.hash()));
+
+        removed
+    }
+
     /// Removes and returns all transactions that are present in the pool.
     pub(crate) fn retain_unknown<A>(&self, announcement: &mut A)
     where
@@ -841,6 +1000,21 @@ where
         let _ = self.blob_store.delete_all(txs);
     }
 
+    /// Keeps the blobs of the given mined transactions until their block is finalized.
+    pub(crate) fn keep_blobs(&self, txs: Vec<TxHash>) {
+        let _ = self.blob_store.keep_all(txs);
+    }
+
+    /// Copies the blobs of finalized blocks into the blob store's static files, if enabled.
+    pub(crate) fn archive_finalized_blobs(
+        &self,
+        blocks: Vec<(BlockNumber, Vec<TxHash>)>,
+    ) -> Result<(), BlobStoreError> {
+        self.blob_store.archive_finalized(blocks).inspect_err(|err| {
+            warn!(target: "txpool", %err, "failed to archive finalized blobs");
+        })
+    }
+
     /// Cleans up the blob store
     pub(crate) fn cleanup_blobs(&self) {
         let stat = self.blob_store.cleanup();
@@ -1172,7 +1346,8 @@ mod tests {
         blobstore::{BlobStore, InMemoryBlobStore},
         test_utils::{MockTransaction, TestPoolBuilder},
         validate::ValidTransaction,
-        BlockInfo, PoolConfig, SubPoolLimit, TransactionOrigin, TransactionValidationOutcome, U256,
+        BlockInfo, DropReason, JournalEvent, PoolConfig, SubPoolLimit, TransactionConditional,
+        TransactionOrigin, TransactionValidationOutcome, U256,
     };
     use reth_primitives::{kzg::Blob, transaction::generate_blob_sidecar};
     use std::{fs, path::PathBuf};
@@ -1231,6 +1406,8 @@ mod tests {
             test_pool
                 .add_transaction(
                     TransactionOrigin::External,
+                    None,
+                    None,
                     TransactionValidationOutcome::Valid {
                         balance: U256::from(1_000),
                         state_nonce: 0,
@@ -1256,4 +1433,37 @@ mod tests {
         // Assert that the pool's blob store matches the expected blob store.
         assert_eq!(*test_pool.blob_store(), blob_store);
     }
+
+    #[test]
+    fn remove_failed_conditional_transactions() {
+        let test_pool = &TestPoolBuilder::default().pool;
+        let valid = |transaction| TransactionValidationOutcome::Valid {
+            balance: U256::from(1_000),
+            state_nonce: 0,
+            transaction: ValidTransaction::Valid(transaction),
+            propagate: true,
+        };
+
+        let tx = MockTransaction::eip1559();
+        let conditional =
+            TransactionConditional { block_number_max: Some(10), ..Default::default() };
+        let hash = test_pool
+            .add_conditional_transaction(TransactionOrigin::Local, valid(tx.next()), conditional)
+            .unwrap();
+        test_pool.add_transactions(TransactionOrigin::Local, [valid(tx)]);
+
+        let conditional = test_pool.conditional_transactions();
+        assert_eq!(conditional.len(), 1);
+        assert_eq!(*conditional[0].hash(), hash);
+        assert_eq!(conditional[0].conditional.as_ref().unwrap().block_number_max, Some(10));
+
+        let removed = test_pool.remove_failed_conditional_transactions(vec![hash]);
+        assert_eq!(removed.len(), 1);
+        assert!(test_pool.conditional_transactions().is_empty());
+        let history = test_pool.get_pool_data().journal().history(&hash).unwrap();
+        assert_eq!(
+            history.events.last().unwrap().event,
+            JournalEvent::Dropped { reason: DropReason::ConditionFailed }
+        );
+    }
 }
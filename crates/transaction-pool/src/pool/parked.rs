@@ -1,6 +1,9 @@
 use crate::{
     identifier::{SenderId, TransactionId},
-    pool::size::SizeTracker,
+    pool::{
+        eviction::{EvictionPool, SenderIndex},
+        size::SizeTracker,
+    },
     PoolTransaction, SubPoolLimit, ValidPoolTransaction, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
 };
 use rustc_hash::FxHashMap;
@@ -45,6 +48,8 @@ pub struct ParkedPool<T: ParkedOrd> {
     ///
     /// See also [`PoolTransaction::size`].
     size_of: SizeTracker,
+    /// Number and combined size of the transactions of each sender.
+    senders: SenderIndex,
 }
 
 // === impl ParkedPool ===
@@ -66,6 +71,7 @@ impl<T: ParkedOrd> ParkedPool<T> {
 
         // keep track of size
         self.size_of += tx.size();
+        self.senders.add(tx.sender_id(), tx.size());
 
         // update or create sender entry
         self.add_sender_count(tx.sender_id(), submission_id);
@@ -135,6 +141,22 @@ impl<T: ParkedOrd> ParkedPool<T> {
         self.by_id.values().map(|tx| tx.transaction.clone().into())
     }
 
+    /// Replaces the transaction with the same id by the given one, which must have the same order.
+    ///
+    /// Returns `false` if the transaction isn't in the pool.
+    pub(crate) fn replace_in_place(
+        &mut self,
+        tx: Arc<ValidPoolTransaction<T::Transaction>>,
+    ) -> bool {
+        let Some(existing) = self.by_id.get_mut(tx.id()) else { return false };
+        let replaced = existing.clone();
+        existing.transaction = tx.into();
+        if self.best.remove(&replaced) {
+            self.best.insert(existing.clone());
+        }
+        true
+    }
+
     /// Removes the transaction from the pool
     pub(crate) fn remove_transaction(
         &mut self,
@@ -147,6 +169,7 @@ impl<T: ParkedOrd> ParkedPool<T> {
 
         // keep track of size
         self.size_of -= tx.transaction.size();
+        self.senders.remove(tx.transaction.sender_id(), tx.transaction.size());
 
         Some(tx.transaction.into())
     }
@@ -333,10 +356,27 @@ impl<T: ParkedOrd> Default for ParkedPool<T> {
             last_sender_submission: Default::default(),
             sender_transaction_count: Default::default(),
             size_of: Default::default(),
+            senders: Default::default(),
         }
     }
 }
 
+impl<T: ParkedOrd> EvictionPool<T::Transaction> for ParkedPool<T> {
+    fn senders(&self) -> &SenderIndex {
+        &self.senders
+    }
+
+    fn sender_transactions_desc(
+        &self,
+        sender: SenderId,
+    ) -> impl Iterator<Item = &Arc<ValidPoolTransaction<T::Transaction>>> + '_ {
+        self.by_id
+            .range(TransactionId::new(sender, 0)..=TransactionId::new(sender, u64::MAX))
+            .rev()
+            .map(|(_, tx)| &*tx.transaction)
+    }
+}
+
 /// Keeps track of the number of transactions and the latest submission id for each sender.
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 struct SenderTransactionCount {
@@ -2,6 +2,7 @@ use crate::{
     identifier::{SenderId, TransactionId},
     pool::{
         best::{BestTransactions, BestTransactionsWithFees},
+        eviction::{EvictionPool, SenderIndex},
         size::SizeTracker,
     },
     Priority, SubPoolLimit, TransactionOrdering, ValidPoolTransaction,
@@ -50,6 +51,8 @@ pub struct PendingPool<T: TransactionOrdering> {
     ///
     /// See also [`PoolTransaction::size`](crate::traits::PoolTransaction::size).
     size_of: SizeTracker,
+    /// Number and combined size of the transactions of each sender.
+    senders: SenderIndex,
     /// Used to broadcast new transactions that have been added to the `PendingPool` to existing
     /// `static_files` of this pool.
     new_transaction_notifier: broadcast::Sender<PendingTransaction<T>>,
@@ -69,6 +72,7 @@ impl<T: TransactionOrdering> PendingPool<T> {
             independent_transactions: Default::default(),
             highest_nonces: Default::default(),
             size_of: Default::default(),
+            senders: Default::default(),
             new_transaction_notifier,
         }
     }
@@ -84,6 +88,7 @@ impl<T: TransactionOrdering> PendingPool<T> {
         self.highest_nonces.clear();
         self.all.clear();
         self.size_of.reset();
+        self.senders.clear();
         std::mem::take(&mut self.by_id)
     }
 
@@ -144,7 +149,7 @@ impl<T: TransactionOrdering> PendingPool<T> {
         for tx in unlocked {
             submission_id += 1;
             debug_assert!(!best.all.contains_key(tx.id()), "transaction already included");
-            let priority = self.ordering.priority(&tx.transaction, base_fee);
+            let priority = self.priority(&tx, base_fee);
             let tx_id = *tx.id();
             let transaction = PendingTransaction { submission_id, transaction: tx, priority };
             if best.ancestor(&tx_id).is_none() {
@@ -197,6 +202,7 @@ impl<T: TransactionOrdering> PendingPool<T> {
                 }
             } else {
                 self.size_of += tx.transaction.size();
+                self.senders.add(id.sender, tx.transaction.size());
                 self.update_independents_and_highest_nonces(&tx, &id);
                 self.all.insert(tx.clone());
                 self.by_id.insert(id, tx);
@@ -240,9 +246,10 @@ impl<T: TransactionOrdering> PendingPool<T> {
                 }
             } else {
                 // Re-insert the transaction with new priority.
-                tx.priority = self.ordering.priority(&tx.transaction.transaction, base_fee);
+                tx.priority = self.priority(&tx.transaction, base_fee);
 
                 self.size_of += tx.transaction.size();
+                self.senders.add(id.sender, tx.transaction.size());
                 self.update_independents_and_highest_nonces(&tx, &id);
                 self.all.insert(tx.clone());
                 self.by_id.insert(id, tx);
@@ -298,11 +305,12 @@ impl<T: TransactionOrdering> PendingPool<T> {
 
         // keep track of size
         self.size_of += tx.size();
+        self.senders.add(tx.sender_id(), tx.size());
 
         let tx_id = *tx.id();
 
         let submission_id = self.next_id();
-        let priority = self.ordering.priority(&tx.transaction, base_fee);
+        let priority = self.priority(&tx, base_fee);
         let tx = PendingTransaction { submission_id, transaction: tx, priority };
 
         self.update_independents_and_highest_nonces(&tx, &tx_id);
@@ -316,6 +324,27 @@ impl<T: TransactionOrdering> PendingPool<T> {
         self.by_id.insert(tx_id, tx);
     }
 
+    /// Replaces the transaction with the same id by the given one, which must have the same
+    /// priority.
+    ///
+    /// The transaction keeps its submission id, so its position in the pool is unchanged. Returns
+    /// `false` if the transaction isn't in the pool.
+    pub(crate) fn replace_in_place(
+        &mut self,
+        tx: Arc<ValidPoolTransaction<T::Transaction>>,
+    ) -> bool {
+        let Some(existing) = self.by_id.get_mut(tx.id()) else { return false };
+        let replaced = existing.clone();
+        existing.transaction = tx;
+        let tx = existing.clone();
+        for set in [&mut self.all, &mut self.independent_transactions, &mut self.highest_nonces] {
+            if set.remove(&replaced) {
+                set.insert(tx.clone());
+            }
+        }
+        true
+    }
+
     /// Removes the transaction from the pool.
     ///
     /// Note: If the transaction has a descendant transaction
@@ -330,6 +359,7 @@ impl<T: TransactionOrdering> PendingPool<T> {
         }
         let tx = self.by_id.remove(id)?;
         self.size_of -= tx.transaction.size();
+        self.senders.remove(id.sender, tx.transaction.size());
         self.all.remove(&tx);
         self.independent_transactions.remove(&tx);
 
@@ -348,6 +378,19 @@ impl<T: TransactionOrdering> PendingPool<T> {
         id
     }
 
+    /// Returns the priority of the transaction, which is [`Priority::None`] if it's deprioritized.
+    fn priority(
+        &self,
+        tx: &ValidPoolTransaction<T::Transaction>,
+        base_fee: u64,
+    ) -> Priority<T::PriorityValue> {
+        if tx.deprioritized {
+            Priority::None
+        } else {
+            self.ordering.priority(&tx.transaction, base_fee)
+        }
+    }
+
     /// Traverses the pool, starting at the highest nonce set, removing the transactions which
     /// would put the pool under the specified limits.
     ///
@@ -537,6 +580,22 @@ impl<T: TransactionOrdering> PendingPool<T> {
     }
 }
 
+impl<T: TransactionOrdering> EvictionPool<T::Transaction> for PendingPool<T> {
+    fn senders(&self) -> &SenderIndex {
+        &self.senders
+    }
+
+    fn sender_transactions_desc(
+        &self,
+        sender: SenderId,
+    ) -> impl Iterator<Item = &Arc<ValidPoolTransaction<T::Transaction>>> + '_ {
+        self.by_id
+            .range(TransactionId::new(sender, 0)..=TransactionId::new(sender, u64::MAX))
+            .rev()
+            .map(|(_, tx)| &tx.transaction)
+    }
+}
+
 /// A transaction that is ready to be included in a block.
 #[derive(Debug)]
 pub(crate) struct PendingTransaction<T: TransactionOrdering> {
@@ -619,6 +678,22 @@ mod tests {
         assert!(pool.is_empty());
     }
 
+    #[test]
+    fn test_deprioritized_ranked_last() {
+        let mut f = MockTransactionFactory::default();
+        let mut pool = PendingPool::new(MockOrdering::default());
+
+        let mut deprioritized = f.validated(MockTransaction::eip1559().inc_price_by(100));
+        deprioritized.deprioritized = true;
+        let deprioritized = Arc::new(deprioritized);
+        let tx = f.validated_arc(MockTransaction::eip1559());
+        pool.add_transaction(deprioritized.clone(), 0);
+        pool.add_transaction(tx.clone(), 0);
+
+        let best = pool.best().map(|tx| *tx.hash()).collect::<Vec<_>>();
+        assert_eq!(best, vec![*tx.hash(), *deprioritized.hash()]);
+    }
+
     #[test]
     fn test_enforce_basefee_descendant() {
         let mut f = MockTransactionFactory::default();
@@ -0,0 +1,293 @@
+//! Accounting of pool transactions against the [`SourceQuotas`] of their origin and propagating
+//! peer.
+
+use crate::{
+    config::SourceQuotas, identifier::TransactionId, PoolTransaction, SubPoolLimit,
+    TransactionOrigin, ValidPoolTransaction,
+};
+use reth_network_peers::PeerId;
+use rustc_hash::FxHashMap;
+use std::{cmp::Ordering, collections::BinaryHeap, sync::Arc};
+
+/// Number and combined size of the transactions of a source.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct SourceUsage {
+    /// Number of transactions.
+    pub(crate) txs: usize,
+    /// Combined size of the transactions.
+    pub(crate) size: usize,
+}
+
+impl SourceUsage {
+    fn add(&mut self, size: usize) {
+        self.txs += 1;
+        self.size += size;
+    }
+
+    fn sub(&mut self, size: usize) {
+        self.txs = self.txs.saturating_sub(1);
+        self.size = self.size.saturating_sub(size);
+    }
+
+    /// Returns `true` if the usage without the given evicted usage exceeds the given quota.
+    const fn exceeds(&self, evicted: &Self, quota: &SubPoolLimit) -> bool {
+        quota.is_exceeded(
+            self.txs.saturating_sub(evicted.txs),
+            self.size.saturating_sub(evicted.size),
+        )
+    }
+}
+
+/// Usage of the transactions selected for eviction, by origin and by propagating peer.
+#[derive(Debug, Default)]
+struct EvictedUsage {
+    /// Usage by origin, see [`origin_index`].
+    origins: [SourceUsage; 3],
+    /// Usage by propagating peer.
+    peers: FxHashMap<PeerId, SourceUsage>,
+}
+
+impl EvictedUsage {
+    /// Accounts a transaction that was selected for eviction.
+    fn add<T: PoolTransaction>(&mut self, tx: &ValidPoolTransaction<T>) {
+        self.origins[origin_index(tx.origin)].add(tx.size());
+        if let Some(peer_id) = tx.peer_id {
+            self.peers.entry(peer_id).or_default().add(tx.size());
+        }
+    }
+}
+
+/// Tracks the transactions in the pool by origin and by propagating peer.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SourceTracker {
+    /// The configured quotas.
+    quotas: SourceQuotas,
+    /// Usage by origin, see [`origin_index`].
+    origins: [SourceUsage; 3],
+    /// Usage by propagating peer.
+    peers: FxHashMap<PeerId, SourceUsage>,
+}
+
+impl SourceTracker {
+    /// Creates a new tracker for the given quotas.
+    pub(crate) fn new(quotas: SourceQuotas) -> Self {
+        Self { quotas, ..Default::default() }
+    }
+
+    /// Returns the usage of the given origin.
+    pub(crate) const fn origin_usage(&self, origin: TransactionOrigin) -> SourceUsage {
+        self.origins[origin_index(origin)]
+    }
+
+    /// Returns the usage of the given peer.
+    pub(crate) fn peer_usage(&self, peer_id: &PeerId) -> SourceUsage {
+        self.peers.get(peer_id).copied().unwrap_or_default()
+    }
+
+    /// Accounts a transaction that was added to the pool.
+    pub(crate) fn add<T: PoolTransaction>(&mut self, tx: &ValidPoolTransaction<T>) {
+        self.origins[origin_index(tx.origin)].add(tx.size());
+        if let Some(peer_id) = tx.peer_id {
+            self.peers.entry(peer_id).or_default().add(tx.size());
+        }
+    }
+
+    /// Accounts a transaction that was removed from the pool.
+    pub(crate) fn remove<T: PoolTransaction>(&mut self, tx: &ValidPoolTransaction<T>) {
+        self.origins[origin_index(tx.origin)].sub(tx.size());
+        if let Some(peer_id) = tx.peer_id {
+            if let Some(usage) = self.peers.get_mut(&peer_id) {
+                usage.sub(tx.size());
+                if usage.txs == 0 {
+                    self.peers.remove(&peer_id);
+                }
+            }
+        }
+    }
+
+    /// Returns the propagating peer of the transaction if adding the transaction would exceed the
+    /// peer's quota.
+    pub(crate) fn exceeded_peer_quota<T: PoolTransaction>(
+        &self,
+        tx: &ValidPoolTransaction<T>,
+    ) -> Option<PeerId> {
+        let (peer_id, quota) = tx.peer_id.zip(self.quotas.peer)?;
+        let usage = self.peer_usage(&peer_id);
+        quota.is_exceeded(usage.txs + 1, usage.size + tx.size()).then_some(peer_id)
+    }
+
+    /// Returns `true` if the origin or the propagating peer of the transaction exceed their quota,
+    /// once the already selected evictions are removed.
+    fn is_over_quota<T: PoolTransaction>(
+        &self,
+        tx: &ValidPoolTransaction<T>,
+        evicted: &EvictedUsage,
+    ) -> bool {
+        if self.quotas.origin(tx.origin).is_some_and(|quota| {
+            let evicted = &evicted.origins[origin_index(tx.origin)];
+            self.origin_usage(tx.origin).exceeds(evicted, &quota)
+        }) {
+            return true
+        }
+        tx.peer_id.zip(self.quotas.peer).is_some_and(|(peer_id, quota)| {
+            let evicted = evicted.peers.get(&peer_id).copied().unwrap_or_default();
+            self.peer_usage(&peer_id).exceeds(&evicted, &quota)
+        })
+    }
+
+    /// Returns the transactions of sources exceeding their quota to evict, until no source exceeds
+    /// its quota anymore.
+    ///
+    /// Sources are accounted across the entire pool, so the given transactions are those of all
+    /// sub-pools, grouped by sender and highest nonce first. Only the highest nonce transaction of
+    /// a sender is evicted at a time, the most recently added one first.
+    pub(crate) fn select_evictions<'a, T, I>(
+        &self,
+        senders: impl IntoIterator<Item = I>,
+    ) -> Vec<TransactionId>
+    where
+        T: PoolTransaction + 'a,
+        I: Iterator<Item = &'a Arc<ValidPoolTransaction<T>>>,
+    {
+        if self.quotas.is_empty() {
+            return Vec::new()
+        }
+
+        let mut heap = senders
+            .into_iter()
+            .filter_map(|mut txs| Some(SenderCandidate { tx: txs.next()?, txs }))
+            .collect::<BinaryHeap<_>>();
+
+        // the usage of the selected transactions
+        let mut usage = EvictedUsage::default();
+        let mut evicted = Vec::new();
+        while let Some(SenderCandidate { tx, mut txs }) = heap.pop() {
+            // usage only decreases, so a source within its quota stays within it and the sender's
+            // lower nonce transactions can't be evicted without this one
+            if !self.is_over_quota(tx, &usage) {
+                continue
+            }
+
+            usage.add(tx);
+            evicted.push(*tx.id());
+            if let Some(next) = txs.next() {
+                heap.push(SenderCandidate { tx: next, txs });
+            }
+        }
+
+        evicted
+    }
+}
+
+/// The next eviction candidate of a sender, ordered so that the most recently added candidate is
+/// the greatest.
+struct SenderCandidate<'a, T: PoolTransaction, I> {
+    /// The sender's highest nonce transaction that isn't evicted yet.
+    tx: &'a Arc<ValidPoolTransaction<T>>,
+    /// The sender's remaining transactions, highest nonce first.
+    txs: I,
+}
+
+impl<T: PoolTransaction, I> PartialEq for SenderCandidate<'_, T, I> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: PoolTransaction, I> Eq for SenderCandidate<'_, T, I> {}
+
+impl<T: PoolTransaction, I> PartialOrd for SenderCandidate<'_, T, I> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: PoolTransaction, I> Ord for SenderCandidate<'_, T, I> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.tx.timestamp.cmp(&other.tx.timestamp).then_with(|| other.tx.id().cmp(self.tx.id()))
+    }
+}
+
+/// Returns the index of the origin in [`SourceTracker::origins`].
+const fn origin_index(origin: TransactionOrigin) -> usize {
+    match origin {
+        TransactionOrigin::Local => 0,
+        TransactionOrigin::External => 1,
+        TransactionOrigin::Private => 2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{MockTransaction, MockTransactionFactory};
+
+    #[test]
+    fn tracks_usage_by_source() {
+        let mut f = MockTransactionFactory::default();
+        let quotas =
+            SourceQuotas { peer: Some(SubPoolLimit::new(2, usize::MAX)), ..Default::default() };
+        let mut tracker = SourceTracker::new(quotas);
+        let peer_id = PeerId::random();
+
+        let mut txs = (0..3)
+            .map(|_| {
+                let mut tx = f.validated(MockTransaction::eip1559().with_size(10));
+                tx.peer_id = Some(peer_id);
+                tx
+            })
+            .collect::<Vec<_>>();
+
+        tracker.add(&txs[0]);
+        assert_eq!(tracker.exceeded_peer_quota(&txs[1]), None);
+        tracker.add(&txs[1]);
+        assert_eq!(tracker.exceeded_peer_quota(&txs[2]), Some(peer_id));
+        assert_eq!(tracker.peer_usage(&peer_id), SourceUsage { txs: 2, size: 20 });
+        assert_eq!(
+            tracker.origin_usage(TransactionOrigin::External),
+            SourceUsage { txs: 2, size: 20 }
+        );
+
+        // transactions of other peers aren't affected
+        txs[2].peer_id = Some(PeerId::random());
+        assert_eq!(tracker.exceeded_peer_quota(&txs[2]), None);
+
+        tracker.remove(&txs[0]);
+        tracker.remove(&txs[1]);
+        assert_eq!(tracker.peer_usage(&peer_id), SourceUsage::default());
+        assert!(tracker.peers.is_empty());
+    }
+
+    #[test]
+    fn evicts_over_quota_origin_first() {
+        let mut f = MockTransactionFactory::default();
+        let quotas =
+            SourceQuotas { external: Some(SubPoolLimit::new(2, usize::MAX)), ..Default::default() };
+        let mut tracker = SourceTracker::new(quotas);
+
+        let local = (0..2)
+            .map(|_| {
+                Arc::new(
+                    f.validated_with_origin(TransactionOrigin::Local, MockTransaction::eip1559()),
+                )
+            })
+            .collect::<Vec<_>>();
+        let tx = MockTransaction::eip1559();
+        let external = [tx.clone(), tx.next(), tx.next().next(), tx.next().next().next()]
+            .map(|tx| f.validated_arc(tx));
+        for tx in local.iter().chain(external.iter()) {
+            tracker.add(tx);
+        }
+
+        // the external origin exceeds its quota by 2, its highest nonces are evicted first
+        let evicted = tracker.select_evictions([
+            local[..1].iter().rev(),
+            local[1..].iter().rev(),
+            external[..].iter().rev(),
+        ]);
+        assert_eq!(evicted, vec![*external[3].id(), *external[2].id()]);
+
+        // the tracker itself is untouched by the selection
+        assert_eq!(tracker.origin_usage(TransactionOrigin::External).txs, 4);
+    }
+}
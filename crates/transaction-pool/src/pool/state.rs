@@ -1,3 +1,5 @@
+pub use reth_transaction_pool_types::SubPool;
+
 bitflags::bitflags! {
     /// Marker to represents the current state of a transaction in the pool and from which the corresponding sub-pool is derived, depending on what bits are set.
     ///
@@ -72,57 +74,6 @@ impl TxState {
     }
 }
 
-/// Identifier for the transaction Sub-pool
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
-#[repr(u8)]
-pub enum SubPool {
-    /// The queued sub-pool contains transactions that are not ready to be included in the next
-    /// block because they have missing or queued ancestors or the sender the lacks funds to
-    /// execute this transaction.
-    Queued = 0,
-    /// The base-fee sub-pool contains transactions that are not ready to be included in the next
-    /// block because they don't meet the base fee requirement.
-    BaseFee,
-    /// The blob sub-pool contains all blob transactions that are __not__ pending.
-    Blob,
-    /// The pending sub-pool contains transactions that are ready to be included in the next block.
-    Pending,
-}
-
-// === impl SubPool ===
-
-impl SubPool {
-    /// Whether this transaction is to be moved to the pending sub-pool.
-    #[inline]
-    pub const fn is_pending(&self) -> bool {
-        matches!(self, Self::Pending)
-    }
-
-    /// Whether this transaction is in the queued pool.
-    #[inline]
-    pub const fn is_queued(&self) -> bool {
-        matches!(self, Self::Queued)
-    }
-
-    /// Whether this transaction is in the base fee pool.
-    #[inline]
-    pub const fn is_base_fee(&self) -> bool {
-        matches!(self, Self::BaseFee)
-    }
-
-    /// Whether this transaction is in the blob pool.
-    #[inline]
-    pub const fn is_blob(&self) -> bool {
-        matches!(self, Self::Blob)
-    }
-
-    /// Returns whether this is a promotion depending on the current sub-pool location.
-    #[inline]
-    pub fn is_promoted(&self, other: Self) -> bool {
-        self > &other
-    }
-}
-
 impl From<TxState> for SubPool {
     fn from(value: TxState) -> Self {
         if value.is_pending() {
@@ -1,24 +1,35 @@
 //! The internal transaction pool implementation.
 
 use crate::{
-    config::{LocalTransactionConfig, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER},
-    error::{Eip4844PoolTransactionError, InvalidPoolTransactionError, PoolError, PoolErrorKind},
+    config::{
+        LocalTransactionConfig, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
+        TXPOOL_MAX_INFLIGHT_DELEGATED_SLOTS,
+    },
+    error::{
+        Eip4844PoolTransactionError, Eip7702PoolTransactionError, InvalidPoolTransactionError,
+        PoolError, PoolErrorKind,
+    },
     identifier::{SenderId, TransactionId},
     metrics::{AllTransactionsMetrics, TxPoolMetrics},
     pool::{
         best::BestTransactions,
         blob::BlobTransactions,
+        eviction::select_evictions,
+        journal::{parked_reason, TransactionJournal},
         parked::{BasefeeOrd, ParkedPool, QueuedOrd},
         pending::PendingPool,
+        quota::SourceTracker,
         state::{SubPool, TxState},
         update::{Destination, PoolUpdate},
-        AddedPendingTransaction, AddedTransaction, OnNewCanonicalStateOutcome,
+        AddedPendingTransaction, AddedTransaction, DropReason, JournalEvent,
+        OnNewCanonicalStateOutcome,
     },
     traits::{BestTransactionsAttributes, BlockInfo, PoolSize},
     PoolConfig, PoolResult, PoolTransaction, PriceBumpConfig, TransactionOrdering,
     ValidPoolTransaction, U256,
 };
 use alloy_primitives::{Address, TxHash, B256};
+use reth_network_peers::PeerId;
 use reth_primitives::{
     constants::{
         eip4844::BLOB_TX_MIN_BLOB_GASPRICE, ETHEREUM_BLOCK_GAS_LIMIT, MIN_PROTOCOL_BASE_FEE,
@@ -73,6 +84,8 @@ pub struct TxPool<T: TransactionOrdering> {
     blob_pool: BlobTransactions<T::Transaction>,
     /// All transactions in the pool.
     all_transactions: AllTransactions<T::Transaction>,
+    /// Records the state transitions of transactions.
+    journal: TransactionJournal,
     /// Transaction pool metrics
     metrics: TxPoolMetrics,
 }
@@ -89,11 +102,17 @@ impl<T: TransactionOrdering> TxPool<T> {
             basefee_pool: Default::default(),
             blob_pool: Default::default(),
             all_transactions: AllTransactions::new(&config),
+            journal: TransactionJournal::new(config.max_journal_transactions),
             config,
             metrics: Default::default(),
         }
     }
 
+    /// Returns the journal of the pool's transactions.
+    pub const fn journal(&self) -> &TransactionJournal {
+        &self.journal
+    }
+
     /// Retrieves the highest nonce for a specific sender from the transaction pool.
     pub fn get_highest_nonce_by_sender(&self, sender: SenderId) -> Option<u64> {
         self.all().txs_iter(sender).last().map(|(_, tx)| tx.transaction.nonce())
@@ -413,11 +432,15 @@ impl<T: TransactionOrdering> TxPool<T> {
     ///
     /// This removes all mined transactions, updates according to the new base fee and rechecks
     /// sender allowance.
+    ///
+    /// Senders that were delegated by an EIP-7702 authorization in the new block(s) are trimmed to
+    /// the in-flight limit of delegated accounts, in place.
     pub(crate) fn on_canonical_state_change(
         &mut self,
         block_info: BlockInfo,
         mined_transactions: Vec<TxHash>,
         changed_senders: HashMap<SenderId, SenderInfo>,
+        delegated_senders: Vec<SenderId>,
     ) -> OnNewCanonicalStateOutcome<T::Transaction> {
         // update block info
         let block_hash = block_info.last_seen_block_hash;
@@ -425,13 +448,25 @@ impl<T: TransactionOrdering> TxPool<T> {
 
         // Remove all transaction that were included in the block
         for tx_hash in &mined_transactions {
-            if self.prune_transaction_by_hash(tx_hash).is_some() {
+            if let Some(tx) = self.prune_transaction_by_hash(tx_hash) {
+                self.journal.record(&tx, JournalEvent::Mined { block_hash });
                 // Update removed transactions metric
                 self.metrics.removed_transactions.increment(1);
             }
         }
 
-        let UpdateOutcome { promoted, discarded } = self.update_accounts(changed_senders);
+        let UpdateOutcome { mut promoted, mut discarded } = self.update_accounts(changed_senders);
+
+        let mut exceeding = Vec::new();
+        for sender in delegated_senders {
+            exceeding.extend(self.discard_exceeding_delegated_txs(sender));
+        }
+        if !exceeding.is_empty() {
+            // transactions promoted by the account update may have been discarded again
+            let exceeding_hashes = exceeding.iter().map(|tx| *tx.hash()).collect::<HashSet<_>>();
+            promoted.retain(|tx| !exceeding_hashes.contains(tx.hash()));
+            discarded.extend(exceeding);
+        }
 
         self.update_transaction_type_metrics();
         self.metrics.performed_state_updates.increment(1);
@@ -439,6 +474,36 @@ impl<T: TransactionOrdering> TxPool<T> {
         OnNewCanonicalStateOutcome { block_hash, mined: mined_transactions, promoted, discarded }
     }
 
+    /// Removes all transactions of the delegated sender that exceed the in-flight limit of
+    /// delegated accounts.
+    fn discard_exceeding_delegated_txs(
+        &mut self,
+        sender: SenderId,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        let Some(state_nonce) = self.sender_info.get(&sender).map(|info| info.state_nonce) else {
+            return Vec::new()
+        };
+        let limit = state_nonce.saturating_add(self.config.max_inflight_delegated_slots as u64);
+        let exceeding = self
+            .all_transactions
+            .txs_iter(sender)
+            .filter(|(id, _)| id.nonce >= limit)
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+
+        let mut discarded = Vec::with_capacity(exceeding.len());
+        for id in exceeding {
+            if let Some(tx) = self.remove_transaction(&id) {
+                self.metrics.removed_transactions.increment(1);
+                self.journal
+                    .record(&tx, JournalEvent::Dropped { reason: DropReason::DelegationLimit });
+                discarded.push(tx);
+            }
+        }
+        self.update_size_metrics();
+        discarded
+    }
+
     /// Update sub-pools size metrics.
     pub(crate) fn update_size_metrics(&self) {
         let stats = self.size();
@@ -514,12 +579,22 @@ impl<T: TransactionOrdering> TxPool<T> {
             return Err(PoolError::new(*tx.hash(), PoolErrorKind::AlreadyImported))
         }
 
+        if let Err(err) = self.all_transactions.ensure_valid_authorizations(&tx, on_chain_nonce) {
+            self.metrics.invalid_transactions.increment(1);
+            self.journal.record(&tx, JournalEvent::Rejected { error: err.to_string() });
+            return Err(PoolError::new(
+                *tx.hash(),
+                PoolErrorKind::InvalidTransaction(InvalidPoolTransactionError::Eip7702(err)),
+            ))
+        }
+
         // Update sender info with balance and nonce
         self.sender_info
             .entry(tx.sender_id())
             .or_default()
             .update(on_chain_nonce, on_chain_balance);
 
+        let (sender, nonce) = (tx.sender(), tx.nonce());
         match self.all_transactions.insert_tx(tx, on_chain_balance, on_chain_nonce) {
             Ok(InsertOk { transaction, move_to, replaced_tx, updates, .. }) => {
                 // replace the new tx and remove the replaced in the subpool(s)
@@ -550,28 +625,31 @@ impl<T: TransactionOrdering> TxPool<T> {
             Err(err) => {
                 // Update invalid transactions metric
                 self.metrics.invalid_transactions.increment(1);
-                match err {
-                    InsertErr::Underpriced { existing: _, transaction } => Err(PoolError::new(
-                        *transaction.hash(),
-                        PoolErrorKind::ReplacementUnderpriced,
-                    )),
+                let err = match err {
+                    InsertErr::Underpriced { existing: _, transaction } => {
+                        PoolError::new(*transaction.hash(), PoolErrorKind::ReplacementUnderpriced)
+                    }
                     InsertErr::FeeCapBelowMinimumProtocolFeeCap { transaction, fee_cap } => {
-                        Err(PoolError::new(
+                        PoolError::new(
                             *transaction.hash(),
                             PoolErrorKind::FeeCapBelowMinimumProtocolFeeCap(fee_cap),
-                        ))
+                        )
                     }
                     InsertErr::ExceededSenderTransactionsCapacity { transaction } => {
-                        Err(PoolError::new(
+                        PoolError::new(
                             *transaction.hash(),
                             PoolErrorKind::SpammerExceededCapacity(transaction.sender()),
-                        ))
+                        )
                     }
+                    InsertErr::ExceededPeerQuota { transaction, peer_id } => PoolError::new(
+                        *transaction.hash(),
+                        PoolErrorKind::ExceededPeerQuota(peer_id),
+                    ),
                     InsertErr::TxGasLimitMoreThanAvailableBlockGas {
                         transaction,
                         block_gas_limit,
                         tx_gas_limit,
-                    } => Err(PoolError::new(
+                    } => PoolError::new(
                         *transaction.hash(),
                         PoolErrorKind::InvalidTransaction(
                             InvalidPoolTransactionError::ExceedsGasLimit(
@@ -579,28 +657,35 @@ impl<T: TransactionOrdering> TxPool<T> {
                                 tx_gas_limit,
                             ),
                         ),
-                    )),
-                    InsertErr::BlobTxHasNonceGap { transaction } => Err(PoolError::new(
+                    ),
+                    InsertErr::BlobTxHasNonceGap { transaction } => PoolError::new(
                         *transaction.hash(),
                         PoolErrorKind::InvalidTransaction(
                             Eip4844PoolTransactionError::Eip4844NonceGap.into(),
                         ),
-                    )),
-                    InsertErr::Overdraft { transaction } => Err(PoolError::new(
+                    ),
+                    InsertErr::Overdraft { transaction } => PoolError::new(
                         *transaction.hash(),
                         PoolErrorKind::InvalidTransaction(InvalidPoolTransactionError::Overdraft {
                             cost: transaction.cost(),
                             balance: on_chain_balance,
                         }),
-                    )),
-                    InsertErr::TxTypeConflict { transaction } => Err(PoolError::new(
+                    ),
+                    InsertErr::TxTypeConflict { transaction } => PoolError::new(
                         *transaction.hash(),
                         PoolErrorKind::ExistingConflictingTransactionType(
                             transaction.sender(),
                             transaction.tx_type(),
                         ),
-                    )),
-                }
+                    ),
+                };
+                self.journal.record_event(
+                    err.hash,
+                    sender,
+                    nonce,
+                    JournalEvent::Rejected { error: err.kind.to_string() },
+                );
+                Err(err)
             }
         }
     }
@@ -615,6 +700,8 @@ impl<T: TransactionOrdering> TxPool<T> {
                 Destination::Discard => {
                     // remove the transaction from the pool and subpool
                     if let Some(tx) = self.prune_transaction_by_hash(&hash) {
+                        self.journal
+                            .record(&tx, JournalEvent::Dropped { reason: DropReason::NonceTooLow });
                         outcome.discarded.push(tx);
                     }
                     self.metrics.removed_transactions.increment(1);
@@ -657,8 +744,53 @@ impl<T: TransactionOrdering> TxPool<T> {
         &mut self,
         hashes: Vec<TxHash>,
     ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
-        let txs =
-            hashes.into_iter().filter_map(|hash| self.remove_transaction_by_hash(&hash)).collect();
+        self.remove_transactions_with_reason(hashes, DropReason::Removed)
+    }
+
+    /// Allows the transaction with the given hash to be propagated, without moving it within the
+    /// pool.
+    ///
+    /// Returns the updated transaction and the sub-pool it resides in, if it is in the pool.
+    pub(crate) fn allow_propagation(
+        &mut self,
+        hash: &TxHash,
+    ) -> Option<(Arc<ValidPoolTransaction<T::Transaction>>, SubPool)> {
+        let tx = self.all_transactions.by_hash.get(hash)?;
+        let subpool = self.all_transactions.txs.get(tx.id())?.subpool;
+        if tx.propagate {
+            return Some((tx.clone(), subpool))
+        }
+
+        let tx = Arc::new(tx.to_propagatable());
+        self.all_transactions.by_hash.insert(*hash, tx.clone());
+        if let Some(internal) = self.all_transactions.txs.get_mut(tx.id()) {
+            internal.transaction = tx.clone();
+        }
+        match subpool {
+            SubPool::Queued => self.queued_pool.replace_in_place(tx.clone()),
+            SubPool::Pending => self.pending_pool.replace_in_place(tx.clone()),
+            SubPool::BaseFee => self.basefee_pool.replace_in_place(tx.clone()),
+            SubPool::Blob => self.blob_pool.replace_in_place(tx.clone()),
+        };
+        Some((tx, subpool))
+    }
+
+    /// Removes and returns all matching transactions from the pool, recording the given reason in
+    /// the journal.
+    ///
+    /// See also [`Self::remove_transactions`].
+    pub(crate) fn remove_transactions_with_reason(
+        &mut self,
+        hashes: Vec<TxHash>,
+        reason: DropReason,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        let txs = hashes
+            .into_iter()
+            .filter_map(|hash| self.remove_transaction_by_hash(&hash))
+            .collect::<Vec<_>>();
+        for tx in &txs {
+            self.journal.record(tx, JournalEvent::Dropped { reason });
+        }
         self.update_size_metrics();
         txs
     }
@@ -782,6 +914,13 @@ impl<T: TransactionOrdering> TxPool<T> {
         // generic and it would not be possible to distinguish whether a transaction is being
         // added to the `BaseFee` pool, or the `Queued` pool.
         trace!(target: "txpool", hash=%tx.transaction.hash(), ?pool, "Adding transaction to a subpool");
+        let event = if pool.is_pending() {
+            JournalEvent::Pending
+        } else {
+            let state = self.all_transactions.get(tx.id()).map(|tx| tx.state).unwrap_or_default();
+            JournalEvent::Parked { subpool: pool, reason: parked_reason(state) }
+        };
+        self.journal.record(&tx, event);
         match pool {
             SubPool::Queued => self.queued_pool.add_transaction(tx),
             SubPool::Pending => {
@@ -803,6 +942,7 @@ impl<T: TransactionOrdering> TxPool<T> {
         if let Some((replaced, replaced_pool)) = replaced {
             // Remove the replaced transaction
             self.remove_from_subpool(replaced_pool, replaced.id());
+            self.journal.record(&replaced, JournalEvent::Replaced { by: *transaction.hash() });
         }
 
         self.add_transaction_to_subpool(pool, transaction)
@@ -811,7 +951,8 @@ impl<T: TransactionOrdering> TxPool<T> {
     /// Ensures that the transactions in the sub-pools are within the given bounds.
     ///
     /// If the current size exceeds the given bounds, the worst transactions are evicted from the
-    /// pool and returned.
+    /// pool and returned. The worst transactions are picked by the configured
+    /// [`EvictionStrategy`](crate::EvictionStrategy), if any.
     ///
     /// This returns all transactions that were removed from the entire pool.
     pub(crate) fn discard_worst(&mut self) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
@@ -819,7 +960,7 @@ impl<T: TransactionOrdering> TxPool<T> {
 
         // Helper macro that discards the worst transactions for the pools
         macro_rules! discard_worst {
-            ($this:ident, $removed:ident, [$($limit:ident => $pool:ident),* $(,)*]) => {
+            ($this:ident, $removed:ident, [$($limit:ident => ($pool:ident, $subpool:expr)),* $(,)*]) => {
                 $ (
                 while $this.$pool.exceeds(&$this.config.$limit)
                     {
@@ -832,8 +973,38 @@ impl<T: TransactionOrdering> TxPool<T> {
                             $this.$pool.len(),
                         );
 
-                        // 1. first remove the worst transaction from the subpool
-                        let removed_from_subpool = $this.$pool.truncate_pool($this.config.$limit.clone());
+                        // 1. first remove the worst transactions from the subpools: transactions of
+                        // sources that exceed their quota from any subpool, since sources are
+                        // accounted across the entire pool, otherwise by the configured eviction
+                        // strategy or by the subpool's own ordering
+                        let over_quota = $this.all_transactions.select_over_quota_evictions();
+                        let (removed_from_subpool, reason) = if !over_quota.is_empty() {
+                            let removed = over_quota
+                                .iter()
+                                .filter_map(|id| {
+                                    let subpool = $this.all_transactions.get(id)?.subpool;
+                                    $this.remove_from_subpool(subpool, id)
+                                })
+                                .collect::<Vec<_>>();
+                            (removed, DropReason::QuotaExceeded)
+                        } else {
+                            let removed = match $this.config.eviction_strategy.clone() {
+                                Some(strategy) => {
+                                    let evicted = select_evictions(
+                                        &*strategy,
+                                        &$this.$pool,
+                                        $this.all_transactions.pending_fees.base_fee,
+                                        &$this.config.$limit,
+                                    );
+                                    evicted
+                                        .iter()
+                                        .filter_map(|id| $this.$pool.remove_transaction(id))
+                                        .collect::<Vec<_>>()
+                                }
+                                None => $this.$pool.truncate_pool($this.config.$limit.clone()),
+                            };
+                            (removed, DropReason::SizeLimit($subpool))
+                        };
 
                         trace!(
                             target: "txpool",
@@ -850,12 +1021,23 @@ impl<T: TransactionOrdering> TxPool<T> {
                             $this.all_transactions.remove_transaction(tx.id());
 
                             let id = *tx.id();
+                            $this.journal.record(
+                                &tx,
+                                JournalEvent::Dropped { reason },
+                            );
 
                             // keep track of removed transaction
                             removed.push(tx);
 
                             // 3. remove all its descendants from the entire pool
+                            let descendants = $removed.len();
                             $this.remove_descendants(&id, &mut $removed);
+                            for tx in &$removed[descendants..] {
+                                $this.journal.record(
+                                    tx,
+                                    JournalEvent::Dropped { reason: DropReason::AncestorDropped },
+                                );
+                            }
                         }
                     }
 
@@ -865,10 +1047,10 @@ impl<T: TransactionOrdering> TxPool<T> {
 
         discard_worst!(
             self, removed, [
-                pending_limit => pending_pool,
-                basefee_limit => basefee_pool,
-                blob_limit    => blob_pool,
-                queued_limit  => queued_pool,
+                pending_limit => (pending_pool, SubPool::Pending),
+                basefee_limit => (basefee_pool, SubPool::BaseFee),
+                blob_limit    => (blob_pool, SubPool::Blob),
+                queued_limit  => (queued_pool, SubPool::Queued),
             ]
         );
 
@@ -956,12 +1138,18 @@ pub(crate) struct AllTransactions<T: PoolTransaction> {
     block_gas_limit: u64,
     /// Max number of executable transaction slots guaranteed per account
     max_account_slots: usize,
+    /// Max number of in-flight transactions of accounts with an EIP-7702 delegation
+    max_inflight_delegated_slots: usize,
     /// _All_ transactions identified by their hash.
     by_hash: HashMap<TxHash, Arc<ValidPoolTransaction<T>>>,
     /// _All_ transaction in the pool sorted by their sender and nonce pair.
     txs: BTreeMap<TransactionId, PoolInternalTransaction<T>>,
     /// Tracks the number of transactions by sender that are currently in the pool.
     tx_counter: FxHashMap<SenderId, usize>,
+    /// Hashes of the pooled transactions carrying an EIP-7702 authorization, by authority.
+    auths: FxHashMap<SenderId, HashSet<TxHash>>,
+    /// Tracks the transactions by origin and propagating peer.
+    sources: SourceTracker,
     /// The current block number the pool keeps track of.
     last_seen_block_number: u64,
     /// The current block hash the pool keeps track of.
@@ -981,10 +1169,12 @@ impl<T: PoolTransaction> AllTransactions<T> {
     fn new(config: &PoolConfig) -> Self {
         Self {
             max_account_slots: config.max_account_slots,
+            max_inflight_delegated_slots: config.max_inflight_delegated_slots,
             price_bumps: config.price_bumps,
             local_transactions_config: config.local_transactions_config.clone(),
             minimal_protocol_basefee: config.minimal_protocol_basefee,
             block_gas_limit: config.gas_limit,
+            sources: SourceTracker::new(config.quotas),
             ..Default::default()
         }
     }
@@ -1033,6 +1223,25 @@ impl<T: PoolTransaction> AllTransactions<T> {
         }
     }
 
+    /// Tracks the authorities of the given transaction.
+    fn add_auths(&mut self, tx: &ValidPoolTransaction<T>) {
+        for authority in tx.authority_ids.iter().flatten() {
+            self.auths.entry(*authority).or_default().insert(*tx.hash());
+        }
+    }
+
+    /// Removes the tracked authorities of the given transaction.
+    fn remove_auths(&mut self, tx: &ValidPoolTransaction<T>) {
+        for authority in tx.authority_ids.iter().flatten() {
+            if let hash_map::Entry::Occupied(mut entry) = self.auths.entry(*authority) {
+                entry.get_mut().remove(tx.hash());
+                if entry.get().is_empty() {
+                    entry.remove();
+                }
+            }
+        }
+    }
+
     /// Updates the block specific info
     fn set_block_info(&mut self, block_info: BlockInfo) {
         let BlockInfo {
@@ -1257,6 +1466,18 @@ impl<T: PoolTransaction> AllTransactions<T> {
             .take_while(move |(other, _)| sender == other.sender)
     }
 
+    /// Returns the transactions of sources exceeding their quota to evict from the entire pool.
+    ///
+    /// See [`SourceTracker::select_evictions`].
+    pub(crate) fn select_over_quota_evictions(&self) -> Vec<TransactionId> {
+        self.sources.select_evictions(self.tx_counter.keys().map(|sender| {
+            self.txs
+                .range(TransactionId::new(*sender, 0)..=TransactionId::new(*sender, u64::MAX))
+                .rev()
+                .map(|(_, tx)| &tx.transaction)
+        }))
+    }
+
     /// Returns a mutable iterator over all transactions for the given sender, starting with the
     /// lowest nonce
     #[cfg(test)]
@@ -1311,6 +1532,8 @@ impl<T: PoolTransaction> AllTransactions<T> {
         let internal = self.txs.remove(&tx.transaction_id)?;
         // decrement the counter for the sender.
         self.tx_decr(tx.sender_id());
+        self.remove_auths(&tx);
+        self.sources.remove(&tx);
         self.update_size_metrics();
         Some((tx, internal.subpool))
     }
@@ -1328,6 +1551,8 @@ impl<T: PoolTransaction> AllTransactions<T> {
 
         // decrement the counter for the sender.
         self.tx_decr(internal.transaction.sender_id());
+        self.remove_auths(&internal.transaction);
+        self.sources.remove(&internal.transaction);
 
         let result =
             self.by_hash.remove(internal.transaction.hash()).map(|tx| (tx, internal.subpool));
@@ -1364,7 +1589,9 @@ impl<T: PoolTransaction> AllTransactions<T> {
         &self,
         transaction: ValidPoolTransaction<T>,
     ) -> Result<ValidPoolTransaction<T>, InsertErr<T>> {
-        if !self.local_transactions_config.is_local(transaction.origin, transaction.sender()) {
+        if transaction.conditional.is_some() ||
+            !self.local_transactions_config.is_local(transaction.origin, transaction.sender())
+        {
             let current_txs =
                 self.tx_counter.get(&transaction.sender_id()).copied().unwrap_or_default();
             if current_txs >= self.max_account_slots {
@@ -1373,6 +1600,15 @@ impl<T: PoolTransaction> AllTransactions<T> {
                 })
             }
         }
+        if !self.txs.contains_key(transaction.id()) {
+            // replacements don't change the number of transactions of a peer
+            if let Some(peer_id) = self.sources.exceeded_peer_quota(&transaction) {
+                return Err(InsertErr::ExceededPeerQuota {
+                    transaction: Arc::new(transaction),
+                    peer_id,
+                })
+            }
+        }
         if transaction.gas_limit() > self.block_gas_limit {
             return Err(InsertErr::TxGasLimitMoreThanAvailableBlockGas {
                 block_gas_limit: self.block_gas_limit,
@@ -1389,6 +1625,39 @@ impl<T: PoolTransaction> AllTransactions<T> {
         Ok(transaction)
     }
 
+    /// Enforces the [EIP-7702](https://eips.ethereum.org/EIPS/eip-7702) rules of the pool:
+    ///   - Senders delegated by a pooled authorization may only have `max_inflight_delegated_slots`
+    ///     gapless transactions in the pool. Replacements are always allowed. Senders delegated on
+    ///     chain are limited by the validator.
+    ///   - Authorizations must not delegate accounts that have pending transactions in the pool.
+    pub(crate) fn ensure_valid_authorizations(
+        &self,
+        transaction: &ValidPoolTransaction<T>,
+        on_chain_nonce: u64,
+    ) -> Result<(), Eip7702PoolTransactionError> {
+        let sender = transaction.sender_id();
+        if self.auths.contains_key(&sender) && !self.txs.contains_key(transaction.id()) {
+            let current_txs = self.tx_counter.get(&sender).copied().unwrap_or_default();
+            if current_txs >= self.max_inflight_delegated_slots {
+                return Err(Eip7702PoolTransactionError::InflightTxLimitReached)
+            }
+            if transaction.nonce() > on_chain_nonce + current_txs as u64 {
+                return Err(Eip7702PoolTransactionError::OutOfOrderTxFromDelegated)
+            }
+        }
+
+        for authority in transaction.authority_ids.iter().flatten() {
+            if *authority == sender {
+                continue
+            }
+            if let Some((_, tx)) = self.txs_iter(*authority).next() {
+                return Err(Eip7702PoolTransactionError::AuthorityReserved(tx.transaction.sender()))
+            }
+        }
+
+        Ok(())
+    }
+
     /// Enforces additional constraints for blob transactions before attempting to insert:
     ///    - new blob transactions must not have any nonce gaps
     ///    - blob transactions cannot go into overdraft
@@ -1626,6 +1895,13 @@ impl<T: PoolTransaction> AllTransactions<T> {
             }
         }
 
+        if let Some((replaced, _)) = &replaced_tx {
+            self.remove_auths(replaced);
+            self.sources.remove(replaced);
+        }
+        self.add_auths(&transaction);
+        self.sources.add(&transaction);
+
         // The next transaction of this sender
         let on_chain_id = TransactionId::new(transaction.sender_id(), on_chain_nonce);
         {
@@ -1747,11 +2023,14 @@ impl<T: PoolTransaction> Default for AllTransactions<T> {
     fn default() -> Self {
         Self {
             max_account_slots: TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
+            max_inflight_delegated_slots: TXPOOL_MAX_INFLIGHT_DELEGATED_SLOTS,
             minimal_protocol_basefee: MIN_PROTOCOL_BASE_FEE,
             block_gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
             by_hash: Default::default(),
             txs: Default::default(),
             tx_counter: Default::default(),
+            auths: Default::default(),
+            sources: Default::default(),
             last_seen_block_number: Default::default(),
             last_seen_block_hash: Default::default(),
             pending_fees: Default::default(),
@@ -1802,6 +2081,8 @@ pub(crate) enum InsertErr<T: PoolTransaction> {
     ///
     /// The sender can be considered a spammer at this point.
     ExceededSenderTransactionsCapacity { transaction: Arc<ValidPoolTransaction<T>> },
+    /// The peer that propagated the transaction reached its quota.
+    ExceededPeerQuota { transaction: Arc<ValidPoolTransaction<T>>, peer_id: PeerId },
     /// Transaction gas limit exceeds block's gas limit
     TxGasLimitMoreThanAvailableBlockGas {
         transaction: Arc<ValidPoolTransaction<T>>,
@@ -1896,7 +2177,7 @@ mod tests {
     use crate::{
         test_utils::{MockOrdering, MockTransaction, MockTransactionFactory, MockTransactionSet},
         traits::TransactionOrigin,
-        SubPoolLimit,
+        SourceQuotas, SubPoolLimit,
     };
 
     #[test]
@@ -2889,6 +3170,174 @@ mod tests {
         }
     }
 
+    #[test]
+    fn discard_by_eviction_strategy() {
+        let mut f = MockTransactionFactory::default();
+        let pending_limit = SubPoolLimit::new(2, usize::MAX);
+        let mut pool = TxPool::new(
+            MockOrdering::default(),
+            PoolConfig {
+                pending_limit,
+                eviction_strategy: Some(Arc::new(crate::EffectiveTipEviction)),
+                ..Default::default()
+            },
+        );
+
+        let valuable =
+            f.validated(MockTransaction::eip1559().with_max_fee(100).with_priority_fee(100));
+        let valuable_hash = *valuable.hash();
+        pool.add_transaction(valuable, U256::from(1_000), 0).unwrap();
+
+        // a burst of low tip transactions from distinct senders
+        for _ in 0..10 {
+            let tx = f.validated(MockTransaction::eip1559().with_max_fee(1).with_priority_fee(1));
+            pool.add_transaction(tx, U256::from(1_000), 0).unwrap();
+
+            let removed = pool.discard_worst();
+            pool.assert_invariants();
+            assert!(pool.size().pending <= pending_limit.max_txs);
+            assert!(removed.iter().all(|tx| *tx.hash() != valuable_hash));
+        }
+
+        assert!(pool.contains(&valuable_hash));
+        assert_eq!(pool.size().pending, pending_limit.max_txs);
+    }
+
+    #[test]
+    fn reject_transactions_exceeding_peer_quota() {
+        let mut f = MockTransactionFactory::default();
+        let quotas =
+            SourceQuotas { peer: Some(SubPoolLimit::new(2, usize::MAX)), ..Default::default() };
+        let mut pool =
+            TxPool::new(MockOrdering::default(), PoolConfig { quotas, ..Default::default() });
+        let peer_id = PeerId::random();
+
+        let mut txs = (0..3)
+            .map(|_| {
+                let mut tx = f.validated(MockTransaction::eip1559());
+                tx.peer_id = Some(peer_id);
+                tx
+            })
+            .collect::<Vec<_>>();
+        let first = *txs[0].hash();
+        let last = txs.pop().unwrap();
+
+        for tx in txs {
+            pool.add_transaction(tx, U256::from(1_000), 0).unwrap();
+        }
+        let err = pool.add_transaction(last.clone(), U256::from(1_000), 0).unwrap_err();
+        assert!(matches!(err.kind, PoolErrorKind::ExceededPeerQuota(id) if id == peer_id));
+
+        // removed transactions free up the quota
+        pool.remove_transactions(vec![first]);
+        pool.add_transaction(last, U256::from(1_000), 0).unwrap();
+        pool.assert_invariants();
+    }
+
+    #[test]
+    fn discard_over_quota_origin_first() {
+        let mut f = MockTransactionFactory::default();
+        let queued_limit = SubPoolLimit::new(3, usize::MAX);
+        let quotas =
+            SourceQuotas { external: Some(SubPoolLimit::new(1, usize::MAX)), ..Default::default() };
+        let mut pool = TxPool::new(
+            MockOrdering::default(),
+            PoolConfig { queued_limit, quotas, ..Default::default() },
+        );
+
+        // queued transactions, because of their nonce gap
+        for _ in 0..2 {
+            let tx = f.validated_with_origin(
+                TransactionOrigin::Local,
+                MockTransaction::eip1559().inc_nonce(),
+            );
+            pool.add_transaction(tx, U256::from(1_000), 0).unwrap();
+        }
+        let external =
+            [MockTransaction::eip1559().inc_nonce(), MockTransaction::eip1559().inc_nonce()]
+                .map(|tx| f.validated(tx));
+        let newest = *external[1].hash();
+        for tx in external {
+            pool.add_transaction(tx, U256::from(1_000), 0).unwrap();
+        }
+        assert_eq!(pool.size().queued, 4);
+
+        let removed = pool.discard_worst();
+        assert_eq!(removed.len(), 1);
+        assert_eq!(*removed[0].hash(), newest);
+        assert_eq!(
+            pool.journal().history(&newest).unwrap().events.last().unwrap().event,
+            JournalEvent::Dropped { reason: DropReason::QuotaExceeded }
+        );
+        pool.assert_invariants();
+    }
+
+    #[test]
+    fn allow_propagation_in_place() {
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+
+        // the second transaction is queued because of its nonce gap
+        let tx = MockTransaction::eip1559();
+        let txs = [tx.clone(), tx.next().inc_nonce()];
+        let [pending, queued] = txs.clone().map(|tx| *tx.get_hash());
+        for tx in txs {
+            pool.add_transaction(f.validated(tx), U256::MAX, 0).unwrap();
+        }
+        assert!(!pool.get(&pending).unwrap().propagate);
+
+        for (hash, subpool) in [(pending, SubPool::Pending), (queued, SubPool::Queued)] {
+            let (tx, in_subpool) = pool.allow_propagation(&hash).unwrap();
+            assert!(tx.propagate);
+            assert_eq!(in_subpool, subpool);
+            assert!(pool.get(&hash).unwrap().propagate);
+        }
+        assert!(pool.pending_transactions()[0].propagate);
+        assert!(pool.queued_transactions()[0].propagate);
+        assert!(pool.allow_propagation(&TxHash::random()).is_none());
+        pool.assert_invariants();
+    }
+
+    #[test]
+    fn discard_over_quota_across_subpools() {
+        let mut f = MockTransactionFactory::default();
+        let queued_limit = SubPoolLimit::new(2, usize::MAX);
+        let quotas =
+            SourceQuotas { external: Some(SubPoolLimit::new(1, usize::MAX)), ..Default::default() };
+        let mut pool = TxPool::new(
+            MockOrdering::default(),
+            PoolConfig { queued_limit, quotas, ..Default::default() },
+        );
+
+        for _ in 0..2 {
+            let tx = f.validated_with_origin(
+                TransactionOrigin::Local,
+                MockTransaction::eip1559().inc_nonce(),
+            );
+            pool.add_transaction(tx, U256::from(1_000), 0).unwrap();
+        }
+        let queued = f.validated(MockTransaction::eip1559().inc_nonce());
+        pool.add_transaction(queued, U256::from(1_000), 0).unwrap();
+        // the most recent transaction of the external origin is pending
+        let pending = f.validated(MockTransaction::eip1559());
+        let pending_hash = *pending.hash();
+        pool.add_transaction(pending, U256::from(1_000), 0).unwrap();
+        assert_eq!(pool.size().queued, 3);
+        assert_eq!(pool.size().pending, 1);
+
+        // the external origin is trimmed in the pending pool, then the queued pool by its limit
+        let removed = pool.discard_worst();
+        assert_eq!(removed.len(), 2);
+        assert_eq!(*removed[0].hash(), pending_hash);
+        assert_eq!(
+            pool.journal().history(&pending_hash).unwrap().events.last().unwrap().event,
+            JournalEvent::Dropped { reason: DropReason::QuotaExceeded }
+        );
+        assert_eq!(pool.size().pending, 0);
+        assert_eq!(pool.size().queued, 2);
+        pool.assert_invariants();
+    }
+
     #[test]
     fn account_updates_nonce_gap() {
         let on_chain_balance = U256::from(10_000);
@@ -3023,4 +3472,146 @@ mod tests {
             vec![1, 2, 3]
         );
     }
+
+    #[test]
+    fn reject_authorization_of_pending_sender() {
+        let on_chain_balance = U256::from(1_000);
+        let on_chain_nonce = 0;
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+
+        let pending = MockTransaction::eip1559();
+        pool.add_transaction(f.validated(pending.clone()), on_chain_balance, on_chain_nonce)
+            .unwrap();
+
+        let mut delegating = f.validated(MockTransaction::eip1559());
+        delegating.authority_ids = Some(vec![f.ids.sender_id_or_create(pending.sender())]);
+        let err = pool.add_transaction(delegating, on_chain_balance, on_chain_nonce).unwrap_err();
+        assert!(matches!(
+            err.kind,
+            PoolErrorKind::InvalidTransaction(InvalidPoolTransactionError::Eip7702(
+                Eip7702PoolTransactionError::AuthorityReserved(addr)
+            )) if addr == pending.sender()
+        ));
+    }
+
+    #[test]
+    fn pooled_authorization_limits_authority() {
+        let on_chain_balance = U256::from(1_000);
+        let on_chain_nonce = 0;
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+
+        let authority = MockTransaction::eip1559();
+        let mut delegating = f.validated(MockTransaction::eip1559());
+        delegating.authority_ids = Some(vec![f.ids.sender_id_or_create(authority.sender())]);
+        let delegating_hash = *delegating.hash();
+        pool.add_transaction(delegating, on_chain_balance, on_chain_nonce).unwrap();
+
+        // the authority is treated as delegated while the authorization is pending
+        pool.add_transaction(f.validated(authority.clone()), on_chain_balance, on_chain_nonce)
+            .unwrap();
+        let err = pool
+            .add_transaction(f.validated(authority.next()), on_chain_balance, on_chain_nonce)
+            .unwrap_err();
+        assert!(matches!(
+            err.kind,
+            PoolErrorKind::InvalidTransaction(InvalidPoolTransactionError::Eip7702(
+                Eip7702PoolTransactionError::InflightTxLimitReached
+            ))
+        ));
+
+        // once the authorization is gone, the authority is no longer limited
+        pool.remove_transactions(vec![delegating_hash]);
+        pool.add_transaction(f.validated(authority.next()), on_chain_balance, on_chain_nonce)
+            .unwrap();
+    }
+
+    #[test]
+    fn discard_exceeding_txs_of_delegated_sender() {
+        let on_chain_balance = U256::from(1_000);
+        let on_chain_nonce = 0;
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+
+        let tx = MockTransaction::eip1559();
+        for tx in [tx.clone(), tx.next(), tx.next().next()] {
+            pool.add_transaction(f.validated(tx), on_chain_balance, on_chain_nonce).unwrap();
+        }
+        let sender = f.ids.sender_id(&tx.sender()).unwrap();
+
+        let mut changed_senders = HashMap::default();
+        changed_senders
+            .insert(sender, SenderInfo { state_nonce: on_chain_nonce, balance: on_chain_balance });
+        let outcome = pool.on_canonical_state_change(
+            pool.block_info(),
+            vec![],
+            changed_senders,
+            vec![sender],
+        );
+
+        assert_eq!(outcome.discarded.len(), 2);
+        assert_eq!(pool.len(), 1);
+        assert!(pool.contains(tx.get_hash()));
+    }
+
+    #[test]
+    fn discarded_delegated_txs_are_not_promoted() {
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+
+        // the sender can't afford the transactions yet
+        let tx = MockTransaction::eip1559();
+        for tx in [tx.clone(), tx.next(), tx.next().next()] {
+            pool.add_transaction(f.validated(tx), U256::ZERO, 0).unwrap();
+        }
+        assert_eq!(pool.queued_transactions().len(), 3);
+        let sender = f.ids.sender_id(&tx.sender()).unwrap();
+
+        let mut changed_senders = HashMap::default();
+        changed_senders.insert(sender, SenderInfo { state_nonce: 0, balance: U256::MAX });
+        let outcome = pool.on_canonical_state_change(
+            pool.block_info(),
+            vec![],
+            changed_senders,
+            vec![sender],
+        );
+
+        assert_eq!(outcome.discarded.len(), 2);
+        assert_eq!(
+            outcome.promoted.iter().map(|tx| *tx.hash()).collect::<Vec<_>>(),
+            vec![*tx.get_hash()]
+        );
+    }
+
+    #[test]
+    fn journal_records_lifecycle() {
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+
+        let tx = MockTransaction::eip1559();
+        pool.add_transaction(f.validated(tx.clone()), U256::MAX, 0).unwrap();
+
+        // demote the transaction to the basefee pool
+        let mut block_info = pool.block_info();
+        block_info.pending_basefee = tx.max_fee_per_gas() as u64 + 1;
+        pool.set_block_info(block_info);
+
+        pool.remove_transactions(vec![*tx.get_hash()]);
+
+        let history = pool.journal().history(tx.get_hash()).unwrap();
+        assert_eq!(history.sender, tx.sender());
+        assert_eq!(
+            history.events.into_iter().map(|entry| entry.event).collect::<Vec<_>>(),
+            vec![
+                JournalEvent::Pending,
+                JournalEvent::Parked {
+                    subpool: SubPool::BaseFee,
+                    reason: Some(ParkedReason::FeeCapBelowBaseFee)
+                },
+                JournalEvent::Dropped { reason: DropReason::Removed },
+            ]
+        );
+        assert_eq!(pool.journal().histories_by_sender(&tx.sender()).len(), 1);
+    }
 }
@@ -0,0 +1,152 @@
+//! Results of simulating pending transactions against the latest state.
+//!
+//! The pool only holds the [`SimulationCache`], see [`TransactionPool::simulations`]. The results
+//! are recorded by a separate service that executes the pending transactions, like the one of the
+//! `reth-transaction-pool-simulation` crate. Results are keyed by transaction hash and the state
+//! root they were simulated on, and are invalidated on every canonical state change.
+//!
+//! [`TransactionPool::simulations`]: crate::TransactionPool::simulations
+
+use crate::{PoolTransaction, ValidPoolTransaction};
+use alloy_primitives::{TxHash, B256};
+use parking_lot::RwLock;
+use std::{collections::HashMap, sync::Arc};
+
+pub use reth_transaction_pool_types::{SimulationResult, SimulationStatus};
+
+/// Results of the simulations on top of the current canonical head.
+#[derive(Debug, Default)]
+struct SimulationCacheInner {
+    /// State root of the current canonical head.
+    state_root: B256,
+    /// Results simulated on top of `state_root`.
+    results: HashMap<TxHash, Arc<SimulationResult>>,
+}
+
+/// A shareable cache of [`SimulationResult`]s of pending transactions.
+///
+/// The cache only holds results simulated on top of the current canonical head, all other results
+/// are dropped once the head changes, see [`Self::set_state_root`].
+#[derive(Debug, Clone, Default)]
+pub struct SimulationCache {
+    inner: Arc<RwLock<SimulationCacheInner>>,
+}
+
+impl SimulationCache {
+    /// Returns the state root the cached results were simulated on.
+    pub fn state_root(&self) -> B256 {
+        self.inner.read().state_root
+    }
+
+    /// Returns the number of cached results.
+    pub fn len(&self) -> usize {
+        self.inner.read().results.len()
+    }
+
+    /// Returns `true` if there are no cached results.
+    pub fn is_empty(&self) -> bool {
+        self.inner.read().results.is_empty()
+    }
+
+    /// Returns the result of the transaction with the given hash, if it was simulated on top of the
+    /// current canonical head.
+    pub fn get(&self, hash: &TxHash) -> Option<Arc<SimulationResult>> {
+        self.inner.read().results.get(hash).cloned()
+    }
+
+    /// Returns `true` if the transaction with the given hash was simulated on top of the current
+    /// canonical head.
+    pub fn contains(&self, hash: &TxHash) -> bool {
+        self.inner.read().results.contains_key(hash)
+    }
+
+    /// Caches the result of the transaction with the given hash.
+    ///
+    /// Results that weren't simulated on top of the current canonical head are discarded.
+    pub fn insert(&self, hash: TxHash, result: SimulationResult) {
+        let mut inner = self.inner.write();
+        if inner.state_root == result.state_root {
+            inner.results.insert(hash, Arc::new(result));
+        }
+    }
+
+    /// Sets the state root of the new canonical head, and drops all results simulated on top of a
+    /// different state.
+    pub fn set_state_root(&self, state_root: B256) {
+        let mut inner = self.inner.write();
+        if inner.state_root != state_root {
+            inner.state_root = state_root;
+            inner.results.clear();
+        }
+    }
+
+    /// Returns a predicate for
+    /// [`BestTransactionsFilter::filter`](crate::BestTransactionsFilter::filter) that skips
+    /// transactions whose simulation on top of the current canonical head didn't succeed.
+    ///
+    /// Transactions that weren't simulated yet are always yielded.
+    pub fn skip_failed<T: PoolTransaction>(
+        &self,
+    ) -> impl FnMut(&Arc<ValidPoolTransaction<T>>) -> bool + Send {
+        let cache = self.clone();
+        move |tx| cache.get(tx.hash()).map_or(true, |result| result.is_success())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{MockTransaction, MockTransactionFactory};
+    use alloy_primitives::Bytes;
+    use std::collections::BTreeMap;
+
+    fn result(state_root: B256, status: SimulationStatus) -> SimulationResult {
+        SimulationResult {
+            state_root,
+            status,
+            gas_used: 21_000,
+            output: Bytes::new(),
+            touched_slots: BTreeMap::new(),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn invalidate_on_new_head() {
+        let cache = SimulationCache::default();
+        let (root, hash) = (B256::random(), B256::random());
+        cache.set_state_root(root);
+
+        // results of other states are discarded
+        cache.insert(hash, result(B256::random(), SimulationStatus::Success));
+        assert!(cache.is_empty());
+
+        cache.insert(hash, result(root, SimulationStatus::Success));
+        assert!(cache.get(&hash).unwrap().is_success());
+
+        // same head, results are kept
+        cache.set_state_root(root);
+        assert_eq!(cache.len(), 1);
+
+        cache.set_state_root(B256::random());
+        assert!(cache.get(&hash).is_none());
+    }
+
+    #[test]
+    fn skip_failed_transactions() {
+        let mut f = MockTransactionFactory::default();
+        let cache = SimulationCache::default();
+        let root = B256::random();
+        cache.set_state_root(root);
+
+        let txs = (0..3).map(|_| f.validated_arc(MockTransaction::eip1559())).collect::<Vec<_>>();
+        cache.insert(*txs[0].hash(), result(root, SimulationStatus::Success));
+        cache.insert(*txs[1].hash(), result(root, SimulationStatus::Revert));
+
+        let mut skip_failed = cache.skip_failed();
+        assert!(skip_failed(&txs[0]));
+        assert!(!skip_failed(&txs[1]));
+        // not simulated yet
+        assert!(skip_failed(&txs[2]));
+    }
+}
@@ -1143,10 +1143,14 @@ impl MockTransactionFactory {
     ) -> MockValidTx {
         MockValidTx {
             propagate: false,
+            deprioritized: false,
             transaction_id: self.tx_id(&transaction),
             transaction,
             timestamp: Instant::now(),
             origin,
+            authority_ids: None,
+            peer_id: None,
+            conditional: None,
         }
     }
 
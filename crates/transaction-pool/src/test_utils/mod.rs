@@ -1,6 +1,12 @@
 //! Internal helpers for testing.
 
-use crate::{blobstore::InMemoryBlobStore, noop::MockTransactionValidator, Pool, PoolConfig};
+use crate::{
+    blobstore::InMemoryBlobStore, noop::MockTransactionValidator, EthPooledTransaction, Pool,
+    PoolConfig,
+};
+use alloy_eips::eip2718::Decodable2718;
+use alloy_primitives::{hex, Address};
+use reth_primitives::PooledTransactionsElement;
 use std::ops::Deref;
 
 mod gen;
@@ -90,3 +96,11 @@ impl Deref for TestPoolBuilder {
 pub fn testing_pool() -> TestPool {
     TestPoolBuilder::default().into()
 }
+
+/// Returns a signed mainnet EIP-1559 transaction and its sender.
+pub fn signed_eth_transaction() -> (EthPooledTransaction, Address) {
+    let tx_bytes = hex!("02f87201830655c2808505ef61f08482565f94388c818ca8b9251b393131c08a736a67ccb192978801049e39c4b5b1f580c001a01764ace353514e8abdfb92446de356b260e3c1225b73fc4c8876a6258d12a129a04f02294aa61ca7676061cd99f29275491218b4754b46a0248e5e42bc5091f507");
+    let tx = PooledTransactionsElement::decode_2718(&mut &tx_bytes[..]).unwrap();
+    let sender = hex!("1f9090aaE28b8a3dCeaDf281B0F12828e676c326").into();
+    (tx.try_into_ecrecovered().unwrap().into(), sender)
+}
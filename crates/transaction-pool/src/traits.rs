@@ -3,15 +3,23 @@
 use crate::{
     blobstore::BlobStoreError,
     error::PoolResult,
-    pool::{state::SubPool, BestTransactionFilter, TransactionEvents},
+    pool::{
+        state::SubPool, BestTransactionFilter, TransactionConditional, TransactionEvents,
+        TransactionHistory,
+    },
+    simulation::SimulationCache,
     validate::ValidPoolTransaction,
     AllTransactionsEvents,
 };
-use alloy_eips::{eip2718::Encodable2718, eip2930::AccessList, eip4844::BlobAndProofV1};
-use alloy_primitives::{Address, TxHash, TxKind, B256, U256};
+use alloy_eips::{
+    eip2718::Encodable2718, eip2930::AccessList, eip4844::BlobAndProofV1,
+    eip7702::SignedAuthorization,
+};
+use alloy_primitives::{Address, BlockNumber, TxHash, TxKind, B256, U256};
 use futures_util::{ready, Stream};
 use reth_eth_wire_types::HandleMempoolData;
 use reth_execution_types::ChangedAccount;
+use reth_network_peers::PeerId;
 use reth_primitives::{
     kzg::KzgSettings, transaction::TryFromRecoveredTransactionError, BlobTransactionSidecar,
     BlobTransactionValidationError, PooledTransactionsElement,
@@ -78,6 +86,35 @@ pub trait TransactionPool: Send + Sync + Clone {
         self.add_transactions(TransactionOrigin::External, transactions)
     }
 
+    /// Imports all _external_ transactions that were propagated by the given peer.
+    ///
+    /// The transactions are accounted against the peer's quota, see
+    /// [`SourceQuotas::peer`](crate::SourceQuotas::peer).
+    ///
+    /// Consumer: P2P
+    fn add_peer_transactions(
+        &self,
+        peer_id: PeerId,
+        transactions: Vec<Self::Transaction>,
+    ) -> impl Future<Output = Vec<PoolResult<TxHash>>> + Send {
+        let _ = peer_id;
+        self.add_external_transactions(transactions)
+    }
+
+    /// Adds an _unvalidated_ transaction into the pool that may only be included in a block while
+    /// the given conditions hold.
+    ///
+    /// The conditions are re-checked on every canonical state change, and the transaction is
+    /// removed once they can no longer be met.
+    ///
+    /// Consumer: RPC
+    fn add_conditional_transaction(
+        &self,
+        origin: TransactionOrigin,
+        transaction: Self::Transaction,
+        conditional: TransactionConditional,
+    ) -> impl Future<Output = PoolResult<TxHash>> + Send;
+
     /// Adds an _unvalidated_ transaction into the pool and subscribe to state changes.
     ///
     /// This is the same as [TransactionPool::add_transaction] but returns an event stream for the
@@ -301,6 +338,26 @@ pub trait TransactionPool: Send + Sync + Clone {
         hashes: Vec<TxHash>,
     ) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>>;
 
+    /// Allows the transaction with the given hash to be propagated to the network, for example a
+    /// [`TransactionOrigin::Private`] transaction that should be propagated after all.
+    ///
+    /// The transaction stays in the pool with its origin, position and blob sidecar. If it is
+    /// pending, it's announced to the network right away.
+    ///
+    /// Returns the updated transaction, or `None` if it isn't in the pool.
+    ///
+    /// Consumer: Utility
+    fn allow_propagation(
+        &self,
+        hash: TxHash,
+    ) -> Option<Arc<ValidPoolTransaction<Self::Transaction>>>;
+
+    /// Returns all transactions that were added with a [`TransactionConditional`], see
+    /// [`Self::add_conditional_transaction`].
+    ///
+    /// Consumer: Utility
+    fn conditional_transactions(&self) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>>;
+
     /// Retains only those hashes that are unknown to the pool.
     /// In other words, removes all transactions from the given set that are currently present in
     /// the pool. Returns hashes already known to the pool.
@@ -340,6 +397,25 @@ pub trait TransactionPool: Send + Sync + Clone {
         sender: Address,
     ) -> Option<Arc<ValidPoolTransaction<Self::Transaction>>>;
 
+    /// Returns the recorded lifecycle of the transaction, if it is tracked by the pool's journal.
+    ///
+    /// Unlike [`Self::transaction_event_listener`], this is also available after the transaction
+    /// left the pool.
+    fn transaction_history(&self, tx_hash: TxHash) -> Option<TransactionHistory>;
+
+    /// Returns the recorded lifecycle of all transactions of the sender that are tracked by the
+    /// pool's journal, ordered by nonce.
+    fn transaction_histories_by_sender(&self, sender: Address) -> Vec<TransactionHistory>;
+
+    /// Returns the simulation results of pending transactions.
+    ///
+    /// Results are only recorded if a simulation service is running, like the one of the
+    /// `reth-transaction-pool-simulation` crate. By default, this returns an empty cache that is
+    /// never filled.
+    fn simulations(&self) -> SimulationCache {
+        SimulationCache::default()
+    }
+
     /// Returns a transaction sent by a given user and a nonce
     fn get_transaction_by_sender_and_nonce(
         &self,
@@ -452,12 +528,36 @@ pub trait TransactionPoolExt: TransactionPool {
     /// Updates the accounts in the pool
     fn update_accounts(&self, accounts: Vec<ChangedAccount>);
 
+    /// Removes the conditional transactions with the given hashes, whose conditions can no longer
+    /// be met.
+    fn remove_failed_conditional_transactions(
+        &self,
+        hashes: Vec<TxHash>,
+    ) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>>;
+
     /// Deletes the blob sidecar for the given transaction from the blob store
     fn delete_blob(&self, tx: B256);
 
     /// Deletes multiple blob sidecars from the blob store
     fn delete_blobs(&self, txs: Vec<B256>);
 
+    /// Keeps the blob sidecars of mined transactions in the blob store until their block is
+    /// finalized, if the blob store keeps the blobs of finalized blocks.
+    ///
+    /// See also [`BlobStore::keep_all`](crate::blobstore::BlobStore::keep_all).
+    fn keep_blobs(&self, txs: Vec<B256>);
+
+    /// Copies the blob sidecars of finalized blocks into the blob store's static files, if the
+    /// blob store keeps the blobs of finalized blocks.
+    ///
+    /// The blobs must not be deleted if this fails.
+    ///
+    /// See also [`BlobStore::archive_finalized`](crate::blobstore::BlobStore::archive_finalized).
+    fn archive_finalized_blobs(
+        &self,
+        blocks: Vec<(BlockNumber, Vec<B256>)>,
+    ) -> Result<(), BlobStoreError>;
+
     /// Maintenance function to cleanup blobs that are no longer needed.
     fn cleanup_blobs(&self);
 }
@@ -648,6 +748,9 @@ pub struct CanonicalStateUpdate<'a> {
     pub changed_accounts: Vec<ChangedAccount>,
     /// All mined transactions in the block range.
     pub mined_transactions: Vec<B256>,
+    /// Accounts delegated by an [EIP-7702](https://eips.ethereum.org/EIPS/eip-7702) authorization
+    /// in the block range.
+    pub delegated_accounts: Vec<Address>,
 }
 
 impl CanonicalStateUpdate<'_> {
@@ -928,6 +1031,12 @@ pub trait PoolTransaction: fmt::Debug + Send + Sync + Clone {
 
     /// Returns `chain_id`
     fn chain_id(&self) -> Option<u64>;
+
+    /// Returns the authorization list of [EIP-7702](https://eips.ethereum.org/EIPS/eip-7702)
+    /// transactions.
+    fn authorization_list(&self) -> Option<&[SignedAuthorization]> {
+        None
+    }
 }
 
 /// Super trait for transactions that can be converted to and from Eth transactions
@@ -1189,6 +1298,10 @@ impl PoolTransaction for EthPooledTransaction {
     fn chain_id(&self) -> Option<u64> {
         self.transaction.chain_id()
     }
+
+    fn authorization_list(&self) -> Option<&[SignedAuthorization]> {
+        self.transaction.transaction.authorization_list()
+    }
 }
 
 impl EthPoolTransaction for EthPooledTransaction {
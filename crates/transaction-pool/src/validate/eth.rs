@@ -10,6 +10,7 @@ use crate::{
     validate::{ValidTransaction, ValidationTask, MAX_INIT_CODE_BYTE_SIZE},
     EthBlobTransactionSidecar, EthPoolTransaction, LocalTransactionConfig, PoolTransaction,
     TransactionValidationOutcome, TransactionValidationTaskExecutor, TransactionValidator,
+    TXPOOL_MAX_INFLIGHT_DELEGATED_SLOTS,
 };
 use reth_chainspec::{ChainSpec, EthereumHardforks};
 use reth_primitives::{
@@ -133,6 +134,8 @@ pub(crate) struct EthTransactionValidatorInner<Client, T> {
     local_transactions_config: LocalTransactionConfig,
     /// Maximum size in bytes a single transaction can have in order to be accepted into the pool.
     max_tx_input_bytes: usize,
+    /// Max number of in-flight transactions of a sender delegated with EIP-7702.
+    max_inflight_delegated_slots: usize,
     /// Marker for the transaction type
     _marker: PhantomData<T>,
 }
@@ -366,6 +369,19 @@ where
                     InvalidTransactionError::SignerAccountHasBytecode.into(),
                 )
             }
+
+            // A delegated account can be drained by its delegation at any time, so only its next
+            // few transactions are accepted.
+            if transaction.nonce() >=
+                account.nonce.saturating_add(self.max_inflight_delegated_slots as u64)
+            {
+                return TransactionValidationOutcome::Invalid(
+                    transaction,
+                    InvalidPoolTransactionError::Eip7702(
+                        Eip7702PoolTransactionError::InflightTxLimitReached,
+                    ),
+                )
+            }
         }
 
         let tx_nonce = transaction.nonce();
@@ -502,6 +518,8 @@ pub struct EthTransactionValidatorBuilder {
     local_transactions_config: LocalTransactionConfig,
     /// Max size in bytes of a single transaction allowed
     max_tx_input_bytes: usize,
+    /// Max number of in-flight transactions of a sender delegated with EIP-7702.
+    max_inflight_delegated_slots: usize,
 }
 
 impl EthTransactionValidatorBuilder {
@@ -522,6 +540,7 @@ impl EthTransactionValidatorBuilder {
             kzg_settings: EnvKzgSettings::Default,
             local_transactions_config: Default::default(),
             max_tx_input_bytes: DEFAULT_MAX_TX_INPUT_BYTES,
+            max_inflight_delegated_slots: TXPOOL_MAX_INFLIGHT_DELEGATED_SLOTS,
 
             // by default all transaction types are allowed
             eip2718: true,
@@ -648,6 +667,13 @@ impl EthTransactionValidatorBuilder {
         self
     }
 
+    /// Sets the max number of in-flight transactions of a sender delegated with EIP-7702, see
+    /// [`PoolConfig::max_inflight_delegated_slots`](crate::PoolConfig::max_inflight_delegated_slots).
+    pub const fn with_max_inflight_delegated_slots(mut self, slots: usize) -> Self {
+        self.max_inflight_delegated_slots = slots;
+        self
+    }
+
     /// Sets the block gas limit
     ///
     /// Transactions with a gas limit greater than this will be rejected.
@@ -679,6 +705,7 @@ impl EthTransactionValidatorBuilder {
             kzg_settings,
             local_transactions_config,
             max_tx_input_bytes,
+            max_inflight_delegated_slots,
             ..
         } = self;
 
@@ -702,6 +729,7 @@ impl EthTransactionValidatorBuilder {
             kzg_settings,
             local_transactions_config,
             max_tx_input_bytes,
+            max_inflight_delegated_slots,
             _marker: Default::default(),
         };
 
@@ -830,11 +858,11 @@ pub fn ensure_intrinsic_gas<T: EthPoolTransaction>(
 mod tests {
     use super::*;
     use crate::{
-        blobstore::InMemoryBlobStore, error::PoolErrorKind, CoinbaseTipOrdering,
-        EthPooledTransaction, Pool, TransactionPool,
+        blobstore::InMemoryBlobStore, error::PoolErrorKind, test_utils::signed_eth_transaction,
+        CoinbaseTipOrdering, EthPooledTransaction, Pool, TransactionPool,
     };
     use alloy_eips::eip2718::Decodable2718;
-    use alloy_primitives::{hex, U256};
+    use alloy_primitives::{hex, Address, U256};
     use reth_chainspec::MAINNET;
     use reth_primitives::PooledTransactionsElement;
     use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
@@ -918,4 +946,37 @@ mod tests {
         let tx = pool.get(transaction.hash());
         assert!(tx.is_none());
     }
+
+    #[test]
+    fn delegated_sender_inflight_limit() {
+        let (transaction, sender) = signed_eth_transaction();
+        let delegation = [&[0xef, 0x01, 0x00][..], Address::random().as_slice()].concat();
+        let validate = |state_nonce| {
+            let provider = MockEthProvider::default();
+            provider.add_account(
+                sender,
+                ExtendedAccount::new(state_nonce, U256::MAX)
+                    .with_bytecode(delegation.clone().into()),
+            );
+            EthTransactionValidatorBuilder::new(MAINNET.clone())
+                .set_prague(true)
+                .with_max_inflight_delegated_slots(1)
+                .build(provider, InMemoryBlobStore::default())
+                .validate_one(TransactionOrigin::External, transaction.clone())
+        };
+
+        // the next transaction of a delegated sender is accepted
+        assert!(validate(transaction.nonce()).is_valid());
+
+        // but not the ones after it
+        assert!(matches!(
+            validate(transaction.nonce() - 1),
+            TransactionValidationOutcome::Invalid(
+                _,
+                InvalidPoolTransactionError::Eip7702(
+                    Eip7702PoolTransactionError::InflightTxLimitReached
+                )
+            )
+        ));
+    }
 }
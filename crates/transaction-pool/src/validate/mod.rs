@@ -3,20 +3,29 @@
 use crate::{
     error::InvalidPoolTransactionError,
     identifier::{SenderId, TransactionId},
+    pool::TransactionConditional,
     traits::{PoolTransaction, TransactionOrigin},
 };
 use alloy_primitives::{Address, TxHash, B256, U256};
 use futures_util::future::Either;
+use reth_network_peers::PeerId;
 use reth_primitives::{BlobTransactionSidecar, SealedBlock, TransactionSignedEcRecovered};
-use std::{fmt, future::Future, time::Instant};
+use std::{fmt, future::Future, sync::Arc, time::Instant};
 
 mod constants;
 mod eth;
+mod policy;
 mod task;
 
 /// A `TransactionValidator` implementation that validates ethereum transaction.
 pub use eth::*;
 
+/// Pluggable admission policies for transactions.
+pub use policy::{
+    ContractGasCapPolicy, DenyListPolicy, MinTipPolicy, PolicyDecision, PolicyOutcome,
+    PolicyRejectedError, PolicyTransaction, TransactionPolicies, TransactionPolicy,
+};
+
 /// A spawnable task that performs transaction validation.
 pub use task::{TransactionValidationTaskExecutor, ValidationTask};
 
@@ -256,10 +265,20 @@ pub struct ValidPoolTransaction<T: PoolTransaction> {
     pub transaction_id: TransactionId,
     /// Whether it is allowed to propagate the transaction.
     pub propagate: bool,
+    /// Whether the transaction is ranked below all other pending transactions, see
+    /// [`PolicyOutcome::Deprioritize`].
+    pub deprioritized: bool,
     /// Timestamp when this was added to the pool.
     pub timestamp: Instant,
     /// Where this transaction originated from.
     pub origin: TransactionOrigin,
+    /// Sender ids of the authorities of the transaction's authorization list, if any.
+    pub authority_ids: Option<Vec<SenderId>>,
+    /// The peer that propagated this transaction, if it was received from the network.
+    pub peer_id: Option<PeerId>,
+    /// The conditions under which the transaction may be included, if it was submitted with
+    /// `eth_sendRawTransactionConditional`.
+    pub conditional: Option<Arc<TransactionConditional>>,
 }
 
 // === impl ValidPoolTransaction ===
@@ -348,8 +367,11 @@ impl<T: PoolTransaction> ValidPoolTransaction<T> {
     }
 
     /// Whether the transaction originated locally.
+    ///
+    /// Conditional transactions are never considered local: they are submitted by third parties
+    /// over RPC and must not be exempt from eviction.
     pub const fn is_local(&self) -> bool {
-        self.origin.is_local()
+        self.origin.is_local() && self.conditional.is_none()
     }
 
     /// Whether the transaction is an EIP-4844 blob transaction.
@@ -383,6 +405,23 @@ impl<T: PoolTransaction<Consensus: Into<TransactionSignedEcRecovered>>> ValidPoo
     }
 }
 
+impl<T: PoolTransaction> ValidPoolTransaction<T> {
+    /// Returns a copy of the transaction that may be propagated to the network.
+    pub(crate) fn to_propagatable(&self) -> Self {
+        Self {
+            transaction: self.transaction.clone(),
+            transaction_id: self.transaction_id,
+            propagate: true,
+            deprioritized: self.deprioritized,
+            timestamp: self.timestamp,
+            origin: self.origin,
+            authority_ids: self.authority_ids.clone(),
+            peer_id: self.peer_id,
+            conditional: self.conditional.clone(),
+        }
+    }
+}
+
 #[cfg(test)]
 impl<T: PoolTransaction> Clone for ValidPoolTransaction<T> {
     fn clone(&self) -> Self {
@@ -390,8 +429,12 @@ impl<T: PoolTransaction> Clone for ValidPoolTransaction<T> {
             transaction: self.transaction.clone(),
             transaction_id: self.transaction_id,
             propagate: self.propagate,
+            deprioritized: self.deprioritized,
             timestamp: self.timestamp,
             origin: self.origin,
+            authority_ids: self.authority_ids.clone(),
+            peer_id: self.peer_id,
+            conditional: self.conditional.clone(),
         }
     }
 }
@@ -0,0 +1,409 @@
+//! Pluggable admission policies for transactions entering the pool.
+//!
+//! A [`TransactionPolicy`] is consulted by the pool for every validated transaction, right before
+//! it's inserted. Policies are chained in [`TransactionPolicies`], configured with
+//! [`PoolConfig::policies`](crate::PoolConfig::policies), and evaluated in order.
+//!
+//! Policies see the transaction, its [`TransactionOrigin`] and the peer it was received from, if
+//! any, so that peer based rules like per-peer rate limits can be implemented.
+
+use crate::{
+    error::PoolTransactionError, metrics::TransactionPolicyMetrics, PoolTransaction,
+    TransactionOrigin,
+};
+use alloy_primitives::{Address, TxHash, U256};
+use reth_network_peers::PeerId;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    sync::Arc,
+};
+
+/// The outcome of a [`TransactionPolicy`] check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicyOutcome {
+    /// The transaction is accepted by the policy.
+    Accept,
+    /// The transaction is rejected, with the given reason.
+    Reject(String),
+    /// The transaction is accepted, but isn't propagated to peers.
+    ///
+    /// Its priority in the pool is unchanged.
+    NoPropagate,
+    /// The transaction is accepted, but ranked below all other pending transactions, regardless
+    /// of the pool's [`TransactionOrdering`](crate::TransactionOrdering).
+    ///
+    /// It's still propagated to peers.
+    Deprioritize,
+}
+
+/// The combined outcome of a [`TransactionPolicies`] chain for an accepted transaction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PolicyDecision {
+    /// Whether a policy returned [`PolicyOutcome::NoPropagate`].
+    pub no_propagate: bool,
+    /// Whether a policy returned [`PolicyOutcome::Deprioritize`].
+    pub deprioritize: bool,
+}
+
+/// The transaction, as seen by a [`TransactionPolicy`].
+#[derive(Debug, Clone, Copy)]
+pub struct PolicyTransaction<'a> {
+    /// Where the transaction originates from.
+    pub origin: TransactionOrigin,
+    /// The peer that propagated the transaction, if it was received from the network.
+    pub peer_id: Option<PeerId>,
+    /// Hash of the transaction.
+    pub hash: TxHash,
+    /// Sender of the transaction.
+    pub sender: Address,
+    /// Recipient of the transaction, `None` for contract creations.
+    pub to: Option<Address>,
+    /// [EIP-2718](https://eips.ethereum.org/EIPS/eip-2718) type of the transaction.
+    pub tx_type: u8,
+    /// Nonce of the transaction.
+    pub nonce: u64,
+    /// Gas limit of the transaction.
+    pub gas_limit: u64,
+    /// Max fee per gas of the transaction, or the gas price of legacy transactions.
+    pub max_fee_per_gas: u128,
+    /// Max priority fee per gas of the transaction, `None` for legacy transactions.
+    pub max_priority_fee_per_gas: Option<u128>,
+    /// Maximum cost of the transaction: `gas_limit * max_fee_per_gas + value`.
+    pub cost: U256,
+    /// Input data of the transaction.
+    pub input: &'a [u8],
+}
+
+impl<'a> PolicyTransaction<'a> {
+    /// Creates the policy view of the given pool transaction.
+    pub fn new<T: PoolTransaction>(
+        origin: TransactionOrigin,
+        peer_id: Option<PeerId>,
+        transaction: &'a T,
+    ) -> Self {
+        Self {
+            origin,
+            peer_id,
+            hash: *transaction.hash(),
+            sender: transaction.sender(),
+            to: transaction.to(),
+            tx_type: transaction.tx_type(),
+            nonce: transaction.nonce(),
+            gas_limit: transaction.gas_limit(),
+            max_fee_per_gas: transaction.max_fee_per_gas(),
+            max_priority_fee_per_gas: transaction.max_priority_fee_per_gas(),
+            cost: transaction.cost(),
+            input: transaction.input(),
+        }
+    }
+}
+
+/// A rule deciding whether a transaction is admitted into the pool.
+pub trait TransactionPolicy: fmt::Debug + Send + Sync {
+    /// Name of the policy, used to label its metrics and rejections.
+    fn name(&self) -> &str;
+
+    /// Checks the given transaction.
+    fn check(&self, transaction: &PolicyTransaction<'_>) -> PolicyOutcome;
+}
+
+/// An ordered chain of [`TransactionPolicy`]s.
+///
+/// The first policy rejecting a transaction wins. Otherwise, a transaction isn't propagated if any
+/// policy returns [`PolicyOutcome::NoPropagate`], and is deprioritized if any policy returns
+/// [`PolicyOutcome::Deprioritize`].
+#[derive(Debug, Clone, Default)]
+pub struct TransactionPolicies {
+    policies: Vec<(Arc<dyn TransactionPolicy>, TransactionPolicyMetrics)>,
+}
+
+impl TransactionPolicies {
+    /// Appends a policy to the chain.
+    pub fn push<P: TransactionPolicy + 'static>(&mut self, policy: P) {
+        self.push_arc(Arc::new(policy))
+    }
+
+    /// Appends a shared policy to the chain.
+    pub fn push_arc(&mut self, policy: Arc<dyn TransactionPolicy>) {
+        let metrics =
+            TransactionPolicyMetrics::new_with_labels(&[("policy", policy.name().to_string())]);
+        self.policies.push((policy, metrics));
+    }
+
+    /// Appends a policy to the chain, returning the chain.
+    pub fn with_policy<P: TransactionPolicy + 'static>(mut self, policy: P) -> Self {
+        self.push(policy);
+        self
+    }
+
+    /// Appends all policies of `other` to the chain.
+    pub fn extend(&mut self, other: Self) {
+        self.policies.extend(other.policies);
+    }
+
+    /// Returns the number of policies in the chain.
+    pub fn len(&self) -> usize {
+        self.policies.len()
+    }
+
+    /// Returns `true` if the chain has no policies.
+    pub fn is_empty(&self) -> bool {
+        self.policies.is_empty()
+    }
+
+    /// Checks the given transaction against all policies of the chain.
+    ///
+    /// Returns the name of the rejecting policy alongside the reason, if the transaction is
+    /// rejected.
+    pub fn check<T: PoolTransaction>(
+        &self,
+        origin: TransactionOrigin,
+        peer_id: Option<PeerId>,
+        transaction: &T,
+    ) -> Result<PolicyDecision, PolicyRejectedError> {
+        let mut decision = PolicyDecision::default();
+        if self.policies.is_empty() {
+            return Ok(decision)
+        }
+
+        let transaction = PolicyTransaction::new(origin, peer_id, transaction);
+        for (policy, metrics) in &self.policies {
+            match policy.check(&transaction) {
+                PolicyOutcome::Accept => metrics.accepted.increment(1),
+                PolicyOutcome::NoPropagate => {
+                    metrics.not_propagated.increment(1);
+                    decision.no_propagate = true;
+                }
+                PolicyOutcome::Deprioritize => {
+                    metrics.deprioritized.increment(1);
+                    decision.deprioritize = true;
+                }
+                PolicyOutcome::Reject(reason) => {
+                    metrics.rejected.increment(1);
+                    return Err(PolicyRejectedError { policy: policy.name().to_string(), reason })
+                }
+            }
+        }
+
+        Ok(decision)
+    }
+}
+
+/// Error returned for transactions rejected by a [`TransactionPolicy`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("transaction rejected by policy {policy}: {reason}")]
+pub struct PolicyRejectedError {
+    /// Name of the rejecting policy.
+    pub policy: String,
+    /// Reason given by the policy.
+    pub reason: String,
+}
+
+impl PoolTransactionError for PolicyRejectedError {
+    fn is_bad_transaction(&self) -> bool {
+        // local rules, the sender can't know about them
+        false
+    }
+}
+
+/// Rejects transactions from or to denied addresses.
+#[derive(Debug, Clone, Default)]
+pub struct DenyListPolicy {
+    /// Senders whose transactions are rejected.
+    pub senders: HashSet<Address>,
+    /// Recipients whose transactions are rejected.
+    pub recipients: HashSet<Address>,
+}
+
+impl TransactionPolicy for DenyListPolicy {
+    fn name(&self) -> &str {
+        "deny_list"
+    }
+
+    fn check(&self, transaction: &PolicyTransaction<'_>) -> PolicyOutcome {
+        if self.senders.contains(&transaction.sender) {
+            return PolicyOutcome::Reject(format!("sender {} is denied", transaction.sender))
+        }
+        if let Some(to) = transaction.to.filter(|to| self.recipients.contains(to)) {
+            return PolicyOutcome::Reject(format!("recipient {to} is denied"))
+        }
+        PolicyOutcome::Accept
+    }
+}
+
+/// Caps the gas limit of transactions calling specific contracts.
+#[derive(Debug, Clone, Default)]
+pub struct ContractGasCapPolicy {
+    /// Maximum gas limit of transactions, per called contract.
+    pub caps: HashMap<Address, u64>,
+}
+
+impl TransactionPolicy for ContractGasCapPolicy {
+    fn name(&self) -> &str {
+        "contract_gas_cap"
+    }
+
+    fn check(&self, transaction: &PolicyTransaction<'_>) -> PolicyOutcome {
+        let Some(to) = transaction.to else { return PolicyOutcome::Accept };
+        match self.caps.get(&to) {
+            Some(cap) if transaction.gas_limit > *cap => PolicyOutcome::Reject(format!(
+                "gas limit {} exceeds cap {cap} of contract {to}",
+                transaction.gas_limit
+            )),
+            _ => PolicyOutcome::Accept,
+        }
+    }
+}
+
+/// Requires a minimum priority fee per gas, depending on the [`TransactionOrigin`].
+///
+/// For legacy transactions, the gas price is used as priority fee.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MinTipPolicy {
+    /// Minimum priority fee of [`TransactionOrigin::Local`] transactions.
+    pub local: Option<u128>,
+    /// Minimum priority fee of [`TransactionOrigin::External`] transactions.
+    pub external: Option<u128>,
+    /// Minimum priority fee of [`TransactionOrigin::Private`] transactions.
+    pub private: Option<u128>,
+}
+
+impl TransactionPolicy for MinTipPolicy {
+    fn name(&self) -> &str {
+        "min_tip"
+    }
+
+    fn check(&self, transaction: &PolicyTransaction<'_>) -> PolicyOutcome {
+        let min_tip = match transaction.origin {
+            TransactionOrigin::Local => self.local,
+            TransactionOrigin::External => self.external,
+            TransactionOrigin::Private => self.private,
+        };
+        let tip = transaction.max_priority_fee_per_gas.unwrap_or(transaction.max_fee_per_gas);
+        match min_tip {
+            Some(min_tip) if tip < min_tip => {
+                PolicyOutcome::Reject(format!("priority fee {tip} below minimum {min_tip}"))
+            }
+            _ => PolicyOutcome::Accept,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MockTransaction;
+
+    #[derive(Debug)]
+    struct FixedOutcome(PolicyOutcome);
+
+    impl TransactionPolicy for FixedOutcome {
+        fn name(&self) -> &str {
+            "fixed_outcome"
+        }
+
+        fn check(&self, _transaction: &PolicyTransaction<'_>) -> PolicyOutcome {
+            self.0.clone()
+        }
+    }
+
+    /// Deprioritizes the transactions of peers that sent more than `limit` transactions.
+    #[derive(Debug)]
+    struct PeerRateLimit {
+        limit: usize,
+        seen: std::sync::Mutex<HashMap<PeerId, usize>>,
+    }
+
+    impl TransactionPolicy for PeerRateLimit {
+        fn name(&self) -> &str {
+            "peer_rate_limit"
+        }
+
+        fn check(&self, transaction: &PolicyTransaction<'_>) -> PolicyOutcome {
+            let Some(peer_id) = transaction.peer_id else { return PolicyOutcome::Accept };
+            let mut seen = self.seen.lock().unwrap();
+            let count = seen.entry(peer_id).or_default();
+            *count += 1;
+            if *count > self.limit {
+                PolicyOutcome::Deprioritize
+            } else {
+                PolicyOutcome::Accept
+            }
+        }
+    }
+
+    #[test]
+    fn policy_chain() {
+        let tx = MockTransaction::eip1559().with_priority_fee(10).with_max_fee(100);
+        let origin = TransactionOrigin::External;
+
+        let policies = TransactionPolicies::default();
+        assert_eq!(policies.check(origin, None, &tx), Ok(PolicyDecision::default()));
+
+        let policies = policies
+            .with_policy(FixedOutcome(PolicyOutcome::NoPropagate))
+            .with_policy(MinTipPolicy { external: Some(5), ..Default::default() });
+        assert_eq!(
+            policies.check(origin, None, &tx),
+            Ok(PolicyDecision { no_propagate: true, deprioritize: false })
+        );
+
+        let policies = policies.with_policy(FixedOutcome(PolicyOutcome::Deprioritize));
+        assert_eq!(
+            policies.check(origin, None, &tx),
+            Ok(PolicyDecision { no_propagate: true, deprioritize: true })
+        );
+
+        let policies = policies.with_policy(DenyListPolicy {
+            senders: HashSet::from([tx.sender()]),
+            ..Default::default()
+        });
+        let err = policies.check(origin, None, &tx).unwrap_err();
+        assert_eq!(err.policy, "deny_list");
+        assert!(!err.is_bad_transaction());
+    }
+
+    #[test]
+    fn per_peer_policy() {
+        let tx = MockTransaction::eip1559();
+        let origin = TransactionOrigin::External;
+        let policies = TransactionPolicies::default()
+            .with_policy(PeerRateLimit { limit: 1, seen: Default::default() });
+        let (peer, other_peer) = (PeerId::random(), PeerId::random());
+
+        assert_eq!(policies.check(origin, Some(peer), &tx), Ok(PolicyDecision::default()));
+        assert_eq!(
+            policies.check(origin, Some(peer), &tx),
+            Ok(PolicyDecision { no_propagate: false, deprioritize: true })
+        );
+        assert_eq!(policies.check(origin, Some(other_peer), &tx), Ok(PolicyDecision::default()));
+        assert_eq!(policies.check(origin, None, &tx), Ok(PolicyDecision::default()));
+    }
+
+    #[test]
+    fn min_tip_per_origin() {
+        let policy = MinTipPolicy { external: Some(20), ..Default::default() };
+        let tx = MockTransaction::eip1559().with_priority_fee(10).with_max_fee(100);
+
+        let external = PolicyTransaction::new(TransactionOrigin::External, None, &tx);
+        assert!(matches!(policy.check(&external), PolicyOutcome::Reject(_)));
+
+        let local = PolicyTransaction::new(TransactionOrigin::Local, None, &tx);
+        assert_eq!(policy.check(&local), PolicyOutcome::Accept);
+    }
+
+    #[test]
+    fn contract_gas_cap() {
+        let tx = MockTransaction::eip1559().with_gas_limit(100_000);
+        let to = tx.to().unwrap();
+        let policy = ContractGasCapPolicy { caps: HashMap::from([(to, 50_000)]) };
+
+        let transaction = PolicyTransaction::new(TransactionOrigin::External, None, &tx);
+        assert!(matches!(policy.check(&transaction), PolicyOutcome::Reject(_)));
+
+        let tx = tx.with_gas_limit(21_000);
+        let transaction = PolicyTransaction::new(TransactionOrigin::External, None, &tx);
+        assert_eq!(policy.check(&transaction), PolicyOutcome::Accept);
+    }
+}
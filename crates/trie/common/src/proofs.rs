@@ -6,7 +6,7 @@ use alloy_rlp::{encode_fixed_size, Decodable, EMPTY_STRING_CODE};
 use alloy_trie::{
     nodes::TrieNode,
     proof::{verify_proof, ProofNodes, ProofVerificationError},
-    EMPTY_ROOT_HASH,
+    TrieMask, EMPTY_ROOT_HASH,
 };
 use itertools::Itertools;
 use reth_primitives_traits::{constants::KECCAK_EMPTY, Account};
@@ -16,10 +16,14 @@ use std::collections::HashMap;
 /// The state multiproof of target accounts and multiproofs of their storage tries.
 /// Multiproof is effectively a state subtrie that only contains the nodes
 /// in the paths of target accounts.  
-#[derive(Clone, Default, Debug)]
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
 pub struct MultiProof {
     /// State trie multiproof for requested accounts.
     pub account_subtree: ProofNodes,
+    /// Hash masks of the branch nodes in the account subtree, as stored in the database trie.
+    pub branch_node_hash_masks: HashMap<Nibbles, TrieMask>,
+    /// Tree masks of the branch nodes in the account subtree, as stored in the database trie.
+    pub branch_node_tree_masks: HashMap<Nibbles, TrieMask>,
     /// Storage trie multiproofs.
     pub storages: HashMap<B256, StorageMultiProof>,
 }
@@ -78,12 +82,16 @@ impl MultiProof {
 }
 
 /// The merkle multiproof of storage trie.
-#[derive(Clone, Debug)]
+#[derive(Clone, PartialEq, Eq, Debug)]
 pub struct StorageMultiProof {
     /// Storage trie root.
     pub root: B256,
     /// Storage multiproof for requested slots.
     pub subtree: ProofNodes,
+    /// Hash masks of the branch nodes in the storage subtree, as stored in the database trie.
+    pub branch_node_hash_masks: HashMap<Nibbles, TrieMask>,
+    /// Tree masks of the branch nodes in the storage subtree, as stored in the database trie.
+    pub branch_node_tree_masks: HashMap<Nibbles, TrieMask>,
 }
 
 impl StorageMultiProof {
@@ -95,6 +103,8 @@ impl StorageMultiProof {
                 Nibbles::default(),
                 Bytes::from([EMPTY_STRING_CODE]),
             )]),
+            branch_node_hash_masks: HashMap::default(),
+            branch_node_tree_masks: HashMap::default(),
         }
     }
 
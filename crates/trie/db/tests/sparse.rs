@@ -0,0 +1,203 @@
+#![allow(missing_docs)]
+
+use alloy_primitives::{
+    map::{HashMap, HashSet},
+    B256, U256,
+};
+use proptest::{
+    collection::{btree_map, vec},
+    prelude::*,
+};
+use reth_db_api::transaction::DbTx;
+use reth_primitives::Account;
+use reth_provider::{test_utils::create_test_provider_factory, StateChangeWriter, TrieWriter};
+use reth_trie::{
+    proof::Proof,
+    sparse::{SparseStateTrie, SparseStateTrieError, SparseTrieError},
+    updates::TrieUpdates,
+    BranchNodeCompact, HashedPostState, HashedStorage, Nibbles, StateRoot,
+};
+use reth_trie_db::{DatabaseProof, DatabaseStateRoot};
+use std::collections::BTreeMap;
+
+/// Branch nodes of the account trie and the storage tries.
+#[derive(PartialEq, Eq, Default, Debug)]
+struct TrieNodes {
+    accounts: BTreeMap<Nibbles, BranchNodeCompact>,
+    storages: BTreeMap<B256, BTreeMap<Nibbles, BranchNodeCompact>>,
+}
+
+impl TrieNodes {
+    /// Applies the trie updates the same way they are written to the database.
+    fn apply(&mut self, updates: &TrieUpdates) {
+        for path in updates.removed_nodes_ref() {
+            self.accounts.remove(path);
+        }
+        self.accounts.extend(updates.account_nodes_ref().clone());
+
+        for (account, storage_updates) in updates.storage_tries_ref() {
+            let nodes = self.storages.entry(*account).or_default();
+            if storage_updates.is_deleted() {
+                nodes.clear();
+            }
+            for path in storage_updates.removed_nodes_ref() {
+                nodes.remove(path);
+            }
+            nodes.extend(storage_updates.storage_nodes_ref().clone());
+        }
+        self.storages.retain(|_, nodes| !nodes.is_empty());
+    }
+}
+
+/// Returns the key that starts with the given path, right padded with zeros.
+fn padded_key(path: &Nibbles) -> B256 {
+    let mut padded_key = path.pack();
+    padded_key.resize(32, 0);
+    B256::from_slice(&padded_key)
+}
+
+/// Applies the state to a sparse trie, revealing the proofs of the changed accounts and slots and
+/// all blinded nodes that are needed to apply the state.
+fn sparse_root_with_updates(tx: &impl DbTx, state: &HashedPostState) -> (B256, TrieUpdates) {
+    let mut targets = HashMap::<B256, HashSet<B256>>::default();
+    for account in state.accounts.keys() {
+        targets.entry(*account).or_default();
+    }
+    for (account, storage) in &state.storages {
+        targets.entry(*account).or_default().extend(storage.storage.keys().copied());
+    }
+
+    let mut trie = SparseStateTrie::default().with_updates(true);
+    let mut revealed_paths = HashSet::default();
+    loop {
+        let proof =
+            Proof::from_tx(tx).with_branch_node_masks(true).multiproof(targets.clone()).unwrap();
+        trie.reveal_multiproof(targets, proof).unwrap();
+
+        let (account, path) = match trie.update(state) {
+            Ok(()) => break,
+            Err(SparseStateTrieError::Account(SparseTrieError::BlindedNode { path, .. })) => {
+                (None, path)
+            }
+            Err(SparseStateTrieError::Storage {
+                account,
+                error: SparseTrieError::BlindedNode { path, .. },
+            }) => (Some(account), path),
+            Err(error) => panic!("failed to update sparse trie: {error}"),
+        };
+        assert!(revealed_paths.insert((account, path.clone())), "node wasn't revealed");
+        targets = match account {
+            Some(account) => {
+                HashMap::from_iter([(account, HashSet::from_iter([padded_key(&path)]))])
+            }
+            None => HashMap::from_iter([(padded_key(&path), HashSet::default())]),
+        };
+    }
+
+    (trie.root().unwrap(), trie.take_trie_updates())
+}
+
+/// Keys that mostly share their first nibbles, so that the tries have extension nodes and branch
+/// nodes collapse when keys are removed.
+fn key() -> impl Strategy<Value = B256> {
+    prop_oneof![
+        3 => (0u8..4, any::<[u8; 31]>()).prop_map(|(first, rest)| {
+            let mut key = [first; 32];
+            key[1..].copy_from_slice(&rest);
+            B256::from(key)
+        }),
+        1 => any::<[u8; 32]>().prop_map(B256::from),
+    ]
+}
+
+fn storage() -> impl Strategy<Value = BTreeMap<B256, U256>> {
+    btree_map(key(), (1u64..).prop_map(U256::from), 0..20)
+}
+
+/// Storage changes, where zero values remove the slots.
+fn storage_changes() -> impl Strategy<Value = BTreeMap<B256, U256>> {
+    btree_map(key(), (0u64..3).prop_map(U256::from), 0..10)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        cases: 64, ..ProptestConfig::default()
+    })]
+
+    #[test]
+    fn sparse_state_trie_equivalence(
+        accounts in btree_map(key(), (1u64.., storage()), 1..50),
+        changes in vec((0u8..6, 1u64.., storage_changes()), 50),
+        new_accounts in btree_map(key(), (1u64.., storage()), 1..10),
+    ) {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+
+        let mut initial_state = HashedPostState::default();
+        for (account, (balance, storage)) in &accounts {
+            let info = Account { balance: U256::from(*balance), ..Default::default() };
+            initial_state.accounts.insert(*account, Some(info));
+            initial_state
+                .storages
+                .insert(*account, HashedStorage::from_iter(false, storage.clone()));
+        }
+        provider.write_hashed_state(&initial_state.into_sorted()).unwrap();
+        let (_, initial_updates) =
+            StateRoot::from_tx(provider.tx_ref()).root_with_updates().unwrap();
+        provider.write_trie_updates(&initial_updates).unwrap();
+
+        let mut state = HashedPostState::default();
+        for ((account, (_, storage)), (action, balance, slots)) in accounts.iter().zip(changes) {
+            let info = Account { balance: U256::from(balance), ..Default::default() };
+            match action {
+                // storage changes only
+                1 => {
+                    state.storages.insert(*account, HashedStorage::from_iter(false, slots));
+                }
+                // account and storage changes
+                2 => {
+                    state.accounts.insert(*account, Some(info));
+                    state.storages.insert(*account, HashedStorage::from_iter(false, slots));
+                }
+                // every other slot is removed
+                3 => {
+                    let removed = storage.keys().step_by(2).map(|slot| (*slot, U256::ZERO));
+                    state.storages.insert(*account, HashedStorage::from_iter(false, removed));
+                }
+                // the storage is wiped and rewritten
+                4 => {
+                    let slots = slots.into_iter().filter(|(_, value)| !value.is_zero());
+                    state.accounts.insert(*account, Some(info));
+                    state.storages.insert(*account, HashedStorage::from_iter(true, slots));
+                }
+                // the account is destroyed
+                5 => {
+                    state.accounts.insert(*account, None);
+                    state.storages.insert(*account, HashedStorage::new(true));
+                }
+                _ => {}
+            }
+        }
+        for (account, (balance, storage)) in new_accounts {
+            if accounts.contains_key(&account) {
+                continue
+            }
+            let info = Account { balance: U256::from(balance), ..Default::default() };
+            state.accounts.insert(account, Some(info));
+            state.storages.insert(account, HashedStorage::from_iter(false, storage));
+        }
+
+        let (expected_root, expected_updates) =
+            StateRoot::overlay_root_with_updates(provider.tx_ref(), state.clone()).unwrap();
+        let (root, updates) = sparse_root_with_updates(provider.tx_ref(), &state);
+        prop_assert_eq!(root, expected_root);
+
+        let mut expected_nodes = TrieNodes::default();
+        expected_nodes.apply(&initial_updates);
+        let mut nodes = TrieNodes::default();
+        nodes.apply(&initial_updates);
+        expected_nodes.apply(&expected_updates);
+        nodes.apply(&updates);
+        prop_assert_eq!(nodes, expected_nodes);
+    }
+}
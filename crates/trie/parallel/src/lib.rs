@@ -16,6 +16,9 @@ pub mod stats;
 /// Implementation of parallel state root computation.
 pub mod parallel_root;
 
-/// Parallel state root metrics.
+/// Implementation of parallel proof generation.
+pub mod proof;
+
+/// Parallel state root and proof metrics.
 #[cfg(feature = "metrics")]
 pub mod metrics;
@@ -42,3 +42,27 @@ pub struct ParallelTrieMetrics {
     /// The number of leaves for which we did not pre-compute the storage roots.
     pub missed_leaves: Histogram,
 }
+
+/// Parallel proof metrics.
+#[derive(Metrics)]
+#[metrics(scope = "trie_parallel.proof")]
+pub struct ParallelProofMetrics {
+    /// The duration of the multiproof generation.
+    pub duration: Histogram,
+    /// The number of storage multiproofs generated in parallel.
+    pub precomputed_storage_proofs: Histogram,
+    /// The number of leaves for which we did not pre-compute the storage multiproof.
+    pub missed_leaves: Histogram,
+    /// The number of account leaves added to the hash builder.
+    pub leaves_added: Histogram,
+}
+
+impl ParallelProofMetrics {
+    /// Record multiproof generation metrics
+    pub fn record(&self, stats: ParallelTrieStats) {
+        self.duration.record(stats.duration().as_secs_f64());
+        self.precomputed_storage_proofs.record(stats.precomputed_storage_roots() as f64);
+        self.missed_leaves.record(stats.missed_leaves() as f64);
+        self.leaves_added.record(stats.leaves_added() as f64);
+    }
+}
@@ -0,0 +1,528 @@
+#[cfg(feature = "metrics")]
+use crate::metrics::ParallelProofMetrics;
+use crate::{parallel_root::ParallelStateRootError, stats::ParallelTrieTracker};
+use alloy_primitives::{
+    keccak256,
+    map::{HashMap, HashSet},
+    Address, Bytes, B256,
+};
+use alloy_rlp::{BufMut, Encodable};
+use itertools::Itertools;
+use reth_execution_errors::StorageRootError;
+use reth_provider::{
+    providers::ConsistentDbView, BlockReader, DBProvider, DatabaseProviderFactory, ProviderError,
+    ProviderResult, StateProofProvider,
+};
+use reth_trie::{
+    hashed_cursor::{HashedCursorFactory, HashedPostStateCursorFactory},
+    node_iter::{TrieElement, TrieNodeIter},
+    prefix_set::{PrefixSetMut, TriePrefixSetsMut},
+    proof::{branch_node_masks, StorageProof},
+    trie_cursor::{InMemoryTrieCursorFactory, TrieCursorFactory},
+    walker::TrieWalker,
+    witness::TrieWitness,
+    AccountProof, HashBuilder, HashedPostState, MultiProof, Nibbles, TrieAccount, TrieInput,
+};
+use reth_trie_common::proof::ProofRetainer;
+use reth_trie_db::{DatabaseHashedCursorFactory, DatabaseTrieCursorFactory};
+use std::sync::Arc;
+use tracing::*;
+
+/// Parallel proof generator.
+///
+/// The generator starts off by launching tasks to compute the storage multiproofs of the target
+/// and changed accounts. Then, it walks the account trie and polls the storage proof task of each
+/// encountered account leaf, same as [`ParallelStateRoot`](crate::parallel_root::ParallelStateRoot)
+/// does for storage roots.
+///
+/// Internally, the generator uses [`ConsistentDbView`] since it needs to rely on database state
+/// staying the same until the last transaction is open.
+#[derive(Debug)]
+pub struct ParallelProof<Factory> {
+    /// Consistent view of the database.
+    view: ConsistentDbView<Factory>,
+    /// Trie input.
+    input: TrieInput,
+    /// Flag indicating whether to include the masks of the branch nodes in the proof.
+    branch_node_masks: bool,
+    /// Parallel proof metrics.
+    #[cfg(feature = "metrics")]
+    metrics: ParallelProofMetrics,
+}
+
+impl<Factory> ParallelProof<Factory> {
+    /// Create new parallel proof generator.
+    pub fn new(view: ConsistentDbView<Factory>, input: TrieInput) -> Self {
+        Self {
+            view,
+            input,
+            branch_node_masks: false,
+            #[cfg(feature = "metrics")]
+            metrics: ParallelProofMetrics::default(),
+        }
+    }
+
+    /// Set the flag indicating whether to include the masks of the branch nodes in the proof.
+    pub const fn with_branch_node_masks(mut self, branch_node_masks: bool) -> Self {
+        self.branch_node_masks = branch_node_masks;
+        self
+    }
+}
+
+impl<Factory> ParallelProof<Factory>
+where
+    Factory: DatabaseProviderFactory<Provider: BlockReader> + Clone + Send + Sync + 'static,
+{
+    /// Generate a state multiproof according to specified targets, computing the storage
+    /// multiproofs in parallel.
+    pub fn multiproof(
+        self,
+        targets: HashMap<B256, HashSet<B256>>,
+    ) -> Result<MultiProof, ParallelStateRootError> {
+        let mut tracker = ParallelTrieTracker::default();
+
+        let trie_nodes_sorted = Arc::new(self.input.nodes.into_sorted());
+        let hashed_state_sorted = Arc::new(self.input.state.into_sorted());
+        let TriePrefixSetsMut { mut account_prefix_set, mut storage_prefix_sets, .. } =
+            self.input.prefix_sets;
+        account_prefix_set.extend_keys(targets.keys().map(Nibbles::unpack));
+        let account_prefix_set = account_prefix_set.freeze();
+
+        // Storage multiproofs are needed for all changed accounts, since their storage roots may
+        // have changed, and all target accounts.
+        let mut storage_proof_targets = account_prefix_set
+            .iter()
+            .map(|nibbles| (B256::from_slice(&nibbles.pack()), PrefixSetMut::default()))
+            .collect::<HashMap<_, _>>();
+        storage_proof_targets.extend(storage_prefix_sets.drain());
+
+        // Pre-calculate storage multiproofs in parallel.
+        tracker.set_precomputed_storage_roots(storage_proof_targets.len() as u64);
+        debug!(target: "trie::parallel_proof", len = storage_proof_targets.len(), "pre-calculating storage proofs");
+        let mut storage_proofs = HashMap::default();
+        for (hashed_address, prefix_set) in
+            storage_proof_targets.into_iter().sorted_unstable_by_key(|(address, _)| *address)
+        {
+            let view = self.view.clone();
+            let trie_nodes_sorted = trie_nodes_sorted.clone();
+            let hashed_state_sorted = hashed_state_sorted.clone();
+            let target_slots = targets.get(&hashed_address).cloned().unwrap_or_default();
+            let branch_node_masks = self.branch_node_masks;
+
+            let (tx, rx) = std::sync::mpsc::sync_channel(1);
+
+            rayon::spawn_fifo(move || {
+                let result = (|| -> Result<_, ParallelStateRootError> {
+                    let provider_ro = view.provider_ro()?;
+                    let trie_cursor_factory = InMemoryTrieCursorFactory::new(
+                        DatabaseTrieCursorFactory::new(provider_ro.tx_ref()),
+                        &trie_nodes_sorted,
+                    );
+                    let hashed_cursor_factory = HashedPostStateCursorFactory::new(
+                        DatabaseHashedCursorFactory::new(provider_ro.tx_ref()),
+                        &hashed_state_sorted,
+                    );
+                    StorageProof::new_hashed(
+                        trie_cursor_factory,
+                        hashed_cursor_factory,
+                        hashed_address,
+                    )
+                    .with_prefix_set_mut(prefix_set)
+                    .with_branch_node_masks(branch_node_masks)
+                    .storage_multiproof(target_slots)
+                    .map_err(|error| ProviderError::from(error).into())
+                })();
+                let _ = tx.send(result);
+            });
+            storage_proofs.insert(hashed_address, rx);
+        }
+
+        trace!(target: "trie::parallel_proof", "generating account multiproof");
+        let provider_ro = self.view.provider_ro()?;
+        let trie_cursor_factory = InMemoryTrieCursorFactory::new(
+            DatabaseTrieCursorFactory::new(provider_ro.tx_ref()),
+            &trie_nodes_sorted,
+        );
+        let hashed_cursor_factory = HashedPostStateCursorFactory::new(
+            DatabaseHashedCursorFactory::new(provider_ro.tx_ref()),
+            &hashed_state_sorted,
+        );
+
+        let walker = TrieWalker::new(
+            trie_cursor_factory.account_trie_cursor().map_err(ProviderError::Database)?,
+            account_prefix_set,
+        );
+        let mut account_node_iter = TrieNodeIter::new(
+            walker,
+            hashed_cursor_factory.hashed_account_cursor().map_err(ProviderError::Database)?,
+        );
+
+        // Create a hash builder to rebuild the root node since it is not available in the database.
+        let retainer = ProofRetainer::from_iter(targets.keys().map(Nibbles::unpack));
+        let mut hash_builder = HashBuilder::default()
+            .with_proof_retainer(retainer)
+            .with_updates(self.branch_node_masks);
+
+        let mut storages = HashMap::default();
+        let mut account_rlp = Vec::with_capacity(128);
+        while let Some(node) = account_node_iter.try_next().map_err(ProviderError::Database)? {
+            match node {
+                TrieElement::Branch(node) => {
+                    tracker.inc_branch();
+                    hash_builder.add_branch(node.key, node.value, node.children_are_in_trie);
+                }
+                TrieElement::Leaf(hashed_address, account) => {
+                    let storage_multiproof = match storage_proofs.remove(&hashed_address) {
+                        Some(rx) => rx.recv().map_err(|_| {
+                            ParallelStateRootError::StorageRoot(StorageRootError::Database(
+                                reth_db::DatabaseError::Other(format!(
+                                    "channel closed for {hashed_address}"
+                                )),
+                            ))
+                        })??,
+                        // Since we do not store all intermediate nodes in the database, there might
+                        // be a possibility of re-adding a non-modified leaf to the hash builder.
+                        None => {
+                            tracker.inc_missed_leaves();
+                            StorageProof::new_hashed(
+                                trie_cursor_factory.clone(),
+                                hashed_cursor_factory.clone(),
+                                hashed_address,
+                            )
+                            .with_branch_node_masks(self.branch_node_masks)
+                            .storage_multiproof(
+                                targets.get(&hashed_address).cloned().unwrap_or_default(),
+                            )
+                            .map_err(ProviderError::from)?
+                        }
+                    };
+
+                    tracker.inc_leaf();
+                    account_rlp.clear();
+                    let account = TrieAccount::from((account, storage_multiproof.root));
+                    account.encode(&mut account_rlp as &mut dyn BufMut);
+                    hash_builder.add_leaf(Nibbles::unpack(hashed_address), &account_rlp);
+                    storages.insert(hashed_address, storage_multiproof);
+                }
+            }
+        }
+        let _ = hash_builder.root();
+
+        let stats = tracker.finish();
+
+        #[cfg(feature = "metrics")]
+        self.metrics.record(stats);
+
+        trace!(
+            target: "trie::parallel_proof",
+            duration = ?stats.duration(),
+            branches_added = stats.branches_added(),
+            leaves_added = stats.leaves_added(),
+            missed_leaves = stats.missed_leaves(),
+            precomputed_storage_proofs = stats.precomputed_storage_roots(),
+            "generated multiproof"
+        );
+
+        let account_subtree = hash_builder.take_proof_nodes();
+        let (branch_node_hash_masks, branch_node_tree_masks) =
+            branch_node_masks(hash_builder.split().1);
+        Ok(MultiProof { account_subtree, branch_node_hash_masks, branch_node_tree_masks, storages })
+    }
+
+    /// Generate the proof of the account and storage slots, computing the storage multiproofs in
+    /// parallel.
+    pub fn account_proof(
+        self,
+        address: Address,
+        slots: &[B256],
+    ) -> Result<AccountProof, ParallelStateRootError> {
+        let targets =
+            HashMap::from_iter([(keccak256(address), slots.iter().map(keccak256).collect())]);
+        let multiproof = self.multiproof(targets)?;
+        Ok(multiproof.account_proof(address, slots).map_err(ProviderError::from)?)
+    }
+
+    /// Generate the state transition witness of the given state, computing the storage
+    /// multiproofs in parallel, see [`TrieWitness::compute`].
+    pub fn witness(
+        self,
+        state: HashedPostState,
+    ) -> Result<HashMap<B256, Bytes>, ParallelStateRootError> {
+        if state.is_empty() {
+            return Ok(HashMap::default())
+        }
+
+        let provider_ro = self.view.provider_ro()?;
+        let trie_nodes_sorted = self.input.nodes.clone().into_sorted();
+        let hashed_state_sorted = self.input.state.clone().into_sorted();
+        let witness = TrieWitness::new(
+            InMemoryTrieCursorFactory::new(
+                DatabaseTrieCursorFactory::new(provider_ro.tx_ref()),
+                &trie_nodes_sorted,
+            ),
+            HashedPostStateCursorFactory::new(
+                DatabaseHashedCursorFactory::new(provider_ro.tx_ref()),
+                &hashed_state_sorted,
+            ),
+        )
+        .with_prefix_sets_mut(self.input.prefix_sets.clone());
+
+        let targets = witness.get_proof_targets(&state).map_err(ProviderError::from)?;
+        let multiproof = self.multiproof(targets.clone())?;
+        Ok(witness
+            .compute_from_multiproof(state, targets, multiproof)
+            .map_err(ProviderError::from)?)
+    }
+}
+
+/// Generates the proof of the account and storage slots in the given state.
+///
+/// The storage multiproofs are computed in parallel with [`ParallelProof`] if a view of the
+/// database is given and the state is backed by the database, see
+/// [`StateProofProvider::database_trie_input`]. The view has to be created before the state, so
+/// that it is consistent with it. Otherwise, or if the database changed since the view was
+/// created, the proof is generated by the state itself.
+pub fn state_account_proof<Factory, S>(
+    view: Option<ConsistentDbView<Factory>>,
+    state: &S,
+    address: Address,
+    slots: &[B256],
+) -> ProviderResult<AccountProof>
+where
+    Factory: DatabaseProviderFactory<Provider: BlockReader> + Clone + Send + Sync + 'static,
+    S: StateProofProvider + ?Sized,
+{
+    if let Some((view, input)) = view.zip(state.database_trie_input()?) {
+        match ParallelProof::new(view, input).account_proof(address, slots) {
+            Ok(proof) => return Ok(proof),
+            Err(ParallelStateRootError::Provider(ProviderError::ConsistentView(error))) => {
+                debug!(target: "trie::parallel_proof", %error, "Parallel proof failed consistency check, falling back");
+            }
+            Err(error) => return Err(error.into()),
+        }
+    }
+    state.proof(Default::default(), address, slots)
+}
+
+/// Generates the state transition witness of the target state on top of the given state.
+///
+/// The storage multiproofs are computed in parallel with [`ParallelProof`] under the same
+/// conditions as in [`state_account_proof`].
+pub fn state_witness<Factory, S>(
+    view: Option<ConsistentDbView<Factory>>,
+    state: &S,
+    target: HashedPostState,
+) -> ProviderResult<HashMap<B256, Bytes>>
+where
+    Factory: DatabaseProviderFactory<Provider: BlockReader> + Clone + Send + Sync + 'static,
+    S: StateProofProvider + ?Sized,
+{
+    if let Some((view, input)) = view.zip(state.database_trie_input()?) {
+        match ParallelProof::new(view, input).witness(target.clone()) {
+            Ok(witness) => return Ok(witness),
+            Err(ParallelStateRootError::Provider(ProviderError::ConsistentView(error))) => {
+                debug!(target: "trie::parallel_proof", %error, "Parallel witness failed consistency check, falling back");
+            }
+            Err(error) => return Err(error.into()),
+        }
+    }
+    state.witness(Default::default(), target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::U256;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use reth_primitives::{Account, StorageEntry};
+    use reth_provider::{
+        test_utils::{create_test_provider_factory, MockNodeTypesWithDB},
+        HashingWriter, ProviderFactory, StateProviderFactory,
+    };
+    use reth_trie::{proof::Proof, HashedStorage};
+
+    type TestState = HashMap<Address, (Account, HashMap<B256, U256>)>;
+
+    /// Inserts 100 random accounts, most of them with storage, into the hashed state tables.
+    fn insert_random_state(
+        factory: &ProviderFactory<MockNodeTypesWithDB>,
+        rng: &mut StdRng,
+    ) -> TestState {
+        let state = (0..100)
+            .map(|_| {
+                let address = Address::from(rng.gen::<[u8; 20]>());
+                let account =
+                    Account { balance: U256::from(rng.gen::<u64>()), ..Default::default() };
+                let mut storage = HashMap::<B256, U256>::default();
+                let has_storage = rng.gen_bool(0.7);
+                if has_storage {
+                    for _ in 0..100 {
+                        storage.insert(
+                            B256::from(U256::from(rng.gen::<u64>())),
+                            U256::from(rng.gen::<u64>()),
+                        );
+                    }
+                }
+                (address, (account, storage))
+            })
+            .collect::<TestState>();
+
+        let provider_rw = factory.provider_rw().unwrap();
+        provider_rw
+            .insert_account_for_hashing(
+                state.iter().map(|(address, (account, _))| (*address, Some(*account))),
+            )
+            .unwrap();
+        provider_rw
+            .insert_storage_for_hashing(state.iter().map(|(address, (_, storage))| {
+                (
+                    *address,
+                    storage.iter().map(|(slot, value)| StorageEntry { key: *slot, value: *value }),
+                )
+            }))
+            .unwrap();
+        provider_rw.commit().unwrap();
+
+        state
+    }
+
+    /// Returns the targets of the first 10 accounts with up to 20 of their slots, a slot that
+    /// doesn't exist for each of them and an account that doesn't exist.
+    fn proof_targets(state: &TestState, rng: &mut StdRng) -> HashMap<B256, HashSet<B256>> {
+        let mut targets = HashMap::<B256, HashSet<B256>>::default();
+        for (address, (_, storage)) in state.iter().take(10) {
+            let target_slots = targets.entry(keccak256(address)).or_default();
+            for slot in storage.keys().take(20) {
+                target_slots.insert(keccak256(slot));
+            }
+            // a slot that doesn't exist
+            target_slots.insert(B256::from(rng.gen::<[u8; 32]>()));
+        }
+        // an account that doesn't exist
+        let (address, slot) = (rng.gen::<[u8; 32]>(), rng.gen::<[u8; 32]>());
+        targets.insert(B256::from(address), HashSet::from_iter([B256::from(slot)]));
+        targets
+    }
+
+    #[test]
+    fn random_parallel_proof() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let factory = create_test_provider_factory();
+        let consistent_view = ConsistentDbView::new(factory.clone(), None);
+        let state = insert_random_state(&factory, &mut rng);
+        let targets = proof_targets(&state, &mut rng);
+
+        let provider_ro = factory.provider().unwrap();
+        let trie_cursor_factory = DatabaseTrieCursorFactory::new(provider_ro.tx_ref());
+        let hashed_cursor_factory = DatabaseHashedCursorFactory::new(provider_ro.tx_ref());
+
+        assert_eq!(
+            ParallelProof::new(consistent_view, Default::default())
+                .multiproof(targets.clone())
+                .unwrap(),
+            Proof::new(trie_cursor_factory, hashed_cursor_factory).multiproof(targets).unwrap()
+        );
+    }
+
+    #[test]
+    fn random_parallel_proof_with_hashed_state() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let factory = create_test_provider_factory();
+        let consistent_view = ConsistentDbView::new(factory.clone(), None);
+        let state = insert_random_state(&factory, &mut rng);
+        let mut targets = proof_targets(&state, &mut rng);
+
+        // in-memory changes on top of the database, some of them to the target accounts
+        let mut hashed_state = HashedPostState::default();
+        for (index, (address, (account, storage))) in state.iter().enumerate().step_by(3) {
+            let hashed_address = keccak256(address);
+            match index % 4 {
+                // the account is destroyed
+                0 => {
+                    hashed_state.accounts.insert(hashed_address, None);
+                    hashed_state.storages.insert(hashed_address, HashedStorage::new(true));
+                }
+                // the storage is wiped and rewritten
+                1 => {
+                    let slots = (0..10)
+                        .map(|_| (B256::from(rng.gen::<[u8; 32]>()), U256::from(rng.gen::<u64>())));
+                    hashed_state
+                        .storages
+                        .insert(hashed_address, HashedStorage::from_iter(true, slots));
+                }
+                // the balance changes, some slots are changed and some are removed
+                _ => {
+                    let account = Account { balance: U256::from(rng.gen::<u64>()), ..*account };
+                    hashed_state.accounts.insert(hashed_address, Some(account));
+                    let slots = storage.keys().take(10).enumerate().map(|(i, slot)| {
+                        let value =
+                            if i % 2 == 0 { U256::ZERO } else { U256::from(rng.gen::<u64>()) };
+                        (keccak256(slot), value)
+                    });
+                    hashed_state
+                        .storages
+                        .insert(hashed_address, HashedStorage::from_iter(false, slots));
+                }
+            }
+        }
+        // accounts that only exist in memory
+        for _ in 0..10 {
+            let hashed_address = B256::from(rng.gen::<[u8; 32]>());
+            let account = Account { balance: U256::from(rng.gen::<u64>()), ..Default::default() };
+            hashed_state.accounts.insert(hashed_address, Some(account));
+            let slots =
+                (0..10).map(|_| (B256::from(rng.gen::<[u8; 32]>()), U256::from(rng.gen::<u64>())));
+            hashed_state.storages.insert(hashed_address, HashedStorage::from_iter(false, slots));
+            targets.insert(hashed_address, HashSet::default());
+        }
+
+        let input = TrieInput::from_state(hashed_state.clone());
+        let provider_ro = factory.provider().unwrap();
+        let trie_cursor_factory = DatabaseTrieCursorFactory::new(provider_ro.tx_ref());
+        let hashed_state_sorted = hashed_state.clone().into_sorted();
+        let hashed_cursor_factory = HashedPostStateCursorFactory::new(
+            DatabaseHashedCursorFactory::new(provider_ro.tx_ref()),
+            &hashed_state_sorted,
+        );
+
+        assert_eq!(
+            ParallelProof::new(consistent_view, input).multiproof(targets.clone()).unwrap(),
+            Proof::new(trie_cursor_factory, hashed_cursor_factory)
+                .with_prefix_sets_mut(hashed_state.construct_prefix_sets())
+                .multiproof(targets)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn parallel_account_proof_and_witness() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let factory = create_test_provider_factory();
+        let consistent_view = ConsistentDbView::new(factory.clone(), None);
+        let state = insert_random_state(&factory, &mut rng);
+        let latest = factory.latest().unwrap();
+
+        let (address, (account, storage)) =
+            state.iter().find(|(_, (_, storage))| !storage.is_empty()).unwrap();
+        let slots = storage.keys().take(5).copied().collect::<Vec<_>>();
+        assert_eq!(
+            state_account_proof(Some(consistent_view.clone()), &latest, *address, &slots).unwrap(),
+            latest.proof(Default::default(), *address, &slots).unwrap()
+        );
+
+        // the balance changes, one slot is removed and one is added
+        let hashed_address = keccak256(address);
+        let mut target = HashedPostState::default();
+        let account = Account { balance: account.balance + U256::from(1), ..*account };
+        target.accounts.insert(hashed_address, Some(account));
+        let slots = [
+            (keccak256(slots[0]), U256::ZERO),
+            (B256::from(rng.gen::<[u8; 32]>()), U256::from(rng.gen::<u64>())),
+        ];
+        target.storages.insert(hashed_address, HashedStorage::from_iter(false, slots));
+        assert_eq!(
+            state_witness(Some(consistent_view), &latest, target.clone()).unwrap(),
+            latest.witness(Default::default(), target).unwrap()
+        );
+    }
+}
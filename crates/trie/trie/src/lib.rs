@@ -46,6 +46,13 @@ pub mod proof;
 /// Trie witness generation.
 pub mod witness;
 
+/// In-memory sparse tries.
+///
+/// A sparse trie only contains the nodes that were revealed from proofs, all other subtries are
+/// represented by their hashes. This is enough to update the revealed parts of the trie and to
+/// compute its root.
+pub mod sparse;
+
 /// The implementation of the Merkle Patricia Trie.
 mod trie;
 pub use trie::{StateRoot, StorageRoot};
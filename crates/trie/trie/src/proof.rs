@@ -4,7 +4,7 @@ use crate::{
     prefix_set::{PrefixSetMut, TriePrefixSetsMut},
     trie_cursor::TrieCursorFactory,
     walker::TrieWalker,
-    HashBuilder, Nibbles,
+    BranchNodeCompact, HashBuilder, Nibbles, TrieMask,
 };
 use alloy_primitives::{
     keccak256,
@@ -30,6 +30,8 @@ pub struct Proof<T, H> {
     hashed_cursor_factory: H,
     /// A set of prefix sets that have changes.
     prefix_sets: TriePrefixSetsMut,
+    /// Flag indicating whether to include the masks of the branch nodes in the proof.
+    branch_node_masks: bool,
 }
 
 impl<T, H> Proof<T, H> {
@@ -39,6 +41,7 @@ impl<T, H> Proof<T, H> {
             trie_cursor_factory: t,
             hashed_cursor_factory: h,
             prefix_sets: TriePrefixSetsMut::default(),
+            branch_node_masks: false,
         }
     }
 
@@ -48,6 +51,7 @@ impl<T, H> Proof<T, H> {
             trie_cursor_factory,
             hashed_cursor_factory: self.hashed_cursor_factory,
             prefix_sets: self.prefix_sets,
+            branch_node_masks: self.branch_node_masks,
         }
     }
 
@@ -57,6 +61,7 @@ impl<T, H> Proof<T, H> {
             trie_cursor_factory: self.trie_cursor_factory,
             hashed_cursor_factory,
             prefix_sets: self.prefix_sets,
+            branch_node_masks: self.branch_node_masks,
         }
     }
 
@@ -65,6 +70,12 @@ impl<T, H> Proof<T, H> {
         self.prefix_sets = prefix_sets;
         self
     }
+
+    /// Set the flag indicating whether to include the masks of the branch nodes in the proof.
+    pub const fn with_branch_node_masks(mut self, branch_node_masks: bool) -> Self {
+        self.branch_node_masks = branch_node_masks;
+        self
+    }
 }
 
 impl<T, H> Proof<T, H>
@@ -101,7 +112,9 @@ where
 
         // Create a hash builder to rebuild the root node since it is not available in the database.
         let retainer = ProofRetainer::from_iter(targets.keys().map(Nibbles::unpack));
-        let mut hash_builder = HashBuilder::default().with_proof_retainer(retainer);
+        let mut hash_builder = HashBuilder::default()
+            .with_proof_retainer(retainer)
+            .with_updates(self.branch_node_masks);
 
         let mut storages = HashMap::default();
         let mut account_rlp = Vec::with_capacity(128);
@@ -124,6 +137,7 @@ where
                         hashed_address,
                     )
                     .with_prefix_set_mut(storage_prefix_set)
+                    .with_branch_node_masks(self.branch_node_masks)
                     .storage_multiproof(proof_targets)?;
 
                     // Encode account
@@ -137,7 +151,10 @@ where
             }
         }
         let _ = hash_builder.root();
-        Ok(MultiProof { account_subtree: hash_builder.take_proof_nodes(), storages })
+        let account_subtree = hash_builder.take_proof_nodes();
+        let (branch_node_hash_masks, branch_node_tree_masks) =
+            branch_node_masks(hash_builder.split().1);
+        Ok(MultiProof { account_subtree, branch_node_hash_masks, branch_node_tree_masks, storages })
     }
 }
 
@@ -152,6 +169,8 @@ pub struct StorageProof<T, H> {
     hashed_address: B256,
     /// The set of storage slot prefixes that have changed.
     prefix_set: PrefixSetMut,
+    /// Flag indicating whether to include the masks of the branch nodes in the proof.
+    branch_node_masks: bool,
 }
 
 impl<T, H> StorageProof<T, H> {
@@ -167,6 +186,7 @@ impl<T, H> StorageProof<T, H> {
             hashed_cursor_factory: h,
             hashed_address,
             prefix_set: PrefixSetMut::default(),
+            branch_node_masks: false,
         }
     }
 
@@ -177,6 +197,7 @@ impl<T, H> StorageProof<T, H> {
             hashed_cursor_factory: self.hashed_cursor_factory,
             hashed_address: self.hashed_address,
             prefix_set: self.prefix_set,
+            branch_node_masks: self.branch_node_masks,
         }
     }
 
@@ -187,6 +208,7 @@ impl<T, H> StorageProof<T, H> {
             hashed_cursor_factory,
             hashed_address: self.hashed_address,
             prefix_set: self.prefix_set,
+            branch_node_masks: self.branch_node_masks,
         }
     }
 
@@ -195,6 +217,12 @@ impl<T, H> StorageProof<T, H> {
         self.prefix_set = prefix_set;
         self
     }
+
+    /// Set the flag indicating whether to include the masks of the branch nodes in the proof.
+    pub const fn with_branch_node_masks(mut self, branch_node_masks: bool) -> Self {
+        self.branch_node_masks = branch_node_masks;
+        self
+    }
 }
 
 impl<T, H> StorageProof<T, H>
@@ -231,7 +259,9 @@ where
         let walker = TrieWalker::new(trie_cursor, self.prefix_set.freeze());
 
         let retainer = ProofRetainer::from_iter(target_nibbles);
-        let mut hash_builder = HashBuilder::default().with_proof_retainer(retainer);
+        let mut hash_builder = HashBuilder::default()
+            .with_proof_retainer(retainer)
+            .with_updates(self.branch_node_masks);
         let mut storage_node_iter = TrieNodeIter::new(walker, hashed_storage_cursor);
         while let Some(node) = storage_node_iter.try_next()? {
             match node {
@@ -248,6 +278,22 @@ where
         }
 
         let root = hash_builder.root();
-        Ok(StorageMultiProof { root, subtree: hash_builder.take_proof_nodes() })
+        let subtree = hash_builder.take_proof_nodes();
+        let (branch_node_hash_masks, branch_node_tree_masks) =
+            branch_node_masks(hash_builder.split().1);
+        Ok(StorageMultiProof { root, subtree, branch_node_hash_masks, branch_node_tree_masks })
     }
 }
+
+/// Splits the branch nodes retained by the hash builder into their hash and tree masks.
+///
+/// The hash builder only retains the branch nodes that are stored in the database trie, so the
+/// masks of all other branch nodes in the proof are empty.
+pub fn branch_node_masks(
+    branch_nodes: impl IntoIterator<Item = (Nibbles, BranchNodeCompact)>,
+) -> (HashMap<Nibbles, TrieMask>, HashMap<Nibbles, TrieMask>) {
+    branch_nodes
+        .into_iter()
+        .map(|(path, node)| ((path.clone(), node.hash_mask), (path, node.tree_mask)))
+        .unzip()
+}
@@ -0,0 +1,13 @@
+mod trie;
+pub use trie::{BranchMasks, RevealedSparseTrie, SparseNode, SparseTrie, SparseTrieUpdates};
+
+mod state;
+pub use state::SparseStateTrie;
+
+pub use reth_execution_errors::{SparseStateTrieError, SparseTrieError};
+
+/// Result type with [`SparseTrieError`] as error.
+pub type SparseTrieResult<Ok> = Result<Ok, SparseTrieError>;
+
+/// Result type with [`SparseStateTrieError`] as error.
+pub type SparseStateTrieResult<Ok> = Result<Ok, SparseStateTrieError>;
@@ -0,0 +1,261 @@
+use super::{
+    BranchMasks, RevealedSparseTrie, SparseStateTrieError, SparseStateTrieResult, SparseTrie,
+    SparseTrieResult,
+};
+use crate::{
+    updates::{StorageTrieUpdates, TrieUpdates},
+    HashedPostState, Nibbles,
+};
+use alloy_primitives::{
+    map::{HashMap, HashSet},
+    B256, U256,
+};
+use alloy_rlp::{Decodable, Encodable};
+use itertools::Itertools;
+use reth_primitives::Account;
+use reth_trie_common::{
+    proof::ProofNodes, MultiProof, StorageMultiProof, TrieAccount, TrieMask, TrieNode,
+    EMPTY_ROOT_HASH,
+};
+
+/// Sparse state trie, the account trie together with the storage tries of the accounts.
+#[derive(Default, Debug)]
+pub struct SparseStateTrie {
+    /// Sparse account trie.
+    state: SparseTrie,
+    /// Sparse storage tries by hashed address.
+    storages: HashMap<B256, SparseTrie>,
+    /// Revealed accounts and the revealed storage slots of each account.
+    revealed: HashMap<B256, HashSet<B256>>,
+    /// Flag indicating whether the updates of the branch nodes of all tries are retained.
+    retain_updates: bool,
+}
+
+impl SparseStateTrie {
+    /// Sets whether the updates of the branch nodes of all tries are retained, so that they can
+    /// be taken with [`Self::take_trie_updates`].
+    ///
+    /// The masks of the branch nodes have to be included in the revealed multiproofs.
+    pub const fn with_updates(mut self, retain_updates: bool) -> Self {
+        self.retain_updates = retain_updates;
+        self
+    }
+
+    /// Returns `true` if the account was revealed.
+    pub fn is_account_revealed(&self, account: &B256) -> bool {
+        self.revealed.contains_key(account)
+    }
+
+    /// Returns `true` if the storage slot of the account was revealed.
+    pub fn is_storage_slot_revealed(&self, account: &B256, slot: &B256) -> bool {
+        self.revealed.get(account).map_or(false, |slots| slots.contains(slot))
+    }
+
+    /// Reveals the nodes of the multiproof that was generated for the given targets.
+    pub fn reveal_multiproof(
+        &mut self,
+        targets: HashMap<B256, HashSet<B256>>,
+        multiproof: MultiProof,
+    ) -> SparseStateTrieResult<()> {
+        let MultiProof {
+            account_subtree,
+            branch_node_hash_masks,
+            branch_node_tree_masks,
+            mut storages,
+        } = multiproof;
+        reveal_proof_nodes(
+            &mut self.state,
+            account_subtree,
+            &branch_node_hash_masks,
+            &branch_node_tree_masks,
+            self.retain_updates,
+        )?;
+
+        for (account, slots) in targets {
+            // there is no storage multiproof if the account doesn't exist yet
+            let storage = storages.remove(&account).unwrap_or_else(StorageMultiProof::empty);
+            let trie = self.storages.entry(account).or_default();
+            if storage.root == EMPTY_ROOT_HASH && trie.is_blind() {
+                let revealed = RevealedSparseTrie::default().with_updates(self.retain_updates);
+                *trie = SparseTrie::Revealed(Box::new(revealed));
+            }
+            reveal_proof_nodes(
+                trie,
+                storage.subtree,
+                &storage.branch_node_hash_masks,
+                &storage.branch_node_tree_masks,
+                self.retain_updates,
+            )
+            .map_err(|error| SparseStateTrieError::Storage { account, error })?;
+            self.revealed.entry(account).or_default().extend(slots);
+        }
+
+        Ok(())
+    }
+
+    /// Wipes the storage of the account.
+    pub fn wipe_storage(&mut self, account: B256) {
+        let mut trie = RevealedSparseTrie::default().with_updates(self.retain_updates);
+        trie.wipe();
+        self.storages.insert(account, SparseTrie::Revealed(Box::new(trie)));
+    }
+
+    /// Updates the storage slot of the account, removing it if the value is zero.
+    ///
+    /// The storage trie of the account has to be revealed.
+    pub fn update_storage_slot(
+        &mut self,
+        account: B256,
+        slot: B256,
+        value: U256,
+    ) -> SparseStateTrieResult<()> {
+        let trie = self.storages.entry(account).or_default();
+        let path = Nibbles::unpack(slot);
+        let result = if value.is_zero() {
+            trie.remove_leaf(&path)
+        } else {
+            trie.update_leaf(path, alloy_rlp::encode_fixed_size(&value).to_vec())
+        };
+        result.map_err(|error| SparseStateTrieError::Storage { account, error })
+    }
+
+    /// Updates the account, removing it if it doesn't exist and has no storage.
+    ///
+    /// The storage root of the account is computed from its storage trie if it was revealed,
+    /// otherwise the current storage root of the account is kept.
+    pub fn update_account(
+        &mut self,
+        account: B256,
+        info: Option<Account>,
+    ) -> SparseStateTrieResult<()> {
+        let path = Nibbles::unpack(account);
+        let storage_root = match self.storages.get_mut(&account).and_then(SparseTrie::root) {
+            Some(root) => root,
+            None => self.trie_account(&path)?.map_or(EMPTY_ROOT_HASH, |acc| acc.storage_root),
+        };
+
+        if info.is_none() && storage_root == EMPTY_ROOT_HASH {
+            self.state.remove_leaf(&path)?;
+        } else {
+            let mut account_rlp = Vec::new();
+            TrieAccount::from((info.unwrap_or_default(), storage_root)).encode(&mut account_rlp);
+            self.state.update_leaf(path, account_rlp)?;
+        }
+        Ok(())
+    }
+
+    /// Updates the storage root of the account, keeping the rest of the account unchanged.
+    fn update_account_storage_root(&mut self, account: B256) -> SparseStateTrieResult<()> {
+        let path = Nibbles::unpack(account);
+        let Some(mut trie_account) = self.trie_account(&path)? else { return Ok(()) };
+        let Some(storage_root) = self.storages.get_mut(&account).and_then(SparseTrie::root) else {
+            return Ok(())
+        };
+        trie_account.storage_root = storage_root;
+
+        let mut account_rlp = Vec::new();
+        trie_account.encode(&mut account_rlp);
+        self.state.update_leaf(path, account_rlp)?;
+        Ok(())
+    }
+
+    /// Returns the revealed account at the given path of the account trie.
+    fn trie_account(&mut self, path: &Nibbles) -> SparseStateTrieResult<Option<TrieAccount>> {
+        let Some(value) = self.state.as_revealed_mut().and_then(|trie| trie.get_leaf_value(path))
+        else {
+            return Ok(None)
+        };
+        Ok(Some(TrieAccount::decode(&mut &value[..])?))
+    }
+
+    /// Applies the hashed post state to the revealed tries.
+    ///
+    /// All changed accounts and storage slots have to be revealed. Applying the same state again
+    /// is a no-op, so the state can be re-applied after revealing missing nodes.
+    pub fn update(&mut self, state: &HashedPostState) -> SparseStateTrieResult<()> {
+        for (account, storage) in state.storages.iter().sorted_unstable_by_key(|(a, _)| *a) {
+            if storage.wiped {
+                self.wipe_storage(*account);
+            }
+            for (slot, value) in storage.storage.iter().sorted_unstable_by_key(|(s, _)| *s) {
+                self.update_storage_slot(*account, *slot, *value)?;
+            }
+        }
+
+        for (account, info) in state.accounts.iter().sorted_unstable_by_key(|(a, _)| *a) {
+            self.update_account(*account, *info)?;
+        }
+
+        // accounts with changed storage that are not part of the changed accounts
+        for account in state.storages.keys().sorted_unstable() {
+            if !state.accounts.contains_key(account) {
+                self.update_account_storage_root(*account)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Calculates the state root, if the account trie is revealed.
+    pub fn root(&mut self) -> Option<B256> {
+        self.state.root()
+    }
+
+    /// Takes the updates of the branch nodes of the account and storage tries that were retained
+    /// since the tries were revealed, or since the updates were taken last.
+    ///
+    /// The updates only include the branch nodes that changed when the roots were calculated.
+    pub fn take_trie_updates(&mut self) -> TrieUpdates {
+        let mut trie_updates = TrieUpdates::default();
+        if let Some(trie) = self.state.as_revealed_mut() {
+            let updates = trie.take_updates();
+            trie_updates.account_nodes.extend(updates.updated_nodes);
+            trie_updates.removed_nodes.extend(updates.removed_nodes);
+        }
+
+        for (account, trie) in &mut self.storages {
+            let Some(trie) = trie.as_revealed_mut() else { continue };
+            let updates = trie.take_updates();
+            let storage_updates = StorageTrieUpdates {
+                is_deleted: updates.wiped,
+                storage_nodes: updates.updated_nodes.into_iter().collect(),
+                removed_nodes: updates.removed_nodes.into_iter().collect(),
+            };
+            if !storage_updates.is_empty() {
+                trie_updates.insert_storage_updates(*account, storage_updates);
+            }
+        }
+        trie_updates
+    }
+}
+
+/// Reveals the proof nodes in the given trie, starting with the root node.
+///
+/// Branch nodes are revealed with their masks in the database trie, if included in the proof.
+fn reveal_proof_nodes(
+    trie: &mut SparseTrie,
+    nodes: ProofNodes,
+    hash_masks: &HashMap<Nibbles, TrieMask>,
+    tree_masks: &HashMap<Nibbles, TrieMask>,
+    retain_updates: bool,
+) -> SparseTrieResult<()> {
+    let masks = |path: &Nibbles| BranchMasks {
+        hash_mask: hash_masks.get(path).copied().unwrap_or_default(),
+        tree_mask: tree_masks.get(path).copied().unwrap_or_default(),
+    };
+
+    let mut nodes = nodes.iter().sorted_unstable_by(|a, b| a.0.cmp(b.0)).peekable();
+    let Some((path, root)) = nodes.peek() else { return Ok(()) };
+    if !path.is_empty() {
+        // the proof doesn't include the root node, so it can't be revealed
+        return Ok(())
+    }
+    let root = TrieNode::decode(&mut &root[..])?;
+    nodes.next();
+
+    let trie = trie.reveal_root(root, masks(&Nibbles::default()), retain_updates)?;
+    for (path, node) in nodes {
+        trie.reveal_node(path.clone(), TrieNode::decode(&mut &node[..])?, masks(path))?;
+    }
+    Ok(())
+}
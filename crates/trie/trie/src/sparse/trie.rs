@@ -0,0 +1,833 @@
+use super::{SparseTrieError, SparseTrieResult};
+use crate::Nibbles;
+use alloy_primitives::{
+    keccak256,
+    map::{HashMap, HashSet},
+    B256,
+};
+use alloy_rlp::{Decodable, EMPTY_STRING_CODE};
+use reth_trie_common::{
+    rlp_node, word_rlp, BranchNode, BranchNodeCompact, ExtensionNode, LeafNode, TrieMask, TrieNode,
+    CHILD_INDEX_RANGE,
+};
+
+/// A sparse trie that is either blind or revealed.
+///
+/// A blind trie doesn't have any revealed nodes and can't be updated until its root is revealed.
+#[derive(PartialEq, Eq, Default, Clone, Debug)]
+pub enum SparseTrie {
+    /// None of the trie nodes are known.
+    #[default]
+    Blind,
+    /// The trie root node is known.
+    Revealed(Box<RevealedSparseTrie>),
+}
+
+impl SparseTrie {
+    /// Creates a new revealed but empty sparse trie.
+    pub fn revealed_empty() -> Self {
+        Self::Revealed(Box::default())
+    }
+
+    /// Returns `true` if the sparse trie has no revealed nodes.
+    pub const fn is_blind(&self) -> bool {
+        matches!(self, Self::Blind)
+    }
+
+    /// Returns a mutable reference to the revealed trie, if any.
+    pub fn as_revealed_mut(&mut self) -> Option<&mut RevealedSparseTrie> {
+        match self {
+            Self::Blind => None,
+            Self::Revealed(trie) => Some(trie),
+        }
+    }
+
+    /// Reveals the root node of the trie, if the trie is still blind.
+    ///
+    /// Returns a mutable reference to the revealed trie.
+    pub fn reveal_root(
+        &mut self,
+        root: TrieNode,
+        masks: BranchMasks,
+        retain_updates: bool,
+    ) -> SparseTrieResult<&mut RevealedSparseTrie> {
+        if self.is_blind() {
+            *self = Self::Revealed(Box::new(RevealedSparseTrie::from_root(
+                root,
+                masks,
+                retain_updates,
+            )?));
+        }
+        Ok(self.as_revealed_mut().expect("revealed above"))
+    }
+
+    /// Updates the leaf at the given path, inserting it if it doesn't exist yet.
+    pub fn update_leaf(&mut self, path: Nibbles, value: Vec<u8>) -> SparseTrieResult<()> {
+        self.as_revealed_mut().ok_or(SparseTrieError::Blind)?.update_leaf(path, value)
+    }
+
+    /// Removes the leaf at the given path, if it exists.
+    pub fn remove_leaf(&mut self, path: &Nibbles) -> SparseTrieResult<()> {
+        self.as_revealed_mut().ok_or(SparseTrieError::Blind)?.remove_leaf(path)
+    }
+
+    /// Calculates the root of the trie, if it is revealed.
+    pub fn root(&mut self) -> Option<B256> {
+        Some(self.as_revealed_mut()?.root())
+    }
+}
+
+/// A node of the [`RevealedSparseTrie`].
+///
+/// Nodes are stored by their path from the root. Leaf values are kept separately, by the full path
+/// of the leaf. The cached hash of a node is cleared whenever the subtrie below it changes.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum SparseNode {
+    /// Empty trie node.
+    Empty,
+    /// A node that wasn't revealed, represented by its hash.
+    Hash(B256),
+    /// Leaf node with the remainder of the key.
+    Leaf {
+        /// Remainder of the leaf key.
+        key: Nibbles,
+        /// Cached hash of the node.
+        hash: Option<B256>,
+    },
+    /// Extension node with the shared part of the keys below it.
+    Extension {
+        /// Shared key part.
+        key: Nibbles,
+        /// Cached hash of the node.
+        hash: Option<B256>,
+    },
+    /// Branch node with the mask of its children.
+    Branch {
+        /// Mask of the children of the branch.
+        state_mask: TrieMask,
+        /// Cached hash of the node.
+        hash: Option<B256>,
+    },
+}
+
+impl SparseNode {
+    /// Creates a new leaf node without a cached hash.
+    pub const fn new_leaf(key: Nibbles) -> Self {
+        Self::Leaf { key, hash: None }
+    }
+
+    /// Creates a new extension node without a cached hash.
+    pub const fn new_ext(key: Nibbles) -> Self {
+        Self::Extension { key, hash: None }
+    }
+
+    /// Creates a new branch node with the two given children and without a cached hash.
+    pub fn new_split_branch(first: u8, second: u8) -> Self {
+        let mut state_mask = TrieMask::default();
+        state_mask.set_bit(first);
+        state_mask.set_bit(second);
+        Self::Branch { state_mask, hash: None }
+    }
+
+    /// Clears the cached hash of the node.
+    fn clear_hash(&mut self) {
+        match self {
+            Self::Leaf { hash, .. } | Self::Extension { hash, .. } | Self::Branch { hash, .. } => {
+                *hash = None
+            }
+            Self::Empty | Self::Hash(_) => {}
+        }
+    }
+}
+
+/// The hash and tree masks of a branch node, as stored in the database trie.
+///
+/// The masks of branch nodes that aren't stored in the database trie are empty.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Debug)]
+pub struct BranchMasks {
+    /// Mask of the children that are branch nodes, whose hashes are stored with the node.
+    pub hash_mask: TrieMask,
+    /// Mask of the children whose subtries contain branch nodes stored in the database trie.
+    pub tree_mask: TrieMask,
+}
+
+/// Updates of the branch nodes of a [`RevealedSparseTrie`], relative to the database trie.
+#[derive(PartialEq, Eq, Clone, Default, Debug)]
+pub struct SparseTrieUpdates {
+    /// Branch nodes that have to be stored in the database trie, by their path.
+    pub updated_nodes: HashMap<Nibbles, BranchNodeCompact>,
+    /// Paths of the branch nodes that have to be removed from the database trie.
+    pub removed_nodes: HashSet<Nibbles>,
+    /// Flag indicating whether the trie was wiped, removing all of its stored branch nodes.
+    pub wiped: bool,
+}
+
+/// A sparse trie with a revealed root.
+///
+/// Nodes that weren't revealed yet are represented by [`SparseNode::Hash`]. Updates that would
+/// need to traverse or restructure such nodes fail with [`SparseTrieError::BlindedNode`], in
+/// which case the node has to be revealed before the update is retried.
+///
+/// If updates are retained, the branch nodes that change when the root is calculated are
+/// collected as [`SparseTrieUpdates`]. Their masks are derived from the revealed children, and
+/// taken from the database trie for blinded children.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct RevealedSparseTrie {
+    /// All revealed nodes by their path.
+    nodes: HashMap<Nibbles, SparseNode>,
+    /// Leaf values by the full path of the leaf.
+    values: HashMap<Nibbles, Vec<u8>>,
+    /// Masks of the branch nodes that are stored in the database trie, by their path.
+    branch_masks: HashMap<Nibbles, BranchMasks>,
+    /// Updates of the branch nodes, if they are retained.
+    updates: Option<SparseTrieUpdates>,
+}
+
+impl Default for RevealedSparseTrie {
+    fn default() -> Self {
+        Self {
+            nodes: HashMap::from_iter([(Nibbles::default(), SparseNode::Empty)]),
+            values: HashMap::default(),
+            branch_masks: HashMap::default(),
+            updates: None,
+        }
+    }
+}
+
+impl RevealedSparseTrie {
+    /// Creates a new sparse trie from the given root node and its masks.
+    pub fn from_root(
+        node: TrieNode,
+        masks: BranchMasks,
+        retain_updates: bool,
+    ) -> SparseTrieResult<Self> {
+        let mut this = Self {
+            nodes: HashMap::default(),
+            values: HashMap::default(),
+            branch_masks: HashMap::default(),
+            updates: None,
+        }
+        .with_updates(retain_updates);
+        this.insert_revealed(Nibbles::default(), node, None, masks)?;
+        Ok(this)
+    }
+
+    /// Sets whether the updates of the branch nodes are retained.
+    pub fn with_updates(mut self, retain_updates: bool) -> Self {
+        self.updates = retain_updates.then(SparseTrieUpdates::default);
+        self
+    }
+
+    /// Takes the updates of the branch nodes that were retained so far.
+    pub fn take_updates(&mut self) -> SparseTrieUpdates {
+        self.updates.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    /// Removes all nodes and values, marking the trie as wiped if updates are retained.
+    pub fn wipe(&mut self) {
+        self.nodes = HashMap::from_iter([(Nibbles::default(), SparseNode::Empty)]);
+        self.values.clear();
+        self.branch_masks.clear();
+        if let Some(updates) = &mut self.updates {
+            *updates = SparseTrieUpdates { wiped: true, ..Default::default() };
+        }
+    }
+
+    /// Returns the value of the leaf at the given full path, if it is revealed.
+    pub fn get_leaf_value(&self, path: &Nibbles) -> Option<&Vec<u8>> {
+        self.values.get(path)
+    }
+
+    /// Reveals the node at the given path, with its masks if it's a branch node.
+    ///
+    /// The node is only revealed if its parent referenced it by hash, nodes that are already
+    /// revealed or that aren't reachable from the root are ignored.
+    pub fn reveal_node(
+        &mut self,
+        path: Nibbles,
+        node: TrieNode,
+        masks: BranchMasks,
+    ) -> SparseTrieResult<()> {
+        let Some(SparseNode::Hash(hash)) = self.nodes.get(&path) else { return Ok(()) };
+        let hash = Some(*hash);
+        self.insert_revealed(path, node, hash, masks)
+    }
+
+    /// Inserts the decoded node and the children it embeds at the given path.
+    fn insert_revealed(
+        &mut self,
+        path: Nibbles,
+        node: TrieNode,
+        hash: Option<B256>,
+        masks: BranchMasks,
+    ) -> SparseTrieResult<()> {
+        match node {
+            TrieNode::EmptyRoot => {
+                self.nodes.insert(path, SparseNode::Empty);
+            }
+            TrieNode::Branch(branch) => {
+                let mut stack_ptr = branch.as_ref().first_child_index();
+                for idx in CHILD_INDEX_RANGE {
+                    if branch.state_mask.is_bit_set(idx) {
+                        let mut child_path = path.clone();
+                        child_path.push(idx);
+                        self.insert_child(child_path, &branch.stack[stack_ptr])?;
+                        stack_ptr += 1;
+                    }
+                }
+                if masks != BranchMasks::default() {
+                    self.branch_masks.insert(path.clone(), masks);
+                }
+                self.nodes.insert(path, SparseNode::Branch { state_mask: branch.state_mask, hash });
+            }
+            TrieNode::Extension(ext) => {
+                let mut child_path = path.clone();
+                child_path.extend_from_slice(&ext.key);
+                self.insert_child(child_path, &ext.child)?;
+                self.nodes.insert(path, SparseNode::Extension { key: ext.key, hash });
+            }
+            TrieNode::Leaf(leaf) => {
+                let mut full_path = path.clone();
+                full_path.extend_from_slice(&leaf.key);
+                self.values.insert(full_path, leaf.value);
+                self.nodes.insert(path, SparseNode::Leaf { key: leaf.key, hash });
+            }
+        }
+        Ok(())
+    }
+
+    /// Inserts the child referenced by its parent, either by hash or as an embedded node.
+    fn insert_child(&mut self, path: Nibbles, child: &[u8]) -> SparseTrieResult<()> {
+        if child.len() == B256::len_bytes() + 1 {
+            self.nodes.insert(path, SparseNode::Hash(B256::from_slice(&child[1..])));
+            Ok(())
+        } else {
+            self.insert_revealed(path, TrieNode::decode(&mut &child[..])?, None, Default::default())
+        }
+    }
+
+    /// Updates the leaf at the given full path, inserting it if it doesn't exist yet.
+    pub fn update_leaf(&mut self, path: Nibbles, value: Vec<u8>) -> SparseTrieResult<()> {
+        let mut current = Nibbles::default();
+        loop {
+            let Some(node) = self.nodes.get_mut(&current) else {
+                return Err(SparseTrieError::MissingNode(current))
+            };
+            match node {
+                SparseNode::Empty => {
+                    *node = SparseNode::new_leaf(path.slice(current.len()..));
+                    break
+                }
+                SparseNode::Hash(hash) => {
+                    return Err(SparseTrieError::BlindedNode { path: current, hash: *hash })
+                }
+                SparseNode::Leaf { key, hash } => {
+                    let mut existing = current.clone();
+                    existing.extend_from_slice(key);
+                    if existing == path {
+                        *hash = None;
+                        break
+                    }
+
+                    // split the leaf into a branch, behind an extension for the shared part
+                    let key = key.clone();
+                    let remaining = path.slice(current.len()..);
+                    let common = key.common_prefix_length(&remaining);
+                    let branch = SparseNode::new_split_branch(key[common], remaining[common]);
+                    let mut branch_path = current.clone();
+                    branch_path.extend_from_slice(&key.slice(..common));
+                    if common > 0 {
+                        *node = SparseNode::new_ext(key.slice(..common));
+                        self.nodes.insert(branch_path.clone(), branch);
+                    } else {
+                        *node = branch;
+                    }
+
+                    self.nodes.insert(
+                        child_path(&branch_path, key[common]),
+                        SparseNode::new_leaf(key.slice(common + 1..)),
+                    );
+                    self.nodes.insert(
+                        child_path(&branch_path, remaining[common]),
+                        SparseNode::new_leaf(remaining.slice(common + 1..)),
+                    );
+                    break
+                }
+                SparseNode::Extension { key, hash } => {
+                    *hash = None;
+                    let remaining = path.slice(current.len()..);
+                    if remaining.starts_with(key) {
+                        current.extend_from_slice(key);
+                        continue
+                    }
+
+                    // split the extension, the child of the extension keeps its path
+                    let key = key.clone();
+                    let mut ext_child = current.clone();
+                    ext_child.extend_from_slice(&key);
+                    self.check_moved_extension_child(&ext_child)?;
+
+                    let common = key.common_prefix_length(&remaining);
+                    let branch = SparseNode::new_split_branch(key[common], remaining[common]);
+                    let mut branch_path = current.clone();
+                    branch_path.extend_from_slice(&key.slice(..common));
+                    if common > 0 {
+                        self.nodes.insert(current, SparseNode::new_ext(key.slice(..common)));
+                        self.nodes.insert(branch_path.clone(), branch);
+                    } else {
+                        self.nodes.insert(current, branch);
+                    }
+
+                    if key.len() > common + 1 {
+                        self.nodes.insert(
+                            child_path(&branch_path, key[common]),
+                            SparseNode::new_ext(key.slice(common + 1..)),
+                        );
+                    }
+                    self.nodes.insert(
+                        child_path(&branch_path, remaining[common]),
+                        SparseNode::new_leaf(remaining.slice(common + 1..)),
+                    );
+                    break
+                }
+                SparseNode::Branch { state_mask, hash } => {
+                    *hash = None;
+                    let nibble = path[current.len()];
+                    current.push(nibble);
+                    if !state_mask.is_bit_set(nibble) {
+                        state_mask.set_bit(nibble);
+                        let leaf = SparseNode::new_leaf(path.slice(current.len()..));
+                        self.nodes.insert(current, leaf);
+                        break
+                    }
+                }
+            }
+        }
+
+        self.values.insert(path, value);
+        Ok(())
+    }
+
+    /// Removes the leaf at the given full path, if it exists.
+    ///
+    /// If the removal leaves a branch with a single child, the branch is collapsed into its
+    /// child. This fails with [`SparseTrieError::BlindedNode`] if that child isn't revealed, in
+    /// which case the trie is left unchanged.
+    pub fn remove_leaf(&mut self, path: &Nibbles) -> SparseTrieResult<()> {
+        // find the leaf and the branch and extension nodes above it
+        let mut current = Nibbles::default();
+        let mut parents = Vec::new();
+        loop {
+            let Some(node) = self.nodes.get(&current) else {
+                return Err(SparseTrieError::MissingNode(current))
+            };
+            match node {
+                SparseNode::Empty => return Ok(()),
+                SparseNode::Hash(hash) => {
+                    return Err(SparseTrieError::BlindedNode { path: current, hash: *hash })
+                }
+                SparseNode::Leaf { key, .. } => {
+                    if path.slice(current.len()..) != *key {
+                        return Ok(())
+                    }
+                    break
+                }
+                SparseNode::Extension { key, .. } => {
+                    if !path.slice(current.len()..).starts_with(key) {
+                        return Ok(())
+                    }
+                    parents.push(current.clone());
+                    current.extend_from_slice(key);
+                }
+                SparseNode::Branch { state_mask, .. } => {
+                    let nibble = path[current.len()];
+                    if !state_mask.is_bit_set(nibble) {
+                        return Ok(())
+                    }
+                    parents.push(current.clone());
+                    current.push(nibble);
+                }
+            }
+        }
+
+        let Some(branch_path) = parents.pop() else {
+            // the leaf is the root
+            self.nodes.insert(current, SparseNode::Empty);
+            self.values.remove(path);
+            return Ok(())
+        };
+
+        // the parent of a leaf is always a branch
+        let Some(SparseNode::Branch { state_mask, .. }) = self.nodes.get(&branch_path) else {
+            return Err(SparseTrieError::MissingNode(branch_path))
+        };
+        let removed_nibble = current[branch_path.len()];
+        let mut state_mask = *state_mask;
+        state_mask.unset_bit(removed_nibble);
+
+        if state_mask.count_ones() > 1 {
+            self.nodes.insert(branch_path.clone(), SparseNode::Branch { state_mask, hash: None });
+        } else {
+            // collapse the branch into its only remaining child, which must be revealed
+            let child_nibble = CHILD_INDEX_RANGE
+                .find(|nibble| state_mask.is_bit_set(*nibble))
+                .expect("branch has a remaining child");
+            let sibling_path = child_path(&branch_path, child_nibble);
+            let collapsed = match self.nodes.get(&sibling_path) {
+                Some(SparseNode::Hash(hash)) => {
+                    return Err(SparseTrieError::BlindedNode { path: sibling_path, hash: *hash })
+                }
+                Some(SparseNode::Leaf { key, .. }) => {
+                    let mut new_key = Nibbles::from_nibbles_unchecked([child_nibble]);
+                    new_key.extend_from_slice(key);
+                    self.nodes.remove(&sibling_path);
+                    SparseNode::new_leaf(new_key)
+                }
+                Some(SparseNode::Extension { key, .. }) => {
+                    let mut ext_child = sibling_path.clone();
+                    ext_child.extend_from_slice(key);
+                    self.check_moved_extension_child(&ext_child)?;
+
+                    let mut new_key = Nibbles::from_nibbles_unchecked([child_nibble]);
+                    new_key.extend_from_slice(key);
+                    self.nodes.remove(&sibling_path);
+                    SparseNode::new_ext(new_key)
+                }
+                Some(SparseNode::Branch { .. }) => {
+                    SparseNode::new_ext(Nibbles::from_nibbles_unchecked([child_nibble]))
+                }
+                Some(SparseNode::Empty) | None => {
+                    return Err(SparseTrieError::MissingNode(sibling_path))
+                }
+            };
+
+            // the branch is replaced by the collapsed node
+            self.remove_branch_masks(&branch_path);
+
+            // merge the collapsed node into the extension above it
+            let parent_ext =
+                parents.last().and_then(|parent_path| match self.nodes.get(parent_path) {
+                    Some(SparseNode::Extension { key, .. }) => {
+                        Some((parent_path.clone(), key.clone()))
+                    }
+                    _ => None,
+                });
+            if let Some((ext_path, mut ext_key)) = parent_ext {
+                let merged = match collapsed {
+                    SparseNode::Leaf { key, .. } => {
+                        ext_key.extend_from_slice(&key);
+                        SparseNode::new_leaf(ext_key)
+                    }
+                    SparseNode::Extension { key, .. } => {
+                        ext_key.extend_from_slice(&key);
+                        SparseNode::new_ext(ext_key)
+                    }
+                    _ => unreachable!("collapsed branch is either a leaf or an extension"),
+                };
+                self.nodes.remove(&branch_path);
+                self.nodes.insert(ext_path, merged);
+            } else {
+                self.nodes.insert(branch_path, collapsed);
+            }
+        }
+
+        self.nodes.remove(&current);
+        self.values.remove(path);
+        for parent in parents {
+            if let Some(node) = self.nodes.get_mut(&parent) {
+                node.clear_hash();
+            }
+        }
+        Ok(())
+    }
+
+    /// Fails with [`SparseTrieError::BlindedNode`] if updates are retained and the child of an
+    /// extension node that is about to be moved below another branch node isn't revealed.
+    ///
+    /// Whether a blinded child is stored in the database trie is only known to the branch node
+    /// above the extension, so the child has to be revealed before the extension is moved.
+    fn check_moved_extension_child(&self, ext_child: &Nibbles) -> SparseTrieResult<()> {
+        if self.updates.is_none() {
+            return Ok(())
+        }
+        match self.nodes.get(ext_child) {
+            Some(SparseNode::Hash(hash)) => {
+                Err(SparseTrieError::BlindedNode { path: ext_child.clone(), hash: *hash })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Forgets the masks of the branch node at the given path, which was removed from the trie,
+    /// and removes the node from the database trie if it was stored there.
+    fn remove_branch_masks(&mut self, path: &Nibbles) {
+        if self.branch_masks.remove(path).is_none() {
+            return
+        }
+        if let Some(updates) = &mut self.updates {
+            updates.updated_nodes.remove(path);
+            updates.removed_nodes.insert(path.clone());
+        }
+    }
+
+    /// Calculates the root of the trie, caching the hashes of all nodes.
+    pub fn root(&mut self) -> B256 {
+        let rlp = self.rlp_node(Nibbles::default());
+        if rlp.len() == B256::len_bytes() + 1 {
+            B256::from_slice(&rlp[1..])
+        } else {
+            keccak256(rlp)
+        }
+    }
+
+    /// Returns the RLP reference of the node at the given path, which is either the hash of the
+    /// node or the encoded node itself if it is shorter than 32 bytes.
+    fn rlp_node(&mut self, path: Nibbles) -> Vec<u8> {
+        let node = self.nodes.get(&path).cloned().expect("node referenced by its parent");
+        let encoded = match node {
+            SparseNode::Empty => return vec![EMPTY_STRING_CODE],
+            SparseNode::Hash(hash) |
+            SparseNode::Leaf { hash: Some(hash), .. } |
+            SparseNode::Extension { hash: Some(hash), .. } |
+            SparseNode::Branch { hash: Some(hash), .. } => return word_rlp(&hash),
+            SparseNode::Leaf { key, hash: None } => {
+                let mut full_path = path.clone();
+                full_path.extend_from_slice(&key);
+                let value = self.values.get(&full_path).cloned().expect("value of revealed leaf");
+                alloy_rlp::encode(LeafNode::new(key, value))
+            }
+            SparseNode::Extension { key, hash: None } => {
+                let mut child = path.clone();
+                child.extend_from_slice(&key);
+                let child = self.rlp_node(child);
+                alloy_rlp::encode(ExtensionNode::new(key, child))
+            }
+            SparseNode::Branch { state_mask, hash: None } => {
+                let stack = CHILD_INDEX_RANGE
+                    .filter(|nibble| state_mask.is_bit_set(*nibble))
+                    .map(|nibble| self.rlp_node(child_path(&path, nibble)))
+                    .collect::<Vec<_>>();
+                if self.updates.is_some() {
+                    self.update_branch(&path, state_mask, &stack);
+                }
+                alloy_rlp::encode(BranchNode::new(stack, state_mask))
+            }
+        };
+
+        let rlp = rlp_node(&encoded);
+        if rlp.len() == B256::len_bytes() + 1 {
+            if let Some(node) = self.nodes.get_mut(&path) {
+                match node {
+                    SparseNode::Leaf { hash, .. } |
+                    SparseNode::Extension { hash, .. } |
+                    SparseNode::Branch { hash, .. } => *hash = Some(B256::from_slice(&rlp[1..])),
+                    SparseNode::Empty | SparseNode::Hash(_) => {}
+                }
+            }
+        }
+        rlp
+    }
+
+    /// Computes the masks of the branch node at the given path from its children, and records
+    /// the node in the updates.
+    ///
+    /// Children that are branch nodes have their hashes stored with the node. A child subtrie
+    /// contains stored branch nodes if the child is a stored branch node, or an extension node
+    /// above one. The masks of blinded children are kept from the database trie.
+    fn update_branch(&mut self, path: &Nibbles, state_mask: TrieMask, stack: &[Vec<u8>]) {
+        let previous = self.branch_masks.get(path).copied().unwrap_or_default();
+        let mut masks = BranchMasks::default();
+        let mut hashes = Vec::new();
+        let children = CHILD_INDEX_RANGE.filter(|nibble| state_mask.is_bit_set(*nibble));
+        for (nibble, rlp) in children.zip(stack) {
+            let child = child_path(path, nibble);
+            let (is_branch, is_stored) = match self.nodes.get(&child) {
+                Some(SparseNode::Branch { .. }) => (true, self.branch_masks.contains_key(&child)),
+                Some(SparseNode::Extension { key, .. }) => {
+                    let mut ext_child = child.clone();
+                    ext_child.extend_from_slice(key);
+                    let is_stored = match self.nodes.get(&ext_child) {
+                        Some(SparseNode::Hash(_)) => previous.tree_mask.is_bit_set(nibble),
+                        _ => self.branch_masks.contains_key(&ext_child),
+                    };
+                    (false, is_stored)
+                }
+                Some(SparseNode::Hash(_)) => {
+                    (previous.hash_mask.is_bit_set(nibble), previous.tree_mask.is_bit_set(nibble))
+                }
+                _ => (false, false),
+            };
+
+            if is_branch {
+                masks.hash_mask.set_bit(nibble);
+                hashes.push(if rlp.len() == B256::len_bytes() + 1 {
+                    B256::from_slice(&rlp[1..])
+                } else {
+                    keccak256(rlp)
+                });
+            }
+            if is_stored {
+                masks.tree_mask.set_bit(nibble);
+            }
+        }
+
+        let is_stored = masks != BranchMasks::default();
+        if is_stored {
+            self.branch_masks.insert(path.clone(), masks);
+        } else {
+            self.branch_masks.remove(path);
+        }
+
+        // the root node isn't part of the updates, it's represented by the root hash
+        let Some(updates) = self.updates.as_mut().filter(|_| !path.is_empty()) else { return };
+        if is_stored {
+            let node =
+                BranchNodeCompact::new(state_mask, masks.tree_mask, masks.hash_mask, hashes, None);
+            updates.removed_nodes.remove(path);
+            updates.updated_nodes.insert(path.clone(), node);
+        } else if previous != BranchMasks::default() {
+            updates.updated_nodes.remove(path);
+            updates.removed_nodes.insert(path.clone());
+        }
+    }
+}
+
+/// Returns the path of the child of the branch at the given path.
+fn child_path(path: &Nibbles, nibble: u8) -> Nibbles {
+    let mut child = path.clone();
+    child.push(nibble);
+    child
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HashBuilder;
+    use alloy_primitives::U256;
+    use reth_trie_common::proof::ProofRetainer;
+    use std::collections::BTreeMap;
+
+    fn key(byte: u8, rest: u8) -> Nibbles {
+        let mut key = B256::repeat_byte(rest);
+        key[0] = byte;
+        Nibbles::unpack(key)
+    }
+
+    fn value(n: u64) -> Vec<u8> {
+        alloy_rlp::encode_fixed_size(&U256::from(n)).to_vec()
+    }
+
+    fn hash_builder_root(leaves: &BTreeMap<Nibbles, Vec<u8>>) -> B256 {
+        let mut hash_builder = HashBuilder::default();
+        for (key, value) in leaves {
+            hash_builder.add_leaf(key.clone(), value);
+        }
+        hash_builder.root()
+    }
+
+    #[test]
+    fn update_and_remove_leaves() {
+        let mut trie = RevealedSparseTrie::default();
+        let mut leaves = BTreeMap::new();
+        assert_eq!(trie.root(), hash_builder_root(&leaves));
+
+        let keys = [key(0x00, 1), key(0x01, 2), key(0x10, 3), key(0x11, 4), key(0xf0, 5)];
+        for (i, key) in keys.iter().enumerate() {
+            trie.update_leaf(key.clone(), value(i as u64 + 1)).unwrap();
+            leaves.insert(key.clone(), value(i as u64 + 1));
+            assert_eq!(trie.root(), hash_builder_root(&leaves));
+        }
+
+        // overwrite an existing leaf
+        trie.update_leaf(keys[2].clone(), value(42)).unwrap();
+        leaves.insert(keys[2].clone(), value(42));
+        assert_eq!(trie.root(), hash_builder_root(&leaves));
+
+        for key in keys {
+            trie.remove_leaf(&key).unwrap();
+            leaves.remove(&key);
+            assert_eq!(trie.root(), hash_builder_root(&leaves));
+        }
+        assert_eq!(trie, RevealedSparseTrie::default());
+    }
+
+    #[test]
+    fn updates_match_hash_builder() {
+        let mut trie = RevealedSparseTrie::default().with_updates(true);
+        let mut leaves = BTreeMap::new();
+        for i in 0..=255u8 {
+            // pairs of keys that share the first byte, so that there are nested branch nodes
+            for key in [key(i, i), key(i, !i)] {
+                trie.update_leaf(key.clone(), value(i as u64 + 1)).unwrap();
+                leaves.insert(key, value(i as u64 + 1));
+            }
+        }
+
+        let mut hash_builder = HashBuilder::default().with_updates(true);
+        for (key, value) in &leaves {
+            hash_builder.add_leaf(key.clone(), value);
+        }
+        assert_eq!(trie.root(), hash_builder.root());
+
+        let (_, mut expected) = hash_builder.split();
+        expected.remove(&Nibbles::default());
+        let updates = trie.take_updates();
+        assert_eq!(updates.updated_nodes, HashMap::from_iter(expected));
+        assert!(updates.removed_nodes.is_empty());
+
+        // removing one key of each pair collapses the nested branch nodes, so that the branch
+        // nodes above them only have leaf children and aren't stored anymore
+        for i in 0..=255u8 {
+            trie.remove_leaf(&key(i, !i)).unwrap();
+        }
+        trie.root();
+        let updates = trie.take_updates();
+        assert!(updates.updated_nodes.is_empty());
+        assert_eq!(
+            updates.removed_nodes,
+            HashSet::from_iter((0..16).map(|nibble| Nibbles::from_nibbles_unchecked([nibble])))
+        );
+    }
+
+    #[test]
+    fn reveal_from_proof() {
+        let leaves = (0..=255u8)
+            .map(|i| (key(i, i), value(i as u64 + 1)))
+            .collect::<BTreeMap<Nibbles, Vec<u8>>>();
+        let target = key(0x42, 0x42);
+
+        let mut hash_builder =
+            HashBuilder::default().with_proof_retainer(ProofRetainer::from_iter([target.clone()]));
+        for (key, value) in &leaves {
+            hash_builder.add_leaf(key.clone(), value);
+        }
+        let root = hash_builder.root();
+        let proof = hash_builder.take_proof_nodes();
+        let mut nodes = proof.iter().collect::<Vec<_>>();
+        nodes.sort_by(|a, b| a.0.cmp(b.0));
+
+        let mut nodes = nodes.into_iter();
+        let (_, root_node) = nodes.next().unwrap();
+        let mut trie = SparseTrie::default();
+        let revealed = trie
+            .reveal_root(TrieNode::decode(&mut &root_node[..]).unwrap(), Default::default(), false)
+            .unwrap();
+        for (path, node) in nodes {
+            let node = TrieNode::decode(&mut &node[..]).unwrap();
+            revealed.reveal_node(path.clone(), node, Default::default()).unwrap();
+        }
+        assert_eq!(trie.root(), Some(root));
+
+        let mut leaves = leaves;
+        trie.update_leaf(target.clone(), value(1337)).unwrap();
+        leaves.insert(target.clone(), value(1337));
+        assert_eq!(trie.root(), Some(hash_builder_root(&leaves)));
+
+        // the sibling of the target isn't revealed
+        let sibling = key(0x43, 0x43);
+        assert!(matches!(
+            trie.update_leaf(sibling, value(1)),
+            Err(SparseTrieError::BlindedNode { .. })
+        ));
+    }
+}
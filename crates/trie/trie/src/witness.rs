@@ -17,7 +17,8 @@ use itertools::{Either, Itertools};
 use reth_execution_errors::{StateProofError, TrieWitnessError};
 use reth_primitives::constants::EMPTY_ROOT_HASH;
 use reth_trie_common::{
-    BranchNode, HashBuilder, Nibbles, StorageMultiProof, TrieAccount, TrieNode, CHILD_INDEX_RANGE,
+    BranchNode, HashBuilder, MultiProof, Nibbles, StorageMultiProof, TrieAccount, TrieNode,
+    CHILD_INDEX_RANGE,
 };
 
 /// State transition witness for the trie.
@@ -82,19 +83,32 @@ where
     /// # Arguments
     ///
     /// `state` - state transition containing both modified and touched accounts and storage slots.
-    pub fn compute(
-        mut self,
-        state: HashedPostState,
-    ) -> Result<HashMap<B256, Bytes>, TrieWitnessError> {
+    pub fn compute(self, state: HashedPostState) -> Result<HashMap<B256, Bytes>, TrieWitnessError> {
         if state.is_empty() {
             return Ok(self.witness)
         }
 
         let proof_targets = self.get_proof_targets(&state)?;
-        let mut account_multiproof =
+        let account_multiproof =
             Proof::new(self.trie_cursor_factory.clone(), self.hashed_cursor_factory.clone())
                 .with_prefix_sets_mut(self.prefix_sets.clone())
                 .multiproof(proof_targets.clone())?;
+        self.compute_from_multiproof(state, proof_targets, account_multiproof)
+    }
+
+    /// Compute the state transition witness for the trie from the multiproof of the proof targets
+    /// of the state, see [`Self::get_proof_targets`].
+    ///
+    /// This allows the multiproof to be generated by other means, for example in parallel.
+    pub fn compute_from_multiproof(
+        mut self,
+        state: HashedPostState,
+        proof_targets: HashMap<B256, HashSet<B256>>,
+        mut account_multiproof: MultiProof,
+    ) -> Result<HashMap<B256, Bytes>, TrieWitnessError> {
+        if state.is_empty() {
+            return Ok(self.witness)
+        }
 
         // Attempt to compute state root from proofs and gather additional
         // information for the witness.
@@ -251,7 +265,7 @@ where
     /// Retrieve proof targets for incoming hashed state.
     /// This method will aggregate all accounts and slots present in the hash state as well as
     /// select all existing slots from the database for the accounts that have been destroyed.
-    fn get_proof_targets(
+    pub fn get_proof_targets(
         &self,
         state: &HashedPostState,
     ) -> Result<HashMap<B256, HashSet<B256>>, StateProofError> {
@@ -5,10 +5,14 @@ use reth_node_ethereum::EthereumNode;
 use reth_node_types::NodeTypesWithDBAdapter;
 use reth_primitives::SealedHeader;
 use reth_provider::{
-    providers::StaticFileProvider, AccountReader, BlockReader, BlockSource, HeaderProvider,
-    ProviderFactory, ReceiptProvider, StateProvider, TransactionsProvider,
+    providers::{PersistedTipWatcher, ProviderNodeTypes},
+    AccountReader, BlockReader, BlockSource, HeaderProvider, ProviderFactory, ReceiptProvider,
+    StateProvider, StaticFileProviderFactory, TransactionsProvider,
+};
+use std::{
+    path::Path,
+    time::{Duration, Instant},
 };
-use std::path::Path;
 
 // Providers are zero cost abstractions on top of an opened MDBX Transaction
 // exposing a familiar API to query the chain's information without requiring knowledge
@@ -24,13 +28,15 @@ fn main() -> eyre::Result<()> {
     // Instantiate a provider factory for Ethereum mainnet using the provided DB.
     // TODO: Should the DB version include the spec so that you do not need to specify it here?
     let spec = ChainSpecBuilder::mainnet().build();
-    let factory =
-        ProviderFactory::<NodeTypesWithDBAdapter<EthereumNode, _>>::new_with_database_path(
-            db_path,
-            spec.into(),
-            Default::default(),
-            StaticFileProvider::read_only(db_path.join("static_files"), false)?,
-        )?;
+    // The database and static files are opened as a secondary reader, which is safe to do while a
+    // node is running on the same datadir: both are opened read-only, and any attempt to write
+    // through the factory is refused.
+    let factory = ProviderFactory::<NodeTypesWithDBAdapter<EthereumNode, _>>::new_secondary(
+        db_path,
+        db_path.join("static_files"),
+        spec.into(),
+        Default::default(),
+    )?;
 
     // This call opens a RO transaction on the database. To write to the DB you'd need to call
     // the `provider_rw` function and look for the `Writer` variants of the traits.
@@ -53,6 +59,36 @@ fn main() -> eyre::Result<()> {
     // Run it with historical state
     state_provider_example(factory.history_by_block_number(block_num)?)?;
 
+    // Follow the tip persisted by the node. This waits for up to a minute, so it only runs when
+    // asked for with `--follow-tip`.
+    if std::env::args().any(|arg| arg == "--follow-tip") {
+        follow_tip_example(&factory)?;
+    }
+
+    Ok(())
+}
+
+/// The `PersistedTipWatcher` follows the blocks persisted by a node running on the same datadir.
+fn follow_tip_example<N: ProviderNodeTypes>(factory: &ProviderFactory<N>) -> eyre::Result<()> {
+    let watcher = PersistedTipWatcher::spawn(factory.static_file_provider())?;
+    let initial_tip = watcher.tip();
+    println!("Persisted tip: {initial_tip:?}");
+
+    // Wait for the node to persist new blocks. Once a new tip is published, all data up to it can
+    // be read.
+    let start = Instant::now();
+    while start.elapsed() < Duration::from_secs(60) {
+        if let Some(tip) = watcher.tip().filter(|tip| Some(*tip) != initial_tip) {
+            let header = factory
+                .provider()?
+                .header_by_number(tip.number)?
+                .ok_or(eyre::eyre!("header of persisted tip not found"))?;
+            println!("New persisted tip {} with timestamp {}", tip.number, header.timestamp);
+            break
+        }
+        std::thread::sleep(Duration::from_millis(500));
+    }
+
     Ok(())
 }
 